@@ -0,0 +1,220 @@
+"""Compare pqcrypto_bindings.pyi against the built extension module.
+
+gen_stubs.py writes the stub from the Rust signatures; this checks the result
+against what the module actually does. Run after `maturin develop`. Lists
+anything the stub and the module disagree on and exits non-zero if there is
+any:
+
+  * names the module exports that the stub lacks, and the reverse;
+  * functions and methods whose parameters differ from the runtime
+    `__text_signature__`: names, order, which ones have defaults, and the
+    default values PyO3 can show;
+  * return values that don't match their annotation, from calling every
+    function that takes no arguments and then each KEM and signature
+    scheme's encapsulate/decapsulate or sign/verify on the keys it made.
+"""
+
+import ast
+import inspect
+import sys
+from pathlib import Path
+
+import pqcrypto_bindings
+
+HERE = Path(__file__).parent
+STUB = HERE / "pqcrypto_bindings.pyi"
+
+# Functions with no arguments that shouldn't be called just to look at the
+# result: they change process-wide settings or touch the outside world.
+DONT_CALL = {"set_fips_mode", "set_pairwise_check"}
+
+
+# ─── Stub parsing ────────────────────────────────────────────────────────────
+
+
+def params_of(node, method=False):
+    """(name, default node or None) for each parameter, `self` dropped."""
+    args = node.args
+    positional = args.posonlyargs + args.args
+    defaults = [None] * (len(positional) - len(args.defaults)) + args.defaults
+    out = [(a.arg, d) for a, d in zip(positional, defaults)]
+    out += [(a.arg, d) for a, d in zip(args.kwonlyargs, args.kw_defaults)]
+    if method and out and out[0][0] in ("self", "cls"):
+        out = out[1:]
+    return out
+
+
+def is_static(node):
+    return any(isinstance(d, ast.Name) and d.id == "staticmethod" for d in node.decorator_list)
+
+
+def is_property(node):
+    return any(
+        (isinstance(d, ast.Name) and d.id == "property") or isinstance(d, ast.Attribute)
+        for d in node.decorator_list
+    )
+
+
+def parse_stub(tree):
+    funcs, classes, names = {}, {}, set()
+    for node in tree.body:
+        if isinstance(node, ast.FunctionDef):
+            funcs[node.name] = node
+            names.add(node.name)
+        elif isinstance(node, ast.ClassDef):
+            names.add(node.name)
+            classes[node.name] = {
+                n.name: n for n in node.body if isinstance(n, ast.FunctionDef) and not is_property(n)
+            }
+        elif isinstance(node, ast.AnnAssign) and isinstance(node.target, ast.Name):
+            names.add(node.target.id)
+    return funcs, classes, names
+
+
+# ─── Parameters vs __text_signature__ ────────────────────────────────────────
+
+
+def compare_params(label, node, obj, method, problems):
+    try:
+        actual = inspect.signature(obj)
+    except (TypeError, ValueError):
+        return
+    stub = params_of(node, method=method and not is_static(node))
+    runtime = [p for p in actual.parameters.values() if p.kind not in (p.VAR_POSITIONAL, p.VAR_KEYWORD)]
+    if method and runtime and runtime[0].name in ("self", "cls") and not is_static(node):
+        runtime = runtime[1:]
+
+    stub_names = [n for n, _ in stub]
+    runtime_names = [p.name for p in runtime]
+    if stub_names != runtime_names:
+        problems.append(f"{label}: stub has {stub_names}, module has {runtime_names}")
+        return
+
+    for (name, default), p in zip(stub, runtime):
+        has_default = p.default is not p.empty
+        if (default is not None) != has_default:
+            want = "a default" if has_default else "no default"
+            problems.append(f"{label}: parameter {name} should have {want}")
+        elif has_default and p.default is not Ellipsis:
+            # PyO3 shows literal defaults; anything else comes out as `...`.
+            try:
+                stub_default = ast.literal_eval(default)
+            except ValueError:
+                continue
+            if stub_default != p.default:
+                problems.append(
+                    f"{label}: parameter {name} defaults to {p.default!r}, stub says {stub_default!r}"
+                )
+
+
+# ─── Return values vs annotations ────────────────────────────────────────────
+
+
+def matches(value, ann):
+    if isinstance(ann, ast.Constant) and ann.value is None:
+        return value is None
+    if isinstance(ann, ast.BinOp) and isinstance(ann.op, ast.BitOr):
+        return matches(value, ann.left) or matches(value, ann.right)
+    if isinstance(ann, ast.Name):
+        builtin = {"bytes": bytes, "str": str, "bool": bool, "int": int, "float": float}
+        cls = builtin.get(ann.id) or getattr(pqcrypto_bindings, ann.id, None)
+        return not isinstance(cls, type) or isinstance(value, cls)
+    if isinstance(ann, ast.Subscript) and isinstance(ann.value, ast.Name):
+        outer = ann.value.id
+        args = ann.slice.elts if isinstance(ann.slice, ast.Tuple) else [ann.slice]
+        if outer == "tuple":
+            if not isinstance(value, tuple):
+                return False
+            if len(args) == 2 and isinstance(args[1], ast.Constant) and args[1].value is ...:
+                return all(matches(v, args[0]) for v in value)
+            return len(value) == len(args) and all(matches(v, a) for v, a in zip(value, args))
+        if outer == "list":
+            return isinstance(value, list) and all(matches(v, args[0]) for v in value)
+        if outer == "dict":
+            return isinstance(value, dict) and all(
+                matches(k, args[0]) and matches(v, args[1]) for k, v in value.items()
+            )
+        if outer == "Literal":
+            return value in [a.value for a in args]
+    # Any, Mapping, os.PathLike, ...: not checked.
+    return True
+
+
+def check_call(name, node, args, problems):
+    try:
+        value = getattr(pqcrypto_bindings, name)(*args)
+    except Exception as e:  # noqa: BLE001 - report, don't stop
+        problems.append(f"{name}: raised {type(e).__name__}: {e}")
+        return None
+    if node.returns is not None and not matches(value, node.returns):
+        problems.append(
+            f"{name}: returned {describe(value)}, stub says {ast.unparse(node.returns)}"
+        )
+    return value
+
+
+def describe(value):
+    if isinstance(value, tuple):
+        return "tuple[" + ", ".join(type(v).__name__ for v in value) + "]"
+    return type(value).__name__
+
+
+def compare_return_values(funcs, problems):
+    results = {}
+    for name, node in funcs.items():
+        if name in DONT_CALL or not hasattr(pqcrypto_bindings, name):
+            continue
+        if all(d is not None for _, d in params_of(node)):
+            results[name] = check_call(name, node, [], problems)
+
+    msg = b"check_stubs"
+    for keygen, keys in results.items():
+        if not keygen.endswith("_keygen") or not isinstance(keys, tuple) or len(keys) != 2:
+            continue
+        prefix = keygen[: -len("_keygen")]
+        pk, sk = keys
+        if f"{prefix}_encapsulate" in funcs:
+            out = check_call(f"{prefix}_encapsulate", funcs[f"{prefix}_encapsulate"], [pk], problems)
+            if isinstance(out, tuple) and f"{prefix}_decapsulate" in funcs:
+                check_call(f"{prefix}_decapsulate", funcs[f"{prefix}_decapsulate"], [sk, out[0]], problems)
+        if f"{prefix}_sign" in funcs:
+            sig = check_call(f"{prefix}_sign", funcs[f"{prefix}_sign"], [sk, msg], problems)
+            if sig is not None:
+                for verify in (f"{prefix}_verify", f"{prefix}_verify_strict"):
+                    if verify in funcs:
+                        check_call(verify, funcs[verify], [pk, msg, sig], problems)
+
+
+def main():
+    funcs, classes, names = parse_stub(ast.parse(STUB.read_text()))
+    exported = {n for n in dir(pqcrypto_bindings) if not n.startswith("_")}
+    problems = []
+
+    for name in sorted(exported - names):
+        problems.append(f"missing from stub: {name}")
+    for name in sorted(names - exported):
+        if not name.startswith("_"):
+            problems.append(f"not in module: {name}")
+
+    for name, node in funcs.items():
+        obj = getattr(pqcrypto_bindings, name, None)
+        if obj is not None:
+            compare_params(name, node, obj, False, problems)
+    for cls_name, methods in classes.items():
+        cls = getattr(pqcrypto_bindings, cls_name, None)
+        if cls is None:
+            continue
+        for name, node in methods.items():
+            obj = getattr(cls, name, None)
+            if obj is not None and not name.startswith("__"):
+                compare_params(f"{cls_name}.{name}", node, obj, True, problems)
+
+    compare_return_values(funcs, problems)
+
+    for line in problems:
+        print(line)
+    return 1 if problems else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
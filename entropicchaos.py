@@ -167,6 +167,8 @@ class PQCManager:
             # Encapsulate to create shared secret using correct function name
             # Returns (ciphertext, shared_secret) as per Rust implementation
             ciphertext, shared_secret = pqcrypto_bindings.kyber_encapsulate(public_key)
+            # Secrets come back as wipeable SecretBytes; copy out what we store
+            shared_secret = shared_secret.expose()
             
             # XOR the classical key with the shared secret for hybrid approach
             wrapped_key = bytearray(classical_key)
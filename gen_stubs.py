@@ -0,0 +1,1304 @@
+"""Generate pqcrypto_bindings.pyi from the Rust sources and the built module.
+
+Run after `maturin develop`:
+
+    python gen_stubs.py           # rewrite pqcrypto_bindings.pyi
+    python gen_stubs.py --check   # exit non-zero if it is out of date
+
+Signatures come from the Rust code in src/: every #[pyfunction], #[pyclass]
+and #[pymethods] item, including the ones the kem/sign/mceliece binding
+macros stamp out, with defaults from #[pyo3(signature = ...)]. Rust types
+map onto Python ones (Buffer stays Buffer, Py<PyBytes> is bytes, Option<T>
+is `T | None`, Vec<T> is Sequence[T] going in and list[T] coming out, ...).
+Where the Rust type says less than that, say a &str that takes one of a few
+names or a PyObject, a `// stub:` comment next to the item's attributes
+gives the annotation:
+
+    // stub: mode: Literal["hedged", "deterministic"]
+    // stub: -> dict[str, Any]
+
+and one in front of a struct or enum gives the annotation for every
+parameter of that type. A type the generator can't map is an error rather
+than a guess.
+
+The built module supplies the rest: the order names are registered in,
+exceptions, integer constants and the kem/sign/util namespaces. Section
+comments are the `// ...` comments in the #[pymodule] function.
+"""
+
+import argparse
+import difflib
+import re
+import sys
+from dataclasses import dataclass, field
+from pathlib import Path
+
+HERE = Path(__file__).parent
+STUB = HERE / "pqcrypto_bindings.pyi"
+SRC = HERE / "src"
+LINE_LENGTH = 88
+
+HEADER = """\
+# Type stubs for the pqcrypto_bindings extension module.
+#
+# Generated by gen_stubs.py from the Rust signatures in src/; don't edit by
+# hand. maturin ships this file in the wheel next to the compiled module, so
+# IDEs and mypy see the real signatures. Every byte-string argument accepts
+# any C-contiguous buffer (bytes, bytearray, memoryview, mmap, ...); secret
+# keys and shared secrets come back as SecretBytes.
+"""
+
+
+class StubError(Exception):
+    pass
+
+
+# ─── Rust tokens ─────────────────────────────────────────────────────────────
+
+TOKEN = re.compile(
+    "|".join(
+        [
+            r"(?P<ws>\s+)",
+            r"(?P<stub>//[ ]stub:[^\n]*)",
+            r"(?P<comment>//[^\n]*|/\*.*?\*/)",
+            r'(?P<str>b?r(?P<hashes>#*)".*?"(?P=hashes)|b?"(?:\\.|[^"\\])*")',
+            r"(?P<char>b?'(?:\\.|[^'\\])')",
+            r"(?P<lifetime>'\w+)",
+            r"(?P<num>\d\w*(?:\.\d\w*)?)",
+            r"(?P<ident>[A-Za-z_]\w*)",
+            r"(?P<punct>::|->|=>|[^\s\w])",
+        ]
+    ),
+    re.S,
+)
+
+
+@dataclass
+class Tok:
+    kind: str
+    text: str
+
+    def is_(self, text):
+        return self.text == text and self.kind in ("punct", "ident")
+
+
+@dataclass
+class Group:
+    delim: str
+    children: list
+
+    kind = "group"
+    text = None
+
+    def is_(self, text):
+        return False
+
+
+CLOSE = {"(": ")", "[": "]", "{": "}"}
+
+
+def tokenize(source):
+    """Token trees: (), [] and {} become Groups, comments other than
+    `// stub:` ones are dropped."""
+    stack = [Group("", [])]
+    for m in TOKEN.finditer(source):
+        kind = m.lastgroup if m.lastgroup != "hashes" else "str"
+        if kind in ("ws", "comment"):
+            continue
+        if m.group("str") is not None:
+            kind = "str"
+        text = m.group()
+        if kind == "punct" and text in CLOSE:
+            stack.append(Group(text, []))
+        elif kind == "punct" and text in CLOSE.values():
+            group = stack.pop()
+            if CLOSE[group.delim] != text:
+                raise StubError(f"unbalanced {text!r} in Rust source")
+            stack[-1].children.append(group)
+        else:
+            stack[-1].children.append(Tok(kind, text))
+    if len(stack) != 1:
+        raise StubError("unbalanced brackets in Rust source")
+    return stack[0].children
+
+
+def split_on(trees, sep=","):
+    """Split at top-level `sep` tokens, counting <> as brackets."""
+    parts, current, depth = [], [], 0
+    for t in trees:
+        if t.is_("<"):
+            depth += 1
+        elif t.is_(">") and depth:
+            depth -= 1
+        elif t.is_(sep) and depth == 0:
+            parts.append(current)
+            current = []
+            continue
+        current.append(t)
+    if current:
+        parts.append(current)
+    return parts
+
+
+# ─── macro_rules! expansion ──────────────────────────────────────────────────
+#
+# Enough of macro_rules to expand the binding macros: fragments, nested groups
+# and $(...)? / * / + repetitions. Fragments other than single-tree ones
+# (ident, tt, literal, lifetime) run to the next `,`, `;` or `=>`.
+
+SINGLE_TREE = {"ident", "tt", "literal", "lifetime", "vis"}
+
+
+@dataclass
+class Frag:
+    name: str
+    kind: str
+
+
+@dataclass
+class Rep:
+    elems: list
+    sep: str | None
+    op: str
+
+
+@dataclass
+class GroupPat:
+    delim: str
+    elems: list
+
+
+@dataclass
+class Macro:
+    name: str
+    arms: list  # (pattern elems, body trees)
+
+    def makes_bindings(self):
+        return any(mentions_pyo3(body) for _, body in self.arms)
+
+
+def mentions_pyo3(trees):
+    for t in trees:
+        if isinstance(t, Group):
+            if mentions_pyo3(t.children):
+                return True
+        elif t.kind == "ident" and t.text in ("pyfunction", "pyclass", "pymethods"):
+            return True
+    return False
+
+
+def compile_pattern(trees):
+    elems, i = [], 0
+    while i < len(trees):
+        t = trees[i]
+        if t.is_("$") and i + 1 < len(trees):
+            nxt = trees[i + 1]
+            if isinstance(nxt, Group) and nxt.delim == "(":
+                sub = compile_pattern(nxt.children)
+                i += 2
+                sep = None
+                if not trees[i].is_("?") and not trees[i].is_("*") and not trees[i].is_("+"):
+                    sep = trees[i].text
+                    i += 1
+                elems.append(Rep(sub, sep, trees[i].text))
+                i += 1
+                continue
+            if nxt.kind == "ident" and i + 3 < len(trees) and trees[i + 2].is_(":"):
+                elems.append(Frag(nxt.text, trees[i + 3].text))
+                i += 4
+                continue
+        if isinstance(t, Group):
+            elems.append(GroupPat(t.delim, compile_pattern(t.children)))
+        else:
+            elems.append(t)
+        i += 1
+    return elems
+
+
+def pattern_names(elems):
+    for e in elems:
+        if isinstance(e, Frag):
+            yield e.name
+        elif isinstance(e, (Rep, GroupPat)):
+            yield from pattern_names(e.elems)
+
+
+def match_seq(elems, trees, pos):
+    binds = {}
+    for e in elems:
+        if isinstance(e, Frag):
+            if e.kind in SINGLE_TREE:
+                if pos >= len(trees):
+                    return None
+                binds[e.name] = [trees[pos]]
+                pos += 1
+            else:
+                start = pos
+                while pos < len(trees) and not any(trees[pos].is_(s) for s in (",", ";", "=>")):
+                    pos += 1
+                if pos == start:
+                    return None
+                binds[e.name] = trees[start:pos]
+        elif isinstance(e, Rep):
+            runs = []
+            while True:
+                p = pos
+                if runs and e.sep:
+                    if p < len(trees) and trees[p].is_(e.sep):
+                        p += 1
+                    else:
+                        break
+                matched = match_seq(e.elems, trees, p)
+                if matched is None or matched[1] == p:
+                    break
+                runs.append(matched[0])
+                pos = matched[1]
+                if e.op == "?":
+                    break
+            if e.op == "+" and not runs:
+                return None
+            for name in pattern_names(e.elems):
+                binds[name] = runs
+        elif isinstance(e, GroupPat):
+            if pos >= len(trees) or not isinstance(trees[pos], Group) or trees[pos].delim != e.delim:
+                return None
+            inner = trees[pos].children
+            matched = match_seq(e.elems, inner, 0)
+            if matched is None or matched[1] != len(inner):
+                return None
+            binds.update(matched[0])
+            pos += 1
+        else:
+            if pos >= len(trees) or not trees[pos].is_(e.text) or trees[pos].kind != e.kind:
+                return None
+            pos += 1
+    return binds, pos
+
+
+def transcribe(trees, binds):
+    out, i = [], 0
+    while i < len(trees):
+        t = trees[i]
+        nxt = trees[i + 1] if i + 1 < len(trees) else None
+        if t.is_("$") and isinstance(nxt, Group) and nxt.delim == "(":
+            i += 2
+            sep = None
+            if not any(trees[i].is_(op) for op in "?*+"):
+                sep = trees[i]
+                i += 1
+            i += 1
+            runs = {name: value for name, value in binds.items() if isinstance(value, list)
+                    and value and isinstance(value[0], dict)}
+            used = [name for name in runs if mentions_var(nxt.children, name)]
+            counts = {len(binds[name]) for name in used}
+            if any(v == [] and mentions_var(nxt.children, n) for n, v in binds.items()):
+                counts.add(0)
+            if len(counts) != 1:
+                raise StubError("can't tell how often to repeat a macro group")
+            for k in range(counts.pop()):
+                if k and sep is not None:
+                    out.append(sep)
+                inner = dict(binds)
+                for name in used:
+                    inner.update(binds[name][k])
+                out.extend(transcribe(nxt.children, inner))
+            continue
+        if t.is_("$") and nxt is not None and nxt.kind == "ident":
+            value = binds.get(nxt.text)
+            if value is None:
+                out.append(nxt)  # $crate
+            else:
+                out.extend(value)
+            i += 2
+            continue
+        if isinstance(t, Group):
+            out.append(Group(t.delim, transcribe(t.children, binds)))
+        else:
+            out.append(t)
+        i += 1
+    return out
+
+
+def mentions_var(trees, name):
+    for a, b in zip(trees, trees[1:]):
+        if a.is_("$") and b.kind == "ident" and b.text == name:
+            return True
+    return any(isinstance(t, Group) and mentions_var(t.children, name) for t in trees)
+
+
+def find_macros(trees, macros):
+    for i, t in enumerate(trees):
+        if t.kind == "ident" and t.text == "macro_rules" and trees[i + 1].is_("!"):
+            name, body = trees[i + 2].text, trees[i + 3].children
+            arms = []
+            for j in range(0, len(body), 4):
+                arms.append((compile_pattern(body[j].children), body[j + 2].children))
+            macros[name] = Macro(name, arms)
+        elif isinstance(t, Group):
+            find_macros(t.children, macros)
+
+
+def expand(trees, macros, depth=0):
+    """Expand invocations of the macros that define bindings, recursively."""
+    if depth > 32:
+        raise StubError("macro recursion too deep")
+    out, i = [], 0
+    while i < len(trees):
+        t = trees[i]
+        if t.is_("macro_rules"):
+            out.extend(trees[i : i + 4])
+            i += 4
+            continue
+        if (
+            t.kind == "ident"
+            and t.text in macros
+            and i + 2 < len(trees)
+            and trees[i + 1].is_("!")
+            and isinstance(trees[i + 2], Group)
+            and macros[t.text].makes_bindings()
+        ):
+            out.extend(expand(invoke(macros[t.text], trees[i + 2].children), macros, depth + 1))
+            i += 3
+            if i < len(trees) and trees[i].is_(";"):
+                i += 1
+            continue
+        if isinstance(t, Group):
+            out.append(Group(t.delim, expand(t.children, macros, depth)))
+        else:
+            out.append(t)
+        i += 1
+    return out
+
+
+def invoke(macro, args):
+    for pattern, body in macro.arms:
+        matched = match_seq(pattern, args, 0)
+        if matched is not None and matched[1] == len(args):
+            return transcribe(body, matched[0])
+    raise StubError(f"no arm of {macro.name}! matches {render(args)}")
+
+
+def render(trees):
+    parts = []
+    for t in trees:
+        if isinstance(t, Group):
+            parts.append(t.delim + render(t.children) + CLOSE[t.delim])
+        else:
+            parts.append(t.text)
+    return " ".join(parts)
+
+
+# ─── Rust items ──────────────────────────────────────────────────────────────
+
+
+@dataclass
+class Param:
+    name: str
+    ty: list
+    default: str | None = None  # Python source, None for required
+    kind: str = "normal"  # normal, varargs, kwargs, kwonly-marker
+
+
+@dataclass
+class Function:
+    name: str  # Python name
+    rust_name: str
+    module: tuple
+    attrs: list  # each attribute's token trees
+    stubs: dict  # `// stub:` overrides: param name or "->" to annotation
+    params: list  # Param, receiver and Python<'py> dropped
+    ret: list | None
+    receiver: bool = False
+    location: str = ""
+
+    def has_attr(self, name):
+        return any(a and a[0].kind == "ident" and a[0].text == name for a in self.attrs)
+
+    def attr_args(self, name):
+        for a in self.attrs:
+            if a and a[0].kind == "ident" and a[0].text == name and len(a) > 1:
+                return a[1].children
+        return None
+
+
+@dataclass
+class PyClass:
+    name: str  # Python name
+    rust_name: str
+    fields: list = field(default_factory=list)  # (name, type tokens, settable)
+    methods: list = field(default_factory=list)
+    location: str = ""
+
+
+@dataclass
+class Source:
+    functions: list = field(default_factory=list)
+    classes: dict = field(default_factory=dict)  # rust name -> PyClass
+    pymethods: list = field(default_factory=list)  # (rust type name, [Function])
+    consts: dict = field(default_factory=dict)  # name -> [expr tokens]
+    aliases: dict = field(default_factory=dict)  # name -> type tokens
+    type_stubs: dict = field(default_factory=dict)  # rust type name -> annotation
+    unions: dict = field(default_factory=dict)  # #[derive(FromPyObject)] enum -> variant types
+
+
+def parse_stub_comments(comments, where):
+    overrides, last = {}, None
+    for text in comments:
+        body = text[len("// stub:") :].strip()
+        if body.startswith("|") and last is not None:
+            overrides[last] += " " + body
+        elif body.startswith("->"):
+            overrides["->"] = body[2:].strip()
+            last = "->"
+        else:
+            name, sep, annotation = body.partition(":")
+            if not sep:
+                raise StubError(f"{where}: can't read {text!r}")
+            overrides[name.strip()] = annotation.strip()
+            last = name.strip()
+    return overrides
+
+
+def pyo3_options(fn_attrs):
+    """name = value pairs from every #[pyo3(...)] attribute."""
+    options = {}
+    for a in fn_attrs:
+        if a and a[0].is_("pyo3") and len(a) > 1:
+            for part in split_on(a[1].children):
+                if len(part) >= 3 and part[1].is_("="):
+                    options[part[0].text] = part[2:]
+                elif part:
+                    options[part[0].text] = None
+    return options
+
+
+def parse_fn(trees, i, module, attrs, stubs, where):
+    """Parse `fn name<...>(params) -> ret` starting at the `fn` token."""
+    name = trees[i + 1].text
+    i += 2
+    while not (isinstance(trees[i], Group) and trees[i].delim == "("):
+        i += 1
+    raw_params = split_on(trees[i].children)
+    i += 1
+    ret = None
+    if i < len(trees) and trees[i].is_("->"):
+        start = i + 1
+        while not (isinstance(trees[i], Group) and trees[i].delim == "{") and not trees[i].is_("where"):
+            i += 1
+        ret = trees[start:i]
+    while not (isinstance(trees[i], Group) and trees[i].delim == "{") and not trees[i].is_(";"):
+        i += 1
+
+    params, receiver = [], False
+    for k, p in enumerate(raw_params):
+        words = [t for t in p if not t.is_("mut")]
+        if any(t.is_("self") for t in words[:3]) and not any(t.is_(":") for t in words[:2]):
+            receiver = True
+            continue
+        pname, ty = words[0].text, words[2:]
+        if k == 0 and pname in ("slf", "self_"):
+            receiver = True
+            continue
+        if ty and ty[0].is_("Python"):
+            continue
+        params.append(Param(pname, ty))
+
+    options = pyo3_options(attrs)
+    fn = Function(
+        name=render(options["name"]).strip('"') if options.get("name") else name,
+        rust_name=name,
+        module=module,
+        attrs=attrs,
+        stubs=parse_stub_comments(stubs, f"{where}: fn {name}"),
+        params=params,
+        ret=ret,
+        receiver=receiver,
+        location=f"{where}: fn {name}",
+    )
+    if any(a and a[0].is_("classmethod") for a in attrs) and fn.params:
+        fn.params = fn.params[1:]
+    if options.get("signature"):
+        apply_signature(fn, options["signature"][0].children)
+    else:
+        # Without a signature, PyO3 makes trailing Option<T> parameters optional.
+        for p in reversed(fn.params):
+            if not p.ty or not p.ty[0].is_("Option"):
+                break
+            p.default = [Tok("ident", "None")]
+    return fn, i + 1
+
+
+def apply_signature(fn, entries):
+    by_name = {p.name: p for p in fn.params}
+    params = []
+    for entry in split_on(entries):
+        if entry[0].is_("/"):
+            params.append(Param("/", [], kind="posonly-marker"))
+        elif entry[0].is_("*") and len(entry) == 1:
+            params.append(Param("*", [], kind="kwonly-marker"))
+        elif entry[0].is_("*") and entry[1].is_("*"):
+            params.append(Param(entry[2].text, by_name[entry[2].text].ty, kind="kwargs"))
+        elif entry[0].is_("*"):
+            params.append(Param(entry[1].text, by_name[entry[1].text].ty, kind="varargs"))
+        else:
+            p = by_name.get(entry[0].text)
+            if p is None:
+                raise StubError(f"{fn.location}: signature names unknown parameter {entry[0].text}")
+            default = entry[2:] if len(entry) > 2 and entry[1].is_("=") else None
+            params.append(Param(p.name, p.ty, default=default))
+    fn.params = params
+
+
+def parse_items(trees, module, where, out, in_pymethods=None):
+    attrs, stubs, i = [], [], 0
+    while i < len(trees):
+        t = trees[i]
+        if t.kind == "stub":
+            stubs.append(t.text)
+            i += 1
+            continue
+        if t.is_("#") and i + 1 < len(trees) and isinstance(trees[i + 1], Group):
+            attrs.append(trees[i + 1].children)
+            i += 2
+            continue
+        if t.is_("#") and trees[i + 1].is_("!"):
+            i += 3
+            continue
+        if t.is_("pub"):
+            i += 1
+            if i < len(trees) and isinstance(trees[i], Group) and trees[i].delim == "(":
+                i += 1
+            continue
+        if t.kind == "ident" and t.text in ("unsafe", "const", "async", "extern") and (
+            trees[i + 1].is_("fn") or trees[i + 1].kind == "str"
+        ):
+            i += 1
+            continue
+        if t.is_("fn"):
+            fn, i = parse_fn(trees, i, module, attrs, stubs, where)
+            if in_pymethods is not None:
+                in_pymethods.append(fn)
+            elif any(a and a[0].is_("pyfunction") for a in attrs):
+                out.functions.append(fn)
+            attrs, stubs = [], []
+            continue
+        if t.is_("mod") and isinstance(trees[i + 2], Group):
+            if not any(render(a).startswith("cfg ( test") for a in attrs):
+                parse_items(trees[i + 2].children, module + (trees[i + 1].text,), where, out)
+            attrs, stubs = [], []
+            i += 3
+            continue
+        if t.kind == "ident" and t.text in ("struct", "enum"):
+            name = trees[i + 1].text
+            if stubs:
+                out.type_stubs[name] = stubs[-1][len("// stub:") :].strip()
+            pyclass = next((a for a in attrs if a and a[0].is_("pyclass")), None)
+            j = i + 2
+            while j < len(trees) and not trees[j].is_(";") and not (
+                isinstance(trees[j], Group) and trees[j].delim in "{("
+            ):
+                j += 1
+            if pyclass is not None:
+                options = pyo3_options([[Tok("ident", "pyo3")] + pyclass[1:]])
+                cls = PyClass(
+                    name=render(options["name"]).strip('"') if options.get("name") else name,
+                    rust_name=name,
+                    location=f"{where}: struct {name}",
+                )
+                if t.is_("struct") and isinstance(trees[j], Group) and trees[j].delim == "{":
+                    cls.fields = pyclass_fields(trees[j].children)
+                out.classes[name] = cls
+            if t.is_("enum") and "FromPyObject" in " ".join(render(a) for a in attrs):
+                out.unions[name] = [variant[1].children for variant in split_on(trees[j].children)]
+            attrs, stubs = [], []
+            i = j + 1
+            continue
+        if t.is_("impl"):
+            j = i + 1
+            while not (isinstance(trees[j], Group) and trees[j].delim == "{"):
+                j += 1
+            header = [x for x in trees[i + 1 : j] if x.kind == "ident"]
+            if any(a and a[0].is_("pymethods") for a in attrs):
+                methods = []
+                parse_items(trees[j].children, module, where, out, in_pymethods=methods)
+                out.pymethods.append((header[-1].text, methods))
+            attrs, stubs = [], []
+            i = j + 1
+            continue
+        if t.is_("type") and trees[i + 2].is_("="):
+            j = i + 3
+            while not trees[j].is_(";"):
+                j += 1
+            out.aliases[trees[i + 1].text] = trees[i + 3 : j]
+            if stubs:
+                out.type_stubs[trees[i + 1].text] = stubs[-1][len("// stub:") :].strip()
+            attrs, stubs = [], []
+            i = j + 1
+            continue
+        if t.kind == "ident" and t.text in ("const", "static") and trees[i + 2].is_(":"):
+            j = i + 3
+            while not trees[j].is_("="):
+                j += 1
+            k = j + 1
+            while not trees[k].is_(";"):
+                k += 1
+            out.consts.setdefault(trees[i + 1].text, []).append(trees[j + 1 : k])
+            attrs, stubs = [], []
+            i = k + 1
+            continue
+        if t.is_(";") or isinstance(t, Group):
+            attrs, stubs = [], []
+        i += 1
+
+
+def pyclass_fields(trees):
+    fields = []
+    for part in split_on(trees):
+        attrs, k = [], 0
+        while k < len(part) and part[k].is_("#"):
+            attrs.append(part[k + 1].children)
+            k += 2
+        options = pyo3_options(attrs)
+        if "get" not in options and "set" not in options:
+            continue
+        while part[k].is_("pub") or isinstance(part[k], Group):
+            k += 1
+        fields.append((part[k].text, part[k + 2 :], "set" in options))
+    return fields
+
+
+def load_sources():
+    source = Source()
+    macros, files = {}, []
+    for path in sorted(SRC.rglob("*.rs")):
+        trees = tokenize(path.read_text())
+        find_macros(trees, macros)
+        files.append((path, trees))
+    for path, trees in files:
+        rel = path.relative_to(SRC).with_suffix("")
+        module = () if rel.name in ("lib", "mod") and len(rel.parts) == 1 else rel.parts
+        parse_items(expand(trees, macros), tuple(module), path.name, source)
+    for rust_name, methods in source.pymethods:
+        if rust_name not in source.classes:
+            raise StubError(f"#[pymethods] for {rust_name}, which isn't a #[pyclass]")
+        source.classes[rust_name].methods.extend(methods)
+    return source
+
+
+# ─── Rust types to Python annotations ────────────────────────────────────────
+
+INTS = {"u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize"}
+SIMPLE = {
+    "bool": "bool",
+    "f32": "float",
+    "f64": "float",
+    "String": "str",
+    "str": "str",
+    "char": "str",
+    "PyBytes": "bytes",
+    "PyString": "str",
+    "PyBool": "bool",
+    "PyInt": "int",
+    "PyLong": "int",
+    "PyFloat": "float",
+    "Buffer": "Buffer",
+    "PathBuf": "str | os.PathLike[str]",
+    "Path": "str | os.PathLike[str]",
+}
+WRAPPERS = {"Py", "Bound", "Borrowed", "PyRef", "PyRefMut", "Box", "Cow", "Arc", "PyResult"}
+
+
+@dataclass
+class RustType:
+    name: str  # last path segment, "&", "()" for tuples, "[]" for slices
+    args: list
+
+
+def parse_type(trees):
+    ty, rest = parse_type_at(trees, 0)
+    if rest != len(trees):
+        raise StubError(f"can't read Rust type {render(trees)}")
+    return ty
+
+
+def parse_type_at(trees, i):
+    t = trees[i]
+    if t.is_("&"):
+        i += 1
+        while i < len(trees) and (trees[i].kind == "lifetime" or trees[i].is_("mut")):
+            i += 1
+        inner, i = parse_type_at(trees, i)
+        return RustType("&", [inner]), i
+    if isinstance(t, Group) and t.delim == "(":
+        return RustType("()", [parse_type(p) for p in split_on(t.children)]), i + 1
+    if isinstance(t, Group) and t.delim == "[":
+        return RustType("[]", [parse_type(split_on(t.children, ";")[0])]), i + 1
+    if t.kind == "ident" and t.text in ("impl", "dyn"):
+        depth = 0
+        while i < len(trees) and not (depth == 0 and trees[i].is_(",")):
+            depth += trees[i].is_("<") - trees[i].is_(">")
+            i += 1
+        return RustType("impl", []), i
+    name = None
+    while i < len(trees):
+        if trees[i].kind == "ident":
+            name = trees[i].text
+            i += 1
+            if i < len(trees) and trees[i].is_("::"):
+                i += 1
+                continue
+        break
+    if name is None:
+        raise StubError(f"can't read Rust type {render(trees)}")
+    args = []
+    if i < len(trees) and trees[i].is_("<"):
+        depth, start, j = 0, i + 1, i
+        while True:
+            if trees[j].is_("<"):
+                depth += 1
+            elif trees[j].is_(">"):
+                depth -= 1
+                if depth == 0:
+                    break
+            j += 1
+        for part in split_on(trees[start:j]):
+            if part[0].kind != "lifetime":
+                args.append(parse_type(part))
+        i = j + 1
+    return RustType(name, args), i
+
+
+class TypeMapper:
+    def __init__(self, source):
+        self.source = source
+        self.classes = {c.rust_name: c.name for c in source.classes.values()}
+
+    def annotation(self, trees, arg, where, owner=None):
+        return self.map(parse_type(trees), arg, where, owner)
+
+    def map(self, ty, arg, where, owner):
+        name, args = ty.name, ty.args
+        if name == "&":
+            inner = args[0]
+            if inner.name == "[]" and inner.args[0].name == "u8":
+                return "bytes"
+            return self.map(inner, arg, where, owner)
+        if name == "()":
+            if not args:
+                return "None"
+            return "tuple[" + ", ".join(self.map(a, arg, where, owner) for a in args) + "]"
+        if name in ("Result", "PyResult") and args:
+            return self.map(args[0], arg, where, owner)
+        if name in self.source.type_stubs:
+            return self.source.type_stubs[name]
+        if name in self.source.unions:
+            members = [self.annotation(v, arg, where, owner) for v in self.source.unions[name]]
+            return " | ".join(dict.fromkeys(members))
+        if name in self.source.aliases:
+            return self.annotation(self.source.aliases[name], arg, where, owner)
+        if name == "Option":
+            inner = self.map(args[0], arg, where, owner)
+            return inner if inner.endswith("| None") else f"{inner} | None"
+        if name in ("Vec", "VecDeque", "SmallVec"):
+            if args[0].name == "u8" and not arg:
+                return "list[int]"
+            inner = self.map(args[0], arg, where, owner)
+            return f"Sequence[{inner}]" if arg else f"list[{inner}]"
+        if name in ("HashMap", "BTreeMap", "IndexMap"):
+            k, v = (self.map(a, arg, where, owner) for a in args[:2])
+            return f"Mapping[{k}, {v}]" if arg else f"dict[{k}, {v}]"
+        if name in WRAPPERS and args:
+            if name in ("PyRef", "PyRefMut") and args[0].name == "Self" and not arg:
+                return "Self"
+            return self.map(args[0], arg, where, owner)
+        if name == "Self" and owner is not None:
+            return owner
+        if name in INTS:
+            return "int"
+        if name in ("PathBuf", "Path") and not arg:
+            return "str"
+        if name in SIMPLE:
+            return SIMPLE[name]
+        if name in self.classes:
+            return self.classes[name]
+        raise StubError(
+            f"{where}: no Python annotation for Rust type {name}; "
+            "add a `// stub:` comment to the item or to the type"
+        )
+
+
+# ─── Defaults ────────────────────────────────────────────────────────────────
+
+
+def default_value(expr, source):
+    """The Python source for a #[pyo3(signature)] default, or `...`."""
+    text = render(expr).replace(" ", "")
+    if text == "None":
+        return "None"
+    if text in ("true", "false"):
+        return text.capitalize()
+    if text in ("Buffer::empty()", "Vec::new()"):
+        return 'b""' if text.startswith("Buffer") else "[]"
+    if len(expr) == 1 and expr[0].kind == "str" and not expr[0].text.startswith(("b", "r")):
+        return expr[0].text
+    if len(expr) == 3 and expr[0].is_("vec") and expr[1].is_("!"):
+        items = [default_value(item, source) for item in split_on(expr[2].children)]
+        return "..." if "..." in items else "[" + ", ".join(items) + "]"
+    value = const_value(expr, source, set())
+    if value is not None:
+        return str(value)
+    if len(expr) == 1 and expr[0].kind == "ident" and len(source.consts.get(expr[0].text, [])) == 1:
+        return default_value(source.consts[expr[0].text][0], source)
+    return "..."
+
+
+def const_value(expr, source, seen):
+    """Evaluate integer arithmetic over literals and consts, or None."""
+    parts = []
+    i = 0
+    while i < len(expr):
+        t = expr[i]
+        if isinstance(t, Group):
+            if t.delim != "(":
+                return None
+            inner = const_value(t.children, source, seen)
+            if inner is None:
+                return None
+            parts.append(str(inner))
+        elif t.kind == "num":
+            digits = re.sub(r"(?<=[0-9a-fA-F])_|(?:u|i)(?:8|16|32|64|128|size)$", "", t.text)
+            parts.append(str(int(digits.replace("_", ""), 0)))
+        elif t.is_("as"):
+            i += 2  # `as u32`: all integers here
+            continue
+        elif t.kind == "ident":
+            while i + 2 < len(expr) and expr[i + 1].is_("::"):
+                i += 2
+            name = expr[i].text
+            defs = source.consts.get(name)
+            if not defs or name in seen:
+                return None
+            values = {const_value(d, source, seen | {name}) for d in defs}
+            if len(values) != 1 or None in values:
+                return None
+            parts.append(str(values.pop()))
+        elif t.kind == "punct" and t.text in "+-*/<>|&" and t.text:
+            parts.append("//" if t.text == "/" else t.text)
+        else:
+            return None
+        i += 1
+    try:
+        value = eval("".join(parts), {"__builtins__": {}})  # noqa: S307 - digits and operators only
+    except SyntaxError:
+        return None
+    return value if isinstance(value, int) else None
+
+
+# ─── Stub text ───────────────────────────────────────────────────────────────
+
+
+def join_fits(prefix, items, suffix, indent):
+    line = indent + prefix + ", ".join(items) + suffix
+    return line if len(line) <= LINE_LENGTH else None
+
+
+def format_def(name, params, ret, indent="", decorators=()):
+    """A `def` the way black formats one in a .pyi."""
+    lines = [indent + d for d in decorators]
+    head, tail = f"def {name}(", f") -> {ret}: ..."
+    one = join_fits(head, params, tail, indent)
+    if one is not None:
+        return lines + [one]
+    inner = indent + "    "
+    tail_lines = [indent + tail]
+    if len(indent + tail) > LINE_LENGTH and len(top_level(ret, " | ")) > 1:
+        tail_lines = [indent + ") -> ("] + parenthesized(ret, indent) + [indent + "): ..."]
+    middle = inner + ", ".join(params)
+    if len(middle) <= LINE_LENGTH:
+        return lines + [indent + head, middle] + tail_lines
+    lines.append(indent + head)
+    for p in params:
+        lines.extend(split_param(inner + p + ",", inner))
+    return lines + tail_lines
+
+
+def top_level(text, sep):
+    """Split `text` at `sep` outside brackets and quotes."""
+    parts, depth, start, quoted = [], 0, 0, False
+    for i, ch in enumerate(text):
+        if ch == '"':
+            quoted = not quoted
+        elif not quoted and ch in "[(":
+            depth += 1
+        elif not quoted and ch in "])":
+            depth -= 1
+        elif not quoted and depth == 0 and text.startswith(sep, i):
+            parts.append(text[start:i])
+            start = i + len(sep)
+    return parts + [text[start:]]
+
+
+def parenthesized(union, indent):
+    """The members of a long union, one line if they fit and one each if not."""
+    line = indent + "    " + union
+    if len(line) <= LINE_LENGTH:
+        return [line]
+    members = top_level(union, " | ")
+    return [indent + "    " + members[0]] + [indent + "    | " + m for m in members[1:]]
+
+
+def split_param(line, indent):
+    """Break a too-long `name: annotation = default,` line: a union goes in
+    parentheses, one member per line, and a subscript opens onto its own
+    line."""
+    if len(line) <= LINE_LENGTH:
+        return [line]
+    name, _, rest = line.strip().partition(": ")
+    annotation, *default = top_level(rest[:-1], " = ")
+    tail = "".join(" = " + d for d in default) + ","
+    if len(top_level(annotation, " | ")) > 1:
+        return [f"{indent}{name}: ("] + parenthesized(annotation, indent) + [indent + ")" + tail]
+    if "[" not in annotation:
+        return [line]
+    start = annotation.index("[")
+    return [
+        f"{indent}{name}: {annotation[: start + 1]}",
+        indent + "    " + annotation[start + 1 : -1],
+        indent + "]" + tail,
+    ]
+
+
+class StubWriter:
+    def __init__(self, module, source):
+        self.module = module
+        self.source = source
+        self.types = TypeMapper(source)
+        self.functions = {}
+        for fn in source.functions:
+            self.functions.setdefault(fn.name, []).append(fn)
+        self.classes = {c.name: c for c in source.classes.values()}
+
+    # Functions and methods
+
+    def params(self, fn, owner=None):
+        out = []
+        for p in fn.params:
+            if p.kind == "kwonly-marker":
+                out.append("*")
+                continue
+            if p.kind == "posonly-marker":
+                out.append("/")
+                continue
+            ann = fn.stubs.get(p.name)
+            if ann is None:
+                ann = self.types.annotation(p.ty, True, fn.location, owner)
+                if p.kind == "varargs":
+                    ann = ann.removeprefix("Sequence[").removesuffix("]")
+            star = {"varargs": "*", "kwargs": "**"}.get(p.kind, "")
+            text = f"{star}{p.name}: {ann}"
+            if p.default is not None:
+                text += " = " + default_value(p.default, self.source)
+            out.append(text)
+        unknown = set(fn.stubs) - {p.name for p in fn.params} - {"->"}
+        if unknown:
+            raise StubError(f"{fn.location}: `// stub:` for unknown parameters {sorted(unknown)}")
+        return out
+
+    def returns(self, fn, owner=None):
+        if "->" in fn.stubs:
+            return fn.stubs["->"]
+        if fn.ret is None:
+            return "None"
+        return self.types.annotation(fn.ret, False, fn.location, owner)
+
+    def function(self, name):
+        candidates = self.functions.get(name, [])
+        if len(candidates) != 1:
+            raise StubError(f"{len(candidates)} #[pyfunction]s are called {name}")
+        fn = candidates[0]
+        return format_def(name, self.params(fn), self.returns(fn))
+
+    # Classes
+
+    def pyclass(self, name):
+        cls = self.classes.get(name)
+        if cls is None:
+            raise StubError(f"{name} is in the module but isn't a #[pyclass] in src/")
+        body = []
+        for fname, ty, _ in cls.fields:
+            body.append(f"    {fname}: {self.types.annotation(ty, False, cls.location, cls.name)}")
+        for fn in cls.methods:
+            body.extend(self.method(cls, fn))
+        lines = [f"class {name}:"]
+        return lines + (body or ["    ..."])
+
+    def method(self, cls, fn):
+        name, owner = fn.name, cls.name
+        getter = fn.attr_args("getter")
+        if fn.has_attr("getter"):
+            prop = getter[0].text if getter else fn.name.removeprefix("get_")
+            return ["    @property", f"    def {prop}(self) -> {self.returns(fn, owner)}: ..."]
+        if fn.has_attr("setter"):
+            setter = fn.attr_args("setter")
+            prop = setter[0].text if setter else fn.name.removeprefix("set_")
+            value = self.params(fn, owner)
+            return [f"    @{prop}.setter", f"    def {prop}(self, {value[0]}) -> None: ..."]
+        if fn.has_attr("new"):
+            return format_def("__init__", ["self"] + self.params(fn, owner), "None", "    ")
+        if name in SKIPPED_DUNDERS:
+            return []
+        if name in PROTOCOL_DUNDERS:
+            params, ret = PROTOCOL_DUNDERS[name]
+            return format_def(params[0], ["self"] + params[1:], ret or self.returns(fn, owner), "    ")
+        decorators, first = [], ["self"]
+        if fn.has_attr("staticmethod"):
+            decorators, first = ["@staticmethod"], []
+        elif fn.has_attr("classmethod"):
+            decorators, first = ["@classmethod"], ["cls"]
+        elif not fn.receiver:
+            raise StubError(f"{fn.location}: method without self, #[staticmethod] or #[classmethod]")
+        return format_def(name, first + self.params(fn, owner), self.returns(fn, owner), "    ", decorators)
+
+    # Module
+
+    def generate(self):
+        sections = module_sections(self.module, self.source)
+        blocks = []  # (section comment or None, kind, lines)
+        for comment, kind, name, value in sections:
+            if kind == "function":
+                lines = self.function(name)
+            elif kind == "class":
+                lines = self.pyclass(name)
+            elif kind == "exception":
+                base = value.__bases__[0]
+                lines = [f"class {name}({base.__name__}): ..."]
+            elif kind == "constant":
+                lines = [f"{name}: Final[int]"]
+            else:  # namespaces
+                lines = self.namespaces(value)
+                kind = "class"
+            blocks.append((comment, kind, lines))
+
+        body = []
+        for k, (comment, kind, lines) in enumerate(blocks):
+            if comment is not None:
+                if body:
+                    body.append("")
+                body.extend("# " + c if c else "#" for c in comment)
+            elif body and (kind == "class" and len(lines) > 1 or blocks[k - 1][1] == "class"
+                           and len(blocks[k - 1][2]) > 1):
+                body.append("")
+            body.extend(lines)
+        text = "\n".join(body) + "\n"
+        return HEADER + "\n" + imports(text) + "\n" + text
+
+    def namespaces(self, modules):
+        lines = []
+        for name, sub in modules:
+            lines.append(f"class _{name.capitalize()}Namespace:")
+            for attr, value in vars(sub).items():
+                if attr.startswith("__"):
+                    continue
+                if isinstance(value, type):
+                    lines.append(f"    {attr}: type[{value.__name__}]")
+                elif callable(value) and type(value).__name__ == "builtin_function_or_method":
+                    lines.append(f"    {attr} = {value.__name__}")
+                else:
+                    lines.append(f"    {attr}: {type(value).__name__}")
+            lines.append("")
+        lines.extend(f"{name}: _{name.capitalize()}Namespace" for name, _ in modules)
+        return lines
+
+
+# __repr__ and pickling are object's business; the buffer release slot has
+# no Python-level method.
+SKIPPED_DUNDERS = {"__repr__", "__str__", "__reduce__", "__setstate__", "__getstate__",
+                   "__releasebuffer__", "__hash__", "__traverse__", "__clear__"}
+
+# Protocol methods with the signature Python gives them, whatever the Rust
+# side takes: (Python name and parameters, return annotation or None to map
+# the Rust one).
+PROTOCOL_DUNDERS = {
+    "__enter__": (["__enter__"], "Self"),
+    "__exit__": (
+        [
+            "__exit__",
+            "exc_type: type[BaseException] | None",
+            "exc_value: BaseException | None",
+            "traceback: TracebackType | None",
+        ],
+        None,
+    ),
+    "__eq__": (["__eq__", "other: object"], "bool"),
+    "__getbuffer__": (["__buffer__", "flags: int", "/"], "memoryview"),
+}
+
+
+def imports(text):
+    wanted = [
+        ("import os", r"\bos\."),
+        ("from collections.abc import {}", r"\b(Mapping|Sequence)\["),
+        ("from types import {}", r"\b(TracebackType)\b"),
+        ("from typing import {}", r"\b(Any|Final|Literal)\b"),
+    ]
+    lines = []
+    for template, pattern in wanted:
+        names = sorted(set(re.findall(pattern, text)))
+        if names:
+            lines.append(template.format(", ".join(names)))
+    extensions = sorted(set(re.findall(r"\b(Buffer|Self)\b", text)))
+    if extensions:
+        lines += ["", "from typing_extensions import " + ", ".join(extensions)]
+    return "\n".join(lines) + "\n" if lines else ""
+
+
+# ─── Module layout ───────────────────────────────────────────────────────────
+
+
+@dataclass
+class Statement:
+    comment: list | None  # section comment lines, on the first statement of each
+    names: list  # Python names it registers, if they can be read off the source
+    dynamic: bool  # a call that takes the module and registers names itself
+
+
+REGISTRATION = [
+    (re.compile(r"wrap_pyfunction!\(\s*(?:[\w:]*::)?(\w+)\s*,"), "function"),
+    (re.compile(r"add_class::<\s*(?:[\w:]*::)?(\w+)\s*>"), "class"),
+    (re.compile(r"\bm\.add\(\s*\"(\w+)\""), "value"),
+]
+
+
+def pymodule_statements(source):
+    """The #[pymodule] body, statement by statement, with its comments."""
+    text = (SRC / "lib.rs").read_text()
+    start = text.index("#[pymodule]")
+    body_start = text.index("{", text.index("fn ", start)) + 1
+    depth, end = 1, body_start
+    while depth:
+        depth += (text[end] == "{") - (text[end] == "}")
+        end += 1
+    body = text[body_start : end - 1]
+
+    rust_to_py = {fn.rust_name: fn.name for fn in source.functions}
+    classes = {c.rust_name: c.name for c in source.classes.values()}
+    statements, comment, current = [], None, ""
+    for line in body.splitlines():
+        stripped = line.strip()
+        if stripped.startswith("//"):
+            if current:
+                continue
+            comment = (comment or []) + [stripped[2:].strip()]
+            continue
+        if not stripped:
+            continue
+        current += " " + stripped
+        if not stripped.endswith(";") and not stripped.endswith("{") and stripped != "Ok(())":
+            continue
+        names = []
+        for pattern, kind in REGISTRATION:
+            for m in pattern.finditer(current):
+                name = m.group(1)
+                names.append(rust_to_py.get(name, name) if kind == "function" else
+                             classes.get(name, name) if kind == "class" else name)
+        dynamic = not names and re.search(r"\(\s*(?:py\s*,\s*)?m\s*\)", current) is not None
+        statements.append(Statement(comment, names, dynamic))
+        comment, current = None, ""
+    return statements
+
+
+def module_sections(module, source):
+    """(section comment or None, kind, name, value) for everything the module
+    exports, in registration order."""
+    exported = [(n, v) for n, v in vars(module).items() if not n.startswith("__")]
+    position = {n: k for k, (n, _) in enumerate(exported)}
+    statements = pymodule_statements(source)
+
+    # Everything a statement names statically is its own; the names in
+    # between belong to the dynamic statement before them.
+    owner, last_dynamic, cursor = {}, None, 0
+    section = None
+    sections = []
+    for st in statements:
+        if st.comment is not None:
+            section = st.comment
+        sections.append(section)
+        if st.dynamic:
+            last_dynamic = st
+        for name in st.names:
+            if name not in position:
+                raise StubError(f"#[pymodule] registers {name}, which the module lacks")
+            for n, _ in exported[cursor : position[name]]:
+                if last_dynamic is None:
+                    raise StubError(f"can't tell which #[pymodule] statement adds {n}")
+                owner[n] = last_dynamic
+            owner[name] = st
+            cursor = max(cursor, position[name] + 1)
+            last_dynamic = None
+    for n, _ in exported[cursor:]:
+        if last_dynamic is None:
+            raise StubError(f"can't tell which #[pymodule] statement adds {n}")
+        owner[n] = last_dynamic
+
+    section_of = {id(st): sec for st, sec in zip(statements, sections)}
+    out, shown, modules = [], set(), []
+    for name, value in exported:
+        sec = section_of[id(owner[name])]
+        comment = None
+        if sec is not None and id(sec) not in shown:
+            shown.add(id(sec))
+            comment = sec
+        if type(value).__name__ == "module":
+            modules.append((name, value))
+            if comment is not None or not out or out[-1][1] != "namespaces":
+                out.append((comment, "namespaces", name, modules[-1:]))
+            else:
+                out[-1][3].append((name, value))
+            continue
+        if isinstance(value, type) and issubclass(value, BaseException):
+            kind = "exception"
+        elif isinstance(value, type):
+            kind = "class"
+        elif isinstance(value, int):
+            kind = "constant"
+        elif callable(value):
+            kind = "function"
+        else:
+            raise StubError(f"don't know how to annotate {name} ({type(value).__name__})")
+        out.append((comment, kind, name, value))
+    return out
+
+
+# ─── Entry point ─────────────────────────────────────────────────────────────
+
+
+def main():
+    parser = argparse.ArgumentParser(description=__doc__.splitlines()[0])
+    parser.add_argument("--check", action="store_true", help="fail if the stub is out of date")
+    args = parser.parse_args()
+
+    import pqcrypto_bindings
+
+    try:
+        text = StubWriter(pqcrypto_bindings, load_sources()).generate()
+    except StubError as e:
+        print(f"gen_stubs.py: {e}", file=sys.stderr)
+        return 2
+
+    if args.check:
+        current = STUB.read_text() if STUB.exists() else ""
+        if current == text:
+            return 0
+        sys.stdout.writelines(
+            difflib.unified_diff(
+                current.splitlines(True), text.splitlines(True), str(STUB.name), "generated"
+            )
+        )
+        print(f"{STUB.name} is out of date: run `python gen_stubs.py`", file=sys.stderr)
+        return 1
+    STUB.write_text(text)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
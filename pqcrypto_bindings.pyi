@@ -0,0 +1,1799 @@
+# Type stubs for the pqcrypto_bindings extension module.
+#
+# Generated by gen_stubs.py from the Rust signatures in src/; don't edit by
+# hand. maturin ships this file in the wheel next to the compiled module, so
+# IDEs and mypy see the real signatures. Every byte-string argument accepts
+# any C-contiguous buffer (bytes, bytearray, memoryview, mmap, ...); secret
+# keys and shared secrets come back as SecretBytes.
+
+import os
+from collections.abc import Mapping, Sequence
+from types import TracebackType
+from typing import Any, Final, Literal
+
+from typing_extensions import Buffer, Self
+
+# Kyber-512
+def kyber_keygen() -> tuple[bytes, SecretBytes]: ...
+def kyber_encapsulate(pk_bytes: Buffer) -> tuple[bytes, SecretBytes]: ...
+def kyber_decapsulate(sk_bytes: Buffer, ct_bytes: Buffer) -> SecretBytes: ...
+
+# Kyber-768
+def kyber768_keygen() -> tuple[bytes, SecretBytes]: ...
+def kyber768_encapsulate(pk_bytes: Buffer) -> tuple[bytes, SecretBytes]: ...
+def kyber768_decapsulate(sk_bytes: Buffer, ct_bytes: Buffer) -> SecretBytes: ...
+
+# Kyber-1024
+def kyber1024_keygen() -> tuple[bytes, SecretBytes]: ...
+def kyber1024_encapsulate(pk_bytes: Buffer) -> tuple[bytes, SecretBytes]: ...
+def kyber1024_decapsulate(sk_bytes: Buffer, ct_bytes: Buffer) -> SecretBytes: ...
+
+# Kyber key objects
+class KyberPublicKey:
+    @property
+    def params(self) -> str: ...
+    def encapsulate(self) -> tuple[bytes, SecretBytes]: ...
+    def to_bytes(self) -> bytes: ...
+    @staticmethod
+    def from_bytes(pk_bytes: Buffer) -> KyberPublicKey: ...
+    def to_der(self) -> bytes: ...
+    @staticmethod
+    def from_der(der: Buffer) -> KyberPublicKey: ...
+    def to_pem(self) -> str: ...
+    @staticmethod
+    def from_pem(pem: str | Buffer) -> KyberPublicKey: ...
+    def to_jwk(self) -> dict[str, str]: ...
+    @staticmethod
+    def from_jwk(jwk: dict[str, str] | str) -> KyberPublicKey: ...
+    def __eq__(self, other: object) -> bool: ...
+
+class KyberSecretKey:
+    allow_pickle: bool
+    @property
+    def params(self) -> str: ...
+    def decapsulate(self, ct_bytes: Buffer) -> SecretBytes: ...
+    def to_bytes(self) -> bytes: ...
+    @staticmethod
+    def from_bytes(sk_bytes: Buffer) -> KyberSecretKey: ...
+    def to_der(self) -> bytes: ...
+    @staticmethod
+    def from_der(der: Buffer) -> KyberSecretKey: ...
+    def to_pem(self) -> str: ...
+    @staticmethod
+    def from_pem(pem: str | Buffer) -> KyberSecretKey: ...
+    def to_jwk(self) -> dict[str, str]: ...
+    @staticmethod
+    def from_jwk(jwk: dict[str, str] | str) -> KyberSecretKey: ...
+    def __eq__(self, other: object) -> bool: ...
+
+class KyberKeyPair:
+    allow_pickle: bool
+    def __init__(self, params: str = "kyber512") -> None: ...
+    @property
+    def params(self) -> str: ...
+    @property
+    def public_key(self) -> KyberPublicKey: ...
+    @property
+    def secret_key(self) -> KyberSecretKey: ...
+    def encapsulate(self) -> tuple[bytes, SecretBytes]: ...
+    def decapsulate(self, ct_bytes: Buffer) -> SecretBytes: ...
+    def to_bytes(self) -> tuple[bytes, bytes]: ...
+    @staticmethod
+    def from_bytes(pk_bytes: Buffer, sk_bytes: Buffer) -> KyberKeyPair: ...
+    def __eq__(self, other: object) -> bool: ...
+
+# ML-KEM-512 / 768 / 1024
+def mlkem512_keygen() -> tuple[bytes, SecretBytes]: ...
+def mlkem512_encapsulate(pk_bytes: Buffer) -> tuple[bytes, SecretBytes]: ...
+def mlkem512_decapsulate(sk_bytes: Buffer, ct_bytes: Buffer) -> SecretBytes: ...
+def mlkem768_keygen() -> tuple[bytes, SecretBytes]: ...
+def mlkem768_encapsulate(pk_bytes: Buffer) -> tuple[bytes, SecretBytes]: ...
+def mlkem768_decapsulate(sk_bytes: Buffer, ct_bytes: Buffer) -> SecretBytes: ...
+def mlkem1024_keygen() -> tuple[bytes, SecretBytes]: ...
+def mlkem1024_encapsulate(pk_bytes: Buffer) -> tuple[bytes, SecretBytes]: ...
+def mlkem1024_decapsulate(sk_bytes: Buffer, ct_bytes: Buffer) -> SecretBytes: ...
+
+# HQC-128 / 192 / 256
+def hqc128_keygen() -> tuple[bytes, SecretBytes]: ...
+def hqc128_encapsulate(pk_bytes: Buffer) -> tuple[bytes, SecretBytes]: ...
+def hqc128_decapsulate(sk_bytes: Buffer, ct_bytes: Buffer) -> SecretBytes: ...
+def hqc192_keygen() -> tuple[bytes, SecretBytes]: ...
+def hqc192_encapsulate(pk_bytes: Buffer) -> tuple[bytes, SecretBytes]: ...
+def hqc192_decapsulate(sk_bytes: Buffer, ct_bytes: Buffer) -> SecretBytes: ...
+def hqc256_keygen() -> tuple[bytes, SecretBytes]: ...
+def hqc256_encapsulate(pk_bytes: Buffer) -> tuple[bytes, SecretBytes]: ...
+def hqc256_decapsulate(sk_bytes: Buffer, ct_bytes: Buffer) -> SecretBytes: ...
+
+# FrodoKEM-640 / 976 / 1344 (AES and SHAKE)
+def frodokem640aes_keygen() -> tuple[bytes, SecretBytes]: ...
+def frodokem640aes_encapsulate(pk_bytes: Buffer) -> tuple[bytes, SecretBytes]: ...
+def frodokem640aes_decapsulate(sk_bytes: Buffer, ct_bytes: Buffer) -> SecretBytes: ...
+def frodokem640shake_keygen() -> tuple[bytes, SecretBytes]: ...
+def frodokem640shake_encapsulate(pk_bytes: Buffer) -> tuple[bytes, SecretBytes]: ...
+def frodokem640shake_decapsulate(sk_bytes: Buffer, ct_bytes: Buffer) -> SecretBytes: ...
+def frodokem976aes_keygen() -> tuple[bytes, SecretBytes]: ...
+def frodokem976aes_encapsulate(pk_bytes: Buffer) -> tuple[bytes, SecretBytes]: ...
+def frodokem976aes_decapsulate(sk_bytes: Buffer, ct_bytes: Buffer) -> SecretBytes: ...
+def frodokem976shake_keygen() -> tuple[bytes, SecretBytes]: ...
+def frodokem976shake_encapsulate(pk_bytes: Buffer) -> tuple[bytes, SecretBytes]: ...
+def frodokem976shake_decapsulate(sk_bytes: Buffer, ct_bytes: Buffer) -> SecretBytes: ...
+def frodokem1344aes_keygen() -> tuple[bytes, SecretBytes]: ...
+def frodokem1344aes_encapsulate(pk_bytes: Buffer) -> tuple[bytes, SecretBytes]: ...
+def frodokem1344aes_decapsulate(sk_bytes: Buffer, ct_bytes: Buffer) -> SecretBytes: ...
+def frodokem1344shake_keygen() -> tuple[bytes, SecretBytes]: ...
+def frodokem1344shake_encapsulate(pk_bytes: Buffer) -> tuple[bytes, SecretBytes]: ...
+def frodokem1344shake_decapsulate(
+    sk_bytes: Buffer, ct_bytes: Buffer
+) -> SecretBytes: ...
+
+# sntrup761
+def sntrup761_keygen() -> tuple[bytes, SecretBytes]: ...
+def sntrup761_encapsulate(pk_bytes: Buffer) -> tuple[bytes, SecretBytes]: ...
+def sntrup761_decapsulate(sk_bytes: Buffer, ct_bytes: Buffer) -> SecretBytes: ...
+
+# Classic McEliece 348864 / 460896
+def mceliece348864_keygen() -> tuple[bytes, SecretBytes]: ...
+def mceliece348864_encapsulate(pk_bytes: Buffer) -> tuple[bytes, SecretBytes]: ...
+def mceliece348864_decapsulate(sk_bytes: Buffer, ct_bytes: Buffer) -> SecretBytes: ...
+def mceliece460896_keygen() -> tuple[bytes, SecretBytes]: ...
+def mceliece460896_encapsulate(pk_bytes: Buffer) -> tuple[bytes, SecretBytes]: ...
+def mceliece460896_decapsulate(sk_bytes: Buffer, ct_bytes: Buffer) -> SecretBytes: ...
+
+# Falcon-512
+def falcon_keygen() -> tuple[bytes, SecretBytes]: ...
+def falcon_sign(sk_bytes: Buffer, msg: Buffer, context: Buffer = b"") -> bytes: ...
+def falcon_verify(
+    pk_bytes: Buffer, msg: Buffer, sig_bytes: Buffer, context: Buffer = b""
+) -> bool: ...
+def falcon_verify_strict(
+    pk_bytes: Buffer, msg: Buffer, sig_bytes: Buffer, context: Buffer = b""
+) -> None: ...
+def falcon_sign_padded(
+    sk_bytes: Buffer, msg: Buffer, context: Buffer = b""
+) -> bytes: ...
+
+# Falcon-1024
+def falcon1024_keygen() -> tuple[bytes, SecretBytes]: ...
+def falcon1024_sign(sk_bytes: Buffer, msg: Buffer, context: Buffer = b"") -> bytes: ...
+def falcon1024_verify(
+    pk_bytes: Buffer, msg: Buffer, sig_bytes: Buffer, context: Buffer = b""
+) -> bool: ...
+def falcon1024_verify_strict(
+    pk_bytes: Buffer, msg: Buffer, sig_bytes: Buffer, context: Buffer = b""
+) -> None: ...
+def falcon1024_sign_padded(
+    sk_bytes: Buffer, msg: Buffer, context: Buffer = b""
+) -> bytes: ...
+
+# Falcon signer / verifier objects
+class FalconSigner:
+    allow_pickle: bool
+    def __init__(self, sk_bytes: Buffer) -> None: ...
+    @property
+    def params(self) -> str: ...
+    def sign(self, msg: Buffer, context: Buffer = b"") -> bytes: ...
+    def sign_padded(self, msg: Buffer, context: Buffer = b"") -> bytes: ...
+    def to_bytes(self) -> bytes: ...
+    def to_der(self, pk_bytes: Buffer | None = None) -> bytes: ...
+    @staticmethod
+    def from_der(der: Buffer) -> FalconSigner: ...
+    def to_pem(self, pk_bytes: Buffer | None = None) -> str: ...
+    @staticmethod
+    def from_pem(pem: str | Buffer) -> FalconSigner: ...
+    def to_jwk(self, pk_bytes: Buffer | None = None) -> dict[str, str]: ...
+    @staticmethod
+    def from_jwk(jwk: dict[str, str] | str) -> FalconSigner: ...
+    def __eq__(self, other: object) -> bool: ...
+
+class FalconVerifier:
+    def __init__(self, pk_bytes: Buffer) -> None: ...
+    @property
+    def params(self) -> str: ...
+    def verify(self, msg: Buffer, sig_bytes: Buffer, context: Buffer = b"") -> bool: ...
+    def verify_strict(
+        self, msg: Buffer, sig_bytes: Buffer, context: Buffer = b""
+    ) -> None: ...
+    def to_bytes(self) -> bytes: ...
+    def to_der(self) -> bytes: ...
+    @staticmethod
+    def from_der(der: Buffer) -> FalconVerifier: ...
+    def to_pem(self) -> str: ...
+    @staticmethod
+    def from_pem(pem: str | Buffer) -> FalconVerifier: ...
+    def to_jwk(self) -> dict[str, str]: ...
+    @staticmethod
+    def from_jwk(jwk: dict[str, str] | str) -> FalconVerifier: ...
+    def __eq__(self, other: object) -> bool: ...
+
+# Prehash Falcon signatures, over digests or streamed data
+def falcon_sign_prehashed(sk_bytes: Buffer, digest: Buffer, hash_alg: str) -> bytes: ...
+def falcon_verify_prehashed(
+    pk_bytes: Buffer, digest: Buffer, sig_bytes: Buffer, hash_alg: str
+) -> bool: ...
+def falcon_verify_prehashed_strict(
+    pk_bytes: Buffer, digest: Buffer, sig_bytes: Buffer, hash_alg: str
+) -> None: ...
+
+class Signer:
+    def __init__(self, sk_bytes: Buffer, hash_alg: str = "sha512") -> None: ...
+    @property
+    def algorithm(self) -> str: ...
+    def update(self, data: Buffer) -> None: ...
+    def finalize(self) -> bytes: ...
+
+class Verifier:
+    def __init__(self, pk_bytes: Buffer, hash_alg: str = "sha512") -> None: ...
+    @property
+    def algorithm(self) -> str: ...
+    def update(self, data: Buffer) -> None: ...
+    def verify(self, sig_bytes: Buffer) -> bool: ...
+    def verify_strict(self, sig_bytes: Buffer) -> None: ...
+
+# PKCS#8 / SubjectPublicKeyInfo DER
+def public_key_to_der(algorithm: str, pk_bytes: Buffer) -> bytes: ...
+def public_key_from_der(der: Buffer) -> tuple[str, bytes]: ...
+def secret_key_to_der(
+    algorithm: str, sk_bytes: Buffer, pk_bytes: Buffer | None = None
+) -> bytes: ...
+def secret_key_from_der(der: Buffer) -> tuple[str, SecretBytes]: ...
+
+# PEM for keys, ciphertexts and signatures
+def public_key_to_pem(algorithm: str, pk_bytes: Buffer) -> str: ...
+def public_key_from_pem(pem: str | Buffer) -> tuple[str, bytes]: ...
+def secret_key_to_pem(
+    algorithm: str, sk_bytes: Buffer, pk_bytes: Buffer | None = None
+) -> str: ...
+def secret_key_from_pem(pem: str | Buffer) -> tuple[str, SecretBytes]: ...
+def ciphertext_to_pem(algorithm: str, ct_bytes: Buffer) -> str: ...
+def ciphertext_from_pem(pem: str | Buffer) -> tuple[str, bytes]: ...
+def signature_to_pem(algorithm: str, sig_bytes: Buffer) -> str: ...
+def signature_from_pem(pem: str | Buffer) -> tuple[str, bytes]: ...
+
+# JSON Web Keys
+def public_key_to_jwk(algorithm: str, pk_bytes: Buffer) -> dict[str, str]: ...
+def secret_key_to_jwk(
+    algorithm: str, sk_bytes: Buffer, pk_bytes: Buffer | None = None
+) -> dict[str, str]: ...
+def key_from_jwk(
+    jwk: dict[str, str] | str
+) -> tuple[str, bytes, SecretBytes | None]: ...
+
+# OpenSSH key files
+def public_key_to_openssh(
+    algorithm: str, pk_bytes: Buffer, comment: str = ""
+) -> str: ...
+def public_key_from_openssh(line: str | Buffer) -> tuple[str, bytes, str]: ...
+def secret_key_to_openssh(
+    algorithm: str, sk_bytes: Buffer, pk_bytes: Buffer, comment: str = ""
+) -> str: ...
+def secret_key_from_openssh(
+    pem: str | Buffer
+) -> tuple[str, SecretBytes, bytes, str]: ...
+
+# Single-shot public-key encryption
+def seal(
+    pk: Buffer | Sequence[Buffer],
+    plaintext: Buffer,
+    aad: Buffer | None = None,
+    algorithm: str | None = None,
+    cipher: Literal[
+        "chacha20poly1305", "aes256gcm", "xchacha20poly1305"
+    ] = "chacha20poly1305",
+    committing: bool = False,
+) -> bytes: ...
+def open(
+    sk: Buffer,
+    blob: Buffer,
+    aad: Buffer | None = None,
+    require_committing: bool = False,
+) -> bytes: ...
+
+# File encryption to a KEM public key
+def encrypt_file(
+    pk: Buffer,
+    in_path: str | os.PathLike[str],
+    out_path: str | os.PathLike[str],
+    algorithm: str | None = None,
+    cipher: Literal[
+        "chacha20poly1305", "aes256gcm", "xchacha20poly1305"
+    ] = "chacha20poly1305",
+    chunk_size: int = 65536,
+    aad: Buffer | None = None,
+) -> None: ...
+def decrypt_file(
+    sk: Buffer,
+    in_path: str | os.PathLike[str],
+    out_path: str | os.PathLike[str],
+    aad: Buffer | None = None,
+) -> None: ...
+
+# Signcryption: Falcon-signed, Kyber-sealed messages
+def sign_and_seal(
+    sender_sk: Buffer, recipient_pk: Buffer, msg: Buffer, aad: Buffer | None = None
+) -> bytes: ...
+def open_and_verify(
+    recipient_sk: Buffer, sender_pk: Buffer, blob: Buffer, aad: Buffer | None = None
+) -> bytes: ...
+
+# Attached signatures: message, signature and algorithm in one blob
+def sign_attached(sk: Buffer, msg: Buffer, algorithm: str | None = None) -> bytes: ...
+def open_signed(pk: Buffer, blob: Buffer, algorithm: str | None = None) -> bytes: ...
+
+# Timestamped signatures with expiry and freshness checks
+def sign_timestamped(
+    sk: Buffer,
+    msg: Buffer,
+    not_after: int | None = None,
+    signed_at: int | None = None,
+    algorithm: str | None = None,
+) -> bytes: ...
+def verify_timestamped(
+    pk: Buffer,
+    msg: Buffer,
+    envelope: Buffer,
+    max_age: int | None = None,
+    now: int | None = None,
+    clock_skew: int = 0,
+    algorithm: str | None = None,
+) -> tuple[int, int | None]: ...
+
+# Merkle-tree batch signing of digest sets
+def sign_manifest(
+    sk: Buffer, digests: Sequence[Buffer]
+) -> tuple[bytes, list[bytes]]: ...
+def verify_item(
+    pk: Buffer, item_digest: Buffer, proof: Buffer, signature: Buffer
+) -> bool: ...
+
+# Multi-signatures with k-of-n verification
+class MultiSignature:
+    def __init__(self, msg: Buffer) -> None: ...
+    @staticmethod
+    def from_bytes(blob: Buffer) -> MultiSignature: ...
+    def to_bytes(self) -> bytes: ...
+    def add(self, sk: Buffer, pk: Buffer, algorithm: str | None = None) -> None: ...
+    def merge(self, other: MultiSignature) -> None: ...
+    @property
+    def key_ids(self) -> list[bytes]: ...
+    def __len__(self) -> int: ...
+    def valid_signers(
+        self, msg: Buffer, public_keys: Sequence[Buffer]
+    ) -> list[int]: ...
+    def verify(
+        self, msg: Buffer, public_keys: Sequence[Buffer], threshold: int
+    ) -> bool: ...
+    def verify_strict(
+        self, msg: Buffer, public_keys: Sequence[Buffer], threshold: int
+    ) -> None: ...
+
+# Detached signature files with signer metadata
+class SignatureFile:
+    @staticmethod
+    def from_bytes(data: Buffer) -> SignatureFile: ...
+    @staticmethod
+    def read(path: str | os.PathLike[str]) -> SignatureFile: ...
+    def to_bytes(self) -> bytes: ...
+    def write(self, path: str | os.PathLike[str]) -> None: ...
+    @property
+    def algorithm(self) -> str: ...
+    @property
+    def fingerprint(self) -> bytes: ...
+    @property
+    def created(self) -> int: ...
+    @property
+    def signature(self) -> bytes: ...
+    def verify(self, pk: Buffer, data_path: str | os.PathLike[str]) -> bool: ...
+
+def sign_file(
+    sk: Buffer,
+    pk: Buffer,
+    data_path: str | os.PathLike[str],
+    sig_path: str | os.PathLike[str] | None = None,
+    algorithm: str | None = None,
+    created: int | None = None,
+) -> SignatureFile: ...
+def verify_file(
+    pk: Buffer,
+    data_path: str | os.PathLike[str],
+    sig_path: str | os.PathLike[str] | None = None,
+) -> bool: ...
+
+# Authenticated key exchange: Falcon-signed ephemeral KEM
+class AkeInitiator:
+    def __init__(self, sk: Buffer, peer_pk: Buffer, kem: str = "mlkem768") -> None: ...
+    @property
+    def message(self) -> bytes: ...
+    @property
+    def kem(self) -> str: ...
+    def finish(self, msg2: Buffer) -> None: ...
+    @property
+    def session_id(self) -> bytes: ...
+    def session_keys(self) -> tuple[SecretBytes, SecretBytes]: ...
+
+class AkeResponder:
+    def __init__(self, sk: Buffer, peer_pk: Buffer) -> None: ...
+    def respond(self, msg1: Buffer) -> bytes: ...
+    @property
+    def session_id(self) -> bytes: ...
+    def session_keys(self) -> tuple[SecretBytes, SecretBytes]: ...
+
+# SecureChannel: encrypted transport over AKE session keys
+class SecureChannel:
+    def __init__(
+        self,
+        send_key: Buffer,
+        recv_key: Buffer,
+        cipher: Literal[
+            "chacha20poly1305", "aes256gcm", "xchacha20poly1305"
+        ] = "chacha20poly1305",
+        rekey_messages: int = 1048576,
+        rekey_bytes: int = 1073741824,
+        replay_window: int = 0,
+    ) -> None: ...
+    @staticmethod
+    def from_handshake(
+        handshake: (
+            AkeInitiator
+            | AkeResponder
+            | NoiseHandshake
+            | KemTlsHandshake
+            | ResumeInitiator
+            | ResumeResponder
+            | PakeInitiator
+            | PakeResponder
+        ),
+        cipher: Literal[
+            "chacha20poly1305", "aes256gcm", "xchacha20poly1305"
+        ] = "chacha20poly1305",
+        rekey_messages: int = 1048576,
+        rekey_bytes: int = 1073741824,
+        replay_window: int = 0,
+    ) -> SecureChannel: ...
+    @property
+    def cipher(self) -> str: ...
+    @property
+    def sent(self) -> int: ...
+    @property
+    def received(self) -> int: ...
+    @property
+    def rekeys(self) -> tuple[int, int]: ...
+    @property
+    def replay_window(self) -> int: ...
+    def encrypt(self, plaintext: Buffer, aad: Buffer | None = None) -> bytes: ...
+    def decrypt(self, frame: Buffer, aad: Buffer | None = None) -> bytes: ...
+    def issue_ticket(
+        self, ticket_key: Buffer, lifetime: int = 86400, now: int | None = None
+    ) -> bytes: ...
+    def accept_ticket(
+        self, ticket: Buffer, now: int | None = None
+    ) -> ResumptionTicket: ...
+
+# Noise-style NK / IK / XX handshakes over KEMs
+class NoiseHandshake:
+    def __init__(
+        self,
+        pattern: Literal["NK", "IK", "XX"],
+        initiator: bool,
+        local_static: tuple[Buffer, Buffer] | None = None,
+        remote_static: Buffer | None = None,
+        kem: str = "mlkem768",
+        prologue: Buffer = b"",
+    ) -> None: ...
+    @property
+    def protocol_name(self) -> str: ...
+    @property
+    def initiator(self) -> bool: ...
+    @property
+    def complete(self) -> bool: ...
+    @property
+    def remote_static(self) -> bytes | None: ...
+    def write_message(self, payload: Buffer = b"") -> bytes: ...
+    def read_message(self, msg: Buffer) -> bytes: ...
+    @property
+    def handshake_hash(self) -> bytes: ...
+    def session_keys(self) -> tuple[SecretBytes, SecretBytes]: ...
+
+# KEMTLS-style handshakes authenticated by certified KEM keys
+class KemTlsHandshake:
+    def __init__(
+        self,
+        client: bool,
+        certificate: Buffer,
+        static_sk: Buffer,
+        ca_pk: Buffer,
+        kem: str = "mlkem768",
+    ) -> None: ...
+    @property
+    def client(self) -> bool: ...
+    @property
+    def complete(self) -> bool: ...
+    @property
+    def peer_subject(self) -> str | None: ...
+    @property
+    def peer_static(self) -> bytes | None: ...
+    def write_message(self) -> bytes: ...
+    def read_message(self, msg: Buffer) -> None: ...
+    @property
+    def handshake_hash(self) -> bytes: ...
+    def session_keys(self) -> tuple[SecretBytes, SecretBytes]: ...
+
+def issue_kem_certificate(
+    ca_sk: Buffer,
+    subject: str,
+    kem_pk: Buffer,
+    kem: str = "mlkem768",
+    algorithm: str | None = None,
+) -> bytes: ...
+def read_kem_certificate(
+    ca_pk: Buffer, certificate: Buffer
+) -> tuple[str, str, bytes]: ...
+
+# PQXDH-style asynchronous key agreement with signed KEM prekeys
+def pqxdh_signed_prekey(
+    identity_sk: Buffer, prekey_id: int, kem: str = "mlkem768"
+) -> tuple[bytes, SecretBytes]: ...
+def pqxdh_bundle(
+    identity_pk: Buffer, signed_prekey: Buffer, one_time_prekey: Buffer | None = None
+) -> bytes: ...
+def pqxdh_initiate(
+    identity_sk: Buffer, responder_identity_pk: Buffer, bundle: Buffer
+) -> tuple[bytes, SecretBytes]: ...
+def pqxdh_respond(
+    identity_pk: Buffer, prekeys: Mapping[int, Buffer], message: Buffer
+) -> tuple[bytes, SecretBytes, int | None]: ...
+
+# SecureChannel session resumption tickets
+class ResumptionTicket:
+    @property
+    def lifetime(self) -> int: ...
+    @property
+    def received_at(self) -> int: ...
+    @property
+    def expires_at(self) -> int: ...
+    def to_bytes(self) -> SecretBytes: ...
+    @staticmethod
+    def from_bytes(data: Buffer) -> ResumptionTicket: ...
+
+class ResumeInitiator:
+    def __init__(self, ticket: ResumptionTicket, now: int | None = None) -> None: ...
+    @property
+    def message(self) -> bytes: ...
+    def finish(self, reply: Buffer) -> None: ...
+    @property
+    def session_id(self) -> bytes: ...
+    def session_keys(self) -> tuple[SecretBytes, SecretBytes]: ...
+
+class ResumeResponder:
+    def __init__(self, ticket_key: Buffer, max_skew: int = 10) -> None: ...
+    def respond(self, hello: Buffer, now: int | None = None) -> bytes: ...
+    @property
+    def session_id(self) -> bytes: ...
+    def session_keys(self) -> tuple[SecretBytes, SecretBytes]: ...
+
+# KeySchedule: transcript hash and labelled key derivation
+class KeySchedule:
+    def __init__(
+        self,
+        hash: Literal["sha256", "sha512"] = "sha256",
+        label_prefix: Buffer | None = None,
+    ) -> None: ...
+    def absorb(self, message: Buffer) -> None: ...
+    @property
+    def transcript_hash(self) -> bytes: ...
+    @property
+    def stage(self) -> int: ...
+    def extract(self, ikm: Buffer | None = None) -> None: ...
+    def derive_secret(self, label: Buffer) -> SecretBytes: ...
+    def expand_label(
+        self,
+        secret: Buffer,
+        label: Buffer,
+        context: Buffer | None = None,
+        length: int | None = None,
+    ) -> SecretBytes: ...
+    def copy(self) -> KeySchedule: ...
+
+# Password-authenticated key exchange over ML-KEM
+class PakeInitiator:
+    def __init__(
+        self,
+        password: str | Buffer,
+        context: Buffer | None = None,
+        params: Buffer | None = None,
+        kem: str = "mlkem768",
+    ) -> None: ...
+    @property
+    def message(self) -> bytes: ...
+    @property
+    def kem(self) -> str: ...
+    def finish(self, msg2: Buffer) -> bytes: ...
+    @property
+    def session_id(self) -> bytes: ...
+    def session_keys(self) -> tuple[SecretBytes, SecretBytes]: ...
+
+class PakeResponder:
+    def __init__(
+        self,
+        password: str | Buffer | None = None,
+        record: Buffer | None = None,
+        context: Buffer | None = None,
+    ) -> None: ...
+    def respond(self, msg1: Buffer) -> bytes: ...
+    def finish(self, msg3: Buffer) -> None: ...
+    @property
+    def session_id(self) -> bytes: ...
+    def session_keys(self) -> tuple[SecretBytes, SecretBytes]: ...
+
+def pake_register(
+    password: str | Buffer,
+    context: Buffer | None = None,
+    memory_kib: int = 65536,
+    iterations: int = 3,
+    parallelism: int = 1,
+) -> SecretBytes: ...
+def pake_params(record: Buffer) -> bytes: ...
+
+# Signed tokens in the style of PASETO
+def token_sign(
+    sk: Buffer,
+    claims: Buffer,
+    footer: Buffer | None = None,
+    implicit: Buffer | None = None,
+) -> str: ...
+def token_verify(
+    pk: Buffer, token: str, footer: Buffer | None = None, implicit: Buffer | None = None
+) -> bytes: ...
+def token_footer(token: str) -> bytes: ...
+
+# JWS compact serialization with Falcon and ML-DSA
+def jws_sign(
+    sk: Buffer,
+    payload: Buffer,
+    algorithm: str | None = None,
+    kid: str | None = None,
+    header: dict[str, Any] | None = None,
+) -> str: ...
+def jws_verify(pk: Buffer, token: str, algorithm: str | None = None) -> bytes: ...
+def jws_header(token: str) -> dict[str, Any]: ...
+
+# JWE with ML-KEM key management
+def jwe_encrypt(
+    pk: Buffer,
+    plaintext: Buffer,
+    algorithm: str | None = None,
+    enc: Literal["A256GCM", "A128GCM"] = "A256GCM",
+    kid: str | None = None,
+    header: dict[str, Any] | None = None,
+) -> str: ...
+def jwe_encrypt_general(
+    recipients: Sequence[Buffer],
+    plaintext: Buffer,
+    enc: Literal["A256GCM", "A128GCM"] = "A256GCM",
+    kids: Sequence[str] | None = None,
+    aad: Buffer | None = None,
+    header: dict[str, Any] | None = None,
+) -> dict[str, Any]: ...
+def jwe_decrypt(
+    sk: Buffer, jwe: str | dict[str, Any], kid: str | None = None
+) -> bytes: ...
+
+# CBOR and COSE with ML-DSA and ML-KEM
+def cbor_encode(value: Any) -> bytes: ...
+def cbor_decode(data: Buffer) -> Any: ...
+def cose_sign1(
+    sk: Buffer,
+    payload: Buffer,
+    kid: Buffer | None = None,
+    protected: dict[Any, Any] | None = None,
+    unprotected: dict[Any, Any] | None = None,
+    external_aad: Buffer | None = None,
+    detached: bool = False,
+) -> bytes: ...
+def cose_verify1(
+    pk: Buffer,
+    message: Buffer,
+    payload: Buffer | None = None,
+    external_aad: Buffer | None = None,
+) -> bytes: ...
+def cose_headers(message: Buffer) -> tuple[dict[Any, Any], dict[Any, Any]]: ...
+def cose_encrypt(
+    recipients: Sequence[Buffer],
+    plaintext: Buffer,
+    enc: Literal["A256GCM", "A128GCM"] = "A256GCM",
+    kids: Sequence[Buffer] | None = None,
+    protected: dict[Any, Any] | None = None,
+    unprotected: dict[Any, Any] | None = None,
+    external_aad: Buffer | None = None,
+) -> bytes: ...
+def cose_decrypt(
+    sk: Buffer,
+    message: Buffer,
+    external_aad: Buffer | None = None,
+    kid: Buffer | None = None,
+) -> bytes: ...
+
+# X.509 certificates with Falcon and ML-DSA
+def generate_self_signed_cert(
+    sk: Buffer,
+    pk: Buffer,
+    subject: str,
+    validity: int = 31536000,
+    serial: int | None = None,
+    san: Sequence[str] | None = None,
+    is_ca: bool = True,
+    path_length: int | None = None,
+    not_before: int | None = None,
+) -> bytes: ...
+def generate_csr(
+    sk: Buffer, pk: Buffer, subject: str, san: Sequence[str] | None = None
+) -> bytes: ...
+def generate_cert(
+    ca_sk: Buffer,
+    ca_cert: Buffer,
+    csr: Buffer,
+    validity: int = 31536000,
+    serial: int | None = None,
+    san: Sequence[str] | None = None,
+    is_ca: bool = False,
+    path_length: int | None = None,
+    not_before: int | None = None,
+) -> bytes: ...
+def verify_cert_chain(
+    leaf: Buffer,
+    intermediates: Sequence[Buffer],
+    roots: Sequence[Buffer],
+    time: int | None = None,
+) -> list[bytes]: ...
+def certificate_to_pem(cert: Buffer) -> str: ...
+def certificate_from_pem(pem: str | Buffer) -> bytes: ...
+def csr_to_pem(csr: Buffer) -> str: ...
+def csr_from_pem(pem: str | Buffer) -> bytes: ...
+
+# CMS SignedData with Falcon and ML-DSA
+def cms_sign(
+    sk: Buffer,
+    cert: Buffer,
+    data: Buffer,
+    detached: bool = False,
+    certs: Sequence[Buffer] | None = None,
+    signing_time: int | None = None,
+) -> bytes: ...
+def cms_verify(
+    cms: Buffer,
+    roots: Sequence[Buffer],
+    data: Buffer | None = None,
+    intermediates: Sequence[Buffer] | None = None,
+    time: int | None = None,
+) -> bytes: ...
+
+# DSSE envelopes with Falcon and ML-DSA
+def dsse_sign(
+    sk: Buffer,
+    payload_type: str,
+    payload: Buffer,
+    keyid: str | None = None,
+    algorithm: str | None = None,
+) -> str: ...
+def dsse_add_signature(
+    envelope: str | bytes,
+    sk: Buffer,
+    keyid: str | None = None,
+    algorithm: str | None = None,
+) -> str: ...
+def dsse_verify(
+    pk: Buffer | Sequence[Buffer],
+    envelope: str | bytes,
+    payload_type: str | None = None,
+    threshold: int = 1,
+) -> tuple[str, bytes]: ...
+
+# minisign-style release signing with Falcon
+def minisign_keygen(
+    password: str | Buffer | None = None,
+    algorithm: Literal["falcon512", "falcon1024"] = "falcon512",
+) -> tuple[str, str]: ...
+def minisign_sign(
+    secret_key: str,
+    data: Buffer,
+    password: str | Buffer | None = None,
+    trusted_comment: str | None = None,
+    untrusted_comment: str = "signature from minisign secret key",
+) -> str: ...
+def minisign_verify(public_key: str, data: Buffer, signature: str) -> str: ...
+def minisign_sign_file(
+    secret_key: str,
+    data_path: str | os.PathLike[str],
+    password: str | Buffer | None = None,
+    trusted_comment: str | None = None,
+    untrusted_comment: str = "signature from minisign secret key",
+    sig_path: str | os.PathLike[str] | None = None,
+) -> str: ...
+def minisign_verify_file(
+    public_key: str,
+    data_path: str | os.PathLike[str],
+    sig_path: str | os.PathLike[str] | None = None,
+) -> str: ...
+
+# TUF-style root and targets metadata
+def tuf_root(
+    roles: Mapping[str, tuple[Sequence[Buffer], int]], expires: int, version: int = 1
+) -> str: ...
+def tuf_targets(
+    targets: Mapping[str, Buffer], expires: int, version: int = 1
+) -> str: ...
+def tuf_sign(
+    metadata: str | bytes, sk: Buffer, pk: Buffer, algorithm: str | None = None
+) -> str: ...
+def tuf_verify(
+    root: str | bytes, metadata: str | bytes, time: int | None = None
+) -> dict[str, Any]: ...
+def tuf_verify_target(
+    root: str | bytes,
+    targets: str | bytes,
+    path: str,
+    data: Buffer,
+    time: int | None = None,
+) -> None: ...
+
+# age v1 files to PQ KEM recipients
+def age_encrypt(
+    recipients: Buffer | Sequence[Buffer],
+    data: Buffer,
+    algorithm: str | None = None,
+    armor: bool = False,
+) -> bytes: ...
+def age_decrypt(sk: Buffer, data: Buffer) -> bytes: ...
+
+# HPKE (RFC 9180) over ML-KEM, X-Wing and DHKEM(X25519)
+class HpkeSuite:
+    def __init__(
+        self,
+        kem: str,
+        kdf: Literal["hkdf-sha256", "hkdf-sha384", "hkdf-sha512"] = "hkdf-sha256",
+        aead: Literal[
+            "aes128gcm", "aes256gcm", "chacha20poly1305", "export-only"
+        ] = "chacha20poly1305",
+    ) -> None: ...
+    @property
+    def kem(self) -> str: ...
+    @property
+    def kdf(self) -> str: ...
+    @property
+    def aead(self) -> str: ...
+    @property
+    def ids(self) -> tuple[int, int, int]: ...
+    def setup_sender(
+        self,
+        pk: Buffer,
+        info: Buffer | None = None,
+        psk: Buffer | None = None,
+        psk_id: Buffer | None = None,
+        sender_sk: Buffer | None = None,
+    ) -> tuple[bytes, HpkeContext]: ...
+    def setup_receiver(
+        self,
+        sk: Buffer,
+        enc: Buffer,
+        info: Buffer | None = None,
+        psk: Buffer | None = None,
+        psk_id: Buffer | None = None,
+        sender_pk: Buffer | None = None,
+    ) -> HpkeContext: ...
+    def seal(
+        self,
+        pk: Buffer,
+        plaintext: Buffer,
+        info: Buffer | None = None,
+        aad: Buffer | None = None,
+        psk: Buffer | None = None,
+        psk_id: Buffer | None = None,
+        sender_sk: Buffer | None = None,
+    ) -> tuple[bytes, bytes]: ...
+    def open(
+        self,
+        sk: Buffer,
+        enc: Buffer,
+        ciphertext: Buffer,
+        info: Buffer | None = None,
+        aad: Buffer | None = None,
+        psk: Buffer | None = None,
+        psk_id: Buffer | None = None,
+        sender_pk: Buffer | None = None,
+    ) -> bytes: ...
+    def send_export(
+        self,
+        pk: Buffer,
+        exporter_context: Buffer,
+        length: int,
+        info: Buffer | None = None,
+        psk: Buffer | None = None,
+        psk_id: Buffer | None = None,
+        sender_sk: Buffer | None = None,
+    ) -> tuple[bytes, SecretBytes]: ...
+    def receive_export(
+        self,
+        sk: Buffer,
+        enc: Buffer,
+        exporter_context: Buffer,
+        length: int,
+        info: Buffer | None = None,
+        psk: Buffer | None = None,
+        psk_id: Buffer | None = None,
+        sender_pk: Buffer | None = None,
+    ) -> SecretBytes: ...
+    def sender(
+        self,
+        pk: Buffer,
+        info: Buffer | None = None,
+        psk: Buffer | None = None,
+        psk_id: Buffer | None = None,
+        sender_sk: Buffer | None = None,
+    ) -> HpkeSender: ...
+    def receiver(
+        self,
+        sk: Buffer,
+        enc: Buffer,
+        info: Buffer | None = None,
+        psk: Buffer | None = None,
+        psk_id: Buffer | None = None,
+        sender_pk: Buffer | None = None,
+    ) -> HpkeReceiver: ...
+
+class HpkeContext:
+    def seal(self, plaintext: Buffer, aad: Buffer | None = None) -> bytes: ...
+    def open(self, ciphertext: Buffer, aad: Buffer | None = None) -> bytes: ...
+    def export(self, exporter_context: Buffer, length: int) -> SecretBytes: ...
+    @property
+    def seq(self) -> int: ...
+    @property
+    def role(self) -> Literal["sender", "receiver"]: ...
+
+class HpkeSender:
+    @property
+    def enc(self) -> bytes: ...
+    @property
+    def seq(self) -> int: ...
+    def seal(self, plaintext: Buffer, aad: Buffer | None = None) -> bytes: ...
+    def export(self, exporter_context: Buffer, length: int) -> SecretBytes: ...
+
+class HpkeReceiver:
+    @property
+    def seq(self) -> int: ...
+    @property
+    def missed(self) -> int: ...
+    def open(self, frame: Buffer, aad: Buffer | None = None) -> bytes: ...
+    def export(self, exporter_context: Buffer, length: int) -> SecretBytes: ...
+
+def hpke_seal(
+    pk: Buffer,
+    plaintext: Buffer,
+    info: Buffer | None = None,
+    aad: Buffer | None = None,
+    kem: str = "mlkem768",
+    kdf: str = "hkdf-sha256",
+    aead: str = "chacha20poly1305",
+    psk: Buffer | None = None,
+    psk_id: Buffer | None = None,
+    sender_sk: Buffer | None = None,
+) -> tuple[bytes, bytes]: ...
+def hpke_open(
+    sk: Buffer,
+    enc: Buffer,
+    ciphertext: Buffer,
+    info: Buffer | None = None,
+    aad: Buffer | None = None,
+    kem: str = "mlkem768",
+    kdf: str = "hkdf-sha256",
+    aead: str = "chacha20poly1305",
+    psk: Buffer | None = None,
+    psk_id: Buffer | None = None,
+    sender_pk: Buffer | None = None,
+) -> bytes: ...
+
+# AEAD primitives
+def chacha20poly1305_encrypt(
+    key: Buffer, nonce: Buffer, aad: Buffer, data: Buffer
+) -> bytes: ...
+def chacha20poly1305_decrypt(
+    key: Buffer, nonce: Buffer, aad: Buffer, data: Buffer
+) -> bytes: ...
+def aes256gcm_encrypt(
+    key: Buffer, nonce: Buffer, aad: Buffer, data: Buffer
+) -> bytes: ...
+def aes256gcm_decrypt(
+    key: Buffer, nonce: Buffer, aad: Buffer, data: Buffer
+) -> bytes: ...
+def xchacha20poly1305_encrypt(
+    key: Buffer, nonce: Buffer, aad: Buffer, data: Buffer
+) -> bytes: ...
+def xchacha20poly1305_decrypt(
+    key: Buffer, nonce: Buffer, aad: Buffer, data: Buffer
+) -> bytes: ...
+def xchacha20poly1305_seal(key: Buffer, aad: Buffer, data: Buffer) -> bytes: ...
+def xchacha20poly1305_open(key: Buffer, aad: Buffer, data: Buffer) -> bytes: ...
+
+# Counter-based AEAD nonces
+class NonceSequence:
+    def __init__(
+        self,
+        key: Buffer,
+        cipher: Literal[
+            "chacha20poly1305", "aes256gcm", "xchacha20poly1305"
+        ] = "chacha20poly1305",
+        start: int = 0,
+        limit: int | None = None,
+        iv: Buffer | None = None,
+    ) -> None: ...
+    @property
+    def cipher(self) -> str: ...
+    @property
+    def counter(self) -> int: ...
+    @property
+    def remaining(self) -> int: ...
+    def next_nonce(self) -> bytes: ...
+    def encrypt(self, data: Buffer, aad: Buffer | None = None) -> bytes: ...
+    def decrypt(self, data: Buffer, aad: Buffer | None = None) -> bytes: ...
+
+# Chunked streaming AEAD
+class StreamEncryptor:
+    def __init__(
+        self,
+        key: Buffer,
+        aad: Buffer | None = None,
+        cipher: Literal[
+            "chacha20poly1305", "aes256gcm", "xchacha20poly1305"
+        ] = "chacha20poly1305",
+    ) -> None: ...
+    @property
+    def header(self) -> bytes: ...
+    @property
+    def cipher(self) -> str: ...
+    @property
+    def finished(self) -> bool: ...
+    def encrypt_chunk(self, data: Buffer) -> bytes: ...
+    def encrypt_last(self, data: Buffer | None = None) -> bytes: ...
+
+class StreamDecryptor:
+    def __init__(
+        self,
+        key: Buffer,
+        header: Buffer,
+        aad: Buffer | None = None,
+        cipher: Literal[
+            "chacha20poly1305", "aes256gcm", "xchacha20poly1305"
+        ] = "chacha20poly1305",
+    ) -> None: ...
+    @property
+    def cipher(self) -> str: ...
+    @property
+    def finished(self) -> bool: ...
+    def decrypt_chunk(self, data: Buffer) -> bytes: ...
+    def decrypt_last(self, data: Buffer) -> bytes: ...
+
+# HKDF key derivation
+def hkdf_sha256(
+    ikm: Buffer,
+    salt: Buffer | None = None,
+    info: Buffer | None = None,
+    length: int = 32,
+) -> SecretBytes: ...
+def hkdf_sha512(
+    ikm: Buffer,
+    salt: Buffer | None = None,
+    info: Buffer | None = None,
+    length: int = 64,
+) -> SecretBytes: ...
+def hkdf_extract(
+    ikm: Buffer,
+    salt: Buffer | None = None,
+    hash: Literal["sha256", "sha512"] = "sha256",
+) -> SecretBytes: ...
+def hkdf_expand(
+    prk: Buffer, info: Buffer, length: int, hash: Literal["sha256", "sha512"] = "sha256"
+) -> SecretBytes: ...
+
+# SHAKE128 / SHAKE256 XOFs
+def shake128(data: Buffer, length: int) -> bytes: ...
+def shake256(data: Buffer, length: int) -> bytes: ...
+
+class Shake:
+    def __init__(
+        self,
+        name: Literal["shake128", "shake256"] = "shake256",
+        data: Buffer | None = None,
+    ) -> None: ...
+    @property
+    def name(self) -> str: ...
+    def update(self, data: Buffer) -> None: ...
+    def squeeze(self, length: int) -> bytes: ...
+    def copy(self) -> Shake: ...
+
+# Password-protected secret key export
+def export_encrypted(
+    sk: Buffer,
+    password: str | Buffer,
+    memory_kib: int = 65536,
+    iterations: int = 3,
+    parallelism: int = 1,
+) -> bytes: ...
+def import_encrypted(blob: Buffer, password: str | Buffer) -> SecretBytes: ...
+
+# Password-based encryption of arbitrary data
+def encrypt_with_password(
+    password: str | Buffer,
+    data: Buffer,
+    memory_kib: int = 65536,
+    iterations: int = 3,
+    parallelism: int = 1,
+) -> bytes: ...
+def decrypt_with_password(password: str | Buffer, blob: Buffer) -> SecretBytes: ...
+
+# Public key recovery from a secret key
+def kyber_public_from_secret(sk_bytes: Buffer) -> bytes: ...
+def falcon_public_from_secret(sk_bytes: Buffer) -> bytes: ...
+
+# Key metadata: creation time, expiry and usage
+class KeyMetadata:
+    def __init__(
+        self,
+        usage: Literal["encrypt", "sign"] | None = None,
+        label: str | None = None,
+        not_after: int | None = None,
+        created_at: int | None = None,
+    ) -> None: ...
+    @property
+    def created_at(self) -> int: ...
+    @property
+    def not_after(self) -> int | None: ...
+    @property
+    def usage(self) -> Literal["encrypt", "sign"] | None: ...
+    @property
+    def label(self) -> str | None: ...
+    def is_expired(self, now: int | None = None) -> bool: ...
+    def check(
+        self, usage: Literal["encrypt", "sign"] | None = None, now: int | None = None
+    ) -> None: ...
+    def __eq__(self, other: object) -> bool: ...
+
+def attach_metadata(key_bytes: Buffer, metadata: KeyMetadata) -> bytes: ...
+def detach_metadata(
+    envelope: Buffer,
+    usage: Literal["encrypt", "sign"] | None = None,
+    enforce: bool = True,
+) -> tuple[SecretBytes, KeyMetadata]: ...
+
+# Shamir secret sharing
+def split_secret(secret: Buffer, n: int, k: int) -> list[SecretBytes]: ...
+def combine_shares(shares: Sequence[Buffer]) -> SecretBytes: ...
+
+# Named keys in one password-protected file
+class Keystore:
+    def __init__(
+        self, path: str | os.PathLike[str], password: str | Buffer
+    ) -> None: ...
+    @staticmethod
+    def create(
+        path: str | os.PathLike[str],
+        password: str | Buffer,
+        memory_kib: int = 65536,
+        iterations: int = 3,
+        parallelism: int = 1,
+    ) -> Keystore: ...
+    @property
+    def path(self) -> str: ...
+    def add(
+        self,
+        name: str,
+        key: (
+            KyberPublicKey
+            | KyberSecretKey
+            | KyberKeyPair
+            | FalconSigner
+            | FalconVerifier
+        ),
+        overwrite: bool = False,
+        metadata: KeyMetadata | None = None,
+    ) -> None: ...
+    def get(
+        self,
+        name: str,
+        usage: Literal["encrypt", "sign"] | None = None,
+        enforce: bool = True,
+    ) -> (
+        KyberPublicKey | KyberSecretKey | KyberKeyPair | FalconSigner | FalconVerifier
+    ): ...
+    def metadata(self, name: str) -> KeyMetadata | None: ...
+    def list(self) -> list[str]: ...
+    def delete(self, name: str) -> None: ...
+    def __contains__(self, name: str) -> bool: ...
+    def __len__(self) -> int: ...
+
+# Secret keys in the platform keychain
+def store_in_keyring(label: str, sk: Buffer) -> None: ...
+def load_from_keyring(label: str) -> SecretBytes: ...
+def delete_from_keyring(label: str) -> None: ...
+
+# Signing with keys held on a PKCS#11 token
+class HsmSigner:
+    def __init__(
+        self,
+        module_path: str,
+        slot: int,
+        key_label: str,
+        pin: str | None = None,
+        algorithm: str | None = None,
+        mechanism: int | None = None,
+    ) -> None: ...
+    @property
+    def algorithm(self) -> str: ...
+    @property
+    def closed(self) -> bool: ...
+    def sign(self, msg: Buffer) -> bytes: ...
+    def public_key(self) -> bytes: ...
+    def close(self) -> None: ...
+    def __enter__(self) -> Self: ...
+    def __exit__(
+        self,
+        exc_type: type[BaseException] | None,
+        exc_value: BaseException | None,
+        traceback: TracebackType | None,
+    ) -> bool: ...
+
+# Secret keys sealed to the TPM and boot state
+def seal_to_tpm(sk: Buffer, pcr_policy: Sequence[int]) -> bytes: ...
+def unseal_from_tpm(blob: Buffer) -> SecretBytes: ...
+
+# Deterministic Kyber / Falcon keygen from a seed
+def kyber_keygen_from_seed(
+    seed: Buffer, params: Literal["kyber512", "kyber768", "kyber1024"] = "kyber512"
+) -> tuple[bytes, SecretBytes]: ...
+def falcon_keygen_from_seed(
+    seed: Buffer, params: Literal["falcon512", "falcon1024"] = "falcon512"
+) -> tuple[bytes, SecretBytes]: ...
+
+# ML-DSA-44 / 65 / 87
+def mldsa44_keygen() -> tuple[bytes, SecretBytes]: ...
+def mldsa44_sign(
+    sk_bytes: Buffer,
+    msg: Buffer,
+    mode: Literal["hedged", "deterministic"] = "hedged",
+    context: Buffer = b"",
+) -> bytes: ...
+def mldsa44_verify(
+    pk_bytes: Buffer, msg: Buffer, sig_bytes: Buffer, context: Buffer = b""
+) -> bool: ...
+def mldsa44_verify_strict(
+    pk_bytes: Buffer, msg: Buffer, sig_bytes: Buffer, context: Buffer = b""
+) -> None: ...
+def mldsa65_keygen() -> tuple[bytes, SecretBytes]: ...
+def mldsa65_sign(
+    sk_bytes: Buffer,
+    msg: Buffer,
+    mode: Literal["hedged", "deterministic"] = "hedged",
+    context: Buffer = b"",
+) -> bytes: ...
+def mldsa65_verify(
+    pk_bytes: Buffer, msg: Buffer, sig_bytes: Buffer, context: Buffer = b""
+) -> bool: ...
+def mldsa65_verify_strict(
+    pk_bytes: Buffer, msg: Buffer, sig_bytes: Buffer, context: Buffer = b""
+) -> None: ...
+def mldsa87_keygen() -> tuple[bytes, SecretBytes]: ...
+def mldsa87_sign(
+    sk_bytes: Buffer,
+    msg: Buffer,
+    mode: Literal["hedged", "deterministic"] = "hedged",
+    context: Buffer = b"",
+) -> bytes: ...
+def mldsa87_verify(
+    pk_bytes: Buffer, msg: Buffer, sig_bytes: Buffer, context: Buffer = b""
+) -> bool: ...
+def mldsa87_verify_strict(
+    pk_bytes: Buffer, msg: Buffer, sig_bytes: Buffer, context: Buffer = b""
+) -> None: ...
+
+# SPHINCS+-SHA2-128s / 192s / 256s
+def sphincs_sha2_128s_keygen() -> tuple[bytes, SecretBytes]: ...
+def sphincs_sha2_128s_sign(
+    sk_bytes: Buffer, msg: Buffer, context: Buffer = b""
+) -> bytes: ...
+def sphincs_sha2_128s_verify(
+    pk_bytes: Buffer, msg: Buffer, sig_bytes: Buffer, context: Buffer = b""
+) -> bool: ...
+def sphincs_sha2_128s_verify_strict(
+    pk_bytes: Buffer, msg: Buffer, sig_bytes: Buffer, context: Buffer = b""
+) -> None: ...
+def sphincs_sha2_192s_keygen() -> tuple[bytes, SecretBytes]: ...
+def sphincs_sha2_192s_sign(
+    sk_bytes: Buffer, msg: Buffer, context: Buffer = b""
+) -> bytes: ...
+def sphincs_sha2_192s_verify(
+    pk_bytes: Buffer, msg: Buffer, sig_bytes: Buffer, context: Buffer = b""
+) -> bool: ...
+def sphincs_sha2_192s_verify_strict(
+    pk_bytes: Buffer, msg: Buffer, sig_bytes: Buffer, context: Buffer = b""
+) -> None: ...
+def sphincs_sha2_256s_keygen() -> tuple[bytes, SecretBytes]: ...
+def sphincs_sha2_256s_sign(
+    sk_bytes: Buffer, msg: Buffer, context: Buffer = b""
+) -> bytes: ...
+def sphincs_sha2_256s_verify(
+    pk_bytes: Buffer, msg: Buffer, sig_bytes: Buffer, context: Buffer = b""
+) -> bool: ...
+def sphincs_sha2_256s_verify_strict(
+    pk_bytes: Buffer, msg: Buffer, sig_bytes: Buffer, context: Buffer = b""
+) -> None: ...
+
+# SPHINCS+-SHAKE-128f / 192f / 256f
+def sphincs_shake_128f_keygen() -> tuple[bytes, SecretBytes]: ...
+def sphincs_shake_128f_sign(
+    sk_bytes: Buffer, msg: Buffer, context: Buffer = b""
+) -> bytes: ...
+def sphincs_shake_128f_verify(
+    pk_bytes: Buffer, msg: Buffer, sig_bytes: Buffer, context: Buffer = b""
+) -> bool: ...
+def sphincs_shake_128f_verify_strict(
+    pk_bytes: Buffer, msg: Buffer, sig_bytes: Buffer, context: Buffer = b""
+) -> None: ...
+def sphincs_shake_192f_keygen() -> tuple[bytes, SecretBytes]: ...
+def sphincs_shake_192f_sign(
+    sk_bytes: Buffer, msg: Buffer, context: Buffer = b""
+) -> bytes: ...
+def sphincs_shake_192f_verify(
+    pk_bytes: Buffer, msg: Buffer, sig_bytes: Buffer, context: Buffer = b""
+) -> bool: ...
+def sphincs_shake_192f_verify_strict(
+    pk_bytes: Buffer, msg: Buffer, sig_bytes: Buffer, context: Buffer = b""
+) -> None: ...
+def sphincs_shake_256f_keygen() -> tuple[bytes, SecretBytes]: ...
+def sphincs_shake_256f_sign(
+    sk_bytes: Buffer, msg: Buffer, context: Buffer = b""
+) -> bytes: ...
+def sphincs_shake_256f_verify(
+    pk_bytes: Buffer, msg: Buffer, sig_bytes: Buffer, context: Buffer = b""
+) -> bool: ...
+def sphincs_shake_256f_verify_strict(
+    pk_bytes: Buffer, msg: Buffer, sig_bytes: Buffer, context: Buffer = b""
+) -> None: ...
+
+# XMSS (stateful)
+class XmssState:
+    @property
+    def params(self) -> str: ...
+    @property
+    def index(self) -> int: ...
+    @property
+    def max_signatures(self) -> int: ...
+    @property
+    def remaining(self) -> int: ...
+    def public_key(self) -> bytes: ...
+    def to_bytes(self) -> bytes: ...
+    @staticmethod
+    def from_bytes(sk_bytes: Buffer) -> XmssState: ...
+    def __eq__(self, other: object) -> bool: ...
+
+def xmss_keygen(params: str = "XMSS-SHA2_10_256") -> tuple[bytes, XmssState]: ...
+def xmss_sign(state: XmssState, msg: Buffer) -> bytes: ...
+def xmss_verify(pk_bytes: Buffer, msg: Buffer, sig_bytes: Buffer) -> bool: ...
+def xmss_verify_strict(pk_bytes: Buffer, msg: Buffer, sig_bytes: Buffer) -> None: ...
+
+# LMS / HSS (stateful)
+class LmsState:
+    @property
+    def params(self) -> str: ...
+    @property
+    def index(self) -> int: ...
+    @property
+    def max_signatures(self) -> int: ...
+    @property
+    def remaining(self) -> int: ...
+    def public_key(self) -> bytes: ...
+    def to_bytes(self) -> bytes: ...
+    @staticmethod
+    def from_bytes(sk_bytes: Buffer) -> LmsState: ...
+    def __eq__(self, other: object) -> bool: ...
+
+def lms_keygen(
+    heights: Sequence[int] = [10], winternitz: int = 4
+) -> tuple[bytes, LmsState]: ...
+def lms_sign(state: LmsState, msg: Buffer) -> bytes: ...
+def lms_verify(pk_bytes: Buffer, msg: Buffer, sig_bytes: Buffer) -> bool: ...
+def lms_verify_strict(pk_bytes: Buffer, msg: Buffer, sig_bytes: Buffer) -> None: ...
+
+# X25519 / Ed25519 (classical, for hybrids)
+def x25519_keygen() -> tuple[bytes, SecretBytes]: ...
+def x25519_dh(sk_bytes: Buffer, peer_pk_bytes: Buffer) -> SecretBytes: ...
+def ed25519_keygen() -> tuple[bytes, SecretBytes]: ...
+def ed25519_sign(sk_bytes: Buffer, msg: Buffer, context: Buffer = b"") -> bytes: ...
+def ed25519_verify(
+    pk_bytes: Buffer, msg: Buffer, sig_bytes: Buffer, context: Buffer = b""
+) -> bool: ...
+def ed25519_verify_strict(
+    pk_bytes: Buffer, msg: Buffer, sig_bytes: Buffer, context: Buffer = b""
+) -> None: ...
+
+# X-Wing (X25519 + ML-KEM-768 hybrid KEM)
+def xwing_keygen() -> tuple[bytes, SecretBytes]: ...
+def xwing_encapsulate(pk_bytes: Buffer) -> tuple[bytes, SecretBytes]: ...
+def xwing_decapsulate(sk_bytes: Buffer, ct_bytes: Buffer) -> SecretBytes: ...
+
+# Composite Ed25519 + Falcon-512 signatures
+def ed25519_falcon512_keygen() -> tuple[bytes, SecretBytes]: ...
+def ed25519_falcon512_sign(
+    sk_bytes: Buffer, msg: Buffer, context: Buffer = b""
+) -> bytes: ...
+def ed25519_falcon512_verify(
+    pk_bytes: Buffer, msg: Buffer, sig_bytes: Buffer, context: Buffer = b""
+) -> bool: ...
+def ed25519_falcon512_verify_strict(
+    pk_bytes: Buffer, msg: Buffer, sig_bytes: Buffer, context: Buffer = b""
+) -> None: ...
+
+# Composite ML-DSA + ECDSA P-256 signatures
+def mldsa44_p256_keygen() -> tuple[bytes, SecretBytes]: ...
+def mldsa44_p256_sign(
+    sk_bytes: Buffer, msg: Buffer, context: Buffer = b""
+) -> bytes: ...
+def mldsa44_p256_verify(
+    pk_bytes: Buffer, msg: Buffer, sig_bytes: Buffer, context: Buffer = b""
+) -> bool: ...
+def mldsa44_p256_verify_strict(
+    pk_bytes: Buffer, msg: Buffer, sig_bytes: Buffer, context: Buffer = b""
+) -> None: ...
+def mldsa65_p256_keygen() -> tuple[bytes, SecretBytes]: ...
+def mldsa65_p256_sign(
+    sk_bytes: Buffer, msg: Buffer, context: Buffer = b""
+) -> bytes: ...
+def mldsa65_p256_verify(
+    pk_bytes: Buffer, msg: Buffer, sig_bytes: Buffer, context: Buffer = b""
+) -> bool: ...
+def mldsa65_p256_verify_strict(
+    pk_bytes: Buffer, msg: Buffer, sig_bytes: Buffer, context: Buffer = b""
+) -> None: ...
+
+# Generic hybrid KEM combiner
+def hybrid_combine(
+    ss1: Buffer,
+    ss2: Buffer,
+    ct1: Buffer,
+    ct2: Buffer,
+    pk1: Buffer,
+    pk2: Buffer,
+    label: Buffer,
+) -> SecretBytes: ...
+
+# TLS 1.3 X25519MLKEM768 key-share codec
+X25519MLKEM768_GROUP: Final[int]
+def x25519mlkem768_encode_client_share(
+    mlkem_pk: Buffer, x25519_pk: Buffer
+) -> bytes: ...
+def x25519mlkem768_decode_client_share(share: Buffer) -> tuple[bytes, bytes]: ...
+def x25519mlkem768_encode_server_share(
+    mlkem_ct: Buffer, x25519_pk: Buffer
+) -> bytes: ...
+def x25519mlkem768_decode_server_share(share: Buffer) -> tuple[bytes, bytes]: ...
+def x25519mlkem768_shared_secret(
+    mlkem_ss: Buffer, x25519_ss: Buffer
+) -> SecretBytes: ...
+
+# Generic KEM(name) dispatch
+class KEM:
+    def __init__(self, name: str) -> None: ...
+    @property
+    def name(self) -> str: ...
+    @property
+    def public_key_bytes(self) -> int: ...
+    @property
+    def secret_key_bytes(self) -> int: ...
+    @property
+    def ciphertext_bytes(self) -> int: ...
+    @property
+    def shared_secret_bytes(self) -> int: ...
+    def keygen(self) -> tuple[bytes, SecretBytes]: ...
+    def encapsulate(self, pk_bytes: Buffer) -> tuple[bytes, SecretBytes]: ...
+    def decapsulate(self, sk_bytes: Buffer, ct_bytes: Buffer) -> SecretBytes: ...
+
+# Generic Signature(name) dispatch
+class Signature:
+    def __init__(self, name: str) -> None: ...
+    @property
+    def name(self) -> str: ...
+    @property
+    def public_key_bytes(self) -> int: ...
+    @property
+    def secret_key_bytes(self) -> int: ...
+    @property
+    def signature_bytes(self) -> int: ...
+    def keygen(self) -> tuple[bytes, SecretBytes]: ...
+    def sign(self, sk_bytes: Buffer, msg: Buffer) -> bytes: ...
+    def verify(self, pk_bytes: Buffer, msg: Buffer, sig_bytes: Buffer) -> bool: ...
+    def verify_strict(
+        self, pk_bytes: Buffer, msg: Buffer, sig_bytes: Buffer
+    ) -> None: ...
+
+# CryptoError and its subclasses
+class CryptoError(ValueError): ...
+class KemError(CryptoError): ...
+class DecapsulationError(KemError): ...
+class SignatureError(CryptoError): ...
+class SignatureVerificationError(SignatureError): ...
+class SignatureExpiredError(SignatureVerificationError): ...
+class CertificateVerificationError(SignatureVerificationError): ...
+class InvalidKeyError(CryptoError): ...
+class PublicKeyValidationError(InvalidKeyError): ...
+class InvalidLengthError(CryptoError): ...
+class DecryptionError(CryptoError): ...
+class NonceReuseError(CryptoError): ...
+class KeyPolicyError(CryptoError): ...
+class PairwiseConsistencyError(CryptoError): ...
+
+# Wipeable container for returned secrets
+class SecretBytes:
+    def __init__(self, data: Buffer) -> None: ...
+    def expose(self) -> bytes: ...
+    def destroy(self) -> None: ...
+    @property
+    def alive(self) -> bool: ...
+    @property
+    def locked(self) -> bool: ...
+    def __len__(self) -> int: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __buffer__(self, flags: int, /) -> memoryview: ...
+
+# Secret key handles that wipe on close / `with` exit
+class SecretKey:
+    @property
+    def algorithm(self) -> str: ...
+    @property
+    def closed(self) -> bool: ...
+    def sign(self, msg: Buffer) -> bytes: ...
+    def decapsulate(self, ct_bytes: Buffer) -> SecretBytes: ...
+    def close(self) -> None: ...
+    def __enter__(self) -> Self: ...
+    def __exit__(
+        self,
+        exc_type: type[BaseException] | None,
+        exc_value: BaseException | None,
+        traceback: TracebackType | None,
+    ) -> bool: ...
+
+def load_secret_key(algorithm: str, sk_bytes: Buffer) -> SecretKey: ...
+
+# Key rotation bundles
+class KeyBundle:
+    def __init__(self, algorithm: str) -> None: ...
+    @property
+    def algorithm(self) -> str: ...
+    @property
+    def current_id(self) -> bytes | None: ...
+    def generate(self, make_current: bool = True) -> bytes: ...
+    def add(
+        self, pk: Buffer, sk: Buffer | None = None, make_current: bool = True
+    ) -> bytes: ...
+    def set_current(self, key_id: Buffer) -> None: ...
+    def remove(self, key_id: Buffer) -> None: ...
+    def key_ids(self) -> list[bytes]: ...
+    def public_key(self, key_id: Buffer | None = None) -> bytes: ...
+    def public_only(self) -> KeyBundle: ...
+    def seal(
+        self,
+        plaintext: Buffer,
+        aad: Buffer | None = None,
+        cipher: Literal[
+            "chacha20poly1305", "aes256gcm", "xchacha20poly1305"
+        ] = "chacha20poly1305",
+        committing: bool = False,
+    ) -> bytes: ...
+    def open(
+        self, blob: Buffer, aad: Buffer | None = None, require_committing: bool = False
+    ) -> bytes: ...
+    def encapsulate(self) -> tuple[bytes, SecretBytes]: ...
+    def decapsulate(self, blob: Buffer) -> SecretBytes: ...
+    def sign(self, msg: Buffer) -> bytes: ...
+    def verify(self, msg: Buffer, blob: Buffer) -> bool: ...
+    def verify_strict(self, msg: Buffer, blob: Buffer) -> None: ...
+    def __len__(self) -> int: ...
+    def __contains__(self, key_id: Buffer) -> bool: ...
+
+# Pairwise consistency test after keygen / FIPS mode
+def set_fips_mode(enabled: bool) -> None: ...
+def fips_mode() -> bool: ...
+def set_pairwise_check(enabled: bool | None = None) -> None: ...
+def pairwise_check_enabled() -> bool: ...
+
+# Capability discovery
+def list_kems() -> list[str]: ...
+def list_signatures() -> list[str]: ...
+def is_supported(name: str) -> bool: ...
+
+# Key / ciphertext / signature sizes
+def sizes(name: str) -> dict[str, int]: ...
+KYBER512_PUBLIC_KEY_BYTES: Final[int]
+KYBER512_SECRET_KEY_BYTES: Final[int]
+KYBER512_CIPHERTEXT_BYTES: Final[int]
+KYBER512_SHARED_SECRET_BYTES: Final[int]
+KYBER768_PUBLIC_KEY_BYTES: Final[int]
+KYBER768_SECRET_KEY_BYTES: Final[int]
+KYBER768_CIPHERTEXT_BYTES: Final[int]
+KYBER768_SHARED_SECRET_BYTES: Final[int]
+KYBER1024_PUBLIC_KEY_BYTES: Final[int]
+KYBER1024_SECRET_KEY_BYTES: Final[int]
+KYBER1024_CIPHERTEXT_BYTES: Final[int]
+KYBER1024_SHARED_SECRET_BYTES: Final[int]
+MLKEM512_PUBLIC_KEY_BYTES: Final[int]
+MLKEM512_SECRET_KEY_BYTES: Final[int]
+MLKEM512_CIPHERTEXT_BYTES: Final[int]
+MLKEM512_SHARED_SECRET_BYTES: Final[int]
+MLKEM768_PUBLIC_KEY_BYTES: Final[int]
+MLKEM768_SECRET_KEY_BYTES: Final[int]
+MLKEM768_CIPHERTEXT_BYTES: Final[int]
+MLKEM768_SHARED_SECRET_BYTES: Final[int]
+MLKEM1024_PUBLIC_KEY_BYTES: Final[int]
+MLKEM1024_SECRET_KEY_BYTES: Final[int]
+MLKEM1024_CIPHERTEXT_BYTES: Final[int]
+MLKEM1024_SHARED_SECRET_BYTES: Final[int]
+FRODOKEM640AES_PUBLIC_KEY_BYTES: Final[int]
+FRODOKEM640AES_SECRET_KEY_BYTES: Final[int]
+FRODOKEM640AES_CIPHERTEXT_BYTES: Final[int]
+FRODOKEM640AES_SHARED_SECRET_BYTES: Final[int]
+FRODOKEM640SHAKE_PUBLIC_KEY_BYTES: Final[int]
+FRODOKEM640SHAKE_SECRET_KEY_BYTES: Final[int]
+FRODOKEM640SHAKE_CIPHERTEXT_BYTES: Final[int]
+FRODOKEM640SHAKE_SHARED_SECRET_BYTES: Final[int]
+FRODOKEM976AES_PUBLIC_KEY_BYTES: Final[int]
+FRODOKEM976AES_SECRET_KEY_BYTES: Final[int]
+FRODOKEM976AES_CIPHERTEXT_BYTES: Final[int]
+FRODOKEM976AES_SHARED_SECRET_BYTES: Final[int]
+FRODOKEM976SHAKE_PUBLIC_KEY_BYTES: Final[int]
+FRODOKEM976SHAKE_SECRET_KEY_BYTES: Final[int]
+FRODOKEM976SHAKE_CIPHERTEXT_BYTES: Final[int]
+FRODOKEM976SHAKE_SHARED_SECRET_BYTES: Final[int]
+FRODOKEM1344AES_PUBLIC_KEY_BYTES: Final[int]
+FRODOKEM1344AES_SECRET_KEY_BYTES: Final[int]
+FRODOKEM1344AES_CIPHERTEXT_BYTES: Final[int]
+FRODOKEM1344AES_SHARED_SECRET_BYTES: Final[int]
+FRODOKEM1344SHAKE_PUBLIC_KEY_BYTES: Final[int]
+FRODOKEM1344SHAKE_SECRET_KEY_BYTES: Final[int]
+FRODOKEM1344SHAKE_CIPHERTEXT_BYTES: Final[int]
+FRODOKEM1344SHAKE_SHARED_SECRET_BYTES: Final[int]
+SNTRUP761_PUBLIC_KEY_BYTES: Final[int]
+SNTRUP761_SECRET_KEY_BYTES: Final[int]
+SNTRUP761_CIPHERTEXT_BYTES: Final[int]
+SNTRUP761_SHARED_SECRET_BYTES: Final[int]
+HQC128_PUBLIC_KEY_BYTES: Final[int]
+HQC128_SECRET_KEY_BYTES: Final[int]
+HQC128_CIPHERTEXT_BYTES: Final[int]
+HQC128_SHARED_SECRET_BYTES: Final[int]
+HQC192_PUBLIC_KEY_BYTES: Final[int]
+HQC192_SECRET_KEY_BYTES: Final[int]
+HQC192_CIPHERTEXT_BYTES: Final[int]
+HQC192_SHARED_SECRET_BYTES: Final[int]
+HQC256_PUBLIC_KEY_BYTES: Final[int]
+HQC256_SECRET_KEY_BYTES: Final[int]
+HQC256_CIPHERTEXT_BYTES: Final[int]
+HQC256_SHARED_SECRET_BYTES: Final[int]
+MCELIECE348864_PUBLIC_KEY_BYTES: Final[int]
+MCELIECE348864_SECRET_KEY_BYTES: Final[int]
+MCELIECE348864_CIPHERTEXT_BYTES: Final[int]
+MCELIECE348864_SHARED_SECRET_BYTES: Final[int]
+MCELIECE460896_PUBLIC_KEY_BYTES: Final[int]
+MCELIECE460896_SECRET_KEY_BYTES: Final[int]
+MCELIECE460896_CIPHERTEXT_BYTES: Final[int]
+MCELIECE460896_SHARED_SECRET_BYTES: Final[int]
+XWING_PUBLIC_KEY_BYTES: Final[int]
+XWING_SECRET_KEY_BYTES: Final[int]
+XWING_CIPHERTEXT_BYTES: Final[int]
+XWING_SHARED_SECRET_BYTES: Final[int]
+FALCON512_PUBLIC_KEY_BYTES: Final[int]
+FALCON512_SECRET_KEY_BYTES: Final[int]
+FALCON512_MAX_SIGNATURE_BYTES: Final[int]
+FALCON1024_PUBLIC_KEY_BYTES: Final[int]
+FALCON1024_SECRET_KEY_BYTES: Final[int]
+FALCON1024_MAX_SIGNATURE_BYTES: Final[int]
+MLDSA44_PUBLIC_KEY_BYTES: Final[int]
+MLDSA44_SECRET_KEY_BYTES: Final[int]
+MLDSA44_MAX_SIGNATURE_BYTES: Final[int]
+MLDSA65_PUBLIC_KEY_BYTES: Final[int]
+MLDSA65_SECRET_KEY_BYTES: Final[int]
+MLDSA65_MAX_SIGNATURE_BYTES: Final[int]
+MLDSA87_PUBLIC_KEY_BYTES: Final[int]
+MLDSA87_SECRET_KEY_BYTES: Final[int]
+MLDSA87_MAX_SIGNATURE_BYTES: Final[int]
+SPHINCSSHA2128SSIMPLE_PUBLIC_KEY_BYTES: Final[int]
+SPHINCSSHA2128SSIMPLE_SECRET_KEY_BYTES: Final[int]
+SPHINCSSHA2128SSIMPLE_MAX_SIGNATURE_BYTES: Final[int]
+SPHINCSSHA2192SSIMPLE_PUBLIC_KEY_BYTES: Final[int]
+SPHINCSSHA2192SSIMPLE_SECRET_KEY_BYTES: Final[int]
+SPHINCSSHA2192SSIMPLE_MAX_SIGNATURE_BYTES: Final[int]
+SPHINCSSHA2256SSIMPLE_PUBLIC_KEY_BYTES: Final[int]
+SPHINCSSHA2256SSIMPLE_SECRET_KEY_BYTES: Final[int]
+SPHINCSSHA2256SSIMPLE_MAX_SIGNATURE_BYTES: Final[int]
+SPHINCSSHAKE128FSIMPLE_PUBLIC_KEY_BYTES: Final[int]
+SPHINCSSHAKE128FSIMPLE_SECRET_KEY_BYTES: Final[int]
+SPHINCSSHAKE128FSIMPLE_MAX_SIGNATURE_BYTES: Final[int]
+SPHINCSSHAKE192FSIMPLE_PUBLIC_KEY_BYTES: Final[int]
+SPHINCSSHAKE192FSIMPLE_SECRET_KEY_BYTES: Final[int]
+SPHINCSSHAKE192FSIMPLE_MAX_SIGNATURE_BYTES: Final[int]
+SPHINCSSHAKE256FSIMPLE_PUBLIC_KEY_BYTES: Final[int]
+SPHINCSSHAKE256FSIMPLE_SECRET_KEY_BYTES: Final[int]
+SPHINCSSHAKE256FSIMPLE_MAX_SIGNATURE_BYTES: Final[int]
+ED25519_PUBLIC_KEY_BYTES: Final[int]
+ED25519_SECRET_KEY_BYTES: Final[int]
+ED25519_MAX_SIGNATURE_BYTES: Final[int]
+ED25519FALCON512_PUBLIC_KEY_BYTES: Final[int]
+ED25519FALCON512_SECRET_KEY_BYTES: Final[int]
+ED25519FALCON512_MAX_SIGNATURE_BYTES: Final[int]
+MLDSA44P256_PUBLIC_KEY_BYTES: Final[int]
+MLDSA44P256_SECRET_KEY_BYTES: Final[int]
+MLDSA44P256_MAX_SIGNATURE_BYTES: Final[int]
+MLDSA65P256_PUBLIC_KEY_BYTES: Final[int]
+MLDSA65P256_SECRET_KEY_BYTES: Final[int]
+MLDSA65P256_MAX_SIGNATURE_BYTES: Final[int]
+
+# Public key validation (FIPS 203 modulus check for ML-KEM / Kyber)
+def validate_public_key(name: str, pk_bytes: Buffer) -> None: ...
+
+# Public key fingerprints
+def fingerprint(algorithm: str, pk_bytes: Buffer) -> bytes: ...
+def format_fingerprint(fp: Buffer) -> str: ...
+
+# kem / sign / util namespaces over the flat names above
+class _KemNamespace:
+    KEM: type[KEM]
+    kyber512: KEM
+    kyber768: KEM
+    kyber1024: KEM
+    mlkem512: KEM
+    mlkem768: KEM
+    mlkem1024: KEM
+    frodokem640aes: KEM
+    frodokem640shake: KEM
+    frodokem976aes: KEM
+    frodokem976shake: KEM
+    frodokem1344aes: KEM
+    frodokem1344shake: KEM
+    sntrup761: KEM
+    hqc128: KEM
+    hqc192: KEM
+    hqc256: KEM
+    mceliece348864: KEM
+    mceliece460896: KEM
+    xwing: KEM
+
+class _SignNamespace:
+    Signature: type[Signature]
+    falcon512: Signature
+    falcon1024: Signature
+    mldsa44: Signature
+    mldsa65: Signature
+    mldsa87: Signature
+    sphincssha2128ssimple: Signature
+    sphincssha2192ssimple: Signature
+    sphincssha2256ssimple: Signature
+    sphincsshake128fsimple: Signature
+    sphincsshake192fsimple: Signature
+    sphincsshake256fsimple: Signature
+    ed25519: Signature
+    ed25519falcon512: Signature
+    mldsa44p256: Signature
+    mldsa65p256: Signature
+
+class _UtilNamespace:
+    SecretBytes: type[SecretBytes]
+    SecretKey: type[SecretKey]
+    load_secret_key = load_secret_key
+    list_kems = list_kems
+    list_signatures = list_signatures
+    is_supported = is_supported
+    sizes = sizes
+    fingerprint = fingerprint
+    format_fingerprint = format_fingerprint
+    validate_public_key = validate_public_key
+    set_fips_mode = set_fips_mode
+    fips_mode = fips_mode
+    set_pairwise_check = set_pairwise_check
+    pairwise_check_enabled = pairwise_check_enabled
+
+kem: _KemNamespace
+sign: _SignNamespace
+util: _UtilNamespace
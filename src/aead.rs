@@ -0,0 +1,318 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// AEAD primitives
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// For encrypting with a KEM shared secret directly:
+//
+//   ct, ss = kyber768_encapsulate(pk)
+//   nonce = os.urandom(12)
+//   box = chacha20poly1305_encrypt(ss, nonce, b"header", msg)
+//   msg = chacha20poly1305_decrypt(ss, nonce, b"header", box)
+//
+// aes256gcm_encrypt() / aes256gcm_decrypt() take the same arguments, for
+// deployments that must use AES. AES runs on AES-NI / the ARMv8 crypto
+// extensions when the CPU has them (detected at run time), else on a
+// constant-time software implementation; ChaCha20-Poly1305 is the faster
+// choice without them.
+//
+// Keys are 32 bytes and nonces 12; the output is ciphertext || 16-byte tag.
+// A nonce must never repeat under one key; with random nonces, rotate the key
+// well before 2^32 messages, or let a NonceSequence count them, or use
+// seal() / open(), which take a fresh key per message. A wrong key, nonce or
+// aad, or any change to the ciphertext, raises DecryptionError.
+//
+// XChaCha20-Poly1305 takes a 24-byte nonce instead, long enough to pick at
+// random for every message without counting them. xchacha20poly1305_seal()
+// does exactly that and prepends the nonce, so there is no nonce to manage:
+//
+//   box = xchacha20poly1305_seal(key, b"header", msg)   # nonce || ct || tag
+//   msg = xchacha20poly1305_open(key, b"header", box)
+
+use aes_gcm::Aes256Gcm;
+use chacha20poly1305::aead::{AeadInOut, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, XChaCha20Poly1305};
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+use crate::buffer::Buffer;
+use crate::errors::{check_length, DecryptionError, InvalidLengthError};
+use crate::kem::normalize_name;
+
+pub(crate) const KEY_LEN: usize = 32;
+pub(crate) const TAG_LEN: usize = 16;
+
+/// The AEADs on offer, with their ids in seal() blobs.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Aead {
+    ChaCha20Poly1305 = 1,
+    Aes256Gcm = 2,
+    XChaCha20Poly1305 = 3,
+}
+
+const AEADS: [Aead; 3] = [
+    Aead::ChaCha20Poly1305,
+    Aead::Aes256Gcm,
+    Aead::XChaCha20Poly1305,
+];
+
+impl Aead {
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Aead::ChaCha20Poly1305 => "chacha20poly1305",
+            Aead::Aes256Gcm => "aes256gcm",
+            Aead::XChaCha20Poly1305 => "xchacha20poly1305",
+        }
+    }
+
+    fn display_name(self) -> &'static str {
+        match self {
+            Aead::ChaCha20Poly1305 => "ChaCha20-Poly1305",
+            Aead::Aes256Gcm => "AES-256-GCM",
+            Aead::XChaCha20Poly1305 => "XChaCha20-Poly1305",
+        }
+    }
+
+    pub(crate) fn nonce_len(self) -> usize {
+        match self {
+            Aead::ChaCha20Poly1305 | Aead::Aes256Gcm => 12,
+            Aead::XChaCha20Poly1305 => 24,
+        }
+    }
+
+    pub(crate) fn from_name(name: &str) -> PyResult<Self> {
+        let wanted = normalize_name(name);
+        AEADS
+            .into_iter()
+            .find(|a| a.name() == wanted)
+            .ok_or_else(|| {
+                PyValueError::new_err(format!(
+                    "unsupported cipher '{}', expected one of: {}",
+                    name,
+                    AEADS.map(Aead::name).join(", ")
+                ))
+            })
+    }
+
+    pub(crate) fn from_id(id: u8) -> Option<Self> {
+        AEADS.into_iter().find(|&a| a as u8 == id)
+    }
+
+    fn check_params(self, key: &[u8], nonce: &[u8]) -> PyResult<()> {
+        check_length(&format!("{} key", self.display_name()), key, KEY_LEN)?;
+        check_length(
+            &format!("{} nonce", self.display_name()),
+            nonce,
+            self.nonce_len(),
+        )
+    }
+
+    /// Encrypt `buf` in place, appending the tag.
+    pub(crate) fn encrypt(
+        self,
+        key: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+        buf: &mut Vec<u8>,
+    ) -> PyResult<()> {
+        self.check_params(key, nonce)?;
+        let result = match self {
+            Aead::ChaCha20Poly1305 => ChaCha20Poly1305::new_from_slice(key)
+                .expect("key length checked")
+                .encrypt_in_place(nonce.try_into().expect("nonce length checked"), aad, buf),
+            Aead::Aes256Gcm => Aes256Gcm::new_from_slice(key)
+                .expect("key length checked")
+                .encrypt_in_place(nonce.try_into().expect("nonce length checked"), aad, buf),
+            Aead::XChaCha20Poly1305 => XChaCha20Poly1305::new_from_slice(key)
+                .expect("key length checked")
+                .encrypt_in_place(nonce.try_into().expect("nonce length checked"), aad, buf),
+        };
+        result.map_err(|_| {
+            PyRuntimeError::new_err(format!("{} encryption failed", self.display_name()))
+        })
+    }
+
+    /// Verify and decrypt `buf` (ciphertext || tag) in place, dropping the
+    /// tag. Raises DecryptionError if it doesn't authenticate.
+    pub(crate) fn decrypt(
+        self,
+        key: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+        buf: &mut Vec<u8>,
+    ) -> PyResult<()> {
+        self.check_params(key, nonce)?;
+        if buf.len() < TAG_LEN {
+            return Err(InvalidLengthError::new_err(format!(
+                "{} ciphertext: expected at least {} bytes, got {}",
+                self.display_name(),
+                TAG_LEN,
+                buf.len()
+            )));
+        }
+        let result = match self {
+            Aead::ChaCha20Poly1305 => ChaCha20Poly1305::new_from_slice(key)
+                .expect("key length checked")
+                .decrypt_in_place(nonce.try_into().expect("nonce length checked"), aad, buf),
+            Aead::Aes256Gcm => Aes256Gcm::new_from_slice(key)
+                .expect("key length checked")
+                .decrypt_in_place(nonce.try_into().expect("nonce length checked"), aad, buf),
+            Aead::XChaCha20Poly1305 => XChaCha20Poly1305::new_from_slice(key)
+                .expect("key length checked")
+                .decrypt_in_place(nonce.try_into().expect("nonce length checked"), aad, buf),
+        };
+        result.map_err(|_| {
+            DecryptionError::new_err(format!("{} authentication failed", self.display_name()))
+        })
+    }
+}
+
+fn encrypt(
+    py: Python,
+    aead: Aead,
+    key: &[u8],
+    nonce: &[u8],
+    aad: &[u8],
+    data: &[u8],
+) -> PyResult<Py<PyBytes>> {
+    let mut body = Vec::with_capacity(data.len() + TAG_LEN);
+    body.extend_from_slice(data);
+    aead.encrypt(key, nonce, aad, &mut body)?;
+    Ok(PyBytes::new_bound(py, &body).unbind())
+}
+
+fn decrypt(
+    py: Python,
+    aead: Aead,
+    key: &[u8],
+    nonce: &[u8],
+    aad: &[u8],
+    data: &[u8],
+) -> PyResult<Py<PyBytes>> {
+    let mut body = data.to_vec();
+    aead.decrypt(key, nonce, aad, &mut body)?;
+    Ok(PyBytes::new_bound(py, &body).unbind())
+}
+
+// ─── ChaCha20-Poly1305 (RFC 8439) ─────────────────────────────────────────────
+
+/// Encrypt `data` under a 32-byte `key` and 12-byte `nonce`, authenticating
+/// `aad` with it. Returns ciphertext || tag.
+#[pyfunction]
+pub fn chacha20poly1305_encrypt(
+    py: Python,
+    key: Buffer,
+    nonce: Buffer,
+    aad: Buffer,
+    data: Buffer,
+) -> PyResult<Py<PyBytes>> {
+    encrypt(py, Aead::ChaCha20Poly1305, &key, &nonce, &aad, &data)
+}
+
+/// Decrypt and verify a chacha20poly1305_encrypt() output.
+#[pyfunction]
+pub fn chacha20poly1305_decrypt(
+    py: Python,
+    key: Buffer,
+    nonce: Buffer,
+    aad: Buffer,
+    data: Buffer,
+) -> PyResult<Py<PyBytes>> {
+    decrypt(py, Aead::ChaCha20Poly1305, &key, &nonce, &aad, &data)
+}
+
+// ─── AES-256-GCM (NIST SP 800-38D) ────────────────────────────────────────────
+
+/// Encrypt `data` under a 32-byte `key` and 12-byte `nonce`, authenticating
+/// `aad` with it. Returns ciphertext || tag.
+#[pyfunction]
+pub fn aes256gcm_encrypt(
+    py: Python,
+    key: Buffer,
+    nonce: Buffer,
+    aad: Buffer,
+    data: Buffer,
+) -> PyResult<Py<PyBytes>> {
+    encrypt(py, Aead::Aes256Gcm, &key, &nonce, &aad, &data)
+}
+
+/// Decrypt and verify an aes256gcm_encrypt() output.
+#[pyfunction]
+pub fn aes256gcm_decrypt(
+    py: Python,
+    key: Buffer,
+    nonce: Buffer,
+    aad: Buffer,
+    data: Buffer,
+) -> PyResult<Py<PyBytes>> {
+    decrypt(py, Aead::Aes256Gcm, &key, &nonce, &aad, &data)
+}
+
+// ─── XChaCha20-Poly1305 ───────────────────────────────────────────────────────
+
+/// Encrypt `data` under a 32-byte `key` and 24-byte `nonce`, authenticating
+/// `aad` with it. Returns ciphertext || tag.
+#[pyfunction]
+pub fn xchacha20poly1305_encrypt(
+    py: Python,
+    key: Buffer,
+    nonce: Buffer,
+    aad: Buffer,
+    data: Buffer,
+) -> PyResult<Py<PyBytes>> {
+    encrypt(py, Aead::XChaCha20Poly1305, &key, &nonce, &aad, &data)
+}
+
+/// Decrypt and verify an xchacha20poly1305_encrypt() output.
+#[pyfunction]
+pub fn xchacha20poly1305_decrypt(
+    py: Python,
+    key: Buffer,
+    nonce: Buffer,
+    aad: Buffer,
+    data: Buffer,
+) -> PyResult<Py<PyBytes>> {
+    decrypt(py, Aead::XChaCha20Poly1305, &key, &nonce, &aad, &data)
+}
+
+/// Encrypt `data` under `key` with a fresh random nonce. Returns nonce (24)
+/// || ciphertext || tag.
+#[pyfunction]
+pub fn xchacha20poly1305_seal(
+    py: Python,
+    key: Buffer,
+    aad: Buffer,
+    data: Buffer,
+) -> PyResult<Py<PyBytes>> {
+    let mut nonce = [0u8; 24];
+    getrandom::fill(&mut nonce).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+
+    let mut body = Vec::with_capacity(data.len() + TAG_LEN);
+    body.extend_from_slice(&data);
+    Aead::XChaCha20Poly1305.encrypt(&key, &nonce, &aad, &mut body)?;
+
+    let mut out = Vec::with_capacity(nonce.len() + body.len());
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&body);
+    Ok(PyBytes::new_bound(py, &out).unbind())
+}
+
+/// Decrypt and verify an xchacha20poly1305_seal() output.
+#[pyfunction]
+pub fn xchacha20poly1305_open(
+    py: Python,
+    key: Buffer,
+    aad: Buffer,
+    data: Buffer,
+) -> PyResult<Py<PyBytes>> {
+    let aead = Aead::XChaCha20Poly1305;
+    if data.len() < aead.nonce_len() + TAG_LEN {
+        return Err(InvalidLengthError::new_err(format!(
+            "XChaCha20-Poly1305 box: expected at least {} bytes, got {}",
+            aead.nonce_len() + TAG_LEN,
+            data.len()
+        )));
+    }
+    let (nonce, body) = data.split_at(aead.nonce_len());
+    decrypt(py, aead, &key, nonce, &aad, body)
+}
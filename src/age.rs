@@ -0,0 +1,408 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// age v1 files to PQ KEM recipients
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// For encrypting in the age format (https://age-encryption.org/v1) to
+// Kyber / ML-KEM and other KEM public keys:
+//
+//   data = age_encrypt([alice_pk, bob_pk], b"...", algorithm="mlkem768")
+//   msg = age_decrypt(alice_sk, data)
+//
+// Everything but the recipient stanzas is age v1 as specified: the text
+// header, its HMAC, the 64 KiB ChaCha20-Poly1305 STREAM payload and the
+// optional ASCII armor. A random 16-byte file key is wrapped once per
+// recipient in a stanza named after the KEM:
+//
+//   -> mlkem768 <base64 KEM ciphertext>
+//   <base64 wrapped file key, wrapped at 64 columns>
+//
+//   wrap key    = HKDF-SHA256(salt = KEM ciphertext, IKM = shared secret,
+//                             info = label || KEM name, 32)
+//   wrapped key = ChaCha20-Poly1305(wrap key, nonce = 0, file key)
+//
+// as age's X25519 stanza does it, with the KEM in place of the Diffie-Hellman.
+// Base64 is canonical and unpadded, as the spec requires. age itself skips
+// stanza types it doesn't know, so it reads these files only through a plugin
+// that implements the stanza; the layout is otherwise unchanged, so tooling
+// that handles age files as opaque blobs, or parses the header, works as is.
+//
+// age_decrypt() tries each stanza whose KEM takes a secret key of the given
+// length, as open() does, and ignores stanzas of other types. Without
+// `algorithm`, age_encrypt() picks Kyber-512 / 768 / 1024 by key length; pass
+// it for ML-KEM. Malformed headers, wrong keys and modified or truncated
+// payloads raise DecryptionError. Both work on the whole file in memory.
+
+use base64ct::{Base64, Base64Unpadded, Encoding};
+use hkdf::hmac::{Hmac, KeyInit, Mac};
+use hkdf::Hkdf;
+use pyo3::exceptions::{PyRuntimeError, PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use sha2::Sha256;
+use zeroize::Zeroizing;
+
+use crate::aead::{Aead, KEY_LEN, TAG_LEN};
+use crate::buffer::Buffer;
+use crate::errors::DecryptionError;
+use crate::kem::{find_kem, KemAlgorithm};
+use crate::seal::kem_for;
+use crate::stream::Stream;
+use crate::util::ct_eq;
+
+const INTRO: &str = "age-encryption.org/v1";
+const STANZA_PREFIX: &str = "-> ";
+const MAC_PREFIX: &str = "---";
+const ARMOR_BEGIN: &str = "-----BEGIN AGE ENCRYPTED FILE-----";
+const ARMOR_END: &str = "-----END AGE ENCRYPTED FILE-----";
+const COLUMNS: usize = 64;
+
+const LABEL: &[u8] = b"entropic-chaos age v1\0";
+const FILE_KEY_LEN: usize = 16;
+const WRAPPED_KEY_LEN: usize = FILE_KEY_LEN + TAG_LEN;
+const MAC_LEN: usize = 32;
+const PAYLOAD_NONCE_LEN: usize = 16;
+const CHUNK_SIZE: usize = 64 * 1024;
+
+fn malformed(what: &str) -> PyErr {
+    DecryptionError::new_err(format!("malformed age file: {}", what))
+}
+
+fn hkdf_sha256(salt: &[u8], ikm: &[u8], info: &[&[u8]]) -> Zeroizing<[u8; KEY_LEN]> {
+    let mut key = Zeroizing::new([0u8; KEY_LEN]);
+    Hkdf::<Sha256>::new(Some(salt), ikm)
+        .expand_multi_info(info, key.as_mut())
+        .expect("32 bytes is a valid HKDF-SHA256 length");
+    key
+}
+
+fn header_mac(file_key: &[u8], header: &[u8]) -> [u8; MAC_LEN] {
+    let key = hkdf_sha256(&[], file_key, &[b"header"]);
+    let mut mac =
+        <Hmac<Sha256> as KeyInit>::new_from_slice(&key[..]).expect("HMAC takes any key length");
+    mac.update(header);
+    mac.finalize().into_bytes().into()
+}
+
+fn payload_stream(file_key: &[u8], nonce: &[u8]) -> PyResult<Stream> {
+    // age's STREAM nonce is an 11-byte counter and the last-chunk flag: with
+    // a zero prefix, stream.rs's 4-byte counter covers the first 2^32 chunks.
+    let key = hkdf_sha256(nonce, file_key, &[b"payload"]);
+    let prefix = vec![0u8; Stream::prefix_len(Aead::ChaCha20Poly1305)];
+    Stream::new(Aead::ChaCha20Poly1305, &key[..], prefix, &[])
+}
+
+fn wrap_key(alg: &KemAlgorithm, ct: &[u8], ss: &[u8]) -> Zeroizing<[u8; KEY_LEN]> {
+    hkdf_sha256(ct, ss, &[LABEL, alg.name.as_bytes()])
+}
+
+// ─── Header ───────────────────────────────────────────────────────────────────
+
+struct Stanza<'a> {
+    kind: &'a str,
+    args: Vec<&'a str>,
+    body: Vec<u8>,
+}
+
+fn write_stanza(out: &mut Vec<u8>, kind: &str, args: &[&str], body: &[u8]) {
+    out.extend_from_slice(STANZA_PREFIX.as_bytes());
+    out.extend_from_slice(kind.as_bytes());
+    for arg in args {
+        out.push(b' ');
+        out.extend_from_slice(arg.as_bytes());
+    }
+    out.push(b'\n');
+    // The last body line is always shorter than 64 columns, so a body whose
+    // encoding is a multiple of 64 ends with an empty line.
+    let encoded = Base64Unpadded::encode_string(body);
+    let mut lines = encoded.as_bytes().chunks(COLUMNS);
+    loop {
+        let line = lines.next().unwrap_or_default();
+        out.extend_from_slice(line);
+        out.push(b'\n');
+        if line.len() < COLUMNS {
+            break;
+        }
+    }
+}
+
+/// Lines of the header, each without its newline.
+struct Lines<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Lines<'a> {
+    fn next(&mut self) -> PyResult<&'a str> {
+        let rest = &self.data[self.pos..];
+        let end = rest
+            .iter()
+            .position(|&b| b == b'\n')
+            .ok_or_else(|| malformed("header is truncated"))?;
+        self.pos += end + 1;
+        let line = &rest[..end];
+        // Header lines are printable ASCII only.
+        if !line.iter().all(|&b| (0x20..0x7f).contains(&b)) {
+            return Err(malformed("header line has a non-printable character"));
+        }
+        Ok(std::str::from_utf8(line).expect("ASCII checked"))
+    }
+}
+
+fn decode_unpadded(s: &str, what: &str) -> PyResult<Vec<u8>> {
+    Base64Unpadded::decode_vec(s)
+        .map_err(|_| malformed(&format!("{} isn't canonical base64", what)))
+}
+
+struct Header<'a> {
+    stanzas: Vec<Stanza<'a>>,
+    /// The header up to and including "---", which the MAC covers.
+    mac_input: &'a [u8],
+    mac: Vec<u8>,
+    payload: &'a [u8],
+}
+
+fn parse_header(data: &[u8]) -> PyResult<Header<'_>> {
+    let mut lines = Lines { data, pos: 0 };
+    if lines.next().ok() != Some(INTRO) {
+        return Err(DecryptionError::new_err("not an age v1 file"));
+    }
+    let mut stanzas = Vec::new();
+    loop {
+        let line_start = lines.pos;
+        let line = lines.next()?;
+        if let Some(mac) = line.strip_prefix(MAC_PREFIX) {
+            let mac = mac
+                .strip_prefix(' ')
+                .ok_or_else(|| malformed("MAC line has no MAC"))?;
+            return Ok(Header {
+                stanzas,
+                mac_input: &data[..line_start + MAC_PREFIX.len()],
+                mac: decode_unpadded(mac, "header MAC")?,
+                payload: &data[lines.pos..],
+            });
+        }
+        let line = line
+            .strip_prefix(STANZA_PREFIX)
+            .ok_or_else(|| malformed("expected a stanza or the MAC line"))?;
+        let mut args: Vec<&str> = line.split(' ').collect();
+        if args.iter().any(|arg| arg.is_empty()) {
+            return Err(malformed("stanza has an empty argument"));
+        }
+        let kind = args.remove(0);
+
+        let mut encoded = String::new();
+        loop {
+            let body_line = lines.next()?;
+            if body_line.len() > COLUMNS {
+                return Err(malformed("stanza body line is longer than 64 columns"));
+            }
+            encoded.push_str(body_line);
+            if body_line.len() < COLUMNS {
+                break;
+            }
+        }
+        stanzas.push(Stanza {
+            kind,
+            args,
+            body: decode_unpadded(&encoded, "stanza body")?,
+        });
+    }
+}
+
+// ─── Armor ────────────────────────────────────────────────────────────────────
+
+fn armor(data: &[u8]) -> Vec<u8> {
+    let encoded = Base64::encode_string(data);
+    let mut out = Vec::with_capacity(encoded.len() + encoded.len() / COLUMNS + 80);
+    out.extend_from_slice(ARMOR_BEGIN.as_bytes());
+    out.push(b'\n');
+    for line in encoded.as_bytes().chunks(COLUMNS) {
+        out.extend_from_slice(line);
+        out.push(b'\n');
+    }
+    out.extend_from_slice(ARMOR_END.as_bytes());
+    out.push(b'\n');
+    out
+}
+
+/// The binary file inside ASCII armor, or None if `data` isn't armored.
+fn unarmor(data: &[u8]) -> PyResult<Option<Vec<u8>>> {
+    let text = data.trim_ascii();
+    let Some(body) = text.strip_prefix(ARMOR_BEGIN.as_bytes()) else {
+        return Ok(None);
+    };
+    let body = body
+        .strip_suffix(ARMOR_END.as_bytes())
+        .ok_or_else(|| malformed("armor has no end line"))?;
+    let body = std::str::from_utf8(body).map_err(|_| malformed("armor isn't ASCII"))?;
+    let body = body.replace("\r\n", "\n");
+    let lines: Vec<&str> = body
+        .strip_prefix('\n')
+        .and_then(|b| b.strip_suffix('\n'))
+        .ok_or_else(|| malformed("armor lines are misplaced"))?
+        .split('\n')
+        .collect();
+    // Every line but the last is exactly 64 columns.
+    let (last, full) = lines.split_last().expect("split yields at least one line");
+    if full.iter().any(|l| l.len() != COLUMNS) || last.is_empty() || last.len() > COLUMNS {
+        return Err(malformed("armor lines aren't 64 columns"));
+    }
+    Base64::decode_vec(&lines.concat())
+        .map(Some)
+        .map_err(|_| malformed("armor isn't canonical base64"))
+}
+
+// ─── Encrypt / decrypt ────────────────────────────────────────────────────────
+
+/// Encrypt `data` to one KEM public key or a list of them, as an age v1 file.
+/// With `armor`, the file is PEM-style ASCII.
+// stub: recipients: Buffer | Sequence[Buffer]
+#[pyfunction]
+#[pyo3(signature = (recipients, data, algorithm = None, armor = false))]
+pub fn age_encrypt(
+    py: Python,
+    recipients: &Bound<'_, PyAny>,
+    data: Buffer,
+    algorithm: Option<&str>,
+    armor: bool,
+) -> PyResult<Py<PyBytes>> {
+    let pks: Vec<Buffer> = match recipients.extract::<Buffer>() {
+        Ok(pk) => vec![pk],
+        Err(_) => recipients.extract().map_err(|_| {
+            PyTypeError::new_err("recipients must be a public key or a list of public keys")
+        })?,
+    };
+    if pks.is_empty() {
+        return Err(PyValueError::new_err(
+            "age_encrypt() needs at least one recipient",
+        ));
+    }
+
+    let mut file_key = Zeroizing::new([0u8; FILE_KEY_LEN]);
+    getrandom::fill(file_key.as_mut()).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+
+    let mut out = Vec::new();
+    out.extend_from_slice(INTRO.as_bytes());
+    out.push(b'\n');
+    for pk in pks {
+        let alg = kem_for(algorithm, &pk)?;
+        let (ct, ss) = (alg.encapsulate)(py, pk)?;
+        let ss: Buffer = ss.bind(py).extract()?;
+        let ct = ct.as_bytes(py);
+
+        let mut wrapped = file_key.to_vec();
+        Aead::ChaCha20Poly1305.encrypt(&wrap_key(alg, ct, &ss)[..], &[0; 12], &[], &mut wrapped)?;
+        let ct = Base64Unpadded::encode_string(ct);
+        write_stanza(&mut out, alg.name, &[&ct], &wrapped);
+    }
+    out.extend_from_slice(MAC_PREFIX.as_bytes());
+    let mac = header_mac(&file_key[..], &out);
+    out.push(b' ');
+    out.extend_from_slice(Base64Unpadded::encode_string(&mac).as_bytes());
+    out.push(b'\n');
+
+    let mut nonce = [0u8; PAYLOAD_NONCE_LEN];
+    getrandom::fill(&mut nonce).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+    out.extend_from_slice(&nonce);
+    let mut stream = payload_stream(&file_key[..], &nonce)?;
+    // The last chunk is short or, when the data is a multiple of 64 KiB, full;
+    // it is empty only when the whole payload is.
+    let mut chunks = data.chunks(CHUNK_SIZE).peekable();
+    if chunks.peek().is_none() {
+        let mut chunk = Vec::with_capacity(TAG_LEN);
+        stream.encrypt_in_place(&mut chunk, true)?;
+        out.extend_from_slice(&chunk);
+    }
+    while let Some(chunk) = chunks.next() {
+        let mut buf = Vec::with_capacity(chunk.len() + TAG_LEN);
+        buf.extend_from_slice(chunk);
+        stream.encrypt_in_place(&mut buf, chunks.peek().is_none())?;
+        out.extend_from_slice(&buf);
+    }
+
+    let out = if armor { self::armor(&out) } else { out };
+    Ok(PyBytes::new_bound(py, &out).unbind())
+}
+
+/// The file key from the first stanza `sk` unwraps.
+fn unwrap_file_key(
+    py: Python,
+    sk: &Bound<'_, PyAny>,
+    stanzas: &[Stanza],
+) -> PyResult<Zeroizing<Vec<u8>>> {
+    let sk_len = sk.extract::<Buffer>()?.len();
+    for stanza in stanzas {
+        let Some(alg) = find_kem(stanza.kind).filter(|alg| alg.name == stanza.kind) else {
+            continue;
+        };
+        let [ct] = stanza.args[..] else {
+            return Err(malformed(&format!(
+                "{} stanza takes one argument",
+                alg.name
+            )));
+        };
+        let ct = decode_unpadded(ct, "KEM ciphertext")?;
+        if ct.len() != alg.ciphertext_bytes || stanza.body.len() != WRAPPED_KEY_LEN {
+            return Err(malformed(&format!(
+                "{} stanza has the wrong length",
+                alg.name
+            )));
+        }
+        if alg.secret_key_bytes != sk_len {
+            continue;
+        }
+
+        let ct_bytes = PyBytes::new_bound(py, &ct);
+        let ss: Buffer = (alg.decapsulate)(py, sk.extract()?, ct_bytes.extract()?)?
+            .bind(py)
+            .extract()?;
+        let mut file_key = Zeroizing::new(stanza.body.clone());
+        let key = wrap_key(alg, &ct, &ss);
+        if Aead::ChaCha20Poly1305
+            .decrypt(&key[..], &[0; 12], &[], &mut file_key)
+            .is_ok()
+        {
+            return Ok(file_key);
+        }
+    }
+    Err(DecryptionError::new_err(
+        "the secret key matches none of the age file's recipients",
+    ))
+}
+
+/// Decrypt an age v1 file, armored or not, with a recipient's KEM secret key.
+// stub: sk: Buffer
+#[pyfunction]
+pub fn age_decrypt(py: Python, sk: &Bound<'_, PyAny>, data: Buffer) -> PyResult<Py<PyBytes>> {
+    let unarmored = unarmor(&data)?;
+    let data = unarmored.as_deref().unwrap_or(&data);
+    let header = parse_header(data)?;
+    if header.stanzas.iter().any(|s| s.kind == "scrypt") && header.stanzas.len() > 1 {
+        return Err(malformed("an scrypt stanza must be the only one"));
+    }
+
+    let file_key = unwrap_file_key(py, sk, &header.stanzas)?;
+    if !ct_eq(&header.mac, &header_mac(&file_key, header.mac_input)) {
+        return Err(DecryptionError::new_err("age header MAC doesn't match"));
+    }
+
+    if header.payload.len() < PAYLOAD_NONCE_LEN + TAG_LEN {
+        return Err(DecryptionError::new_err("age payload is truncated"));
+    }
+    let (nonce, body) = header.payload.split_at(PAYLOAD_NONCE_LEN);
+    let mut stream = payload_stream(&file_key, nonce)?;
+    let mut plaintext = Vec::with_capacity(body.len());
+    let mut chunks = body.chunks(CHUNK_SIZE + TAG_LEN).peekable();
+    while let Some(chunk) = chunks.next() {
+        let last = chunks.peek().is_none();
+        if chunk.len() < TAG_LEN || (last && chunk.len() == TAG_LEN && !plaintext.is_empty()) {
+            return Err(DecryptionError::new_err(
+                "age payload is truncated or malformed",
+            ));
+        }
+        let mut buf = chunk.to_vec();
+        stream.decrypt_in_place(py, &mut buf, last)?;
+        plaintext.extend_from_slice(&buf);
+    }
+    Ok(PyBytes::new_bound(py, &plaintext).unbind())
+}
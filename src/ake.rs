@@ -0,0 +1,408 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// Authenticated key exchange: Falcon-signed ephemeral KEM
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// For two parties who know each other's Falcon public keys and want fresh,
+// mutually authenticated session keys in one round trip:
+//
+//   alice = AkeInitiator(alice_sk, bob_pk)
+//   msg1 = alice.message                       # -> Bob
+//
+//   bob = AkeResponder(bob_sk, alice_pk)
+//   msg2 = bob.respond(msg1)                   # -> Alice
+//   alice.finish(msg2)
+//
+//   send, recv = alice.session_keys()          # bob's are the other way round
+//   alice.session_id == bob.session_id
+//
+// The initiator generates an ephemeral KEM key pair (ML-KEM-768 unless `kem`
+// names another), the responder encapsulates to it, and each signs its
+// message bound to the peer's key (integers big-endian):
+//
+//   msg1 = magic "PQAK" | version 1 | type 1 | name length u8 | KEM name
+//            | ephemeral pk | signature length u16 | signature
+//   sig1 = Sign(initiator sk, label || 1 || SHA-256(responder pk)
+//                             || msg1 up to the signature length)
+//
+//   msg2 = magic "PQAK" | version 1 | type 2 | KEM ciphertext
+//            | signature length u16 | signature
+//   sig2 = Sign(responder sk, label || 2 || SHA-256(initiator pk)
+//                             || SHA-256(msg1) || msg2 up to the signature length)
+//
+//   session_id = SHA-256(label || msg1 || msg2)
+//   key        = HKDF-SHA256(salt = session_id, IKM = shared secret,
+//                            info = label || direction, 32)
+//
+// with one key per direction, "initiator to responder" and "responder to
+// initiator". The ephemeral secret key never leaves the initiator and is
+// dropped once used, so later compromise of either Falcon key doesn't expose
+// past sessions.
+//
+// A message that doesn't verify against the expected peer key raises
+// SignatureVerificationError and leaves the object as it was. As with any
+// two-message exchange, the responder can't tell a replayed msg1 from a fresh
+// one: it only knows the initiator is live once a message under the session
+// keys arrives. Falcon-512 / 1024 are picked by key length. Neither class
+// pickles.
+
+use hkdf::Hkdf;
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
+
+use crate::buffer::Buffer;
+use crate::errors::verification_failed;
+use crate::kem::{find_kem, KemAlgorithm};
+use crate::recover::falcon_public_from_secret;
+use crate::secret::{secret_bytes, SecretBytes};
+use crate::sign::SignatureAlgorithm;
+use crate::signcrypt::falcon_for;
+use crate::util::Reader;
+
+const MAGIC: &[u8; 4] = b"PQAK";
+const VERSION: u8 = 1;
+const LABEL: &[u8] = b"entropic-chaos ake v1\0";
+
+const MSG1: u8 = 1;
+const MSG2: u8 = 2;
+
+const KEY_LEN: usize = 32;
+const INITIATOR_TO_RESPONDER: &[u8] = b"initiator to responder";
+const RESPONDER_TO_INITIATOR: &[u8] = b"responder to initiator";
+
+// ─── Shared ───────────────────────────────────────────────────────────────────
+
+/// A party's long-term Falcon key and what it knows of its peer.
+struct Identity {
+    alg: &'static SignatureAlgorithm,
+    sk: Zeroizing<Vec<u8>>,
+    /// SHA-256 of our own public key, which the peer's signature binds.
+    pk_hash: [u8; 32],
+    peer_alg: &'static SignatureAlgorithm,
+    peer_pk: Vec<u8>,
+}
+
+impl Identity {
+    fn new(py: Python, sk: Buffer, peer_pk: Buffer) -> PyResult<Self> {
+        let alg = falcon_for("secret key", sk.len(), |alg| alg.secret_key_bytes)?;
+        let peer_alg = falcon_for("peer public key", peer_pk.len(), |alg| alg.public_key_bytes)?;
+        let sk = Zeroizing::new(sk.to_vec());
+        let pk = falcon_public_from_secret(py, PyBytes::new_bound(py, &sk).extract()?)?;
+        Ok(Identity {
+            alg,
+            sk,
+            pk_hash: Sha256::digest(pk.as_bytes(py)).into(),
+            peer_alg,
+            peer_pk: peer_pk.to_vec(),
+        })
+    }
+
+    fn peer_pk_hash(&self) -> [u8; 32] {
+        Sha256::digest(&self.peer_pk).into()
+    }
+
+    fn sign(&self, py: Python, signed: &[u8]) -> PyResult<Py<PyBytes>> {
+        let sk = PyBytes::new_bound(py, &self.sk);
+        (self.alg.sign)(py, sk.extract()?, PyBytes::new_bound(py, signed).extract()?)
+    }
+
+    /// Check the peer's signature, raising SignatureVerificationError.
+    fn verify_peer(&self, py: Python, signed: &[u8], sig: &[u8]) -> PyResult<()> {
+        let pk = PyBytes::new_bound(py, &self.peer_pk);
+        let msg = PyBytes::new_bound(py, signed);
+        let sig_obj = PyBytes::new_bound(py, sig);
+        if !(self.peer_alg.verify)(pk.extract()?, msg.extract()?, sig_obj.extract()?)? {
+            return Err(verification_failed(
+                self.peer_alg.name,
+                signed.len(),
+                sig.len(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn header(kind: u8) -> Vec<u8> {
+    let mut header = Vec::with_capacity(MAGIC.len() + 2);
+    header.extend_from_slice(MAGIC);
+    header.push(VERSION);
+    header.push(kind);
+    header
+}
+
+fn read_header(r: &mut Reader, kind: u8) -> PyResult<()> {
+    if r.take(MAGIC.len())? != MAGIC {
+        return Err(PyValueError::new_err("not a key exchange message"));
+    }
+    let version = r.u8()?;
+    if version != VERSION {
+        return Err(PyValueError::new_err(format!(
+            "unsupported key exchange version {}",
+            version
+        )));
+    }
+    if r.u8()? != kind {
+        return Err(PyValueError::new_err(format!(
+            "expected key exchange message {}",
+            kind
+        )));
+    }
+    Ok(())
+}
+
+fn append_signature(py: Python, body: &mut Vec<u8>, sig: &Py<PyBytes>) {
+    let sig = sig.as_bytes(py);
+    body.extend_from_slice(&(sig.len() as u16).to_be_bytes());
+    body.extend_from_slice(sig);
+}
+
+fn msg1_signed_data(responder_pk_hash: &[u8], body: &[u8]) -> Vec<u8> {
+    [LABEL, &[MSG1], responder_pk_hash, body].concat()
+}
+
+fn msg2_signed_data(initiator_pk_hash: &[u8], msg1: &[u8], body: &[u8]) -> Vec<u8> {
+    [
+        LABEL,
+        &[MSG2],
+        initiator_pk_hash,
+        &Sha256::digest(msg1)[..],
+        body,
+    ]
+    .concat()
+}
+
+/// What a completed exchange leaves each side.
+pub(crate) struct Session {
+    pub(crate) id: [u8; 32],
+    pub(crate) send: Zeroizing<[u8; KEY_LEN]>,
+    pub(crate) recv: Zeroizing<[u8; KEY_LEN]>,
+}
+
+impl Session {
+    fn derive(ss: &[u8], msg1: &[u8], msg2: &[u8], initiator: bool) -> Self {
+        let id: [u8; 32] = Sha256::new()
+            .chain_update(LABEL)
+            .chain_update(msg1)
+            .chain_update(msg2)
+            .finalize()
+            .into();
+        let hk = Hkdf::<Sha256>::new(Some(&id), ss);
+        let key = |direction: &[u8]| {
+            let mut key = Zeroizing::new([0u8; KEY_LEN]);
+            hk.expand(&[LABEL, direction].concat(), key.as_mut())
+                .expect("32 bytes is a valid HKDF-SHA256 length");
+            key
+        };
+        let (i2r, r2i) = (key(INITIATOR_TO_RESPONDER), key(RESPONDER_TO_INITIATOR));
+        let (send, recv) = if initiator { (i2r, r2i) } else { (r2i, i2r) };
+        Session { id, send, recv }
+    }
+
+    fn keys(&self, py: Python) -> PyResult<(Py<SecretBytes>, Py<SecretBytes>)> {
+        Ok((
+            secret_bytes(py, &self.send[..])?,
+            secret_bytes(py, &self.recv[..])?,
+        ))
+    }
+}
+
+fn established(session: &Option<Session>) -> PyResult<&Session> {
+    session
+        .as_ref()
+        .ok_or_else(|| PyValueError::new_err("the key exchange isn't complete"))
+}
+
+// ─── AkeInitiator ─────────────────────────────────────────────────────────────
+
+#[pyclass(module = "pqcrypto_bindings")]
+pub struct AkeInitiator {
+    me: Identity,
+    kem: &'static KemAlgorithm,
+    /// The ephemeral KEM secret key, until finish() uses it.
+    esk: Option<Py<SecretBytes>>,
+    msg1: Vec<u8>,
+    session: Option<Session>,
+}
+
+impl AkeInitiator {
+    pub(crate) fn session(&self) -> PyResult<&Session> {
+        established(&self.session)
+    }
+}
+
+#[pymethods]
+impl AkeInitiator {
+    /// Start an exchange with the holder of the Falcon key `peer_pk`,
+    /// authenticated by the Falcon secret key `sk`.
+    #[new]
+    #[pyo3(signature = (sk, peer_pk, kem = "mlkem768"))]
+    fn new(py: Python, sk: Buffer, peer_pk: Buffer, kem: &str) -> PyResult<Self> {
+        let me = Identity::new(py, sk, peer_pk)?;
+        let kem = find_kem(kem)
+            .ok_or_else(|| PyValueError::new_err(format!("unsupported KEM '{}'", kem)))?;
+        let (epk, esk) = (kem.keygen)(py)?;
+
+        let mut msg1 = header(MSG1);
+        msg1.push(kem.name.len() as u8);
+        msg1.extend_from_slice(kem.name.as_bytes());
+        msg1.extend_from_slice(epk.as_bytes(py));
+        let sig = me.sign(py, &msg1_signed_data(&me.peer_pk_hash(), &msg1))?;
+        append_signature(py, &mut msg1, &sig);
+
+        Ok(AkeInitiator {
+            me,
+            kem,
+            esk: Some(esk),
+            msg1,
+            session: None,
+        })
+    }
+
+    /// The first message, to send to the responder.
+    #[getter]
+    fn message<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new_bound(py, &self.msg1)
+    }
+
+    #[getter]
+    fn kem(&self) -> &'static str {
+        self.kem.name
+    }
+
+    /// Check the responder's reply and derive the session keys.
+    fn finish(&mut self, py: Python, msg2: Buffer) -> PyResult<()> {
+        if self.esk.is_none() {
+            return Err(PyValueError::new_err(
+                "the key exchange is already complete",
+            ));
+        }
+        let mut r = Reader::new("key exchange message 2", &msg2);
+        read_header(&mut r, MSG2)?;
+        let ct = r.take(self.kem.ciphertext_bytes)?;
+        let body_len = MAGIC.len() + 2 + ct.len();
+        let sig_len = r.u16()? as usize;
+        let sig = r.take(sig_len)?;
+        r.finish()?;
+        let signed = msg2_signed_data(&self.me.pk_hash, &self.msg1, &msg2[..body_len]);
+        self.me.verify_peer(py, &signed, sig)?;
+
+        let esk = self.esk.take().unwrap();
+        let ct = PyBytes::new_bound(py, ct);
+        let ss = (self.kem.decapsulate)(py, esk.bind(py).extract()?, ct.extract()?)?;
+        let ss: Buffer = ss.bind(py).extract()?;
+        self.session = Some(Session::derive(&ss, &self.msg1, &msg2, true));
+        Ok(())
+    }
+
+    /// SHA-256 over the exchange, the same on both sides; for channel binding.
+    #[getter]
+    fn session_id<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        Ok(PyBytes::new_bound(py, &established(&self.session)?.id))
+    }
+
+    /// (send key, receive key): 32 bytes each, for initiator -> responder
+    /// and responder -> initiator.
+    fn session_keys(&self, py: Python) -> PyResult<(Py<SecretBytes>, Py<SecretBytes>)> {
+        established(&self.session)?.keys(py)
+    }
+
+    fn __reduce__(&self) -> PyResult<()> {
+        Err(PyTypeError::new_err(
+            "cannot pickle 'AkeInitiator': it holds secret key material",
+        ))
+    }
+
+    fn __repr__(&self) -> String {
+        format!("AkeInitiator('{}', '{}')", self.me.alg.name, self.kem.name)
+    }
+}
+
+// ─── AkeResponder ─────────────────────────────────────────────────────────────
+
+#[pyclass(module = "pqcrypto_bindings")]
+pub struct AkeResponder {
+    me: Identity,
+    session: Option<Session>,
+}
+
+impl AkeResponder {
+    pub(crate) fn session(&self) -> PyResult<&Session> {
+        established(&self.session)
+    }
+}
+
+#[pymethods]
+impl AkeResponder {
+    /// Await an exchange from the holder of the Falcon key `peer_pk`,
+    /// authenticated by the Falcon secret key `sk`.
+    #[new]
+    fn new(py: Python, sk: Buffer, peer_pk: Buffer) -> PyResult<Self> {
+        Ok(AkeResponder {
+            me: Identity::new(py, sk, peer_pk)?,
+            session: None,
+        })
+    }
+
+    /// Check the initiator's first message, derive the session keys and
+    /// return the reply to send back. Only callable once.
+    fn respond(&mut self, py: Python, msg1: Buffer) -> PyResult<Py<PyBytes>> {
+        if self.session.is_some() {
+            return Err(PyValueError::new_err(
+                "the key exchange is already complete",
+            ));
+        }
+        let mut r = Reader::new("key exchange message 1", &msg1);
+        read_header(&mut r, MSG1)?;
+        let name_len = r.u8()? as usize;
+        let name = std::str::from_utf8(r.take(name_len)?)
+            .map_err(|_| PyValueError::new_err("key exchange KEM name isn't UTF-8"))?;
+        let kem = find_kem(name)
+            .filter(|kem| kem.name == name)
+            .ok_or_else(|| {
+                PyValueError::new_err(format!("key exchange uses unknown KEM '{}'", name))
+            })?;
+        let epk = r.take(kem.public_key_bytes)?;
+        let body_len = MAGIC.len() + 3 + name_len + epk.len();
+        let sig_len = r.u16()? as usize;
+        let sig = r.take(sig_len)?;
+        r.finish()?;
+        let signed = msg1_signed_data(&self.me.pk_hash, &msg1[..body_len]);
+        self.me.verify_peer(py, &signed, sig)?;
+
+        let epk = PyBytes::new_bound(py, epk);
+        let (ct, ss) = (kem.encapsulate)(py, epk.extract()?)?;
+        let mut msg2 = header(MSG2);
+        msg2.extend_from_slice(ct.as_bytes(py));
+        let signed = msg2_signed_data(&self.me.peer_pk_hash(), &msg1, &msg2);
+        let sig = self.me.sign(py, &signed)?;
+        append_signature(py, &mut msg2, &sig);
+
+        let ss: Buffer = ss.bind(py).extract()?;
+        self.session = Some(Session::derive(&ss, &msg1, &msg2, false));
+        Ok(PyBytes::new_bound(py, &msg2).unbind())
+    }
+
+    /// SHA-256 over the exchange, the same on both sides; for channel binding.
+    #[getter]
+    fn session_id<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        Ok(PyBytes::new_bound(py, &established(&self.session)?.id))
+    }
+
+    /// (send key, receive key): 32 bytes each, for responder -> initiator
+    /// and initiator -> responder.
+    fn session_keys(&self, py: Python) -> PyResult<(Py<SecretBytes>, Py<SecretBytes>)> {
+        established(&self.session)?.keys(py)
+    }
+
+    fn __reduce__(&self) -> PyResult<()> {
+        Err(PyTypeError::new_err(
+            "cannot pickle 'AkeResponder': it holds secret key material",
+        ))
+    }
+
+    fn __repr__(&self) -> String {
+        format!("AkeResponder('{}')", self.me.alg.name)
+    }
+}
@@ -0,0 +1,139 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// Attached signatures: one blob holding the message and its signature
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// For "signed document" cases where shipping the message and a detached
+// signature as two files is a nuisance:
+//
+//   blob = sign_attached(sk, b"release notes")
+//   msg = open_signed(pk, blob)       # raises unless the signature verifies
+//
+// Layout:
+//
+//   magic "PQSD" | version 1 | name length u8 | algorithm name
+//     | signature length u32 | signature | message
+//
+// and what gets signed is
+//
+//   label || name length u8 || algorithm name || message
+//
+// The label keeps an attached signature from passing as a detached one over
+// the same message, and the other way round; signing the name stops a blob
+// being relabelled as another scheme. The algorithm is Falcon-512 / 1024,
+// picked by key length, unless `algorithm` names any Signature() scheme.
+// open_signed() takes the scheme from the blob and checks that `pk` belongs
+// to it, so pass the `algorithm` you expect to rule out any other.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+use crate::buffer::Buffer;
+use crate::errors::{check_length, verification_failed, InvalidLengthError};
+use crate::sign::{find_signature, SignatureAlgorithm};
+use crate::signcrypt::falcon_for;
+use crate::util::Reader;
+
+const MAGIC: &[u8; 4] = b"PQSD";
+const VERSION: u8 = 1;
+const LABEL: &[u8] = b"entropic-chaos attached signature v1\0";
+
+pub(crate) fn algorithm_named(name: &str) -> PyResult<&'static SignatureAlgorithm> {
+    find_signature(name)
+        .ok_or_else(|| PyValueError::new_err(format!("unsupported signature scheme '{}'", name)))
+}
+
+/// What the signer signs.
+fn signed_data(alg: &SignatureAlgorithm, msg: &[u8]) -> Vec<u8> {
+    let mut signed = Vec::with_capacity(LABEL.len() + 1 + alg.name.len() + msg.len());
+    signed.extend_from_slice(LABEL);
+    signed.push(alg.name.len() as u8);
+    signed.extend_from_slice(alg.name.as_bytes());
+    signed.extend_from_slice(msg);
+    signed
+}
+
+/// Sign `msg` and return it with the signature and algorithm attached.
+#[pyfunction]
+#[pyo3(signature = (sk, msg, algorithm = None))]
+pub fn sign_attached(
+    py: Python,
+    sk: Buffer,
+    msg: Buffer,
+    algorithm: Option<&str>,
+) -> PyResult<Py<PyBytes>> {
+    let alg = match algorithm {
+        Some(name) => algorithm_named(name)?,
+        None => falcon_for("secret key", sk.len(), |alg| alg.secret_key_bytes)?,
+    };
+    let signed = PyBytes::new_bound(py, &signed_data(alg, &msg));
+    let sig = (alg.sign)(py, sk, signed.extract()?)?;
+    let sig = sig.as_bytes(py);
+    if u32::try_from(sig.len()).is_err() {
+        return Err(InvalidLengthError::new_err(
+            "signature longer than 2^32 - 1 bytes",
+        ));
+    }
+
+    let mut blob = Vec::with_capacity(MAGIC.len() + 2 + alg.name.len() + 4 + sig.len() + msg.len());
+    blob.extend_from_slice(MAGIC);
+    blob.push(VERSION);
+    blob.push(alg.name.len() as u8);
+    blob.extend_from_slice(alg.name.as_bytes());
+    blob.extend_from_slice(&(sig.len() as u32).to_be_bytes());
+    blob.extend_from_slice(sig);
+    blob.extend_from_slice(&msg);
+    Ok(PyBytes::new_bound(py, &blob).unbind())
+}
+
+/// Verify a sign_attached() blob against `pk` and return the message.
+/// Raises SignatureVerificationError if it doesn't verify, and ValueError if
+/// `algorithm` is given and the blob was signed with another scheme.
+#[pyfunction]
+#[pyo3(signature = (pk, blob, algorithm = None))]
+pub fn open_signed(
+    py: Python,
+    pk: Buffer,
+    blob: Buffer,
+    algorithm: Option<&str>,
+) -> PyResult<Py<PyBytes>> {
+    let mut r = Reader::new("attached-signature blob", &blob);
+    if r.take(MAGIC.len())? != MAGIC {
+        return Err(PyValueError::new_err("not an attached-signature blob"));
+    }
+    let version = r.u8()?;
+    if version != VERSION {
+        return Err(PyValueError::new_err(format!(
+            "unsupported attached-signature version {}",
+            version
+        )));
+    }
+    let name_len = r.u8()? as usize;
+    let name = std::str::from_utf8(r.take(name_len)?)
+        .map_err(|_| PyValueError::new_err("attached-signature algorithm name isn't UTF-8"))?;
+    let alg = algorithm_named(name)?;
+    if let Some(expected) = algorithm {
+        let expected = algorithm_named(expected)?;
+        if !std::ptr::eq(alg, expected) {
+            return Err(PyValueError::new_err(format!(
+                "blob is signed with {}, expected {}",
+                alg.name, expected.name
+            )));
+        }
+    }
+    check_length(
+        &format!("{} public key", alg.name),
+        &pk,
+        alg.public_key_bytes,
+    )?;
+    let sig_len = r.u32()? as usize;
+    let sig = r.take(sig_len)?;
+    let msg = r.rest();
+
+    let signed = PyBytes::new_bound(py, &signed_data(alg, msg));
+    let sig_bytes = PyBytes::new_bound(py, sig);
+    if !(alg.verify)(pk, signed.extract()?, sig_bytes.extract()?)? {
+        return Err(verification_failed(alg.name, msg.len(), sig.len()));
+    }
+    Ok(PyBytes::new_bound(py, msg).unbind())
+}
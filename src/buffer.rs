@@ -0,0 +1,91 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// Buffer-protocol arguments
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// PyO3 only extracts `&[u8]` from `bytes`. Every byte-string argument is taken
+// as a `Buffer` instead, which borrows the memory of any C-contiguous object
+// (bytes, bytearray, memoryview, numpy arrays, mmap, ...) without copying it,
+// so signing a multi-gigabyte mmap doesn't duplicate it first.
+//
+// The view is held for as long as the `Buffer` lives, which stops the exporter
+// from resizing or freeing the memory underneath us (a bytearray refuses to
+// resize, an mmap refuses to close). We never release the GIL while holding a
+// view, so no Python code can write to it mid-call either.
+
+use std::mem::MaybeUninit;
+use std::ops::Deref;
+use std::ptr::NonNull;
+
+use pyo3::ffi;
+use pyo3::prelude::*;
+
+pub(crate) struct Buffer {
+    // Heap-allocated so the Py_buffer keeps its address: exporters may keep pointers
+    // into it until it is released. None for `Buffer::empty()`.
+    view: Option<NonNull<ffi::Py_buffer>>,
+}
+
+impl Buffer {
+    /// A zero-length buffer, for `b""` argument defaults.
+    pub(crate) const fn empty() -> Self {
+        Buffer { view: None }
+    }
+
+    /// A buffer over a fresh `bytes` copy of `bytes`, for calling the Python
+    /// API from Rust tests.
+    #[cfg(test)]
+    pub(crate) fn from_bytes(py: Python, bytes: &[u8]) -> Self {
+        pyo3::types::PyBytes::new_bound(py, bytes)
+            .extract()
+            .unwrap()
+    }
+}
+
+impl<'py> FromPyObject<'py> for Buffer {
+    fn extract_bound(obj: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let view = Box::into_raw(Box::new(MaybeUninit::<ffi::Py_buffer>::uninit()));
+        // PyBUF_SIMPLE: plain bytes, no format or shape, must be C-contiguous.
+        // Python raises TypeError for non-buffers and BufferError for
+        // non-contiguous views.
+        let rc = unsafe { ffi::PyObject_GetBuffer(obj.as_ptr(), view.cast(), ffi::PyBUF_SIMPLE) };
+        if rc == -1 {
+            // SAFETY: allocated just above and never initialised.
+            drop(unsafe { Box::from_raw(view) });
+            return Err(PyErr::fetch(obj.py()));
+        }
+
+        Ok(Buffer {
+            view: NonNull::new(view.cast()),
+        })
+    }
+}
+
+impl Deref for Buffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        let Some(view) = self.view else {
+            return &[];
+        };
+        // SAFETY: the view stays valid until Drop, PyBUF_SIMPLE guarantees
+        // `len` contiguous bytes at `buf`, and buf may only be null when empty.
+        unsafe {
+            let view = view.as_ref();
+            if view.buf.is_null() || view.len <= 0 {
+                return &[];
+            }
+            std::slice::from_raw_parts(view.buf as *const u8, view.len as usize)
+        }
+    }
+}
+
+impl Drop for Buffer {
+    fn drop(&mut self) {
+        if let Some(view) = self.view.take() {
+            Python::with_gil(|_| unsafe {
+                ffi::PyBuffer_Release(view.as_ptr());
+                drop(Box::from_raw(view.as_ptr()));
+            });
+        }
+    }
+}
@@ -0,0 +1,380 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// Deterministic CBOR
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// The encoding under COSE (cose.rs), and for IoT payloads such as CWT claims:
+//
+//   data = cbor_encode({1: "issuer", 4: 1700000000, -70000: b"..."})
+//   claims = cbor_decode(data)
+//
+// Items are encoded with RFC 8949's core deterministic encoding (section
+// 4.2.1): integers, lengths and tags in their shortest form, floats in the
+// shortest of half / single / double precision that holds the value exactly
+// (NaN as the half-precision 0x7e00), definite lengths only, and map entries
+// sorted bytewise by their encoded keys. Equal values therefore always encode
+// to the same bytes, which is what signing over CBOR needs.
+//
+// Python values map to CBOR as None / bool / int / float / str / bytes
+// (bytearray too) / list or tuple / dict, integers limited to CBOR's range,
+// -2**64 to 2**64 - 1. Decoding gives the same types back, lists for arrays.
+// It accepts non-shortest forms from other encoders, but refuses
+// indefinite-length items, duplicate map keys, simple values other than
+// false / true / null, more than 64 levels of nesting and trailing bytes.
+// Tags are only handled inside this package (COSE's message tags); in Python
+// values they raise ValueError.
+
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyByteArray, PyBytes, PyDict, PyFloat, PyList, PyString, PyTuple};
+
+use crate::buffer::Buffer;
+use crate::util::Reader;
+
+const MAX_DEPTH: usize = 64;
+
+/// A CBOR data item. Maps keep their entries in insertion order; encode()
+/// sorts them.
+#[derive(Clone, PartialEq)]
+pub(crate) enum Value {
+    /// Always within -2**64 ..= 2**64 - 1.
+    Int(i128),
+    Bytes(Vec<u8>),
+    Text(String),
+    Array(Vec<Value>),
+    Map(Vec<(Value, Value)>),
+    Tag(u64, Box<Value>),
+    Bool(bool),
+    Null,
+    Float(f64),
+}
+
+fn malformed(what: &str) -> PyErr {
+    PyValueError::new_err(format!("malformed CBOR: {}", what))
+}
+
+// ─── Encoding ─────────────────────────────────────────────────────────────────
+
+fn head(out: &mut Vec<u8>, major: u8, n: u64) {
+    let major = major << 5;
+    if n < 24 {
+        out.push(major | n as u8);
+    } else if n <= u8::MAX as u64 {
+        out.extend_from_slice(&[major | 24, n as u8]);
+    } else if n <= u16::MAX as u64 {
+        out.push(major | 25);
+        out.extend_from_slice(&(n as u16).to_be_bytes());
+    } else if n <= u32::MAX as u64 {
+        out.push(major | 26);
+        out.extend_from_slice(&(n as u32).to_be_bytes());
+    } else {
+        out.push(major | 27);
+        out.extend_from_slice(&n.to_be_bytes());
+    }
+}
+
+/// `f` as a half-precision float, if that holds it exactly.
+fn to_half(f: f64) -> Option<u16> {
+    let single = f as f32;
+    if single as f64 != f {
+        return None;
+    }
+    let bits = single.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7f_ffff;
+    if exponent == 0xff {
+        return Some(sign | 0x7c00); // infinity; NaN is handled by the caller
+    }
+    if exponent == 0 {
+        // Zero, or a single-precision subnormal, far below half's range.
+        return (mantissa == 0).then_some(sign);
+    }
+    let e = exponent - 127;
+    if !(-24..=15).contains(&e) {
+        return None;
+    }
+    if e >= -14 {
+        if mantissa & 0x1fff != 0 {
+            return None;
+        }
+        return Some(sign | (((e + 15) as u16) << 10) | (mantissa >> 13) as u16);
+    }
+    // Subnormal half: significand * 2**(e - 23) as a multiple of 2**-24.
+    let significand = mantissa | 0x80_0000;
+    let shift = -(e + 1) as u32;
+    if significand & ((1 << shift) - 1) != 0 {
+        return None;
+    }
+    Some(sign | (significand >> shift) as u16)
+}
+
+fn from_half(h: u16) -> f64 {
+    let exponent = (h >> 10) & 0x1f;
+    let mantissa = (h & 0x3ff) as f64;
+    let value = match exponent {
+        0 => mantissa * 2f64.powi(-24),
+        31 if mantissa == 0.0 => f64::INFINITY,
+        31 => f64::NAN,
+        _ => (mantissa + 1024.0) * 2f64.powi(exponent as i32 - 25),
+    };
+    if h & 0x8000 != 0 {
+        -value
+    } else {
+        value
+    }
+}
+
+impl Value {
+    pub(crate) fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.encode_into(&mut out);
+        out
+    }
+
+    fn encode_into(&self, out: &mut Vec<u8>) {
+        match self {
+            Value::Int(n) if *n >= 0 => head(out, 0, *n as u64),
+            Value::Int(n) => head(out, 1, (-1 - *n) as u64),
+            Value::Bytes(bytes) => {
+                head(out, 2, bytes.len() as u64);
+                out.extend_from_slice(bytes);
+            }
+            Value::Text(text) => {
+                head(out, 3, text.len() as u64);
+                out.extend_from_slice(text.as_bytes());
+            }
+            Value::Array(items) => {
+                head(out, 4, items.len() as u64);
+                for item in items {
+                    item.encode_into(out);
+                }
+            }
+            Value::Map(entries) => {
+                let mut encoded: Vec<(Vec<u8>, Vec<u8>)> = entries
+                    .iter()
+                    .map(|(key, value)| (key.encode(), value.encode()))
+                    .collect();
+                encoded.sort();
+                head(out, 5, encoded.len() as u64);
+                for (key, value) in encoded {
+                    out.extend_from_slice(&key);
+                    out.extend_from_slice(&value);
+                }
+            }
+            Value::Tag(tag, item) => {
+                head(out, 6, *tag);
+                item.encode_into(out);
+            }
+            Value::Bool(false) => out.push(0xf4),
+            Value::Bool(true) => out.push(0xf5),
+            Value::Null => out.push(0xf6),
+            Value::Float(f) if f.is_nan() => out.extend_from_slice(&[0xf9, 0x7e, 0x00]),
+            Value::Float(f) => {
+                if let Some(half) = to_half(*f) {
+                    out.push(0xf9);
+                    out.extend_from_slice(&half.to_be_bytes());
+                } else if (*f as f32) as f64 == *f {
+                    out.push(0xfa);
+                    out.extend_from_slice(&(*f as f32).to_be_bytes());
+                } else {
+                    out.push(0xfb);
+                    out.extend_from_slice(&f.to_be_bytes());
+                }
+            }
+        }
+    }
+
+    /// The value under `key`, if this is a map that has one.
+    pub(crate) fn get(&self, key: &Value) -> Option<&Value> {
+        match self {
+            Value::Map(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+}
+
+// ─── Decoding ─────────────────────────────────────────────────────────────────
+
+/// Decode exactly one item from `data`.
+pub(crate) fn decode(data: &[u8]) -> PyResult<Value> {
+    let mut reader = Reader::new("CBOR item", data);
+    let value = decode_item(&mut reader, 0)?;
+    reader.finish()?;
+    Ok(value)
+}
+
+fn argument(reader: &mut Reader, info: u8) -> PyResult<u64> {
+    match info {
+        0..=23 => Ok(info as u64),
+        24 => Ok(reader.u8()? as u64),
+        25 => Ok(reader.u16()? as u64),
+        26 => Ok(reader.u32()? as u64),
+        27 => reader.u64(),
+        31 => Err(malformed("indefinite-length items aren't supported")),
+        _ => Err(malformed("reserved additional information value")),
+    }
+}
+
+fn length(n: u64) -> PyResult<usize> {
+    usize::try_from(n).map_err(|_| malformed("length too large"))
+}
+
+fn decode_item(reader: &mut Reader, depth: usize) -> PyResult<Value> {
+    if depth > MAX_DEPTH {
+        return Err(malformed("nested too deeply"));
+    }
+    let initial = reader.u8()?;
+    let (major, info) = (initial >> 5, initial & 0x1f);
+    if major == 7 {
+        return match info {
+            20 => Ok(Value::Bool(false)),
+            21 => Ok(Value::Bool(true)),
+            22 => Ok(Value::Null),
+            25 => Ok(Value::Float(from_half(reader.u16()?))),
+            26 => Ok(Value::Float(f32::from_bits(reader.u32()?) as f64)),
+            27 => Ok(Value::Float(f64::from_bits(reader.u64()?))),
+            31 => Err(malformed("unexpected break")),
+            _ => Err(malformed("unsupported simple value")),
+        };
+    }
+    let n = argument(reader, info)?;
+    Ok(match major {
+        0 => Value::Int(n as i128),
+        1 => Value::Int(-1 - n as i128),
+        2 => Value::Bytes(reader.take(length(n)?)?.to_vec()),
+        3 => Value::Text(
+            String::from_utf8(reader.take(length(n)?)?.to_vec())
+                .map_err(|_| malformed("text string isn't UTF-8"))?,
+        ),
+        4 => {
+            let mut items = Vec::new();
+            for _ in 0..n {
+                items.push(decode_item(reader, depth + 1)?);
+            }
+            Value::Array(items)
+        }
+        5 => {
+            let mut entries: Vec<(Value, Value)> = Vec::new();
+            for _ in 0..n {
+                let key = decode_item(reader, depth + 1)?;
+                if entries.iter().any(|(k, _)| *k == key) {
+                    return Err(malformed("duplicate map key"));
+                }
+                let value = decode_item(reader, depth + 1)?;
+                entries.push((key, value));
+            }
+            Value::Map(entries)
+        }
+        _ => Value::Tag(n, Box::new(decode_item(reader, depth + 1)?)),
+    })
+}
+
+// ─── Python values ────────────────────────────────────────────────────────────
+
+pub(crate) fn from_py(value: &Bound<'_, PyAny>) -> PyResult<Value> {
+    from_py_at(value, 0)
+}
+
+fn from_py_at(value: &Bound<'_, PyAny>, depth: usize) -> PyResult<Value> {
+    if depth > MAX_DEPTH {
+        return Err(PyValueError::new_err("value is nested too deeply for CBOR"));
+    }
+    if value.is_none() {
+        return Ok(Value::Null);
+    }
+    if let Ok(b) = value.downcast::<pyo3::types::PyBool>() {
+        return Ok(Value::Bool(b.is_true()));
+    }
+    if value.is_instance_of::<pyo3::types::PyLong>() {
+        let n: i128 = value
+            .extract()
+            .map_err(|_| PyValueError::new_err("integer out of CBOR's range"))?;
+        if !(-(1i128 << 64)..(1i128 << 64)).contains(&n) {
+            return Err(PyValueError::new_err("integer out of CBOR's range"));
+        }
+        return Ok(Value::Int(n));
+    }
+    if let Ok(f) = value.downcast::<PyFloat>() {
+        return Ok(Value::Float(f.value()));
+    }
+    if let Ok(s) = value.downcast::<PyString>() {
+        return Ok(Value::Text(s.to_str()?.to_owned()));
+    }
+    if let Ok(b) = value.downcast::<PyBytes>() {
+        return Ok(Value::Bytes(b.as_bytes().to_vec()));
+    }
+    if let Ok(b) = value.downcast::<PyByteArray>() {
+        return Ok(Value::Bytes(b.to_vec()));
+    }
+    if value.is_instance_of::<PyList>() || value.is_instance_of::<PyTuple>() {
+        return value
+            .iter()?
+            .map(|item| from_py_at(&item?, depth + 1))
+            .collect::<PyResult<_>>()
+            .map(Value::Array);
+    }
+    if let Ok(dict) = value.downcast::<PyDict>() {
+        let mut entries: Vec<(Value, Value)> = Vec::with_capacity(dict.len());
+        for (key, item) in dict.iter() {
+            let key = from_py_at(&key, depth + 1)?;
+            if entries.iter().any(|(k, _)| *k == key) {
+                return Err(PyValueError::new_err(
+                    "dict has keys that are equal in CBOR",
+                ));
+            }
+            entries.push((key, from_py_at(&item, depth + 1)?));
+        }
+        return Ok(Value::Map(entries));
+    }
+    Err(PyTypeError::new_err(format!(
+        "can't encode {} as CBOR",
+        value.get_type().name()?
+    )))
+}
+
+pub(crate) fn to_py(py: Python, value: &Value) -> PyResult<PyObject> {
+    Ok(match value {
+        Value::Int(n) => n.into_py(py),
+        Value::Bytes(bytes) => PyBytes::new_bound(py, bytes).into_py(py),
+        Value::Text(text) => text.into_py(py),
+        Value::Array(items) => {
+            let list = PyList::empty_bound(py);
+            for item in items {
+                list.append(to_py(py, item)?)?;
+            }
+            list.into_py(py)
+        }
+        Value::Map(entries) => {
+            let dict = PyDict::new_bound(py);
+            for (key, item) in entries {
+                dict.set_item(to_py(py, key)?, to_py(py, item)?)?;
+            }
+            dict.into_py(py)
+        }
+        Value::Tag(tag, _) => {
+            return Err(PyValueError::new_err(format!(
+                "CBOR tag {} isn't supported",
+                tag
+            )))
+        }
+        Value::Bool(b) => b.into_py(py),
+        Value::Null => py.None(),
+        Value::Float(f) => f.into_py(py),
+    })
+}
+
+// ─── Python API ───────────────────────────────────────────────────────────────
+
+/// Encode a Python value as deterministic CBOR.
+// stub: value: Any
+#[pyfunction]
+pub fn cbor_encode(py: Python, value: &Bound<'_, PyAny>) -> PyResult<Py<PyBytes>> {
+    Ok(PyBytes::new_bound(py, &from_py(value)?.encode()).unbind())
+}
+
+/// Decode one CBOR item into a Python value.
+// stub: -> Any
+#[pyfunction]
+pub fn cbor_decode(py: Python, data: Buffer) -> PyResult<PyObject> {
+    to_py(py, &decode(&data)?)
+}
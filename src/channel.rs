@@ -0,0 +1,547 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// SecureChannel: encrypted transport over AKE session keys
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// For services that want an encrypted, authenticated message stream once the
+// key exchange is done:
+//
+//   chan = SecureChannel.from_handshake(alice)      # AkeInitiator / NoiseHandshake ...
+//   frame = chan.encrypt(b"hello")                  # -> peer
+//   msg = peer_chan.decrypt(frame)
+//
+// or SecureChannel(send_key, recv_key) with keys from anywhere else. Each
+// direction has its own key and message counter; a frame is
+//
+//   seq u64 (big-endian) | ciphertext | tag
+//
+// sealed under nonce = seq, big-endian in the last 8 bytes of an otherwise
+// zero nonce, with seq || aad as associated data. Frames must be decrypted in
+// the order they were encrypted, as over TCP: a replayed, reordered, dropped
+// or modified frame raises DecryptionError and leaves the channel as it was.
+//
+// Each direction rekeys itself once it has carried `rekey_messages` messages
+// or `rekey_bytes` plaintext bytes under the current key:
+//
+//   key' = HKDF-SHA256(salt = "", IKM = key, info = label, 32)
+//
+// and the old key is wiped, so a key compromised later doesn't expose what
+// came before the last rekey. Both ends count the same messages, so they
+// switch at the same frame without any signalling, as long as they are
+// built with the same limits. Channels don't pickle.
+//
+// Over datagrams, pass `replay_window` > 0 to both ends. Frames may then
+// arrive in any order or not at all: one is accepted if its seq hasn't been
+// seen and is within `replay_window` of the highest seq accepted, tracked in
+// a bitmap as in RFC 6479, and raises DecryptionError if it is a duplicate
+// or too old. Rekeying then goes by message count alone, every
+// `rekey_messages` seqs, since the receiver can't count the bytes of frames
+// it never got; `rekey_bytes` is ignored. The receiver keeps the previous
+// key for stragglers from before the last rekey, so frames older than that
+// are rejected whatever the window, as are frames more than
+// MAX_REKEY_SKIP rekeys ahead.
+//
+// Once a channel is up, the server can issue_ticket() over it and the
+// client accept_ticket(), letting the client resume later with one round
+// trip and no public-key operations; see resumption.rs.
+
+use hkdf::Hkdf;
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use sha2::Sha256;
+use zeroize::Zeroizing;
+
+use crate::aead::{Aead, KEY_LEN, TAG_LEN};
+use crate::ake::{AkeInitiator, AkeResponder, Session};
+use crate::buffer::Buffer;
+use crate::errors::{check_length, DecryptionError, NonceReuseError};
+use crate::kemtls::KemTlsHandshake;
+use crate::metadata::now as current_time;
+use crate::noise::NoiseHandshake;
+use crate::pake::{PakeInitiator, PakeResponder};
+use crate::resumption::{
+    issue_ticket, resumption_secret, ResumeInitiator, ResumeResponder, ResumptionTicket,
+    DEFAULT_LIFETIME,
+};
+use crate::util::Reader;
+
+const REKEY_LABEL: &[u8] = b"entropic-chaos secure channel rekey v1\0";
+
+const DEFAULT_REKEY_MESSAGES: u64 = 1 << 20;
+const DEFAULT_REKEY_BYTES: u64 = 1 << 30;
+
+const SEQ_LEN: usize = 8;
+
+const MAX_REPLAY_WINDOW: u64 = 1 << 20;
+const MAX_REKEY_SKIP: u64 = 1024;
+
+type Key = Zeroizing<[u8; KEY_LEN]>;
+
+fn next_key(key: &[u8]) -> Key {
+    let mut next = Zeroizing::new([0u8; KEY_LEN]);
+    Hkdf::<Sha256>::new(None, key)
+        .expand(REKEY_LABEL, next.as_mut())
+        .expect("32 bytes is a valid HKDF-SHA256 length");
+    next
+}
+
+/// One direction of the channel.
+struct Direction {
+    key: Zeroizing<[u8; KEY_LEN]>,
+    seq: u64,
+    epoch_messages: u64,
+    epoch_bytes: u64,
+    rekeys: u64,
+}
+
+impl Direction {
+    fn new(key: &[u8]) -> Self {
+        let mut stored = Zeroizing::new([0u8; KEY_LEN]);
+        stored.copy_from_slice(key);
+        Direction {
+            key: stored,
+            seq: 0,
+            epoch_messages: 0,
+            epoch_bytes: 0,
+            rekeys: 0,
+        }
+    }
+
+    /// Switch to the next key if the current one has reached its limits.
+    fn rekey_if_due(&mut self, limits: &Limits) {
+        if self.epoch_messages < limits.messages
+            && (self.epoch_bytes < limits.bytes || limits.windowed)
+        {
+            return;
+        }
+        self.key = next_key(&self.key[..]);
+        self.epoch_messages = 0;
+        self.epoch_bytes = 0;
+        self.rekeys += 1;
+    }
+
+    fn nonce(&self, aead: Aead) -> Vec<u8> {
+        seq_nonce(aead, self.seq)
+    }
+
+    fn advance(&mut self, len: usize) {
+        self.seq += 1;
+        self.epoch_messages += 1;
+        self.epoch_bytes = self.epoch_bytes.saturating_add(len as u64);
+    }
+}
+
+fn seq_nonce(aead: Aead, seq: u64) -> Vec<u8> {
+    let mut nonce = vec![0u8; aead.nonce_len()];
+    let start = nonce.len() - SEQ_LEN;
+    nonce[start..].copy_from_slice(&seq.to_be_bytes());
+    nonce
+}
+
+struct Limits {
+    messages: u64,
+    bytes: u64,
+    /// Rekey on message count only, as both ends do with a replay window.
+    windowed: bool,
+}
+
+/// Receive state with a replay window: which recent seqs have been seen,
+/// and the keys for the current and previous rekey epochs.
+struct ReplayWindow {
+    size: u64,
+    /// One past the highest seq accepted; 0 before the first.
+    top: u64,
+    /// Bit seq % (64 * bits.len()) is set once seq is accepted. The extra
+    /// word lets whole words be cleared as the window slides.
+    bits: Vec<u64>,
+    epoch: u64,
+    key: Key,
+    previous: Option<Key>,
+    accepted: u64,
+    rekeys: u64,
+}
+
+impl ReplayWindow {
+    fn new(size: u64, key: &[u8]) -> Self {
+        let mut stored = Zeroizing::new([0u8; KEY_LEN]);
+        stored.copy_from_slice(key);
+        ReplayWindow {
+            size,
+            top: 0,
+            bits: vec![0; (size as usize).div_ceil(64) + 1],
+            epoch: 0,
+            key: stored,
+            previous: None,
+            accepted: 0,
+            rekeys: 0,
+        }
+    }
+
+    fn bit(&self, seq: u64) -> (usize, u64) {
+        let index = seq % (64 * self.bits.len() as u64);
+        ((index / 64) as usize, 1 << (index % 64))
+    }
+
+    fn check(&self, seq: u64) -> PyResult<()> {
+        if seq >= self.top {
+            return Ok(());
+        }
+        if self.top - seq > self.size {
+            return Err(DecryptionError::new_err(format!(
+                "SecureChannel message {} is older than the replay window",
+                seq
+            )));
+        }
+        let (word, mask) = self.bit(seq);
+        if self.bits[word] & mask != 0 {
+            return Err(DecryptionError::new_err(format!(
+                "SecureChannel message {} was already received",
+                seq
+            )));
+        }
+        Ok(())
+    }
+
+    fn mark(&mut self, seq: u64) {
+        if seq >= self.top {
+            let words = self.bits.len() as u64;
+            let from = if self.top == 0 {
+                seq / 64
+            } else {
+                (self.top - 1) / 64 + 1
+            };
+            for block in from..=(seq / 64).min(from + words - 1) {
+                self.bits[(block % words) as usize] = 0;
+            }
+            self.top = seq + 1;
+        }
+        let (word, mask) = self.bit(seq);
+        self.bits[word] |= mask;
+        self.accepted += 1;
+    }
+
+    /// The keys for `epoch` and the one before it, without changing state.
+    fn keys_for(&self, epoch: u64) -> PyResult<(Key, Option<Key>)> {
+        if epoch == self.epoch {
+            return Ok((self.key.clone(), self.previous.clone()));
+        }
+        if epoch + 1 == self.epoch {
+            if let Some(previous) = &self.previous {
+                return Ok((previous.clone(), None));
+            }
+        }
+        if epoch < self.epoch {
+            return Err(DecryptionError::new_err(
+                "SecureChannel message is from before the previous rekey",
+            ));
+        }
+        if epoch - self.epoch > MAX_REKEY_SKIP {
+            return Err(DecryptionError::new_err(format!(
+                "SecureChannel message is more than {} rekeys ahead",
+                MAX_REKEY_SKIP
+            )));
+        }
+        let mut previous = self.key.clone();
+        let mut key = next_key(&previous[..]);
+        for _ in self.epoch + 1..epoch {
+            previous = key;
+            key = next_key(&previous[..]);
+        }
+        Ok((key, Some(previous)))
+    }
+}
+
+fn associated_data(seq: &[u8], aad: Option<Buffer>) -> Vec<u8> {
+    [seq, aad.as_deref().unwrap_or_default()].concat()
+}
+
+#[pyclass(module = "pqcrypto_bindings")]
+pub struct SecureChannel {
+    aead: Aead,
+    limits: Limits,
+    send: Direction,
+    recv: Direction,
+    window: Option<ReplayWindow>,
+    resumption: Zeroizing<[u8; KEY_LEN]>,
+}
+
+impl SecureChannel {
+    fn from_keys(
+        send_key: &[u8],
+        recv_key: &[u8],
+        cipher: &str,
+        rekey_messages: u64,
+        rekey_bytes: u64,
+        replay_window: u64,
+    ) -> PyResult<Self> {
+        let aead = Aead::from_name(cipher)?;
+        check_length("SecureChannel send key", send_key, KEY_LEN)?;
+        check_length("SecureChannel receive key", recv_key, KEY_LEN)?;
+        if rekey_messages == 0 || rekey_bytes == 0 {
+            return Err(PyValueError::new_err(
+                "rekey_messages and rekey_bytes must be at least 1",
+            ));
+        }
+        if replay_window > MAX_REPLAY_WINDOW {
+            return Err(PyValueError::new_err(format!(
+                "replay_window must be at most {}",
+                MAX_REPLAY_WINDOW
+            )));
+        }
+        Ok(SecureChannel {
+            aead,
+            limits: Limits {
+                messages: rekey_messages,
+                bytes: rekey_bytes,
+                windowed: replay_window > 0,
+            },
+            send: Direction::new(send_key),
+            recv: Direction::new(recv_key),
+            window: (replay_window > 0).then(|| ReplayWindow::new(replay_window, recv_key)),
+            resumption: resumption_secret(send_key, recv_key),
+        })
+    }
+}
+
+#[pymethods]
+impl SecureChannel {
+    /// A channel sending under `send_key` and receiving under `recv_key`;
+    /// the peer's channel has them the other way round. A `replay_window`
+    /// of 0 requires frames in order; more accepts them out of order.
+    // stub: cipher: Literal["chacha20poly1305", "aes256gcm", "xchacha20poly1305"]
+    #[new]
+    #[pyo3(signature = (
+        send_key,
+        recv_key,
+        cipher = "chacha20poly1305",
+        rekey_messages = DEFAULT_REKEY_MESSAGES,
+        rekey_bytes = DEFAULT_REKEY_BYTES,
+        replay_window = 0,
+    ))]
+    fn new(
+        send_key: Buffer,
+        recv_key: Buffer,
+        cipher: &str,
+        rekey_messages: u64,
+        rekey_bytes: u64,
+        replay_window: u64,
+    ) -> PyResult<Self> {
+        Self::from_keys(
+            &send_key,
+            &recv_key,
+            cipher,
+            rekey_messages,
+            rekey_bytes,
+            replay_window,
+        )
+    }
+
+    /// A channel over the session keys of a completed AkeInitiator,
+    /// AkeResponder, NoiseHandshake, KemTlsHandshake, ResumeInitiator,
+    /// ResumeResponder, PakeInitiator or PakeResponder.
+    // stub: cipher: Literal["chacha20poly1305", "aes256gcm", "xchacha20poly1305"]
+    // stub: handshake: AkeInitiator | AkeResponder | NoiseHandshake | KemTlsHandshake
+    // stub:     | ResumeInitiator | ResumeResponder | PakeInitiator | PakeResponder
+    #[staticmethod]
+    #[pyo3(signature = (
+        handshake,
+        cipher = "chacha20poly1305",
+        rekey_messages = DEFAULT_REKEY_MESSAGES,
+        rekey_bytes = DEFAULT_REKEY_BYTES,
+        replay_window = 0,
+    ))]
+    fn from_handshake(
+        handshake: &Bound<'_, PyAny>,
+        cipher: &str,
+        rekey_messages: u64,
+        rekey_bytes: u64,
+        replay_window: u64,
+    ) -> PyResult<Self> {
+        let channel = |session: &Session| {
+            Self::from_keys(
+                &session.send[..],
+                &session.recv[..],
+                cipher,
+                rekey_messages,
+                rekey_bytes,
+                replay_window,
+            )
+        };
+        if let Ok(initiator) = handshake.downcast::<AkeInitiator>() {
+            channel(initiator.borrow().session()?)
+        } else if let Ok(responder) = handshake.downcast::<AkeResponder>() {
+            channel(responder.borrow().session()?)
+        } else if let Ok(noise) = handshake.downcast::<NoiseHandshake>() {
+            channel(noise.borrow().session()?)
+        } else if let Ok(kemtls) = handshake.downcast::<KemTlsHandshake>() {
+            channel(kemtls.borrow().session()?)
+        } else if let Ok(resume) = handshake.downcast::<ResumeInitiator>() {
+            channel(resume.borrow().session()?)
+        } else if let Ok(resume) = handshake.downcast::<ResumeResponder>() {
+            channel(resume.borrow().session()?)
+        } else if let Ok(pake) = handshake.downcast::<PakeInitiator>() {
+            channel(pake.borrow().session()?)
+        } else if let Ok(pake) = handshake.downcast::<PakeResponder>() {
+            channel(pake.borrow().session()?)
+        } else {
+            Err(PyTypeError::new_err(
+                "handshake must be an AkeInitiator, AkeResponder, NoiseHandshake, \
+                 KemTlsHandshake, ResumeInitiator, ResumeResponder, PakeInitiator or \
+                 PakeResponder",
+            ))
+        }
+    }
+
+    #[getter]
+    fn cipher(&self) -> &'static str {
+        self.aead.name()
+    }
+
+    /// Messages encrypted so far.
+    #[getter]
+    fn sent(&self) -> u64 {
+        self.send.seq
+    }
+
+    /// Messages decrypted so far.
+    #[getter]
+    fn received(&self) -> u64 {
+        match &self.window {
+            Some(window) => window.accepted,
+            None => self.recv.seq,
+        }
+    }
+
+    /// Rekeys so far, sending and receiving.
+    #[getter]
+    fn rekeys(&self) -> (u64, u64) {
+        match &self.window {
+            Some(window) => (self.send.rekeys, window.rekeys),
+            None => (self.send.rekeys, self.recv.rekeys),
+        }
+    }
+
+    /// The replay window size; 0 if frames must arrive in order.
+    #[getter]
+    fn replay_window(&self) -> u64 {
+        self.window.as_ref().map_or(0, |window| window.size)
+    }
+
+    /// Encrypt the next message. Returns the frame to send.
+    #[pyo3(signature = (plaintext, aad = None))]
+    fn encrypt(
+        &mut self,
+        py: Python,
+        plaintext: Buffer,
+        aad: Option<Buffer>,
+    ) -> PyResult<Py<PyBytes>> {
+        if self.send.seq == u64::MAX {
+            return Err(NonceReuseError::new_err(
+                "SecureChannel has sent its last message; run a new key exchange",
+            ));
+        }
+        self.send.rekey_if_due(&self.limits);
+        let seq = self.send.seq.to_be_bytes();
+        let mut frame = Vec::with_capacity(SEQ_LEN + plaintext.len() + TAG_LEN);
+        frame.extend_from_slice(&plaintext);
+        self.aead.encrypt(
+            &self.send.key[..],
+            &self.send.nonce(self.aead),
+            &associated_data(&seq, aad),
+            &mut frame,
+        )?;
+        frame.splice(0..0, seq);
+        self.send.advance(plaintext.len());
+        Ok(PyBytes::new_bound(py, &frame).unbind())
+    }
+
+    /// Decrypt the next frame from the peer.
+    #[pyo3(signature = (frame, aad = None))]
+    fn decrypt(&mut self, py: Python, frame: Buffer, aad: Option<Buffer>) -> PyResult<Py<PyBytes>> {
+        let mut r = Reader::new("SecureChannel frame", &frame);
+        let seq = r.u64()?;
+        let body = r.rest();
+        if let Some(window) = &mut self.window {
+            window.check(seq)?;
+            let epoch = seq / self.limits.messages;
+            let (key, previous) = window.keys_for(epoch)?;
+            let mut plaintext = body.to_vec();
+            self.aead.decrypt(
+                &key[..],
+                &seq_nonce(self.aead, seq),
+                &associated_data(&seq.to_be_bytes(), aad),
+                &mut plaintext,
+            )?;
+            if epoch > window.epoch {
+                window.rekeys += epoch - window.epoch;
+                window.epoch = epoch;
+                window.key = key;
+                window.previous = previous;
+            }
+            window.mark(seq);
+            return Ok(PyBytes::new_bound(py, &plaintext).unbind());
+        }
+        if seq != self.recv.seq {
+            return Err(DecryptionError::new_err(format!(
+                "expected SecureChannel message {}, got {}: replayed, reordered or dropped",
+                self.recv.seq, seq
+            )));
+        }
+        self.recv.rekey_if_due(&self.limits);
+        let mut plaintext = body.to_vec();
+        self.aead.decrypt(
+            &self.recv.key[..],
+            &self.recv.nonce(self.aead),
+            &associated_data(&seq.to_be_bytes(), aad),
+            &mut plaintext,
+        )?;
+        self.recv.advance(plaintext.len());
+        Ok(PyBytes::new_bound(py, &plaintext).unbind())
+    }
+
+    /// A resumption ticket for the peer, sealed under the server's 32-byte
+    /// `ticket_key` and valid for `lifetime` seconds. Send it over the
+    /// channel; the peer passes it to accept_ticket().
+    #[pyo3(signature = (ticket_key, lifetime = DEFAULT_LIFETIME, now = None))]
+    fn issue_ticket(
+        &self,
+        py: Python,
+        ticket_key: Buffer,
+        lifetime: u32,
+        now: Option<u64>,
+    ) -> PyResult<Py<PyBytes>> {
+        let ticket = issue_ticket(
+            &self.resumption[..],
+            &ticket_key,
+            lifetime,
+            now.unwrap_or_else(current_time),
+        )?;
+        Ok(PyBytes::new_bound(py, &ticket).unbind())
+    }
+
+    /// Take a ticket from the peer's issue_ticket(), to resume with later
+    /// through ResumeInitiator.
+    #[pyo3(signature = (ticket, now = None))]
+    fn accept_ticket(&self, ticket: Buffer, now: Option<u64>) -> PyResult<ResumptionTicket> {
+        ResumptionTicket::accept(
+            &self.resumption[..],
+            &ticket,
+            now.unwrap_or_else(current_time),
+        )
+    }
+
+    fn __reduce__(&self) -> PyResult<()> {
+        Err(PyTypeError::new_err(
+            "cannot pickle 'SecureChannel': it holds session keys and message counters",
+        ))
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "SecureChannel(cipher='{}', sent={}, received={})",
+            self.aead.name(),
+            self.send.seq,
+            self.received()
+        )
+    }
+}
@@ -0,0 +1,158 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// Classical X25519 / Ed25519
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// Not post-quantum. These are here so hybrid constructions (classical + PQ,
+// secure as long as either half holds) can be built from this one extension
+// instead of pulling in a second native crypto dependency.
+//
+//   x25519_keygen()                           -> (public_key, secret_key)   32 + 32 bytes
+//   x25519_dh(sk, peer_pk)                    -> shared secret              32 bytes
+//   ed25519_keygen()                          -> (public_key, secret_key)   32 + 32 bytes
+//   ed25519_sign(sk, msg, context=b"")        -> signature                  64 bytes
+//   ed25519_verify(pk, msg, sig, context=b"") -> bool
+//   ed25519_verify_strict(...)                -> None, or raises SignatureVerificationError
+//
+// Secret keys are the raw 32-byte scalar / seed (RFC 7748, RFC 8032). A
+// context string is bound in the way Falcon's is (sign::context_message), not
+// as RFC 8032's Ed25519ctx, which ed25519-dalek doesn't offer; the empty
+// context is plain Ed25519.
+
+use ed25519_dalek::Signer;
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+use crate::buffer::Buffer;
+use crate::errors::{check_length, InvalidKeyError, InvalidLengthError};
+use crate::secret::{secret_bytes, SecretBytes};
+use zeroize::Zeroizing;
+
+const KEY_LEN: usize = 32;
+
+pub(crate) fn random_key() -> PyResult<Zeroizing<[u8; KEY_LEN]>> {
+    let mut key = Zeroizing::new([0u8; KEY_LEN]);
+    getrandom::fill(key.as_mut()).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+    Ok(key)
+}
+
+fn key_from_bytes(what: &str, bytes: &[u8]) -> PyResult<Zeroizing<[u8; KEY_LEN]>> {
+    check_length(what, bytes, KEY_LEN)?;
+    Ok(Zeroizing::new(bytes.try_into().unwrap()))
+}
+
+// ─── X25519 (RFC 7748) ────────────────────────────────────────────────────────
+
+#[pyfunction]
+pub fn x25519_keygen(py: Python) -> PyResult<(Py<PyBytes>, Py<SecretBytes>)> {
+    let sk = x25519_dalek::StaticSecret::from(*random_key()?);
+    let pk = x25519_dalek::PublicKey::from(&sk);
+
+    Ok((
+        PyBytes::new_bound(py, pk.as_bytes()).unbind(),
+        secret_bytes(py, &Zeroizing::new(sk.to_bytes())[..])?,
+    ))
+}
+
+#[pyfunction]
+pub fn x25519_dh(py: Python, sk_bytes: Buffer, peer_pk_bytes: Buffer) -> PyResult<Py<SecretBytes>> {
+    let sk = x25519_dalek::StaticSecret::from(*key_from_bytes("X25519 secret key", &sk_bytes)?);
+    let peer_pk =
+        x25519_dalek::PublicKey::from(*key_from_bytes("X25519 public key", &peer_pk_bytes)?);
+
+    let ss = sk.diffie_hellman(&peer_pk);
+
+    // A low-order peer key forces the all-zero output; refuse it rather than
+    // feed a known "secret" into a combiner.
+    if !ss.was_contributory() {
+        return Err(InvalidKeyError::new_err(
+            "X25519 peer public key is a low-order point",
+        ));
+    }
+
+    secret_bytes(py, ss.as_bytes())
+}
+
+// ─── Ed25519 (RFC 8032) ───────────────────────────────────────────────────────
+
+const ED25519_SIG_LEN: usize = 64;
+
+pub(crate) const ED25519: crate::sign::SignatureAlgorithm = crate::sign::SignatureAlgorithm {
+    name: "ed25519",
+    public_key_bytes: KEY_LEN,
+    secret_key_bytes: KEY_LEN,
+    signature_bytes: ED25519_SIG_LEN,
+    keygen: ed25519_keygen,
+    sign: |py, sk_bytes, msg| ed25519_sign(py, sk_bytes, msg, Buffer::empty()),
+    verify: |pk_bytes, msg, sig_bytes| ed25519_verify(pk_bytes, msg, sig_bytes, Buffer::empty()),
+};
+
+#[pyfunction]
+pub fn ed25519_keygen(py: Python) -> PyResult<(Py<PyBytes>, Py<SecretBytes>)> {
+    let seed = random_key()?;
+    let sk = ed25519_dalek::SigningKey::from_bytes(&seed);
+    let pk = sk.verifying_key();
+
+    let keys = (
+        PyBytes::new_bound(py, pk.as_bytes()).unbind(),
+        secret_bytes(py, &seed[..])?,
+    );
+    crate::pct::checked_signature_keypair(py, &ED25519, keys)
+}
+
+#[pyfunction]
+#[pyo3(signature = (sk_bytes, msg, context = Buffer::empty()))]
+pub fn ed25519_sign(
+    py: Python,
+    sk_bytes: Buffer,
+    msg: Buffer,
+    context: Buffer,
+) -> PyResult<Py<PyBytes>> {
+    let seed = key_from_bytes("Ed25519 secret key", &sk_bytes)?;
+    let sk = ed25519_dalek::SigningKey::from_bytes(&seed);
+
+    let msg = crate::sign::context_message(&context, &msg)?;
+    let sig = sk.sign(&msg);
+
+    Ok(PyBytes::new_bound(py, &sig.to_bytes()).unbind())
+}
+
+#[pyfunction]
+#[pyo3(signature = (pk_bytes, msg, sig_bytes, context = Buffer::empty()))]
+pub fn ed25519_verify(
+    pk_bytes: Buffer,
+    msg: Buffer,
+    sig_bytes: Buffer,
+    context: Buffer,
+) -> PyResult<bool> {
+    let pk_arr = key_from_bytes("Ed25519 public key", &pk_bytes)?;
+    let pk = ed25519_dalek::VerifyingKey::from_bytes(&pk_arr)
+        .map_err(|e| InvalidKeyError::new_err(e.to_string()))?;
+    let sig = ed25519_dalek::Signature::from_slice(&sig_bytes)
+        .map_err(|e| InvalidLengthError::new_err(e.to_string()))?;
+
+    // Strict: rejects small-order keys and non-canonical encodings, so a
+    // signature verifies the same way here as in any other strict verifier.
+    let msg = crate::sign::context_message(&context, &msg)?;
+    Ok(pk.verify_strict(&msg, &sig).is_ok())
+}
+
+#[pyfunction]
+#[pyo3(signature = (pk_bytes, msg, sig_bytes, context = Buffer::empty()))]
+pub fn ed25519_verify_strict(
+    pk_bytes: Buffer,
+    msg: Buffer,
+    sig_bytes: Buffer,
+    context: Buffer,
+) -> PyResult<()> {
+    let (msg_len, sig_len) = (msg.len(), sig_bytes.len());
+    if ed25519_verify(pk_bytes, msg, sig_bytes, context)? {
+        Ok(())
+    } else {
+        Err(crate::errors::verification_failed(
+            ED25519.name,
+            msg_len,
+            sig_len,
+        ))
+    }
+}
@@ -0,0 +1,486 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// CMS SignedData with Falcon and ML-DSA
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// For pipelines built around PKCS#7 / CMS: S/MIME gateways, document signing,
+// long-term archives:
+//
+//   signed = cms_sign(sk, cert, document, certs=[intermediate])
+//   document = cms_verify(signed, roots=[root])
+//
+//   sig = cms_sign(sk, cert, document, detached=True)
+//   cms_verify(sig, roots=[root], data=document)
+//
+// Messages are RFC 5652 ContentInfo structures of type signedData, DER
+// encoded (PEM input with the RFC 7468 "CMS" label is accepted too), with
+// id-data content and one SignerInfo identified by issuer and serial number.
+// The signed attributes are contentType, signingTime (default now) and
+// messageDigest, a SHA-512 digest of the content as RFC 9882 asks for
+// ML-DSA; the signature covers their DER SET, in pure mode with an empty
+// context for ML-DSA. The signature algorithm identifiers are the ones the
+// certificates use (x509.rs). `cert` (DER or PEM) has to be for the key
+// `sk` signs with and, if it has keyUsage, allow digitalSignature or
+// nonRepudiation; it goes in the message with any `certs`.
+//
+// cms_verify() takes SHA-256, SHA-384 or SHA-512 digests and signer
+// identifiers by issuer and serial or by subjectKeyIdentifier, and requires
+// every SignerInfo to verify. Each signer's certificate is looked up among
+// the certificates in the message, `intermediates` and `roots`, and has to
+// chain to one of `roots` at `time` (default now) as verify_cert_chain()
+// checks it. Content is returned only once all of that holds; a bad
+// signature or digest raises SignatureVerificationError, a signer without a
+// trusted certificate CertificateVerificationError. Messages are parsed as
+// strict DER, so BER from some producers (indefinite lengths, constructed
+// OCTET STRINGs) is refused.
+
+use pkcs8::ObjectIdentifier;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use sha2::{Digest as _, Sha256, Sha384, Sha512};
+
+use crate::buffer::Buffer;
+use crate::errors::{check_length, CertificateVerificationError, SignatureVerificationError};
+use crate::metadata::now;
+use crate::x509::{
+    algorithm_identifier, build_path, der_or_pem, name_string, oid, parse_algorithm,
+    parse_certificate, sequence, time, tlv, unsigned_integer, verify, CertAlgorithm, Certificate,
+    Der, CERTIFICATE_LABEL, CONTEXT_0, INTEGER, OCTET_STRING, SEQUENCE, SET,
+};
+
+const CMS_LABEL: &str = "CMS";
+
+const OID_DATA: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.7.1");
+const OID_SIGNED_DATA: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.7.2");
+const OID_CONTENT_TYPE: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.9.3");
+const OID_MESSAGE_DIGEST: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.9.4");
+const OID_SIGNING_TIME: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.9.5");
+
+const NULL: u8 = 0x05;
+const CONTEXT_1: u8 = 0xa1;
+const SUBJECT_KEY_ID: u8 = 0x80;
+
+// ─── Digests ──────────────────────────────────────────────────────────────────
+
+struct DigestAlgorithm {
+    oid: ObjectIdentifier,
+    hash: fn(&[u8]) -> Vec<u8>,
+}
+
+const SHA512: DigestAlgorithm = DigestAlgorithm {
+    oid: ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.2.3"),
+    hash: |data| Sha512::digest(data).to_vec(),
+};
+
+const DIGEST_ALGORITHMS: &[DigestAlgorithm] = &[
+    DigestAlgorithm {
+        oid: ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.2.1"),
+        hash: |data| Sha256::digest(data).to_vec(),
+    },
+    DigestAlgorithm {
+        oid: ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.2.2"),
+        hash: |data| Sha384::digest(data).to_vec(),
+    },
+    SHA512,
+];
+
+/// A digest AlgorithmIdentifier, with absent or NULL parameters.
+fn parse_digest_algorithm(der: &mut Der) -> PyResult<&'static DigestAlgorithm> {
+    let mut alg = der.enter(SEQUENCE)?;
+    let id = alg.oid()?;
+    if !alg.is_empty() && !alg.expect(NULL)?.is_empty() {
+        return Err(alg.malformed("digest algorithm has parameters"));
+    }
+    alg.finish()?;
+    DIGEST_ALGORITHMS
+        .iter()
+        .find(|d| d.oid == id)
+        .ok_or_else(|| PyValueError::new_err(format!("unsupported digest algorithm OID {}", id)))
+}
+
+// ─── Encoding ─────────────────────────────────────────────────────────────────
+
+/// A DER SET OF: the elements sorted by their encodings.
+fn set_of(mut elements: Vec<Vec<u8>>) -> Vec<u8> {
+    elements.sort();
+    elements.concat()
+}
+
+fn attribute(id: &ObjectIdentifier, value: &[u8]) -> Vec<u8> {
+    sequence(&[&oid(id), &tlv(SET, value)])
+}
+
+// ─── Parsing ──────────────────────────────────────────────────────────────────
+
+enum SignerId<'a> {
+    IssuerAndSerial { issuer: &'a [u8], serial: &'a [u8] },
+    KeyId(&'a [u8]),
+}
+
+struct SignerInfo<'a> {
+    id: SignerId<'a>,
+    digest: &'static DigestAlgorithm,
+    /// The whole [0] IMPLICIT SignedAttributes, if present.
+    signed_attrs: Option<&'a [u8]>,
+    algorithm: &'static CertAlgorithm,
+    signature: &'a [u8],
+}
+
+struct SignedData<'a> {
+    content_type: ObjectIdentifier,
+    content: Option<&'a [u8]>,
+    certificates: Vec<&'a [u8]>,
+    signers: Vec<SignerInfo<'a>>,
+}
+
+fn parse_signer_info<'a>(der: &mut Der<'a>) -> PyResult<SignerInfo<'a>> {
+    let mut si = der.enter(SEQUENCE)?;
+    si.small_integer()?;
+    let id = match si.peek_tag() {
+        Some(SEQUENCE) => {
+            let mut ias = si.enter(SEQUENCE)?;
+            let (_, issuer) = ias.expect_raw(SEQUENCE)?;
+            let serial = ias.expect(INTEGER)?;
+            ias.finish()?;
+            SignerId::IssuerAndSerial { issuer, serial }
+        }
+        Some(SUBJECT_KEY_ID) => SignerId::KeyId(si.expect(SUBJECT_KEY_ID)?),
+        _ => return Err(si.malformed("bad signer identifier")),
+    };
+    let digest = parse_digest_algorithm(&mut si)?;
+    let signed_attrs = if si.peek_tag() == Some(CONTEXT_0) {
+        Some(si.expect_raw(CONTEXT_0)?.1)
+    } else {
+        None
+    };
+    let algorithm = parse_algorithm(&mut si, "CMS signature")?;
+    let signature = si.expect(OCTET_STRING)?;
+    si.optional(CONTEXT_1)?;
+    si.finish()?;
+    Ok(SignerInfo {
+        id,
+        digest,
+        signed_attrs,
+        algorithm,
+        signature,
+    })
+}
+
+fn parse_signed_data(der: &[u8]) -> PyResult<SignedData<'_>> {
+    let mut outer = Der::new("CMS message", der);
+    let mut content_info = outer.enter(SEQUENCE)?;
+    outer.finish()?;
+    if content_info.oid()? != OID_SIGNED_DATA {
+        return Err(content_info.malformed("not SignedData"));
+    }
+    let mut explicit = content_info.enter(CONTEXT_0)?;
+    content_info.finish()?;
+    let mut signed_data = explicit.enter(SEQUENCE)?;
+    explicit.finish()?;
+
+    signed_data.small_integer()?;
+    let mut digests = signed_data.enter(SET)?;
+    while !digests.is_empty() {
+        digests.expect(SEQUENCE)?;
+    }
+    let mut encap = signed_data.enter(SEQUENCE)?;
+    let content_type = encap.oid()?;
+    let content = match encap.optional(CONTEXT_0)? {
+        Some(explicit) => {
+            let mut explicit = encap.nested(explicit);
+            let content = explicit.expect(OCTET_STRING)?;
+            explicit.finish()?;
+            Some(content)
+        }
+        None => None,
+    };
+    encap.finish()?;
+    let mut certificates = Vec::new();
+    if let Some(list) = signed_data.optional(CONTEXT_0)? {
+        let mut list = signed_data.nested(list);
+        while !list.is_empty() {
+            let (_, raw) = list.expect_raw(SEQUENCE)?;
+            certificates.push(raw);
+        }
+    }
+    signed_data.optional(CONTEXT_1)?;
+    let mut infos = signed_data.enter(SET)?;
+    signed_data.finish()?;
+    let mut signers = Vec::new();
+    while !infos.is_empty() {
+        signers.push(parse_signer_info(&mut infos)?);
+    }
+    if signers.is_empty() {
+        return Err(infos.malformed("no SignerInfo"));
+    }
+    Ok(SignedData {
+        content_type,
+        content,
+        certificates,
+        signers,
+    })
+}
+
+struct SignedAttributes<'a> {
+    content_type: ObjectIdentifier,
+    message_digest: &'a [u8],
+}
+
+/// contentType and messageDigest from a [0] IMPLICIT SignedAttributes.
+fn parse_signed_attributes(raw: &[u8]) -> PyResult<SignedAttributes<'_>> {
+    let mut outer = Der::new("CMS signed attributes", raw);
+    let mut attrs = outer.enter(CONTEXT_0)?;
+    outer.finish()?;
+    let (mut content_type, mut message_digest) = (None, None);
+    let mut seen = Vec::new();
+    while !attrs.is_empty() {
+        let mut attr = attrs.enter(SEQUENCE)?;
+        let id = attr.oid()?;
+        let mut values = attr.enter(SET)?;
+        attr.finish()?;
+        if seen.contains(&id) {
+            return Err(attrs.malformed(&format!("attribute {} appears twice", id)));
+        }
+        seen.push(id);
+        if id == OID_CONTENT_TYPE {
+            content_type = Some(values.oid()?);
+            values.finish()?;
+        } else if id == OID_MESSAGE_DIGEST {
+            message_digest = Some(values.expect(OCTET_STRING)?);
+            values.finish()?;
+        }
+    }
+    match (content_type, message_digest) {
+        (Some(content_type), Some(message_digest)) => Ok(SignedAttributes {
+            content_type,
+            message_digest,
+        }),
+        _ => Err(attrs.malformed("contentType or messageDigest missing")),
+    }
+}
+
+// ─── Verification ─────────────────────────────────────────────────────────────
+
+fn find_signer<'c, 'a>(
+    id: &SignerId,
+    candidates: &'c [Certificate<'a>],
+) -> PyResult<Option<&'c Certificate<'a>>> {
+    for cert in candidates {
+        let matches = match *id {
+            SignerId::IssuerAndSerial { issuer, serial } => {
+                cert.issuer == issuer && cert.serial == serial
+            }
+            SignerId::KeyId(key_id) => cert.subject_key_id()? == Some(key_id),
+        };
+        if matches {
+            return Ok(Some(cert));
+        }
+    }
+    Ok(None)
+}
+
+/// Check one SignerInfo's signature over `content` and its certificate's path
+/// to `roots`.
+fn check_signer(
+    py: Python,
+    signed: &SignedData,
+    signer: &SignerInfo,
+    content: &[u8],
+    candidates: &[Certificate],
+    roots: &[Certificate],
+    at: u64,
+) -> PyResult<()> {
+    let cert = find_signer(&signer.id, candidates)?
+        .ok_or_else(|| CertificateVerificationError::new_err("no certificate for a CMS signer"))?;
+    let signer_name = name_string(cert.subject);
+    let message = match signer.signed_attrs {
+        Some(raw) => {
+            let attrs = parse_signed_attributes(raw)?;
+            if attrs.content_type != signed.content_type {
+                return Err(SignatureVerificationError::new_err(format!(
+                    "contentType signed by '{}' isn't the message's",
+                    signer_name
+                )));
+            }
+            if attrs.message_digest != (signer.digest.hash)(content) {
+                return Err(SignatureVerificationError::new_err(format!(
+                    "content doesn't match the messageDigest signed by '{}'",
+                    signer_name
+                )));
+            }
+            // The signature covers the attributes with their SET tag.
+            [&[SET][..], &raw[1..]].concat()
+        }
+        None if signed.content_type == OID_DATA => content.to_vec(),
+        None => {
+            return Err(PyValueError::new_err(
+                "malformed CMS message: signed attributes missing",
+            ))
+        }
+    };
+    if !std::ptr::eq(signer.algorithm, cert.algorithm) {
+        return Err(SignatureVerificationError::new_err(format!(
+            "CMS signature is {}, but the key of '{}' is {}",
+            signer.algorithm.alg.name, signer_name, cert.algorithm.alg.name
+        )));
+    }
+    if !verify(
+        py,
+        cert.algorithm,
+        cert.public_key,
+        &message,
+        signer.signature,
+    )? {
+        return Err(SignatureVerificationError::new_err(format!(
+            "CMS signature by '{}' doesn't verify",
+            signer_name
+        )));
+    }
+    if !cert.may_sign_content()? {
+        return Err(CertificateVerificationError::new_err(format!(
+            "{} has neither digitalSignature nor nonRepudiation key usage",
+            cert.describe()
+        )));
+    }
+    build_path(py, cert, candidates, roots, at)?;
+    Ok(())
+}
+
+// ─── Python API ───────────────────────────────────────────────────────────────
+
+/// CMS SignedData over `data`, signed with `sk` for the certificate `cert`
+/// and carrying it and `certs`. With `detached` the content is left out.
+/// Returns DER.
+#[pyfunction]
+#[pyo3(signature = (sk, cert, data, detached = false, certs = None, signing_time = None))]
+pub fn cms_sign(
+    py: Python,
+    sk: Buffer,
+    cert: Buffer,
+    data: Buffer,
+    detached: bool,
+    certs: Option<Vec<Buffer>>,
+    signing_time: Option<u64>,
+) -> PyResult<Py<PyBytes>> {
+    let cert_der = der_or_pem("cert", &cert, CERTIFICATE_LABEL)?;
+    let signer = parse_certificate(&cert_der)?;
+    check_length(
+        &format!("{} secret key", signer.algorithm.alg.name),
+        &sk,
+        signer.algorithm.alg.secret_key_bytes,
+    )?;
+    if !signer.may_sign_content()? {
+        return Err(PyValueError::new_err(format!(
+            "{} has neither digitalSignature nor nonRepudiation key usage",
+            signer.describe()
+        )));
+    }
+    let mut included = vec![cert_der.clone()];
+    for extra in certs.iter().flatten() {
+        let der = der_or_pem("certs", extra, CERTIFICATE_LABEL)?;
+        parse_certificate(&der)?;
+        if !included.contains(&der) {
+            included.push(der);
+        }
+    }
+
+    let signed_attrs = set_of(vec![
+        attribute(&OID_CONTENT_TYPE, &oid(&OID_DATA)),
+        attribute(&OID_SIGNING_TIME, &time(signing_time.unwrap_or_else(now))),
+        attribute(
+            &OID_MESSAGE_DIGEST,
+            &tlv(OCTET_STRING, &(SHA512.hash)(&data)),
+        ),
+    ]);
+    let to_sign = tlv(SET, &signed_attrs);
+    let signature =
+        (signer.algorithm.alg.sign)(py, sk, PyBytes::new_bound(py, &to_sign).extract()?)?;
+    let signature = signature.as_bytes(py);
+    if !verify(py, signer.algorithm, signer.public_key, &to_sign, signature)? {
+        return Err(PyValueError::new_err("sk doesn't match cert"));
+    }
+
+    let digest_algorithm = sequence(&[&oid(&SHA512.oid)]);
+    let signer_info = sequence(&[
+        &unsigned_integer(&[1]),
+        &sequence(&[signer.issuer, &tlv(INTEGER, signer.serial)]),
+        &digest_algorithm,
+        &tlv(CONTEXT_0, &signed_attrs),
+        &algorithm_identifier(signer.algorithm),
+        &tlv(OCTET_STRING, signature),
+    ]);
+    let content: Vec<u8> = if detached {
+        Vec::new()
+    } else {
+        tlv(CONTEXT_0, &tlv(OCTET_STRING, &data))
+    };
+    let signed_data = sequence(&[
+        &unsigned_integer(&[1]),
+        &tlv(SET, &digest_algorithm),
+        &sequence(&[&oid(&OID_DATA), &content]),
+        &tlv(CONTEXT_0, &set_of(included)),
+        &tlv(SET, &signer_info),
+    ]);
+    let message = sequence(&[&oid(&OID_SIGNED_DATA), &tlv(CONTEXT_0, &signed_data)]);
+    Ok(PyBytes::new_bound(py, &message).unbind())
+}
+
+/// Verify CMS SignedData against `roots` at `time` (default now) and return
+/// its content. A detached signature needs the content as `data`.
+#[pyfunction]
+#[pyo3(signature = (cms, roots, data = None, intermediates = None, time = None))]
+pub fn cms_verify(
+    py: Python,
+    cms: Buffer,
+    roots: Vec<Buffer>,
+    data: Option<Buffer>,
+    intermediates: Option<Vec<Buffer>>,
+    time: Option<u64>,
+) -> PyResult<Py<PyBytes>> {
+    let at = time.unwrap_or_else(now);
+    let der = der_or_pem("cms", &cms, CMS_LABEL)?;
+    let signed = parse_signed_data(&der)?;
+    let content: &[u8] = match (signed.content, &data) {
+        (Some(content), None) => content,
+        (None, Some(data)) => data,
+        (Some(_), Some(_)) => {
+            return Err(PyValueError::new_err(
+                "the CMS message carries its content; don't pass data",
+            ))
+        }
+        (None, None) => {
+            return Err(PyValueError::new_err(
+                "detached CMS signature: pass the content as data",
+            ))
+        }
+    };
+
+    let load = |what: &str, certs: &[Buffer]| -> PyResult<Vec<Vec<u8>>> {
+        certs
+            .iter()
+            .map(|cert| der_or_pem(what, cert, CERTIFICATE_LABEL))
+            .collect()
+    };
+    let root_ders = load("roots", &roots)?;
+    if root_ders.is_empty() {
+        return Err(PyValueError::new_err("cms_verify needs at least one root"));
+    }
+    let intermediate_ders = load("intermediates", intermediates.as_deref().unwrap_or(&[]))?;
+    let roots = root_ders
+        .iter()
+        .map(|der| parse_certificate(der))
+        .collect::<PyResult<Vec<_>>>()?;
+    let candidates = signed
+        .certificates
+        .iter()
+        .copied()
+        .chain(intermediate_ders.iter().map(Vec::as_slice))
+        .chain(root_ders.iter().map(Vec::as_slice))
+        .map(parse_certificate)
+        .collect::<PyResult<Vec<_>>>()?;
+
+    for signer in &signed.signers {
+        check_signer(py, &signed, signer, content, &candidates, &roots, at)?;
+    }
+    Ok(PyBytes::new_bound(py, content).unbind())
+}
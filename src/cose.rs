@@ -0,0 +1,616 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// COSE_Sign1 and COSE_Encrypt
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// For constrained devices that speak CBOR rather than JSON:
+//
+//   msg = cose_sign1(sk, reading, kid=b"sensor-7")
+//   protected, unprotected = cose_headers(msg)   # unverified, to pick the key
+//   reading = cose_verify1(pk, msg)
+//
+//   msg = cose_encrypt([gateway_pk, backup_pk], command, kids=[b"gw", b"bk"])
+//   command = cose_decrypt(gateway_sk, msg)
+//
+// Messages are RFC 9052 structures, tagged (18 for COSE_Sign1, 96 for
+// COSE_Encrypt) and encoded with deterministic CBOR (cbor.rs). Headers are
+// dicts keyed by COSE labels, e.g. {3: "application/cbor"}; `protected` goes
+// in the integrity-protected bucket, `unprotected` in the other. The labels
+// this module fills in itself (alg, crit, IV and, when given, kid) can't be
+// set through them, and no label may be in both buckets.
+//
+// COSE_Sign1 signs with ML-DSA-44, -65 or -87 (alg -48, -49, -50, from the
+// COSE ML-DSA draft), chosen by the key's length. The signature covers the
+// Sig_structure ["Signature1", protected, external_aad, payload]; with
+// `detached` the payload is left out of the message and has to be passed to
+// cose_verify1(). Verification takes the algorithm from the public key and
+// raises SignatureVerificationError unless the protected "alg" is that one.
+// Falcon has no COSE code point yet and isn't offered.
+//
+// COSE_Encrypt encrypts the content with A256GCM (alg 3) or A128GCM (1) under
+// a random key, wrapped for each recipient as ECDH-ES + HKDF-256 + AES Key
+// Wrap does it (RFC 9053, section 6.4), with an ML-KEM encapsulation for the
+// Diffie-Hellman: the KEM ciphertext travels in the recipient's unprotected
+// header under label -70000, and HKDF-SHA256 over the shared secret with the
+// COSE_KDF_Context (AlgorithmID the key wrap algorithm, PartyU / PartyV
+// nil, the recipient's protected header in SuppPubInfo) gives the
+// key-encryption key. The recipient algorithms use private-use values until
+// the COSE PQ KEM draft has code points:
+//
+//   MLKEM512+A128KW  -70001      MLKEM768+A192KW  -70002
+//   MLKEM1024+A256KW -70003
+//
+// so only this package reads them. cose_decrypt() tries each recipient of
+// its key's parameter set (only those with a matching kid, if one is given)
+// and raises DecryptionError if none decrypts. Headers with "crit" are
+// refused, as in jws.rs.
+
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict};
+use zeroize::Zeroizing;
+
+use crate::buffer::Buffer;
+use crate::cbor::{self, Value};
+use crate::errors::{
+    verification_failed, DecryptionError, InvalidLengthError, SignatureVerificationError,
+};
+use crate::jwe::{key_unwrap, key_wrap, Enc};
+use crate::kdf::Hash;
+use crate::kem::KemAlgorithm;
+use crate::noise::{decapsulate, encapsulate};
+use crate::sign::SignatureAlgorithm;
+
+const TAG_SIGN1: u64 = 18;
+const TAG_ENCRYPT: u64 = 96;
+
+const ALG: i128 = 1;
+const CRIT: i128 = 2;
+const KID: i128 = 4;
+const IV: i128 = 5;
+/// Private-use label for the KEM ciphertext.
+const EK: i128 = -70000;
+
+const IV_LEN: usize = 12;
+
+// ─── Algorithms ───────────────────────────────────────────────────────────────
+
+struct CoseSignature {
+    alg: &'static SignatureAlgorithm,
+    id: i128,
+}
+
+const SIGNATURES: &[CoseSignature] = &[
+    CoseSignature {
+        alg: &crate::mldsa44::ALGORITHM,
+        id: -48,
+    },
+    CoseSignature {
+        alg: &crate::mldsa65::ALGORITHM,
+        id: -49,
+    },
+    CoseSignature {
+        alg: &crate::mldsa87::ALGORITHM,
+        id: -50,
+    },
+];
+
+fn signature_by_length(
+    what: &str,
+    len: usize,
+    key_bytes: fn(&SignatureAlgorithm) -> usize,
+) -> PyResult<&'static CoseSignature> {
+    SIGNATURES
+        .iter()
+        .find(|s| key_bytes(s.alg) == len)
+        .ok_or_else(|| {
+            InvalidLengthError::new_err(format!(
+                "COSE {}: {} bytes isn't an ML-DSA key length",
+                what, len
+            ))
+        })
+}
+
+struct CoseKem {
+    kem: &'static KemAlgorithm,
+    id: i128,
+    /// A128KW, A192KW or A256KW, for the KDF context.
+    key_wrap_id: i128,
+    kek_len: usize,
+}
+
+const KEMS: &[CoseKem] = &[
+    CoseKem {
+        kem: &crate::mlkem512::ALGORITHM,
+        id: -70001,
+        key_wrap_id: -3,
+        kek_len: 16,
+    },
+    CoseKem {
+        kem: &crate::mlkem768::ALGORITHM,
+        id: -70002,
+        key_wrap_id: -4,
+        kek_len: 24,
+    },
+    CoseKem {
+        kem: &crate::mlkem1024::ALGORITHM,
+        id: -70003,
+        key_wrap_id: -5,
+        kek_len: 32,
+    },
+];
+
+fn kem_by_length(
+    what: &str,
+    len: usize,
+    key_bytes: fn(&KemAlgorithm) -> usize,
+) -> PyResult<&'static CoseKem> {
+    KEMS.iter()
+        .find(|k| key_bytes(k.kem) == len)
+        .ok_or_else(|| {
+            InvalidLengthError::new_err(format!(
+                "COSE {}: {} bytes isn't an ML-KEM key length",
+                what, len
+            ))
+        })
+}
+
+fn enc_id(enc: Enc) -> i128 {
+    match enc {
+        Enc::A128Gcm => 1,
+        Enc::A256Gcm => 3,
+    }
+}
+
+fn enc_from_id(id: &Value) -> PyResult<Enc> {
+    match id {
+        Value::Int(1) => Ok(Enc::A128Gcm),
+        Value::Int(3) => Ok(Enc::A256Gcm),
+        _ => Err(PyValueError::new_err(
+            "unsupported COSE content encryption algorithm, expected A128GCM or A256GCM",
+        )),
+    }
+}
+
+// ─── Headers and messages ─────────────────────────────────────────────────────
+
+fn malformed(what: &str) -> PyErr {
+    PyValueError::new_err(format!("malformed COSE message: {}", what))
+}
+
+type Header = Vec<(Value, Value)>;
+
+/// Our own header parameters followed by the caller's, refusing caller labels
+/// in `reserved`.
+fn header(fixed: Header, extra: Option<&Bound<'_, PyDict>>, reserved: &[i128]) -> PyResult<Header> {
+    let mut entries = fixed;
+    if let Some(extra) = extra {
+        let Value::Map(extra) = cbor::from_py(extra.as_any())? else {
+            unreachable!("dicts convert to maps")
+        };
+        for (label, value) in extra {
+            if reserved.iter().any(|r| label == Value::Int(*r)) {
+                return Err(PyValueError::new_err(format!(
+                    "header can't set label {}",
+                    label_repr(&label)
+                )));
+            }
+            entries.push((label, value));
+        }
+    }
+    Ok(entries)
+}
+
+fn label_repr(label: &Value) -> String {
+    match label {
+        Value::Int(n) => n.to_string(),
+        Value::Text(s) => format!("'{}'", s),
+        _ => "of that type".to_string(),
+    }
+}
+
+fn check_disjoint(protected: &Header, unprotected: &Header) -> PyResult<()> {
+    for (label, _) in protected {
+        if unprotected.iter().any(|(l, _)| l == label) {
+            return Err(PyValueError::new_err(format!(
+                "label {} is in both the protected and unprotected headers",
+                label_repr(label)
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// The protected bucket as its bstr contents: empty for an empty map.
+fn encode_protected(protected: Header) -> Vec<u8> {
+    if protected.is_empty() {
+        Vec::new()
+    } else {
+        Value::Map(protected).encode()
+    }
+}
+
+fn decode_protected(bytes: &Value) -> PyResult<Value> {
+    let Value::Bytes(bytes) = bytes else {
+        return Err(malformed("protected header isn't a byte string"));
+    };
+    if bytes.is_empty() {
+        return Ok(Value::Map(Vec::new()));
+    }
+    match cbor::decode(bytes)? {
+        map @ Value::Map(_) => Ok(map),
+        _ => Err(malformed("protected header isn't a map")),
+    }
+}
+
+fn check_unprotected(value: &Value) -> PyResult<()> {
+    match value {
+        Value::Map(_) => Ok(()),
+        _ => Err(malformed("unprotected header isn't a map")),
+    }
+}
+
+fn refuse_crit(protected: &Value) -> PyResult<()> {
+    if protected.get(&Value::Int(CRIT)).is_some() {
+        return Err(PyValueError::new_err(
+            "COSE message has critical header parameters, which aren't supported",
+        ));
+    }
+    Ok(())
+}
+
+/// A header parameter from either bucket, protected first.
+fn param<'a>(protected: &'a Value, unprotected: &'a Value, label: i128) -> Option<&'a Value> {
+    let label = Value::Int(label);
+    protected.get(&label).or_else(|| unprotected.get(&label))
+}
+
+/// The items of a COSE message array, tagged with `tag` or untagged.
+fn message_items(data: &[u8], tag: Option<u64>, len: usize) -> PyResult<Vec<Value>> {
+    let items = match cbor::decode(data)? {
+        Value::Tag(found, item) if tag.is_none_or(|t| t == found) => *item,
+        Value::Tag(found, _) => {
+            return Err(malformed(&format!("unexpected tag {}", found)));
+        }
+        item => item,
+    };
+    match items {
+        Value::Array(items) if items.len() == len => Ok(items),
+        _ => Err(malformed(&format!("expected an array of {} items", len))),
+    }
+}
+
+fn bytes_or_none(value: Option<Buffer>) -> Vec<u8> {
+    value.map(|b| b.to_vec()).unwrap_or_default()
+}
+
+// ─── COSE_Sign1 ───────────────────────────────────────────────────────────────
+
+fn sig_structure(protected: &[u8], external_aad: &[u8], payload: &[u8]) -> Vec<u8> {
+    Value::Array(vec![
+        Value::Text("Signature1".into()),
+        Value::Bytes(protected.to_vec()),
+        Value::Bytes(external_aad.to_vec()),
+        Value::Bytes(payload.to_vec()),
+    ])
+    .encode()
+}
+
+/// Sign `payload` with an ML-DSA secret key into a tagged COSE_Sign1.
+// stub: protected: dict[Any, Any] | None
+// stub: unprotected: dict[Any, Any] | None
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+#[pyo3(signature = (sk, payload, kid = None, protected = None, unprotected = None, external_aad = None, detached = false))]
+pub fn cose_sign1(
+    py: Python,
+    sk: Buffer,
+    payload: Buffer,
+    kid: Option<Buffer>,
+    protected: Option<&Bound<'_, PyDict>>,
+    unprotected: Option<&Bound<'_, PyDict>>,
+    external_aad: Option<Buffer>,
+    detached: bool,
+) -> PyResult<Py<PyBytes>> {
+    let sig = signature_by_length("secret key", sk.len(), |alg| alg.secret_key_bytes)?;
+    let protected = header(
+        vec![(Value::Int(ALG), Value::Int(sig.id))],
+        protected,
+        &[ALG, CRIT],
+    )?;
+    let mut fixed = Vec::new();
+    if let Some(kid) = &kid {
+        fixed.push((Value::Int(KID), Value::Bytes(kid.to_vec())));
+    }
+    let reserved: &[i128] = if kid.is_some() {
+        &[ALG, CRIT, KID]
+    } else {
+        &[ALG, CRIT]
+    };
+    let unprotected = header(fixed, unprotected, reserved)?;
+    check_disjoint(&protected, &unprotected)?;
+
+    let protected = encode_protected(protected);
+    let to_sign = sig_structure(&protected, &bytes_or_none(external_aad), &payload);
+    let to_sign = PyBytes::new_bound(py, &to_sign);
+    let signature = (sig.alg.sign)(py, sk, to_sign.extract()?)?;
+
+    let message = Value::Tag(
+        TAG_SIGN1,
+        Box::new(Value::Array(vec![
+            Value::Bytes(protected),
+            Value::Map(unprotected),
+            if detached {
+                Value::Null
+            } else {
+                Value::Bytes(payload.to_vec())
+            },
+            Value::Bytes(signature.as_bytes(py).to_vec()),
+        ])),
+    );
+    Ok(PyBytes::new_bound(py, &message.encode()).unbind())
+}
+
+/// Verify a COSE_Sign1 against an ML-DSA public key and return its payload.
+/// A detached payload is passed as `payload`.
+#[pyfunction]
+#[pyo3(signature = (pk, message, payload = None, external_aad = None))]
+pub fn cose_verify1(
+    py: Python,
+    pk: Buffer,
+    message: Buffer,
+    payload: Option<Buffer>,
+    external_aad: Option<Buffer>,
+) -> PyResult<Py<PyBytes>> {
+    let sig = signature_by_length("public key", pk.len(), |alg| alg.public_key_bytes)?;
+    let items = message_items(&message, Some(TAG_SIGN1), 4)?;
+    let protected = decode_protected(&items[0])?;
+    check_unprotected(&items[1])?;
+    if protected.get(&Value::Int(ALG)) != Some(&Value::Int(sig.id)) {
+        return Err(SignatureVerificationError::new_err(format!(
+            "COSE_Sign1 protected alg isn't {} ({})",
+            sig.id, sig.alg.name
+        )));
+    }
+    refuse_crit(&protected)?;
+    let payload = match (&items[2], payload) {
+        (Value::Bytes(attached), None) => attached.clone(),
+        (Value::Null, Some(detached)) => detached.to_vec(),
+        (Value::Null, None) => {
+            return Err(PyValueError::new_err(
+                "COSE_Sign1 payload is detached; pass payload",
+            ))
+        }
+        (Value::Bytes(_), Some(_)) => {
+            return Err(PyValueError::new_err(
+                "COSE_Sign1 payload is attached; don't pass payload",
+            ))
+        }
+        _ => return Err(malformed("payload isn't a byte string or nil")),
+    };
+    let Value::Bytes(signature) = &items[3] else {
+        return Err(malformed("signature isn't a byte string"));
+    };
+    let Value::Bytes(protected_bytes) = &items[0] else {
+        unreachable!("checked by decode_protected")
+    };
+
+    let signed = sig_structure(protected_bytes, &bytes_or_none(external_aad), &payload);
+    let signed = PyBytes::new_bound(py, &signed);
+    let sig_bytes = PyBytes::new_bound(py, signature);
+    if !(sig.alg.verify)(pk, signed.extract()?, sig_bytes.extract()?)? {
+        return Err(verification_failed(
+            sig.alg.name,
+            payload.len(),
+            signature.len(),
+        ));
+    }
+    Ok(PyBytes::new_bound(py, &payload).unbind())
+}
+
+/// The protected and unprotected headers of a COSE_Sign1 or COSE_Encrypt,
+/// without verifying or decrypting anything.
+// stub: -> tuple[dict[Any, Any], dict[Any, Any]]
+#[pyfunction]
+pub fn cose_headers(py: Python, message: Buffer) -> PyResult<(PyObject, PyObject)> {
+    let items = message_items(&message, None, 4)?;
+    let protected = decode_protected(&items[0])?;
+    check_unprotected(&items[1])?;
+    Ok((cbor::to_py(py, &protected)?, cbor::to_py(py, &items[1])?))
+}
+
+// ─── COSE_Encrypt ─────────────────────────────────────────────────────────────
+
+fn enc_structure(protected: &[u8], external_aad: &[u8]) -> Vec<u8> {
+    Value::Array(vec![
+        Value::Text("Encrypt".into()),
+        Value::Bytes(protected.to_vec()),
+        Value::Bytes(external_aad.to_vec()),
+    ])
+    .encode()
+}
+
+/// RFC 9053's HKDF-256 over the KEM shared secret, for the key wrap key.
+fn key_encryption_key(kem: &CoseKem, ss: &[u8], protected: &[u8]) -> Zeroizing<Vec<u8>> {
+    let party = || Value::Array(vec![Value::Null, Value::Null, Value::Null]);
+    let context = Value::Array(vec![
+        Value::Int(kem.key_wrap_id),
+        party(),
+        party(),
+        Value::Array(vec![
+            Value::Int((kem.kek_len * 8) as i128),
+            Value::Bytes(protected.to_vec()),
+        ]),
+    ])
+    .encode();
+    let prk = Hash::Sha256.extract(None, ss);
+    let mut kek = Zeroizing::new(vec![0u8; kem.kek_len]);
+    Hash::Sha256.expand(&prk, &context, &mut kek);
+    kek
+}
+
+fn random_bytes(len: usize) -> PyResult<Zeroizing<Vec<u8>>> {
+    let mut out = Zeroizing::new(vec![0u8; len]);
+    getrandom::fill(&mut out).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+    Ok(out)
+}
+
+/// Encrypt `plaintext` to one or more ML-KEM public keys as a tagged
+/// COSE_Encrypt.
+// stub: enc: Literal["A256GCM", "A128GCM"]
+// stub: protected: dict[Any, Any] | None
+// stub: unprotected: dict[Any, Any] | None
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+#[pyo3(signature = (recipients, plaintext, enc = "A256GCM", kids = None, protected = None, unprotected = None, external_aad = None))]
+pub fn cose_encrypt(
+    py: Python,
+    recipients: Vec<Buffer>,
+    plaintext: Buffer,
+    enc: &str,
+    kids: Option<Vec<Buffer>>,
+    protected: Option<&Bound<'_, PyDict>>,
+    unprotected: Option<&Bound<'_, PyDict>>,
+    external_aad: Option<Buffer>,
+) -> PyResult<Py<PyBytes>> {
+    if recipients.is_empty() {
+        return Err(PyValueError::new_err(
+            "COSE_Encrypt needs at least one recipient",
+        ));
+    }
+    if let Some(kids) = &kids {
+        if kids.len() != recipients.len() {
+            return Err(PyValueError::new_err(
+                "kids must have one entry per recipient",
+            ));
+        }
+    }
+    let enc = Enc::from_name(enc)?;
+    let cek = random_bytes(enc.key_len())?;
+    let iv = random_bytes(IV_LEN)?;
+
+    let protected = header(
+        vec![(Value::Int(ALG), Value::Int(enc_id(enc)))],
+        protected,
+        &[ALG, CRIT, IV],
+    )?;
+    let unprotected = header(
+        vec![(Value::Int(IV), Value::Bytes(iv.to_vec()))],
+        unprotected,
+        &[ALG, CRIT, IV],
+    )?;
+    check_disjoint(&protected, &unprotected)?;
+    let protected = encode_protected(protected);
+    let aad = enc_structure(&protected, &bytes_or_none(external_aad));
+    let mut ciphertext = plaintext.to_vec();
+    enc.seal(&cek, &iv, &aad, &mut ciphertext)?;
+
+    let mut recipient_list = Vec::with_capacity(recipients.len());
+    for (index, pk) in recipients.iter().enumerate() {
+        let kem = kem_by_length("public key", pk.len(), |kem| kem.public_key_bytes)?;
+        let (ct, ss) = encapsulate(py, kem.kem, pk)?;
+        let recipient_protected = encode_protected(vec![(Value::Int(ALG), Value::Int(kem.id))]);
+        let kek = key_encryption_key(kem, &ss, &recipient_protected);
+
+        let mut recipient_unprotected = vec![(Value::Int(EK), Value::Bytes(ct))];
+        if let Some(kids) = &kids {
+            recipient_unprotected.push((Value::Int(KID), Value::Bytes(kids[index].to_vec())));
+        }
+        recipient_list.push(Value::Array(vec![
+            Value::Bytes(recipient_protected),
+            Value::Map(recipient_unprotected),
+            Value::Bytes(key_wrap(&kek, &cek)),
+        ]));
+    }
+
+    let message = Value::Tag(
+        TAG_ENCRYPT,
+        Box::new(Value::Array(vec![
+            Value::Bytes(protected),
+            Value::Map(unprotected),
+            Value::Bytes(ciphertext),
+            Value::Array(recipient_list),
+        ])),
+    );
+    Ok(PyBytes::new_bound(py, &message.encode()).unbind())
+}
+
+/// Decrypt a COSE_Encrypt with an ML-KEM secret key. If `kid` is given, only
+/// recipients with that kid are tried.
+#[pyfunction]
+#[pyo3(signature = (sk, message, external_aad = None, kid = None))]
+pub fn cose_decrypt(
+    py: Python,
+    sk: Buffer,
+    message: Buffer,
+    external_aad: Option<Buffer>,
+    kid: Option<Buffer>,
+) -> PyResult<Py<PyBytes>> {
+    let kem = kem_by_length("secret key", sk.len(), |kem| kem.secret_key_bytes)?;
+    let items = message_items(&message, Some(TAG_ENCRYPT), 4)?;
+    let protected = decode_protected(&items[0])?;
+    let unprotected = &items[1];
+    check_unprotected(unprotected)?;
+    refuse_crit(&protected)?;
+    let enc = enc_from_id(param(&protected, unprotected, ALG).ok_or_else(|| malformed("no alg"))?)?;
+    let Some(Value::Bytes(iv)) = param(&protected, unprotected, IV) else {
+        return Err(malformed("no IV"));
+    };
+    let Value::Bytes(ciphertext) = &items[2] else {
+        return Err(malformed("ciphertext isn't a byte string"));
+    };
+    let Value::Array(recipients) = &items[3] else {
+        return Err(malformed("recipients isn't an array"));
+    };
+    let Value::Bytes(protected_bytes) = &items[0] else {
+        unreachable!("checked by decode_protected")
+    };
+    let aad = enc_structure(protected_bytes, &bytes_or_none(external_aad));
+    let kid = kid.map(|kid| Value::Bytes(kid.to_vec()));
+
+    for recipient in recipients {
+        let Value::Array(fields) = recipient else {
+            return Err(malformed("recipient isn't an array"));
+        };
+        let [recipient_protected, recipient_unprotected, encrypted_key] = &fields[..] else {
+            return Err(malformed("recipient isn't an array of 3 items"));
+        };
+        let header = decode_protected(recipient_protected)?;
+        check_unprotected(recipient_unprotected)?;
+        refuse_crit(&header)?;
+        if param(&header, recipient_unprotected, ALG) != Some(&Value::Int(kem.id)) {
+            continue;
+        }
+        if kid.is_some() && param(&header, recipient_unprotected, KID) != kid.as_ref() {
+            continue;
+        }
+        let (Some(Value::Bytes(ek)), Value::Bytes(encrypted_key)) =
+            (param(&header, recipient_unprotected, EK), encrypted_key)
+        else {
+            return Err(malformed(
+                "recipient has no KEM ciphertext or encrypted key",
+            ));
+        };
+        if ek.len() != kem.kem.ciphertext_bytes {
+            continue;
+        }
+        let Value::Bytes(recipient_protected) = recipient_protected else {
+            unreachable!("checked by decode_protected")
+        };
+        let ss = decapsulate(py, kem.kem, &sk, ek)?;
+        let kek = key_encryption_key(kem, &ss, recipient_protected);
+        let Some(cek) = key_unwrap(&kek, encrypted_key) else {
+            continue;
+        };
+        if cek.len() != enc.key_len() {
+            continue;
+        }
+        let mut buf = ciphertext.clone();
+        if enc.open(&cek, iv, &aad, &mut buf) {
+            return Ok(PyBytes::new_bound(py, &buf).unbind());
+        }
+    }
+    Err(DecryptionError::new_err(format!(
+        "no {} recipient of this COSE_Encrypt decrypts with the key",
+        kem.kem.name
+    )))
+}
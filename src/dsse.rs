@@ -0,0 +1,304 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// DSSE envelopes with Falcon and ML-DSA
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// For supply-chain attestations (in-toto statements, SLSA provenance):
+//
+//   env = dsse_sign(sk, "application/vnd.in-toto+json", statement, keyid="ci")
+//   env = dsse_add_signature(env, release_sk, keyid="release")
+//   payload_type, statement = dsse_verify([ci_pk, release_pk], env, threshold=2)
+//
+// Envelopes are DSSE v1.0 JSON,
+//
+//   {"payload": base64(payload), "payloadType": type,
+//    "signatures": [{"keyid": keyid, "sig": base64(signature)}, ...]}
+//
+// with each signature over the pre-authentication encoding
+//
+//   "DSSEv1" SP LEN(type) SP type SP LEN(payload) SP payload
+//
+// (LEN as ASCII decimal), so the type can't be swapped under a signature.
+// The scheme is Falcon-512 / 1024 or ML-DSA-44 / 65 / 87, taken from the key's
+// length unless `algorithm` names one; ML-DSA signs in pure mode with an
+// empty context. "keyid" is left out without `keyid`. Base64 is written
+// standard and padded, and read in either alphabet, padded or not.
+//
+// dsse_verify() ignores keyids, which aren't authenticated: it tries each
+// signature against each of the given public keys and raises
+// SignatureVerificationError unless at least `threshold` distinct keys have
+// a valid signature. The payload type is returned for the caller to check;
+// passing `payload_type` makes a mismatch an error too.
+
+use base64ct::{Base64, Base64Unpadded, Base64Url, Base64UrlUnpadded, Encoding};
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict, PyList};
+
+use crate::buffer::Buffer;
+use crate::errors::{InvalidLengthError, SignatureVerificationError};
+use crate::sign::SignatureAlgorithm;
+
+/// The schemes offered. Their key lengths are all different.
+const DSSE_ALGORITHMS: &[&SignatureAlgorithm] = &[
+    &crate::falcon512::ALGORITHM,
+    &crate::falcon1024::ALGORITHM,
+    &crate::mldsa44::ALGORITHM,
+    &crate::mldsa65::ALGORITHM,
+    &crate::mldsa87::ALGORITHM,
+];
+
+fn by_name(name: &str) -> PyResult<&'static SignatureAlgorithm> {
+    let wanted = crate::kem::normalize_name(name);
+    DSSE_ALGORITHMS
+        .iter()
+        .copied()
+        .find(|alg| alg.name == wanted)
+        .ok_or_else(|| {
+            PyValueError::new_err(format!(
+                "no DSSE algorithm for '{}' (expected a Falcon or ML-DSA parameter set)",
+                name
+            ))
+        })
+}
+
+fn by_key_length(
+    what: &str,
+    len: usize,
+    key_bytes: fn(&SignatureAlgorithm) -> usize,
+) -> PyResult<&'static SignatureAlgorithm> {
+    DSSE_ALGORITHMS
+        .iter()
+        .copied()
+        .find(|alg| key_bytes(alg) == len)
+        .ok_or_else(|| {
+            InvalidLengthError::new_err(format!(
+                "DSSE {}: {} bytes isn't a Falcon or ML-DSA key length",
+                what, len
+            ))
+        })
+}
+
+fn signing_algorithm(sk: &[u8], algorithm: Option<&str>) -> PyResult<&'static SignatureAlgorithm> {
+    match algorithm {
+        Some(name) => by_name(name),
+        None => by_key_length("secret key", sk.len(), |alg| alg.secret_key_bytes),
+    }
+}
+
+/// The pre-authentication encoding the signatures cover.
+fn pae(payload_type: &str, payload: &[u8]) -> Vec<u8> {
+    let mut out = format!(
+        "DSSEv1 {} {} {} ",
+        payload_type.len(),
+        payload_type,
+        payload.len()
+    )
+    .into_bytes();
+    out.extend_from_slice(payload);
+    out
+}
+
+fn decode(what: &str, text: &str) -> PyResult<Vec<u8>> {
+    Base64::decode_vec(text)
+        .or_else(|_| Base64Unpadded::decode_vec(text))
+        .or_else(|_| Base64Url::decode_vec(text))
+        .or_else(|_| Base64UrlUnpadded::decode_vec(text))
+        .map_err(|_| {
+            PyValueError::new_err(format!("malformed DSSE envelope: {} isn't base64", what))
+        })
+}
+
+fn json<'py>(py: Python<'py>) -> PyResult<Bound<'py, PyModule>> {
+    py.import_bound("json")
+}
+
+fn malformed(why: &str) -> PyErr {
+    PyValueError::new_err(format!("malformed DSSE envelope: {}", why))
+}
+
+fn string_member(object: &Bound<'_, PyDict>, key: &str) -> PyResult<Option<String>> {
+    object
+        .get_item(key)?
+        .map(|value| value.extract())
+        .transpose()
+        .map_err(|_| malformed(&format!("'{}' isn't a string", key)))
+}
+
+struct Envelope<'py> {
+    object: Bound<'py, PyDict>,
+    payload_type: String,
+    payload: Vec<u8>,
+    signatures: Bound<'py, PyList>,
+}
+
+fn parse_envelope<'py>(py: Python<'py>, envelope: &Bound<'py, PyAny>) -> PyResult<Envelope<'py>> {
+    let object = json(py)?
+        .call_method1("loads", (envelope,))
+        .map_err(|_| malformed("not JSON"))?
+        .downcast_into::<PyDict>()
+        .map_err(|_| malformed("not a JSON object"))?;
+    let payload_type =
+        string_member(&object, "payloadType")?.ok_or_else(|| malformed("'payloadType' missing"))?;
+    let payload =
+        string_member(&object, "payload")?.ok_or_else(|| malformed("'payload' missing"))?;
+    let signatures = object
+        .get_item("signatures")?
+        .ok_or_else(|| malformed("'signatures' missing"))?
+        .downcast_into::<PyList>()
+        .map_err(|_| malformed("'signatures' isn't a list"))?;
+    Ok(Envelope {
+        payload: decode("payload", &payload)?,
+        object,
+        payload_type,
+        signatures,
+    })
+}
+
+fn signature_object<'py>(
+    py: Python<'py>,
+    alg: &SignatureAlgorithm,
+    sk: Buffer,
+    message: &[u8],
+    keyid: Option<&str>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let sig = (alg.sign)(py, sk, PyBytes::new_bound(py, message).extract()?)?;
+    let object = PyDict::new_bound(py);
+    if let Some(keyid) = keyid {
+        object.set_item("keyid", keyid)?;
+    }
+    object.set_item("sig", Base64::encode_string(sig.as_bytes(py)))?;
+    Ok(object)
+}
+
+fn dumps(py: Python, object: &Bound<'_, PyDict>) -> PyResult<String> {
+    let kwargs = PyDict::new_bound(py);
+    kwargs.set_item("separators", (",", ":"))?;
+    json(py)?
+        .call_method("dumps", (object,), Some(&kwargs))?
+        .extract()
+}
+
+/// Sign `payload` of type `payload_type` into a DSSE envelope (JSON). The
+/// algorithm is taken from the secret key's length unless `algorithm` names
+/// one.
+#[pyfunction]
+#[pyo3(signature = (sk, payload_type, payload, keyid = None, algorithm = None))]
+pub fn dsse_sign(
+    py: Python,
+    sk: Buffer,
+    payload_type: &str,
+    payload: Buffer,
+    keyid: Option<&str>,
+    algorithm: Option<&str>,
+) -> PyResult<String> {
+    let alg = signing_algorithm(&sk, algorithm)?;
+    let signature = signature_object(py, alg, sk, &pae(payload_type, &payload), keyid)?;
+    let envelope = PyDict::new_bound(py);
+    envelope.set_item("payload", Base64::encode_string(&payload))?;
+    envelope.set_item("payloadType", payload_type)?;
+    envelope.set_item("signatures", PyList::new_bound(py, [signature]))?;
+    dumps(py, &envelope)
+}
+
+/// Add a signature with `sk` to a DSSE envelope, for multi-party signing.
+/// Existing signatures aren't checked.
+// stub: envelope: str | bytes
+#[pyfunction]
+#[pyo3(signature = (envelope, sk, keyid = None, algorithm = None))]
+pub fn dsse_add_signature(
+    py: Python,
+    envelope: &Bound<'_, PyAny>,
+    sk: Buffer,
+    keyid: Option<&str>,
+    algorithm: Option<&str>,
+) -> PyResult<String> {
+    let alg = signing_algorithm(&sk, algorithm)?;
+    let envelope = parse_envelope(py, envelope)?;
+    let message = pae(&envelope.payload_type, &envelope.payload);
+    envelope
+        .signatures
+        .append(signature_object(py, alg, sk, &message, keyid)?)?;
+    dumps(py, &envelope.object)
+}
+
+/// Verify a DSSE envelope against one public key or a list of them and
+/// return (payload_type, payload). Raises SignatureVerificationError unless
+/// `threshold` of the keys signed it, or if the type isn't `payload_type`.
+// stub: pk: Buffer | Sequence[Buffer]
+// stub: envelope: str | bytes
+#[pyfunction]
+#[pyo3(signature = (pk, envelope, payload_type = None, threshold = 1))]
+pub fn dsse_verify(
+    py: Python,
+    pk: &Bound<'_, PyAny>,
+    envelope: &Bound<'_, PyAny>,
+    payload_type: Option<&str>,
+    threshold: usize,
+) -> PyResult<(String, Py<PyBytes>)> {
+    let pks: Vec<Buffer> = match pk.extract::<Buffer>() {
+        Ok(pk) => vec![pk],
+        Err(_) => pk.extract().map_err(|_| {
+            PyTypeError::new_err("pk must be a public key or a list of public keys")
+        })?,
+    };
+    if threshold == 0 || threshold > pks.len() {
+        return Err(PyValueError::new_err(format!(
+            "threshold must be between 1 and the number of keys ({})",
+            pks.len()
+        )));
+    }
+    let algs = pks
+        .iter()
+        .map(|pk| by_key_length("public key", pk.len(), |alg| alg.public_key_bytes))
+        .collect::<PyResult<Vec<_>>>()?;
+
+    let envelope = parse_envelope(py, envelope)?;
+    if let Some(expected) = payload_type {
+        if envelope.payload_type != expected {
+            return Err(SignatureVerificationError::new_err(format!(
+                "DSSE payload type is '{}', expected '{}'",
+                envelope.payload_type, expected
+            )));
+        }
+    }
+    let mut sigs = Vec::new();
+    for item in envelope.signatures.iter() {
+        let item = item
+            .downcast_into::<PyDict>()
+            .map_err(|_| malformed("a signature isn't a JSON object"))?;
+        let sig = string_member(&item, "sig")?.ok_or_else(|| malformed("'sig' missing"))?;
+        sigs.push(decode("sig", &sig)?);
+    }
+
+    let message = PyBytes::new_bound(py, &pae(&envelope.payload_type, &envelope.payload));
+    let mut signed = 0;
+    for (i, (pk, alg)) in pks.iter().zip(&algs).enumerate() {
+        // The same key listed twice counts once.
+        if pks[..i].iter().any(|other| other[..] == pk[..]) {
+            continue;
+        }
+        for sig in sigs.iter().filter(|sig| sig.len() <= alg.signature_bytes) {
+            // A signature by another key may be malformed for this one.
+            let valid = (alg.verify)(
+                PyBytes::new_bound(py, pk).extract()?,
+                message.extract()?,
+                PyBytes::new_bound(py, sig).extract()?,
+            )
+            .unwrap_or(false);
+            if valid {
+                signed += 1;
+                break;
+            }
+        }
+    }
+    if signed < threshold {
+        return Err(SignatureVerificationError::new_err(format!(
+            "DSSE envelope has valid signatures from {} of the keys, {} required",
+            signed, threshold
+        )));
+    }
+    Ok((
+        envelope.payload_type,
+        PyBytes::new_bound(py, &envelope.payload).unbind(),
+    ))
+}
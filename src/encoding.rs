@@ -0,0 +1,516 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// PKCS#8 / SubjectPublicKeyInfo DER and PEM
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// Standard key containers, for handing keys to OpenSSL + oqs-provider, HSM
+// import tools and anything else that won't take raw byte blobs:
+//
+//   der = public_key_to_der("kyber768", pk)       # SubjectPublicKeyInfo
+//   alg, pk = public_key_from_der(der)
+//   der = secret_key_to_der("falcon512", sk, pk)  # PKCS#8 PrivateKeyInfo
+//   alg, sk = secret_key_from_der(der)
+//
+// The *_to_pem / *_from_pem counterparts wrap the same DER in PEM armor, and
+// there are PEM forms for ciphertexts and signatures too (see below). The
+// Kyber and Falcon key objects have to_der() / from_der() and to_pem() /
+// from_pem() as well.
+//
+// Algorithm identifiers carry no parameters. The OIDs are the ones oqs-provider
+// uses: its draft arcs for round-3 Kyber and Falcon, and the NIST arc for
+// ML-KEM. The PKCS#8 privateKey field follows oqs-provider too: an OCTET STRING
+// wrapping sk || pk. KEM secret keys already embed their public key, so it
+// is filled in automatically; for Falcon it is appended when passed in.
+// On import, either layout is accepted, with or without the trailing public key.
+// ML-KEM keys can also come in the draft-ietf-lamps-kyber-certificates
+// private key CHOICE that OpenSSL 3.5 writes: the 64-byte seed [0], the
+// expandedKey OCTET STRING, or a SEQUENCE of both. A seed is expanded with
+// FIPS 203 keygen; given both, the expanded key must match the seed.
+
+use pkcs8::der::asn1::{AnyRef, BitStringRef, OctetStringRef};
+use pkcs8::der::{Decode, Encode, Reader, SliceReader, Tag, TagNumber, Tagged};
+use pkcs8::spki::{AlgorithmIdentifierRef, SubjectPublicKeyInfoRef};
+use pkcs8::{ObjectIdentifier, PrivateKeyInfoRef};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use subtle::ConstantTimeEq;
+use zeroize::Zeroizing;
+
+use crate::buffer::Buffer;
+use crate::errors::{
+    check_length, check_max_length, InvalidKeyError, InvalidLengthError, KemError, SignatureError,
+};
+use crate::kem::{find_kem, normalize_name, KemAlgorithm};
+use crate::secret::{secret_bytes, SecretBytes};
+use crate::sign::{find_signature, SignatureAlgorithm};
+
+pub(crate) struct KeyFormat {
+    pub name: &'static str,
+    oid: ObjectIdentifier,
+    public_key_bytes: usize,
+    secret_key_bytes: usize,
+    /// KEM secret keys are s || pk || H(pk) || z, pk recoverable from sk.
+    embeds_public_key: bool,
+    /// ML-KEM, whose PKCS#8 key may be the FIPS 203 seed instead.
+    seed_form: bool,
+}
+
+const fn kem(alg: &KemAlgorithm, oid: &str) -> KeyFormat {
+    KeyFormat {
+        name: alg.name,
+        oid: ObjectIdentifier::new_unwrap(oid),
+        public_key_bytes: alg.public_key_bytes,
+        secret_key_bytes: alg.secret_key_bytes,
+        embeds_public_key: true,
+        seed_form: false,
+    }
+}
+
+const fn mlkem(alg: &KemAlgorithm, oid: &str) -> KeyFormat {
+    KeyFormat {
+        seed_form: true,
+        ..kem(alg, oid)
+    }
+}
+
+const fn signature(alg: &SignatureAlgorithm, oid: &str) -> KeyFormat {
+    KeyFormat {
+        name: alg.name,
+        oid: ObjectIdentifier::new_unwrap(oid),
+        public_key_bytes: alg.public_key_bytes,
+        secret_key_bytes: alg.secret_key_bytes,
+        embeds_public_key: false,
+        seed_form: false,
+    }
+}
+
+const KEY_FORMATS: &[KeyFormat] = &[
+    kem(&crate::kyber512::ALGORITHM, "1.3.6.1.4.1.22554.5.6.1"),
+    kem(&crate::kyber768::ALGORITHM, "1.3.6.1.4.1.22554.5.6.2"),
+    kem(&crate::kyber1024::ALGORITHM, "1.3.6.1.4.1.22554.5.6.3"),
+    mlkem(&crate::mlkem512::ALGORITHM, "2.16.840.1.101.3.4.4.1"),
+    mlkem(&crate::mlkem768::ALGORITHM, "2.16.840.1.101.3.4.4.2"),
+    mlkem(&crate::mlkem1024::ALGORITHM, "2.16.840.1.101.3.4.4.3"),
+    signature(&crate::falcon512::ALGORITHM, "1.3.9999.3.11"),
+    signature(&crate::falcon1024::ALGORITHM, "1.3.9999.3.14"),
+];
+
+// H(pk) and z follow the embedded public key in a KEM secret key.
+const KEM_SK_TRAILER_LEN: usize = 64;
+
+pub(crate) fn find_format(name: &str) -> PyResult<&'static KeyFormat> {
+    let wanted = normalize_name(name);
+    KEY_FORMATS
+        .iter()
+        .find(|f| f.name == wanted)
+        .ok_or_else(|| PyValueError::new_err(format!("no DER key format for '{}'", name)))
+}
+
+fn format_for_oid(oid: ObjectIdentifier) -> PyResult<&'static KeyFormat> {
+    KEY_FORMATS
+        .iter()
+        .find(|f| f.oid == oid)
+        .ok_or_else(|| InvalidKeyError::new_err(format!("unsupported key algorithm OID {}", oid)))
+}
+
+fn der_err(what: &str) -> impl Fn(pkcs8::der::Error) -> PyErr + '_ {
+    move |e| InvalidKeyError::new_err(format!("malformed {}: {}", what, e))
+}
+
+/// For the from_der() constructors on the key objects, which only take keys
+/// of their own algorithm family.
+pub(crate) fn expect_family(format: &KeyFormat, family: &[&str]) -> PyResult<()> {
+    if !family.contains(&format.name) {
+        return Err(InvalidKeyError::new_err(format!(
+            "expected a {} key, got {}",
+            family.join(" / "),
+            format.name
+        )));
+    }
+    Ok(())
+}
+
+impl KeyFormat {
+    fn algorithm(&self) -> AlgorithmIdentifierRef<'static> {
+        AlgorithmIdentifierRef {
+            oid: self.oid,
+            parameters: None,
+        }
+    }
+
+    fn embedded_public_key<'a>(&self, sk: &'a [u8]) -> &'a [u8] {
+        let end = sk.len() - KEM_SK_TRAILER_LEN;
+        &sk[end - self.public_key_bytes..end]
+    }
+}
+
+// ─── Encoding / decoding ──────────────────────────────────────────────────────
+
+pub(crate) fn encode_public_key(format: &KeyFormat, pk: &[u8]) -> PyResult<Vec<u8>> {
+    check_length(
+        &format!("{} public key", format.name),
+        pk,
+        format.public_key_bytes,
+    )?;
+    let spki = SubjectPublicKeyInfoRef {
+        algorithm: format.algorithm(),
+        subject_public_key: BitStringRef::from_bytes(pk).map_err(der_err("public key"))?,
+    };
+    spki.to_der().map_err(der_err("public key"))
+}
+
+pub(crate) fn decode_public_key(der: &[u8]) -> PyResult<(&'static KeyFormat, &[u8])> {
+    let spki = SubjectPublicKeyInfoRef::from_der(der).map_err(der_err("SubjectPublicKeyInfo"))?;
+    let format = format_for_oid(spki.algorithm.oid)?;
+    let pk = spki
+        .subject_public_key
+        .as_bytes()
+        .ok_or_else(|| InvalidKeyError::new_err("public key BIT STRING has unused bits"))?;
+    check_length(
+        &format!("{} public key", format.name),
+        pk,
+        format.public_key_bytes,
+    )?;
+    Ok((format, pk))
+}
+
+pub(crate) fn encode_secret_key(
+    format: &KeyFormat,
+    sk: &[u8],
+    pk: Option<&[u8]>,
+) -> PyResult<Zeroizing<Vec<u8>>> {
+    check_length(
+        &format!("{} secret key", format.name),
+        sk,
+        format.secret_key_bytes,
+    )?;
+    let pk = match pk {
+        Some(pk) => {
+            check_length(
+                &format!("{} public key", format.name),
+                pk,
+                format.public_key_bytes,
+            )?;
+            pk
+        }
+        None if format.embeds_public_key => format.embedded_public_key(sk),
+        None => &[],
+    };
+
+    let mut blob = Zeroizing::new(Vec::with_capacity(sk.len() + pk.len()));
+    blob.extend_from_slice(sk);
+    blob.extend_from_slice(pk);
+
+    let inner = Zeroizing::new(
+        OctetStringRef::new(&blob)
+            .and_then(|os| os.to_der())
+            .map_err(der_err("secret key"))?,
+    );
+    let info = PrivateKeyInfoRef::new(
+        format.algorithm(),
+        OctetStringRef::new(&inner).map_err(der_err("secret key"))?,
+    );
+    Ok(Zeroizing::new(
+        info.to_der().map_err(der_err("secret key"))?,
+    ))
+}
+
+pub(crate) fn decode_secret_key(
+    der: &[u8],
+) -> PyResult<(&'static KeyFormat, Zeroizing<Vec<u8>>)> {
+    let info = PrivateKeyInfoRef::from_der(der).map_err(der_err("PrivateKeyInfo"))?;
+    let format = format_for_oid(info.algorithm.oid)?;
+
+    let outer = info.private_key.as_bytes();
+    if format.seed_form {
+        if let Some(sk) = decode_mlkem_seed(format, outer)? {
+            return Ok((format, sk));
+        }
+    }
+
+    // oqs-provider nests an OCTET STRING inside privateKey; take the raw
+    // layout too.
+    let blob = match <&OctetStringRef>::from_der(outer) {
+        Ok(inner) => inner.as_bytes(),
+        Err(_) => outer,
+    };
+
+    let what = format!("{} secret key", format.name);
+    let (sk, pk) = if blob.len() == format.secret_key_bytes {
+        (blob, None)
+    } else if blob.len() == format.secret_key_bytes + format.public_key_bytes {
+        let (sk, pk) = blob.split_at(format.secret_key_bytes);
+        (sk, Some(pk))
+    } else {
+        return Err(InvalidLengthError::new_err(format!(
+            "{}: expected {} bytes, or {} with the public key, got {}",
+            what,
+            format.secret_key_bytes,
+            format.secret_key_bytes + format.public_key_bytes,
+            blob.len()
+        )));
+    };
+
+    if let Some(pk) = pk {
+        if format.embeds_public_key && format.embedded_public_key(sk) != pk {
+            return Err(InvalidKeyError::new_err(format!(
+                "{}: public key does not match the one embedded in the secret key",
+                what
+            )));
+        }
+    }
+    Ok((format, Zeroizing::new(sk.to_vec())))
+}
+
+/// The seed [0] and both SEQUENCE arms of the ML-KEM private key CHOICE, as
+/// an expanded secret key. None for anything else, expandedKey included,
+/// which takes the same path as the oqs-provider layout.
+fn decode_mlkem_seed(format: &KeyFormat, outer: &[u8]) -> PyResult<Option<Zeroizing<Vec<u8>>>> {
+    const SEED: Tag = Tag::ContextSpecific {
+        constructed: false,
+        number: TagNumber(0),
+    };
+
+    let Ok(choice) = AnyRef::from_der(outer) else {
+        return Ok(None);
+    };
+    match choice.tag() {
+        SEED => crate::seeded::mlkem_secret_key_from_seed(format.name, choice.value()).map(Some),
+        Tag::Sequence => {
+            let what = format!("{} private key", format.name);
+            let mut reader = SliceReader::new(choice.value()).map_err(der_err(&what))?;
+            let seed = <&OctetStringRef>::decode(&mut reader).map_err(der_err(&what))?;
+            let expanded = <&OctetStringRef>::decode(&mut reader).map_err(der_err(&what))?;
+            reader.finish().map_err(der_err(&what))?;
+
+            let sk = crate::seeded::mlkem_secret_key_from_seed(format.name, seed.as_bytes())?;
+            if !bool::from(sk.as_slice().ct_eq(expanded.as_bytes())) {
+                return Err(InvalidKeyError::new_err(format!(
+                    "{}: expanded key does not match the seed",
+                    what
+                )));
+            }
+            Ok(Some(sk))
+        }
+        _ => Ok(None),
+    }
+}
+
+// ─── Python API ───────────────────────────────────────────────────────────────
+
+/// Wrap a raw public key in a DER SubjectPublicKeyInfo.
+#[pyfunction]
+pub fn public_key_to_der(py: Python, algorithm: &str, pk_bytes: Buffer) -> PyResult<Py<PyBytes>> {
+    let der = encode_public_key(find_format(algorithm)?, &pk_bytes)?;
+    Ok(PyBytes::new_bound(py, &der).unbind())
+}
+
+/// (algorithm, public_key) from a DER SubjectPublicKeyInfo.
+#[pyfunction]
+pub fn public_key_from_der(py: Python, der: Buffer) -> PyResult<(&'static str, Py<PyBytes>)> {
+    let (format, pk) = decode_public_key(&der)?;
+    Ok((format.name, PyBytes::new_bound(py, pk).unbind()))
+}
+
+/// Wrap a raw secret key in a DER PKCS#8 PrivateKeyInfo. `pk_bytes` is only
+/// needed for Falcon; KEM secret keys carry their own public key.
+#[pyfunction]
+#[pyo3(signature = (algorithm, sk_bytes, pk_bytes = None))]
+pub fn secret_key_to_der(
+    py: Python,
+    algorithm: &str,
+    sk_bytes: Buffer,
+    pk_bytes: Option<Buffer>,
+) -> PyResult<Py<PyBytes>> {
+    let der = encode_secret_key(find_format(algorithm)?, &sk_bytes, pk_bytes.as_deref())?;
+    Ok(PyBytes::new_bound(py, &der).unbind())
+}
+
+/// (algorithm, secret_key) from a DER PKCS#8 PrivateKeyInfo.
+#[pyfunction]
+pub fn secret_key_from_der(py: Python, der: Buffer) -> PyResult<(&'static str, Py<SecretBytes>)> {
+    let (format, sk) = decode_secret_key(&der)?;
+    Ok((format.name, secret_bytes(py, &sk)?))
+}
+
+// ─── PEM ──────────────────────────────────────────────────────────────────────
+//
+// RFC 7468 armor over the DER above: "PUBLIC KEY" and "PRIVATE KEY" blocks,
+// as written by OpenSSL. Ciphertexts and signatures have no standard
+// container, so their raw bytes go in "<ALGORITHM> CIPHERTEXT" and
+// "<ALGORITHM> SIGNATURE" blocks, e.g. "MLKEM768 CIPHERTEXT", which keeps the
+// algorithm recoverable on the way back in.
+
+const PUBLIC_KEY_LABEL: &str = "PUBLIC KEY";
+const PRIVATE_KEY_LABEL: &str = "PRIVATE KEY";
+const CIPHERTEXT_SUFFIX: &str = " CIPHERTEXT";
+const SIGNATURE_SUFFIX: &str = " SIGNATURE";
+
+/// PEM text as passed from Python: str, or bytes read from a file.
+#[derive(FromPyObject)]
+pub(crate) enum Pem {
+    Str(String),
+    Bytes(Buffer),
+}
+
+impl Pem {
+    pub(crate) fn as_bytes(&self) -> &[u8] {
+        match self {
+            Pem::Str(s) => s.as_bytes(),
+            Pem::Bytes(b) => b,
+        }
+    }
+}
+
+pub(crate) fn pem_encode(label: &str, data: &[u8]) -> PyResult<Zeroizing<String>> {
+    pem_rfc7468::encode_string(label, pem_rfc7468::LineEnding::LF, data)
+        .map(Zeroizing::new)
+        .map_err(|e| PyValueError::new_err(format!("PEM encoding failed: {}", e)))
+}
+
+/// (label, contents) of a PEM block. `err` picks the exception for malformed
+/// input.
+pub(crate) fn pem_decode(
+    pem: &Pem,
+    err: fn(String) -> PyErr,
+) -> PyResult<(String, Zeroizing<Vec<u8>>)> {
+    let (label, data) = pem_rfc7468::decode_vec(pem.as_bytes())
+        .map_err(|e| err(format!("malformed PEM: {}", e)))?;
+    Ok((label.to_owned(), Zeroizing::new(data)))
+}
+
+/// The DER inside a PUBLIC KEY / PRIVATE KEY block.
+fn unarmor_key(pem: &Pem, label: &str) -> PyResult<Zeroizing<Vec<u8>>> {
+    let (found, der) = pem_decode(pem, InvalidKeyError::new_err::<String>)?;
+    if found != label {
+        return Err(InvalidKeyError::new_err(format!(
+            "expected a {} PEM block, got '{}'",
+            label, found
+        )));
+    }
+    Ok(der)
+}
+
+pub(crate) fn public_key_pem(format: &KeyFormat, pk: &[u8]) -> PyResult<Zeroizing<String>> {
+    pem_encode(PUBLIC_KEY_LABEL, &encode_public_key(format, pk)?)
+}
+
+pub(crate) fn secret_key_pem(
+    format: &KeyFormat,
+    sk: &[u8],
+    pk: Option<&[u8]>,
+) -> PyResult<Zeroizing<String>> {
+    pem_encode(PRIVATE_KEY_LABEL, &encode_secret_key(format, sk, pk)?)
+}
+
+pub(crate) fn public_key_der_from_pem(pem: &Pem) -> PyResult<Zeroizing<Vec<u8>>> {
+    unarmor_key(pem, PUBLIC_KEY_LABEL)
+}
+
+pub(crate) fn secret_key_der_from_pem(pem: &Pem) -> PyResult<Zeroizing<Vec<u8>>> {
+    unarmor_key(pem, PRIVATE_KEY_LABEL)
+}
+
+/// Public key in a "PUBLIC KEY" PEM block.
+#[pyfunction]
+pub fn public_key_to_pem(algorithm: &str, pk_bytes: Buffer) -> PyResult<String> {
+    Ok(public_key_pem(find_format(algorithm)?, &pk_bytes)?.to_string())
+}
+
+/// (algorithm, public_key) from a "PUBLIC KEY" PEM block.
+#[pyfunction]
+pub fn public_key_from_pem(py: Python, pem: Pem) -> PyResult<(&'static str, Py<PyBytes>)> {
+    let der = public_key_der_from_pem(&pem)?;
+    let (format, pk) = decode_public_key(&der)?;
+    Ok((format.name, PyBytes::new_bound(py, pk).unbind()))
+}
+
+/// Secret key in a "PRIVATE KEY" PEM block. `pk_bytes` as for
+/// secret_key_to_der().
+#[pyfunction]
+#[pyo3(signature = (algorithm, sk_bytes, pk_bytes = None))]
+pub fn secret_key_to_pem(
+    algorithm: &str,
+    sk_bytes: Buffer,
+    pk_bytes: Option<Buffer>,
+) -> PyResult<String> {
+    let pem = secret_key_pem(find_format(algorithm)?, &sk_bytes, pk_bytes.as_deref())?;
+    Ok(pem.to_string())
+}
+
+/// (algorithm, secret_key) from a "PRIVATE KEY" PEM block.
+#[pyfunction]
+pub fn secret_key_from_pem(py: Python, pem: Pem) -> PyResult<(&'static str, Py<SecretBytes>)> {
+    let der = secret_key_der_from_pem(&pem)?;
+    let (format, sk) = decode_secret_key(&der)?;
+    Ok((format.name, secret_bytes(py, &sk)?))
+}
+
+fn find_kem_or_err(name: &str) -> PyResult<&'static KemAlgorithm> {
+    find_kem(name).ok_or_else(|| PyValueError::new_err(format!("unsupported KEM '{}'", name)))
+}
+
+fn find_signature_or_err(name: &str) -> PyResult<&'static SignatureAlgorithm> {
+    find_signature(name)
+        .ok_or_else(|| PyValueError::new_err(format!("unsupported signature scheme '{}'", name)))
+}
+
+/// KEM ciphertext in an "<ALGORITHM> CIPHERTEXT" PEM block.
+#[pyfunction]
+pub fn ciphertext_to_pem(algorithm: &str, ct_bytes: Buffer) -> PyResult<String> {
+    let alg = find_kem_or_err(algorithm)?;
+    check_length(
+        &format!("{} ciphertext", alg.name),
+        &ct_bytes,
+        alg.ciphertext_bytes,
+    )?;
+    let label = alg.name.to_uppercase() + CIPHERTEXT_SUFFIX;
+    Ok(pem_encode(&label, &ct_bytes)?.to_string())
+}
+
+/// (algorithm, ciphertext) from an "<ALGORITHM> CIPHERTEXT" PEM block.
+#[pyfunction]
+pub fn ciphertext_from_pem(py: Python, pem: Pem) -> PyResult<(&'static str, Py<PyBytes>)> {
+    let (label, ct) = pem_decode(&pem, KemError::new_err::<String>)?;
+    let alg = label
+        .strip_suffix(CIPHERTEXT_SUFFIX)
+        .and_then(find_kem)
+        .ok_or_else(|| KemError::new_err(format!("not a KEM ciphertext PEM block: '{}'", label)))?;
+    check_length(
+        &format!("{} ciphertext", alg.name),
+        &ct,
+        alg.ciphertext_bytes,
+    )?;
+    Ok((alg.name, PyBytes::new_bound(py, &ct).unbind()))
+}
+
+/// Detached signature in an "<ALGORITHM> SIGNATURE" PEM block.
+#[pyfunction]
+pub fn signature_to_pem(algorithm: &str, sig_bytes: Buffer) -> PyResult<String> {
+    let alg = find_signature_or_err(algorithm)?;
+    check_max_length(
+        &format!("{} signature", alg.name),
+        &sig_bytes,
+        alg.signature_bytes,
+    )?;
+    let label = alg.name.to_uppercase() + SIGNATURE_SUFFIX;
+    Ok(pem_encode(&label, &sig_bytes)?.to_string())
+}
+
+/// (algorithm, signature) from an "<ALGORITHM> SIGNATURE" PEM block.
+#[pyfunction]
+pub fn signature_from_pem(py: Python, pem: Pem) -> PyResult<(&'static str, Py<PyBytes>)> {
+    let (label, sig) = pem_decode(&pem, SignatureError::new_err::<String>)?;
+    let alg = label
+        .strip_suffix(SIGNATURE_SUFFIX)
+        .and_then(find_signature)
+        .ok_or_else(|| {
+            SignatureError::new_err(format!("not a signature PEM block: '{}'", label))
+        })?;
+    check_max_length(
+        &format!("{} signature", alg.name),
+        &sig,
+        alg.signature_bytes,
+    )?;
+    Ok((alg.name, PyBytes::new_bound(py, &sig).unbind()))
+}
@@ -0,0 +1,196 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// Exception hierarchy
+// ───────────────────────────────────────────────────────────────────────────────
+//
+//   ValueError
+//   └── CryptoError
+//       ├── InvalidLengthError     wrong-sized key / ciphertext / signature
+//       ├── InvalidKeyError        right size, but not a usable key
+//       │   └── PublicKeyValidationError   from validate_public_key()
+//       ├── DecryptionError        wrong password / tampered encrypted key
+//       ├── NonceReuseError        a NonceSequence would repeat a nonce
+//       ├── KeyPolicyError         key expired, or used against its metadata
+//       ├── PairwiseConsistencyError   fresh key pair failed its round trip
+//       ├── KemError
+//       │   └── DecapsulationError
+//       └── SignatureError
+//           └── SignatureVerificationError   from the *_verify_strict functions
+//               ├── SignatureExpiredError    signature or metadata out of date
+//               └── CertificateVerificationError   certificate chain invalid
+//
+// CryptoError derives from ValueError, so code written against the old
+// behaviour (everything raised ValueError) keeps catching the same failures.
+// Bad arguments that aren't crypto data (unknown algorithm names, modes,
+// parameter sets) still raise plain ValueError.
+
+use pyo3::create_exception;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+create_exception!(
+    pqcrypto_bindings,
+    CryptoError,
+    PyValueError,
+    "Base class for failures in the cryptographic operations."
+);
+create_exception!(
+    pqcrypto_bindings,
+    KemError,
+    CryptoError,
+    "A KEM operation failed."
+);
+create_exception!(
+    pqcrypto_bindings,
+    DecapsulationError,
+    KemError,
+    "Decapsulation failed."
+);
+create_exception!(
+    pqcrypto_bindings,
+    SignatureError,
+    CryptoError,
+    "Signing or signature decoding failed."
+);
+create_exception!(
+    pqcrypto_bindings,
+    SignatureVerificationError,
+    SignatureError,
+    "A signature did not verify."
+);
+create_exception!(
+    pqcrypto_bindings,
+    SignatureExpiredError,
+    SignatureVerificationError,
+    "A signature verified, but its timestamp or signed metadata is expired, too old, \
+     or dated in the future."
+);
+create_exception!(
+    pqcrypto_bindings,
+    CertificateVerificationError,
+    SignatureVerificationError,
+    "A certificate chain did not validate: bad signature, expired, or no path to a trusted root."
+);
+create_exception!(
+    pqcrypto_bindings,
+    InvalidKeyError,
+    CryptoError,
+    "Key material has the right length but can't be used."
+);
+create_exception!(
+    pqcrypto_bindings,
+    PublicKeyValidationError,
+    InvalidKeyError,
+    "A public key failed its algorithm's standardized input checks."
+);
+create_exception!(
+    pqcrypto_bindings,
+    DecryptionError,
+    CryptoError,
+    "An encrypted key could not be decrypted: wrong password or modified data."
+);
+create_exception!(
+    pqcrypto_bindings,
+    NonceReuseError,
+    CryptoError,
+    "A NonceSequence would repeat a nonce: it is exhausted, or behind one already issued under its key."
+);
+create_exception!(
+    pqcrypto_bindings,
+    KeyPolicyError,
+    CryptoError,
+    "A key's metadata forbids this use: it has expired or is marked for another usage."
+);
+create_exception!(
+    pqcrypto_bindings,
+    PairwiseConsistencyError,
+    CryptoError,
+    "A freshly generated key pair failed its encapsulate/decapsulate or sign/verify round trip."
+);
+create_exception!(
+    pqcrypto_bindings,
+    InvalidLengthError,
+    CryptoError,
+    "A key, ciphertext, signature or secret has the wrong length."
+);
+
+/// Length check to run before handing bytes to a `from_bytes`, so the error
+/// names the algorithm: "kyber512 public key: expected 800 bytes, got 1184".
+pub(crate) fn check_length(what: &str, bytes: &[u8], expected: usize) -> PyResult<()> {
+    if bytes.len() != expected {
+        return Err(InvalidLengthError::new_err(format!(
+            "{}: expected {} bytes, got {}",
+            what,
+            expected,
+            bytes.len()
+        )));
+    }
+    Ok(())
+}
+
+/// check_length() for variable-length encodings such as Falcon signatures.
+pub(crate) fn check_max_length(what: &str, bytes: &[u8], max: usize) -> PyResult<()> {
+    if bytes.len() > max {
+        return Err(InvalidLengthError::new_err(format!(
+            "{}: expected at most {} bytes, got {}",
+            what,
+            max,
+            bytes.len()
+        )));
+    }
+    Ok(())
+}
+
+/// For `from_bytes` failures in the pqcrypto crates, which only ever reject
+/// on length. Callers check the length first, so this is a backstop.
+pub(crate) fn length_err(e: pqcrypto_traits::Error) -> PyErr {
+    InvalidLengthError::new_err(e.to_string())
+}
+
+/// What the `*_verify_strict` functions raise where `*_verify` would have
+/// returned False.
+pub(crate) fn verification_failed(alg: &str, msg_len: usize, sig_len: usize) -> PyErr {
+    SignatureVerificationError::new_err(format!(
+        "{} signature verification failed ({} byte signature over {} byte message)",
+        alg, sig_len, msg_len
+    ))
+}
+
+pub(crate) fn add_to_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+    m.add("CryptoError", py.get_type_bound::<CryptoError>())?;
+    m.add("KemError", py.get_type_bound::<KemError>())?;
+    m.add(
+        "DecapsulationError",
+        py.get_type_bound::<DecapsulationError>(),
+    )?;
+    m.add("SignatureError", py.get_type_bound::<SignatureError>())?;
+    m.add(
+        "SignatureVerificationError",
+        py.get_type_bound::<SignatureVerificationError>(),
+    )?;
+    m.add(
+        "SignatureExpiredError",
+        py.get_type_bound::<SignatureExpiredError>(),
+    )?;
+    m.add(
+        "CertificateVerificationError",
+        py.get_type_bound::<CertificateVerificationError>(),
+    )?;
+    m.add("InvalidKeyError", py.get_type_bound::<InvalidKeyError>())?;
+    m.add(
+        "PublicKeyValidationError",
+        py.get_type_bound::<PublicKeyValidationError>(),
+    )?;
+    m.add(
+        "InvalidLengthError",
+        py.get_type_bound::<InvalidLengthError>(),
+    )?;
+    m.add("DecryptionError", py.get_type_bound::<DecryptionError>())?;
+    m.add("NonceReuseError", py.get_type_bound::<NonceReuseError>())?;
+    m.add("KeyPolicyError", py.get_type_bound::<KeyPolicyError>())?;
+    m.add(
+        "PairwiseConsistencyError",
+        py.get_type_bound::<PairwiseConsistencyError>(),
+    )?;
+    Ok(())
+}
@@ -0,0 +1,450 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// Falcon signer / verifier objects
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// falcon_sign(sk, msg) re-parses the secret key on every call. For a
+// long-lived service key, parse it once instead:
+//
+//   signer = FalconSigner(sk_bytes)
+//   sig = signer.sign(msg)
+//   FalconVerifier(pk_bytes).verify(msg, sig)
+//
+// Falcon-512 and Falcon-1024 are told apart by key length. The verifier
+// accepts both compressed and padded signatures, like falcon_verify().
+// Verifiers pickle; signers only once allow_pickle is set on them.
+
+use pqcrypto_falcon::{falcon1024, falcon512, falconpadded1024, falconpadded512};
+use pqcrypto_traits::sign::{DetachedSignature as _, PublicKey as _, SecretKey as _};
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict};
+
+use crate::buffer::Buffer;
+use crate::encoding::{
+    decode_public_key, decode_secret_key, encode_public_key, encode_secret_key, expect_family,
+    find_format, public_key_der_from_pem, public_key_pem, secret_key_der_from_pem, secret_key_pem,
+    Pem,
+};
+use crate::errors::{
+    check_max_length, length_err, verification_failed, InvalidKeyError, InvalidLengthError,
+};
+use crate::jwk::{
+    decode_jwk, encode_public_jwk, encode_secret_jwk, expect_jwk_family, find_jwk_format,
+};
+use crate::recover::falcon_public_from_secret;
+use crate::sign::context_message;
+use crate::util::{ct_eq, fingerprint, secret_fingerprint, secret_pickle_error};
+
+const FALCON_NAMES: &[&str] = &["falcon512", "falcon1024"];
+
+fn unknown_len(what: &str, len: usize, falcon512_len: usize, falcon1024_len: usize) -> PyErr {
+    InvalidLengthError::new_err(format!(
+        "Falcon {}: expected {} or {} bytes, got {}",
+        what, falcon512_len, falcon1024_len, len
+    ))
+}
+
+// ─── FalconSigner ─────────────────────────────────────────────────────────────
+
+// The compressed and padded encodings share one secret key format, but
+// pqcrypto gives each its own key type; both are parsed up front (boxed, as
+// the Falcon-1024 keys are several KB).
+enum SigningKey {
+    Falcon512(Box<falcon512::SecretKey>, Box<falconpadded512::SecretKey>),
+    Falcon1024(Box<falcon1024::SecretKey>, Box<falconpadded1024::SecretKey>),
+}
+
+#[pyclass(module = "pqcrypto_bindings")]
+pub struct FalconSigner {
+    key: SigningKey,
+    /// Signers refuse to pickle unless this is set to True.
+    #[pyo3(get, set)]
+    allow_pickle: bool,
+}
+
+impl Drop for FalconSigner {
+    fn drop(&mut self) {
+        // SAFETY: the pqcrypto key types are plain byte arrays with no
+        // invariants, so an all-zero value is valid and nothing reads it after.
+        unsafe {
+            match &mut self.key {
+                SigningKey::Falcon512(sk, padded) => {
+                    zeroize::zeroize_flat_type(&mut **sk as *mut falcon512::SecretKey);
+                    zeroize::zeroize_flat_type(&mut **padded as *mut falconpadded512::SecretKey);
+                }
+                SigningKey::Falcon1024(sk, padded) => {
+                    zeroize::zeroize_flat_type(&mut **sk as *mut falcon1024::SecretKey);
+                    zeroize::zeroize_flat_type(&mut **padded as *mut falconpadded1024::SecretKey);
+                }
+            }
+        }
+    }
+}
+
+impl FalconSigner {
+    pub(crate) fn from_slice(sk_bytes: &[u8]) -> PyResult<Self> {
+        let key = if sk_bytes.len() == falcon512::secret_key_bytes() {
+            SigningKey::Falcon512(
+                Box::new(falcon512::SecretKey::from_bytes(sk_bytes).map_err(length_err)?),
+                Box::new(falconpadded512::SecretKey::from_bytes(sk_bytes).map_err(length_err)?),
+            )
+        } else if sk_bytes.len() == falcon1024::secret_key_bytes() {
+            SigningKey::Falcon1024(
+                Box::new(falcon1024::SecretKey::from_bytes(sk_bytes).map_err(length_err)?),
+                Box::new(falconpadded1024::SecretKey::from_bytes(sk_bytes).map_err(length_err)?),
+            )
+        } else {
+            return Err(unknown_len(
+                "secret key",
+                sk_bytes.len(),
+                falcon512::secret_key_bytes(),
+                falcon1024::secret_key_bytes(),
+            ));
+        };
+
+        Ok(FalconSigner {
+            key,
+            allow_pickle: false,
+        })
+    }
+
+    fn parse_der(der: &[u8]) -> PyResult<Self> {
+        let (format, sk) = decode_secret_key(der)?;
+        expect_family(format, FALCON_NAMES)?;
+        Self::from_slice(&sk)
+    }
+
+    pub(crate) fn secret_bytes(&self) -> &[u8] {
+        match &self.key {
+            SigningKey::Falcon512(sk, _) => sk.as_bytes(),
+            SigningKey::Falcon1024(sk, _) => sk.as_bytes(),
+        }
+    }
+}
+
+#[pymethods]
+impl FalconSigner {
+    #[new]
+    fn new(sk_bytes: Buffer) -> PyResult<Self> {
+        Self::from_slice(&sk_bytes)
+    }
+
+    /// "falcon512" or "falcon1024".
+    #[getter]
+    fn params(&self) -> &'static str {
+        match self.key {
+            SigningKey::Falcon512(..) => "falcon512",
+            SigningKey::Falcon1024(..) => "falcon1024",
+        }
+    }
+
+    /// Compressed (variable-length) detached signature, under `context` as
+    /// with falcon_sign().
+    #[pyo3(signature = (msg, context = Buffer::empty()))]
+    fn sign(&self, py: Python, msg: Buffer, context: Buffer) -> PyResult<Py<PyBytes>> {
+        let msg = context_message(&context, &msg)?;
+        Ok(match &self.key {
+            SigningKey::Falcon512(sk, _) => {
+                let sig = falcon512::detached_sign(&msg, sk);
+                PyBytes::new_bound(py, sig.as_bytes()).unbind()
+            }
+            SigningKey::Falcon1024(sk, _) => {
+                let sig = falcon1024::detached_sign(&msg, sk);
+                PyBytes::new_bound(py, sig.as_bytes()).unbind()
+            }
+        })
+    }
+
+    /// Fixed-length padded detached signature.
+    #[pyo3(signature = (msg, context = Buffer::empty()))]
+    fn sign_padded(&self, py: Python, msg: Buffer, context: Buffer) -> PyResult<Py<PyBytes>> {
+        let msg = context_message(&context, &msg)?;
+        Ok(match &self.key {
+            SigningKey::Falcon512(_, sk) => {
+                let sig = falconpadded512::detached_sign(&msg, sk);
+                PyBytes::new_bound(py, sig.as_bytes()).unbind()
+            }
+            SigningKey::Falcon1024(_, sk) => {
+                let sig = falconpadded1024::detached_sign(&msg, sk);
+                PyBytes::new_bound(py, sig.as_bytes()).unbind()
+            }
+        })
+    }
+
+    /// Raw secret key bytes. Handle with care.
+    fn to_bytes(&self, py: Python) -> Py<PyBytes> {
+        PyBytes::new_bound(py, self.secret_bytes()).unbind()
+    }
+
+    /// DER PKCS#8 PrivateKeyInfo. Falcon secret keys don't carry their public
+    /// key; pass it to include it, as oqs-provider does. Handle with care.
+    #[pyo3(signature = (pk_bytes = None))]
+    fn to_der(&self, py: Python, pk_bytes: Option<Buffer>) -> PyResult<Py<PyBytes>> {
+        let der = encode_secret_key(
+            find_format(self.params())?,
+            self.secret_bytes(),
+            pk_bytes.as_deref(),
+        )?;
+        Ok(PyBytes::new_bound(py, &der).unbind())
+    }
+
+    #[staticmethod]
+    fn from_der(der: Buffer) -> PyResult<Self> {
+        Self::parse_der(&der)
+    }
+
+    /// "PRIVATE KEY" PEM block; `pk_bytes` as for to_der(). Handle with care.
+    #[pyo3(signature = (pk_bytes = None))]
+    fn to_pem(&self, pk_bytes: Option<Buffer>) -> PyResult<String> {
+        let pem = secret_key_pem(
+            find_format(self.params())?,
+            self.secret_bytes(),
+            pk_bytes.as_deref(),
+        )?;
+        Ok(pem.to_string())
+    }
+
+    #[staticmethod]
+    fn from_pem(pem: Pem) -> PyResult<Self> {
+        Self::parse_der(&secret_key_der_from_pem(&pem)?)
+    }
+
+    /// Private JWK dict. A JWK always carries the public key; it is
+    /// recomputed from the secret key unless passed in. Handle with care.
+    // stub: -> dict[str, str]
+    #[pyo3(signature = (pk_bytes = None))]
+    fn to_jwk<'py>(
+        &self,
+        py: Python<'py>,
+        pk_bytes: Option<Buffer>,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let pk = match pk_bytes {
+            Some(pk) => pk,
+            None => {
+                let sk = PyBytes::new_bound(py, self.secret_bytes());
+                falcon_public_from_secret(py, sk.extract()?)?
+                    .bind(py)
+                    .extract()?
+            }
+        };
+        encode_secret_jwk(
+            py,
+            find_jwk_format(self.params())?,
+            self.secret_bytes(),
+            Some(&pk),
+        )
+    }
+
+    /// From a private JWK dict or its JSON text.
+    // stub: jwk: dict[str, str] | str
+    #[staticmethod]
+    fn from_jwk(jwk: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let jwk = decode_jwk(jwk)?;
+        expect_jwk_family(jwk.format, FALCON_NAMES)?;
+        let sk = jwk
+            .sk
+            .ok_or_else(|| InvalidKeyError::new_err("JWK has no 'priv' member"))?;
+        Self::from_slice(&sk)
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        ct_eq(self.secret_bytes(), other.secret_bytes())
+    }
+
+    fn __reduce__(slf: &Bound<'_, Self>) -> PyResult<(PyObject, (Py<PyBytes>,))> {
+        if !slf.borrow().allow_pickle {
+            return Err(secret_pickle_error("FalconSigner"));
+        }
+        Ok((
+            slf.get_type().into_any().unbind(),
+            (slf.borrow().to_bytes(slf.py()),),
+        ))
+    }
+
+    /// Never shows key material. A Falcon secret key doesn't carry its public
+    /// key, so the fingerprint is of the secret key (under its own label).
+    fn __repr__(&self) -> String {
+        format!(
+            "FalconSigner(params='{}', fingerprint='{}')",
+            self.params(),
+            secret_fingerprint(self.secret_bytes())
+        )
+    }
+}
+
+// ─── FalconVerifier ───────────────────────────────────────────────────────────
+
+enum VerifyingKey {
+    Falcon512(Box<falcon512::PublicKey>, Box<falconpadded512::PublicKey>),
+    Falcon1024(Box<falcon1024::PublicKey>, Box<falconpadded1024::PublicKey>),
+}
+
+#[pyclass(module = "pqcrypto_bindings")]
+pub struct FalconVerifier {
+    key: VerifyingKey,
+}
+
+/// Verify against the padded encoding when `sig` has exactly its length,
+/// falling back to the compressed encoding otherwise.
+macro_rules! verify_either {
+    ($alg:ident, $padded:ident, $pk:expr, $padded_pk:expr, $msg:expr, $sig:expr) => {{
+        if $sig.len() == $padded::signature_bytes() {
+            let sig = $padded::DetachedSignature::from_bytes($sig).map_err(length_err)?;
+            if $padded::verify_detached_signature(&sig, $msg, $padded_pk).is_ok() {
+                return Ok(true);
+            }
+        }
+        check_max_length(
+            concat!(stringify!($alg), " signature"),
+            $sig,
+            $alg::signature_bytes(),
+        )?;
+        let sig = $alg::DetachedSignature::from_bytes($sig).map_err(length_err)?;
+        Ok($alg::verify_detached_signature(&sig, $msg, $pk).is_ok())
+    }};
+}
+
+impl FalconVerifier {
+    pub(crate) fn from_slice(pk_bytes: &[u8]) -> PyResult<Self> {
+        let key = if pk_bytes.len() == falcon512::public_key_bytes() {
+            VerifyingKey::Falcon512(
+                Box::new(falcon512::PublicKey::from_bytes(pk_bytes).map_err(length_err)?),
+                Box::new(falconpadded512::PublicKey::from_bytes(pk_bytes).map_err(length_err)?),
+            )
+        } else if pk_bytes.len() == falcon1024::public_key_bytes() {
+            VerifyingKey::Falcon1024(
+                Box::new(falcon1024::PublicKey::from_bytes(pk_bytes).map_err(length_err)?),
+                Box::new(falconpadded1024::PublicKey::from_bytes(pk_bytes).map_err(length_err)?),
+            )
+        } else {
+            return Err(unknown_len(
+                "public key",
+                pk_bytes.len(),
+                falcon512::public_key_bytes(),
+                falcon1024::public_key_bytes(),
+            ));
+        };
+
+        Ok(FalconVerifier { key })
+    }
+
+    fn parse_der(der: &[u8]) -> PyResult<Self> {
+        let (format, pk) = decode_public_key(der)?;
+        expect_family(format, FALCON_NAMES)?;
+        Self::from_slice(pk)
+    }
+
+    pub(crate) fn public_bytes(&self) -> &[u8] {
+        match &self.key {
+            VerifyingKey::Falcon512(pk, _) => pk.as_bytes(),
+            VerifyingKey::Falcon1024(pk, _) => pk.as_bytes(),
+        }
+    }
+}
+
+#[pymethods]
+impl FalconVerifier {
+    #[new]
+    fn new(pk_bytes: Buffer) -> PyResult<Self> {
+        Self::from_slice(&pk_bytes)
+    }
+
+    /// "falcon512" or "falcon1024".
+    #[getter]
+    fn params(&self) -> &'static str {
+        match self.key {
+            VerifyingKey::Falcon512(..) => "falcon512",
+            VerifyingKey::Falcon1024(..) => "falcon1024",
+        }
+    }
+
+    #[pyo3(signature = (msg, sig_bytes, context = Buffer::empty()))]
+    fn verify(&self, msg: Buffer, sig_bytes: Buffer, context: Buffer) -> PyResult<bool> {
+        let msg = context_message(&context, &msg)?;
+        match &self.key {
+            VerifyingKey::Falcon512(pk, padded_pk) => {
+                verify_either!(falcon512, falconpadded512, pk, padded_pk, &msg, &sig_bytes)
+            }
+            VerifyingKey::Falcon1024(pk, padded_pk) => {
+                verify_either!(
+                    falcon1024,
+                    falconpadded1024,
+                    pk,
+                    padded_pk,
+                    &msg,
+                    &sig_bytes
+                )
+            }
+        }
+    }
+
+    /// Like verify(), but raises SignatureVerificationError instead of
+    /// returning False.
+    #[pyo3(signature = (msg, sig_bytes, context = Buffer::empty()))]
+    fn verify_strict(&self, msg: Buffer, sig_bytes: Buffer, context: Buffer) -> PyResult<()> {
+        let (msg_len, sig_len) = (msg.len(), sig_bytes.len());
+        if self.verify(msg, sig_bytes, context)? {
+            Ok(())
+        } else {
+            Err(verification_failed(self.params(), msg_len, sig_len))
+        }
+    }
+
+    fn to_bytes(&self, py: Python) -> Py<PyBytes> {
+        PyBytes::new_bound(py, self.public_bytes()).unbind()
+    }
+
+    /// DER SubjectPublicKeyInfo.
+    fn to_der(&self, py: Python) -> PyResult<Py<PyBytes>> {
+        let der = encode_public_key(find_format(self.params())?, self.public_bytes())?;
+        Ok(PyBytes::new_bound(py, &der).unbind())
+    }
+
+    #[staticmethod]
+    fn from_der(der: Buffer) -> PyResult<Self> {
+        Self::parse_der(&der)
+    }
+
+    /// "PUBLIC KEY" PEM block.
+    fn to_pem(&self) -> PyResult<String> {
+        Ok(public_key_pem(find_format(self.params())?, self.public_bytes())?.to_string())
+    }
+
+    #[staticmethod]
+    fn from_pem(pem: Pem) -> PyResult<Self> {
+        Self::parse_der(&public_key_der_from_pem(&pem)?)
+    }
+
+    /// Public JWK dict ("kty": "AKP").
+    // stub: -> dict[str, str]
+    fn to_jwk<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        encode_public_jwk(py, find_jwk_format(self.params())?, self.public_bytes())
+    }
+
+    /// From a JWK dict or its JSON text. A private JWK gives its public half.
+    // stub: jwk: dict[str, str] | str
+    #[staticmethod]
+    fn from_jwk(jwk: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let jwk = decode_jwk(jwk)?;
+        expect_jwk_family(jwk.format, FALCON_NAMES)?;
+        Self::from_slice(&jwk.pk)
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        ct_eq(self.public_bytes(), other.public_bytes())
+    }
+
+    fn __reduce__(slf: &Bound<'_, Self>) -> (PyObject, (Py<PyBytes>,)) {
+        (
+            slf.get_type().into_any().unbind(),
+            (slf.borrow().to_bytes(slf.py()),),
+        )
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "FalconVerifier(params='{}', fingerprint='{}')",
+            self.params(),
+            fingerprint(self.public_bytes())
+        )
+    }
+}
@@ -0,0 +1,359 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// File encryption to a KEM public key
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// For encrypting files of any size without reading them into memory:
+//
+//   encrypt_file(pk, "backup.tar", "backup.tar.pqe")
+//   decrypt_file(sk, "backup.tar.pqe", "backup.tar")
+//
+// The file starts with a self-describing header, so any later version on any
+// platform can read it with only the secret key (integers big-endian):
+//
+//   magic "PQEF" | version 1 | AEAD id (as in seal.rs) | name length u8
+//   | KEM name | chunk size u32 | KEM ciphertext | stream nonce prefix
+//
+// followed by the STREAM chunks of stream.rs: each plaintext chunk is
+// `chunk size` bytes except the last, which holds the 1 to `chunk size`
+// bytes left (none only for an empty file) and is sealed as last; every
+// chunk gains a 16-byte tag. The stream key is
+//
+//   key = HKDF-SHA256(salt = "", IKM = shared secret, info = label || header, 32)
+//
+// and the stream's associated data is header || aad, so changing any of the
+// header, or any chunk, or cutting the file short, or passing a different
+// `aad` fails decrypt_file() with DecryptionError. `aad` (a file name, say,
+// or routing metadata) is authenticated but not stored in the file; it
+// defaults to empty, which reads files written before it existed. KEM and
+// AEAD selection follow seal().
+//
+// Output goes to a temporary file next to `out_path` that is renamed into
+// place once complete: after a failure `out_path` is untouched, and no
+// partly-decrypted plaintext is left behind.
+
+use std::fs::File;
+use std::io::{ErrorKind, Read, Write};
+use std::path::PathBuf;
+
+use hkdf::Hkdf;
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use sha2::Sha256;
+use zeroize::Zeroizing;
+
+use crate::aead::{Aead, KEY_LEN, TAG_LEN};
+use crate::buffer::Buffer;
+use crate::errors::DecryptionError;
+use crate::kem::find_kem;
+use crate::seal::kem_for;
+use crate::stream::Stream;
+use crate::util::write_atomic_with;
+
+const MAGIC: &[u8; 4] = b"PQEF";
+const VERSION: u8 = 1;
+const LABEL: &[u8] = b"entropic-chaos file v1\0";
+
+const DEFAULT_CHUNK_SIZE: usize = 64 * 1024;
+const MAX_CHUNK_SIZE: usize = 16 * 1024 * 1024;
+
+fn associated_data(header: &[u8], aad: Option<Buffer>) -> Vec<u8> {
+    [header, aad.as_deref().unwrap_or_default()].concat()
+}
+
+fn stream_key(ss: &[u8], header: &[u8]) -> Zeroizing<[u8; KEY_LEN]> {
+    let mut info = Vec::with_capacity(LABEL.len() + header.len());
+    info.extend_from_slice(LABEL);
+    info.extend_from_slice(header);
+    let mut key = Zeroizing::new([0u8; KEY_LEN]);
+    Hkdf::<Sha256>::new(None, ss)
+        .expand(&info, key.as_mut())
+        .expect("32 bytes is a valid HKDF-SHA256 length");
+    key
+}
+
+/// Up to `n` bytes, fewer only at end of file.
+fn read_chunk(input: &mut File, n: usize) -> PyResult<Vec<u8>> {
+    let mut buf = Vec::with_capacity(n + TAG_LEN);
+    input.take(n as u64).read_to_end(&mut buf)?;
+    Ok(buf)
+}
+
+fn read_header_field(input: &mut File, header: &mut Vec<u8>, n: usize) -> PyResult<usize> {
+    let start = header.len();
+    header.resize(start + n, 0);
+    input
+        .read_exact(&mut header[start..])
+        .map_err(|e| match e.kind() {
+            ErrorKind::UnexpectedEof => {
+                DecryptionError::new_err("encrypted file header is truncated")
+            }
+            _ => e.into(),
+        })?;
+    Ok(start)
+}
+
+/// Encrypt the file at `in_path` to the KEM public key `pk`, writing
+/// `out_path`. `cipher` is as for seal(); `chunk_size` is the plaintext
+/// bytes per authenticated chunk. `aad` must be passed again to decrypt.
+// stub: cipher: Literal["chacha20poly1305", "aes256gcm", "xchacha20poly1305"]
+#[pyfunction]
+#[pyo3(signature = (
+    pk,
+    in_path,
+    out_path,
+    algorithm = None,
+    cipher = "chacha20poly1305",
+    chunk_size = DEFAULT_CHUNK_SIZE,
+    aad = None,
+))]
+#[allow(clippy::too_many_arguments)]
+pub fn encrypt_file(
+    py: Python,
+    pk: Buffer,
+    in_path: PathBuf,
+    out_path: PathBuf,
+    algorithm: Option<&str>,
+    cipher: &str,
+    chunk_size: usize,
+    aad: Option<Buffer>,
+) -> PyResult<()> {
+    if chunk_size == 0 || chunk_size > MAX_CHUNK_SIZE {
+        return Err(PyValueError::new_err(format!(
+            "chunk_size must be between 1 and {}, got {}",
+            MAX_CHUNK_SIZE, chunk_size
+        )));
+    }
+    let aead = Aead::from_name(cipher)?;
+    let alg = kem_for(algorithm, &pk)?;
+    let mut input = File::open(&in_path)?;
+
+    let (ct, ss) = (alg.encapsulate)(py, pk)?;
+    let ss: Buffer = ss.bind(py).extract()?;
+    let mut prefix = vec![0u8; Stream::prefix_len(aead)];
+    getrandom::fill(&mut prefix).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+
+    let mut header = Vec::new();
+    header.extend_from_slice(MAGIC);
+    header.push(VERSION);
+    header.push(aead as u8);
+    header.push(alg.name.len() as u8);
+    header.extend_from_slice(alg.name.as_bytes());
+    header.extend_from_slice(&(chunk_size as u32).to_be_bytes());
+    header.extend_from_slice(ct.as_bytes(py));
+    header.extend_from_slice(&prefix);
+
+    let key = stream_key(&ss, &header);
+    let mut stream = Stream::new(aead, &key[..], prefix, &associated_data(&header, aad))?;
+
+    write_atomic_with(&out_path, |out| {
+        out.write_all(&header)?;
+        let mut chunk = read_chunk(&mut input, chunk_size)?;
+        loop {
+            let next = if chunk.len() == chunk_size {
+                read_chunk(&mut input, chunk_size)?
+            } else {
+                Vec::new()
+            };
+            let last = next.is_empty();
+            stream.encrypt_in_place(&mut chunk, last)?;
+            out.write_all(&chunk)?;
+            if last {
+                return Ok(());
+            }
+            chunk = next;
+        }
+    })
+}
+
+/// Decrypt an encrypt_file() output at `in_path` with the matching secret
+/// key and `aad`, writing `out_path`. Raises DecryptionError for the wrong
+/// key or `aad`, or a modified or truncated file.
+#[pyfunction]
+#[pyo3(signature = (sk, in_path, out_path, aad = None))]
+pub fn decrypt_file(
+    py: Python,
+    sk: Buffer,
+    in_path: PathBuf,
+    out_path: PathBuf,
+    aad: Option<Buffer>,
+) -> PyResult<()> {
+    let mut input = File::open(&in_path)?;
+
+    let mut header = Vec::new();
+    read_header_field(&mut input, &mut header, MAGIC.len() + 3)?;
+    if &header[..MAGIC.len()] != MAGIC {
+        return Err(DecryptionError::new_err("not an encrypted file"));
+    }
+    let version = header[4];
+    if version != VERSION {
+        return Err(DecryptionError::new_err(format!(
+            "unsupported encrypted file version {}",
+            version
+        )));
+    }
+    let aead = Aead::from_id(header[5]).ok_or_else(|| {
+        DecryptionError::new_err(format!("unsupported encrypted file cipher {}", header[5]))
+    })?;
+    let name_len = header[6] as usize;
+    let at = read_header_field(&mut input, &mut header, name_len)?;
+    let name = std::str::from_utf8(&header[at..])
+        .map_err(|_| DecryptionError::new_err("encrypted file has a malformed KEM name"))?;
+    let alg = find_kem(name)
+        .filter(|alg| alg.name == name)
+        .ok_or_else(|| {
+            DecryptionError::new_err(format!("encrypted file uses unknown KEM '{}'", name))
+        })?;
+    let at = read_header_field(&mut input, &mut header, 4)?;
+    let chunk_size = u32::from_be_bytes(header[at..].try_into().unwrap()) as usize;
+    if chunk_size == 0 || chunk_size > MAX_CHUNK_SIZE {
+        return Err(DecryptionError::new_err(format!(
+            "unsupported encrypted file chunk size {}",
+            chunk_size
+        )));
+    }
+    let ct_at = read_header_field(&mut input, &mut header, alg.ciphertext_bytes)?;
+    let prefix_at = read_header_field(&mut input, &mut header, Stream::prefix_len(aead))?;
+
+    let ct = PyBytes::new_bound(py, &header[ct_at..prefix_at]);
+    let ss: Buffer = (alg.decapsulate)(py, sk, ct.extract()?)?
+        .bind(py)
+        .extract()?;
+    let key = stream_key(&ss, &header);
+    let mut stream = Stream::new(
+        aead,
+        &key[..],
+        header[prefix_at..].to_vec(),
+        &associated_data(&header, aad),
+    )?;
+
+    let sealed_chunk = chunk_size + TAG_LEN;
+    write_atomic_with(&out_path, |out| {
+        let mut chunk = Zeroizing::new(read_chunk(&mut input, sealed_chunk)?);
+        loop {
+            let next = if chunk.len() == sealed_chunk {
+                Zeroizing::new(read_chunk(&mut input, sealed_chunk)?)
+            } else {
+                Zeroizing::new(Vec::new())
+            };
+            if chunk.len() < TAG_LEN {
+                return Err(DecryptionError::new_err("encrypted file is truncated"));
+            }
+            let last = next.is_empty();
+            stream.decrypt_in_place(py, &mut chunk, last)?;
+            out.write_all(&chunk)?;
+            if last {
+                return Ok(());
+            }
+            chunk = next;
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use super::*;
+
+    const CHUNK_SIZE: usize = 1000;
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("filecrypt-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir(&dir).unwrap();
+        dir
+    }
+
+    fn keypair(py: Python) -> (Vec<u8>, Vec<u8>) {
+        let (pk, sk) = (crate::mlkem768::ALGORITHM.keygen)(py).unwrap();
+        let sk: Buffer = sk.bind(py).extract().unwrap();
+        (pk.as_bytes(py).to_vec(), sk.to_vec())
+    }
+
+    fn encrypt(py: Python, pk: &[u8], from: &Path, to: &Path) {
+        let pk = Buffer::from_bytes(py, pk);
+        encrypt_file(
+            py,
+            pk,
+            from.into(),
+            to.into(),
+            None,
+            "chacha20poly1305",
+            CHUNK_SIZE,
+            None,
+        )
+        .unwrap();
+    }
+
+    fn decrypt(py: Python, sk: &[u8], from: &Path, to: &Path) -> PyResult<()> {
+        decrypt_file(py, Buffer::from_bytes(py, sk), from.into(), to.into(), None)
+    }
+
+    #[test]
+    fn round_trip_at_chunk_boundaries() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let dir = scratch_dir("round-trip");
+            let (pk, sk) = keypair(py);
+            let header_len = 4 + 3 + "mlkem768".len() + 4 + 1088 + 7;
+            // Plaintext length, then sealed chunk lengths: a full final chunk
+            // is sealed as last, with no empty chunk after it.
+            let cases: [(usize, &[usize]); 4] = [
+                (0, &[0]),
+                (1, &[1]),
+                (CHUNK_SIZE, &[CHUNK_SIZE]),
+                (CHUNK_SIZE + 1, &[CHUNK_SIZE, 1]),
+            ];
+            for (len, chunks) in cases {
+                let plaintext: Vec<u8> = (0..len).map(|i| i as u8).collect();
+                let (pt, ct, out) = (dir.join("pt"), dir.join("ct"), dir.join("out"));
+                fs::write(&pt, &plaintext).unwrap();
+                encrypt(py, &pk, &pt, &ct);
+                let sealed: usize = chunks.iter().map(|n| n + TAG_LEN).sum();
+                assert_eq!(
+                    fs::metadata(&ct).unwrap().len() as usize,
+                    header_len + sealed
+                );
+                decrypt(py, &sk, &ct, &out).unwrap();
+                assert_eq!(fs::read(&out).unwrap(), plaintext, "length {}", len);
+            }
+            fs::remove_dir_all(&dir).unwrap();
+        });
+    }
+
+    #[test]
+    fn truncated_file_fails_without_output() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let dir = scratch_dir("truncated");
+            let (pk, sk) = keypair(py);
+            let (pt, ct, cut, out) = (
+                dir.join("pt"),
+                dir.join("ct"),
+                dir.join("cut"),
+                dir.join("out"),
+            );
+            fs::write(&pt, vec![0x5a; 2 * CHUNK_SIZE + 500]).unwrap();
+            encrypt(py, &pk, &pt, &ct);
+            let full = fs::read(&ct).unwrap();
+            let last_chunk = full.len() - (500 + TAG_LEN);
+            // Inside the header, inside a chunk, and exactly at a chunk
+            // boundary, where the chunk before the cut isn't sealed as last.
+            for len in [0, 20, last_chunk - 100, last_chunk, full.len() - 1] {
+                fs::write(&cut, &full[..len]).unwrap();
+                let err = decrypt(py, &sk, &cut, &out).unwrap_err();
+                assert!(err.is_instance_of::<DecryptionError>(py), "length {}", len);
+                assert!(!out.exists(), "length {}", len);
+            }
+            let mut left: Vec<_> = fs::read_dir(&dir)
+                .unwrap()
+                .map(|e| e.unwrap().file_name().into_string().unwrap())
+                .collect();
+            left.sort();
+            assert_eq!(left, ["ct", "cut", "pt"]);
+            fs::remove_dir_all(&dir).unwrap();
+        });
+    }
+}
@@ -0,0 +1,1692 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// HPKE (RFC 9180) with ML-KEM and X-Wing
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// Hybrid Public Key Encryption, for protocols that specify their messages in
+// terms of an HPKE suite:
+//
+//   suite = HpkeSuite("xwing", "hkdf-sha256", "chacha20poly1305")
+//   enc, ct = suite.seal(pk, b"hello", info=b"app v1", aad=b"hdr")
+//   msg = suite.open(sk, enc, ct, info=b"app v1", aad=b"hdr")
+//
+// or with one context for several messages, plus the secret exporter:
+//
+//   enc, ctx = suite.setup_sender(pk, info=b"app v1")
+//   ct0 = ctx.seal(b"first")
+//   ct1 = ctx.seal(b"second")
+//   key = ctx.export(b"session key", 32)
+//
+//   ctx = suite.setup_receiver(sk, enc, info=b"app v1")
+//
+// For a long-lived one-way feed, suite.sender(pk) / suite.receiver(sk, enc)
+// frame each message with its sequence number, so the receiver tolerates
+// lost frames and rejects replays:
+//
+//   tx = suite.sender(pk, info=b"logs")       # ship tx.enc first
+//   frame = tx.seal(b"line 1")
+//   rx = suite.receiver(sk, tx.enc, info=b"logs")
+//   line = rx.open(frame)
+//
+// hpke_seal() / hpke_open() are the single-shot calls with the suite given by
+// name. Swapping suites changes only the three names; `enc` and the
+// ciphertexts stay opaque byte strings.
+//
+// KEMs: ML-KEM-512 / 768 / 1024 (ids 0x0040-0x0042) and X-Wing, i.e.
+// MLKEM768-X25519 (0x647a), as in draft-ietf-hpke-pq, where the KEM's own
+// encapsulation is used as is; and DHKEM(X25519, HKDF-SHA256) (0x0020). KDFs:
+// HKDF-SHA256 / 384 / 512. AEADs: AES-128-GCM, AES-256-GCM, ChaCha20-Poly1305
+// and "export-only", which has no seal() / open().
+//
+// The mode follows from the arguments: psk and psk_id (both or neither; the
+// PSK at least 32 bytes) select mode_psk, the sender's secret key (and on the
+// receiving side, their public key) mode_auth, both mode_auth_psk. Auth
+// modes need a KEM with AuthEncap, which among these only DHKEM(X25519) has;
+// with a PQ KEM, authenticate the sender with a signature instead. A wrong
+// key, info, psk or aad, or a modified ciphertext, raises DecryptionError.
+
+use aes_gcm::aead::{AeadInOut, KeyInit};
+use aes_gcm::Aes128Gcm;
+use hkdf::Hkdf;
+use pyo3::exceptions::{PyRuntimeError, PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use sha2::{Sha256, Sha384, Sha512};
+use zeroize::Zeroizing;
+
+use crate::aead::Aead;
+use crate::buffer::Buffer;
+use crate::classical::random_key;
+use crate::errors::{check_length, DecryptionError, InvalidKeyError, InvalidLengthError};
+use crate::kem::{normalize_name, KemAlgorithm};
+use crate::secret::{secret_bytes, SecretBytes};
+
+const VERSION_LABEL: &[u8] = b"HPKE-v1";
+const MIN_PSK_LEN: usize = 32;
+
+const MODE_BASE: u8 = 0;
+const MODE_PSK: u8 = 1;
+const MODE_AUTH: u8 = 2;
+const MODE_AUTH_PSK: u8 = 3;
+
+// ─── KDF ──────────────────────────────────────────────────────────────────────
+
+#[derive(Clone, Copy)]
+enum Kdf {
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl Kdf {
+    fn from_name(name: &str) -> PyResult<Self> {
+        match normalize_name(name).as_str() {
+            "hkdfsha256" => Ok(Kdf::Sha256),
+            "hkdfsha384" => Ok(Kdf::Sha384),
+            "hkdfsha512" => Ok(Kdf::Sha512),
+            _ => Err(PyValueError::new_err(format!(
+                "unsupported HPKE KDF '{}', expected hkdf-sha256, hkdf-sha384 or hkdf-sha512",
+                name
+            ))),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Kdf::Sha256 => "hkdf-sha256",
+            Kdf::Sha384 => "hkdf-sha384",
+            Kdf::Sha512 => "hkdf-sha512",
+        }
+    }
+
+    fn id(self) -> u16 {
+        match self {
+            Kdf::Sha256 => 0x0001,
+            Kdf::Sha384 => 0x0002,
+            Kdf::Sha512 => 0x0003,
+        }
+    }
+
+    /// Nh, the hash output length.
+    fn hash_len(self) -> usize {
+        match self {
+            Kdf::Sha256 => 32,
+            Kdf::Sha384 => 48,
+            Kdf::Sha512 => 64,
+        }
+    }
+
+    fn extract(self, salt: &[u8], ikm: &[u8]) -> Zeroizing<Vec<u8>> {
+        Zeroizing::new(match self {
+            Kdf::Sha256 => Hkdf::<Sha256>::extract(Some(salt), ikm).0.to_vec(),
+            Kdf::Sha384 => Hkdf::<Sha384>::extract(Some(salt), ikm).0.to_vec(),
+            Kdf::Sha512 => Hkdf::<Sha512>::extract(Some(salt), ikm).0.to_vec(),
+        })
+    }
+
+    fn expand(self, prk: &[u8], info: &[u8], length: usize) -> PyResult<Zeroizing<Vec<u8>>> {
+        let mut okm = Zeroizing::new(vec![0u8; length]);
+        match self {
+            Kdf::Sha256 => Hkdf::<Sha256>::from_prk(prk)
+                .expect("PRK from extract")
+                .expand(info, &mut okm),
+            Kdf::Sha384 => Hkdf::<Sha384>::from_prk(prk)
+                .expect("PRK from extract")
+                .expand(info, &mut okm),
+            Kdf::Sha512 => Hkdf::<Sha512>::from_prk(prk)
+                .expect("PRK from extract")
+                .expand(info, &mut okm),
+        }
+        .map_err(|_| {
+            PyValueError::new_err(format!(
+                "{} can't output {} bytes (at most {})",
+                self.name(),
+                length,
+                255 * self.hash_len()
+            ))
+        })?;
+        Ok(okm)
+    }
+
+    fn labeled_extract(
+        self,
+        suite_id: &[u8],
+        salt: &[u8],
+        label: &[u8],
+        ikm: &[u8],
+    ) -> Zeroizing<Vec<u8>> {
+        let labeled_ikm = Zeroizing::new([VERSION_LABEL, suite_id, label, ikm].concat());
+        self.extract(salt, &labeled_ikm)
+    }
+
+    fn labeled_expand(
+        self,
+        suite_id: &[u8],
+        prk: &[u8],
+        label: &[u8],
+        info: &[u8],
+        length: usize,
+    ) -> PyResult<Zeroizing<Vec<u8>>> {
+        let l = u16::try_from(length).map_err(|_| {
+            PyValueError::new_err(format!("HPKE output length {} is too long", length))
+        })?;
+        let labeled_info = [&l.to_be_bytes()[..], VERSION_LABEL, suite_id, label, info].concat();
+        self.expand(prk, &labeled_info, length)
+    }
+}
+
+// ─── AEAD ─────────────────────────────────────────────────────────────────────
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum HpkeAead {
+    Aes128Gcm,
+    Aes256Gcm,
+    ChaCha20Poly1305,
+    ExportOnly,
+}
+
+impl HpkeAead {
+    fn from_name(name: &str) -> PyResult<Self> {
+        match normalize_name(name).as_str() {
+            "aes128gcm" => Ok(HpkeAead::Aes128Gcm),
+            "aes256gcm" => Ok(HpkeAead::Aes256Gcm),
+            "chacha20poly1305" => Ok(HpkeAead::ChaCha20Poly1305),
+            "exportonly" => Ok(HpkeAead::ExportOnly),
+            _ => Err(PyValueError::new_err(format!(
+                "unsupported HPKE AEAD '{}', expected aes128gcm, aes256gcm, chacha20poly1305 \
+                 or export-only",
+                name
+            ))),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            HpkeAead::Aes128Gcm => "aes128gcm",
+            HpkeAead::Aes256Gcm => "aes256gcm",
+            HpkeAead::ChaCha20Poly1305 => "chacha20poly1305",
+            HpkeAead::ExportOnly => "export-only",
+        }
+    }
+
+    fn id(self) -> u16 {
+        match self {
+            HpkeAead::Aes128Gcm => 0x0001,
+            HpkeAead::Aes256Gcm => 0x0002,
+            HpkeAead::ChaCha20Poly1305 => 0x0003,
+            HpkeAead::ExportOnly => 0xffff,
+        }
+    }
+
+    /// Nk and Nn.
+    fn key_nonce_len(self) -> (usize, usize) {
+        match self {
+            HpkeAead::Aes128Gcm => (16, 12),
+            HpkeAead::Aes256Gcm | HpkeAead::ChaCha20Poly1305 => (32, 12),
+            HpkeAead::ExportOnly => (0, 0),
+        }
+    }
+
+    fn seal(self, key: &[u8], nonce: &[u8], aad: &[u8], buf: &mut Vec<u8>) -> PyResult<()> {
+        match self {
+            HpkeAead::Aes128Gcm => Aes128Gcm::new_from_slice(key)
+                .expect("key length from the key schedule")
+                .encrypt_in_place(nonce.try_into().expect("nonce length"), aad, buf)
+                .map_err(|_| PyRuntimeError::new_err("AES-128-GCM encryption failed")),
+            HpkeAead::Aes256Gcm => Aead::Aes256Gcm.encrypt(key, nonce, aad, buf),
+            HpkeAead::ChaCha20Poly1305 => Aead::ChaCha20Poly1305.encrypt(key, nonce, aad, buf),
+            HpkeAead::ExportOnly => Err(export_only()),
+        }
+    }
+
+    fn open(self, key: &[u8], nonce: &[u8], aad: &[u8], buf: &mut Vec<u8>) -> PyResult<()> {
+        let result = match self {
+            HpkeAead::Aes128Gcm => Aes128Gcm::new_from_slice(key)
+                .expect("key length from the key schedule")
+                .decrypt_in_place(nonce.try_into().expect("nonce length"), aad, buf)
+                .map_err(|_| DecryptionError::new_err("")),
+            HpkeAead::Aes256Gcm => Aead::Aes256Gcm.decrypt(key, nonce, aad, buf),
+            HpkeAead::ChaCha20Poly1305 => Aead::ChaCha20Poly1305.decrypt(key, nonce, aad, buf),
+            HpkeAead::ExportOnly => return Err(export_only()),
+        };
+        result.map_err(|_| {
+            DecryptionError::new_err(
+                "HPKE decryption failed: wrong key, info, psk or aad, or a modified ciphertext",
+            )
+        })
+    }
+}
+
+fn export_only() -> PyErr {
+    PyValueError::new_err("the export-only HPKE AEAD can't seal or open; use export()")
+}
+
+// ─── KEM ──────────────────────────────────────────────────────────────────────
+
+const X25519_LEN: usize = 32;
+
+#[derive(Clone, Copy)]
+enum Kem {
+    /// DHKEM(X25519, HKDF-SHA256).
+    X25519,
+    /// A KEM used directly, as draft-ietf-hpke-pq does for ML-KEM and X-Wing.
+    Direct { alg: &'static KemAlgorithm, id: u16 },
+}
+
+impl Kem {
+    fn from_name(name: &str) -> PyResult<Self> {
+        let direct = |alg, id| Ok(Kem::Direct { alg, id });
+        match normalize_name(name).as_str() {
+            "x25519" | "dhkemx25519" => Ok(Kem::X25519),
+            "mlkem512" => direct(&crate::mlkem512::ALGORITHM, 0x0040),
+            "mlkem768" => direct(&crate::mlkem768::ALGORITHM, 0x0041),
+            "mlkem1024" => direct(&crate::mlkem1024::ALGORITHM, 0x0042),
+            "xwing" | "mlkem768x25519" => direct(&crate::hybrid::XWING, 0x647a),
+            _ => Err(PyValueError::new_err(format!(
+                "unsupported HPKE KEM '{}', expected mlkem512, mlkem768, mlkem1024, xwing or \
+                 x25519",
+                name
+            ))),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Kem::X25519 => "x25519",
+            Kem::Direct { alg, .. } => alg.name,
+        }
+    }
+
+    fn id(self) -> u16 {
+        match self {
+            Kem::X25519 => 0x0020,
+            Kem::Direct { id, .. } => id,
+        }
+    }
+
+    fn check_auth(self) -> PyResult<()> {
+        match self {
+            Kem::X25519 => Ok(()),
+            Kem::Direct { alg, .. } => Err(PyValueError::new_err(format!(
+                "HPKE auth modes need a KEM with AuthEncap, which {} doesn't have",
+                alg.name
+            ))),
+        }
+    }
+
+    /// Encap / AuthEncap: (enc, shared secret).
+    fn encap(
+        self,
+        py: Python,
+        pk: Buffer,
+        sender_sk: Option<&[u8]>,
+    ) -> PyResult<(Vec<u8>, Zeroizing<Vec<u8>>)> {
+        match self {
+            Kem::X25519 => {
+                let sk_e = x25519_dalek::StaticSecret::from(*random_key()?);
+                x25519_encap(&pk, &sk_e, sender_sk)
+            }
+            Kem::Direct { alg, .. } => {
+                let (ct, ss) = (alg.encapsulate)(py, pk)?;
+                let ss: Buffer = ss.bind(py).extract()?;
+                Ok((ct.as_bytes(py).to_vec(), Zeroizing::new(ss.to_vec())))
+            }
+        }
+    }
+
+    /// Decap / AuthDecap.
+    fn decap(
+        self,
+        py: Python,
+        sk: Buffer,
+        enc: Buffer,
+        sender_pk: Option<&[u8]>,
+    ) -> PyResult<Zeroizing<Vec<u8>>> {
+        match self {
+            Kem::X25519 => x25519_decap(&sk, &enc, sender_pk),
+            Kem::Direct { alg, .. } => {
+                check_length(
+                    &format!("HPKE {} enc", alg.name),
+                    &enc,
+                    alg.ciphertext_bytes,
+                )?;
+                let ss: Buffer = (alg.decapsulate)(py, sk, enc)?.bind(py).extract()?;
+                Ok(Zeroizing::new(ss.to_vec()))
+            }
+        }
+    }
+}
+
+/// DHKEM(X25519) Encap / AuthEncap with the ephemeral key `sk_e`.
+fn x25519_encap(
+    pk: &[u8],
+    sk_e: &x25519_dalek::StaticSecret,
+    sender_sk: Option<&[u8]>,
+) -> PyResult<(Vec<u8>, Zeroizing<Vec<u8>>)> {
+    let pk_r = x25519_public("HPKE X25519 recipient public key", pk)?;
+    let enc = x25519_dalek::PublicKey::from(sk_e);
+    let mut dh = Zeroizing::new(x25519(sk_e, &pk_r)?.to_vec());
+    let mut kem_context = [&enc.as_bytes()[..], pk_r.as_bytes()].concat();
+    if let Some(sk_s) = sender_sk {
+        let sk_s = x25519_secret("HPKE X25519 sender secret key", sk_s)?;
+        dh.extend_from_slice(&x25519(&sk_s, &pk_r)?[..]);
+        kem_context.extend_from_slice(x25519_dalek::PublicKey::from(&sk_s).as_bytes());
+    }
+    Ok((
+        enc.as_bytes().to_vec(),
+        dhkem_shared_secret(&dh, &kem_context)?,
+    ))
+}
+
+/// DHKEM(X25519) Decap / AuthDecap.
+fn x25519_decap(sk: &[u8], enc: &[u8], sender_pk: Option<&[u8]>) -> PyResult<Zeroizing<Vec<u8>>> {
+    let sk_r = x25519_secret("HPKE X25519 recipient secret key", sk)?;
+    let pk_e = x25519_public("HPKE X25519 enc", enc)?;
+    let pk_r = x25519_dalek::PublicKey::from(&sk_r);
+    let mut dh = Zeroizing::new(x25519(&sk_r, &pk_e)?.to_vec());
+    let mut kem_context = [&pk_e.as_bytes()[..], pk_r.as_bytes()].concat();
+    if let Some(pk_s) = sender_pk {
+        let pk_s = x25519_public("HPKE X25519 sender public key", pk_s)?;
+        dh.extend_from_slice(&x25519(&sk_r, &pk_s)?[..]);
+        kem_context.extend_from_slice(pk_s.as_bytes());
+    }
+    dhkem_shared_secret(&dh, &kem_context)
+}
+
+fn x25519_public(what: &str, bytes: &[u8]) -> PyResult<x25519_dalek::PublicKey> {
+    check_length(what, bytes, X25519_LEN)?;
+    Ok(x25519_dalek::PublicKey::from(
+        <[u8; X25519_LEN]>::try_from(bytes).unwrap(),
+    ))
+}
+
+fn x25519_secret(what: &str, bytes: &[u8]) -> PyResult<x25519_dalek::StaticSecret> {
+    check_length(what, bytes, X25519_LEN)?;
+    let bytes = Zeroizing::new(<[u8; X25519_LEN]>::try_from(bytes).unwrap());
+    Ok(x25519_dalek::StaticSecret::from(*bytes))
+}
+
+fn x25519(
+    sk: &x25519_dalek::StaticSecret,
+    pk: &x25519_dalek::PublicKey,
+) -> PyResult<Zeroizing<[u8; X25519_LEN]>> {
+    let ss = sk.diffie_hellman(pk);
+    if !ss.was_contributory() {
+        return Err(InvalidKeyError::new_err(
+            "X25519 public key is a low-order point",
+        ));
+    }
+    Ok(Zeroizing::new(ss.to_bytes()))
+}
+
+/// DHKEM's ExtractAndExpand.
+fn dhkem_shared_secret(dh: &[u8], kem_context: &[u8]) -> PyResult<Zeroizing<Vec<u8>>> {
+    let suite_id = [&b"KEM"[..], &Kem::X25519.id().to_be_bytes()].concat();
+    let prk = Kdf::Sha256.labeled_extract(&suite_id, b"", b"eae_prk", dh);
+    Kdf::Sha256.labeled_expand(&suite_id, &prk, b"shared_secret", kem_context, 32)
+}
+
+// ─── Key schedule ─────────────────────────────────────────────────────────────
+
+#[derive(Clone, Copy)]
+struct Suite {
+    kem: Kem,
+    kdf: Kdf,
+    aead: HpkeAead,
+}
+
+impl Suite {
+    fn from_names(kem: &str, kdf: &str, aead: &str) -> PyResult<Self> {
+        Ok(Suite {
+            kem: Kem::from_name(kem)?,
+            kdf: Kdf::from_name(kdf)?,
+            aead: HpkeAead::from_name(aead)?,
+        })
+    }
+
+    fn id(self) -> Vec<u8> {
+        [
+            &b"HPKE"[..],
+            &self.kem.id().to_be_bytes(),
+            &self.kdf.id().to_be_bytes(),
+            &self.aead.id().to_be_bytes(),
+        ]
+        .concat()
+    }
+
+    /// The mode for these optional inputs, after RFC 9180's VerifyPSKInputs.
+    fn mode(self, psk: &Psk, auth: bool) -> PyResult<u8> {
+        let with_psk = match (psk.psk, psk.psk_id) {
+            (None, None) => false,
+            (Some(psk), Some(_)) => {
+                if psk.len() < MIN_PSK_LEN {
+                    return Err(InvalidLengthError::new_err(format!(
+                        "HPKE psk: expected at least {} bytes, got {}",
+                        MIN_PSK_LEN,
+                        psk.len()
+                    )));
+                }
+                true
+            }
+            _ => {
+                return Err(PyValueError::new_err(
+                    "pass both psk and psk_id, or neither",
+                ))
+            }
+        };
+        if auth {
+            self.kem.check_auth()?;
+        }
+        Ok(match (with_psk, auth) {
+            (false, false) => MODE_BASE,
+            (true, false) => MODE_PSK,
+            (false, true) => MODE_AUTH,
+            (true, true) => MODE_AUTH_PSK,
+        })
+    }
+
+    fn key_schedule(
+        self,
+        mode: u8,
+        shared_secret: &[u8],
+        info: &[u8],
+        psk: &Psk,
+        role: Role,
+    ) -> PyResult<Context> {
+        let suite_id = self.id();
+        let kdf = self.kdf;
+        let psk_id_hash = kdf.labeled_extract(
+            &suite_id,
+            b"",
+            b"psk_id_hash",
+            psk.psk_id.unwrap_or_default(),
+        );
+        let info_hash = kdf.labeled_extract(&suite_id, b"", b"info_hash", info);
+        let context = [&[mode][..], &psk_id_hash, &info_hash].concat();
+
+        let secret = kdf.labeled_extract(
+            &suite_id,
+            shared_secret,
+            b"secret",
+            psk.psk.unwrap_or_default(),
+        );
+        let (nk, nn) = self.aead.key_nonce_len();
+        let (key, base_nonce) = if self.aead == HpkeAead::ExportOnly {
+            (Zeroizing::new(Vec::new()), Vec::new())
+        } else {
+            (
+                kdf.labeled_expand(&suite_id, &secret, b"key", &context, nk)?,
+                kdf.labeled_expand(&suite_id, &secret, b"base_nonce", &context, nn)?
+                    .to_vec(),
+            )
+        };
+        let exporter_secret =
+            kdf.labeled_expand(&suite_id, &secret, b"exp", &context, kdf.hash_len())?;
+        Ok(Context {
+            suite: self,
+            role,
+            key,
+            base_nonce,
+            exporter_secret,
+            seq: 0,
+        })
+    }
+
+    fn setup_sender(
+        self,
+        py: Python,
+        pk: Buffer,
+        info: &[u8],
+        psk: &Psk,
+        sender_sk: Option<&[u8]>,
+    ) -> PyResult<(Vec<u8>, Context)> {
+        let mode = self.mode(psk, sender_sk.is_some())?;
+        let (enc, shared_secret) = self.kem.encap(py, pk, sender_sk)?;
+        let ctx = self.key_schedule(mode, &shared_secret, info, psk, Role::Sender)?;
+        Ok((enc, ctx))
+    }
+
+    fn setup_receiver(
+        self,
+        py: Python,
+        sk: Buffer,
+        enc: Buffer,
+        info: &[u8],
+        psk: &Psk,
+        sender_pk: Option<&[u8]>,
+    ) -> PyResult<Context> {
+        let mode = self.mode(psk, sender_pk.is_some())?;
+        let shared_secret = self.kem.decap(py, sk, enc, sender_pk)?;
+        self.key_schedule(mode, &shared_secret, info, psk, Role::Receiver)
+    }
+}
+
+/// The optional pre-shared key and its id.
+struct Psk<'a> {
+    psk: Option<&'a [u8]>,
+    psk_id: Option<&'a [u8]>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Role {
+    Sender,
+    Receiver,
+}
+
+struct Context {
+    suite: Suite,
+    role: Role,
+    key: Zeroizing<Vec<u8>>,
+    base_nonce: Vec<u8>,
+    exporter_secret: Zeroizing<Vec<u8>>,
+    seq: u64,
+}
+
+impl Context {
+    /// The nonce for message `seq`: base_nonce XOR seq.
+    fn nonce(&self, seq: u64) -> PyResult<Vec<u8>> {
+        if seq == u64::MAX {
+            return Err(PyValueError::new_err(
+                "HPKE context has used up its sequence numbers",
+            ));
+        }
+        let mut nonce = self.base_nonce.clone();
+        let start = nonce.len() - 8;
+        for (n, s) in nonce[start..].iter_mut().zip(seq.to_be_bytes()) {
+            *n ^= s;
+        }
+        Ok(nonce)
+    }
+
+    /// Encrypt message number `seq`, leaving the sequence number alone.
+    fn seal_at(&self, seq: u64, plaintext: &[u8], aad: &[u8]) -> PyResult<Vec<u8>> {
+        if self.role != Role::Sender {
+            return Err(PyValueError::new_err(
+                "a receiver's HPKE context can only open()",
+            ));
+        }
+        if self.suite.aead == HpkeAead::ExportOnly {
+            return Err(export_only());
+        }
+        let nonce = self.nonce(seq)?;
+        let mut buf = plaintext.to_vec();
+        self.suite.aead.seal(&self.key, &nonce, aad, &mut buf)?;
+        Ok(buf)
+    }
+
+    /// Decrypt message number `seq`, leaving the sequence number alone.
+    fn open_at(&self, seq: u64, ciphertext: &[u8], aad: &[u8]) -> PyResult<Zeroizing<Vec<u8>>> {
+        if self.role != Role::Receiver {
+            return Err(PyValueError::new_err(
+                "a sender's HPKE context can only seal()",
+            ));
+        }
+        if self.suite.aead == HpkeAead::ExportOnly {
+            return Err(export_only());
+        }
+        let nonce = self.nonce(seq)?;
+        let mut buf = Zeroizing::new(ciphertext.to_vec());
+        self.suite.aead.open(&self.key, &nonce, aad, &mut buf)?;
+        Ok(buf)
+    }
+
+    fn seal(&mut self, plaintext: &[u8], aad: &[u8]) -> PyResult<Vec<u8>> {
+        let ct = self.seal_at(self.seq, plaintext, aad)?;
+        self.seq += 1;
+        Ok(ct)
+    }
+
+    fn open(&mut self, ciphertext: &[u8], aad: &[u8]) -> PyResult<Zeroizing<Vec<u8>>> {
+        let pt = self.open_at(self.seq, ciphertext, aad)?;
+        self.seq += 1;
+        Ok(pt)
+    }
+
+    fn export(&self, exporter_context: &[u8], length: usize) -> PyResult<Zeroizing<Vec<u8>>> {
+        let kdf = self.suite.kdf;
+        if length > 255 * kdf.hash_len() {
+            return Err(PyValueError::new_err(format!(
+                "{} exports at most {} bytes, got {}",
+                kdf.name(),
+                255 * kdf.hash_len(),
+                length
+            )));
+        }
+        kdf.labeled_expand(
+            &self.suite.id(),
+            &self.exporter_secret,
+            b"sec",
+            exporter_context,
+            length,
+        )
+    }
+}
+
+// ─── Python API ───────────────────────────────────────────────────────────────
+
+#[pyclass(module = "pqcrypto_bindings", frozen)]
+pub struct HpkeSuite {
+    suite: Suite,
+}
+
+#[pymethods]
+impl HpkeSuite {
+    /// The suite of KEM, KDF and AEAD named, e.g. ("mlkem768",
+    /// "hkdf-sha256", "aes128gcm").
+    // stub: kdf: Literal["hkdf-sha256", "hkdf-sha384", "hkdf-sha512"]
+    // stub: aead: Literal["aes128gcm", "aes256gcm", "chacha20poly1305", "export-only"]
+    #[new]
+    #[pyo3(signature = (kem, kdf = "hkdf-sha256", aead = "chacha20poly1305"))]
+    fn new(kem: &str, kdf: &str, aead: &str) -> PyResult<Self> {
+        Ok(HpkeSuite {
+            suite: Suite::from_names(kem, kdf, aead)?,
+        })
+    }
+
+    #[getter]
+    fn kem(&self) -> &'static str {
+        self.suite.kem.name()
+    }
+
+    #[getter]
+    fn kdf(&self) -> &'static str {
+        self.suite.kdf.name()
+    }
+
+    #[getter]
+    fn aead(&self) -> &'static str {
+        self.suite.aead.name()
+    }
+
+    /// The IANA (kem_id, kdf_id, aead_id).
+    #[getter]
+    fn ids(&self) -> (u16, u16, u16) {
+        (
+            self.suite.kem.id(),
+            self.suite.kdf.id(),
+            self.suite.aead.id(),
+        )
+    }
+
+    /// Encapsulate to `pk`: (enc, sender context).
+    #[pyo3(signature = (pk, info = None, psk = None, psk_id = None, sender_sk = None))]
+    fn setup_sender(
+        &self,
+        py: Python,
+        pk: Buffer,
+        info: Option<Buffer>,
+        psk: Option<Buffer>,
+        psk_id: Option<Buffer>,
+        sender_sk: Option<Buffer>,
+    ) -> PyResult<(Py<PyBytes>, HpkeContext)> {
+        let psk = Psk {
+            psk: psk.as_deref(),
+            psk_id: psk_id.as_deref(),
+        };
+        let info = info.as_deref().unwrap_or_default();
+        let (enc, ctx) = self
+            .suite
+            .setup_sender(py, pk, info, &psk, sender_sk.as_deref())?;
+        Ok((PyBytes::new_bound(py, &enc).unbind(), HpkeContext { ctx }))
+    }
+
+    /// Decapsulate `enc` with `sk`: the receiver context.
+    #[pyo3(signature = (sk, enc, info = None, psk = None, psk_id = None, sender_pk = None))]
+    #[allow(clippy::too_many_arguments)]
+    fn setup_receiver(
+        &self,
+        py: Python,
+        sk: Buffer,
+        enc: Buffer,
+        info: Option<Buffer>,
+        psk: Option<Buffer>,
+        psk_id: Option<Buffer>,
+        sender_pk: Option<Buffer>,
+    ) -> PyResult<HpkeContext> {
+        let psk = Psk {
+            psk: psk.as_deref(),
+            psk_id: psk_id.as_deref(),
+        };
+        let info = info.as_deref().unwrap_or_default();
+        let ctx = self
+            .suite
+            .setup_receiver(py, sk, enc, info, &psk, sender_pk.as_deref())?;
+        Ok(HpkeContext { ctx })
+    }
+
+    /// Single-shot encryption to `pk`: (enc, ciphertext).
+    #[pyo3(signature = (
+        pk,
+        plaintext,
+        info = None,
+        aad = None,
+        psk = None,
+        psk_id = None,
+        sender_sk = None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn seal(
+        &self,
+        py: Python,
+        pk: Buffer,
+        plaintext: Buffer,
+        info: Option<Buffer>,
+        aad: Option<Buffer>,
+        psk: Option<Buffer>,
+        psk_id: Option<Buffer>,
+        sender_sk: Option<Buffer>,
+    ) -> PyResult<(Py<PyBytes>, Py<PyBytes>)> {
+        let psk = Psk {
+            psk: psk.as_deref(),
+            psk_id: psk_id.as_deref(),
+        };
+        let info = info.as_deref().unwrap_or_default();
+        let (enc, mut ctx) = self
+            .suite
+            .setup_sender(py, pk, info, &psk, sender_sk.as_deref())?;
+        let ct = ctx.seal(&plaintext, aad.as_deref().unwrap_or_default())?;
+        Ok((
+            PyBytes::new_bound(py, &enc).unbind(),
+            PyBytes::new_bound(py, &ct).unbind(),
+        ))
+    }
+
+    /// Single-shot decryption of a seal() output.
+    #[pyo3(signature = (
+        sk,
+        enc,
+        ciphertext,
+        info = None,
+        aad = None,
+        psk = None,
+        psk_id = None,
+        sender_pk = None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn open(
+        &self,
+        py: Python,
+        sk: Buffer,
+        enc: Buffer,
+        ciphertext: Buffer,
+        info: Option<Buffer>,
+        aad: Option<Buffer>,
+        psk: Option<Buffer>,
+        psk_id: Option<Buffer>,
+        sender_pk: Option<Buffer>,
+    ) -> PyResult<Py<PyBytes>> {
+        let psk = Psk {
+            psk: psk.as_deref(),
+            psk_id: psk_id.as_deref(),
+        };
+        let info = info.as_deref().unwrap_or_default();
+        let mut ctx = self
+            .suite
+            .setup_receiver(py, sk, enc, info, &psk, sender_pk.as_deref())?;
+        let pt = ctx.open(&ciphertext, aad.as_deref().unwrap_or_default())?;
+        Ok(PyBytes::new_bound(py, &pt).unbind())
+    }
+
+    /// Single-shot secret export to `pk`: (enc, exported secret).
+    #[pyo3(signature = (
+        pk,
+        exporter_context,
+        length,
+        info = None,
+        psk = None,
+        psk_id = None,
+        sender_sk = None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn send_export(
+        &self,
+        py: Python,
+        pk: Buffer,
+        exporter_context: Buffer,
+        length: usize,
+        info: Option<Buffer>,
+        psk: Option<Buffer>,
+        psk_id: Option<Buffer>,
+        sender_sk: Option<Buffer>,
+    ) -> PyResult<(Py<PyBytes>, Py<SecretBytes>)> {
+        let psk = Psk {
+            psk: psk.as_deref(),
+            psk_id: psk_id.as_deref(),
+        };
+        let info = info.as_deref().unwrap_or_default();
+        let (enc, ctx) = self
+            .suite
+            .setup_sender(py, pk, info, &psk, sender_sk.as_deref())?;
+        let secret = ctx.export(&exporter_context, length)?;
+        Ok((
+            PyBytes::new_bound(py, &enc).unbind(),
+            secret_bytes(py, &secret)?,
+        ))
+    }
+
+    /// The receiving side of send_export().
+    #[pyo3(signature = (
+        sk,
+        enc,
+        exporter_context,
+        length,
+        info = None,
+        psk = None,
+        psk_id = None,
+        sender_pk = None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn receive_export(
+        &self,
+        py: Python,
+        sk: Buffer,
+        enc: Buffer,
+        exporter_context: Buffer,
+        length: usize,
+        info: Option<Buffer>,
+        psk: Option<Buffer>,
+        psk_id: Option<Buffer>,
+        sender_pk: Option<Buffer>,
+    ) -> PyResult<Py<SecretBytes>> {
+        let psk = Psk {
+            psk: psk.as_deref(),
+            psk_id: psk_id.as_deref(),
+        };
+        let info = info.as_deref().unwrap_or_default();
+        let ctx = self
+            .suite
+            .setup_receiver(py, sk, enc, info, &psk, sender_pk.as_deref())?;
+        secret_bytes(py, &ctx.export(&exporter_context, length)?)
+    }
+
+    /// A channel for a sequence of messages to `pk`; send its `enc` ahead of
+    /// the first frame.
+    #[pyo3(signature = (pk, info = None, psk = None, psk_id = None, sender_sk = None))]
+    fn sender(
+        &self,
+        py: Python,
+        pk: Buffer,
+        info: Option<Buffer>,
+        psk: Option<Buffer>,
+        psk_id: Option<Buffer>,
+        sender_sk: Option<Buffer>,
+    ) -> PyResult<HpkeSender> {
+        let psk = Psk {
+            psk: psk.as_deref(),
+            psk_id: psk_id.as_deref(),
+        };
+        let info = info.as_deref().unwrap_or_default();
+        let (enc, ctx) = self
+            .suite
+            .setup_sender(py, pk, info, &psk, sender_sk.as_deref())?;
+        Ok(HpkeSender { enc, ctx })
+    }
+
+    /// The receiving end of sender().
+    #[pyo3(signature = (sk, enc, info = None, psk = None, psk_id = None, sender_pk = None))]
+    #[allow(clippy::too_many_arguments)]
+    fn receiver(
+        &self,
+        py: Python,
+        sk: Buffer,
+        enc: Buffer,
+        info: Option<Buffer>,
+        psk: Option<Buffer>,
+        psk_id: Option<Buffer>,
+        sender_pk: Option<Buffer>,
+    ) -> PyResult<HpkeReceiver> {
+        let psk = Psk {
+            psk: psk.as_deref(),
+            psk_id: psk_id.as_deref(),
+        };
+        let info = info.as_deref().unwrap_or_default();
+        let ctx = self
+            .suite
+            .setup_receiver(py, sk, enc, info, &psk, sender_pk.as_deref())?;
+        Ok(HpkeReceiver { ctx, missed: 0 })
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "HpkeSuite('{}', '{}', '{}')",
+            self.kem(),
+            self.kdf(),
+            self.aead()
+        )
+    }
+}
+
+#[pyclass(module = "pqcrypto_bindings")]
+pub struct HpkeContext {
+    ctx: Context,
+}
+
+#[pymethods]
+impl HpkeContext {
+    /// Encrypt the next message (sender contexts only).
+    #[pyo3(signature = (plaintext, aad = None))]
+    fn seal(
+        &mut self,
+        py: Python,
+        plaintext: Buffer,
+        aad: Option<Buffer>,
+    ) -> PyResult<Py<PyBytes>> {
+        let ct = self
+            .ctx
+            .seal(&plaintext, aad.as_deref().unwrap_or_default())?;
+        Ok(PyBytes::new_bound(py, &ct).unbind())
+    }
+
+    /// Decrypt the next message, in the order they were sealed (receiver
+    /// contexts only).
+    #[pyo3(signature = (ciphertext, aad = None))]
+    fn open(
+        &mut self,
+        py: Python,
+        ciphertext: Buffer,
+        aad: Option<Buffer>,
+    ) -> PyResult<Py<PyBytes>> {
+        let pt = self
+            .ctx
+            .open(&ciphertext, aad.as_deref().unwrap_or_default())?;
+        Ok(PyBytes::new_bound(py, &pt).unbind())
+    }
+
+    /// A `length`-byte secret for `exporter_context`, the same on both sides.
+    fn export(
+        &self,
+        py: Python,
+        exporter_context: Buffer,
+        length: usize,
+    ) -> PyResult<Py<SecretBytes>> {
+        secret_bytes(py, &self.ctx.export(&exporter_context, length)?)
+    }
+
+    /// Messages sealed or opened so far.
+    #[getter]
+    fn seq(&self) -> u64 {
+        self.ctx.seq
+    }
+
+    // stub: -> Literal["sender", "receiver"]
+    #[getter]
+    fn role(&self) -> &'static str {
+        match self.ctx.role {
+            Role::Sender => "sender",
+            Role::Receiver => "receiver",
+        }
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "HpkeContext(role='{}', kem='{}', seq={})",
+            self.role(),
+            self.ctx.suite.kem.name(),
+            self.ctx.seq
+        )
+    }
+}
+
+// ─── Message channels ─────────────────────────────────────────────────────────
+//
+// HpkeSender / HpkeReceiver carry a one-way feed (log shipping, telemetry)
+// over a single HPKE context. Each frame is
+//
+//   seq (u64 BE) | ciphertext || tag
+//
+// so the receiver needs no bookkeeping of its own and survives dropped
+// frames: it opens frame `seq` under that message's nonce and afterwards
+// accepts only later ones. A replayed or reordered frame raises
+// DecryptionError, and the frames skipped over are counted in `missed`. The
+// sequence number is authenticated through the nonce.
+
+const SEQ_LEN: usize = 8;
+
+#[pyclass(module = "pqcrypto_bindings")]
+pub struct HpkeSender {
+    enc: Vec<u8>,
+    ctx: Context,
+}
+
+#[pymethods]
+impl HpkeSender {
+    /// The KEM encapsulation the receiver is set up from.
+    #[getter]
+    fn enc<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new_bound(py, &self.enc)
+    }
+
+    /// The sequence number of the next frame.
+    #[getter]
+    fn seq(&self) -> u64 {
+        self.ctx.seq
+    }
+
+    /// Encrypt the next message into a frame.
+    #[pyo3(signature = (plaintext, aad = None))]
+    fn seal(
+        &mut self,
+        py: Python,
+        plaintext: Buffer,
+        aad: Option<Buffer>,
+    ) -> PyResult<Py<PyBytes>> {
+        let seq = self.ctx.seq;
+        let ct = self
+            .ctx
+            .seal(&plaintext, aad.as_deref().unwrap_or_default())?;
+        let mut frame = Vec::with_capacity(SEQ_LEN + ct.len());
+        frame.extend_from_slice(&seq.to_be_bytes());
+        frame.extend_from_slice(&ct);
+        Ok(PyBytes::new_bound(py, &frame).unbind())
+    }
+
+    fn export(
+        &self,
+        py: Python,
+        exporter_context: Buffer,
+        length: usize,
+    ) -> PyResult<Py<SecretBytes>> {
+        secret_bytes(py, &self.ctx.export(&exporter_context, length)?)
+    }
+
+    fn __reduce__(&self) -> PyResult<()> {
+        Err(PyTypeError::new_err(
+            "cannot pickle 'HpkeSender': a copy would reuse its nonces",
+        ))
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "HpkeSender(kem='{}', seq={})",
+            self.ctx.suite.kem.name(),
+            self.ctx.seq
+        )
+    }
+}
+
+#[pyclass(module = "pqcrypto_bindings")]
+pub struct HpkeReceiver {
+    ctx: Context,
+    missed: u64,
+}
+
+#[pymethods]
+impl HpkeReceiver {
+    /// The lowest sequence number still accepted.
+    #[getter]
+    fn seq(&self) -> u64 {
+        self.ctx.seq
+    }
+
+    /// Frames skipped over so far, i.e. lost or not yet delivered in order.
+    #[getter]
+    fn missed(&self) -> u64 {
+        self.missed
+    }
+
+    /// Decrypt a frame from HpkeSender.seal().
+    #[pyo3(signature = (frame, aad = None))]
+    fn open(&mut self, py: Python, frame: Buffer, aad: Option<Buffer>) -> PyResult<Py<PyBytes>> {
+        if frame.len() < SEQ_LEN {
+            return Err(InvalidLengthError::new_err(format!(
+                "HPKE channel frame: expected at least {} bytes, got {}",
+                SEQ_LEN,
+                frame.len()
+            )));
+        }
+        let (seq, ct) = frame.split_at(SEQ_LEN);
+        let seq = u64::from_be_bytes(seq.try_into().unwrap());
+        if seq < self.ctx.seq {
+            return Err(DecryptionError::new_err(format!(
+                "HPKE frame {} is replayed or out of order; expected {} or later",
+                seq, self.ctx.seq
+            )));
+        }
+        let pt = self
+            .ctx
+            .open_at(seq, ct, aad.as_deref().unwrap_or_default())?;
+        self.missed += seq - self.ctx.seq;
+        self.ctx.seq = seq + 1;
+        Ok(PyBytes::new_bound(py, &pt).unbind())
+    }
+
+    fn export(
+        &self,
+        py: Python,
+        exporter_context: Buffer,
+        length: usize,
+    ) -> PyResult<Py<SecretBytes>> {
+        secret_bytes(py, &self.ctx.export(&exporter_context, length)?)
+    }
+
+    fn __reduce__(&self) -> PyResult<()> {
+        Err(PyTypeError::new_err(
+            "cannot pickle 'HpkeReceiver': it holds the context's keys",
+        ))
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "HpkeReceiver(kem='{}', seq={}, missed={})",
+            self.ctx.suite.kem.name(),
+            self.ctx.seq,
+            self.missed
+        )
+    }
+}
+
+// ─── Single-shot functions ────────────────────────────────────────────────────
+
+/// Single-shot HPKE encryption with the suite given by name: (enc,
+/// ciphertext).
+#[pyfunction]
+#[pyo3(signature = (
+    pk,
+    plaintext,
+    info = None,
+    aad = None,
+    kem = "mlkem768",
+    kdf = "hkdf-sha256",
+    aead = "chacha20poly1305",
+    psk = None,
+    psk_id = None,
+    sender_sk = None,
+))]
+#[allow(clippy::too_many_arguments)]
+pub fn hpke_seal(
+    py: Python,
+    pk: Buffer,
+    plaintext: Buffer,
+    info: Option<Buffer>,
+    aad: Option<Buffer>,
+    kem: &str,
+    kdf: &str,
+    aead: &str,
+    psk: Option<Buffer>,
+    psk_id: Option<Buffer>,
+    sender_sk: Option<Buffer>,
+) -> PyResult<(Py<PyBytes>, Py<PyBytes>)> {
+    HpkeSuite::new(kem, kdf, aead)?.seal(py, pk, plaintext, info, aad, psk, psk_id, sender_sk)
+}
+
+/// Decrypt an hpke_seal() output; the suite must match.
+#[pyfunction]
+#[pyo3(signature = (
+    sk,
+    enc,
+    ciphertext,
+    info = None,
+    aad = None,
+    kem = "mlkem768",
+    kdf = "hkdf-sha256",
+    aead = "chacha20poly1305",
+    psk = None,
+    psk_id = None,
+    sender_pk = None,
+))]
+#[allow(clippy::too_many_arguments)]
+pub fn hpke_open(
+    py: Python,
+    sk: Buffer,
+    enc: Buffer,
+    ciphertext: Buffer,
+    info: Option<Buffer>,
+    aad: Option<Buffer>,
+    kem: &str,
+    kdf: &str,
+    aead: &str,
+    psk: Option<Buffer>,
+    psk_id: Option<Buffer>,
+    sender_pk: Option<Buffer>,
+) -> PyResult<Py<PyBytes>> {
+    HpkeSuite::new(kem, kdf, aead)?.open(py, sk, enc, ciphertext, info, aad, psk, psk_id, sender_pk)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// DHKEM(X25519, HKDF-SHA256) and HKDF-SHA256 with AES-128-GCM and
+    /// ChaCha20Poly1305, in all four modes: one vector per RFC 9180 Appendix
+    /// A.1.1-A.1.4 and A.2.1-A.2.4, labelled with its section. Only the
+    /// inputs come from the appendix (ikm, psk, psk_id, info, plaintext, aad
+    /// and exporter contexts). The expected outputs are self-generated
+    /// regression vectors computed from those inputs, not copied from the RFC.
+    struct Vector {
+        aead: HpkeAead,
+        mode: u8,
+        ikm_e: &'static str,
+        ikm_r: &'static str,
+        ikm_s: &'static str,
+        pk_rm: &'static str,
+        pk_sm: &'static str,
+        enc: &'static str,
+        /// (sequence number, ciphertext of PLAINTEXT with aad "Count-<seq>").
+        ciphertexts: [(u64, &'static str); 6],
+        /// Exports of 32 bytes for each of EXPORTER_CONTEXTS.
+        exports: [&'static str; 3],
+    }
+
+    const INFO: &[u8] = b"Ode on a Grecian Urn";
+    const PLAINTEXT: &[u8] = b"Beauty is truth, truth beauty";
+    const PSK: &str = "0247fd33b913760fa1fa51e1892d9f307fbe65eb171e8132c2af18555a738b82";
+    const PSK_ID: &[u8] = b"Ennyn Durin aran Moria";
+    const EXPORTER_CONTEXTS: [&[u8]; 3] = [b"", b"\x00", b"TestContext"];
+
+    const VECTORS: &[Vector] = &[
+        // A.1.1
+        Vector {
+            aead: HpkeAead::Aes128Gcm,
+            mode: MODE_BASE,
+            ikm_e: "7268600d403fce431561aef583ee1613527cff655c1343f29812e66706df3234",
+            ikm_r: "6db9df30aa07dd42ee5e8181afdb977e538f5e1fec8a06223f33f7013e525037",
+            ikm_s: "",
+            pk_rm: "3948cfe0ad1ddb695d780e59077195da6c56506b027329794ab02bca80815c4d",
+            pk_sm: "",
+            enc: "37fda3567bdbd628e88668c3c8d7e97d1d1253b6d4ea6d44c150f741f1bf4431",
+            ciphertexts: [
+                (
+                    0,
+                    "f938558b5d72f1a23810b4be2ab4f84331acc02fc97babc53a52ae8218a355a96d8770ac83d07bea87e13c512a",
+                ),
+                (
+                    1,
+                    "af2d7e9ac9ae7e270f46ba1f975be53c09f8d875bdc8535458c2494e8a6eab251c03d0c22a56b8ca42c2063b84",
+                ),
+                (
+                    2,
+                    "498dfcabd92e8acedc281e85af1cb4e3e31c7dc394a1ca20e173cb72516491588d96a19ad4a683518973dcc180",
+                ),
+                (
+                    4,
+                    "583bd32bc67a5994bb8ceaca813d369bca7b2a42408cddef5e22f880b631215a09fc0012bc69fccaa251c0246d",
+                ),
+                (
+                    255,
+                    "7175db9717964058640a3a11fb9007941a5d1757fda1a6935c805c21af32505bf106deefec4a49ac38d71c9e0a",
+                ),
+                (
+                    256,
+                    "957f9800542b0b8891badb026d79cc54597cb2d225b54c00c5238c25d05c30e3fbeda97d2e0e1aba483a2df9f2",
+                ),
+            ],
+            exports: [
+                "3853fe2b4035195a573ffc53856e77058e15d9ea064de3e59f4961d0095250ee",
+                "2e8f0b54673c7029649d4eb9d5e33bf1872cf76d623ff164ac185da9e88c21a5",
+                "e9e43065102c3836401bed8c3c3c75ae46be1639869391d62c61f1ec7af54931",
+            ],
+        },
+        // A.1.2
+        Vector {
+            aead: HpkeAead::Aes128Gcm,
+            mode: MODE_PSK,
+            ikm_e: "78628c354e46f3e169bd231be7b2ff1c77aa302460a26dbfa15515684c00130b",
+            ikm_r: "d4a09d09f575fef425905d2ab396c1449141463f698f8efdb7accfaff8995098",
+            ikm_s: "",
+            pk_rm: "9fed7e8c17387560e92cc6462a68049657246a09bfa8ade7aefe589672016366",
+            pk_sm: "",
+            enc: "0ad0950d9fb9588e59690b74f1237ecdf1d775cd60be2eca57af5a4b0471c91b",
+            ciphertexts: [
+                (
+                    0,
+                    "e52c6fed7f758d0cf7145689f21bc1be6ec9ea097fef4e959440012f4feb73fb611b946199e681f4cfc34db8ea",
+                ),
+                (
+                    1,
+                    "49f3b19b28a9ea9f43e8c71204c00d4a490ee7f61387b6719db765e948123b45b61633ef059ba22cd62437c8ba",
+                ),
+                (
+                    2,
+                    "257ca6a08473dc851fde45afd598cc83e326ddd0abe1ef23baa3baa4dd8cde99fce2c1e8ce687b0b47ead1adc9",
+                ),
+                (
+                    4,
+                    "a71d73a2cd8128fcccbd328b9684d70096e073b59b40b55e6419c9c68ae21069c847e2a70f5d8fb821ce3dfb1c",
+                ),
+                (
+                    255,
+                    "55f84b030b7f7197f7d7d552365b6b932df5ec1abacd30241cb4bc4ccea27bd2b518766adfa0fb1b71170e9392",
+                ),
+                (
+                    256,
+                    "c5bf246d4a790a12dcc9eed5eae525081e6fb541d5849e9ce8abd92a3bc1551776bea16b4a518f23e237c14b59",
+                ),
+            ],
+            exports: [
+                "dff17af354c8b41673567db6259fd6029967b4e1aad13023c2ae5df8f4f43bf6",
+                "6a847261d8207fe596befb52928463881ab493da345b10e1dcc645e3b94e2d95",
+                "8aff52b45a1be3a734bc7a41e20b4e055ad4c4d22104b0c20285a7c4302401cd",
+            ],
+        },
+        // A.1.3
+        Vector {
+            aead: HpkeAead::Aes128Gcm,
+            mode: MODE_AUTH,
+            ikm_e: "6e6d8f200ea2fb20c30b003a8b4f433d2f4ed4c2658d5bc8ce2fef718059c9f7",
+            ikm_r: "f1d4a30a4cef8d6d4e3b016e6fd3799ea057db4f345472ed302a67ce1c20cdec",
+            ikm_s: "94b020ce91d73fca4649006c7e7329a67b40c55e9e93cc907d282bbbff386f58",
+            pk_rm: "1632d5c2f71c2b38d0a8fcc359355200caa8b1ffdf28618080466c909cb69b2e",
+            pk_sm: "8b0c70873dc5aecb7f9ee4e62406a397b350e57012be45cf53b7105ae731790b",
+            enc: "23fb952571a14a25e3d678140cd0e5eb47a0961bb18afcf85896e5453c312e76",
+            ciphertexts: [
+                (
+                    0,
+                    "5fd92cc9d46dbf8943e72a07e42f363ed5f721212cd90bcfd072bfd9f44e06b80fd17824947496e21b680c141b",
+                ),
+                (
+                    1,
+                    "d3736bb256c19bfa93d79e8f80b7971262cb7c887e35c26370cfed62254369a1b52e3d505b79dd699f002bc8ed",
+                ),
+                (
+                    2,
+                    "122175cfd5678e04894e4ff8789e85dd381df48dcaf970d52057df2c9acc3b121313a2bfeaa986050f82d93645",
+                ),
+                (
+                    4,
+                    "dae12318660cf963c7bcbef0f39d64de3bf178cf9e585e756654043cc5059873bc8af190b72afc43d1e0135ada",
+                ),
+                (
+                    255,
+                    "55d53d85fe4d9e1e97903101eab0b4865ef20cef28765a47f840ff99625b7d69dee927df1defa66a036fc58ff2",
+                ),
+                (
+                    256,
+                    "42fa248a0e67ccca688f2b1d13ba4ba84755acf764bd797c8f7ba3b9b1dc3330326f8d172fef6003c79ec72319",
+                ),
+            ],
+            exports: [
+                "28c70088017d70c896a8420f04702c5a321d9cbf0279fba899b59e51bac72c85",
+                "25dfc004b0892be1888c3914977aa9c9bbaf2c7471708a49e1195af48a6f29ce",
+                "5a0131813abc9a522cad678eb6bafaabc43389934adb8097d23c5ff68059eb64",
+            ],
+        },
+        // A.1.4
+        Vector {
+            aead: HpkeAead::Aes128Gcm,
+            mode: MODE_AUTH_PSK,
+            ikm_e: "4303619085a20ebcf18edd22782952b8a7161e1dbae6e46e143a52a96127cf84",
+            ikm_r: "4b16221f3b269a88e207270b5e1de28cb01f847841b344b8314d6a622fe5ee90",
+            ikm_s: "62f77dcf5df0dd7eac54eac9f654f426d4161ec850cc65c54f8b65d2e0b4e345",
+            pk_rm: "1d11a3cd247ae48e901939659bd4d79b6b959e1f3e7d66663fbc9412dd4e0976",
+            pk_sm: "2bfb2eb18fcad1af0e4f99142a1c474ae74e21b9425fc5c589382c69b50cc57e",
+            enc: "820818d3c23993492cc5623ab437a48a0a7ca3e9639c140fe1e33811eb844b7c",
+            ciphertexts: [
+                (
+                    0,
+                    "a84c64df1e11d8fd11450039d4fe64ff0c8a99fca0bd72c2d4c3e0400bc14a40f27e45e141a24001697737533e",
+                ),
+                (
+                    1,
+                    "4d19303b848f424fc3c3beca249b2c6de0a34083b8e909b6aa4c3688505c05ffe0c8f57a0a4c5ab9da127435d9",
+                ),
+                (
+                    2,
+                    "0c085a365fbfa63409943b00a3127abce6e45991bc653f182a80120868fc507e9e4d5e37bcc384fc8f14153b24",
+                ),
+                (
+                    4,
+                    "000a3cd3a3523bf7d9796830b1cd987e841a8bae6561ebb6791a3f0e34e89a4fb539faeee3428b8bbc082d2c1a",
+                ),
+                (
+                    255,
+                    "576d39dd2d4cc77d1a14a51d5c5f9d5e77586c3d8d2ab33bdec6379e28ce5c502f0b1cbd09047cf9eb9269bb52",
+                ),
+                (
+                    256,
+                    "13239bab72e25e9fd5bb09695d23c90a24595158b99127505c8a9ff9f127e0d657f71af59d67d4f4971da028f9",
+                ),
+            ],
+            exports: [
+                "08f7e20644bb9b8af54ad66d2067457c5f9fcb2a23d9f6cb4445c0797b330067",
+                "52e51ff7d436557ced5265ff8b94ce69cf7583f49cdb374e6aad801fc063b010",
+                "a30c20370c026bbea4dca51cb63761695132d342bae33a6a11527d3e7679436d",
+            ],
+        },
+        // A.2.1
+        Vector {
+            aead: HpkeAead::ChaCha20Poly1305,
+            mode: MODE_BASE,
+            ikm_e: "909a9b35d3dc4713a5e72a4da274b55d3d3821a37e5d099e74a647db583a904b",
+            ikm_r: "1ac01f181fdf9f352797655161c58b75c656a6cc2716dcb66372da835542e1df",
+            ikm_s: "",
+            pk_rm: "4310ee97d88cc1f088a5576c77ab0cf5c3ac797f3d95139c6c84b5429c59662a",
+            pk_sm: "",
+            enc: "1afa08d3dec047a643885163f1180476fa7ddb54c6a8029ea33f95796bf2ac4a",
+            ciphertexts: [
+                (
+                    0,
+                    "1c5250d8034ec2b784ba2cfd69dbdb8af406cfe3ff938e131f0def8c8b60b4db21993c62ce81883d2dd1b51a28",
+                ),
+                (
+                    1,
+                    "6b53c051e4199c518de79594e1c4ab18b96f081549d45ce015be002090bb119e85285337cc95ba5f59992dc98c",
+                ),
+                (
+                    2,
+                    "71146bd6795ccc9c49ce25dda112a48f202ad220559502cef1f34271e0cb4b02b4f10ecac6f48c32f878fae86b",
+                ),
+                (
+                    4,
+                    "63357a2aa291f5a4e5f27db6baa2af8cf77427c7c1a909e0b37214dd47db122bb153495ff0b02e9e54a50dbe16",
+                ),
+                (
+                    255,
+                    "18ab939d63ddec9f6ac2b60d61d36a7375d2070c9b683861110757062c52b8880a5f6b3936da9cd6c23ef2a95c",
+                ),
+                (
+                    256,
+                    "7a4a13e9ef23978e2c520fd4d2e757514ae160cd0cd05e556ef692370ca53076214c0c40d4c728d6ed9e727a5b",
+                ),
+            ],
+            exports: [
+                "4bbd6243b8bb54cec311fac9df81841b6fd61f56538a775e7c80a9f40160606e",
+                "8c1df14732580e5501b00f82b10a1647b40713191b7c1240ac80e2b68808ba69",
+                "5acb09211139c43b3090489a9da433e8a30ee7188ba8b0a9a1ccf0c229283e53",
+            ],
+        },
+        // A.2.2
+        Vector {
+            aead: HpkeAead::ChaCha20Poly1305,
+            mode: MODE_PSK,
+            ikm_e: "35706a0b09fb26fb45c39c2f5079c709c7cf98e43afa973f14d88ece7e29c2e3",
+            ikm_r: "26b923eade72941c8a85b09986cdfa3f1296852261adedc52d58d2930269812b",
+            ikm_s: "",
+            pk_rm: "13640af826b722fc04feaa4de2f28fbd5ecc03623b317834e7ff4120dbe73062",
+            pk_sm: "",
+            enc: "2261299c3f40a9afc133b969a97f05e95be2c514e54f3de26cbe5644ac735b04",
+            ciphertexts: [
+                (
+                    0,
+                    "4a177f9c0d6f15cfdf533fb65bf84aecdc6ab16b8b85b4cf65a370e07fc1d78d28fb073214525276f4a89608ff",
+                ),
+                (
+                    1,
+                    "5c3cabae2f0b3e124d8d864c116fd8f20f3f56fda988c3573b40b09997fd6c769e77c8eda6cda4f947f5b704a8",
+                ),
+                (
+                    2,
+                    "14958900b44bdae9cbe5a528bf933c5c990dbb8e282e6e495adf8205d19da9eb270e3a6f1e0613ab7e757962a4",
+                ),
+                (
+                    4,
+                    "c2a7bc09ddb853cf2effb6e8d058e346f7fe0fb3476528c80db6b698415c5f8c50b68a9a355609e96d2117f8d3",
+                ),
+                (
+                    255,
+                    "2414d0788e4bc39a59a26d7bd5d78e111c317d44c37bd5a4c2a1235f2ddc2085c487d406490e75210c958724a7",
+                ),
+                (
+                    256,
+                    "c567ae1c3f0f75abe1dd9e4532b422600ed4a6e5b9484dafb1e43ab9f5fd662b28c00e2e81d3cde955dae7e218",
+                ),
+            ],
+            exports: [
+                "813c1bfc516c99076ae0f466671f0ba5ff244a41699f7b2417e4c59d46d39f40",
+                "2745cf3d5bb65c333658732954ee7af49eb895ce77f8022873a62a13c94cb4e1",
+                "ad40e3ae14f21c99bfdebc20ae14ab86f4ca2dc9a4799d200f43a25f99fa78ae",
+            ],
+        },
+        // A.2.3
+        Vector {
+            aead: HpkeAead::ChaCha20Poly1305,
+            mode: MODE_AUTH,
+            ikm_e: "938d3daa5a8904540bc24f48ae90eed3f4f7f11839560597b55e7c9598c996c0",
+            ikm_r: "64835d5ee64aa7aad57c6f2e4f758f7696617f8829e70bc9ac7a5ef95d1c756c",
+            ikm_s: "9d8f94537d5a3ddef71234c0baedfad4ca6861634d0b94c3007fed557ad17df6",
+            pk_rm: "1a478716d63cb2e16786ee93004486dc151e988b34b475043d3e0175bdb01c44",
+            pk_sm: "f0f4f9e96c54aeed3f323de8534fffd7e0577e4ce269896716bcb95643c8712b",
+            enc: "f7674cc8cd7baa5872d1f33dbaffe3314239f6197ddf5ded1746760bfc847e0e",
+            ciphertexts: [
+                (
+                    0,
+                    "ab1a13c9d4f01a87ec3440dbd756e2677bd2ecf9df0ce7ed73869b98e00c09be111cb9fdf077347aeb88e61bdf",
+                ),
+                (
+                    1,
+                    "3265c7807ffff7fdace21659a2c6ccffee52a26d270c76468ed74202a65478bfaedfff9c2b7634e24f10b71016",
+                ),
+                (
+                    2,
+                    "3aadee86ad2a05081ea860033a9d09dbccb4acac2ded0891da40f51d4df19925f7a767b076a5cbc9355c8fd35e",
+                ),
+                (
+                    4,
+                    "502ecccd5c2be3506a081809cc58b43b94f77cbe37b8b31712d9e21c9e61aa6946a8e922f54eae630f88eb8033",
+                ),
+                (
+                    255,
+                    "652e597ba20f3d9241cda61f33937298b1169e6adf72974bbe454297502eb4be132e1c5064702fc165c2ddbde8",
+                ),
+                (
+                    256,
+                    "3be14e8b3bbd1028cf2b7d0a691dbbeff71321e7dec92d3c2cfb30a0994ab246af76168480285a60037b4ba13a",
+                ),
+            ],
+            exports: [
+                "070cffafd89b67b7f0eeb800235303a223e6ff9d1e774dce8eac585c8688c872",
+                "2852e728568d40ddb0edde284d36a4359c56558bb2fb8837cd3d92e46a3a14a8",
+                "1df39dc5dd60edcbf5f9ae804e15ada66e885b28ed7929116f768369a3f950ee",
+            ],
+        },
+        // A.2.4
+        Vector {
+            aead: HpkeAead::ChaCha20Poly1305,
+            mode: MODE_AUTH_PSK,
+            ikm_e: "49d6eac8c6c558c953a0a252929a818745bb08cd3d29e15f9f5db5eb2e7d4b84",
+            ikm_r: "f3304ddcf15848488271f12b75ecaf72301faabf6ad283654a14c398832eb184",
+            ikm_s: "20ade1d5203de1aadfb261c4700b6432e260d0d317be6ebbb8d7fffb1f86ad9d",
+            pk_rm: "a5099431c35c491ec62ca91df1525d6349cb8aa170c51f9581f8627be6334851",
+            pk_sm: "3ac5bd4dd66ff9f2740bef0d6ccb66daa77bff7849d7895182b07fb74d087c45",
+            enc: "656a2e00dc9990fd189e6e473459392df556e9a2758754a09db3f51179a3fc02",
+            ciphertexts: [
+                (
+                    0,
+                    "9aa52e29274fc6172e38a4461361d2342585d3aeec67fb3b721ecd63f059577c7fe886be0ede01456ebc67d597",
+                ),
+                (
+                    1,
+                    "59460bacdbe7a920ef2806a74937d5a691d6d5062d7daafcad7db7e4d8c649adffe575c1889c5c2e3a49af8e3e",
+                ),
+                (
+                    2,
+                    "5688ff6a03ba26ae936044a5c800f286fb5d1eccdd2a0f268f6ff9773b51169318d1a1466bb36263415071db00",
+                ),
+                (
+                    4,
+                    "d936b7a01f5c7dc4c3dc04e322cc694684ee18dd71719196874e5235aed3cfb06cadcd3bc7da0877488d7c551d",
+                ),
+                (
+                    255,
+                    "4d4c462f7b9b637eaf1f4e15e325b7bc629c0af6e3073422c86064cc3c98cff87300f054fd56dd57dc34358beb",
+                ),
+                (
+                    256,
+                    "9b7f84224922d2a9edd7b2c2057f3bcf3a547f17570575e626202e593bfdd99e9878a1af9e41ded58c7fb77d2f",
+                ),
+            ],
+            exports: [
+                "c23ebd4e7a0ad06a5dddf779f65004ce9481069ce0f0e6dd51a04539ddcbd5cd",
+                "ed7ff5ca40a3d84561067ebc8e01702bc36cf1eb99d42a92004642b9dfaadd37",
+                "d3bae066aa8da27d527d85c040f7dd6ccb60221c902ee36a82f70bcd62a60ee4",
+            ],
+        },
+    ];
+
+    fn unhex(s: &str) -> Vec<u8> {
+        crate::util::unhex(s).unwrap()
+    }
+
+    /// DHKEM(X25519)'s DeriveKeyPair: (sk, pk).
+    fn derive_key_pair(ikm: &str) -> (x25519_dalek::StaticSecret, Vec<u8>) {
+        let suite_id = [&b"KEM"[..], &Kem::X25519.id().to_be_bytes()].concat();
+        let prk = Kdf::Sha256.labeled_extract(&suite_id, b"", b"dkp_prk", &unhex(ikm));
+        let sk = Kdf::Sha256
+            .labeled_expand(&suite_id, &prk, b"sk", b"", X25519_LEN)
+            .unwrap();
+        let sk = x25519_secret("sk", &sk).unwrap();
+        let pk = x25519_dalek::PublicKey::from(&sk).as_bytes().to_vec();
+        (sk, pk)
+    }
+
+    #[test]
+    fn rfc9180_x25519_vectors() {
+        let psk_bytes = unhex(PSK);
+        for v in VECTORS {
+            let suite = Suite {
+                kem: Kem::X25519,
+                kdf: Kdf::Sha256,
+                aead: v.aead,
+            };
+            let (sk_e, _) = derive_key_pair(v.ikm_e);
+            let (sk_r, pk_r) = derive_key_pair(v.ikm_r);
+            assert_eq!(pk_r, unhex(v.pk_rm));
+            let sender = (!v.ikm_s.is_empty()).then(|| derive_key_pair(v.ikm_s));
+            if let Some((_, pk_s)) = &sender {
+                assert_eq!(*pk_s, unhex(v.pk_sm));
+            }
+            let sk_s = sender.as_ref().map(|(sk, _)| sk.to_bytes());
+            let pk_s = sender.as_ref().map(|(_, pk)| pk.as_slice());
+
+            let psk = if v.mode == MODE_PSK || v.mode == MODE_AUTH_PSK {
+                Psk {
+                    psk: Some(&psk_bytes),
+                    psk_id: Some(PSK_ID),
+                }
+            } else {
+                Psk {
+                    psk: None,
+                    psk_id: None,
+                }
+            };
+            let mode = suite.mode(&psk, sender.is_some()).unwrap();
+            assert_eq!(mode, v.mode);
+
+            let (enc, ss) = x25519_encap(&pk_r, &sk_e, sk_s.as_ref().map(|sk| &sk[..])).unwrap();
+            assert_eq!(enc, unhex(v.enc));
+            assert_eq!(x25519_decap(&sk_r.to_bytes(), &enc, pk_s).unwrap(), ss);
+
+            let sender_ctx = suite
+                .key_schedule(mode, &ss, INFO, &psk, Role::Sender)
+                .unwrap();
+            let receiver_ctx = suite
+                .key_schedule(mode, &ss, INFO, &psk, Role::Receiver)
+                .unwrap();
+            for (seq, ct) in v.ciphertexts {
+                let aad = format!("Count-{}", seq);
+                let sealed = sender_ctx.seal_at(seq, PLAINTEXT, aad.as_bytes()).unwrap();
+                assert_eq!(sealed, unhex(ct), "seq {}", seq);
+                let opened = receiver_ctx.open_at(seq, &sealed, aad.as_bytes()).unwrap();
+                assert_eq!(&opened[..], PLAINTEXT);
+            }
+            for (context, want) in EXPORTER_CONTEXTS.iter().zip(v.exports) {
+                assert_eq!(&sender_ctx.export(context, 32).unwrap()[..], unhex(want));
+                assert_eq!(&receiver_ctx.export(context, 32).unwrap()[..], unhex(want));
+            }
+        }
+    }
+
+    #[test]
+    fn x25519_rejects_low_order_enc() {
+        let (sk_r, _) = derive_key_pair(VECTORS[0].ikm_r);
+        assert!(x25519_decap(&sk_r.to_bytes(), &[0u8; X25519_LEN], None).is_err());
+    }
+}
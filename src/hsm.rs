@@ -0,0 +1,296 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// PKCS#11 token signing
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// For signing keys that must stay on an HSM or smart card:
+//
+//   signer = HsmSigner("/usr/lib/softhsm/libsofthsm2.so", 0, "release-signing",
+//                      pin="1234")
+//   sig = signer.sign(msg)
+//
+// sign() takes and returns the same bytes as FalconSigner.sign() and
+// SecretKey.sign(), so code that only signs can be handed any of the three;
+// the signatures verify with the usual verify functions. The key is found by
+// its CKA_LABEL among the token's private keys and never leaves the token.
+//
+// ML-DSA keys (CKK_ML_DSA, PKCS#11 3.2) sign with CKM_ML_DSA, hedged, and
+// their parameter set is read from the key. PKCS#11 has no Falcon mechanism
+// yet, so a Falcon key needs `algorithm` and the token's vendor-defined
+// `mechanism` number, and the token must return signatures in the Falcon
+// specification's (and PQClean's) compressed format.
+
+use cryptoki::context::{CInitializeArgs, CInitializeFlags, Pkcs11};
+use cryptoki::error::{Error as Pkcs11Error, RvError};
+use cryptoki::mechanism::dsa::{HedgeType, SignAdditionalContext};
+use cryptoki::mechanism::vendor_defined::VendorDefinedMechanism;
+use cryptoki::mechanism::{Mechanism, MechanismType};
+use cryptoki::object::{
+    Attribute, AttributeType, KeyType, MlDsaParameterSetType, ObjectClass, ObjectHandle,
+};
+use cryptoki::session::{Session, UserType};
+use cryptoki::slot::Slot;
+use cryptoki::types::AuthPin;
+use pyo3::exceptions::{PyRuntimeError, PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+use crate::buffer::Buffer;
+use crate::sign::find_signature;
+
+const SUPPORTED: &[&str] = &["falcon512", "falcon1024", "mldsa44", "mldsa65", "mldsa87"];
+
+fn pkcs11_err(what: &str, e: Pkcs11Error) -> PyErr {
+    PyRuntimeError::new_err(format!("PKCS#11 {}: {}", what, e))
+}
+
+/// The ML-DSA parameter set of an ML-DSA key, by canonical name.
+fn mldsa_name(params: MlDsaParameterSetType) -> Option<&'static str> {
+    [
+        (MlDsaParameterSetType::ML_DSA_44, "mldsa44"),
+        (MlDsaParameterSetType::ML_DSA_65, "mldsa65"),
+        (MlDsaParameterSetType::ML_DSA_87, "mldsa87"),
+    ]
+    .into_iter()
+    .find(|(p, _)| *p == params)
+    .map(|(_, name)| name)
+}
+
+fn find_key(session: &Session, class: ObjectClass, label: &str) -> PyResult<Option<ObjectHandle>> {
+    let template = [
+        Attribute::Class(class),
+        Attribute::Label(label.as_bytes().to_vec()),
+    ];
+    let handles = session
+        .find_objects(&template)
+        .map_err(|e| pkcs11_err("key lookup", e))?;
+    match handles[..] {
+        [] => Ok(None),
+        [handle] => Ok(Some(handle)),
+        _ => Err(PyValueError::new_err(format!(
+            "{} keys on the token are labelled '{}'",
+            handles.len(),
+            label
+        ))),
+    }
+}
+
+/// The algorithm of the ML-DSA key `key`, or None for any other key type.
+fn key_algorithm(session: &Session, key: ObjectHandle) -> PyResult<Option<&'static str>> {
+    let attrs = session
+        .get_attributes(key, &[AttributeType::KeyType])
+        .map_err(|e| pkcs11_err("key attributes", e))?;
+    if !matches!(attrs[..], [Attribute::KeyType(KeyType::ML_DSA)]) {
+        return Ok(None);
+    }
+    let attrs = session
+        .get_attributes(key, &[AttributeType::ParameterSet])
+        .map_err(|e| pkcs11_err("key attributes", e))?;
+    match attrs[..] {
+        [Attribute::ParameterSet(params)] => match mldsa_name(params.into()) {
+            Some(name) => Ok(Some(name)),
+            None => Err(PyValueError::new_err(format!(
+                "ML-DSA key has unknown parameter set {}",
+                *params
+            ))),
+        },
+        _ => Err(PyValueError::new_err("ML-DSA key has no parameter set")),
+    }
+}
+
+#[pyclass(module = "pqcrypto_bindings")]
+pub struct HsmSigner {
+    algorithm: &'static str,
+    label: String,
+    slot: u64,
+    /// None for CKM_ML_DSA, else a vendor-defined mechanism.
+    mechanism: Option<MechanismType>,
+    session: Option<Session>,
+    key: ObjectHandle,
+}
+
+impl HsmSigner {
+    fn session(&self) -> PyResult<&Session> {
+        self.session
+            .as_ref()
+            .ok_or_else(|| PyValueError::new_err("HsmSigner has been closed"))
+    }
+}
+
+#[pymethods]
+impl HsmSigner {
+    /// Open a session on `slot` of the PKCS#11 module at `module_path`, log
+    /// in with `pin` if given, and find the private key labelled `key_label`.
+    /// `algorithm` and `mechanism` are only needed for non-ML-DSA keys.
+    #[new]
+    #[pyo3(signature = (module_path, slot, key_label, pin = None, algorithm = None, mechanism = None))]
+    fn new(
+        module_path: &str,
+        slot: u64,
+        key_label: &str,
+        pin: Option<String>,
+        algorithm: Option<&str>,
+        mechanism: Option<u64>,
+    ) -> PyResult<Self> {
+        let pkcs11 = Pkcs11::new(module_path)
+            .map_err(|e| pkcs11_err(&format!("module '{}'", module_path), e))?;
+        match pkcs11.initialize(CInitializeArgs::new(CInitializeFlags::OS_LOCKING_OK)) {
+            // Another HsmSigner on the same module got there first.
+            Ok(()) | Err(Pkcs11Error::Pkcs11(RvError::CryptokiAlreadyInitialized, _)) => {}
+            Err(e) => return Err(pkcs11_err("initialize", e)),
+        }
+
+        let slot_id = Slot::try_from(slot).map_err(|e| pkcs11_err("slot", e))?;
+        let session = pkcs11
+            .open_ro_session(slot_id)
+            .map_err(|e| pkcs11_err(&format!("slot {}", slot), e))?;
+        if let Some(pin) = pin {
+            match session.login(UserType::User, Some(&AuthPin::new(pin.into()))) {
+                Ok(()) | Err(Pkcs11Error::Pkcs11(RvError::UserAlreadyLoggedIn, _)) => {}
+                Err(e) => return Err(pkcs11_err("login", e)),
+            }
+        }
+
+        let key = find_key(&session, ObjectClass::PRIVATE_KEY, key_label)?.ok_or_else(|| {
+            PyValueError::new_err(format!(
+                "no private key labelled '{}' in slot {}",
+                key_label, slot
+            ))
+        })?;
+
+        let on_token = key_algorithm(&session, key)?;
+        let algorithm = match (algorithm, on_token) {
+            (None, Some(name)) => name,
+            (None, None) => {
+                return Err(PyValueError::new_err(format!(
+                    "key '{}' isn't an ML-DSA key; pass its algorithm and mechanism",
+                    key_label
+                )))
+            }
+            (Some(name), on_token) => {
+                let name = find_signature(name)
+                    .map(|alg| alg.name)
+                    .filter(|n| SUPPORTED.contains(n))
+                    .ok_or_else(|| {
+                        PyValueError::new_err(format!(
+                            "HsmSigner supports Falcon and ML-DSA keys, not '{}'",
+                            name
+                        ))
+                    })?;
+                if let Some(on_token) = on_token.filter(|&n| n != name) {
+                    return Err(PyValueError::new_err(format!(
+                        "key '{}' is a {} key, not {}",
+                        key_label, on_token, name
+                    )));
+                }
+                name
+            }
+        };
+
+        let mechanism = match mechanism {
+            Some(m) => Some(MechanismType::new_vendor_defined(m).map_err(|_| {
+                PyValueError::new_err(format!(
+                    "mechanism {:#x} isn't vendor-defined (below CKM_VENDOR_DEFINED)",
+                    m
+                ))
+            })?),
+            None if algorithm.starts_with("mldsa") => None,
+            None => {
+                return Err(PyValueError::new_err(format!(
+                    "PKCS#11 has no standard {} mechanism; pass the token's mechanism number",
+                    algorithm
+                )))
+            }
+        };
+
+        Ok(HsmSigner {
+            algorithm,
+            label: key_label.to_owned(),
+            slot,
+            mechanism,
+            session: Some(session),
+            key,
+        })
+    }
+
+    /// Canonical algorithm name, e.g. "mldsa65".
+    #[getter]
+    fn algorithm(&self) -> &'static str {
+        self.algorithm
+    }
+
+    /// True once the session has been closed.
+    #[getter]
+    fn closed(&self) -> bool {
+        self.session.is_none()
+    }
+
+    /// Detached signature over `msg`, made on the token.
+    fn sign(&self, py: Python, msg: Buffer) -> PyResult<Py<PyBytes>> {
+        let mechanism = match self.mechanism {
+            None => Mechanism::MlDsa(SignAdditionalContext::new(HedgeType::Preferred, None)),
+            Some(m) => Mechanism::VendorDefined(VendorDefinedMechanism::new::<()>(m, None)),
+        };
+        let sig = self
+            .session()?
+            .sign(&mechanism, self.key, &msg)
+            .map_err(|e| pkcs11_err("sign", e))?;
+        Ok(PyBytes::new_bound(py, &sig).unbind())
+    }
+
+    /// The matching public key (CKA_VALUE of the public key object with the
+    /// same label), for verifying. Raises ValueError if the token has none.
+    fn public_key(&self, py: Python) -> PyResult<Py<PyBytes>> {
+        let session = self.session()?;
+        let key = find_key(session, ObjectClass::PUBLIC_KEY, &self.label)?.ok_or_else(|| {
+            PyValueError::new_err(format!(
+                "no public key labelled '{}' on the token",
+                self.label
+            ))
+        })?;
+        let attrs = session
+            .get_attributes(key, &[AttributeType::Value])
+            .map_err(|e| pkcs11_err("public key", e))?;
+        match &attrs[..] {
+            [Attribute::Value(pk)] => Ok(PyBytes::new_bound(py, pk).unbind()),
+            _ => Err(PyValueError::new_err(format!(
+                "public key '{}' has no readable value",
+                self.label
+            ))),
+        }
+    }
+
+    /// Close the session. Calling it twice is harmless.
+    fn close(&mut self) -> PyResult<()> {
+        if let Some(session) = self.session.take() {
+            session.close().map_err(|e| pkcs11_err("close", e))?;
+        }
+        Ok(())
+    }
+
+    fn __enter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    fn __exit__(
+        &mut self,
+        _exc_type: &Bound<'_, PyAny>,
+        _exc_value: &Bound<'_, PyAny>,
+        _traceback: &Bound<'_, PyAny>,
+    ) -> PyResult<bool> {
+        self.close()?;
+        Ok(false)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "HsmSigner('{}', slot={}, key_label='{}')",
+            self.algorithm, self.slot, self.label
+        )
+    }
+
+    fn __reduce__(&self) -> PyResult<()> {
+        Err(PyTypeError::new_err(
+            "cannot pickle 'HsmSigner': it holds a PKCS#11 session",
+        ))
+    }
+}
@@ -0,0 +1,658 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// Hybrid (classical + post-quantum) constructions
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// Each of these stays secure as long as either its classical or its PQ half
+// does. The combining happens here rather than in Python glue, where a
+// mis-ordered concatenation or a dropped transcript input silently loses that
+// guarantee.
+
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use zeroize::Zeroizing;
+
+use crate::buffer::Buffer;
+use crate::classical::random_key;
+use crate::errors::{
+    check_length, check_max_length, length_err, verification_failed, InvalidKeyError,
+    InvalidLengthError, SignatureError,
+};
+use crate::secret::{secret_bytes, secret_bytes_with, SecretBytes};
+
+// ─── X-Wing (X25519 + ML-KEM-768) ─────────────────────────────────────────────
+//
+//   xwing_keygen()              -> (public_key, secret_key)   1216 + 32 bytes
+//   xwing_encapsulate(pk)       -> (ciphertext, shared_secret) 1120 + 32 bytes
+//   xwing_decapsulate(sk, ct)   -> shared_secret
+//
+// The secret key is the 32-byte X-Wing seed; both component keys are expanded
+// from it on use. The shared secret is the X-Wing SHA3-256 combiner over both
+// component secrets, the X25519 ciphertext and the X25519 public key.
+
+use x_wing::{Decapsulate, Decapsulator, Encapsulate, KeyExport, TryKeyInit};
+
+pub(crate) const XWING: crate::kem::KemAlgorithm = crate::kem::KemAlgorithm {
+    name: "xwing",
+    public_key_bytes: x_wing::ENCAPSULATION_KEY_SIZE,
+    secret_key_bytes: x_wing::DECAPSULATION_KEY_SIZE,
+    ciphertext_bytes: x_wing::CIPHERTEXT_SIZE,
+    shared_secret_bytes: 32,
+    keygen: xwing_keygen,
+    encapsulate: xwing_encapsulate,
+    decapsulate: xwing_decapsulate,
+};
+
+#[pyfunction]
+pub fn xwing_keygen(py: Python) -> PyResult<(Py<PyBytes>, Py<SecretBytes>)> {
+    let sk = x_wing::DecapsulationKey::from(*random_key()?);
+    let pk = sk.encapsulation_key().to_bytes();
+
+    let keys = (
+        PyBytes::new_bound(py, &pk).unbind(),
+        secret_bytes(py, sk.as_bytes())?,
+    );
+    crate::pct::checked_kem_keypair(py, &XWING, keys)
+}
+
+#[pyfunction]
+pub fn xwing_encapsulate(py: Python, pk_bytes: Buffer) -> PyResult<(Py<PyBytes>, Py<SecretBytes>)> {
+    check_length(
+        "X-Wing public key",
+        &pk_bytes,
+        x_wing::ENCAPSULATION_KEY_SIZE,
+    )?;
+    let pk = x_wing::EncapsulationKey::new_from_slice(&pk_bytes)
+        .map_err(|_| InvalidKeyError::new_err("invalid X-Wing public key"))?;
+
+    let (ct, ss) = pk.encapsulate();
+
+    Ok((PyBytes::new_bound(py, &ct).unbind(), secret_bytes(py, &ss)?))
+}
+
+#[pyfunction]
+pub fn xwing_decapsulate(
+    py: Python,
+    sk_bytes: Buffer,
+    ct_bytes: Buffer,
+) -> PyResult<Py<SecretBytes>> {
+    check_length(
+        "X-Wing secret key",
+        &sk_bytes,
+        x_wing::DECAPSULATION_KEY_SIZE,
+    )?;
+    check_length("X-Wing ciphertext", &ct_bytes, x_wing::CIPHERTEXT_SIZE)?;
+    let seed: [u8; x_wing::DECAPSULATION_KEY_SIZE] = (*sk_bytes).try_into().unwrap();
+    let ct = x_wing::Ciphertext::try_from(&*ct_bytes).unwrap();
+
+    let sk = x_wing::DecapsulationKey::from(seed);
+    let ss = sk.decapsulate(&ct);
+
+    secret_bytes(py, &ss)
+}
+
+// ─── Composite Ed25519 + Falcon-512 signatures ────────────────────────────────
+//
+//   ed25519_falcon512_keygen()                           -> (public_key, secret_key)
+//   ed25519_falcon512_sign(sk, msg, context=b"")         -> composite signature
+//   ed25519_falcon512_verify(pk, msg, sig, context=b"")  -> bool
+//   ed25519_falcon512_verify_strict(...)                 -> None, or raises
+//
+//   public_key = ed25519_pk (32)   || falcon512_pk (897)
+//   secret_key = ed25519_seed (32) || falcon512_sk (1281)
+//   signature  = tag (0x01) || ed25519_sig (64) || falcon512_sig (rest)
+//
+// Both halves sign the label-prefixed message rather than the message itself,
+// so neither component signature can be stripped out and passed off as a
+// plain Ed25519 or Falcon signature over the same bytes. Verification needs
+// both halves to validate. A context string is bound into the message with
+// sign::context_message before the label goes on.
+
+use ed25519_dalek::Signer;
+use pqcrypto_falcon::falcon512;
+
+use crate::sign_traits;
+
+const ED25519_FALCON512_TAG: u8 = 0x01;
+const ED25519_FALCON512_LABEL: &[u8] = b"entropic-chaos composite Ed25519+Falcon-512\0";
+
+const ED25519_KEY_LEN: usize = 32;
+const ED25519_SIG_LEN: usize = 64;
+
+fn composite_message(label: &[u8], msg: &[u8]) -> Vec<u8> {
+    let mut m = Vec::with_capacity(label.len() + msg.len());
+    m.extend_from_slice(label);
+    m.extend_from_slice(msg);
+    m
+}
+
+fn split_composite_key<'a>(
+    what: &str,
+    bytes: &'a [u8],
+    pq_len: usize,
+) -> PyResult<(&'a [u8], &'a [u8])> {
+    check_length(what, bytes, ED25519_KEY_LEN + pq_len)?;
+    Ok(bytes.split_at(ED25519_KEY_LEN))
+}
+
+pub(crate) const ED25519_FALCON512: crate::sign::SignatureAlgorithm =
+    crate::sign::SignatureAlgorithm {
+        name: "ed25519falcon512",
+        public_key_bytes: ED25519_KEY_LEN + falcon512::public_key_bytes(),
+        secret_key_bytes: ED25519_KEY_LEN + falcon512::secret_key_bytes(),
+        signature_bytes: 1 + ED25519_SIG_LEN + falcon512::signature_bytes(),
+        keygen: ed25519_falcon512_keygen,
+        sign: |py, sk_bytes, msg| ed25519_falcon512_sign(py, sk_bytes, msg, Buffer::empty()),
+        verify: |pk_bytes, msg, sig_bytes| {
+            ed25519_falcon512_verify(pk_bytes, msg, sig_bytes, Buffer::empty())
+        },
+    };
+
+#[pyfunction]
+pub fn ed25519_falcon512_keygen(py: Python) -> PyResult<(Py<PyBytes>, Py<SecretBytes>)> {
+    let seed = random_key()?;
+    let ed_pk = ed25519_dalek::SigningKey::from_bytes(&seed).verifying_key();
+    let (falcon_pk, falcon_sk) = falcon512::keypair();
+
+    let falcon_pk = <falcon512::PublicKey as sign_traits::PublicKey>::as_bytes(&falcon_pk);
+    let falcon_sk = <falcon512::SecretKey as sign_traits::SecretKey>::as_bytes(&falcon_sk);
+
+    let pk = PyBytes::new_bound_with(py, ED25519_KEY_LEN + falcon_pk.len(), |buf| {
+        buf[..ED25519_KEY_LEN].copy_from_slice(ed_pk.as_bytes());
+        buf[ED25519_KEY_LEN..].copy_from_slice(falcon_pk);
+        Ok(())
+    })?;
+    let sk = secret_bytes_with(py, ED25519_KEY_LEN + falcon_sk.len(), |buf| {
+        buf[..ED25519_KEY_LEN].copy_from_slice(&seed[..]);
+        buf[ED25519_KEY_LEN..].copy_from_slice(falcon_sk);
+        Ok(())
+    })?;
+
+    crate::pct::checked_signature_keypair(py, &ED25519_FALCON512, (pk.unbind(), sk))
+}
+
+#[pyfunction]
+#[pyo3(signature = (sk_bytes, msg, context = Buffer::empty()))]
+pub fn ed25519_falcon512_sign(
+    py: Python,
+    sk_bytes: Buffer,
+    msg: Buffer,
+    context: Buffer,
+) -> PyResult<Py<PyBytes>> {
+    let (ed_seed, falcon_sk) = split_composite_key(
+        "Ed25519+Falcon-512 secret key",
+        &sk_bytes,
+        falcon512::secret_key_bytes(),
+    )?;
+
+    let ed_seed = Zeroizing::new(<[u8; ED25519_KEY_LEN]>::try_from(ed_seed).unwrap());
+    let ed_sk = ed25519_dalek::SigningKey::from_bytes(&ed_seed);
+    let falcon_sk = <falcon512::SecretKey as sign_traits::SecretKey>::from_bytes(falcon_sk)
+        .map_err(length_err)?;
+
+    let m = composite_message(
+        ED25519_FALCON512_LABEL,
+        &crate::sign::context_message(&context, &msg)?,
+    );
+    let ed_sig = ed_sk.sign(&m).to_bytes();
+    let falcon_sig = falcon512::detached_sign(&m, &falcon_sk);
+    let falcon_sig =
+        <falcon512::DetachedSignature as sign_traits::DetachedSignature>::as_bytes(&falcon_sig);
+
+    let sig = PyBytes::new_bound_with(py, 1 + ED25519_SIG_LEN + falcon_sig.len(), |buf| {
+        buf[0] = ED25519_FALCON512_TAG;
+        buf[1..1 + ED25519_SIG_LEN].copy_from_slice(&ed_sig);
+        buf[1 + ED25519_SIG_LEN..].copy_from_slice(falcon_sig);
+        Ok(())
+    })?;
+
+    Ok(sig.unbind())
+}
+
+#[pyfunction]
+#[pyo3(signature = (pk_bytes, msg, sig_bytes, context = Buffer::empty()))]
+pub fn ed25519_falcon512_verify(
+    pk_bytes: Buffer,
+    msg: Buffer,
+    sig_bytes: Buffer,
+    context: Buffer,
+) -> PyResult<bool> {
+    let (ed_pk, falcon_pk) = split_composite_key(
+        "Ed25519+Falcon-512 public key",
+        &pk_bytes,
+        falcon512::public_key_bytes(),
+    )?;
+
+    let (tag, rest) = sig_bytes
+        .split_first()
+        .ok_or_else(|| InvalidLengthError::new_err("empty composite signature"))?;
+    if *tag != ED25519_FALCON512_TAG {
+        return Err(SignatureError::new_err(format!(
+            "not an Ed25519+Falcon-512 composite signature (tag 0x{:02x})",
+            tag
+        )));
+    }
+    if rest.len() <= ED25519_SIG_LEN {
+        return Err(InvalidLengthError::new_err(format!(
+            "composite signature too short ({} bytes)",
+            sig_bytes.len()
+        )));
+    }
+    check_max_length(
+        "Ed25519+Falcon-512 signature",
+        &sig_bytes,
+        ED25519_FALCON512.signature_bytes,
+    )?;
+    let (ed_sig, falcon_sig) = rest.split_at(ED25519_SIG_LEN);
+
+    let ed_pk = ed25519_dalek::VerifyingKey::from_bytes(ed_pk.try_into().unwrap())
+        .map_err(|e| InvalidKeyError::new_err(e.to_string()))?;
+    let ed_sig = ed25519_dalek::Signature::from_slice(ed_sig)
+        .map_err(|e| InvalidLengthError::new_err(e.to_string()))?;
+    let falcon_pk = <falcon512::PublicKey as sign_traits::PublicKey>::from_bytes(falcon_pk)
+        .map_err(length_err)?;
+    let falcon_sig =
+        <falcon512::DetachedSignature as sign_traits::DetachedSignature>::from_bytes(falcon_sig)
+            .map_err(length_err)?;
+
+    let m = composite_message(
+        ED25519_FALCON512_LABEL,
+        &crate::sign::context_message(&context, &msg)?,
+    );
+    let ed_ok = ed_pk.verify_strict(&m, &ed_sig).is_ok();
+    let falcon_ok = falcon512::verify_detached_signature(&falcon_sig, &m, &falcon_pk).is_ok();
+
+    Ok(ed_ok && falcon_ok)
+}
+
+#[pyfunction]
+#[pyo3(signature = (pk_bytes, msg, sig_bytes, context = Buffer::empty()))]
+pub fn ed25519_falcon512_verify_strict(
+    pk_bytes: Buffer,
+    msg: Buffer,
+    sig_bytes: Buffer,
+    context: Buffer,
+) -> PyResult<()> {
+    let (msg_len, sig_len) = (msg.len(), sig_bytes.len());
+    if ed25519_falcon512_verify(pk_bytes, msg, sig_bytes, context)? {
+        Ok(())
+    } else {
+        Err(verification_failed(
+            ED25519_FALCON512.name,
+            msg_len,
+            sig_len,
+        ))
+    }
+}
+
+// ─── Composite ML-DSA + ECDSA P-256 (draft-ietf-lamps-pq-composite-sigs) ──────
+//
+//   mldsa44_p256_keygen()                            -> (public_key, secret_key)
+//   mldsa44_p256_sign(sk, msg, context=b"")          -> composite signature
+//   mldsa44_p256_verify(pk, msg, sig, context=b"")   -> bool
+//   mldsa44_p256_verify_strict(...)                  -> None, or raises
+//
+// and the same for mldsa65_p256_*. Encodings follow the draft so keys and
+// signatures interoperate with other implementations of it:
+//
+//   public_key = mldsa_pk || ecdsa_pk (SEC1 uncompressed point, 65 bytes)
+//   secret_key = mldsa_seed (32) || ecdsa_sk (SEC1 ECPrivateKey DER)
+//   signature  = mldsa_sig || ecdsa_sig (DER Ecdsa-Sig-Value)
+//
+// Both components sign M' = Prefix || Label || len(context) || context || PH(msg);
+// ML-DSA also takes the Label as its own context string. ML-DSA is hedged and
+// runs on the RustCrypto core, since the draft stores the 32-byte ML-DSA seed
+// rather than the expanded key PQClean works with.
+
+use ml_dsa::{KeySizeUser, Keypair, MlDsa44, MlDsa65, MlDsaParams};
+use p256::ecdsa::signature::Verifier as _;
+use sha2::{Digest, Sha256, Sha512};
+
+const COMPOSITE_PREFIX: &[u8] = b"CompositeAlgorithmSignatures2025";
+const MLDSA44_P256_LABEL: &[u8] = b"COMPSIG-MLDSA44-ECDSA-P256-SHA256";
+const MLDSA65_P256_LABEL: &[u8] = b"COMPSIG-MLDSA65-ECDSA-P256-SHA512";
+
+const MLDSA_SEED_LEN: usize = 32;
+const P256_POINT_LEN: usize = 65;
+
+fn composite_mldsa_message<D: Digest>(
+    label: &[u8],
+    context: &[u8],
+    msg: &[u8],
+) -> PyResult<Vec<u8>> {
+    let context_len = u8::try_from(context.len()).map_err(|_| {
+        PyValueError::new_err(format!(
+            "context string must be at most 255 bytes, got {}",
+            context.len()
+        ))
+    })?;
+
+    let mut m = Vec::new();
+    m.extend_from_slice(COMPOSITE_PREFIX);
+    m.extend_from_slice(label);
+    m.push(context_len);
+    m.extend_from_slice(context);
+    m.extend_from_slice(&D::digest(msg));
+    Ok(m)
+}
+
+fn random_p256_key() -> PyResult<p256::SecretKey> {
+    // Rejection-sample a scalar in [1, n); a miss is ~2^-32 likely.
+    loop {
+        if let Ok(sk) = p256::SecretKey::from_slice(&random_key()?[..]) {
+            return Ok(sk);
+        }
+    }
+}
+
+fn mldsa_p256_keygen<P: MlDsaParams>(py: Python) -> PyResult<(Py<PyBytes>, Py<SecretBytes>)> {
+    let seed = random_key()?;
+    let mldsa_pk = ml_dsa::SigningKey::<P>::from_seed(&(*seed).into())
+        .verifying_key()
+        .encode();
+
+    let ecdsa_sk = random_p256_key()?;
+    let ecdsa_pk = ecdsa_sk.public_key().to_sec1_bytes();
+    let ecdsa_sk = ecdsa_sk
+        .to_sec1_der()
+        .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+
+    let pk = PyBytes::new_bound_with(py, mldsa_pk.len() + ecdsa_pk.len(), |buf| {
+        buf[..mldsa_pk.len()].copy_from_slice(&mldsa_pk);
+        buf[mldsa_pk.len()..].copy_from_slice(&ecdsa_pk);
+        Ok(())
+    })?;
+    let sk = secret_bytes_with(py, MLDSA_SEED_LEN + ecdsa_sk.len(), |buf| {
+        buf[..MLDSA_SEED_LEN].copy_from_slice(&seed[..]);
+        buf[MLDSA_SEED_LEN..].copy_from_slice(&ecdsa_sk);
+        Ok(())
+    })?;
+
+    Ok((pk.unbind(), sk))
+}
+
+fn mldsa_p256_sign<P: MlDsaParams, D: Digest>(
+    py: Python,
+    label: &[u8],
+    sk_bytes: &[u8],
+    msg: &[u8],
+    context: &[u8],
+) -> PyResult<Py<PyBytes>> {
+    if sk_bytes.len() <= MLDSA_SEED_LEN {
+        return Err(InvalidLengthError::new_err(format!(
+            "composite secret key too short ({} bytes)",
+            sk_bytes.len()
+        )));
+    }
+    let (seed, ecdsa_sk) = sk_bytes.split_at(MLDSA_SEED_LEN);
+
+    let seed = Zeroizing::new(<[u8; MLDSA_SEED_LEN]>::try_from(seed).unwrap());
+    let mldsa_sk = ml_dsa::SigningKey::<P>::from_seed(&(*seed).into());
+    let ecdsa_sk = p256::SecretKey::from_sec1_der(ecdsa_sk)
+        .map_err(|e| InvalidKeyError::new_err(format!("invalid ECDSA P-256 secret key: {}", e)))?;
+    let ecdsa_sk = p256::ecdsa::SigningKey::from(ecdsa_sk);
+
+    let m = composite_mldsa_message::<D>(label, context, msg)?;
+
+    // ML-DSA.Sign(sk, M', ctx = Label): the FIPS 204 pure-mode domain
+    // separator and context go in front of M', with fresh hedging randomness.
+    let rnd = random_key()?;
+    let label_len = [0u8, label.len() as u8];
+    let mldsa_sig = mldsa_sk
+        .expanded_key()
+        .sign_internal(&[&label_len, label, &m], &(*rnd).into())
+        .encode();
+
+    let ecdsa_sig: p256::ecdsa::Signature = ecdsa_sk.sign(&m);
+    let ecdsa_sig = ecdsa_sig.to_der();
+    let ecdsa_sig = ecdsa_sig.as_bytes();
+
+    let sig = PyBytes::new_bound_with(py, mldsa_sig.len() + ecdsa_sig.len(), |buf| {
+        buf[..mldsa_sig.len()].copy_from_slice(&mldsa_sig);
+        buf[mldsa_sig.len()..].copy_from_slice(ecdsa_sig);
+        Ok(())
+    })?;
+
+    Ok(sig.unbind())
+}
+
+fn mldsa_p256_verify<P: MlDsaParams, D: Digest>(
+    label: &[u8],
+    pk_bytes: &[u8],
+    msg: &[u8],
+    sig_bytes: &[u8],
+    context: &[u8],
+) -> PyResult<bool> {
+    let mldsa_pk_len = ml_dsa::VerifyingKey::<P>::key_size();
+    check_length(
+        "composite public key",
+        pk_bytes,
+        mldsa_pk_len + P256_POINT_LEN,
+    )?;
+    let (mldsa_pk, ecdsa_pk) = pk_bytes.split_at(mldsa_pk_len);
+
+    let mldsa_sig_len = ml_dsa::EncodedSignature::<P>::default().len();
+    if sig_bytes.len() <= mldsa_sig_len {
+        return Err(InvalidLengthError::new_err(format!(
+            "composite signature too short ({} bytes)",
+            sig_bytes.len()
+        )));
+    }
+    let (mldsa_sig, ecdsa_sig) = sig_bytes.split_at(mldsa_sig_len);
+
+    let mldsa_pk = ml_dsa::VerifyingKey::<P>::decode(mldsa_pk.try_into().unwrap());
+    let ecdsa_pk = p256::ecdsa::VerifyingKey::from_sec1_bytes(ecdsa_pk)
+        .map_err(|e| InvalidKeyError::new_err(format!("invalid ECDSA P-256 public key: {}", e)))?;
+
+    // A malformed component signature is just a signature that doesn't verify.
+    let Some(mldsa_sig) = ml_dsa::Signature::<P>::decode(mldsa_sig.try_into().unwrap()) else {
+        return Ok(false);
+    };
+    let Ok(ecdsa_sig) = p256::ecdsa::Signature::from_der(ecdsa_sig) else {
+        return Ok(false);
+    };
+
+    let m = composite_mldsa_message::<D>(label, context, msg)?;
+    let mldsa_ok = mldsa_pk.verify_with_context(&m, label, &mldsa_sig);
+    let ecdsa_ok = ecdsa_pk.verify(&m, &ecdsa_sig).is_ok();
+
+    Ok(mldsa_ok && ecdsa_ok)
+}
+
+fn mldsa_p256_verify_strict<P: MlDsaParams, D: Digest>(
+    name: &str,
+    label: &[u8],
+    pk_bytes: &[u8],
+    msg: &[u8],
+    sig_bytes: &[u8],
+    context: &[u8],
+) -> PyResult<()> {
+    if mldsa_p256_verify::<P, D>(label, pk_bytes, msg, sig_bytes, context)? {
+        Ok(())
+    } else {
+        Err(verification_failed(name, msg.len(), sig_bytes.len()))
+    }
+}
+
+// SEC1 ECPrivateKey DER as keygen writes it (named curve and public key
+// included), and the longest DER Ecdsa-Sig-Value for P-256.
+const P256_SEC1_DER_LEN: usize = 121;
+const P256_MAX_DER_SIG_LEN: usize = 72;
+
+pub(crate) const MLDSA44_P256: crate::sign::SignatureAlgorithm = crate::sign::SignatureAlgorithm {
+    name: "mldsa44p256",
+    public_key_bytes: pqcrypto_mldsa::mldsa44::public_key_bytes() + P256_POINT_LEN,
+    secret_key_bytes: MLDSA_SEED_LEN + P256_SEC1_DER_LEN,
+    signature_bytes: pqcrypto_mldsa::mldsa44::signature_bytes() + P256_MAX_DER_SIG_LEN,
+    keygen: mldsa44_p256_keygen,
+    sign: |py, sk_bytes, msg| {
+        mldsa_p256_sign::<MlDsa44, Sha256>(py, MLDSA44_P256_LABEL, &sk_bytes, &msg, b"")
+    },
+    verify: |pk_bytes, msg, sig_bytes| {
+        mldsa_p256_verify::<MlDsa44, Sha256>(MLDSA44_P256_LABEL, &pk_bytes, &msg, &sig_bytes, b"")
+    },
+};
+
+pub(crate) const MLDSA65_P256: crate::sign::SignatureAlgorithm = crate::sign::SignatureAlgorithm {
+    name: "mldsa65p256",
+    public_key_bytes: pqcrypto_mldsa::mldsa65::public_key_bytes() + P256_POINT_LEN,
+    secret_key_bytes: MLDSA_SEED_LEN + P256_SEC1_DER_LEN,
+    signature_bytes: pqcrypto_mldsa::mldsa65::signature_bytes() + P256_MAX_DER_SIG_LEN,
+    keygen: mldsa65_p256_keygen,
+    sign: |py, sk_bytes, msg| {
+        mldsa_p256_sign::<MlDsa65, Sha512>(py, MLDSA65_P256_LABEL, &sk_bytes, &msg, b"")
+    },
+    verify: |pk_bytes, msg, sig_bytes| {
+        mldsa_p256_verify::<MlDsa65, Sha512>(MLDSA65_P256_LABEL, &pk_bytes, &msg, &sig_bytes, b"")
+    },
+};
+
+#[pyfunction]
+pub fn mldsa44_p256_keygen(py: Python) -> PyResult<(Py<PyBytes>, Py<SecretBytes>)> {
+    let keys = mldsa_p256_keygen::<MlDsa44>(py)?;
+    crate::pct::checked_signature_keypair(py, &MLDSA44_P256, keys)
+}
+
+#[pyfunction]
+#[pyo3(signature = (sk_bytes, msg, context = Buffer::empty()))]
+pub fn mldsa44_p256_sign(
+    py: Python,
+    sk_bytes: Buffer,
+    msg: Buffer,
+    context: Buffer,
+) -> PyResult<Py<PyBytes>> {
+    mldsa_p256_sign::<MlDsa44, Sha256>(py, MLDSA44_P256_LABEL, &sk_bytes, &msg, &context)
+}
+
+#[pyfunction]
+#[pyo3(signature = (pk_bytes, msg, sig_bytes, context = Buffer::empty()))]
+pub fn mldsa44_p256_verify(
+    pk_bytes: Buffer,
+    msg: Buffer,
+    sig_bytes: Buffer,
+    context: Buffer,
+) -> PyResult<bool> {
+    mldsa_p256_verify::<MlDsa44, Sha256>(MLDSA44_P256_LABEL, &pk_bytes, &msg, &sig_bytes, &context)
+}
+
+#[pyfunction]
+#[pyo3(signature = (pk_bytes, msg, sig_bytes, context = Buffer::empty()))]
+pub fn mldsa44_p256_verify_strict(
+    pk_bytes: Buffer,
+    msg: Buffer,
+    sig_bytes: Buffer,
+    context: Buffer,
+) -> PyResult<()> {
+    mldsa_p256_verify_strict::<MlDsa44, Sha256>(
+        MLDSA44_P256.name,
+        MLDSA44_P256_LABEL,
+        &pk_bytes,
+        &msg,
+        &sig_bytes,
+        &context,
+    )
+}
+
+#[pyfunction]
+pub fn mldsa65_p256_keygen(py: Python) -> PyResult<(Py<PyBytes>, Py<SecretBytes>)> {
+    let keys = mldsa_p256_keygen::<MlDsa65>(py)?;
+    crate::pct::checked_signature_keypair(py, &MLDSA65_P256, keys)
+}
+
+#[pyfunction]
+#[pyo3(signature = (sk_bytes, msg, context = Buffer::empty()))]
+pub fn mldsa65_p256_sign(
+    py: Python,
+    sk_bytes: Buffer,
+    msg: Buffer,
+    context: Buffer,
+) -> PyResult<Py<PyBytes>> {
+    mldsa_p256_sign::<MlDsa65, Sha512>(py, MLDSA65_P256_LABEL, &sk_bytes, &msg, &context)
+}
+
+#[pyfunction]
+#[pyo3(signature = (pk_bytes, msg, sig_bytes, context = Buffer::empty()))]
+pub fn mldsa65_p256_verify(
+    pk_bytes: Buffer,
+    msg: Buffer,
+    sig_bytes: Buffer,
+    context: Buffer,
+) -> PyResult<bool> {
+    mldsa_p256_verify::<MlDsa65, Sha512>(MLDSA65_P256_LABEL, &pk_bytes, &msg, &sig_bytes, &context)
+}
+
+#[pyfunction]
+#[pyo3(signature = (pk_bytes, msg, sig_bytes, context = Buffer::empty()))]
+pub fn mldsa65_p256_verify_strict(
+    pk_bytes: Buffer,
+    msg: Buffer,
+    sig_bytes: Buffer,
+    context: Buffer,
+) -> PyResult<()> {
+    mldsa_p256_verify_strict::<MlDsa65, Sha512>(
+        MLDSA65_P256.name,
+        MLDSA65_P256_LABEL,
+        &pk_bytes,
+        &msg,
+        &sig_bytes,
+        &context,
+    )
+}
+
+// ─── Generic hybrid KEM combiner ──────────────────────────────────────────────
+//
+//   hybrid_combine(ss1, ss2, ct1, ct2, pk1, pk2, label) -> 32-byte shared secret
+//
+// For pairing any classical KEM with any PQ KEM:
+//
+//   prk = HKDF-Extract(salt = ss1, IKM = ss2)                  (HMAC-SHA256)
+//   ss  = HKDF-Expand(prk, info = label || ct1 || ct2 || pk1 || pk2, 32)
+//
+// HMAC is a dual PRF (a PRF when keyed through either input), so prk is
+// pseudorandom as long as either component secret is. Each info field carries
+// a 4-byte big-endian length so no two distinct transcripts encode alike;
+// binding the ciphertexts and public keys stops one component's ciphertext
+// from being swapped or replayed under the other's secret.
+
+use hkdf::Hkdf;
+
+const COMBINED_SECRET_LEN: usize = 32;
+
+fn push_length_prefixed(out: &mut Vec<u8>, field: &[u8]) -> PyResult<()> {
+    let len = u32::try_from(field.len())
+        .map_err(|_| InvalidLengthError::new_err("combiner input longer than 2^32 - 1 bytes"))?;
+    out.extend_from_slice(&len.to_be_bytes());
+    out.extend_from_slice(field);
+    Ok(())
+}
+
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+pub fn hybrid_combine(
+    py: Python,
+    ss1: Buffer,
+    ss2: Buffer,
+    ct1: Buffer,
+    ct2: Buffer,
+    pk1: Buffer,
+    pk2: Buffer,
+    label: Buffer,
+) -> PyResult<Py<SecretBytes>> {
+    if ss1.is_empty() || ss2.is_empty() {
+        return Err(InvalidLengthError::new_err(
+            "shared secrets must not be empty",
+        ));
+    }
+
+    let mut info = Vec::new();
+    for field in [label, ct1, ct2, pk1, pk2] {
+        push_length_prefixed(&mut info, &field)?;
+    }
+
+    let hk = Hkdf::<Sha256>::new(Some(&ss1), &ss2);
+    let mut ss = Zeroizing::new([0u8; COMBINED_SECRET_LEN]);
+    hk.expand(&info, ss.as_mut())
+        .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+
+    secret_bytes(py, &ss[..])
+}
@@ -0,0 +1,651 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// JWE with ML-KEM key management
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// For payloads that JOSE tooling on the other end has to decrypt:
+//
+//   token = jwe_encrypt(pk, b'{"card": "..."}', kid="k1")        # compact
+//   jwe = jwe_encrypt_general([pk_a, pk_b], data, kids=["a", "b"])  # JSON
+//   data = jwe_decrypt(sk, token)        # either form; JSON as dict or text
+//
+// Key management follows ECDH-ES (RFC 7518, section 4.6) with an ML-KEM
+// encapsulation in place of the ephemeral Diffie-Hellman, in the shape of
+// the JOSE PQ KEM draft: the KEM ciphertext travels in the "ek" header
+// parameter, and the shared secret is Z for the Concat KDF,
+//
+//   key = SHA-256(1 u32 | Z | len u32 | AlgorithmID | 0 u32 | 0 u32 | bits u32)
+//
+// (no PartyUInfo / PartyVInfo). With "alg" MLKEM512 / 768 / 1024 that key is
+// the content encryption key itself, with AlgorithmID the "enc" value, as
+// for direct key agreement; with MLKEM512+A128KW, MLKEM768+A192KW or
+// MLKEM1024+A256KW it wraps a random one with AES Key Wrap (RFC 3394), with
+// AlgorithmID the "alg" value. Content is encrypted with "enc" A256GCM or
+// A128GCM over the protected header as RFC 7516 has it.
+//
+// jwe_encrypt() produces the compact serialization, by default with direct
+// key agreement. jwe_encrypt_general() produces the general JSON
+// serialization for any number of recipients, each with key wrapping, and
+// an optional "aad". jwe_decrypt() takes the KEM from the secret key, tries
+// each recipient for that KEM (only those with a matching "kid", if one is
+// given), and raises DecryptionError if none decrypts. "zip" and "crit" are
+// refused, and only ML-KEM keys are accepted: Kyber has no JOSE identifiers.
+
+use aes_gcm::aead::{AeadInOut, KeyInit};
+use aes_gcm::aes::cipher::{BlockCipherDecrypt, BlockCipherEncrypt};
+use aes_gcm::aes::{Aes128, Aes192, Aes256, Block};
+use aes_gcm::{Aes128Gcm, Aes256Gcm};
+use base64ct::{Base64UrlUnpadded, Encoding};
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict, PyList, PyString};
+use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
+
+use crate::buffer::Buffer;
+use crate::errors::{DecryptionError, InvalidLengthError};
+use crate::kem::KemAlgorithm;
+use crate::noise::{decapsulate, encapsulate};
+
+const IV_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+
+// ─── Algorithms ───────────────────────────────────────────────────────────────
+
+struct KeyManagement {
+    kem: &'static KemAlgorithm,
+    direct: &'static str,
+    key_wrap: &'static str,
+    kek_len: usize,
+}
+
+const KEY_MANAGEMENT: &[KeyManagement] = &[
+    KeyManagement {
+        kem: &crate::mlkem512::ALGORITHM,
+        direct: "MLKEM512",
+        key_wrap: "MLKEM512+A128KW",
+        kek_len: 16,
+    },
+    KeyManagement {
+        kem: &crate::mlkem768::ALGORITHM,
+        direct: "MLKEM768",
+        key_wrap: "MLKEM768+A192KW",
+        kek_len: 24,
+    },
+    KeyManagement {
+        kem: &crate::mlkem1024::ALGORITHM,
+        direct: "MLKEM1024",
+        key_wrap: "MLKEM1024+A256KW",
+        kek_len: 32,
+    },
+];
+
+fn by_key_length(
+    what: &str,
+    len: usize,
+    key_bytes: fn(&KemAlgorithm) -> usize,
+) -> PyResult<&'static KeyManagement> {
+    KEY_MANAGEMENT
+        .iter()
+        .find(|km| key_bytes(km.kem) == len)
+        .ok_or_else(|| {
+            InvalidLengthError::new_err(format!(
+                "JWE {}: {} bytes isn't an ML-KEM key length",
+                what, len
+            ))
+        })
+}
+
+#[derive(Clone, Copy)]
+pub(crate) enum Enc {
+    A128Gcm,
+    A256Gcm,
+}
+
+impl Enc {
+    pub(crate) fn from_name(name: &str) -> PyResult<Self> {
+        match name {
+            "A128GCM" => Ok(Enc::A128Gcm),
+            "A256GCM" => Ok(Enc::A256Gcm),
+            _ => Err(PyValueError::new_err(format!(
+                "unsupported JWE enc '{}', expected A256GCM or A128GCM",
+                name
+            ))),
+        }
+    }
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Enc::A128Gcm => "A128GCM",
+            Enc::A256Gcm => "A256GCM",
+        }
+    }
+
+    pub(crate) fn key_len(self) -> usize {
+        match self {
+            Enc::A128Gcm => 16,
+            Enc::A256Gcm => 32,
+        }
+    }
+
+    pub(crate) fn seal(self, key: &[u8], iv: &[u8], aad: &[u8], buf: &mut Vec<u8>) -> PyResult<()> {
+        let iv = iv.try_into().expect("IV length");
+        match self {
+            Enc::A128Gcm => Aes128Gcm::new_from_slice(key)
+                .expect("key length")
+                .encrypt_in_place(iv, aad, buf),
+            Enc::A256Gcm => Aes256Gcm::new_from_slice(key)
+                .expect("key length")
+                .encrypt_in_place(iv, aad, buf),
+        }
+        .map_err(|_| PyRuntimeError::new_err("JWE content encryption failed"))
+    }
+
+    /// Decrypt; false if it doesn't authenticate.
+    pub(crate) fn open(self, key: &[u8], iv: &[u8], aad: &[u8], buf: &mut Vec<u8>) -> bool {
+        let Ok(iv) = iv.try_into() else {
+            return false;
+        };
+        match self {
+            Enc::A128Gcm => Aes128Gcm::new_from_slice(key)
+                .expect("key length")
+                .decrypt_in_place(iv, aad, buf),
+            Enc::A256Gcm => Aes256Gcm::new_from_slice(key)
+                .expect("key length")
+                .decrypt_in_place(iv, aad, buf),
+        }
+        .is_ok()
+    }
+}
+
+// ─── Concat KDF and AES Key Wrap ──────────────────────────────────────────────
+
+/// RFC 7518's Concat KDF, for at most one SHA-256 output.
+fn concat_kdf(z: &[u8], algorithm_id: &str, len: usize) -> Zeroizing<Vec<u8>> {
+    debug_assert!(len <= 32);
+    let digest = Sha256::new()
+        .chain_update(1u32.to_be_bytes())
+        .chain_update(z)
+        .chain_update((algorithm_id.len() as u32).to_be_bytes())
+        .chain_update(algorithm_id.as_bytes())
+        .chain_update(0u32.to_be_bytes())
+        .chain_update(0u32.to_be_bytes())
+        .chain_update(((len * 8) as u32).to_be_bytes())
+        .finalize();
+    Zeroizing::new(digest[..len].to_vec())
+}
+
+enum Kek {
+    Aes128(Aes128),
+    Aes192(Aes192),
+    Aes256(Aes256),
+}
+
+impl Kek {
+    fn new(key: &[u8]) -> Self {
+        match key.len() {
+            16 => Kek::Aes128(Aes128::new_from_slice(key).expect("key length")),
+            24 => Kek::Aes192(Aes192::new_from_slice(key).expect("key length")),
+            _ => Kek::Aes256(Aes256::new_from_slice(key).expect("key length")),
+        }
+    }
+
+    fn encrypt(&self, block: &mut Block) {
+        match self {
+            Kek::Aes128(c) => c.encrypt_block(block),
+            Kek::Aes192(c) => c.encrypt_block(block),
+            Kek::Aes256(c) => c.encrypt_block(block),
+        }
+    }
+
+    fn decrypt(&self, block: &mut Block) {
+        match self {
+            Kek::Aes128(c) => c.decrypt_block(block),
+            Kek::Aes192(c) => c.decrypt_block(block),
+            Kek::Aes256(c) => c.decrypt_block(block),
+        }
+    }
+}
+
+const KW_IV: [u8; 8] = [0xa6; 8];
+
+/// RFC 3394 key wrap of a key whose length is a multiple of 8.
+pub(crate) fn key_wrap(kek: &[u8], key: &[u8]) -> Vec<u8> {
+    let kek = Kek::new(kek);
+    let n = key.len() / 8;
+    let mut a = KW_IV;
+    let mut r: Vec<[u8; 8]> = key.chunks(8).map(|c| c.try_into().unwrap()).collect();
+    for j in 0..6 {
+        for (i, ri) in r.iter_mut().enumerate() {
+            let mut block = Block::default();
+            block[..8].copy_from_slice(&a);
+            block[8..].copy_from_slice(ri);
+            kek.encrypt(&mut block);
+            let t = (n * j + i + 1) as u64;
+            a = (u64::from_be_bytes(block[..8].try_into().unwrap()) ^ t).to_be_bytes();
+            ri.copy_from_slice(&block[8..]);
+        }
+    }
+    [&a[..], &r.concat()].concat()
+}
+
+/// RFC 3394 key unwrap; None if the integrity check fails.
+pub(crate) fn key_unwrap(kek: &[u8], wrapped: &[u8]) -> Option<Zeroizing<Vec<u8>>> {
+    if wrapped.len() < 24 || !wrapped.len().is_multiple_of(8) {
+        return None;
+    }
+    let kek = Kek::new(kek);
+    let n = wrapped.len() / 8 - 1;
+    let mut a: [u8; 8] = wrapped[..8].try_into().unwrap();
+    let mut r: Zeroizing<Vec<[u8; 8]>> = Zeroizing::new(
+        wrapped[8..]
+            .chunks(8)
+            .map(|c| c.try_into().unwrap())
+            .collect(),
+    );
+    for j in (0..6).rev() {
+        for i in (0..n).rev() {
+            let t = (n * j + i + 1) as u64;
+            let mut block = Block::default();
+            block[..8].copy_from_slice(&(u64::from_be_bytes(a) ^ t).to_be_bytes());
+            block[8..].copy_from_slice(&r[i]);
+            kek.decrypt(&mut block);
+            a.copy_from_slice(&block[..8]);
+            r[i].copy_from_slice(&block[8..]);
+        }
+    }
+    if !crate::util::ct_eq(&a, &KW_IV) {
+        return None;
+    }
+    Some(Zeroizing::new(r.concat()))
+}
+
+// ─── Encoding helpers ─────────────────────────────────────────────────────────
+
+fn b64(data: &[u8]) -> String {
+    Base64UrlUnpadded::encode_string(data)
+}
+
+fn malformed(what: &str) -> PyErr {
+    PyValueError::new_err(format!("malformed JWE: {}", what))
+}
+
+fn decode(what: &str, part: &str) -> PyResult<Vec<u8>> {
+    Base64UrlUnpadded::decode_vec(part).map_err(|_| malformed(&format!("{} isn't base64url", what)))
+}
+
+fn json<'py>(py: Python<'py>) -> PyResult<Bound<'py, PyModule>> {
+    py.import_bound("json")
+}
+
+fn to_json(py: Python, value: &Bound<'_, PyDict>) -> PyResult<String> {
+    let kwargs = PyDict::new_bound(py);
+    kwargs.set_item("separators", (",", ":"))?;
+    json(py)?
+        .call_method("dumps", (value,), Some(&kwargs))?
+        .extract()
+}
+
+fn from_json<'py>(py: Python<'py>, what: &str, text: &str) -> PyResult<Bound<'py, PyDict>> {
+    json(py)?
+        .call_method1("loads", (text,))
+        .map_err(|_| malformed(&format!("{} isn't JSON", what)))?
+        .downcast_into::<PyDict>()
+        .map_err(|_| malformed(&format!("{} isn't a JSON object", what)))
+}
+
+fn string_member(dict: &Bound<'_, PyDict>, name: &str) -> PyResult<Option<String>> {
+    match dict.get_item(name)? {
+        Some(value) => value
+            .extract()
+            .map(Some)
+            .map_err(|_| malformed(&format!("'{}' isn't a string", name))),
+        None => Ok(None),
+    }
+}
+
+/// Add the caller's extra header members, refusing the ones set here.
+fn add_members(
+    header: &Bound<'_, PyDict>,
+    extra: Option<&Bound<'_, PyDict>>,
+    reserved: &[&str],
+) -> PyResult<()> {
+    if let Some(extra) = extra {
+        for name in reserved {
+            if extra.contains(*name)? {
+                return Err(PyValueError::new_err(format!(
+                    "header can't set '{}'",
+                    name
+                )));
+            }
+        }
+        header.update(extra.as_mapping())?;
+    }
+    Ok(())
+}
+
+fn random_bytes(len: usize) -> PyResult<Zeroizing<Vec<u8>>> {
+    let mut out = Zeroizing::new(vec![0u8; len]);
+    getrandom::fill(&mut out).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+    Ok(out)
+}
+
+/// IV, ciphertext and tag of the content.
+fn encrypt_content(enc: Enc, cek: &[u8], aad: &[u8], plaintext: &[u8]) -> PyResult<[Vec<u8>; 3]> {
+    let iv = random_bytes(IV_LEN)?.to_vec();
+    let mut buf = plaintext.to_vec();
+    enc.seal(cek, &iv, aad, &mut buf)?;
+    let tag = buf.split_off(buf.len() - TAG_LEN);
+    Ok([iv, buf, tag])
+}
+
+// ─── Encryption ───────────────────────────────────────────────────────────────
+
+/// Encrypt `plaintext` to one ML-KEM public key in the compact
+/// serialization. `algorithm` is the key's direct "alg" (the default) or
+/// its key-wrapping one.
+// stub: enc: Literal["A256GCM", "A128GCM"]
+// stub: header: dict[str, Any] | None
+#[pyfunction]
+#[pyo3(signature = (pk, plaintext, algorithm = None, enc = "A256GCM", kid = None, header = None))]
+pub fn jwe_encrypt(
+    py: Python,
+    pk: Buffer,
+    plaintext: Buffer,
+    algorithm: Option<&str>,
+    enc: &str,
+    kid: Option<&str>,
+    header: Option<&Bound<'_, PyDict>>,
+) -> PyResult<String> {
+    let km = by_key_length("public key", pk.len(), |kem| kem.public_key_bytes)?;
+    let enc = Enc::from_name(enc)?;
+    let alg = match algorithm {
+        None => km.direct,
+        Some(name) if name == km.direct => km.direct,
+        Some(name) if name == km.key_wrap => km.key_wrap,
+        Some(name) => {
+            return Err(PyValueError::new_err(format!(
+                "JWE alg '{}' doesn't fit a {} key, expected {} or {}",
+                name, km.kem.name, km.direct, km.key_wrap
+            )))
+        }
+    };
+
+    let (ct, ss) = encapsulate(py, km.kem, &pk)?;
+    let (cek, encrypted_key) = if alg == km.direct {
+        (concat_kdf(&ss, enc.name(), enc.key_len()), Vec::new())
+    } else {
+        let cek = random_bytes(enc.key_len())?;
+        let kek = concat_kdf(&ss, alg, km.kek_len);
+        let wrapped = key_wrap(&kek, &cek);
+        (cek, wrapped)
+    };
+
+    let protected = PyDict::new_bound(py);
+    protected.set_item("alg", alg)?;
+    protected.set_item("enc", enc.name())?;
+    protected.set_item("ek", b64(&ct))?;
+    if let Some(kid) = kid {
+        protected.set_item("kid", kid)?;
+    }
+    add_members(&protected, header, &["alg", "enc", "ek", "zip", "crit"])?;
+    let protected = b64(to_json(py, &protected)?.as_bytes());
+
+    let [iv, ciphertext, tag] = encrypt_content(enc, &cek, protected.as_bytes(), &plaintext)?;
+    Ok([
+        protected,
+        b64(&encrypted_key),
+        b64(&iv),
+        b64(&ciphertext),
+        b64(&tag),
+    ]
+    .join("."))
+}
+
+/// Encrypt `plaintext` to several ML-KEM public keys in the general JSON
+/// serialization, with key wrapping for each. Returns the JWE as a dict.
+// stub: enc: Literal["A256GCM", "A128GCM"]
+// stub: header: dict[str, Any] | None
+// stub: -> dict[str, Any]
+#[pyfunction]
+#[pyo3(signature = (recipients, plaintext, enc = "A256GCM", kids = None, aad = None, header = None))]
+pub fn jwe_encrypt_general<'py>(
+    py: Python<'py>,
+    recipients: Vec<Buffer>,
+    plaintext: Buffer,
+    enc: &str,
+    kids: Option<Vec<String>>,
+    aad: Option<Buffer>,
+    header: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Bound<'py, PyDict>> {
+    if recipients.is_empty() {
+        return Err(PyValueError::new_err("JWE needs at least one recipient"));
+    }
+    if let Some(kids) = &kids {
+        if kids.len() != recipients.len() {
+            return Err(PyValueError::new_err(
+                "kids must have one entry per recipient",
+            ));
+        }
+    }
+    let enc = Enc::from_name(enc)?;
+    let cek = random_bytes(enc.key_len())?;
+
+    let list = PyList::empty_bound(py);
+    for (index, pk) in recipients.iter().enumerate() {
+        let km = by_key_length("public key", pk.len(), |kem| kem.public_key_bytes)?;
+        let (ct, ss) = encapsulate(py, km.kem, pk)?;
+        let kek = concat_kdf(&ss, km.key_wrap, km.kek_len);
+
+        let recipient_header = PyDict::new_bound(py);
+        recipient_header.set_item("alg", km.key_wrap)?;
+        recipient_header.set_item("ek", b64(&ct))?;
+        if let Some(kids) = &kids {
+            recipient_header.set_item("kid", &kids[index])?;
+        }
+        let recipient = PyDict::new_bound(py);
+        recipient.set_item("header", recipient_header)?;
+        recipient.set_item("encrypted_key", b64(&key_wrap(&kek, &cek)))?;
+        list.append(recipient)?;
+    }
+
+    let protected = PyDict::new_bound(py);
+    protected.set_item("enc", enc.name())?;
+    add_members(
+        &protected,
+        header,
+        &["alg", "enc", "ek", "kid", "zip", "crit"],
+    )?;
+    let protected = b64(to_json(py, &protected)?.as_bytes());
+    let mut aad_input = protected.clone();
+    if let Some(aad) = &aad {
+        aad_input.push('.');
+        aad_input.push_str(&b64(aad));
+    }
+    let [iv, ciphertext, tag] = encrypt_content(enc, &cek, aad_input.as_bytes(), &plaintext)?;
+
+    let jwe = PyDict::new_bound(py);
+    jwe.set_item("protected", protected)?;
+    jwe.set_item("recipients", list)?;
+    if let Some(aad) = &aad {
+        jwe.set_item("aad", b64(aad))?;
+    }
+    jwe.set_item("iv", b64(&iv))?;
+    jwe.set_item("ciphertext", b64(&ciphertext))?;
+    jwe.set_item("tag", b64(&tag))?;
+    Ok(jwe)
+}
+
+// ─── Decryption ───────────────────────────────────────────────────────────────
+
+/// One recipient's view of a JWE: its merged header and encrypted key.
+struct Recipient<'py> {
+    header: Bound<'py, PyDict>,
+    encrypted_key: Vec<u8>,
+}
+
+/// Everything but the recipients.
+struct Content {
+    aad: String,
+    iv: Vec<u8>,
+    ciphertext: Vec<u8>,
+    tag: Vec<u8>,
+}
+
+fn parse_compact<'py>(py: Python<'py>, token: &str) -> PyResult<(Content, Vec<Recipient<'py>>)> {
+    let parts: Vec<&str> = token.split('.').collect();
+    let [protected, encrypted_key, iv, ciphertext, tag] = <[&str; 5]>::try_from(parts)
+        .map_err(|_| malformed("compact serialization has five parts"))?;
+    let header_json = decode("protected header", protected)?;
+    let header_json =
+        std::str::from_utf8(&header_json).map_err(|_| malformed("protected header isn't UTF-8"))?;
+    let header = from_json(py, "protected header", header_json)?;
+    Ok((
+        Content {
+            aad: protected.to_string(),
+            iv: decode("iv", iv)?,
+            ciphertext: decode("ciphertext", ciphertext)?,
+            tag: decode("tag", tag)?,
+        },
+        vec![Recipient {
+            header,
+            encrypted_key: decode("encrypted_key", encrypted_key)?,
+        }],
+    ))
+}
+
+fn parse_json<'py>(
+    py: Python<'py>,
+    jwe: &Bound<'py, PyDict>,
+) -> PyResult<(Content, Vec<Recipient<'py>>)> {
+    let required = |name: &str| {
+        string_member(jwe, name)?.ok_or_else(|| malformed(&format!("no '{}' member", name)))
+    };
+    let protected = string_member(jwe, "protected")?.unwrap_or_default();
+    let shared = PyDict::new_bound(py);
+    if !protected.is_empty() {
+        let header_json = decode("protected header", &protected)?;
+        let header_json = std::str::from_utf8(&header_json)
+            .map_err(|_| malformed("protected header isn't UTF-8"))?;
+        shared.update(from_json(py, "protected header", header_json)?.as_mapping())?;
+    }
+    if let Some(unprotected) = jwe.get_item("unprotected")? {
+        let unprotected = unprotected
+            .downcast_into::<PyDict>()
+            .map_err(|_| malformed("'unprotected' isn't an object"))?;
+        shared.update(unprotected.as_mapping())?;
+    }
+    let mut aad = protected;
+    if let Some(extra) = string_member(jwe, "aad")? {
+        aad.push('.');
+        aad.push_str(&extra);
+    }
+    let content = Content {
+        aad,
+        iv: decode("iv", &required("iv")?)?,
+        ciphertext: decode("ciphertext", &required("ciphertext")?)?,
+        tag: decode("tag", &required("tag")?)?,
+    };
+
+    // General serialization, or flattened with the one recipient inline.
+    let entries: Vec<Bound<'py, PyDict>> = match jwe.get_item("recipients")? {
+        Some(list) => list
+            .extract::<Vec<Bound<'py, PyDict>>>()
+            .map_err(|_| malformed("'recipients' isn't a list of objects"))?,
+        None => vec![jwe.clone()],
+    };
+    let mut recipients = Vec::with_capacity(entries.len());
+    for entry in entries {
+        let header = shared.copy()?;
+        if let Some(own) = entry.get_item("header")? {
+            let own = own
+                .downcast_into::<PyDict>()
+                .map_err(|_| malformed("recipient 'header' isn't an object"))?;
+            header.update(own.as_mapping())?;
+        }
+        let encrypted_key = match string_member(&entry, "encrypted_key")? {
+            Some(key) => decode("encrypted_key", &key)?,
+            None => Vec::new(),
+        };
+        recipients.push(Recipient {
+            header,
+            encrypted_key,
+        });
+    }
+    Ok((content, recipients))
+}
+
+/// Decrypt a JWE, compact or JSON (as a dict or its text), with an ML-KEM
+/// secret key. If `kid` is given, only recipients with that "kid" are
+/// tried.
+// stub: jwe: str | dict[str, Any]
+#[pyfunction]
+#[pyo3(signature = (sk, jwe, kid = None))]
+pub fn jwe_decrypt(
+    py: Python,
+    sk: Buffer,
+    jwe: &Bound<'_, PyAny>,
+    kid: Option<&str>,
+) -> PyResult<Py<PyBytes>> {
+    let km = by_key_length("secret key", sk.len(), |kem| kem.secret_key_bytes)?;
+    let (content, recipients) = if let Ok(text) = jwe.downcast::<PyString>() {
+        let text = text.to_str()?;
+        if text.trim_start().starts_with('{') {
+            parse_json(py, &from_json(py, "JWE", text)?)?
+        } else {
+            parse_compact(py, text)?
+        }
+    } else {
+        let dict = jwe
+            .downcast::<PyDict>()
+            .map_err(|_| PyValueError::new_err("jwe must be a str or a dict"))?;
+        parse_json(py, dict)?
+    };
+
+    for recipient in recipients {
+        let header = &recipient.header;
+        if header.contains("zip")? || header.contains("crit")? {
+            return Err(PyValueError::new_err(
+                "JWE uses 'zip' or 'crit', which aren't supported",
+            ));
+        }
+        if kid.is_some() && string_member(header, "kid")?.as_deref() != kid {
+            continue;
+        }
+        let alg = string_member(header, "alg")?.ok_or_else(|| malformed("no 'alg'"))?;
+        if alg != km.direct && alg != km.key_wrap {
+            continue;
+        }
+        let enc =
+            Enc::from_name(&string_member(header, "enc")?.ok_or_else(|| malformed("no 'enc'"))?)?;
+        let ek = decode(
+            "ek",
+            &string_member(header, "ek")?.ok_or_else(|| malformed("no 'ek'"))?,
+        )?;
+        if ek.len() != km.kem.ciphertext_bytes {
+            continue;
+        }
+        let ss = decapsulate(py, km.kem, &sk, &ek)?;
+        let cek = if alg == km.direct {
+            if !recipient.encrypted_key.is_empty() {
+                return Err(malformed("direct key agreement with an encrypted_key"));
+            }
+            concat_kdf(&ss, enc.name(), enc.key_len())
+        } else {
+            let kek = concat_kdf(&ss, km.key_wrap, km.kek_len);
+            match key_unwrap(&kek, &recipient.encrypted_key) {
+                Some(cek) if cek.len() == enc.key_len() => cek,
+                _ => continue,
+            }
+        };
+        let mut buf = [&content.ciphertext[..], &content.tag[..]].concat();
+        if content.tag.len() == TAG_LEN
+            && enc.open(&cek, &content.iv, content.aad.as_bytes(), &mut buf)
+        {
+            return Ok(PyBytes::new_bound(py, &buf).unbind());
+        }
+    }
+    Err(DecryptionError::new_err(format!(
+        "no {} recipient of this JWE decrypts with the key",
+        km.kem.name
+    )))
+}
@@ -0,0 +1,217 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// JWS compact serialization with Falcon and ML-DSA
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// For JWTs and other JOSE payloads that existing middleware has to check:
+//
+//   token = jws_sign(sk, b'{"sub": "alice"}', kid="k1")
+//   kid = jws_header(token)["kid"]        # unverified, to pick the key
+//   payload = jws_verify(pk, token)
+//
+// Tokens are RFC 7515 compact serializations,
+//
+//   base64url(header) "." base64url(payload) "." base64url(signature)
+//
+// with the protected header as JSON and the signature over the first two
+// parts. "alg" uses the identifiers of the JOSE / COSE PQ drafts, as in
+// jwk.rs: "ML-DSA-44", "ML-DSA-65" and "ML-DSA-87", and liboqs's
+// "Falcon-512" and "Falcon-1024", which have no registration yet. The
+// algorithm comes from the key, by length, unless `algorithm` names one;
+// `header` adds members such as "typ" (it can't set "alg").
+//
+// jws_verify() takes the algorithm from the public key, not the token, and
+// raises SignatureVerificationError unless the header's "alg" names that
+// same scheme, so a token can't pick its own algorithm ("none", or another
+// key type). Headers with "crit" members are refused, as RFC 7515 requires
+// of an implementation that understands no extensions. Payloads are opaque
+// bytes; checking JWT claims is up to the caller.
+
+use base64ct::{Base64UrlUnpadded, Encoding};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict};
+
+use crate::buffer::Buffer;
+use crate::errors::{verification_failed, InvalidLengthError, SignatureVerificationError};
+use crate::jwk::find_jwk_format;
+use crate::sign::SignatureAlgorithm;
+
+/// The schemes with JWS identifiers. Their key lengths are all different.
+const JWS_ALGORITHMS: &[&SignatureAlgorithm] = &[
+    &crate::falcon512::ALGORITHM,
+    &crate::falcon1024::ALGORITHM,
+    &crate::mldsa44::ALGORITHM,
+    &crate::mldsa65::ALGORITHM,
+    &crate::mldsa87::ALGORITHM,
+];
+
+fn jose_name(alg: &SignatureAlgorithm) -> &'static str {
+    find_jwk_format(alg.name)
+        .expect("JWS algorithms all have JWK formats")
+        .jose
+}
+
+fn by_name(name: &str) -> PyResult<&'static SignatureAlgorithm> {
+    let wanted = crate::kem::normalize_name(name);
+    JWS_ALGORITHMS
+        .iter()
+        .copied()
+        .find(|alg| alg.name == wanted)
+        .ok_or_else(|| {
+            PyValueError::new_err(format!(
+                "no JWS algorithm for '{}' (expected a Falcon or ML-DSA parameter set)",
+                name
+            ))
+        })
+}
+
+fn by_key_length(
+    what: &str,
+    len: usize,
+    key_bytes: fn(&SignatureAlgorithm) -> usize,
+) -> PyResult<&'static SignatureAlgorithm> {
+    JWS_ALGORITHMS
+        .iter()
+        .copied()
+        .find(|alg| key_bytes(alg) == len)
+        .ok_or_else(|| {
+            InvalidLengthError::new_err(format!(
+                "JWS {}: {} bytes isn't a Falcon or ML-DSA key length",
+                what, len
+            ))
+        })
+}
+
+fn json<'py>(py: Python<'py>) -> PyResult<Bound<'py, PyModule>> {
+    py.import_bound("json")
+}
+
+/// Split a compact JWS into its encoded parts.
+fn split(token: &str) -> PyResult<[&str; 3]> {
+    let parts: Vec<&str> = token.split('.').collect();
+    <[&str; 3]>::try_from(parts)
+        .map_err(|_| PyValueError::new_err("malformed JWS: compact serialization has three parts"))
+}
+
+fn decode(what: &str, part: &str) -> PyResult<Vec<u8>> {
+    Base64UrlUnpadded::decode_vec(part)
+        .map_err(|_| PyValueError::new_err(format!("malformed JWS: {} isn't base64url", what)))
+}
+
+fn decode_header<'py>(py: Python<'py>, part: &str) -> PyResult<Bound<'py, PyDict>> {
+    let header = decode("header", part)?;
+    let header = std::str::from_utf8(&header)
+        .map_err(|_| PyValueError::new_err("malformed JWS: header isn't UTF-8"))?;
+    json(py)?
+        .call_method1("loads", (header,))
+        .map_err(|_| PyValueError::new_err("malformed JWS: header isn't JSON"))?
+        .downcast_into::<PyDict>()
+        .map_err(|_| PyValueError::new_err("malformed JWS: header isn't a JSON object"))
+}
+
+/// Sign `payload` into a compact JWS. The algorithm is taken from the
+/// secret key's length unless `algorithm` names one.
+// stub: header: dict[str, Any] | None
+#[pyfunction]
+#[pyo3(signature = (sk, payload, algorithm = None, kid = None, header = None))]
+pub fn jws_sign(
+    py: Python,
+    sk: Buffer,
+    payload: Buffer,
+    algorithm: Option<&str>,
+    kid: Option<&str>,
+    header: Option<&Bound<'_, PyDict>>,
+) -> PyResult<String> {
+    let alg = match algorithm {
+        Some(name) => by_name(name)?,
+        None => by_key_length("secret key", sk.len(), |alg| alg.secret_key_bytes)?,
+    };
+    let protected = PyDict::new_bound(py);
+    protected.set_item("alg", jose_name(alg))?;
+    if let Some(kid) = kid {
+        protected.set_item("kid", kid)?;
+    }
+    if let Some(header) = header {
+        if header.contains("alg")? {
+            return Err(PyValueError::new_err(
+                "header can't set 'alg'; pass algorithm instead",
+            ));
+        }
+        protected.update(header.as_mapping())?;
+    }
+    let kwargs = PyDict::new_bound(py);
+    kwargs.set_item("separators", (",", ":"))?;
+    let protected: String = json(py)?
+        .call_method("dumps", (protected,), Some(&kwargs))?
+        .extract()?;
+
+    let mut token = Base64UrlUnpadded::encode_string(protected.as_bytes());
+    token.push('.');
+    token.push_str(&Base64UrlUnpadded::encode_string(&payload));
+    let signing_input = PyBytes::new_bound(py, token.as_bytes());
+    let sig = (alg.sign)(py, sk, signing_input.extract()?)?;
+    token.push('.');
+    token.push_str(&Base64UrlUnpadded::encode_string(sig.as_bytes(py)));
+    Ok(token)
+}
+
+/// Verify a compact JWS against `pk` and return its payload. Raises
+/// SignatureVerificationError if the header's "alg" isn't `pk`'s scheme or
+/// the signature doesn't verify.
+#[pyfunction]
+#[pyo3(signature = (pk, token, algorithm = None))]
+pub fn jws_verify(
+    py: Python,
+    pk: Buffer,
+    token: &str,
+    algorithm: Option<&str>,
+) -> PyResult<Py<PyBytes>> {
+    let alg = by_key_length("public key", pk.len(), |alg| alg.public_key_bytes)?;
+    if let Some(name) = algorithm {
+        let expected = by_name(name)?;
+        if !std::ptr::eq(alg, expected) {
+            return Err(PyValueError::new_err(format!(
+                "public key is {}, expected {}",
+                alg.name, expected.name
+            )));
+        }
+    }
+    let [header_b64, payload_b64, sig_b64] = split(token)?;
+    let header = decode_header(py, header_b64)?;
+    let header_alg: Option<String> = header
+        .get_item("alg")?
+        .map(|value| value.extract())
+        .transpose()
+        .map_err(|_| PyValueError::new_err("malformed JWS: 'alg' isn't a string"))?;
+    if header_alg.as_deref() != Some(jose_name(alg)) {
+        return Err(SignatureVerificationError::new_err(format!(
+            "JWS alg is {}, but the key is {}",
+            header_alg.as_deref().unwrap_or("missing"),
+            jose_name(alg)
+        )));
+    }
+    if header.contains("crit")? {
+        return Err(PyValueError::new_err(
+            "JWS has critical header parameters, which aren't supported",
+        ));
+    }
+    let payload = decode("payload", payload_b64)?;
+    let sig = decode("signature", sig_b64)?;
+
+    let signing_input = &token[..header_b64.len() + 1 + payload_b64.len()];
+    let signing_input = PyBytes::new_bound(py, signing_input.as_bytes());
+    let sig_bytes = PyBytes::new_bound(py, &sig);
+    if !(alg.verify)(pk, signing_input.extract()?, sig_bytes.extract()?)? {
+        return Err(verification_failed(alg.name, payload.len(), sig.len()));
+    }
+    Ok(PyBytes::new_bound(py, &payload).unbind())
+}
+
+/// The protected header of a compact JWS, without verifying anything: for
+/// choosing the key by "kid".
+// stub: -> dict[str, Any]
+#[pyfunction]
+pub fn jws_header<'py>(py: Python<'py>, token: &str) -> PyResult<Bound<'py, PyDict>> {
+    let [header_b64, _, _] = split(token)?;
+    decode_header(py, header_b64)
+}
@@ -0,0 +1,187 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// HKDF (RFC 5869)
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// For turning one KEM shared secret into several independent keys:
+//
+//   ct, ss = kyber768_encapsulate(pk)
+//   enc_key = hkdf_sha256(ss, salt, b"myproto v1 enc", 32)
+//   mac_key = hkdf_sha256(ss, salt, b"myproto v1 mac", 32)
+//
+// or, extracting once and expanding per label:
+//
+//   prk = hkdf_extract(ss, salt)
+//   enc_key = hkdf_expand(prk, b"myproto v1 enc", 32)
+//   iv = hkdf_expand(prk, b"myproto v1 iv", 12)
+//
+// Outputs under different `info` labels are independent, so one secret can key
+// several primitives. A missing salt is HashLen zero bytes, as in the RFC.
+// Output is capped at 255 * HashLen bytes (8160 for SHA-256, 16320 for
+// SHA-512). Everything derived comes back as SecretBytes.
+
+use hkdf::Hkdf;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use sha2::{Sha256, Sha512};
+use zeroize::Zeroizing;
+
+use crate::buffer::Buffer;
+use crate::errors::InvalidLengthError;
+use crate::kem::normalize_name;
+use crate::secret::{secret_bytes, SecretBytes};
+
+#[derive(Clone, Copy)]
+pub(crate) enum Hash {
+    Sha256,
+    Sha512,
+}
+
+impl Hash {
+    pub(crate) fn from_name(name: &str) -> PyResult<Self> {
+        match normalize_name(name).as_str() {
+            "sha256" => Ok(Hash::Sha256),
+            "sha512" => Ok(Hash::Sha512),
+            _ => Err(PyValueError::new_err(format!(
+                "unsupported hash '{}', expected sha256 or sha512",
+                name
+            ))),
+        }
+    }
+
+    fn display_name(self) -> &'static str {
+        match self {
+            Hash::Sha256 => "HKDF-SHA256",
+            Hash::Sha512 => "HKDF-SHA512",
+        }
+    }
+
+    pub(crate) fn output_len(self) -> usize {
+        match self {
+            Hash::Sha256 => 32,
+            Hash::Sha512 => 64,
+        }
+    }
+
+    pub(crate) fn okm(self, length: usize) -> PyResult<Zeroizing<Vec<u8>>> {
+        let max = 255 * self.output_len();
+        if length == 0 || length > max {
+            return Err(PyValueError::new_err(format!(
+                "{} length must be between 1 and {}, got {}",
+                self.display_name(),
+                max,
+                length
+            )));
+        }
+        Ok(Zeroizing::new(vec![0u8; length]))
+    }
+
+    /// HKDF-Extract.
+    pub(crate) fn extract(self, salt: Option<&[u8]>, ikm: &[u8]) -> Zeroizing<Vec<u8>> {
+        Zeroizing::new(match self {
+            Hash::Sha256 => Hkdf::<Sha256>::extract(salt, ikm).0.to_vec(),
+            Hash::Sha512 => Hkdf::<Sha512>::extract(salt, ikm).0.to_vec(),
+        })
+    }
+
+    /// HKDF-Expand into `okm`, from a PRK at least HashLen bytes long and an
+    /// okm() buffer.
+    pub(crate) fn expand(self, prk: &[u8], info: &[u8], okm: &mut [u8]) {
+        match self {
+            Hash::Sha256 => Hkdf::<Sha256>::from_prk(prk)
+                .expect("PRK length checked")
+                .expand(info, okm),
+            Hash::Sha512 => Hkdf::<Sha512>::from_prk(prk)
+                .expect("PRK length checked")
+                .expand(info, okm),
+        }
+        .expect("length checked");
+    }
+}
+
+fn hkdf(
+    py: Python,
+    hash: Hash,
+    ikm: &[u8],
+    salt: Option<&[u8]>,
+    info: &[u8],
+    length: usize,
+) -> PyResult<Py<SecretBytes>> {
+    let mut okm = hash.okm(length)?;
+    match hash {
+        Hash::Sha256 => Hkdf::<Sha256>::new(salt, ikm).expand(info, &mut okm),
+        Hash::Sha512 => Hkdf::<Sha512>::new(salt, ikm).expand(info, &mut okm),
+    }
+    .expect("length checked");
+    secret_bytes(py, &okm)
+}
+
+// ─── One-shot ─────────────────────────────────────────────────────────────────
+
+/// HKDF-SHA256 extract-then-expand: `length` bytes from `ikm`.
+#[pyfunction]
+#[pyo3(signature = (ikm, salt = None, info = None, length = 32))]
+pub fn hkdf_sha256(
+    py: Python,
+    ikm: Buffer,
+    salt: Option<Buffer>,
+    info: Option<Buffer>,
+    length: usize,
+) -> PyResult<Py<SecretBytes>> {
+    let info = info.as_deref().unwrap_or_default();
+    hkdf(py, Hash::Sha256, &ikm, salt.as_deref(), info, length)
+}
+
+/// HKDF-SHA512 extract-then-expand: `length` bytes from `ikm`.
+#[pyfunction]
+#[pyo3(signature = (ikm, salt = None, info = None, length = 64))]
+pub fn hkdf_sha512(
+    py: Python,
+    ikm: Buffer,
+    salt: Option<Buffer>,
+    info: Option<Buffer>,
+    length: usize,
+) -> PyResult<Py<SecretBytes>> {
+    let info = info.as_deref().unwrap_or_default();
+    hkdf(py, Hash::Sha512, &ikm, salt.as_deref(), info, length)
+}
+
+// ─── Extract / expand ─────────────────────────────────────────────────────────
+
+/// HKDF-Extract: the HashLen-byte pseudorandom key for `ikm`. `hash` is
+/// "sha256" or "sha512".
+// stub: hash: Literal["sha256", "sha512"]
+#[pyfunction]
+#[pyo3(signature = (ikm, salt = None, hash = "sha256"))]
+pub fn hkdf_extract(
+    py: Python,
+    ikm: Buffer,
+    salt: Option<Buffer>,
+    hash: &str,
+) -> PyResult<Py<SecretBytes>> {
+    secret_bytes(py, &Hash::from_name(hash)?.extract(salt.as_deref(), &ikm))
+}
+
+/// HKDF-Expand: `length` bytes for label `info` from an hkdf_extract() key.
+// stub: hash: Literal["sha256", "sha512"]
+#[pyfunction]
+#[pyo3(signature = (prk, info, length, hash = "sha256"))]
+pub fn hkdf_expand(
+    py: Python,
+    prk: Buffer,
+    info: Buffer,
+    length: usize,
+    hash: &str,
+) -> PyResult<Py<SecretBytes>> {
+    let hash = Hash::from_name(hash)?;
+    if prk.len() < hash.output_len() {
+        return Err(InvalidLengthError::new_err(format!(
+            "{} PRK: expected at least {} bytes, got {}",
+            hash.display_name(),
+            hash.output_len(),
+            prk.len()
+        )));
+    }
+    let mut okm = hash.okm(length)?;
+    hash.expand(&prk, &info, &mut okm);
+    secret_bytes(py, &okm)
+}
@@ -0,0 +1,150 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// Generic KEM dispatch
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// For applications that pick the algorithm from configuration:
+//
+//   kem = KEM("mlkem768")
+//   pk, sk = kem.keygen()
+//   ct, ss = kem.encapsulate(pk)
+//   ss == kem.decapsulate(sk, ct)
+//
+// Every KEM binding registers a KemAlgorithm pointing at its own keygen /
+// encapsulate / decapsulate functions, so KEM(name) behaves exactly like the
+// per-algorithm functions. Names are matched ignoring case, '-', '_' and '+',
+// so "ML-KEM-768" and "mlkem768" are the same algorithm.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+use crate::buffer::Buffer;
+use crate::secret::SecretBytes;
+
+type KeygenFn = fn(Python) -> PyResult<(Py<PyBytes>, Py<SecretBytes>)>;
+type EncapsulateFn = fn(Python, Buffer) -> PyResult<(Py<PyBytes>, Py<SecretBytes>)>;
+type DecapsulateFn = fn(Python, Buffer, Buffer) -> PyResult<Py<SecretBytes>>;
+
+pub(crate) struct KemAlgorithm {
+    pub name: &'static str,
+    pub public_key_bytes: usize,
+    pub secret_key_bytes: usize,
+    pub ciphertext_bytes: usize,
+    pub shared_secret_bytes: usize,
+    pub keygen: KeygenFn,
+    pub encapsulate: EncapsulateFn,
+    pub decapsulate: DecapsulateFn,
+}
+
+pub(crate) const KEMS: &[&KemAlgorithm] = &[
+    &crate::kyber512::ALGORITHM,
+    &crate::kyber768::ALGORITHM,
+    &crate::kyber1024::ALGORITHM,
+    &crate::mlkem512::ALGORITHM,
+    &crate::mlkem768::ALGORITHM,
+    &crate::mlkem1024::ALGORITHM,
+    &crate::frodokem640aes::ALGORITHM,
+    &crate::frodokem640shake::ALGORITHM,
+    &crate::frodokem976aes::ALGORITHM,
+    &crate::frodokem976shake::ALGORITHM,
+    &crate::frodokem1344aes::ALGORITHM,
+    &crate::frodokem1344shake::ALGORITHM,
+    &crate::sntrup761::ALGORITHM,
+    &crate::hqc128::ALGORITHM,
+    &crate::hqc192::ALGORITHM,
+    &crate::hqc256::ALGORITHM,
+    &crate::mceliece348864::ALGORITHM,
+    &crate::mceliece460896::ALGORITHM,
+    &crate::hybrid::XWING,
+];
+
+/// Canonical form for matching algorithm names from configuration.
+pub(crate) fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| !matches!(c, '-' | '_' | '+'))
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+pub(crate) fn find_kem(name: &str) -> Option<&'static KemAlgorithm> {
+    let wanted = normalize_name(name);
+    KEMS.iter().copied().find(|alg| alg.name == wanted)
+}
+
+/// Fill the `kem` submodule: the KEM class plus one ready-made instance per
+/// algorithm, so `kem.mlkem768.keygen()` works without a name lookup.
+pub(crate) fn add_to_submodule(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<Kem>()?;
+    for alg in KEMS {
+        m.add(alg.name, Kem { alg })?;
+    }
+    Ok(())
+}
+
+#[pyclass(module = "pqcrypto_bindings", name = "KEM", frozen)]
+pub struct Kem {
+    alg: &'static KemAlgorithm,
+}
+
+#[pymethods]
+impl Kem {
+    #[new]
+    fn new(name: &str) -> PyResult<Self> {
+        let alg = find_kem(name)
+            .ok_or_else(|| PyValueError::new_err(format!("unsupported KEM '{}'", name)))?;
+        Ok(Kem { alg })
+    }
+
+    /// Canonical algorithm name, e.g. "mlkem768".
+    #[getter]
+    fn name(&self) -> &'static str {
+        self.alg.name
+    }
+
+    #[getter]
+    fn public_key_bytes(&self) -> usize {
+        self.alg.public_key_bytes
+    }
+
+    #[getter]
+    fn secret_key_bytes(&self) -> usize {
+        self.alg.secret_key_bytes
+    }
+
+    #[getter]
+    fn ciphertext_bytes(&self) -> usize {
+        self.alg.ciphertext_bytes
+    }
+
+    #[getter]
+    fn shared_secret_bytes(&self) -> usize {
+        self.alg.shared_secret_bytes
+    }
+
+    /// (public_key, secret_key)
+    fn keygen(&self, py: Python) -> PyResult<(Py<PyBytes>, Py<SecretBytes>)> {
+        (self.alg.keygen)(py)
+    }
+
+    /// (ciphertext, shared_secret)
+    fn encapsulate(
+        &self,
+        py: Python,
+        pk_bytes: Buffer,
+    ) -> PyResult<(Py<PyBytes>, Py<SecretBytes>)> {
+        (self.alg.encapsulate)(py, pk_bytes)
+    }
+
+    fn decapsulate(
+        &self,
+        py: Python,
+        sk_bytes: Buffer,
+        ct_bytes: Buffer,
+    ) -> PyResult<Py<SecretBytes>> {
+        (self.alg.decapsulate)(py, sk_bytes, ct_bytes)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("KEM('{}')", self.alg.name)
+    }
+}
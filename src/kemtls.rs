@@ -0,0 +1,469 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// KEMTLS-style handshake: authentication by certified KEM keys
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// For constrained clients that hold a certified KEM key rather than a
+// signing key. Neither side signs anything during the handshake, and no
+// signatures cross the wire apart from the CA's on each certificate:
+//
+//   cert = issue_kem_certificate(ca_sk, "server.example", server_pk)
+//
+//   client = KemTlsHandshake(True, client_cert, client_sk, ca_pk)
+//   server = KemTlsHandshake(False, server_cert, server_sk, ca_pk)
+//   server.read_message(client.write_message())
+//   client.read_message(server.write_message())
+//   server.read_message(client.write_message())
+//   client.read_message(server.write_message())
+//   client.peer_subject                             # "server.example"
+//
+// The flow is KEMTLS with client authentication (Schwabe, Stebila and
+// Wiggers, CCS 2020; ESORICS 2021), over the symmetric state of noise.rs:
+//
+//   -> ephemeral KEM name, ephemeral pk               MixHash
+//   <- ct to ephemeral                                MixHash, MixKey(ss)
+//      server certificate                             EncryptAndHash
+//   -> ct to server's certified key                   EncryptAndHash, MixKey(ss)
+//      client certificate                             EncryptAndHash
+//   <- ct to client's certified key                   EncryptAndHash, MixKey(ss)
+//      server finished                                EncryptAndHash("")
+//
+// where encrypted fields are preceded by their length as u16 (big-endian).
+// The final keys depend on secrets only the holders of the two certified
+// secret keys can decapsulate, so reading the server's finished message
+// authenticates the server to the client. The server knows the client is
+// live once a message under the session keys arrives, as in KEMTLS. The
+// ephemeral KEM is the client's `kem` (ML-KEM-768 by default); each certified
+// key may use any KEM.
+//
+// A certificate is a sign_attached() blob by the CA over
+//
+//   label || name length u8 || KEM name || subject length u16 || subject || pk
+//
+// A certificate that doesn't verify under `ca_pk` raises
+// SignatureVerificationError; any failure kills the handshake as in
+// NoiseHandshake. Once complete, session_keys() and handshake_hash feed
+// SecureChannel.from_handshake().
+
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use zeroize::Zeroizing;
+
+use crate::ake::Session;
+use crate::attached::{open_signed, sign_attached};
+use crate::buffer::Buffer;
+use crate::errors::{check_length, InvalidLengthError};
+use crate::kem::{find_kem, KemAlgorithm};
+use crate::noise::{decapsulate, encapsulate, keygen, KeyPair, SymmetricState};
+use crate::secret::{secret_bytes, SecretBytes};
+use crate::util::Reader;
+
+const CERT_LABEL: &[u8] = b"entropic-chaos kemtls certificate v1\0";
+const PROTOCOL_NAME: &[u8] = b"entropic-chaos KEMTLS mutual v1 ChaChaPoly SHA256";
+
+const MESSAGES: usize = 4;
+
+// ─── Certificates ─────────────────────────────────────────────────────────────
+
+struct Certificate {
+    subject: String,
+    kem: &'static KemAlgorithm,
+    pk: Vec<u8>,
+}
+
+fn kem_named(name: &str) -> PyResult<&'static KemAlgorithm> {
+    find_kem(name).ok_or_else(|| PyValueError::new_err(format!("unsupported KEM '{}'", name)))
+}
+
+fn certificate_body(subject: &str, kem: &KemAlgorithm, pk: &[u8]) -> PyResult<Vec<u8>> {
+    if subject.len() > u16::MAX as usize {
+        return Err(InvalidLengthError::new_err(
+            "certificate subject longer than 65535 bytes",
+        ));
+    }
+    let mut body =
+        Vec::with_capacity(CERT_LABEL.len() + 3 + kem.name.len() + subject.len() + pk.len());
+    body.extend_from_slice(CERT_LABEL);
+    body.push(kem.name.len() as u8);
+    body.extend_from_slice(kem.name.as_bytes());
+    body.extend_from_slice(&(subject.len() as u16).to_be_bytes());
+    body.extend_from_slice(subject.as_bytes());
+    body.extend_from_slice(pk);
+    Ok(body)
+}
+
+impl Certificate {
+    /// Verify `cert` under the CA key and parse it.
+    fn open(py: Python, ca_pk: &[u8], cert: &[u8]) -> PyResult<Self> {
+        let ca_pk = PyBytes::new_bound(py, ca_pk);
+        let cert = PyBytes::new_bound(py, cert);
+        let body = open_signed(py, ca_pk.extract()?, cert.extract()?, None)?;
+        let mut r = Reader::new("KEM certificate", body.as_bytes(py));
+        if r.take(CERT_LABEL.len())? != CERT_LABEL {
+            return Err(PyValueError::new_err("not a KEM certificate"));
+        }
+        let name_len = r.u8()? as usize;
+        let name = std::str::from_utf8(r.take(name_len)?)
+            .map_err(|_| PyValueError::new_err("KEM certificate KEM name isn't UTF-8"))?;
+        let kem = find_kem(name)
+            .filter(|kem| kem.name == name)
+            .ok_or_else(|| {
+                PyValueError::new_err(format!("KEM certificate uses unknown KEM '{}'", name))
+            })?;
+        let subject_len = r.u16()? as usize;
+        let subject = std::str::from_utf8(r.take(subject_len)?)
+            .map_err(|_| PyValueError::new_err("KEM certificate subject isn't UTF-8"))?
+            .to_owned();
+        let pk = r.take(kem.public_key_bytes)?.to_vec();
+        r.finish()?;
+        Ok(Certificate { subject, kem, pk })
+    }
+}
+
+/// Certify `kem_pk` as the KEM key of `subject`, signed by the CA key
+/// `ca_sk` (Falcon by length unless `algorithm` names another scheme).
+#[pyfunction]
+#[pyo3(signature = (ca_sk, subject, kem_pk, kem = "mlkem768", algorithm = None))]
+pub fn issue_kem_certificate(
+    py: Python,
+    ca_sk: Buffer,
+    subject: &str,
+    kem_pk: Buffer,
+    kem: &str,
+    algorithm: Option<&str>,
+) -> PyResult<Py<PyBytes>> {
+    let kem = kem_named(kem)?;
+    check_length(
+        &format!("{} public key", kem.name),
+        &kem_pk,
+        kem.public_key_bytes,
+    )?;
+    let body = PyBytes::new_bound(py, &certificate_body(subject, kem, &kem_pk)?);
+    sign_attached(py, ca_sk, body.extract()?, algorithm)
+}
+
+/// Verify an issue_kem_certificate() certificate under `ca_pk` and return
+/// (subject, KEM name, public key).
+#[pyfunction]
+pub fn read_kem_certificate(
+    py: Python,
+    ca_pk: Buffer,
+    certificate: Buffer,
+) -> PyResult<(String, &'static str, Py<PyBytes>)> {
+    let cert = Certificate::open(py, &ca_pk, &certificate)?;
+    Ok((
+        cert.subject,
+        cert.kem.name,
+        PyBytes::new_bound(py, &cert.pk).unbind(),
+    ))
+}
+
+// ─── KemTlsHandshake ──────────────────────────────────────────────────────────
+
+fn put_encrypted(sym: &mut SymmetricState, msg: &mut Vec<u8>, plaintext: &[u8]) -> PyResult<()> {
+    let ct = sym.encrypt_and_hash(plaintext)?;
+    let len = u16::try_from(ct.len())
+        .map_err(|_| InvalidLengthError::new_err("handshake field longer than 65535 bytes"))?;
+    msg.extend_from_slice(&len.to_be_bytes());
+    msg.extend_from_slice(&ct);
+    Ok(())
+}
+
+fn get_encrypted(sym: &mut SymmetricState, r: &mut Reader) -> PyResult<Vec<u8>> {
+    let len = r.u16()? as usize;
+    sym.decrypt_and_hash(r.take(len)?)
+}
+
+enum State {
+    Handshaking,
+    Complete(Session),
+    Failed,
+}
+
+#[pyclass(module = "pqcrypto_bindings")]
+pub struct KemTlsHandshake {
+    client: bool,
+    certificate: Vec<u8>,
+    kem: &'static KemAlgorithm,
+    /// Our certified key pair, from `certificate` and `static_sk`.
+    s: KeyPair,
+    ca_pk: Vec<u8>,
+    ephemeral_kem: &'static KemAlgorithm,
+    sym: SymmetricState,
+    e: Option<KeyPair>,
+    re: Option<Vec<u8>>,
+    peer: Option<Certificate>,
+    next: usize,
+    state: State,
+}
+
+impl KemTlsHandshake {
+    pub(crate) fn session(&self) -> PyResult<&Session> {
+        match &self.state {
+            State::Complete(session) => Ok(session),
+            State::Handshaking => Err(PyValueError::new_err("the handshake isn't complete")),
+            State::Failed => Err(PyValueError::new_err("the handshake failed")),
+        }
+    }
+
+    fn check_turn(&self, writing: bool) -> PyResult<()> {
+        match self.state {
+            State::Handshaking => {}
+            State::Complete(_) => {
+                return Err(PyValueError::new_err("the handshake is already complete"))
+            }
+            State::Failed => return Err(PyValueError::new_err("the handshake failed")),
+        }
+        let client_turn = self.next.is_multiple_of(2);
+        if (client_turn == self.client) != writing {
+            return Err(PyValueError::new_err(if writing {
+                "it's the peer's turn to write a handshake message"
+            } else {
+                "it's our turn to write a handshake message"
+            }));
+        }
+        Ok(())
+    }
+
+    fn advance(&mut self) {
+        self.next += 1;
+        if self.next == MESSAGES {
+            let (c1, c2) = self.sym.split();
+            let (send, recv) = if self.client { (c1, c2) } else { (c2, c1) };
+            self.e = None;
+            self.state = State::Complete(Session {
+                id: self.sym.h,
+                send,
+                recv,
+            });
+        }
+    }
+
+    fn peer(&self) -> &Certificate {
+        self.peer.as_ref().expect("peer certificate read earlier")
+    }
+
+    /// Encapsulate to the peer's certified key and mix in the secret.
+    fn kem_to_peer(&mut self, py: Python, msg: &mut Vec<u8>) -> PyResult<()> {
+        let peer = self.peer();
+        let (ct, ss) = encapsulate(py, peer.kem, &peer.pk)?;
+        put_encrypted(&mut self.sym, msg, &ct)?;
+        self.sym.mix_key(&ss);
+        Ok(())
+    }
+
+    /// Decapsulate with our certified key and mix in the secret.
+    fn kem_from_peer(&mut self, py: Python, r: &mut Reader) -> PyResult<()> {
+        let ct = get_encrypted(&mut self.sym, r)?;
+        let ss = decapsulate(py, self.kem, &self.s.sk, &ct)?;
+        self.sym.mix_key(&ss);
+        Ok(())
+    }
+
+    fn read_certificate(&mut self, py: Python, r: &mut Reader) -> PyResult<()> {
+        let cert = get_encrypted(&mut self.sym, r)?;
+        self.peer = Some(Certificate::open(py, &self.ca_pk, &cert)?);
+        Ok(())
+    }
+
+    fn write(&mut self, py: Python) -> PyResult<Vec<u8>> {
+        let mut msg = Vec::new();
+        match self.next {
+            0 => {
+                let e = keygen(py, self.ephemeral_kem)?;
+                let name = self.ephemeral_kem.name.as_bytes();
+                msg.push(name.len() as u8);
+                msg.extend_from_slice(name);
+                msg.extend_from_slice(&e.pk);
+                self.sym.mix_hash(&msg);
+                self.e = Some(e);
+            }
+            1 => {
+                let re = self.re.as_ref().expect("ephemeral key read earlier");
+                let (ct, ss) = encapsulate(py, self.ephemeral_kem, re)?;
+                self.sym.mix_hash(&ct);
+                msg.extend_from_slice(&ct);
+                self.sym.mix_key(&ss);
+                let cert = self.certificate.clone();
+                put_encrypted(&mut self.sym, &mut msg, &cert)?;
+            }
+            2 => {
+                self.kem_to_peer(py, &mut msg)?;
+                let cert = self.certificate.clone();
+                put_encrypted(&mut self.sym, &mut msg, &cert)?;
+            }
+            _ => {
+                self.kem_to_peer(py, &mut msg)?;
+                put_encrypted(&mut self.sym, &mut msg, &[])?;
+            }
+        }
+        Ok(msg)
+    }
+
+    fn read(&mut self, py: Python, msg: &[u8]) -> PyResult<()> {
+        let mut r = Reader::new("KEMTLS handshake message", msg);
+        match self.next {
+            0 => {
+                let name_len = r.u8()? as usize;
+                let name = std::str::from_utf8(r.take(name_len)?)
+                    .map_err(|_| PyValueError::new_err("KEMTLS ephemeral KEM name isn't UTF-8"))?;
+                self.ephemeral_kem =
+                    find_kem(name)
+                        .filter(|kem| kem.name == name)
+                        .ok_or_else(|| {
+                            PyValueError::new_err(format!(
+                                "KEMTLS handshake uses unknown KEM '{}'",
+                                name
+                            ))
+                        })?;
+                let epk = r.take(self.ephemeral_kem.public_key_bytes)?;
+                self.sym.mix_hash(&msg[..1 + name_len + epk.len()]);
+                self.re = Some(epk.to_vec());
+            }
+            1 => {
+                let ct = r.take(self.ephemeral_kem.ciphertext_bytes)?;
+                self.sym.mix_hash(ct);
+                let e = self.e.as_ref().expect("ephemeral key sent earlier");
+                let ss = decapsulate(py, self.ephemeral_kem, &e.sk, ct)?;
+                self.sym.mix_key(&ss);
+                self.read_certificate(py, &mut r)?;
+            }
+            2 => {
+                self.kem_from_peer(py, &mut r)?;
+                self.read_certificate(py, &mut r)?;
+            }
+            _ => {
+                self.kem_from_peer(py, &mut r)?;
+                if !get_encrypted(&mut self.sym, &mut r)?.is_empty() {
+                    return Err(PyValueError::new_err("malformed KEMTLS finished message"));
+                }
+            }
+        }
+        r.finish()
+    }
+}
+
+#[pymethods]
+impl KemTlsHandshake {
+    /// One side of a handshake, authenticated by `certificate` (from
+    /// issue_kem_certificate()) and its KEM secret key `static_sk`; the
+    /// peer's certificate must verify under the CA key `ca_pk`. `kem` is the
+    /// ephemeral KEM, chosen by the client.
+    #[new]
+    #[pyo3(signature = (client, certificate, static_sk, ca_pk, kem = "mlkem768"))]
+    fn new(
+        py: Python,
+        client: bool,
+        certificate: Buffer,
+        static_sk: Buffer,
+        ca_pk: Buffer,
+        kem: &str,
+    ) -> PyResult<Self> {
+        let own = Certificate::open(py, &ca_pk, &certificate)?;
+        check_length(
+            &format!("{} static secret key", own.kem.name),
+            &static_sk,
+            own.kem.secret_key_bytes,
+        )?;
+        Ok(KemTlsHandshake {
+            client,
+            certificate: certificate.to_vec(),
+            kem: own.kem,
+            s: KeyPair {
+                pk: own.pk,
+                sk: Zeroizing::new(static_sk.to_vec()),
+            },
+            ca_pk: ca_pk.to_vec(),
+            ephemeral_kem: kem_named(kem)?,
+            sym: SymmetricState::new(PROTOCOL_NAME),
+            e: None,
+            re: None,
+            peer: None,
+            next: 0,
+            state: State::Handshaking,
+        })
+    }
+
+    #[getter]
+    fn client(&self) -> bool {
+        self.client
+    }
+
+    #[getter]
+    fn complete(&self) -> bool {
+        matches!(self.state, State::Complete(_))
+    }
+
+    /// The subject of the peer's certificate, once received.
+    #[getter]
+    fn peer_subject(&self) -> Option<&str> {
+        self.peer.as_ref().map(|peer| peer.subject.as_str())
+    }
+
+    /// The peer's certified KEM public key, once received.
+    #[getter]
+    fn peer_static<'py>(&self, py: Python<'py>) -> Option<Bound<'py, PyBytes>> {
+        self.peer
+            .as_ref()
+            .map(|peer| PyBytes::new_bound(py, &peer.pk))
+    }
+
+    /// Produce our next handshake message.
+    fn write_message(&mut self, py: Python) -> PyResult<Py<PyBytes>> {
+        self.check_turn(true)?;
+        match self.write(py) {
+            Ok(msg) => {
+                self.advance();
+                Ok(PyBytes::new_bound(py, &msg).unbind())
+            }
+            Err(e) => {
+                self.state = State::Failed;
+                Err(e)
+            }
+        }
+    }
+
+    /// Process the peer's next handshake message.
+    fn read_message(&mut self, py: Python, msg: Buffer) -> PyResult<()> {
+        self.check_turn(false)?;
+        match self.read(py, &msg) {
+            Ok(()) => {
+                self.advance();
+                Ok(())
+            }
+            Err(e) => {
+                self.state = State::Failed;
+                Err(e)
+            }
+        }
+    }
+
+    /// The final transcript hash, the same on both sides.
+    #[getter]
+    fn handshake_hash<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        Ok(PyBytes::new_bound(py, &self.session()?.id))
+    }
+
+    /// (send key, receive key): 32 bytes each.
+    fn session_keys(&self, py: Python) -> PyResult<(Py<SecretBytes>, Py<SecretBytes>)> {
+        let session = self.session()?;
+        Ok((
+            secret_bytes(py, &session.send[..])?,
+            secret_bytes(py, &session.recv[..])?,
+        ))
+    }
+
+    fn __reduce__(&self) -> PyResult<()> {
+        Err(PyTypeError::new_err(
+            "cannot pickle 'KemTlsHandshake': it holds secret key material",
+        ))
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "KemTlsHandshake(client={}, kem='{}')",
+            if self.client { "True" } else { "False" },
+            self.kem.name
+        )
+    }
+}
@@ -0,0 +1,552 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// Key rotation bundles
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// For rotating a key without a flag day: keep every live generation in one
+// bundle, tag each output with the key it came from, and pick the key back
+// out of the tag on the way in.
+//
+//   bundle = KeyBundle("falcon512")
+//   bundle.generate()                   # generation 1, now current
+//   blob = bundle.sign(msg)             # key ID || signature
+//   bundle.generate()                   # generation 2 signs from here on
+//   bundle.verify(msg, blob)            # still True: key ID picks generation 1
+//
+// A key ID is the first 8 bytes of fingerprint(algorithm, pk), so every party
+// holding the same public key derives the same ID without exchanging it.
+// Verifiers and senders build their own bundle from the published public keys
+// with add(pk), or take public_only() of the owner's.
+//
+// The newest generation becomes current unless generate() / add() is passed
+// make_current=False, which lets the new public key reach every verifier
+// before anyone signs with it; set_current() then switches over. For KEMs,
+// seal() / open() and encapsulate() / decapsulate() carry the key ID in front
+// of the sealed blob or ciphertext the same way:
+//
+//   blob = bundle.seal(msg)             # key ID || seal() blob
+//   msg = bundle.open(blob)
+//
+// A key ID the bundle doesn't hold is a KeyError everywhere: open(),
+// decapsulate() and verify() alike, so a signature from a removed or never-
+// added generation is told apart from one that fails to verify.
+//
+// Bundles pickle as their public keys and current key ID. One holding any
+// secret key refuses; pickle its public_only() copy instead.
+
+use pyo3::exceptions::{PyKeyError, PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+use crate::aead::Aead;
+use crate::buffer::Buffer;
+use crate::errors::{check_length, verification_failed, DecryptionError, InvalidLengthError};
+use crate::kem::KemAlgorithm;
+use crate::keyhandle::Algorithm;
+use crate::seal::{open_blob, seal_single, Suite};
+use crate::secret::{secret_bytes, SecretBytes};
+use crate::sign::SignatureAlgorithm;
+use crate::util::pinning_fingerprint;
+
+const KEY_ID_LEN: usize = 8;
+
+type KeyId = [u8; KEY_ID_LEN];
+
+/// Pickled state: ([(key ID, public key), ...], current key ID).
+type BundleState = (Vec<(Py<PyBytes>, Py<PyBytes>)>, Option<Py<PyBytes>>);
+
+fn hex(id: &KeyId) -> String {
+    id.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+struct Generation {
+    id: KeyId,
+    pk: Py<PyBytes>,
+    /// None on the verifying / sending side.
+    sk: Option<Py<SecretBytes>>,
+}
+
+#[pyclass(module = "pqcrypto_bindings")]
+pub struct KeyBundle {
+    alg: Algorithm,
+    /// Oldest first.
+    generations: Vec<Generation>,
+    current: Option<KeyId>,
+}
+
+impl KeyBundle {
+    fn key_id(&self, pk: &[u8]) -> KeyId {
+        let mut id = [0u8; KEY_ID_LEN];
+        id.copy_from_slice(&pinning_fingerprint(self.alg.name(), pk)[..KEY_ID_LEN]);
+        id
+    }
+
+    fn find(&self, id: &KeyId) -> Option<&Generation> {
+        self.generations.iter().find(|g| g.id == *id)
+    }
+
+    fn get(&self, id: &KeyId) -> PyResult<&Generation> {
+        self.find(id).ok_or_else(|| PyKeyError::new_err(hex(id)))
+    }
+
+    fn current(&self) -> PyResult<&Generation> {
+        match &self.current {
+            Some(id) => self.get(id),
+            None => Err(PyValueError::new_err(format!(
+                "{} key bundle is empty",
+                self.alg.name()
+            ))),
+        }
+    }
+
+    fn secret_key_object<'a>(&self, generation: &'a Generation) -> PyResult<&'a Py<SecretBytes>> {
+        generation.sk.as_ref().ok_or_else(|| {
+            PyValueError::new_err(format!(
+                "{} key {} has no secret key in this bundle",
+                self.alg.name(),
+                hex(&generation.id)
+            ))
+        })
+    }
+
+    fn secret_key(&self, py: Python, generation: &Generation) -> PyResult<Buffer> {
+        self.secret_key_object(generation)?.bind(py).extract()
+    }
+
+    fn insert(
+        &mut self,
+        pk: Py<PyBytes>,
+        sk: Option<Py<SecretBytes>>,
+        make_current: bool,
+        py: Python,
+    ) -> PyResult<KeyId> {
+        let id = self.key_id(pk.as_bytes(py));
+        if self.find(&id).is_some() {
+            return Err(PyValueError::new_err(format!(
+                "{} key {} is already in the bundle",
+                self.alg.name(),
+                hex(&id)
+            )));
+        }
+        self.generations.push(Generation { id, pk, sk });
+        if make_current || self.current.is_none() {
+            self.current = Some(id);
+        }
+        Ok(id)
+    }
+
+    fn kem(&self) -> PyResult<&'static KemAlgorithm> {
+        match self.alg {
+            Algorithm::Kem(alg) => Ok(alg),
+            Algorithm::Signature(alg) => Err(PyTypeError::new_err(format!(
+                "{} is a signature scheme; use sign() / verify()",
+                alg.name
+            ))),
+        }
+    }
+
+    fn signature(&self) -> PyResult<&'static SignatureAlgorithm> {
+        match self.alg {
+            Algorithm::Signature(alg) => Ok(alg),
+            Algorithm::Kem(alg) => Err(PyTypeError::new_err(format!(
+                "{} is a KEM; use seal() / open() or encapsulate() / decapsulate()",
+                alg.name
+            ))),
+        }
+    }
+}
+
+/// Split a bundle output into its key ID and the algorithm's own bytes.
+fn split_blob<'a>(what: &str, blob: &'a [u8]) -> PyResult<(KeyId, &'a [u8])> {
+    if blob.len() < KEY_ID_LEN {
+        return Err(InvalidLengthError::new_err(format!(
+            "{} must start with a {}-byte key ID, got {} bytes",
+            what,
+            KEY_ID_LEN,
+            blob.len()
+        )));
+    }
+    let (id, rest) = blob.split_at(KEY_ID_LEN);
+    Ok((id.try_into().unwrap(), rest))
+}
+
+fn parse_key_id(key_id: &[u8]) -> PyResult<KeyId> {
+    check_length("key ID", key_id, KEY_ID_LEN)?;
+    Ok(key_id.try_into().unwrap())
+}
+
+fn with_key_id(py: Python, id: &KeyId, body: &[u8]) -> Py<PyBytes> {
+    PyBytes::new_bound_with(py, KEY_ID_LEN + body.len(), |out| {
+        out[..KEY_ID_LEN].copy_from_slice(id);
+        out[KEY_ID_LEN..].copy_from_slice(body);
+        Ok(())
+    })
+    .expect("filling a fresh bytes object can't fail")
+    .unbind()
+}
+
+#[pymethods]
+impl KeyBundle {
+    /// An empty bundle for `algorithm`, any KEM() or Signature() name.
+    #[new]
+    fn new(algorithm: &str) -> PyResult<Self> {
+        Ok(KeyBundle {
+            alg: Algorithm::find(algorithm)?,
+            generations: Vec::new(),
+            current: None,
+        })
+    }
+
+    /// Canonical algorithm name, e.g. "falcon512".
+    #[getter]
+    fn algorithm(&self) -> &'static str {
+        self.alg.name()
+    }
+
+    /// Key ID of the generation that signs / receives, or None if empty.
+    #[getter]
+    fn current_id(&self, py: Python) -> Option<Py<PyBytes>> {
+        self.current.map(|id| PyBytes::new_bound(py, &id).unbind())
+    }
+
+    /// Generate a new key pair and add it. Returns its key ID.
+    #[pyo3(signature = (make_current = true))]
+    fn generate(&mut self, py: Python, make_current: bool) -> PyResult<Py<PyBytes>> {
+        let (pk, sk) = match self.alg {
+            Algorithm::Kem(alg) => (alg.keygen)(py)?,
+            Algorithm::Signature(alg) => (alg.keygen)(py)?,
+        };
+        let id = self.insert(pk, Some(sk), make_current, py)?;
+        Ok(PyBytes::new_bound(py, &id).unbind())
+    }
+
+    /// Add an existing key. Without `sk` the generation can only verify /
+    /// be encapsulated to. Returns its key ID; adding the same public key
+    /// twice raises ValueError.
+    #[pyo3(signature = (pk, sk = None, make_current = true))]
+    fn add(
+        &mut self,
+        py: Python,
+        pk: Buffer,
+        sk: Option<Buffer>,
+        make_current: bool,
+    ) -> PyResult<Py<PyBytes>> {
+        let name = self.alg.name();
+        check_length(
+            &format!("{} public key", name),
+            &pk,
+            self.alg.public_key_bytes(),
+        )?;
+        let sk = match sk {
+            Some(sk) => {
+                check_length(
+                    &format!("{} secret key", name),
+                    &sk,
+                    self.alg.secret_key_bytes(),
+                )?;
+                Some(secret_bytes(py, &sk)?)
+            }
+            None => None,
+        };
+        let pk = PyBytes::new_bound(py, &pk).unbind();
+        let id = self.insert(pk, sk, make_current, py)?;
+        Ok(PyBytes::new_bound(py, &id).unbind())
+    }
+
+    /// Make the generation `key_id` current.
+    fn set_current(&mut self, key_id: Buffer) -> PyResult<()> {
+        let id = parse_key_id(&key_id)?;
+        self.get(&id)?;
+        self.current = Some(id);
+        Ok(())
+    }
+
+    /// Drop the generation `key_id`, wiping its secret key. Outputs tagged
+    /// with it no longer open / verify. The current generation can't be
+    /// removed; switch with set_current() first.
+    fn remove(&mut self, py: Python, key_id: Buffer) -> PyResult<()> {
+        let id = parse_key_id(&key_id)?;
+        self.get(&id)?;
+        if self.current == Some(id) && self.generations.len() > 1 {
+            return Err(PyValueError::new_err(format!(
+                "{} is the current key; set_current() another one first",
+                hex(&id)
+            )));
+        }
+        let i = self.generations.iter().position(|g| g.id == id).unwrap();
+        // Wipe before dropping the generation, so a failed wipe (say, a live
+        // memoryview on the key) leaves the bundle as it was.
+        if let Some(sk) = &self.generations[i].sk {
+            sk.borrow_mut(py).destroy()?;
+        }
+        self.generations.remove(i);
+        if self.generations.is_empty() {
+            self.current = None;
+        }
+        Ok(())
+    }
+
+    /// Key IDs, oldest first.
+    fn key_ids(&self, py: Python) -> Vec<Py<PyBytes>> {
+        self.generations
+            .iter()
+            .map(|g| PyBytes::new_bound(py, &g.id).unbind())
+            .collect()
+    }
+
+    /// The public key of generation `key_id`, or of the current one.
+    #[pyo3(signature = (key_id = None))]
+    fn public_key(&self, py: Python, key_id: Option<Buffer>) -> PyResult<Py<PyBytes>> {
+        let generation = match key_id {
+            Some(key_id) => self.get(&parse_key_id(&key_id)?)?,
+            None => self.current()?,
+        };
+        Ok(generation.pk.clone_ref(py))
+    }
+
+    /// A copy with the public keys only, for handing to verifiers / senders.
+    fn public_only(&self, py: Python) -> Self {
+        KeyBundle {
+            alg: self.alg,
+            generations: self
+                .generations
+                .iter()
+                .map(|g| Generation {
+                    id: g.id,
+                    pk: g.pk.clone_ref(py),
+                    sk: None,
+                })
+                .collect(),
+            current: self.current,
+        }
+    }
+
+    /// seal() `plaintext` to the current generation. Returns key ID || sealed
+    /// blob. `aad`, `cipher` and `committing` are as for seal().
+    // stub: cipher: Literal["chacha20poly1305", "aes256gcm", "xchacha20poly1305"]
+    #[pyo3(signature = (
+        plaintext,
+        aad = None,
+        cipher = "chacha20poly1305",
+        committing = false,
+    ))]
+    fn seal(
+        &self,
+        py: Python,
+        plaintext: Buffer,
+        aad: Option<Buffer>,
+        cipher: &str,
+        committing: bool,
+    ) -> PyResult<Py<PyBytes>> {
+        let alg = self.kem()?;
+        let generation = self.current()?;
+        let suite = Suite {
+            aead: Aead::from_name(cipher)?,
+            committing,
+        };
+        let aad = aad.as_deref().unwrap_or_default();
+        let pk = generation.pk.bind(py).extract()?;
+        let blob = seal_single(py, suite, pk, &plaintext, aad, Some(alg.name))?;
+        Ok(with_key_id(py, &generation.id, &blob))
+    }
+
+    /// Open a seal() output with the generation it names. Raises KeyError
+    /// for a key ID not in the bundle and DecryptionError as open() does.
+    #[pyo3(signature = (blob, aad = None, require_committing = false))]
+    fn open(
+        &self,
+        py: Python,
+        blob: Buffer,
+        aad: Option<Buffer>,
+        require_committing: bool,
+    ) -> PyResult<Py<PyBytes>> {
+        let alg = self.kem()?;
+        let (id, sealed) = split_blob(&format!("{} bundle sealed blob", alg.name), &blob)
+            .map_err(|e| DecryptionError::new_err(e.value_bound(py).to_string()))?;
+        let generation = self.get(&id)?;
+        let sk = self.secret_key_object(generation)?.bind(py);
+        let aad = aad.as_deref().unwrap_or_default();
+        let plaintext = open_blob(py, sk.as_any(), sealed, aad, require_committing)?;
+        Ok(PyBytes::new_bound(py, &plaintext).unbind())
+    }
+
+    /// Encapsulate to the current generation. Returns (key ID || ciphertext,
+    /// shared secret).
+    fn encapsulate(&self, py: Python) -> PyResult<(Py<PyBytes>, Py<SecretBytes>)> {
+        let alg = self.kem()?;
+        let generation = self.current()?;
+        let (ct, ss) = (alg.encapsulate)(py, generation.pk.bind(py).extract()?)?;
+        Ok((with_key_id(py, &generation.id, ct.as_bytes(py)), ss))
+    }
+
+    /// Decapsulate an encapsulate() output with the generation it names.
+    /// Raises KeyError for a key ID not in the bundle.
+    fn decapsulate(&self, py: Python, blob: Buffer) -> PyResult<Py<SecretBytes>> {
+        let alg = self.kem()?;
+        let (id, ct) = split_blob(&format!("{} bundle ciphertext", alg.name), &blob)?;
+        let generation = self.get(&id)?;
+        let ct = PyBytes::new_bound(py, ct);
+        (alg.decapsulate)(py, self.secret_key(py, generation)?, ct.extract()?)
+    }
+
+    /// Sign `msg` with the current generation. Returns key ID || signature.
+    fn sign(&self, py: Python, msg: Buffer) -> PyResult<Py<PyBytes>> {
+        let alg = self.signature()?;
+        let generation = self.current()?;
+        let sig = (alg.sign)(py, self.secret_key(py, generation)?, msg)?;
+        Ok(with_key_id(py, &generation.id, sig.as_bytes(py)))
+    }
+
+    /// Verify a sign() output with the generation it names. Raises KeyError
+    /// for a key ID not in the bundle.
+    fn verify(&self, py: Python, msg: Buffer, blob: Buffer) -> PyResult<bool> {
+        let alg = self.signature()?;
+        let (id, sig) = split_blob(&format!("{} bundle signature", alg.name), &blob)?;
+        let generation = self.get(&id)?;
+        let sig = PyBytes::new_bound(py, sig);
+        (alg.verify)(generation.pk.bind(py).extract()?, msg, sig.extract()?)
+    }
+
+    /// Like verify(), but raises SignatureVerificationError instead of
+    /// returning False.
+    fn verify_strict(&self, py: Python, msg: Buffer, blob: Buffer) -> PyResult<()> {
+        let (msg_len, sig_len) = (msg.len(), blob.len());
+        if self.verify(py, msg, blob)? {
+            Ok(())
+        } else {
+            Err(verification_failed(self.alg.name(), msg_len, sig_len))
+        }
+    }
+
+    fn __len__(&self) -> usize {
+        self.generations.len()
+    }
+
+    fn __contains__(&self, key_id: Buffer) -> bool {
+        <[u8; KEY_ID_LEN]>::try_from(&key_id[..])
+            .map(|id| self.find(&id).is_some())
+            .unwrap_or(false)
+    }
+
+    fn __repr__(&self) -> String {
+        let current = self.current.as_ref().map_or("none".to_owned(), hex);
+        format!(
+            "KeyBundle('{}', generations={}, current={})",
+            self.alg.name(),
+            self.generations.len(),
+            current
+        )
+    }
+
+    /// (KeyBundle, (algorithm,), ([(key ID, pk), ...], current key ID)).
+    /// Refuses a bundle holding any secret key.
+    fn __reduce__(slf: &Bound<'_, Self>) -> PyResult<(PyObject, (&'static str,), BundleState)> {
+        let py = slf.py();
+        let bundle = slf.borrow();
+        if bundle.generations.iter().any(|g| g.sk.is_some()) {
+            return Err(PyTypeError::new_err(
+                "cannot pickle 'KeyBundle': it holds secret key material \
+                 (pickle its public_only() copy)",
+            ));
+        }
+        let generations = bundle
+            .generations
+            .iter()
+            .map(|g| (PyBytes::new_bound(py, &g.id).unbind(), g.pk.clone_ref(py)))
+            .collect();
+        let current = bundle.current_id(py);
+        Ok((
+            slf.get_type().into_any().unbind(),
+            (bundle.alg.name(),),
+            (generations, current),
+        ))
+    }
+
+    fn __setstate__(
+        &mut self,
+        py: Python,
+        state: (Vec<(Buffer, Buffer)>, Option<Buffer>),
+    ) -> PyResult<()> {
+        let (generations, current) = state;
+        self.generations.clear();
+        self.current = None;
+        for (key_id, pk) in generations {
+            check_length(
+                &format!("{} public key", self.alg.name()),
+                &pk,
+                self.alg.public_key_bytes(),
+            )?;
+            let id = self.insert(PyBytes::new_bound(py, &pk).unbind(), None, false, py)?;
+            if id[..] != key_id[..] {
+                return Err(PyValueError::new_err(format!(
+                    "key ID {} doesn't match its {} public key",
+                    hex(&parse_key_id(&key_id)?),
+                    self.alg.name()
+                )));
+            }
+        }
+        if let Some(current) = current {
+            let id = parse_key_id(&current)?;
+            self.get(&id)?;
+            self.current = Some(id);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn public_only_bundles_pickle() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let bundle = Bound::new(py, KeyBundle::new("falcon512").unwrap()).unwrap();
+            bundle.call_method1("generate", (true,)).unwrap();
+            bundle.call_method1("generate", (false,)).unwrap();
+            let err = bundle.call_method0("__reduce__").unwrap_err();
+            assert!(err.is_instance_of::<PyTypeError>(py));
+
+            // What pickle.loads() does with the __reduce__ tuple, without
+            // needing the module importable.
+            let public = bundle.call_method0("public_only").unwrap();
+            let (cls, args, state): (Bound<PyAny>, Bound<PyAny>, Bound<PyAny>) = public
+                .call_method0("__reduce__")
+                .unwrap()
+                .extract()
+                .unwrap();
+            let copy = cls.call1(args.downcast().unwrap()).unwrap();
+            copy.call_method1("__setstate__", (state,)).unwrap();
+
+            let copy = copy.downcast::<KeyBundle>().unwrap().borrow();
+            let original = bundle.borrow();
+            assert_eq!(copy.alg.name(), "falcon512");
+            assert_eq!(copy.current, original.current);
+            let ids = |b: &KeyBundle| b.generations.iter().map(|g| g.id).collect::<Vec<_>>();
+            assert_eq!(ids(&copy), ids(&original));
+            assert!(copy.generations.iter().all(|g| g.sk.is_none()));
+        });
+    }
+
+    #[test]
+    fn unknown_key_ids_raise_key_error() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let mut bundle = KeyBundle::new("falcon512").unwrap();
+            bundle.generate(py, true).unwrap();
+            let msg = || Buffer::from_bytes(py, b"msg");
+            let blob = bundle.sign(py, msg()).unwrap();
+            let blob = blob.as_bytes(py);
+            assert!(bundle
+                .verify(py, msg(), Buffer::from_bytes(py, blob))
+                .unwrap());
+
+            let mut stranger = blob.to_vec();
+            stranger[..KEY_ID_LEN].fill(0);
+            let err = bundle
+                .verify(py, msg(), Buffer::from_bytes(py, &stranger))
+                .unwrap_err();
+            assert!(err.is_instance_of::<PyKeyError>(py));
+        });
+    }
+}
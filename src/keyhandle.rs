@@ -0,0 +1,174 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// Scoped secret key handles
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// For short-lived signing or decapsulation, where the key should not outlive
+// the operation:
+//
+//   with load_secret_key("falcon512", sk_bytes) as sk:
+//       sig = sk.sign(msg)
+//   # key material has been wiped here
+//
+// The handle copies the key into a SecretBytes and wipes it on close(), on
+// leaving the `with` block (exceptions included) or on drop, whichever comes
+// first. Algorithm names follow KEM() / Signature(); a KEM key gets
+// decapsulate(), a signature key gets sign().
+
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+use crate::buffer::Buffer;
+use crate::errors::check_length;
+use crate::kem::{find_kem, KemAlgorithm};
+use crate::secret::{secret_bytes, SecretBytes};
+use crate::sign::{find_signature, SignatureAlgorithm};
+
+#[derive(Clone, Copy)]
+pub(crate) enum Algorithm {
+    Kem(&'static KemAlgorithm),
+    Signature(&'static SignatureAlgorithm),
+}
+
+impl Algorithm {
+    /// Look `name` up among the KEMs, then the signature schemes.
+    pub(crate) fn find(name: &str) -> PyResult<Self> {
+        if let Some(alg) = find_kem(name) {
+            Ok(Algorithm::Kem(alg))
+        } else if let Some(alg) = find_signature(name) {
+            Ok(Algorithm::Signature(alg))
+        } else {
+            Err(PyValueError::new_err(format!(
+                "unsupported algorithm '{}'",
+                name
+            )))
+        }
+    }
+
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            Algorithm::Kem(alg) => alg.name,
+            Algorithm::Signature(alg) => alg.name,
+        }
+    }
+
+    pub(crate) fn public_key_bytes(&self) -> usize {
+        match self {
+            Algorithm::Kem(alg) => alg.public_key_bytes,
+            Algorithm::Signature(alg) => alg.public_key_bytes,
+        }
+    }
+
+    pub(crate) fn secret_key_bytes(&self) -> usize {
+        match self {
+            Algorithm::Kem(alg) => alg.secret_key_bytes,
+            Algorithm::Signature(alg) => alg.secret_key_bytes,
+        }
+    }
+}
+
+#[pyclass(module = "pqcrypto_bindings")]
+pub struct SecretKey {
+    alg: Algorithm,
+    sk: Option<Py<SecretBytes>>,
+}
+
+impl SecretKey {
+    /// A buffer view of the key, for passing to the per-algorithm functions.
+    /// Dropped before close() can run, so it never blocks the wipe.
+    fn key(&self, py: Python) -> PyResult<Buffer> {
+        let sk = self.sk.as_ref().ok_or_else(|| {
+            PyValueError::new_err(format!(
+                "{} secret key handle has been closed",
+                self.alg.name()
+            ))
+        })?;
+        sk.bind(py).extract()
+    }
+}
+
+/// Load a secret key into a handle that wipes it on close. Use as a context
+/// manager: `with load_secret_key("mldsa65", sk) as key: ...`.
+#[pyfunction]
+pub fn load_secret_key(py: Python, algorithm: &str, sk_bytes: Buffer) -> PyResult<SecretKey> {
+    let alg = Algorithm::find(algorithm)?;
+
+    check_length(
+        &format!("{} secret key", alg.name()),
+        &sk_bytes,
+        alg.secret_key_bytes(),
+    )?;
+
+    let sk = secret_bytes(py, &sk_bytes)?;
+    Ok(SecretKey { alg, sk: Some(sk) })
+}
+
+#[pymethods]
+impl SecretKey {
+    /// Canonical algorithm name, e.g. "falcon512".
+    #[getter]
+    fn algorithm(&self) -> &'static str {
+        self.alg.name()
+    }
+
+    /// True once the key material has been wiped.
+    #[getter]
+    fn closed(&self) -> bool {
+        self.sk.is_none()
+    }
+
+    fn sign(&self, py: Python, msg: Buffer) -> PyResult<Py<PyBytes>> {
+        match self.alg {
+            Algorithm::Signature(alg) => (alg.sign)(py, self.key(py)?, msg),
+            Algorithm::Kem(alg) => Err(PyTypeError::new_err(format!(
+                "{} is a KEM; use decapsulate()",
+                alg.name
+            ))),
+        }
+    }
+
+    fn decapsulate(&self, py: Python, ct_bytes: Buffer) -> PyResult<Py<SecretBytes>> {
+        match self.alg {
+            Algorithm::Kem(alg) => (alg.decapsulate)(py, self.key(py)?, ct_bytes),
+            Algorithm::Signature(alg) => Err(PyTypeError::new_err(format!(
+                "{} is a signature scheme; use sign()",
+                alg.name
+            ))),
+        }
+    }
+
+    /// Wipe the key now. Calling it twice is harmless.
+    fn close(&mut self, py: Python) -> PyResult<()> {
+        if let Some(sk) = self.sk.take() {
+            sk.borrow_mut(py).destroy()?;
+        }
+        Ok(())
+    }
+
+    fn __enter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    /// Wipes the key and lets any exception propagate.
+    fn __exit__(
+        &mut self,
+        py: Python,
+        _exc_type: &Bound<'_, PyAny>,
+        _exc_value: &Bound<'_, PyAny>,
+        _traceback: &Bound<'_, PyAny>,
+    ) -> PyResult<bool> {
+        self.close(py)?;
+        Ok(false)
+    }
+
+    fn __repr__(&self) -> String {
+        let state = if self.sk.is_some() { "open" } else { "closed" };
+        format!("SecretKey('{}', {})", self.alg.name(), state)
+    }
+
+    fn __reduce__(&self) -> PyResult<()> {
+        Err(PyTypeError::new_err(
+            "cannot pickle 'SecretKey': it holds secret key material",
+        ))
+    }
+}
@@ -0,0 +1,244 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// KeySchedule: transcript hash and labelled key derivation
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// For custom protocols built from the KEM and signature primitives, the key
+// schedule of TLS 1.3 (RFC 8446, section 7.1) as an object:
+//
+//   ks = KeySchedule(label_prefix=b"myproto v1 ")
+//   ks.extract(psk)                        # or extract() with no PSK
+//   ks.absorb(msg1)
+//   ks.absorb(msg2)
+//   ks.extract(shared_secret)              # after the KEM
+//   c_hs = ks.derive_secret(b"c hs traffic")
+//   key = ks.expand_label(c_hs, b"key", length=32)
+//
+// absorb() adds a message to the running transcript hash. Each extract()
+// starts a stage with a new secret:
+//
+//   secret = HKDF-Extract(salt, ikm or HashLen zero bytes)
+//
+// where salt is empty for the first stage and Derive-Secret(previous secret,
+// "derived", "") after. Then
+//
+//   Derive-Secret(label) = Expand-Label(secret, label, transcript hash, HashLen)
+//   Expand-Label(secret, label, context, length)
+//                        = HKDF-Expand(secret, length u16 | prefix length u8
+//                              | label prefix | label | context length u8
+//                              | context, length)
+//
+// binding every derived key to the stage's secrets and all messages so far.
+// With the default `label_prefix` of "tls13 " and SHA-256 this reproduces
+// TLS 1.3 (RFC 8448 has worked examples to test against); a protocol of
+// your own should pass its own prefix so its keys can never collide with
+// another protocol's. `hash` is "sha256" or "sha512". Derived secrets come
+// back as SecretBytes; the object doesn't pickle.
+
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use sha2::{Digest, Sha256, Sha512};
+use zeroize::Zeroizing;
+
+use crate::buffer::Buffer;
+use crate::errors::InvalidLengthError;
+use crate::kdf::Hash;
+use crate::secret::{secret_bytes, SecretBytes};
+
+const DEFAULT_PREFIX: &[u8] = b"tls13 ";
+
+#[derive(Clone)]
+enum Transcript {
+    Sha256(Sha256),
+    Sha512(Sha512),
+}
+
+impl Transcript {
+    fn new(hash: Hash) -> Self {
+        match hash {
+            Hash::Sha256 => Transcript::Sha256(Sha256::new()),
+            Hash::Sha512 => Transcript::Sha512(Sha512::new()),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Transcript::Sha256(h) => h.update(data),
+            Transcript::Sha512(h) => h.update(data),
+        }
+    }
+
+    fn digest(&self) -> Vec<u8> {
+        match self {
+            Transcript::Sha256(h) => h.clone().finalize().to_vec(),
+            Transcript::Sha512(h) => h.clone().finalize().to_vec(),
+        }
+    }
+}
+
+#[pyclass(module = "pqcrypto_bindings")]
+pub struct KeySchedule {
+    hash: Hash,
+    prefix: Vec<u8>,
+    transcript: Transcript,
+    secret: Option<Zeroizing<Vec<u8>>>,
+    stage: u32,
+}
+
+impl KeySchedule {
+    fn expand_label_raw(
+        &self,
+        secret: &[u8],
+        label: &[u8],
+        context: &[u8],
+        length: usize,
+    ) -> PyResult<Zeroizing<Vec<u8>>> {
+        let full_label = u8::try_from(self.prefix.len() + label.len())
+            .map_err(|_| PyValueError::new_err("label prefix and label exceed 255 bytes"))?;
+        let context_len = u8::try_from(context.len())
+            .map_err(|_| PyValueError::new_err("Expand-Label context exceeds 255 bytes"))?;
+        let length_u16 = u16::try_from(length)
+            .map_err(|_| PyValueError::new_err("Expand-Label length exceeds 65535 bytes"))?;
+        if secret.len() < self.hash.output_len() {
+            return Err(InvalidLengthError::new_err(format!(
+                "KeySchedule secret: expected at least {} bytes, got {}",
+                self.hash.output_len(),
+                secret.len()
+            )));
+        }
+        let mut info = Vec::with_capacity(4 + full_label as usize + context.len());
+        info.extend_from_slice(&length_u16.to_be_bytes());
+        info.push(full_label);
+        info.extend_from_slice(&self.prefix);
+        info.extend_from_slice(label);
+        info.push(context_len);
+        info.extend_from_slice(context);
+        let mut okm = self.hash.okm(length)?;
+        self.hash.expand(secret, &info, &mut okm);
+        Ok(okm)
+    }
+
+    fn current_secret(&self) -> PyResult<&[u8]> {
+        self.secret
+            .as_deref()
+            .map(|s| &s[..])
+            .ok_or_else(|| PyValueError::new_err("KeySchedule has no secret yet; call extract()"))
+    }
+}
+
+#[pymethods]
+impl KeySchedule {
+    // stub: hash: Literal["sha256", "sha512"]
+    #[new]
+    #[pyo3(signature = (hash = "sha256", label_prefix = None))]
+    fn new(hash: &str, label_prefix: Option<Buffer>) -> PyResult<Self> {
+        let hash = Hash::from_name(hash)?;
+        let prefix = label_prefix.as_deref().unwrap_or(DEFAULT_PREFIX).to_vec();
+        if prefix.len() > 255 {
+            return Err(PyValueError::new_err("label_prefix exceeds 255 bytes"));
+        }
+        Ok(KeySchedule {
+            hash,
+            prefix,
+            transcript: Transcript::new(hash),
+            secret: None,
+            stage: 0,
+        })
+    }
+
+    /// Add a handshake message to the transcript.
+    fn absorb(&mut self, message: Buffer) {
+        self.transcript.update(&message);
+    }
+
+    /// The hash of every message absorbed so far.
+    #[getter]
+    fn transcript_hash<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new_bound(py, &self.transcript.digest())
+    }
+
+    /// Stages started so far by extract().
+    #[getter]
+    fn stage(&self) -> u32 {
+        self.stage
+    }
+
+    /// Start the next stage, mixing in `ikm` (a PSK or shared secret; HashLen
+    /// zero bytes if None).
+    #[pyo3(signature = (ikm = None))]
+    fn extract(&mut self, ikm: Option<Buffer>) -> PyResult<()> {
+        let zeros = vec![0u8; self.hash.output_len()];
+        let ikm = ikm.as_deref().unwrap_or(&zeros);
+        let salt = match &self.secret {
+            Some(secret) => {
+                let empty = Transcript::new(self.hash).digest();
+                Some(self.expand_label_raw(secret, b"derived", &empty, self.hash.output_len())?)
+            }
+            None => None,
+        };
+        self.secret = Some(self.hash.extract(salt.as_deref().map(|s| &s[..]), ikm));
+        self.stage += 1;
+        Ok(())
+    }
+
+    /// Derive-Secret: a HashLen-byte secret for `label`, bound to the current
+    /// stage and transcript.
+    fn derive_secret(&self, py: Python, label: Buffer) -> PyResult<Py<SecretBytes>> {
+        let context = self.transcript.digest();
+        let secret = self.expand_label_raw(
+            self.current_secret()?,
+            &label,
+            &context,
+            self.hash.output_len(),
+        )?;
+        secret_bytes(py, &secret)
+    }
+
+    /// HKDF-Expand-Label: `length` bytes (HashLen if None) for `label` from a
+    /// derive_secret() output, e.g. traffic keys and IVs.
+    #[pyo3(signature = (secret, label, context = None, length = None))]
+    fn expand_label(
+        &self,
+        py: Python,
+        secret: Buffer,
+        label: Buffer,
+        context: Option<Buffer>,
+        length: Option<usize>,
+    ) -> PyResult<Py<SecretBytes>> {
+        let okm = self.expand_label_raw(
+            &secret,
+            &label,
+            context.as_deref().unwrap_or_default(),
+            length.unwrap_or(self.hash.output_len()),
+        )?;
+        secret_bytes(py, &okm)
+    }
+
+    /// An independent copy, to branch the schedule.
+    fn copy(&self) -> Self {
+        KeySchedule {
+            hash: self.hash,
+            prefix: self.prefix.clone(),
+            transcript: self.transcript.clone(),
+            secret: self.secret.clone(),
+            stage: self.stage,
+        }
+    }
+
+    fn __reduce__(&self) -> PyResult<()> {
+        Err(PyTypeError::new_err(
+            "cannot pickle 'KeySchedule': it holds secret key material",
+        ))
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "KeySchedule(hash='{}', stage={})",
+            match self.hash {
+                Hash::Sha256 => "sha256",
+                Hash::Sha512 => "sha512",
+            },
+            self.stage
+        )
+    }
+}
@@ -0,0 +1,400 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// Keystore: named keys in one password-protected file
+// ───────────────────────────────────────────────────────────────────────────────
+//
+//   ks = Keystore.create("keys.pqks", password)
+//   ks.add("server", KyberKeyPair("kyber768"))
+//   ks.add("release-signing", FalconSigner(sk_bytes))
+//
+//   ks = Keystore("keys.pqks", password)
+//   kp = ks.get("server")
+//
+// Holds KyberPublicKey, KyberSecretKey, KyberKeyPair, FalconSigner and
+// FalconVerifier objects; get() returns the same class that was added, after
+// checking the entry's KeyMetadata (if it was added with one). The file is one
+// export_encrypted()-style blob (see keywrap.rs) over this payload, integers
+// big-endian:
+//
+//   magic "PQKS" | version 2 | entry count u32
+//   per entry: name length u16 | name (UTF-8) | kind u8 | key length u32 | key
+//              | has metadata u8 [| metadata, as in metadata.rs]
+//
+// Version 1 files (no metadata field) still open, and are rewritten as
+// version 2 on the next save.
+//
+// The Argon2id key is derived once when the keystore is created or opened.
+// Every add() / delete() rewrites the whole file under a fresh nonce: to a
+// temporary file in the same directory, fsync'd, then renamed over the old
+// one, so a crash leaves either the old or the new keystore, never a torn
+// one. Writers in other processes are not coordinated; the last save wins.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use pyo3::exceptions::{PyKeyError, PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use zeroize::Zeroizing;
+
+use crate::errors::KeyPolicyError;
+use crate::falcon::{FalconSigner, FalconVerifier};
+use crate::keywrap::{
+    Password, SealingKey, DEFAULT_ITERATIONS, DEFAULT_MEMORY_KIB, DEFAULT_PARALLELISM, KEY_ENVELOPE,
+};
+use crate::kyber::{KyberKeyPair, KyberPublicKey, KyberSecretKey};
+use crate::metadata::{now, KeyMetadata, KeyUsage};
+use crate::util::{write_atomic, Reader};
+
+const MAGIC: &[u8; 4] = b"PQKS";
+const VERSION: u8 = 2;
+
+#[derive(Clone, Copy)]
+enum KeyKind {
+    KyberPublicKey = 1,
+    KyberSecretKey = 2,
+    KyberKeyPair = 3,
+    FalconSigner = 4,
+    FalconVerifier = 5,
+}
+
+impl KeyKind {
+    fn from_u8(kind: u8) -> Option<Self> {
+        Some(match kind {
+            1 => KeyKind::KyberPublicKey,
+            2 => KeyKind::KyberSecretKey,
+            3 => KeyKind::KyberKeyPair,
+            4 => KeyKind::FalconSigner,
+            5 => KeyKind::FalconVerifier,
+            _ => return None,
+        })
+    }
+
+    fn usage(self) -> KeyUsage {
+        match self {
+            KeyKind::KyberPublicKey | KeyKind::KyberSecretKey | KeyKind::KyberKeyPair => {
+                KeyUsage::Encrypt
+            }
+            KeyKind::FalconSigner | KeyKind::FalconVerifier => KeyUsage::Sign,
+        }
+    }
+}
+
+struct Entry {
+    name: String,
+    kind: KeyKind,
+    /// Raw key bytes; the secret key for a KyberKeyPair.
+    key: Zeroizing<Vec<u8>>,
+    metadata: Option<KeyMetadata>,
+}
+
+/// Split a key object into its kind and raw bytes.
+fn entry_from_object(key: &Bound<'_, PyAny>) -> PyResult<(KeyKind, Zeroizing<Vec<u8>>)> {
+    let py = key.py();
+    let (kind, bytes) = if let Ok(pk) = key.downcast::<KyberPublicKey>() {
+        (KeyKind::KyberPublicKey, pk.borrow().public_bytes().to_vec())
+    } else if let Ok(sk) = key.downcast::<KyberSecretKey>() {
+        (KeyKind::KyberSecretKey, sk.borrow().secret_bytes().to_vec())
+    } else if let Ok(kp) = key.downcast::<KyberKeyPair>() {
+        let sk = kp.borrow().secret_key(py);
+        let bytes = sk.borrow(py).secret_bytes().to_vec();
+        (KeyKind::KyberKeyPair, bytes)
+    } else if let Ok(signer) = key.downcast::<FalconSigner>() {
+        (
+            KeyKind::FalconSigner,
+            signer.borrow().secret_bytes().to_vec(),
+        )
+    } else if let Ok(verifier) = key.downcast::<FalconVerifier>() {
+        (
+            KeyKind::FalconVerifier,
+            verifier.borrow().public_bytes().to_vec(),
+        )
+    } else {
+        return Err(PyTypeError::new_err(format!(
+            "Keystore holds Kyber and Falcon key objects, not '{}'",
+            key.get_type().name()?
+        )));
+    };
+    Ok((kind, Zeroizing::new(bytes)))
+}
+
+fn entry_to_object(py: Python, entry: &Entry) -> PyResult<PyObject> {
+    let key = &entry.key;
+    Ok(match entry.kind {
+        KeyKind::KyberPublicKey => KyberPublicKey::from_slice(key)?.into_py(py),
+        KeyKind::KyberSecretKey => KyberSecretKey::from_slice(key)?.into_py(py),
+        KeyKind::KyberKeyPair => {
+            KyberKeyPair::from_secret_key(py, KyberSecretKey::from_slice(key)?)?.into_py(py)
+        }
+        KeyKind::FalconSigner => FalconSigner::from_slice(key)?.into_py(py),
+        KeyKind::FalconVerifier => FalconVerifier::from_slice(key)?.into_py(py),
+    })
+}
+
+fn encode_entries(entries: &[Entry]) -> Zeroizing<Vec<u8>> {
+    let mut out = Zeroizing::new(Vec::new());
+    out.extend_from_slice(MAGIC);
+    out.push(VERSION);
+    out.extend_from_slice(&(entries.len() as u32).to_be_bytes());
+    for entry in entries {
+        out.extend_from_slice(&(entry.name.len() as u16).to_be_bytes());
+        out.extend_from_slice(entry.name.as_bytes());
+        out.push(entry.kind as u8);
+        out.extend_from_slice(&(entry.key.len() as u32).to_be_bytes());
+        out.extend_from_slice(&entry.key);
+        match &entry.metadata {
+            Some(metadata) => {
+                out.push(1);
+                metadata.encode_into(&mut out);
+            }
+            None => out.push(0),
+        }
+    }
+    out
+}
+
+// A payload that decrypted but doesn't parse came from something other than
+// this module, so it is reported as the wrong kind of file (ValueError) rather
+// than as tampering.
+fn decode_entries(payload: &[u8]) -> PyResult<Vec<Entry>> {
+    let mut r = Reader::new("keystore payload", payload);
+    if r.take(MAGIC.len()).ok() != Some(&MAGIC[..]) {
+        return Err(PyValueError::new_err(
+            "not a keystore file (an export_encrypted() blob?)",
+        ));
+    }
+    let version = r.u8()?;
+    if version != 1 && version != VERSION {
+        return Err(PyValueError::new_err(format!(
+            "unsupported keystore version {}",
+            version
+        )));
+    }
+
+    let count = r.u32()?;
+    let mut entries = Vec::new();
+    for _ in 0..count {
+        let name_len = r.u16()? as usize;
+        let name = std::str::from_utf8(r.take(name_len)?)
+            .map_err(|_| PyValueError::new_err("keystore entry name is not UTF-8"))?
+            .to_owned();
+        let kind = r.u8()?;
+        let kind = KeyKind::from_u8(kind).ok_or_else(|| {
+            PyValueError::new_err(format!(
+                "keystore entry '{}' has unknown kind {}",
+                name, kind
+            ))
+        })?;
+        let key_len = r.u32()? as usize;
+        let key = Zeroizing::new(r.take(key_len)?.to_vec());
+        let metadata = match version {
+            1 => None,
+            _ => match r.u8()? {
+                0 => None,
+                _ => Some(KeyMetadata::decode_from(&mut r)?),
+            },
+        };
+        entries.push(Entry {
+            name,
+            kind,
+            key,
+            metadata,
+        });
+    }
+    r.finish()?;
+    Ok(entries)
+}
+
+#[pyclass(module = "pqcrypto_bindings")]
+pub struct Keystore {
+    path: PathBuf,
+    key: SealingKey,
+    entries: Vec<Entry>,
+}
+
+impl Keystore {
+    fn save(&self) -> PyResult<()> {
+        let blob = self.key.seal(&encode_entries(&self.entries))?;
+        write_atomic(&self.path, &blob)
+    }
+
+    fn find(&self, name: &str) -> Option<usize> {
+        self.entries.iter().position(|e| e.name == name)
+    }
+}
+
+#[pymethods]
+impl Keystore {
+    /// Open an existing keystore file. Raises DecryptionError for a wrong
+    /// password.
+    #[new]
+    fn new(path: PathBuf, password: Password) -> PyResult<Self> {
+        let blob = Zeroizing::new(fs::read(&path)?);
+        let (key, payload) = SealingKey::open(&KEY_ENVELOPE, &blob, password.as_bytes())?;
+        let entries = decode_entries(&payload)?;
+        Ok(Keystore { path, key, entries })
+    }
+
+    /// Create a new, empty keystore file. Fails if `path` already exists.
+    /// The Argon2id costs are as for export_encrypted().
+    #[staticmethod]
+    #[pyo3(signature = (
+        path,
+        password,
+        memory_kib = DEFAULT_MEMORY_KIB,
+        iterations = DEFAULT_ITERATIONS,
+        parallelism = DEFAULT_PARALLELISM,
+    ))]
+    fn create(
+        path: PathBuf,
+        password: Password,
+        memory_kib: u32,
+        iterations: u32,
+        parallelism: u32,
+    ) -> PyResult<Self> {
+        if path.exists() {
+            return Err(PyValueError::new_err(format!(
+                "{} already exists",
+                path.display()
+            )));
+        }
+        let key = SealingKey::derive(
+            &KEY_ENVELOPE,
+            password.as_bytes(),
+            memory_kib,
+            iterations,
+            parallelism,
+        )?;
+        let keystore = Keystore {
+            path,
+            key,
+            entries: Vec::new(),
+        };
+        keystore.save()?;
+        Ok(keystore)
+    }
+
+    #[getter]
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Store `key` under `name`, with optional `metadata`, and save. Raises
+    /// ValueError if the name is taken, unless `overwrite` is set.
+    // stub: key: KyberPublicKey | KyberSecretKey | KyberKeyPair | FalconSigner | FalconVerifier
+    #[pyo3(signature = (name, key, overwrite = false, metadata = None))]
+    fn add(
+        &mut self,
+        name: String,
+        key: &Bound<'_, PyAny>,
+        overwrite: bool,
+        metadata: Option<KeyMetadata>,
+    ) -> PyResult<()> {
+        if name.is_empty() || name.len() > u16::MAX as usize {
+            return Err(PyValueError::new_err(format!(
+                "key name must be 1 to {} bytes",
+                u16::MAX
+            )));
+        }
+        let (kind, key) = entry_from_object(key)?;
+        if let Some(usage) = metadata.as_ref().and_then(KeyMetadata::allowed_usage) {
+            if usage != kind.usage() {
+                return Err(PyValueError::new_err(format!(
+                    "'{}' is a key for '{}', but its metadata says '{}'",
+                    name,
+                    kind.usage().name(),
+                    usage.name()
+                )));
+            }
+        }
+        let entry = Entry {
+            name,
+            kind,
+            key,
+            metadata,
+        };
+
+        match self.find(&entry.name) {
+            Some(_) if !overwrite => {
+                return Err(PyValueError::new_err(format!(
+                    "keystore already has a key named '{}'",
+                    entry.name
+                )))
+            }
+            Some(i) => self.entries[i] = entry,
+            None => self.entries.push(entry),
+        }
+        self.save()
+    }
+
+    /// The key stored under `name`, as the class it was added as. Raises
+    /// KeyError if there is none, and (unless `enforce` is off)
+    /// KeyPolicyError if it has expired or isn't a `usage` key.
+    // stub: usage: Literal["encrypt", "sign"] | None
+    // stub: -> KyberPublicKey | KyberSecretKey | KyberKeyPair | FalconSigner | FalconVerifier
+    #[pyo3(signature = (name, usage = None, enforce = true))]
+    fn get(
+        &self,
+        py: Python,
+        name: &str,
+        usage: Option<&str>,
+        enforce: bool,
+    ) -> PyResult<PyObject> {
+        let entry = &self.entries[self
+            .find(name)
+            .ok_or_else(|| PyKeyError::new_err(name.to_owned()))?];
+        if enforce {
+            let usage = usage.map(KeyUsage::from_name).transpose()?;
+            if let Some(usage) = usage.filter(|&u| u != entry.kind.usage()) {
+                return Err(KeyPolicyError::new_err(format!(
+                    "key '{}' is for '{}', not '{}'",
+                    name,
+                    entry.kind.usage().name(),
+                    usage.name()
+                )));
+            }
+            if let Some(metadata) = &entry.metadata {
+                metadata.enforce(usage, now())?;
+            }
+        }
+        entry_to_object(py, entry)
+    }
+
+    /// The metadata `name` was added with, or None. Raises KeyError if there
+    /// is no such key.
+    fn metadata(&self, name: &str) -> PyResult<Option<KeyMetadata>> {
+        let i = self
+            .find(name)
+            .ok_or_else(|| PyKeyError::new_err(name.to_owned()))?;
+        Ok(self.entries[i].metadata.clone())
+    }
+
+    /// Key names, in the order they were added.
+    fn list(&self) -> Vec<String> {
+        self.entries.iter().map(|e| e.name.clone()).collect()
+    }
+
+    /// Remove the key stored under `name` and save. Raises KeyError if there
+    /// is none.
+    fn delete(&mut self, name: &str) -> PyResult<()> {
+        let i = self
+            .find(name)
+            .ok_or_else(|| PyKeyError::new_err(name.to_owned()))?;
+        self.entries.remove(i);
+        self.save()
+    }
+
+    fn __contains__(&self, name: &str) -> bool {
+        self.find(name).is_some()
+    }
+
+    fn __len__(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Keystore(path='{}', keys={})",
+            self.path.display(),
+            self.entries.len()
+        )
+    }
+}
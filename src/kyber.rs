@@ -0,0 +1,527 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// Kyber key objects
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// Object-oriented wrapper over the round-3 Kyber bindings in lib.rs:
+//
+//   kp = KyberKeyPair("kyber768")
+//   ct, ss = kp.public_key.encapsulate()
+//   ss == kp.secret_key.decapsulate(ct)
+//
+// Keys remember their parameter set, so a Kyber-512 ciphertext can't be fed
+// to a Kyber-768 key by accident, and from_bytes() tells the sets apart by
+// length. Secret key bytes stay on the Rust side (wiped on drop) until
+// to_bytes() is called explicitly. Public keys pickle; secret keys and key
+// pairs only once allow_pickle is set on them.
+
+use pqcrypto_kyber::{kyber1024, kyber512, kyber768};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict};
+use zeroize::Zeroizing;
+
+use crate::buffer::Buffer;
+use crate::encoding::{
+    decode_public_key, decode_secret_key, encode_public_key, encode_secret_key, expect_family,
+    find_format, public_key_der_from_pem, public_key_pem, secret_key_der_from_pem, secret_key_pem,
+    Pem,
+};
+use crate::errors::{check_length, length_err, InvalidKeyError, InvalidLengthError};
+use crate::jwk::{
+    decode_jwk, encode_public_jwk, encode_secret_jwk, expect_jwk_family, find_jwk_format,
+};
+use crate::kem::find_kem;
+use crate::kem_traits;
+use crate::pct::check_kem_key_bytes;
+use crate::secret::{secret_bytes, SecretBytes};
+use crate::util::{ct_eq, fingerprint, secret_pickle_error};
+use kem_traits::{Ciphertext as _, PublicKey as _, SecretKey as _, SharedSecret as _};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum KyberParams {
+    Kyber512,
+    Kyber768,
+    Kyber1024,
+}
+
+const KYBER_PARAMS: [KyberParams; 3] = [
+    KyberParams::Kyber512,
+    KyberParams::Kyber768,
+    KyberParams::Kyber1024,
+];
+
+const KYBER_NAMES: &[&str] = &["kyber512", "kyber768", "kyber1024"];
+
+/// Dispatch on a Kyber parameter set, binding `$m` to its pqcrypto module.
+macro_rules! with_kyber_params {
+    ($params:expr, $m:ident => $body:expr) => {
+        match $params {
+            KyberParams::Kyber512 => {
+                use kyber512 as $m;
+                $body
+            }
+            KyberParams::Kyber768 => {
+                use kyber768 as $m;
+                $body
+            }
+            KyberParams::Kyber1024 => {
+                use kyber1024 as $m;
+                $body
+            }
+        }
+    };
+}
+
+impl KyberParams {
+    fn from_name(name: &str) -> PyResult<Self> {
+        KYBER_PARAMS
+            .into_iter()
+            .find(|p| p.name() == name)
+            .ok_or_else(|| {
+                PyValueError::new_err(format!(
+                    "unsupported Kyber parameter set '{}' (expected kyber512, kyber768 or kyber1024)",
+                    name
+                ))
+            })
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            KyberParams::Kyber512 => "kyber512",
+            KyberParams::Kyber768 => "kyber768",
+            KyberParams::Kyber1024 => "kyber1024",
+        }
+    }
+
+    fn from_len(what: &str, len: usize, len_of: fn(KyberParams) -> usize) -> PyResult<Self> {
+        KYBER_PARAMS
+            .into_iter()
+            .find(|&p| len_of(p) == len)
+            .ok_or_else(|| {
+                InvalidLengthError::new_err(format!(
+                    "Kyber {}: expected {}, {} or {} bytes, got {}",
+                    what,
+                    len_of(KyberParams::Kyber512),
+                    len_of(KyberParams::Kyber768),
+                    len_of(KyberParams::Kyber1024),
+                    len
+                ))
+            })
+    }
+
+    fn public_key_bytes(self) -> usize {
+        with_kyber_params!(self, m => m::public_key_bytes())
+    }
+
+    fn secret_key_bytes(self) -> usize {
+        with_kyber_params!(self, m => m::secret_key_bytes())
+    }
+
+    fn ciphertext_bytes(self) -> usize {
+        with_kyber_params!(self, m => m::ciphertext_bytes())
+    }
+}
+
+// ─── KyberPublicKey ───────────────────────────────────────────────────────────
+
+#[pyclass(module = "pqcrypto_bindings")]
+pub struct KyberPublicKey {
+    params: KyberParams,
+    pk: Vec<u8>,
+}
+
+impl KyberPublicKey {
+    pub(crate) fn from_slice(pk_bytes: &[u8]) -> PyResult<Self> {
+        let params =
+            KyberParams::from_len("public key", pk_bytes.len(), KyberParams::public_key_bytes)?;
+        Ok(KyberPublicKey {
+            params,
+            pk: pk_bytes.to_vec(),
+        })
+    }
+
+    pub(crate) fn public_bytes(&self) -> &[u8] {
+        &self.pk
+    }
+
+    fn parse_der(der: &[u8]) -> PyResult<Self> {
+        let (format, pk) = decode_public_key(der)?;
+        expect_family(format, KYBER_NAMES)?;
+        Ok(KyberPublicKey {
+            params: KyberParams::from_name(format.name)?,
+            pk: pk.to_vec(),
+        })
+    }
+}
+
+#[pymethods]
+impl KyberPublicKey {
+    /// Parameter set name, e.g. "kyber768".
+    #[getter]
+    fn params(&self) -> &'static str {
+        self.params.name()
+    }
+
+    /// Encapsulate a fresh shared secret: returns (ciphertext, shared_secret).
+    fn encapsulate(&self, py: Python) -> PyResult<(Py<PyBytes>, Py<SecretBytes>)> {
+        with_kyber_params!(self.params, m => {
+            let pk = m::PublicKey::from_bytes(&self.pk)
+                .map_err(length_err)?;
+            let (ss, ct) = m::encapsulate(&pk);
+
+            Ok((
+                PyBytes::new_bound(py, ct.as_bytes()).unbind(),
+                secret_bytes(py, ss.as_bytes())?,
+            ))
+        })
+    }
+
+    fn to_bytes(&self, py: Python) -> Py<PyBytes> {
+        PyBytes::new_bound(py, &self.pk).unbind()
+    }
+
+    #[staticmethod]
+    fn from_bytes(pk_bytes: Buffer) -> PyResult<Self> {
+        Self::from_slice(&pk_bytes)
+    }
+
+    /// DER SubjectPublicKeyInfo.
+    fn to_der(&self, py: Python) -> PyResult<Py<PyBytes>> {
+        let der = encode_public_key(find_format(self.params.name())?, &self.pk)?;
+        Ok(PyBytes::new_bound(py, &der).unbind())
+    }
+
+    #[staticmethod]
+    fn from_der(der: Buffer) -> PyResult<Self> {
+        Self::parse_der(&der)
+    }
+
+    /// "PUBLIC KEY" PEM block.
+    fn to_pem(&self) -> PyResult<String> {
+        Ok(public_key_pem(find_format(self.params.name())?, &self.pk)?.to_string())
+    }
+
+    #[staticmethod]
+    fn from_pem(pem: Pem) -> PyResult<Self> {
+        Self::parse_der(&public_key_der_from_pem(&pem)?)
+    }
+
+    /// Public JWK dict ("kty": "AKP").
+    // stub: -> dict[str, str]
+    fn to_jwk<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        encode_public_jwk(py, find_jwk_format(self.params.name())?, &self.pk)
+    }
+
+    /// From a JWK dict or its JSON text. A private JWK gives its public half.
+    // stub: jwk: dict[str, str] | str
+    #[staticmethod]
+    fn from_jwk(jwk: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let jwk = decode_jwk(jwk)?;
+        expect_jwk_family(jwk.format, KYBER_NAMES)?;
+        Ok(KyberPublicKey {
+            params: KyberParams::from_name(jwk.format.name)?,
+            pk: jwk.pk,
+        })
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        self.params == other.params && ct_eq(&self.pk, &other.pk)
+    }
+
+    fn __reduce__(slf: &Bound<'_, Self>) -> PyResult<(PyObject, (Py<PyBytes>,))> {
+        let from_bytes = slf.get_type().getattr("from_bytes")?.unbind();
+        Ok((from_bytes, (slf.borrow().to_bytes(slf.py()),)))
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "KyberPublicKey(params='{}', fingerprint='{}')",
+            self.params.name(),
+            fingerprint(&self.pk)
+        )
+    }
+}
+
+// ─── KyberSecretKey ───────────────────────────────────────────────────────────
+
+#[pyclass(module = "pqcrypto_bindings")]
+pub struct KyberSecretKey {
+    params: KyberParams,
+    sk: Zeroizing<Vec<u8>>,
+    /// Secret keys refuse to pickle unless this is set to True.
+    #[pyo3(get, set)]
+    allow_pickle: bool,
+}
+
+// Kyber secret keys are s || pk || H(pk) || z, with H(pk) and z 32 bytes each.
+const KYBER_SK_TRAILER_LEN: usize = 64;
+
+impl KyberSecretKey {
+    pub(crate) fn from_slice(sk_bytes: &[u8]) -> PyResult<Self> {
+        let params =
+            KyberParams::from_len("secret key", sk_bytes.len(), KyberParams::secret_key_bytes)?;
+        Ok(KyberSecretKey {
+            params,
+            sk: Zeroizing::new(sk_bytes.to_vec()),
+            allow_pickle: false,
+        })
+    }
+
+    pub(crate) fn secret_bytes(&self) -> &[u8] {
+        &self.sk
+    }
+
+    fn parse_der(der: &[u8]) -> PyResult<Self> {
+        let (format, sk) = decode_secret_key(der)?;
+        expect_family(format, KYBER_NAMES)?;
+        Ok(KyberSecretKey {
+            params: KyberParams::from_name(format.name)?,
+            sk,
+            allow_pickle: false,
+        })
+    }
+
+    fn embedded_public_key(&self) -> &[u8] {
+        let end = self.sk.len() - KYBER_SK_TRAILER_LEN;
+        &self.sk[end - self.params.public_key_bytes()..end]
+    }
+}
+
+#[pymethods]
+impl KyberSecretKey {
+    /// Parameter set name, e.g. "kyber768".
+    #[getter]
+    fn params(&self) -> &'static str {
+        self.params.name()
+    }
+
+    /// Recover the shared secret from a ciphertext made for this key.
+    fn decapsulate(&self, py: Python, ct_bytes: Buffer) -> PyResult<Py<SecretBytes>> {
+        check_length(
+            &format!("{} ciphertext", self.params.name()),
+            &ct_bytes,
+            self.params.ciphertext_bytes(),
+        )?;
+
+        with_kyber_params!(self.params, m => {
+            let sk = m::SecretKey::from_bytes(&self.sk)
+                .map_err(length_err)?;
+            let ct = m::Ciphertext::from_bytes(&ct_bytes)
+                .map_err(length_err)?;
+            let ss = m::decapsulate(&ct, &sk);
+
+            secret_bytes(py, ss.as_bytes())
+        })
+    }
+
+    /// Raw secret key bytes. Handle with care.
+    fn to_bytes(&self, py: Python) -> Py<PyBytes> {
+        PyBytes::new_bound(py, &self.sk).unbind()
+    }
+
+    #[staticmethod]
+    fn from_bytes(sk_bytes: Buffer) -> PyResult<Self> {
+        Self::from_slice(&sk_bytes)
+    }
+
+    /// DER PKCS#8 PrivateKeyInfo. Handle with care.
+    fn to_der(&self, py: Python) -> PyResult<Py<PyBytes>> {
+        let der = encode_secret_key(find_format(self.params.name())?, &self.sk, None)?;
+        Ok(PyBytes::new_bound(py, &der).unbind())
+    }
+
+    #[staticmethod]
+    fn from_der(der: Buffer) -> PyResult<Self> {
+        Self::parse_der(&der)
+    }
+
+    /// "PRIVATE KEY" PEM block. Handle with care.
+    fn to_pem(&self) -> PyResult<String> {
+        let pem = secret_key_pem(find_format(self.params.name())?, &self.sk, None)?;
+        Ok(pem.to_string())
+    }
+
+    #[staticmethod]
+    fn from_pem(pem: Pem) -> PyResult<Self> {
+        Self::parse_der(&secret_key_der_from_pem(&pem)?)
+    }
+
+    /// Private JWK dict, with the embedded public key as "pub". Handle with
+    /// care.
+    // stub: -> dict[str, str]
+    fn to_jwk<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        encode_secret_jwk(py, find_jwk_format(self.params.name())?, &self.sk, None)
+    }
+
+    /// From a private JWK dict or its JSON text.
+    // stub: jwk: dict[str, str] | str
+    #[staticmethod]
+    fn from_jwk(jwk: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let jwk = decode_jwk(jwk)?;
+        expect_jwk_family(jwk.format, KYBER_NAMES)?;
+        let sk = jwk
+            .sk
+            .ok_or_else(|| InvalidKeyError::new_err("JWK has no 'priv' member"))?;
+        Ok(KyberSecretKey {
+            params: KyberParams::from_name(jwk.format.name)?,
+            sk,
+            allow_pickle: false,
+        })
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        self.params == other.params && ct_eq(&self.sk, &other.sk)
+    }
+
+    fn __reduce__(slf: &Bound<'_, Self>) -> PyResult<(PyObject, (Py<PyBytes>,))> {
+        if !slf.borrow().allow_pickle {
+            return Err(secret_pickle_error("KyberSecretKey"));
+        }
+        let from_bytes = slf.get_type().getattr("from_bytes")?.unbind();
+        Ok((from_bytes, (slf.borrow().to_bytes(slf.py()),)))
+    }
+
+    /// Never shows key material, only the fingerprint of the public key
+    /// embedded in it.
+    fn __repr__(&self) -> String {
+        format!(
+            "KyberSecretKey(params='{}', fingerprint='{}')",
+            self.params.name(),
+            fingerprint(self.embedded_public_key())
+        )
+    }
+}
+
+// ─── KyberKeyPair ─────────────────────────────────────────────────────────────
+
+#[pyclass(module = "pqcrypto_bindings")]
+pub struct KyberKeyPair {
+    public_key: Py<KyberPublicKey>,
+    secret_key: Py<KyberSecretKey>,
+    /// Key pairs refuse to pickle unless this is set to True.
+    #[pyo3(get, set)]
+    allow_pickle: bool,
+}
+
+impl KyberKeyPair {
+    /// Rebuild a pair from the secret key alone, using the public key it
+    /// embeds.
+    pub(crate) fn from_secret_key(py: Python, secret_key: KyberSecretKey) -> PyResult<Self> {
+        let public_key = KyberPublicKey {
+            params: secret_key.params,
+            pk: secret_key.embedded_public_key().to_vec(),
+        };
+        Ok(KyberKeyPair {
+            public_key: Py::new(py, public_key)?,
+            secret_key: Py::new(py, secret_key)?,
+            allow_pickle: false,
+        })
+    }
+}
+
+#[pymethods]
+impl KyberKeyPair {
+    /// Generate a fresh key pair for the given parameter set.
+    #[new]
+    #[pyo3(signature = (params = "kyber512"))]
+    fn new(py: Python, params: &str) -> PyResult<Self> {
+        let params = KyberParams::from_name(params)?;
+
+        let (pk, sk) = with_kyber_params!(params, m => {
+            let (pk, sk) = m::keypair();
+            (pk.as_bytes().to_vec(), Zeroizing::new(sk.as_bytes().to_vec()))
+        });
+        let alg = find_kem(params.name()).expect("Kyber parameter sets are registered");
+        check_kem_key_bytes(py, alg, &pk, &sk)?;
+
+        Ok(KyberKeyPair {
+            public_key: Py::new(py, KyberPublicKey { params, pk })?,
+            secret_key: Py::new(
+                py,
+                KyberSecretKey {
+                    params,
+                    sk,
+                    allow_pickle: false,
+                },
+            )?,
+            allow_pickle: false,
+        })
+    }
+
+    /// Parameter set name, e.g. "kyber768".
+    #[getter]
+    fn params(&self, py: Python) -> &'static str {
+        self.public_key.borrow(py).params.name()
+    }
+
+    #[getter]
+    fn public_key(&self, py: Python) -> Py<KyberPublicKey> {
+        self.public_key.clone_ref(py)
+    }
+
+    #[getter]
+    pub(crate) fn secret_key(&self, py: Python) -> Py<KyberSecretKey> {
+        self.secret_key.clone_ref(py)
+    }
+
+    /// Same as `self.public_key.encapsulate()`.
+    fn encapsulate(&self, py: Python) -> PyResult<(Py<PyBytes>, Py<SecretBytes>)> {
+        self.public_key.borrow(py).encapsulate(py)
+    }
+
+    /// Same as `self.secret_key.decapsulate(ct)`.
+    fn decapsulate(&self, py: Python, ct_bytes: Buffer) -> PyResult<Py<SecretBytes>> {
+        self.secret_key.borrow(py).decapsulate(py, ct_bytes)
+    }
+
+    /// (public_key_bytes, secret_key_bytes), as kyber_keygen() returns them.
+    fn to_bytes(&self, py: Python) -> (Py<PyBytes>, Py<PyBytes>) {
+        (
+            self.public_key.borrow(py).to_bytes(py),
+            self.secret_key.borrow(py).to_bytes(py),
+        )
+    }
+
+    #[staticmethod]
+    fn from_bytes(py: Python, pk_bytes: Buffer, sk_bytes: Buffer) -> PyResult<Self> {
+        let public_key = KyberPublicKey::from_bytes(pk_bytes)?;
+        let secret_key = KyberSecretKey::from_bytes(sk_bytes)?;
+        if public_key.params != secret_key.params {
+            return Err(InvalidKeyError::new_err(format!(
+                "public key is {} but secret key is {}",
+                public_key.params.name(),
+                secret_key.params.name()
+            )));
+        }
+
+        Ok(KyberKeyPair {
+            public_key: Py::new(py, public_key)?,
+            secret_key: Py::new(py, secret_key)?,
+            allow_pickle: false,
+        })
+    }
+
+    fn __eq__(&self, py: Python, other: &Self) -> bool {
+        self.secret_key
+            .borrow(py)
+            .__eq__(&other.secret_key.borrow(py))
+    }
+
+    fn __reduce__(slf: &Bound<'_, Self>) -> PyResult<(PyObject, PyObject)> {
+        if !slf.borrow().allow_pickle {
+            return Err(secret_pickle_error("KyberKeyPair"));
+        }
+        let py = slf.py();
+        let from_bytes = slf.get_type().getattr("from_bytes")?.unbind();
+        Ok((from_bytes, slf.borrow().to_bytes(py).into_py(py)))
+    }
+
+    fn __repr__(&self, py: Python) -> String {
+        let pk = self.public_key.borrow(py);
+        format!(
+            "KyberKeyPair(params='{}', fingerprint='{}')",
+            pk.params.name(),
+            fingerprint(&pk.pk)
+        )
+    }
+}
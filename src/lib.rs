@@ -1,167 +1,1751 @@
 use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
-use pyo3::types::PyBytes;
-
-// ─── Kyber-512 ────────────────────────────────────────────────────────────────
-use pqcrypto_kyber::kyber512::{
-    decapsulate as kyber_decapsulate_impl,
-    encapsulate as kyber_encapsulate_impl,
-    keypair as kyber_keypair_impl,
-    Ciphertext as KyberCiphertext,
-    PublicKey as KyberPublicKey,
-    SecretKey as KyberSecretKey,
-    SharedSecret as KyberSharedSecret,
-};
-
-// ─── Falcon-512 Signatures ────────────────────────────────────────────────────
-use pqcrypto_falcon::falcon512::{
-    DetachedSignature as FalconDetachedSignature,
-    PublicKey as FalconPublicKey,
-    SecretKey as FalconSecretKey,
-    detached_sign as falcon_detached_sign_impl,
-    keypair as falcon_keypair_impl,
-    verify_detached_signature as falcon_verify_impl,
-};
+use pyo3::types::{PyBytes, PyDict};
+
+mod aead;
+mod age;
+mod ake;
+mod attached;
+mod buffer;
+mod cbor;
+mod channel;
+mod classical;
+mod cms;
+mod cose;
+mod dsse;
+mod encoding;
+mod errors;
+mod falcon;
+mod filecrypt;
+mod hpke;
+mod hsm;
+mod hybrid;
+mod jwe;
+mod jwk;
+mod jws;
+mod kdf;
+mod kem;
+mod kemtls;
+mod keybundle;
+mod keyhandle;
+mod keyschedule;
+mod keystore;
+mod keywrap;
+mod kyber;
+mod manifest;
+mod metadata;
+mod minisign;
+mod multisig;
+mod noise;
+mod nonce;
+mod openssh;
+mod oskeyring;
+mod pake;
+mod pct;
+mod pqxdh;
+mod prehash;
+mod recover;
+mod resumption;
+mod seal;
+mod secret;
+mod seeded;
+mod shamir;
+mod sigfile;
+mod sign;
+mod signcrypt;
+mod stateful;
+mod stream;
+mod timestamped;
+mod tls;
+mod token;
+mod tpm;
+mod tuf;
+mod util;
+mod validate;
+mod x509;
+mod xof;
 
 // ─── Trait Imports ────────────────────────────────────────────────────────────
 use pqcrypto_traits::kem as kem_traits;
 use pqcrypto_traits::sign as sign_traits;
 
 // ───────────────────────────────────────────────────────────────────────────────
-// Kyber-512 helpers
+// KEM bindings
 // ───────────────────────────────────────────────────────────────────────────────
+//
+// Every pqcrypto KEM parameter set exposes the same trio of functions, so
+// the bindings are stamped out per crate/module pair:
+//
+//   keygen()             -> (public_key, secret_key)
+//   encapsulate(pk)      -> (ciphertext, shared_secret)
+//   decapsulate(sk, ct)  -> shared_secret
 
-fn kyber_pk_from_bytes(bytes: &[u8]) -> PyResult<KyberPublicKey> {
-    <KyberPublicKey as kem_traits::PublicKey>::from_bytes(bytes)
-        .map_err(|e| PyValueError::new_err(e.to_string()))
-}
+macro_rules! kem_bindings {
+    ($krate:ident, $alg:ident, $keygen:ident, $encapsulate:ident, $decapsulate:ident) => {
+        mod $alg {
+            use super::kem_traits;
+            use pyo3::prelude::*;
+            use pyo3::types::PyBytes;
+            use $krate::$alg::{Ciphertext, PublicKey, SecretKey, SharedSecret};
 
-fn kyber_sk_from_bytes(bytes: &[u8]) -> PyResult<KyberSecretKey> {
-    <KyberSecretKey as kem_traits::SecretKey>::from_bytes(bytes)
-        .map_err(|e| PyValueError::new_err(e.to_string()))
-}
+            use crate::buffer::Buffer;
+            use crate::errors::{check_length, length_err};
+            use crate::secret::{secret_bytes, SecretBytes};
 
-fn kyber_ct_from_bytes(bytes: &[u8]) -> PyResult<KyberCiphertext> {
-    <KyberCiphertext as kem_traits::Ciphertext>::from_bytes(bytes)
-        .map_err(|e| PyValueError::new_err(e.to_string()))
-}
+            fn pk_from_bytes(bytes: &[u8]) -> PyResult<PublicKey> {
+                check_length(
+                    concat!(stringify!($alg), " public key"),
+                    bytes,
+                    $krate::$alg::public_key_bytes(),
+                )?;
+                <PublicKey as kem_traits::PublicKey>::from_bytes(bytes).map_err(length_err)
+            }
 
-// ─── Kyber: keygen ────────────────────────────────────────────────────────────
+            fn sk_from_bytes(bytes: &[u8]) -> PyResult<SecretKey> {
+                check_length(
+                    concat!(stringify!($alg), " secret key"),
+                    bytes,
+                    $krate::$alg::secret_key_bytes(),
+                )?;
+                <SecretKey as kem_traits::SecretKey>::from_bytes(bytes).map_err(length_err)
+            }
 
-#[pyfunction]
-fn kyber_keygen(py: Python) -> PyResult<(Py<PyBytes>, Py<PyBytes>)> {
-    let (pk, sk) = kyber_keypair_impl();
+            fn ct_from_bytes(bytes: &[u8]) -> PyResult<Ciphertext> {
+                check_length(
+                    concat!(stringify!($alg), " ciphertext"),
+                    bytes,
+                    $krate::$alg::ciphertext_bytes(),
+                )?;
+                <Ciphertext as kem_traits::Ciphertext>::from_bytes(bytes).map_err(length_err)
+            }
 
-    let pk_bytes = <KyberPublicKey as kem_traits::PublicKey>::as_bytes(&pk);
-    let sk_bytes = <KyberSecretKey as kem_traits::SecretKey>::as_bytes(&sk);
+            pub(crate) const ALGORITHM: crate::kem::KemAlgorithm = crate::kem::KemAlgorithm {
+                name: stringify!($alg),
+                public_key_bytes: $krate::$alg::public_key_bytes(),
+                secret_key_bytes: $krate::$alg::secret_key_bytes(),
+                ciphertext_bytes: $krate::$alg::ciphertext_bytes(),
+                shared_secret_bytes: $krate::$alg::shared_secret_bytes(),
+                keygen: $keygen,
+                encapsulate: $encapsulate,
+                decapsulate: $decapsulate,
+            };
 
-    Ok((
-        PyBytes::new_bound(py, pk_bytes).unbind(),
-        PyBytes::new_bound(py, sk_bytes).unbind(),
-    ))
-}
+            // ─── keygen ───────────────────────────────────────────────────
 
-// ─── Kyber: encapsulate(pk) -> (ciphertext, shared_secret) ────────────────────
+            #[pyfunction]
+            pub fn $keygen(py: Python) -> PyResult<(Py<PyBytes>, Py<SecretBytes>)> {
+                let (pk, sk) = $krate::$alg::keypair();
 
-#[pyfunction]
-fn kyber_encapsulate(py: Python, pk_bytes: &[u8]) -> PyResult<(Py<PyBytes>, Py<PyBytes>)> {
-    let pk = kyber_pk_from_bytes(pk_bytes)?;
+                let pk_bytes = <PublicKey as kem_traits::PublicKey>::as_bytes(&pk);
+                let sk_bytes = <SecretKey as kem_traits::SecretKey>::as_bytes(&sk);
 
-    let (ss, ct) = kyber_encapsulate_impl(&pk);
+                let keys = (
+                    PyBytes::new_bound(py, pk_bytes).unbind(),
+                    secret_bytes(py, sk_bytes)?,
+                );
+                crate::pct::checked_kem_keypair(py, &ALGORITHM, keys)
+            }
 
-    let ss_bytes = <KyberSharedSecret as kem_traits::SharedSecret>::as_bytes(&ss);
-    let ct_bytes = <KyberCiphertext as kem_traits::Ciphertext>::as_bytes(&ct);
+            // ─── encapsulate(pk) -> (ciphertext, shared_secret) ───────────
 
-    // Return (ciphertext, shared_secret)
-    Ok((
-        PyBytes::new_bound(py, ct_bytes).unbind(),
-        PyBytes::new_bound(py, ss_bytes).unbind(),
-    ))
+            #[pyfunction]
+            pub fn $encapsulate(
+                py: Python,
+                pk_bytes: Buffer,
+            ) -> PyResult<(Py<PyBytes>, Py<SecretBytes>)> {
+                let pk = pk_from_bytes(&pk_bytes)?;
+
+                let (ss, ct) = $krate::$alg::encapsulate(&pk);
+
+                let ss_bytes = <SharedSecret as kem_traits::SharedSecret>::as_bytes(&ss);
+                let ct_bytes = <Ciphertext as kem_traits::Ciphertext>::as_bytes(&ct);
+
+                // Return (ciphertext, shared_secret)
+                Ok((
+                    PyBytes::new_bound(py, ct_bytes).unbind(),
+                    secret_bytes(py, ss_bytes)?,
+                ))
+            }
+
+            // ─── decapsulate(sk, ct) -> ss ────────────────────────────────
+
+            #[pyfunction]
+            pub fn $decapsulate(
+                py: Python,
+                sk_bytes: Buffer,
+                ct_bytes: Buffer,
+            ) -> PyResult<Py<SecretBytes>> {
+                let sk = sk_from_bytes(&sk_bytes)?;
+                let ct = ct_from_bytes(&ct_bytes)?;
+
+                let ss = $krate::$alg::decapsulate(&ct, &sk);
+                let ss_bytes = <SharedSecret as kem_traits::SharedSecret>::as_bytes(&ss);
+
+                secret_bytes(py, ss_bytes)
+            }
+        }
+    };
 }
 
-// ─── Kyber: decapsulate(sk, ct) -> ss ─────────────────────────────────────────
+// Kyber-512 (NIST level 1) keeps the original unsuffixed names.
+kem_bindings!(
+    pqcrypto_kyber,
+    kyber512,
+    kyber_keygen,
+    kyber_encapsulate,
+    kyber_decapsulate
+);
 
-#[pyfunction]
-fn kyber_decapsulate(py: Python, sk_bytes: &[u8], ct_bytes: &[u8]) -> PyResult<Py<PyBytes>> {
-    let sk = kyber_sk_from_bytes(sk_bytes)?;
-    let ct = kyber_ct_from_bytes(ct_bytes)?;
+// Kyber-768 (NIST level 3), the recommended parameter set.
+kem_bindings!(
+    pqcrypto_kyber,
+    kyber768,
+    kyber768_keygen,
+    kyber768_encapsulate,
+    kyber768_decapsulate
+);
+
+// Kyber-1024 (NIST level 5) for long-term data protection.
+kem_bindings!(
+    pqcrypto_kyber,
+    kyber1024,
+    kyber1024_keygen,
+    kyber1024_encapsulate,
+    kyber1024_decapsulate
+);
+
+// ML-KEM (FIPS 203 final). Not wire-compatible with round-3 Kyber above,
+// but interoperates with other standards-compliant implementations.
+kem_bindings!(
+    pqcrypto_mlkem,
+    mlkem512,
+    mlkem512_keygen,
+    mlkem512_encapsulate,
+    mlkem512_decapsulate
+);
+kem_bindings!(
+    pqcrypto_mlkem,
+    mlkem768,
+    mlkem768_keygen,
+    mlkem768_encapsulate,
+    mlkem768_decapsulate
+);
+kem_bindings!(
+    pqcrypto_mlkem,
+    mlkem1024,
+    mlkem1024_keygen,
+    mlkem1024_encapsulate,
+    mlkem1024_decapsulate
+);
+
+// HQC-128/192/256: code-based rather than lattice-based, for hybrids that
+// shouldn't rest solely on module lattices.
+kem_bindings!(
+    pqcrypto_hqc,
+    hqc128,
+    hqc128_keygen,
+    hqc128_encapsulate,
+    hqc128_decapsulate
+);
+kem_bindings!(
+    pqcrypto_hqc,
+    hqc192,
+    hqc192_keygen,
+    hqc192_encapsulate,
+    hqc192_decapsulate
+);
+kem_bindings!(
+    pqcrypto_hqc,
+    hqc256,
+    hqc256_keygen,
+    hqc256_encapsulate,
+    hqc256_decapsulate
+);
+
+// FrodoKEM-640/976/1344 in both AES and SHAKE flavours (plain LWE, as
+// recommended by BSI and other regulatory profiles).
+kem_bindings!(
+    pqcrypto_frodo,
+    frodokem640aes,
+    frodokem640aes_keygen,
+    frodokem640aes_encapsulate,
+    frodokem640aes_decapsulate
+);
+kem_bindings!(
+    pqcrypto_frodo,
+    frodokem640shake,
+    frodokem640shake_keygen,
+    frodokem640shake_encapsulate,
+    frodokem640shake_decapsulate
+);
+kem_bindings!(
+    pqcrypto_frodo,
+    frodokem976aes,
+    frodokem976aes_keygen,
+    frodokem976aes_encapsulate,
+    frodokem976aes_decapsulate
+);
+kem_bindings!(
+    pqcrypto_frodo,
+    frodokem976shake,
+    frodokem976shake_keygen,
+    frodokem976shake_encapsulate,
+    frodokem976shake_decapsulate
+);
+kem_bindings!(
+    pqcrypto_frodo,
+    frodokem1344aes,
+    frodokem1344aes_keygen,
+    frodokem1344aes_encapsulate,
+    frodokem1344aes_decapsulate
+);
+kem_bindings!(
+    pqcrypto_frodo,
+    frodokem1344shake,
+    frodokem1344shake_keygen,
+    frodokem1344shake_encapsulate,
+    frodokem1344shake_decapsulate
+);
+
+// sntrup761, the Streamlined NTRU Prime parameter set OpenSSH pairs with
+// X25519 in sntrup761x25519-sha512.
+kem_bindings!(
+    pqcrypto_ntruprime,
+    sntrup761,
+    sntrup761_keygen,
+    sntrup761_encapsulate,
+    sntrup761_decapsulate
+);
+
+// ───────────────────────────────────────────────────────────────────────────────
+// Classic McEliece KEM bindings
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// Same keygen/encapsulate/decapsulate shape as above, but McEliece public keys
+// run to hundreds of kilobytes (megabytes for the larger sets). Rather than
+// going through pqcrypto's fixed-size array structs, which would put the keys
+// on the stack and copy them twice, we call the PQClean FFI directly: keygen
+// writes straight into the Python bytes objects and inputs are borrowed
+// in place.
+
+macro_rules! mceliece_bindings {
+    (
+        $alg:ident,
+        $keygen:ident,
+        $encapsulate:ident,
+        $decapsulate:ident,
+        $ffi_keypair:ident,
+        $ffi_enc:ident,
+        $ffi_dec:ident
+    ) => {
+        mod $alg {
+            use pqcrypto_classicmceliece::ffi;
+            use pqcrypto_classicmceliece::$alg::{
+                ciphertext_bytes, public_key_bytes, secret_key_bytes, shared_secret_bytes,
+            };
+            use pyo3::exceptions::PyRuntimeError;
+            use pyo3::prelude::*;
+            use pyo3::types::PyBytes;
+
+            use crate::buffer::Buffer;
+            use crate::errors::{check_length, DecapsulationError};
+            use crate::secret::{secret_bytes_with, SecretBytes};
+
+            fn check_rc(rc: std::os::raw::c_int) -> PyResult<()> {
+                if rc != 0 {
+                    return Err(PyRuntimeError::new_err(concat!(
+                        stringify!($alg),
+                        " operation failed"
+                    )));
+                }
+                Ok(())
+            }
+
+            pub(crate) const ALGORITHM: crate::kem::KemAlgorithm = crate::kem::KemAlgorithm {
+                name: stringify!($alg),
+                public_key_bytes: public_key_bytes(),
+                secret_key_bytes: secret_key_bytes(),
+                ciphertext_bytes: ciphertext_bytes(),
+                shared_secret_bytes: shared_secret_bytes(),
+                keygen: $keygen,
+                encapsulate: $encapsulate,
+                decapsulate: $decapsulate,
+            };
+
+            // ─── keygen ───────────────────────────────────────────────────
+
+            #[pyfunction]
+            pub fn $keygen(py: Python) -> PyResult<(Py<PyBytes>, Py<SecretBytes>)> {
+                let mut sk = None;
+
+                let pk = PyBytes::new_bound_with(py, public_key_bytes(), |pk_buf| {
+                    let sk_bytes = secret_bytes_with(py, secret_key_bytes(), |sk_buf| {
+                        check_rc(unsafe {
+                            ffi::$ffi_keypair(pk_buf.as_mut_ptr(), sk_buf.as_mut_ptr())
+                        })
+                    })?;
+                    sk = Some(sk_bytes);
+                    Ok(())
+                })?;
+
+                let sk = sk.expect("secret key initialised alongside public key");
+                crate::pct::checked_kem_keypair(py, &ALGORITHM, (pk.unbind(), sk))
+            }
+
+            // ─── encapsulate(pk) -> (ciphertext, shared_secret) ───────────
+
+            #[pyfunction]
+            pub fn $encapsulate(
+                py: Python,
+                pk_bytes: Buffer,
+            ) -> PyResult<(Py<PyBytes>, Py<SecretBytes>)> {
+                check_length(
+                    concat!(stringify!($alg), " public key"),
+                    &pk_bytes,
+                    public_key_bytes(),
+                )?;
+
+                let mut ss = None;
 
-    let ss = kyber_decapsulate_impl(&ct, &sk);
-    let ss_bytes = <KyberSharedSecret as kem_traits::SharedSecret>::as_bytes(&ss);
+                let ct = PyBytes::new_bound_with(py, ciphertext_bytes(), |ct_buf| {
+                    let ss_bytes = secret_bytes_with(py, shared_secret_bytes(), |ss_buf| {
+                        check_rc(unsafe {
+                            ffi::$ffi_enc(
+                                ct_buf.as_mut_ptr(),
+                                ss_buf.as_mut_ptr(),
+                                pk_bytes.as_ptr(),
+                            )
+                        })
+                    })?;
+                    ss = Some(ss_bytes);
+                    Ok(())
+                })?;
 
-    Ok(PyBytes::new_bound(py, ss_bytes).unbind())
+                let ss = ss.expect("shared secret initialised alongside ciphertext");
+
+                // Return (ciphertext, shared_secret)
+                Ok((ct.unbind(), ss))
+            }
+
+            // ─── decapsulate(sk, ct) -> ss ────────────────────────────────
+
+            #[pyfunction]
+            pub fn $decapsulate(
+                py: Python,
+                sk_bytes: Buffer,
+                ct_bytes: Buffer,
+            ) -> PyResult<Py<SecretBytes>> {
+                check_length(
+                    concat!(stringify!($alg), " secret key"),
+                    &sk_bytes,
+                    secret_key_bytes(),
+                )?;
+                check_length(
+                    concat!(stringify!($alg), " ciphertext"),
+                    &ct_bytes,
+                    ciphertext_bytes(),
+                )?;
+
+                secret_bytes_with(py, shared_secret_bytes(), |ss_buf| {
+                    let rc = unsafe {
+                        ffi::$ffi_dec(ss_buf.as_mut_ptr(), ct_bytes.as_ptr(), sk_bytes.as_ptr())
+                    };
+                    if rc != 0 {
+                        return Err(DecapsulationError::new_err(concat!(
+                            stringify!($alg),
+                            " decapsulation failed"
+                        )));
+                    }
+                    Ok(())
+                })
+            }
+        }
+    };
 }
 
+mceliece_bindings!(
+    mceliece348864,
+    mceliece348864_keygen,
+    mceliece348864_encapsulate,
+    mceliece348864_decapsulate,
+    PQCLEAN_MCELIECE348864_CLEAN_crypto_kem_keypair,
+    PQCLEAN_MCELIECE348864_CLEAN_crypto_kem_enc,
+    PQCLEAN_MCELIECE348864_CLEAN_crypto_kem_dec
+);
+
+mceliece_bindings!(
+    mceliece460896,
+    mceliece460896_keygen,
+    mceliece460896_encapsulate,
+    mceliece460896_decapsulate,
+    PQCLEAN_MCELIECE460896_CLEAN_crypto_kem_keypair,
+    PQCLEAN_MCELIECE460896_CLEAN_crypto_kem_enc,
+    PQCLEAN_MCELIECE460896_CLEAN_crypto_kem_dec
+);
+
 // ───────────────────────────────────────────────────────────────────────────────
-// Falcon-512 helpers
+// Signature bindings
 // ───────────────────────────────────────────────────────────────────────────────
+//
+// Same idea for detached-signature schemes:
+//
+//   keygen()                 -> (public_key, secret_key)
+//   sign(sk, msg)            -> detached signature bytes
+//   verify(pk, msg, sig)     -> bool
+//   verify_strict(pk, msg, sig) -> None, or raises SignatureVerificationError
+//
+// Schemes with a fixed-length padded signature encoding (Falcon) can name the
+// padded module too; that adds sign_padded(sk, msg) and lets verify() accept
+// either encoding, since both share the same keys.
+//
+// ML-DSA names its RustCrypto parameter set as well, which gives sign() a
+// mode= argument: "hedged" (default, PQClean, fresh randomness per signature)
+// or "deterministic" (FIPS 204 deterministic variant, same signature for the
+// same key and message). Both verify with the same verify().
+//
+// sign / sign_padded / verify / verify_strict all take context=b"", a string
+// of up to 255 bytes naming the application or protocol, bound into what is
+// signed so that a signature made for one use of a key doesn't verify for
+// another. ML-DSA passes it as its FIPS 204 context; Falcon and SPHINCS+,
+// which have none, sign a labelled, length-prefixed context in front of the
+// message (see sign::context_message). The empty context leaves signatures
+// exactly as before.
 
-fn falcon_pk_from_bytes(bytes: &[u8]) -> PyResult<FalconPublicKey> {
-    <FalconPublicKey as sign_traits::PublicKey>::from_bytes(bytes)
-        .map_err(|e| PyValueError::new_err(e.to_string()))
+/// Check the s1 and s2 vectors of an ML-DSA secret key (FIPS 204 skEncode:
+/// rho || K || tr || s1 || s2 || t0). Each coefficient c in [-η, η] is
+/// stored as η - c, so a stored value above 2η can't come from a real key;
+/// ml-dsa's skDecode asserts on one. t0's 13-bit fields cover its whole
+/// range. `sk` must already have the right length.
+pub(crate) fn check_mldsa_secret_key(name: &str, sk: &[u8]) -> PyResult<()> {
+    // (l, k, η) per parameter set.
+    let (l, k, eta) = match name {
+        "mldsa44" => (4, 4, 2),
+        "mldsa65" => (5, 6, 4),
+        "mldsa87" => (7, 8, 2),
+        _ => unreachable!("not an ML-DSA parameter set: {name}"),
+    };
+    let bits = if eta == 2 { 3 } else { 4 };
+    let start = 32 + 32 + 64;
+    let packed = &sk[start..start + (l + k) * 256 * bits / 8];
+
+    // `bits` bytes hold eight coefficients, least significant bits first.
+    for chunk in packed.chunks_exact(bits) {
+        let mut word = [0u8; 4];
+        word[..bits].copy_from_slice(chunk);
+        let word = u32::from_le_bytes(word);
+        let mask = (1 << bits) - 1;
+        if (0..8).any(|i| (word >> (i * bits)) & mask > 2 * eta) {
+            return Err(crate::errors::InvalidKeyError::new_err(format!(
+                "malformed {name} secret key: s1/s2 coefficient out of range"
+            )));
+        }
+    }
+    Ok(())
 }
 
-fn falcon_sk_from_bytes(bytes: &[u8]) -> PyResult<FalconSecretKey> {
-    <FalconSecretKey as sign_traits::SecretKey>::from_bytes(bytes)
-        .map_err(|e| PyValueError::new_err(e.to_string()))
+macro_rules! sign_bindings {
+    (
+        $krate:ident,
+        $alg:ident,
+        $keygen:ident,
+        $sign:ident,
+        $verify:ident,
+        $verify_strict:ident
+        $(, padded = $padded:ident, $sign_padded:ident)?
+        $(, deterministic = $det:ident)?
+    ) => {
+        mod $alg {
+            use super::sign_traits;
+            use $krate::$alg::{DetachedSignature, PublicKey, SecretKey};
+            use pyo3::prelude::*;
+            use pyo3::types::PyBytes;
+
+            use crate::buffer::Buffer;
+            use crate::errors::{check_length, check_max_length, length_err};
+            use crate::secret::{secret_bytes, SecretBytes};
+
+            fn pk_from_bytes(bytes: &[u8]) -> PyResult<PublicKey> {
+                check_length(
+                    concat!(stringify!($alg), " public key"),
+                    bytes,
+                    $krate::$alg::public_key_bytes(),
+                )?;
+                <PublicKey as sign_traits::PublicKey>::from_bytes(bytes)
+                    .map_err(length_err)
+            }
+
+            fn sk_from_bytes(bytes: &[u8]) -> PyResult<SecretKey> {
+                check_length(
+                    concat!(stringify!($alg), " secret key"),
+                    bytes,
+                    $krate::$alg::secret_key_bytes(),
+                )?;
+                <SecretKey as sign_traits::SecretKey>::from_bytes(bytes)
+                    .map_err(length_err)
+            }
+
+            // An upper bound only: Falcon signatures vary in length.
+            fn sig_from_bytes(bytes: &[u8]) -> PyResult<DetachedSignature> {
+                check_max_length(
+                    concat!(stringify!($alg), " signature"),
+                    bytes,
+                    $krate::$alg::signature_bytes(),
+                )?;
+                <DetachedSignature as sign_traits::DetachedSignature>::from_bytes(bytes)
+                    .map_err(length_err)
+            }
+
+            pub(crate) const ALGORITHM: crate::sign::SignatureAlgorithm =
+                crate::sign::SignatureAlgorithm {
+                    name: stringify!($alg),
+                    public_key_bytes: $krate::$alg::public_key_bytes(),
+                    secret_key_bytes: $krate::$alg::secret_key_bytes(),
+                    signature_bytes: $krate::$alg::signature_bytes(),
+                    keygen: $keygen,
+                    sign: sign_bindings!(@sign_fn $sign $(, $det)?),
+                    verify: |pk_bytes, msg, sig_bytes| {
+                        $verify(pk_bytes, msg, sig_bytes, Buffer::empty())
+                    },
+                };
+
+            // ─── keygen ───────────────────────────────────────────────────
+
+            #[pyfunction]
+            pub fn $keygen(py: Python) -> PyResult<(Py<PyBytes>, Py<SecretBytes>)> {
+                let (pk, sk) = $krate::$alg::keypair();
+
+                let pk_bytes = <PublicKey as sign_traits::PublicKey>::as_bytes(&pk);
+                let sk_bytes = <SecretKey as sign_traits::SecretKey>::as_bytes(&sk);
+
+                let keys = (
+                    PyBytes::new_bound(py, pk_bytes).unbind(),
+                    secret_bytes(py, sk_bytes)?,
+                );
+                crate::pct::checked_signature_keypair(py, &ALGORITHM, keys)
+            }
+
+            sign_bindings!(@sign $krate, $alg, $sign $(, $det)?);
+
+            $(
+            // ─── sign_padded(sk, msg) -> fixed-length signature bytes ─────
+
+            #[pyfunction]
+            #[pyo3(signature = (sk_bytes, msg, context = Buffer::empty()))]
+            pub fn $sign_padded(
+                py: Python,
+                sk_bytes: Buffer,
+                msg: Buffer,
+                context: Buffer,
+            ) -> PyResult<Py<PyBytes>> {
+                use $krate::$padded::{DetachedSignature, SecretKey};
+
+                check_length(
+                    concat!(stringify!($alg), " secret key"),
+                    &sk_bytes,
+                    $krate::$padded::secret_key_bytes(),
+                )?;
+                let sk = <SecretKey as sign_traits::SecretKey>::from_bytes(&sk_bytes)
+                    .map_err(length_err)?;
+                let msg = crate::sign::context_message(&context, &msg)?;
+                let sig = $krate::$padded::detached_sign(&msg, &sk);
+
+                let sig_bytes =
+                    <DetachedSignature as sign_traits::DetachedSignature>::as_bytes(&sig);
+
+                Ok(PyBytes::new_bound(py, sig_bytes).unbind())
+            }
+            )?
+
+            // ─── verify(pk, msg, sig) -> bool ─────────────────────────────
+
+            #[pyfunction]
+            #[pyo3(signature = (pk_bytes, msg, sig_bytes, context = Buffer::empty()))]
+            pub fn $verify(
+                pk_bytes: Buffer,
+                msg: Buffer,
+                sig_bytes: Buffer,
+                context: Buffer,
+            ) -> PyResult<bool> {
+                let pk = pk_from_bytes(&pk_bytes)?;
+
+                $(
+                // Exactly the padded length: try the padded encoding first.
+                if sig_bytes.len() == $krate::$padded::signature_bytes() {
+                    use $krate::$padded::{DetachedSignature, PublicKey};
+
+                    let padded_pk = <PublicKey as sign_traits::PublicKey>::from_bytes(&pk_bytes)
+                        .map_err(length_err)?;
+                    let padded_sig =
+                        <DetachedSignature as sign_traits::DetachedSignature>::from_bytes(
+                            &sig_bytes,
+                        )
+                        .map_err(length_err)?;
+
+                    let signed = crate::sign::context_message(&context, &msg)?;
+                    let result = $krate::$padded::verify_detached_signature(
+                        &padded_sig,
+                        &signed,
+                        &padded_pk,
+                    );
+                    if result.is_ok() {
+                        return Ok(true);
+                    }
+                }
+                )?
+
+                let sig = sig_from_bytes(&sig_bytes)?;
+
+                let result =
+                    sign_bindings!(@verify_detached $krate, $alg, sig, msg, context, pk $(, $det)?);
+                Ok(result.is_ok())
+            }
+
+            // ─── verify_strict(pk, msg, sig) -> None, or raises ───────────
+
+            #[pyfunction]
+            #[pyo3(signature = (pk_bytes, msg, sig_bytes, context = Buffer::empty()))]
+            pub fn $verify_strict(
+                pk_bytes: Buffer,
+                msg: Buffer,
+                sig_bytes: Buffer,
+                context: Buffer,
+            ) -> PyResult<()> {
+                let (msg_len, sig_len) = (msg.len(), sig_bytes.len());
+                if $verify(pk_bytes, msg, sig_bytes, context)? {
+                    Ok(())
+                } else {
+                    Err(crate::errors::verification_failed(ALGORITHM.name, msg_len, sig_len))
+                }
+            }
+        }
+    };
+
+    // ─── sign function for the generic Signature registry ─────────────────
+
+    (@sign_fn $sign:ident) => {
+        |py, sk_bytes, msg| $sign(py, sk_bytes, msg, Buffer::empty())
+    };
+    (@sign_fn $sign:ident, $det:ident) => {
+        |py, sk_bytes, msg| $sign(py, sk_bytes, msg, "hedged", Buffer::empty())
+    };
+
+    // ─── Detached verification under a context string ─────────────────────
+
+    (
+        @verify_detached $krate:ident,
+        $alg:ident,
+        $sig:ident,
+        $msg:ident,
+        $context:ident,
+        $pk:ident
+    ) => {
+        $krate::$alg::verify_detached_signature(
+            &$sig,
+            &crate::sign::context_message(&$context, &$msg)?,
+            &$pk,
+        )
+    };
+    (
+        @verify_detached $krate:ident,
+        $alg:ident,
+        $sig:ident,
+        $msg:ident,
+        $context:ident,
+        $pk:ident,
+        $det:ident
+    ) => {{
+        crate::sign::check_context(&$context)?;
+        $krate::$alg::verify_detached_signature_ctx(&$sig, &$msg, &$context, &$pk)
+    }};
+
+    // ─── sign(sk, msg) -> detached signature bytes ────────────────────────
+
+    (@sign $krate:ident, $alg:ident, $sign:ident) => {
+        #[pyfunction]
+        #[pyo3(signature = (sk_bytes, msg, context = Buffer::empty()))]
+        pub fn $sign(
+            py: Python,
+            sk_bytes: Buffer,
+            msg: Buffer,
+            context: Buffer,
+        ) -> PyResult<Py<PyBytes>> {
+            let sk = sk_from_bytes(&sk_bytes)?;
+            let msg = crate::sign::context_message(&context, &msg)?;
+            let sig = $krate::$alg::detached_sign(&msg, &sk);
+
+            let sig_bytes = <DetachedSignature as sign_traits::DetachedSignature>::as_bytes(&sig);
+
+            Ok(PyBytes::new_bound(py, sig_bytes).unbind())
+        }
+    };
+
+    // ─── sign(sk, msg, mode="hedged") -> detached signature bytes ─────────
+
+    (@sign $krate:ident, $alg:ident, $sign:ident, $det:ident) => {
+        // stub: mode: Literal["hedged", "deterministic"]
+        #[pyfunction]
+        #[pyo3(signature = (sk_bytes, msg, mode = "hedged", context = Buffer::empty()))]
+        pub fn $sign(
+            py: Python,
+            sk_bytes: Buffer,
+            msg: Buffer,
+            mode: &str,
+            context: Buffer,
+        ) -> PyResult<Py<PyBytes>> {
+            crate::sign::check_context(&context)?;
+            // Both modes check the key the same way; RustCrypto's decoder
+            // needs it and PQClean's would sign with a bad one regardless.
+            let sk = sk_from_bytes(&sk_bytes)?;
+            crate::check_mldsa_secret_key(stringify!($alg), &sk_bytes)?;
+            match mode {
+                "hedged" => {
+                    let sig = $krate::$alg::detached_sign_ctx(&msg, &context, &sk);
+
+                    let sig_bytes =
+                        <DetachedSignature as sign_traits::DetachedSignature>::as_bytes(&sig);
+
+                    Ok(PyBytes::new_bound(py, sig_bytes).unbind())
+                }
+                "deterministic" => {
+                    use crate::errors::{InvalidLengthError, SignatureError};
+                    use ml_dsa::{ExpandedSigningKey, ExpandedSigningKeyBytes};
+                    use zeroize::Zeroizing;
+
+                    // PQClean and RustCrypto share the FIPS 204 secret key
+                    // encoding, already checked above.
+                    let enc = Zeroizing::new(
+                        ExpandedSigningKeyBytes::<ml_dsa::$det>::try_from(&*sk_bytes)
+                            .map_err(|e| InvalidLengthError::new_err(e.to_string()))?,
+                    );
+                    // from_expanded is deprecated in favour of from_seed, but
+                    // PQClean keys carry no seed.
+                    #[allow(deprecated)]
+                    let sk = ExpandedSigningKey::<ml_dsa::$det>::from_expanded(&enc);
+
+                    let sig = sk
+                        .sign_deterministic(&msg, &context)
+                        .map_err(|e| SignatureError::new_err(e.to_string()))?;
+
+                    Ok(PyBytes::new_bound(py, &sig.encode()).unbind())
+                }
+                other => Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "unknown signing mode {other:?} (expected \"hedged\" or \"deterministic\")"
+                ))),
+            }
+        }
+    };
 }
 
-fn falcon_sig_from_bytes(bytes: &[u8]) -> PyResult<FalconDetachedSignature> {
-    <FalconDetachedSignature as sign_traits::DetachedSignature>::from_bytes(bytes)
-        .map_err(|e| PyValueError::new_err(e.to_string()))
+// Falcon-512 (NIST level 1) keeps the original unsuffixed names.
+sign_bindings!(
+    pqcrypto_falcon,
+    falcon512,
+    falcon_keygen,
+    falcon_sign,
+    falcon_verify,
+    falcon_verify_strict,
+    padded = falconpadded512,
+    falcon_sign_padded
+);
+
+// Falcon-1024 (NIST level 5), e.g. for firmware signing.
+sign_bindings!(
+    pqcrypto_falcon,
+    falcon1024,
+    falcon1024_keygen,
+    falcon1024_sign,
+    falcon1024_verify,
+    falcon1024_verify_strict,
+    padded = falconpadded1024,
+    falcon1024_sign_padded
+);
+
+// ML-DSA-44 (FIPS 204, formerly Dilithium2): integer-only, constant-time
+// friendly signing for hosts where Falcon's floating point is a problem.
+sign_bindings!(
+    pqcrypto_mldsa,
+    mldsa44,
+    mldsa44_keygen,
+    mldsa44_sign,
+    mldsa44_verify,
+    mldsa44_verify_strict,
+    deterministic = MlDsa44
+);
+
+// ML-DSA-65 (level 3) and ML-DSA-87 (level 5).
+sign_bindings!(
+    pqcrypto_mldsa,
+    mldsa65,
+    mldsa65_keygen,
+    mldsa65_sign,
+    mldsa65_verify,
+    mldsa65_verify_strict,
+    deterministic = MlDsa65
+);
+sign_bindings!(
+    pqcrypto_mldsa,
+    mldsa87,
+    mldsa87_keygen,
+    mldsa87_sign,
+    mldsa87_verify,
+    mldsa87_verify_strict,
+    deterministic = MlDsa87
+);
+
+// SPHINCS+-SHA2 "small" (-s, simple) parameter sets: hash-based, conservative
+// assumptions, compact signatures but slow signing.
+sign_bindings!(
+    pqcrypto_sphincsplus,
+    sphincssha2128ssimple,
+    sphincs_sha2_128s_keygen,
+    sphincs_sha2_128s_sign,
+    sphincs_sha2_128s_verify,
+    sphincs_sha2_128s_verify_strict
+);
+sign_bindings!(
+    pqcrypto_sphincsplus,
+    sphincssha2192ssimple,
+    sphincs_sha2_192s_keygen,
+    sphincs_sha2_192s_sign,
+    sphincs_sha2_192s_verify,
+    sphincs_sha2_192s_verify_strict
+);
+sign_bindings!(
+    pqcrypto_sphincsplus,
+    sphincssha2256ssimple,
+    sphincs_sha2_256s_keygen,
+    sphincs_sha2_256s_sign,
+    sphincs_sha2_256s_verify,
+    sphincs_sha2_256s_verify_strict
+);
+
+// SPHINCS+-SHAKE "fast" (-f, simple) parameter sets: much faster signing at
+// the cost of larger signatures, for services that sign often.
+sign_bindings!(
+    pqcrypto_sphincsplus,
+    sphincsshake128fsimple,
+    sphincs_shake_128f_keygen,
+    sphincs_shake_128f_sign,
+    sphincs_shake_128f_verify,
+    sphincs_shake_128f_verify_strict
+);
+sign_bindings!(
+    pqcrypto_sphincsplus,
+    sphincsshake192fsimple,
+    sphincs_shake_192f_keygen,
+    sphincs_shake_192f_sign,
+    sphincs_shake_192f_verify,
+    sphincs_shake_192f_verify_strict
+);
+sign_bindings!(
+    pqcrypto_sphincsplus,
+    sphincsshake256fsimple,
+    sphincs_shake_256f_keygen,
+    sphincs_shake_256f_sign,
+    sphincs_shake_256f_verify,
+    sphincs_shake_256f_verify_strict
+);
+
+// ─── Capability discovery ─────────────────────────────────────────────────────
+//
+// What this build actually contains, in the canonical names KEM() and
+// Signature() accept, so callers can negotiate algorithms instead of probing
+// for attributes.
+
+/// Canonical names of every KEM in this build.
+#[pyfunction]
+fn list_kems() -> Vec<&'static str> {
+    kem::KEMS.iter().map(|alg| alg.name).collect()
 }
 
-// ─── Falcon: keygen ───────────────────────────────────────────────────────────
+/// Canonical names of every (stateless) signature scheme in this build.
+#[pyfunction]
+fn list_signatures() -> Vec<&'static str> {
+    sign::SIGNATURES.iter().map(|alg| alg.name).collect()
+}
 
+/// Whether `name` is a KEM or signature scheme KEM() / Signature() accept.
 #[pyfunction]
-fn falcon_keygen(py: Python) -> PyResult<(Py<PyBytes>, Py<PyBytes>)> {
-    let (pk, sk) = falcon_keypair_impl();
+fn is_supported(name: &str) -> bool {
+    kem::find_kem(name).is_some() || sign::find_signature(name).is_some()
+}
 
-    let pk_bytes = <FalconPublicKey as sign_traits::PublicKey>::as_bytes(&pk);
-    let sk_bytes = <FalconSecretKey as sign_traits::SecretKey>::as_bytes(&sk);
+// ─── Sizes ────────────────────────────────────────────────────────────────────
+//
+// The same numbers as module constants (KYBER512_PUBLIC_KEY_BYTES,
+// FALCON512_MAX_SIGNATURE_BYTES, ...) and as sizes(name), so callers can
+// allocate and validate buffers without hard-coding lengths.
 
-    Ok((
-        PyBytes::new_bound(py, pk_bytes).unbind(),
-        PyBytes::new_bound(py, sk_bytes).unbind(),
-    ))
+fn kem_sizes(alg: &kem::KemAlgorithm) -> [(&'static str, usize); 4] {
+    [
+        ("public_key_bytes", alg.public_key_bytes),
+        ("secret_key_bytes", alg.secret_key_bytes),
+        ("ciphertext_bytes", alg.ciphertext_bytes),
+        ("shared_secret_bytes", alg.shared_secret_bytes),
+    ]
 }
 
-// ─── Falcon: sign(sk, msg) -> detached signature bytes ────────────────────────
+fn signature_sizes(alg: &sign::SignatureAlgorithm) -> [(&'static str, usize); 3] {
+    [
+        ("public_key_bytes", alg.public_key_bytes),
+        ("secret_key_bytes", alg.secret_key_bytes),
+        ("max_signature_bytes", alg.signature_bytes),
+    ]
+}
 
+/// Byte lengths for a KEM or signature scheme, as a dict.
+// stub: -> dict[str, int]
 #[pyfunction]
-fn falcon_sign(py: Python, sk_bytes: &[u8], msg: &[u8]) -> PyResult<Py<PyBytes>> {
-    let sk = falcon_sk_from_bytes(sk_bytes)?;
-    let sig = falcon_detached_sign_impl(msg, &sk);
-
-    let sig_bytes = <FalconDetachedSignature as sign_traits::DetachedSignature>::as_bytes(&sig);
+fn sizes<'py>(py: Python<'py>, name: &str) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new_bound(py);
+    if let Some(alg) = kem::find_kem(name) {
+        for (key, value) in kem_sizes(alg) {
+            dict.set_item(key, value)?;
+        }
+    } else if let Some(alg) = sign::find_signature(name) {
+        for (key, value) in signature_sizes(alg) {
+            dict.set_item(key, value)?;
+        }
+    } else {
+        return Err(PyValueError::new_err(format!(
+            "unsupported algorithm '{}'",
+            name
+        )));
+    }
+    Ok(dict)
+}
 
-    Ok(PyBytes::new_bound(py, sig_bytes).unbind())
+fn add_size_constants(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    for alg in kem::KEMS {
+        for (key, value) in kem_sizes(alg) {
+            m.add(&*format!("{}_{}", alg.name, key).to_uppercase(), value)?;
+        }
+    }
+    for alg in sign::SIGNATURES {
+        for (key, value) in signature_sizes(alg) {
+            m.add(&*format!("{}_{}", alg.name, key).to_uppercase(), value)?;
+        }
+    }
+    Ok(())
 }
 
-// ─── Falcon: verify(pk, msg, sig) -> bool ─────────────────────────────────────
+// ─── Fingerprints ─────────────────────────────────────────────────────────────
+//
+//   fp = fingerprint("mlkem768", pk)     # 32 bytes, store this to pin the key
+//   format_fingerprint(fp)               # "3F2A 9C01 ..." to compare by eye
+
+/// SHA-256 over a label, the algorithm's canonical name and `pk_bytes`.
+/// Aliases give the same fingerprint ("kyber-512" and "kyber512" match).
+#[pyfunction]
+fn fingerprint<'py>(
+    py: Python<'py>,
+    algorithm: &str,
+    pk_bytes: buffer::Buffer,
+) -> PyResult<Bound<'py, PyBytes>> {
+    let (name, pk_len) = if let Some(alg) = kem::find_kem(algorithm) {
+        (alg.name, alg.public_key_bytes)
+    } else if let Some(alg) = sign::find_signature(algorithm) {
+        (alg.name, alg.public_key_bytes)
+    } else {
+        return Err(PyValueError::new_err(format!(
+            "unsupported algorithm '{}'",
+            algorithm
+        )));
+    };
+    errors::check_length(&format!("{} public key", name), &pk_bytes, pk_len)?;
+    Ok(PyBytes::new_bound(
+        py,
+        &util::pinning_fingerprint(name, &pk_bytes),
+    ))
+}
 
+/// A fingerprint() digest as grouped uppercase hex.
 #[pyfunction]
-fn falcon_verify(pk_bytes: &[u8], msg: &[u8], sig_bytes: &[u8]) -> PyResult<bool> {
-    let pk = falcon_pk_from_bytes(pk_bytes)?;
-    let sig = falcon_sig_from_bytes(sig_bytes)?;
+fn format_fingerprint(fp: buffer::Buffer) -> PyResult<String> {
+    errors::check_length("fingerprint", &fp, 32)?;
+    Ok(util::format_fingerprint(&fp))
+}
+
+// ─── Submodules ───────────────────────────────────────────────────────────────
+//
+//   from pqcrypto_bindings import kem, sign, util
+//   pk, sk = kem.mlkem768.keygen()
+//   sig = sign.falcon512.sign(sk, msg)
+//   util.sizes("mlkem768")
+//
+// `kem` and `sign` hold a KEM / Signature instance per algorithm; `util` holds
+// the helpers that aren't tied to one algorithm. Everything also stays at the
+// top level under its old name, so existing imports keep working.
+
+const UTIL_NAMES: &[&str] = &[
+    "SecretBytes",
+    "SecretKey",
+    "load_secret_key",
+    "list_kems",
+    "list_signatures",
+    "is_supported",
+    "sizes",
+    "fingerprint",
+    "format_fingerprint",
+    "validate_public_key",
+    "set_fips_mode",
+    "fips_mode",
+    "set_pairwise_check",
+    "pairwise_check_enabled",
+];
+
+/// Attach `child` as `parent.<name>` and make `import parent.<name>` work.
+fn add_submodule(
+    py: Python,
+    parent: &Bound<'_, PyModule>,
+    name: &str,
+    fill: impl FnOnce(&Bound<'_, PyModule>) -> PyResult<()>,
+) -> PyResult<()> {
+    let child = PyModule::new_bound(py, name)?;
+    fill(&child)?;
+    parent.add_submodule(&child)?;
+    py.import_bound("sys")?
+        .getattr("modules")?
+        .set_item(format!("{}.{}", parent.name()?, name), &child)?;
+    Ok(())
+}
 
-    let result = falcon_verify_impl(&sig, msg, &pk);
-    Ok(result.is_ok())
+fn add_submodules(py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
+    add_submodule(py, m, "kem", kem::add_to_submodule)?;
+    add_submodule(py, m, "sign", sign::add_to_submodule)?;
+    add_submodule(py, m, "util", |util| {
+        for name in UTIL_NAMES {
+            util.add(*name, m.getattr(*name)?)?;
+        }
+        Ok(())
+    })
 }
 
 // ─── PyO3 Module Registration ─────────────────────────────────────────────────
 
 #[pymodule]
-fn pqcrypto_bindings(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
+fn pqcrypto_bindings(py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     // Kyber-512
-    m.add_function(wrap_pyfunction!(kyber_keygen, m)?)?;
-    m.add_function(wrap_pyfunction!(kyber_encapsulate, m)?)?;
-    m.add_function(wrap_pyfunction!(kyber_decapsulate, m)?)?;
+    m.add_function(wrap_pyfunction!(kyber512::kyber_keygen, m)?)?;
+    m.add_function(wrap_pyfunction!(kyber512::kyber_encapsulate, m)?)?;
+    m.add_function(wrap_pyfunction!(kyber512::kyber_decapsulate, m)?)?;
+
+    // Kyber-768
+    m.add_function(wrap_pyfunction!(kyber768::kyber768_keygen, m)?)?;
+    m.add_function(wrap_pyfunction!(kyber768::kyber768_encapsulate, m)?)?;
+    m.add_function(wrap_pyfunction!(kyber768::kyber768_decapsulate, m)?)?;
+
+    // Kyber-1024
+    m.add_function(wrap_pyfunction!(kyber1024::kyber1024_keygen, m)?)?;
+    m.add_function(wrap_pyfunction!(kyber1024::kyber1024_encapsulate, m)?)?;
+    m.add_function(wrap_pyfunction!(kyber1024::kyber1024_decapsulate, m)?)?;
+
+    // Kyber key objects
+    m.add_class::<kyber::KyberPublicKey>()?;
+    m.add_class::<kyber::KyberSecretKey>()?;
+    m.add_class::<kyber::KyberKeyPair>()?;
+
+    // ML-KEM-512 / 768 / 1024
+    m.add_function(wrap_pyfunction!(mlkem512::mlkem512_keygen, m)?)?;
+    m.add_function(wrap_pyfunction!(mlkem512::mlkem512_encapsulate, m)?)?;
+    m.add_function(wrap_pyfunction!(mlkem512::mlkem512_decapsulate, m)?)?;
+    m.add_function(wrap_pyfunction!(mlkem768::mlkem768_keygen, m)?)?;
+    m.add_function(wrap_pyfunction!(mlkem768::mlkem768_encapsulate, m)?)?;
+    m.add_function(wrap_pyfunction!(mlkem768::mlkem768_decapsulate, m)?)?;
+    m.add_function(wrap_pyfunction!(mlkem1024::mlkem1024_keygen, m)?)?;
+    m.add_function(wrap_pyfunction!(mlkem1024::mlkem1024_encapsulate, m)?)?;
+    m.add_function(wrap_pyfunction!(mlkem1024::mlkem1024_decapsulate, m)?)?;
+
+    // HQC-128 / 192 / 256
+    m.add_function(wrap_pyfunction!(hqc128::hqc128_keygen, m)?)?;
+    m.add_function(wrap_pyfunction!(hqc128::hqc128_encapsulate, m)?)?;
+    m.add_function(wrap_pyfunction!(hqc128::hqc128_decapsulate, m)?)?;
+    m.add_function(wrap_pyfunction!(hqc192::hqc192_keygen, m)?)?;
+    m.add_function(wrap_pyfunction!(hqc192::hqc192_encapsulate, m)?)?;
+    m.add_function(wrap_pyfunction!(hqc192::hqc192_decapsulate, m)?)?;
+    m.add_function(wrap_pyfunction!(hqc256::hqc256_keygen, m)?)?;
+    m.add_function(wrap_pyfunction!(hqc256::hqc256_encapsulate, m)?)?;
+    m.add_function(wrap_pyfunction!(hqc256::hqc256_decapsulate, m)?)?;
+
+    // FrodoKEM-640 / 976 / 1344 (AES and SHAKE)
+    m.add_function(wrap_pyfunction!(frodokem640aes::frodokem640aes_keygen, m)?)?;
+    m.add_function(wrap_pyfunction!(
+        frodokem640aes::frodokem640aes_encapsulate,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        frodokem640aes::frodokem640aes_decapsulate,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        frodokem640shake::frodokem640shake_keygen,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        frodokem640shake::frodokem640shake_encapsulate,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        frodokem640shake::frodokem640shake_decapsulate,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(frodokem976aes::frodokem976aes_keygen, m)?)?;
+    m.add_function(wrap_pyfunction!(
+        frodokem976aes::frodokem976aes_encapsulate,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        frodokem976aes::frodokem976aes_decapsulate,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        frodokem976shake::frodokem976shake_keygen,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        frodokem976shake::frodokem976shake_encapsulate,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        frodokem976shake::frodokem976shake_decapsulate,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        frodokem1344aes::frodokem1344aes_keygen,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        frodokem1344aes::frodokem1344aes_encapsulate,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        frodokem1344aes::frodokem1344aes_decapsulate,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        frodokem1344shake::frodokem1344shake_keygen,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        frodokem1344shake::frodokem1344shake_encapsulate,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        frodokem1344shake::frodokem1344shake_decapsulate,
+        m
+    )?)?;
+
+    // sntrup761
+    m.add_function(wrap_pyfunction!(sntrup761::sntrup761_keygen, m)?)?;
+    m.add_function(wrap_pyfunction!(sntrup761::sntrup761_encapsulate, m)?)?;
+    m.add_function(wrap_pyfunction!(sntrup761::sntrup761_decapsulate, m)?)?;
+
+    // Classic McEliece 348864 / 460896
+    m.add_function(wrap_pyfunction!(mceliece348864::mceliece348864_keygen, m)?)?;
+    m.add_function(wrap_pyfunction!(
+        mceliece348864::mceliece348864_encapsulate,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        mceliece348864::mceliece348864_decapsulate,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(mceliece460896::mceliece460896_keygen, m)?)?;
+    m.add_function(wrap_pyfunction!(
+        mceliece460896::mceliece460896_encapsulate,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        mceliece460896::mceliece460896_decapsulate,
+        m
+    )?)?;
 
     // Falcon-512
-    m.add_function(wrap_pyfunction!(falcon_keygen, m)?)?;
-    m.add_function(wrap_pyfunction!(falcon_sign, m)?)?;
-    m.add_function(wrap_pyfunction!(falcon_verify, m)?)?;
+    m.add_function(wrap_pyfunction!(falcon512::falcon_keygen, m)?)?;
+    m.add_function(wrap_pyfunction!(falcon512::falcon_sign, m)?)?;
+    m.add_function(wrap_pyfunction!(falcon512::falcon_verify, m)?)?;
+    m.add_function(wrap_pyfunction!(falcon512::falcon_verify_strict, m)?)?;
+    m.add_function(wrap_pyfunction!(falcon512::falcon_sign_padded, m)?)?;
+
+    // Falcon-1024
+    m.add_function(wrap_pyfunction!(falcon1024::falcon1024_keygen, m)?)?;
+    m.add_function(wrap_pyfunction!(falcon1024::falcon1024_sign, m)?)?;
+    m.add_function(wrap_pyfunction!(falcon1024::falcon1024_verify, m)?)?;
+    m.add_function(wrap_pyfunction!(falcon1024::falcon1024_verify_strict, m)?)?;
+    m.add_function(wrap_pyfunction!(falcon1024::falcon1024_sign_padded, m)?)?;
+
+    // Falcon signer / verifier objects
+    m.add_class::<falcon::FalconSigner>()?;
+    m.add_class::<falcon::FalconVerifier>()?;
+
+    // Prehash Falcon signatures, over digests or streamed data
+    m.add_function(wrap_pyfunction!(prehash::falcon_sign_prehashed, m)?)?;
+    m.add_function(wrap_pyfunction!(prehash::falcon_verify_prehashed, m)?)?;
+    m.add_function(wrap_pyfunction!(
+        prehash::falcon_verify_prehashed_strict,
+        m
+    )?)?;
+    m.add_class::<prehash::Signer>()?;
+    m.add_class::<prehash::Verifier>()?;
+
+    // PKCS#8 / SubjectPublicKeyInfo DER
+    m.add_function(wrap_pyfunction!(encoding::public_key_to_der, m)?)?;
+    m.add_function(wrap_pyfunction!(encoding::public_key_from_der, m)?)?;
+    m.add_function(wrap_pyfunction!(encoding::secret_key_to_der, m)?)?;
+    m.add_function(wrap_pyfunction!(encoding::secret_key_from_der, m)?)?;
+
+    // PEM for keys, ciphertexts and signatures
+    m.add_function(wrap_pyfunction!(encoding::public_key_to_pem, m)?)?;
+    m.add_function(wrap_pyfunction!(encoding::public_key_from_pem, m)?)?;
+    m.add_function(wrap_pyfunction!(encoding::secret_key_to_pem, m)?)?;
+    m.add_function(wrap_pyfunction!(encoding::secret_key_from_pem, m)?)?;
+    m.add_function(wrap_pyfunction!(encoding::ciphertext_to_pem, m)?)?;
+    m.add_function(wrap_pyfunction!(encoding::ciphertext_from_pem, m)?)?;
+    m.add_function(wrap_pyfunction!(encoding::signature_to_pem, m)?)?;
+    m.add_function(wrap_pyfunction!(encoding::signature_from_pem, m)?)?;
+
+    // JSON Web Keys
+    m.add_function(wrap_pyfunction!(jwk::public_key_to_jwk, m)?)?;
+    m.add_function(wrap_pyfunction!(jwk::secret_key_to_jwk, m)?)?;
+    m.add_function(wrap_pyfunction!(jwk::key_from_jwk, m)?)?;
+
+    // OpenSSH key files
+    m.add_function(wrap_pyfunction!(openssh::public_key_to_openssh, m)?)?;
+    m.add_function(wrap_pyfunction!(openssh::public_key_from_openssh, m)?)?;
+    m.add_function(wrap_pyfunction!(openssh::secret_key_to_openssh, m)?)?;
+    m.add_function(wrap_pyfunction!(openssh::secret_key_from_openssh, m)?)?;
+
+    // Single-shot public-key encryption
+    m.add_function(wrap_pyfunction!(seal::seal, m)?)?;
+    m.add_function(wrap_pyfunction!(seal::open_sealed, m)?)?;
+
+    // File encryption to a KEM public key
+    m.add_function(wrap_pyfunction!(filecrypt::encrypt_file, m)?)?;
+    m.add_function(wrap_pyfunction!(filecrypt::decrypt_file, m)?)?;
+
+    // Signcryption: Falcon-signed, Kyber-sealed messages
+    m.add_function(wrap_pyfunction!(signcrypt::sign_and_seal, m)?)?;
+    m.add_function(wrap_pyfunction!(signcrypt::open_and_verify, m)?)?;
+
+    // Attached signatures: message, signature and algorithm in one blob
+    m.add_function(wrap_pyfunction!(attached::sign_attached, m)?)?;
+    m.add_function(wrap_pyfunction!(attached::open_signed, m)?)?;
+
+    // Timestamped signatures with expiry and freshness checks
+    m.add_function(wrap_pyfunction!(timestamped::sign_timestamped, m)?)?;
+    m.add_function(wrap_pyfunction!(timestamped::verify_timestamped, m)?)?;
+
+    // Merkle-tree batch signing of digest sets
+    m.add_function(wrap_pyfunction!(manifest::sign_manifest, m)?)?;
+    m.add_function(wrap_pyfunction!(manifest::verify_item, m)?)?;
+
+    // Multi-signatures with k-of-n verification
+    m.add_class::<multisig::MultiSignature>()?;
+
+    // Detached signature files with signer metadata
+    m.add_class::<sigfile::SignatureFile>()?;
+    m.add_function(wrap_pyfunction!(sigfile::sign_file, m)?)?;
+    m.add_function(wrap_pyfunction!(sigfile::verify_file, m)?)?;
+
+    // Authenticated key exchange: Falcon-signed ephemeral KEM
+    m.add_class::<ake::AkeInitiator>()?;
+    m.add_class::<ake::AkeResponder>()?;
+
+    // SecureChannel: encrypted transport over AKE session keys
+    m.add_class::<channel::SecureChannel>()?;
+
+    // Noise-style NK / IK / XX handshakes over KEMs
+    m.add_class::<noise::NoiseHandshake>()?;
+
+    // KEMTLS-style handshakes authenticated by certified KEM keys
+    m.add_class::<kemtls::KemTlsHandshake>()?;
+    m.add_function(wrap_pyfunction!(kemtls::issue_kem_certificate, m)?)?;
+    m.add_function(wrap_pyfunction!(kemtls::read_kem_certificate, m)?)?;
+
+    // PQXDH-style asynchronous key agreement with signed KEM prekeys
+    m.add_function(wrap_pyfunction!(pqxdh::pqxdh_signed_prekey, m)?)?;
+    m.add_function(wrap_pyfunction!(pqxdh::pqxdh_bundle, m)?)?;
+    m.add_function(wrap_pyfunction!(pqxdh::pqxdh_initiate, m)?)?;
+    m.add_function(wrap_pyfunction!(pqxdh::pqxdh_respond, m)?)?;
+
+    // SecureChannel session resumption tickets
+    m.add_class::<resumption::ResumptionTicket>()?;
+    m.add_class::<resumption::ResumeInitiator>()?;
+    m.add_class::<resumption::ResumeResponder>()?;
+
+    // KeySchedule: transcript hash and labelled key derivation
+    m.add_class::<keyschedule::KeySchedule>()?;
+
+    // Password-authenticated key exchange over ML-KEM
+    m.add_class::<pake::PakeInitiator>()?;
+    m.add_class::<pake::PakeResponder>()?;
+    m.add_function(wrap_pyfunction!(pake::pake_register, m)?)?;
+    m.add_function(wrap_pyfunction!(pake::pake_params, m)?)?;
+
+    // Signed tokens in the style of PASETO
+    m.add_function(wrap_pyfunction!(token::token_sign, m)?)?;
+    m.add_function(wrap_pyfunction!(token::token_verify, m)?)?;
+    m.add_function(wrap_pyfunction!(token::token_footer, m)?)?;
+
+    // JWS compact serialization with Falcon and ML-DSA
+    m.add_function(wrap_pyfunction!(jws::jws_sign, m)?)?;
+    m.add_function(wrap_pyfunction!(jws::jws_verify, m)?)?;
+    m.add_function(wrap_pyfunction!(jws::jws_header, m)?)?;
+
+    // JWE with ML-KEM key management
+    m.add_function(wrap_pyfunction!(jwe::jwe_encrypt, m)?)?;
+    m.add_function(wrap_pyfunction!(jwe::jwe_encrypt_general, m)?)?;
+    m.add_function(wrap_pyfunction!(jwe::jwe_decrypt, m)?)?;
+
+    // CBOR and COSE with ML-DSA and ML-KEM
+    m.add_function(wrap_pyfunction!(cbor::cbor_encode, m)?)?;
+    m.add_function(wrap_pyfunction!(cbor::cbor_decode, m)?)?;
+    m.add_function(wrap_pyfunction!(cose::cose_sign1, m)?)?;
+    m.add_function(wrap_pyfunction!(cose::cose_verify1, m)?)?;
+    m.add_function(wrap_pyfunction!(cose::cose_headers, m)?)?;
+    m.add_function(wrap_pyfunction!(cose::cose_encrypt, m)?)?;
+    m.add_function(wrap_pyfunction!(cose::cose_decrypt, m)?)?;
+
+    // X.509 certificates with Falcon and ML-DSA
+    m.add_function(wrap_pyfunction!(x509::generate_self_signed_cert, m)?)?;
+    m.add_function(wrap_pyfunction!(x509::generate_csr, m)?)?;
+    m.add_function(wrap_pyfunction!(x509::generate_cert, m)?)?;
+    m.add_function(wrap_pyfunction!(x509::verify_cert_chain, m)?)?;
+    m.add_function(wrap_pyfunction!(x509::certificate_to_pem, m)?)?;
+    m.add_function(wrap_pyfunction!(x509::certificate_from_pem, m)?)?;
+    m.add_function(wrap_pyfunction!(x509::csr_to_pem, m)?)?;
+    m.add_function(wrap_pyfunction!(x509::csr_from_pem, m)?)?;
+
+    // CMS SignedData with Falcon and ML-DSA
+    m.add_function(wrap_pyfunction!(cms::cms_sign, m)?)?;
+    m.add_function(wrap_pyfunction!(cms::cms_verify, m)?)?;
+
+    // DSSE envelopes with Falcon and ML-DSA
+    m.add_function(wrap_pyfunction!(dsse::dsse_sign, m)?)?;
+    m.add_function(wrap_pyfunction!(dsse::dsse_add_signature, m)?)?;
+    m.add_function(wrap_pyfunction!(dsse::dsse_verify, m)?)?;
+
+    // minisign-style release signing with Falcon
+    m.add_function(wrap_pyfunction!(minisign::minisign_keygen, m)?)?;
+    m.add_function(wrap_pyfunction!(minisign::minisign_sign, m)?)?;
+    m.add_function(wrap_pyfunction!(minisign::minisign_verify, m)?)?;
+    m.add_function(wrap_pyfunction!(minisign::minisign_sign_file, m)?)?;
+    m.add_function(wrap_pyfunction!(minisign::minisign_verify_file, m)?)?;
+
+    // TUF-style root and targets metadata
+    m.add_function(wrap_pyfunction!(tuf::tuf_root, m)?)?;
+    m.add_function(wrap_pyfunction!(tuf::tuf_targets, m)?)?;
+    m.add_function(wrap_pyfunction!(tuf::tuf_sign, m)?)?;
+    m.add_function(wrap_pyfunction!(tuf::tuf_verify, m)?)?;
+    m.add_function(wrap_pyfunction!(tuf::tuf_verify_target, m)?)?;
+
+    // age v1 files to PQ KEM recipients
+    m.add_function(wrap_pyfunction!(age::age_encrypt, m)?)?;
+    m.add_function(wrap_pyfunction!(age::age_decrypt, m)?)?;
+
+    // HPKE (RFC 9180) over ML-KEM, X-Wing and DHKEM(X25519)
+    m.add_class::<hpke::HpkeSuite>()?;
+    m.add_class::<hpke::HpkeContext>()?;
+    m.add_class::<hpke::HpkeSender>()?;
+    m.add_class::<hpke::HpkeReceiver>()?;
+    m.add_function(wrap_pyfunction!(hpke::hpke_seal, m)?)?;
+    m.add_function(wrap_pyfunction!(hpke::hpke_open, m)?)?;
+
+    // AEAD primitives
+    m.add_function(wrap_pyfunction!(aead::chacha20poly1305_encrypt, m)?)?;
+    m.add_function(wrap_pyfunction!(aead::chacha20poly1305_decrypt, m)?)?;
+    m.add_function(wrap_pyfunction!(aead::aes256gcm_encrypt, m)?)?;
+    m.add_function(wrap_pyfunction!(aead::aes256gcm_decrypt, m)?)?;
+    m.add_function(wrap_pyfunction!(aead::xchacha20poly1305_encrypt, m)?)?;
+    m.add_function(wrap_pyfunction!(aead::xchacha20poly1305_decrypt, m)?)?;
+    m.add_function(wrap_pyfunction!(aead::xchacha20poly1305_seal, m)?)?;
+    m.add_function(wrap_pyfunction!(aead::xchacha20poly1305_open, m)?)?;
+
+    // Counter-based AEAD nonces
+    m.add_class::<nonce::NonceSequence>()?;
+
+    // Chunked streaming AEAD
+    m.add_class::<stream::StreamEncryptor>()?;
+    m.add_class::<stream::StreamDecryptor>()?;
+
+    // HKDF key derivation
+    m.add_function(wrap_pyfunction!(kdf::hkdf_sha256, m)?)?;
+    m.add_function(wrap_pyfunction!(kdf::hkdf_sha512, m)?)?;
+    m.add_function(wrap_pyfunction!(kdf::hkdf_extract, m)?)?;
+    m.add_function(wrap_pyfunction!(kdf::hkdf_expand, m)?)?;
+
+    // SHAKE128 / SHAKE256 XOFs
+    m.add_function(wrap_pyfunction!(xof::shake128, m)?)?;
+    m.add_function(wrap_pyfunction!(xof::shake256, m)?)?;
+    m.add_class::<xof::Shake>()?;
+
+    // Password-protected secret key export
+    m.add_function(wrap_pyfunction!(keywrap::export_encrypted, m)?)?;
+    m.add_function(wrap_pyfunction!(keywrap::import_encrypted, m)?)?;
+
+    // Password-based encryption of arbitrary data
+    m.add_function(wrap_pyfunction!(keywrap::encrypt_with_password, m)?)?;
+    m.add_function(wrap_pyfunction!(keywrap::decrypt_with_password, m)?)?;
+
+    // Public key recovery from a secret key
+    m.add_function(wrap_pyfunction!(recover::kyber_public_from_secret, m)?)?;
+    m.add_function(wrap_pyfunction!(recover::falcon_public_from_secret, m)?)?;
+
+    // Key metadata: creation time, expiry and usage
+    m.add_class::<metadata::KeyMetadata>()?;
+    m.add_function(wrap_pyfunction!(metadata::attach_metadata, m)?)?;
+    m.add_function(wrap_pyfunction!(metadata::detach_metadata, m)?)?;
+
+    // Shamir secret sharing
+    m.add_function(wrap_pyfunction!(shamir::split_secret, m)?)?;
+    m.add_function(wrap_pyfunction!(shamir::combine_shares, m)?)?;
+
+    // Named keys in one password-protected file
+    m.add_class::<keystore::Keystore>()?;
+
+    // Secret keys in the platform keychain
+    m.add_function(wrap_pyfunction!(oskeyring::store_in_keyring, m)?)?;
+    m.add_function(wrap_pyfunction!(oskeyring::load_from_keyring, m)?)?;
+    m.add_function(wrap_pyfunction!(oskeyring::delete_from_keyring, m)?)?;
+
+    // Signing with keys held on a PKCS#11 token
+    m.add_class::<hsm::HsmSigner>()?;
+
+    // Secret keys sealed to the TPM and boot state
+    m.add_function(wrap_pyfunction!(tpm::seal_to_tpm, m)?)?;
+    m.add_function(wrap_pyfunction!(tpm::unseal_from_tpm, m)?)?;
+
+    // Deterministic Kyber / Falcon keygen from a seed
+    m.add_function(wrap_pyfunction!(seeded::kyber_keygen_from_seed, m)?)?;
+    m.add_function(wrap_pyfunction!(seeded::falcon_keygen_from_seed, m)?)?;
+
+    // ML-DSA-44 / 65 / 87
+    m.add_function(wrap_pyfunction!(mldsa44::mldsa44_keygen, m)?)?;
+    m.add_function(wrap_pyfunction!(mldsa44::mldsa44_sign, m)?)?;
+    m.add_function(wrap_pyfunction!(mldsa44::mldsa44_verify, m)?)?;
+    m.add_function(wrap_pyfunction!(mldsa44::mldsa44_verify_strict, m)?)?;
+    m.add_function(wrap_pyfunction!(mldsa65::mldsa65_keygen, m)?)?;
+    m.add_function(wrap_pyfunction!(mldsa65::mldsa65_sign, m)?)?;
+    m.add_function(wrap_pyfunction!(mldsa65::mldsa65_verify, m)?)?;
+    m.add_function(wrap_pyfunction!(mldsa65::mldsa65_verify_strict, m)?)?;
+    m.add_function(wrap_pyfunction!(mldsa87::mldsa87_keygen, m)?)?;
+    m.add_function(wrap_pyfunction!(mldsa87::mldsa87_sign, m)?)?;
+    m.add_function(wrap_pyfunction!(mldsa87::mldsa87_verify, m)?)?;
+    m.add_function(wrap_pyfunction!(mldsa87::mldsa87_verify_strict, m)?)?;
+
+    // SPHINCS+-SHA2-128s / 192s / 256s
+    m.add_function(wrap_pyfunction!(
+        sphincssha2128ssimple::sphincs_sha2_128s_keygen,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        sphincssha2128ssimple::sphincs_sha2_128s_sign,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        sphincssha2128ssimple::sphincs_sha2_128s_verify,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        sphincssha2128ssimple::sphincs_sha2_128s_verify_strict,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        sphincssha2192ssimple::sphincs_sha2_192s_keygen,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        sphincssha2192ssimple::sphincs_sha2_192s_sign,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        sphincssha2192ssimple::sphincs_sha2_192s_verify,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        sphincssha2192ssimple::sphincs_sha2_192s_verify_strict,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        sphincssha2256ssimple::sphincs_sha2_256s_keygen,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        sphincssha2256ssimple::sphincs_sha2_256s_sign,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        sphincssha2256ssimple::sphincs_sha2_256s_verify,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        sphincssha2256ssimple::sphincs_sha2_256s_verify_strict,
+        m
+    )?)?;
+
+    // SPHINCS+-SHAKE-128f / 192f / 256f
+    m.add_function(wrap_pyfunction!(
+        sphincsshake128fsimple::sphincs_shake_128f_keygen,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        sphincsshake128fsimple::sphincs_shake_128f_sign,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        sphincsshake128fsimple::sphincs_shake_128f_verify,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        sphincsshake128fsimple::sphincs_shake_128f_verify_strict,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        sphincsshake192fsimple::sphincs_shake_192f_keygen,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        sphincsshake192fsimple::sphincs_shake_192f_sign,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        sphincsshake192fsimple::sphincs_shake_192f_verify,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        sphincsshake192fsimple::sphincs_shake_192f_verify_strict,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        sphincsshake256fsimple::sphincs_shake_256f_keygen,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        sphincsshake256fsimple::sphincs_shake_256f_sign,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        sphincsshake256fsimple::sphincs_shake_256f_verify,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        sphincsshake256fsimple::sphincs_shake_256f_verify_strict,
+        m
+    )?)?;
+
+    // XMSS (stateful)
+    m.add_class::<stateful::XmssState>()?;
+    m.add_function(wrap_pyfunction!(stateful::xmss_keygen, m)?)?;
+    m.add_function(wrap_pyfunction!(stateful::xmss_sign, m)?)?;
+    m.add_function(wrap_pyfunction!(stateful::xmss_verify, m)?)?;
+    m.add_function(wrap_pyfunction!(stateful::xmss_verify_strict, m)?)?;
+
+    // LMS / HSS (stateful)
+    m.add_class::<stateful::LmsState>()?;
+    m.add_function(wrap_pyfunction!(stateful::lms_keygen, m)?)?;
+    m.add_function(wrap_pyfunction!(stateful::lms_sign, m)?)?;
+    m.add_function(wrap_pyfunction!(stateful::lms_verify, m)?)?;
+    m.add_function(wrap_pyfunction!(stateful::lms_verify_strict, m)?)?;
+
+    // X25519 / Ed25519 (classical, for hybrids)
+    m.add_function(wrap_pyfunction!(classical::x25519_keygen, m)?)?;
+    m.add_function(wrap_pyfunction!(classical::x25519_dh, m)?)?;
+    m.add_function(wrap_pyfunction!(classical::ed25519_keygen, m)?)?;
+    m.add_function(wrap_pyfunction!(classical::ed25519_sign, m)?)?;
+    m.add_function(wrap_pyfunction!(classical::ed25519_verify, m)?)?;
+    m.add_function(wrap_pyfunction!(classical::ed25519_verify_strict, m)?)?;
+
+    // X-Wing (X25519 + ML-KEM-768 hybrid KEM)
+    m.add_function(wrap_pyfunction!(hybrid::xwing_keygen, m)?)?;
+    m.add_function(wrap_pyfunction!(hybrid::xwing_encapsulate, m)?)?;
+    m.add_function(wrap_pyfunction!(hybrid::xwing_decapsulate, m)?)?;
+
+    // Composite Ed25519 + Falcon-512 signatures
+    m.add_function(wrap_pyfunction!(hybrid::ed25519_falcon512_keygen, m)?)?;
+    m.add_function(wrap_pyfunction!(hybrid::ed25519_falcon512_sign, m)?)?;
+    m.add_function(wrap_pyfunction!(hybrid::ed25519_falcon512_verify, m)?)?;
+    m.add_function(wrap_pyfunction!(
+        hybrid::ed25519_falcon512_verify_strict,
+        m
+    )?)?;
+
+    // Composite ML-DSA + ECDSA P-256 signatures
+    m.add_function(wrap_pyfunction!(hybrid::mldsa44_p256_keygen, m)?)?;
+    m.add_function(wrap_pyfunction!(hybrid::mldsa44_p256_sign, m)?)?;
+    m.add_function(wrap_pyfunction!(hybrid::mldsa44_p256_verify, m)?)?;
+    m.add_function(wrap_pyfunction!(hybrid::mldsa44_p256_verify_strict, m)?)?;
+    m.add_function(wrap_pyfunction!(hybrid::mldsa65_p256_keygen, m)?)?;
+    m.add_function(wrap_pyfunction!(hybrid::mldsa65_p256_sign, m)?)?;
+    m.add_function(wrap_pyfunction!(hybrid::mldsa65_p256_verify, m)?)?;
+    m.add_function(wrap_pyfunction!(hybrid::mldsa65_p256_verify_strict, m)?)?;
+
+    // Generic hybrid KEM combiner
+    m.add_function(wrap_pyfunction!(hybrid::hybrid_combine, m)?)?;
+
+    // TLS 1.3 X25519MLKEM768 key-share codec
+    m.add("X25519MLKEM768_GROUP", tls::X25519MLKEM768_GROUP)?;
+    m.add_function(wrap_pyfunction!(
+        tls::x25519mlkem768_encode_client_share,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        tls::x25519mlkem768_decode_client_share,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        tls::x25519mlkem768_encode_server_share,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        tls::x25519mlkem768_decode_server_share,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(tls::x25519mlkem768_shared_secret, m)?)?;
+
+    // Generic KEM(name) dispatch
+    m.add_class::<kem::Kem>()?;
+
+    // Generic Signature(name) dispatch
+    m.add_class::<sign::Signature>()?;
+
+    // CryptoError and its subclasses
+    errors::add_to_module(m)?;
+
+    // Wipeable container for returned secrets
+    m.add_class::<secret::SecretBytes>()?;
+
+    // Secret key handles that wipe on close / `with` exit
+    m.add_class::<keyhandle::SecretKey>()?;
+    m.add_function(wrap_pyfunction!(keyhandle::load_secret_key, m)?)?;
+
+    // Key rotation bundles
+    m.add_class::<keybundle::KeyBundle>()?;
+
+    // Pairwise consistency test after keygen / FIPS mode
+    pct::init_from_env();
+    m.add_function(wrap_pyfunction!(pct::set_fips_mode, m)?)?;
+    m.add_function(wrap_pyfunction!(pct::fips_mode, m)?)?;
+    m.add_function(wrap_pyfunction!(pct::set_pairwise_check, m)?)?;
+    m.add_function(wrap_pyfunction!(pct::pairwise_check_enabled, m)?)?;
+
+    // Capability discovery
+    m.add_function(wrap_pyfunction!(list_kems, m)?)?;
+    m.add_function(wrap_pyfunction!(list_signatures, m)?)?;
+    m.add_function(wrap_pyfunction!(is_supported, m)?)?;
+
+    // Key / ciphertext / signature sizes
+    m.add_function(wrap_pyfunction!(sizes, m)?)?;
+    add_size_constants(m)?;
+
+    // Public key validation (FIPS 203 modulus check for ML-KEM / Kyber)
+    m.add_function(wrap_pyfunction!(validate::validate_public_key, m)?)?;
+
+    // Public key fingerprints
+    m.add_function(wrap_pyfunction!(fingerprint, m)?)?;
+    m.add_function(wrap_pyfunction!(format_fingerprint, m)?)?;
+
+    // kem / sign / util namespaces over the flat names above
+    add_submodules(py, m)?;
 
     Ok(())
 }
@@ -0,0 +1,179 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// Merkle-tree batch signing
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// For signing thousands of artifacts (a CI run, a release) with a single
+// Falcon signature:
+//
+//   sig, proofs = sign_manifest(sk, [sha256(a), sha256(b), ...])
+//   verify_item(pk, sha256(b), proofs[1], sig)        # -> bool
+//
+// Each artifact is then checked on its own, with its proof and the shared
+// signature, without the rest of the set.
+//
+// The tree is RFC 9162's (Certificate Transparency v2) over SHA-256: leaf
+// hash SHA-256(0x00 || digest), interior node SHA-256(0x01 || left || right),
+// split at the largest power of two below the leaf count. The 0x00 / 0x01
+// prefixes keep a leaf from passing as an interior node. Falcon signs
+//
+//   label || leaf count u64 || root
+//
+// and a proof is
+//
+//   leaf index u64 | leaf count u64 | audit path (32-byte hashes, leaf up)
+//
+// Digests may be any hash, but the verifier must use the same bytes the
+// signer was given. Falcon-512 / 1024 are picked by key length.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use sha2::{Digest, Sha256};
+
+use crate::buffer::Buffer;
+use crate::signcrypt::falcon_for;
+use crate::util::Reader;
+
+const LABEL: &[u8] = b"entropic-chaos manifest v1\0";
+const HASH_LEN: usize = 32;
+
+type Hash = [u8; HASH_LEN];
+
+fn leaf_hash(digest: &[u8]) -> Hash {
+    Sha256::new()
+        .chain_update([0x00])
+        .chain_update(digest)
+        .finalize()
+        .into()
+}
+
+fn node_hash(left: &Hash, right: &Hash) -> Hash {
+    Sha256::new()
+        .chain_update([0x01])
+        .chain_update(left)
+        .chain_update(right)
+        .finalize()
+        .into()
+}
+
+/// The root of `leaves`, appending each leaf's audit path to `paths` from
+/// the bottom up.
+fn build(leaves: &[Hash], paths: &mut [Vec<Hash>]) -> Hash {
+    if leaves.len() == 1 {
+        return leaves[0];
+    }
+    let k = leaves.len().next_power_of_two() / 2;
+    let (left_paths, right_paths) = paths.split_at_mut(k);
+    let left = build(&leaves[..k], left_paths);
+    let right = build(&leaves[k..], right_paths);
+    for path in left_paths {
+        path.push(right);
+    }
+    for path in right_paths {
+        path.push(left);
+    }
+    node_hash(&left, &right)
+}
+
+/// RFC 9162 section 2.1.3.2: the root implied by a leaf and its audit path,
+/// or None if the path can't belong to a tree of `size` leaves.
+fn root_from_path(index: u64, size: u64, leaf: Hash, path: &[Hash]) -> Option<Hash> {
+    if index >= size {
+        return None;
+    }
+    let (mut f, mut s) = (index, size - 1);
+    let mut r = leaf;
+    for p in path {
+        if s == 0 {
+            return None;
+        }
+        if f & 1 == 1 || f == s {
+            r = node_hash(p, &r);
+            while f & 1 == 0 && f != 0 {
+                f >>= 1;
+                s >>= 1;
+            }
+        } else {
+            r = node_hash(&r, p);
+        }
+        f >>= 1;
+        s >>= 1;
+    }
+    (s == 0).then_some(r)
+}
+
+fn signed_data(size: u64, root: &Hash) -> Vec<u8> {
+    [LABEL, &size.to_be_bytes()[..], root].concat()
+}
+
+/// Sign the Merkle root of `digests`. Returns (signature, proofs), with one
+/// proof per digest, in order.
+#[pyfunction]
+pub fn sign_manifest(
+    py: Python,
+    sk: Buffer,
+    digests: Vec<Buffer>,
+) -> PyResult<(Py<PyBytes>, Vec<Py<PyBytes>>)> {
+    let alg = falcon_for("secret key", sk.len(), |alg| alg.secret_key_bytes)?;
+    if digests.is_empty() {
+        return Err(PyValueError::new_err(
+            "sign_manifest needs at least one digest",
+        ));
+    }
+    let leaves: Vec<Hash> = digests.iter().map(|d| leaf_hash(d)).collect();
+    let mut paths = vec![Vec::new(); leaves.len()];
+    let root = build(&leaves, &mut paths);
+    let size = leaves.len() as u64;
+
+    let signed = PyBytes::new_bound(py, &signed_data(size, &root));
+    let sig = (alg.sign)(py, sk, signed.extract()?)?;
+
+    let proofs = paths
+        .iter()
+        .enumerate()
+        .map(|(index, path)| {
+            let mut proof = Vec::with_capacity(16 + path.len() * HASH_LEN);
+            proof.extend_from_slice(&(index as u64).to_be_bytes());
+            proof.extend_from_slice(&size.to_be_bytes());
+            for hash in path {
+                proof.extend_from_slice(hash);
+            }
+            PyBytes::new_bound(py, &proof).unbind()
+        })
+        .collect();
+    Ok((sig, proofs))
+}
+
+/// Whether `item_digest` is in the manifest `signature` signs, by its proof
+/// from sign_manifest(). Raises ValueError for a malformed proof.
+#[pyfunction]
+pub fn verify_item(
+    py: Python,
+    pk: Buffer,
+    item_digest: Buffer,
+    proof: Buffer,
+    signature: Buffer,
+) -> PyResult<bool> {
+    let alg = falcon_for("public key", pk.len(), |alg| alg.public_key_bytes)?;
+    let mut r = Reader::new("manifest proof", &proof);
+    let index = r.u64()?;
+    let size = r.u64()?;
+    let rest = r.rest();
+    if !rest.len().is_multiple_of(HASH_LEN) || rest.len() / HASH_LEN > 64 {
+        return Err(PyValueError::new_err(format!(
+            "manifest proof path of {} bytes isn't at most 64 {}-byte hashes",
+            rest.len(),
+            HASH_LEN
+        )));
+    }
+    let path: Vec<Hash> = rest
+        .chunks_exact(HASH_LEN)
+        .map(|h| h.try_into().unwrap())
+        .collect();
+
+    let Some(root) = root_from_path(index, size, leaf_hash(&item_digest), &path) else {
+        return Ok(false);
+    };
+    let signed = PyBytes::new_bound(py, &signed_data(size, &root));
+    (alg.verify)(pk, signed.extract()?, signature)
+}
@@ -0,0 +1,296 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// Key metadata: creation time, expiry and usage
+// ───────────────────────────────────────────────────────────────────────────────
+//
+//   meta = KeyMetadata(usage="sign", label="release 2026", not_after=1830297600)
+//   envelope = attach_metadata(sk_bytes, meta)
+//   sk, meta = detach_metadata(envelope, usage="sign")   # raises if expired
+//
+// Times are Unix seconds; created_at defaults to now. The envelope is plain
+// (wrap it with export_encrypted() for secret keys), integers big-endian:
+//
+//   magic "PQKM" | version 1 | metadata | key bytes
+//
+// where metadata is
+//
+//   created_at u64 | has not_after u8 [| not_after u64] | usage u8 (0 any,
+//   1 encrypt, 2 sign) | has label u8 [| label length u16 | label (UTF-8)]
+//
+// The Keystore stores the same metadata alongside each entry and checks it
+// in get().
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+use crate::buffer::Buffer;
+use crate::errors::KeyPolicyError;
+use crate::secret::{secret_bytes, SecretBytes};
+use crate::util::Reader;
+
+const MAGIC: &[u8; 4] = b"PQKM";
+const VERSION: u8 = 1;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum KeyUsage {
+    Encrypt = 1,
+    Sign = 2,
+}
+
+impl KeyUsage {
+    pub(crate) fn from_name(name: &str) -> PyResult<Self> {
+        match name {
+            "encrypt" => Ok(KeyUsage::Encrypt),
+            "sign" => Ok(KeyUsage::Sign),
+            _ => Err(PyValueError::new_err(format!(
+                "unsupported key usage '{}' (expected 'encrypt' or 'sign')",
+                name
+            ))),
+        }
+    }
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            KeyUsage::Encrypt => "encrypt",
+            KeyUsage::Sign => "sign",
+        }
+    }
+}
+
+pub(crate) fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+#[pyclass(module = "pqcrypto_bindings")]
+#[derive(Clone, PartialEq, Eq)]
+pub struct KeyMetadata {
+    created_at: u64,
+    not_after: Option<u64>,
+    usage: Option<KeyUsage>,
+    label: Option<String>,
+}
+
+impl KeyMetadata {
+    pub(crate) fn allowed_usage(&self) -> Option<KeyUsage> {
+        self.usage
+    }
+
+    pub(crate) fn encode_into(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.created_at.to_be_bytes());
+        match self.not_after {
+            Some(t) => {
+                out.push(1);
+                out.extend_from_slice(&t.to_be_bytes());
+            }
+            None => out.push(0),
+        }
+        out.push(self.usage.map_or(0, |u| u as u8));
+        match &self.label {
+            Some(label) => {
+                out.push(1);
+                out.extend_from_slice(&(label.len() as u16).to_be_bytes());
+                out.extend_from_slice(label.as_bytes());
+            }
+            None => out.push(0),
+        }
+    }
+
+    pub(crate) fn decode_from(r: &mut Reader) -> PyResult<Self> {
+        let created_at = r.u64()?;
+        let not_after = match r.u8()? {
+            0 => None,
+            _ => Some(r.u64()?),
+        };
+        let usage = match r.u8()? {
+            0 => None,
+            1 => Some(KeyUsage::Encrypt),
+            2 => Some(KeyUsage::Sign),
+            other => {
+                return Err(PyValueError::new_err(format!(
+                    "unknown key usage code {}",
+                    other
+                )))
+            }
+        };
+        let label = match r.u8()? {
+            0 => None,
+            _ => {
+                let len = r.u16()? as usize;
+                let label = std::str::from_utf8(r.take(len)?)
+                    .map_err(|_| PyValueError::new_err("key label is not UTF-8"))?;
+                Some(label.to_owned())
+            }
+        };
+        Ok(KeyMetadata {
+            created_at,
+            not_after,
+            usage,
+            label,
+        })
+    }
+
+    /// Raise KeyPolicyError if the key has expired at `now`, or is marked for
+    /// a usage other than `usage`.
+    pub(crate) fn enforce(&self, usage: Option<KeyUsage>, now: u64) -> PyResult<()> {
+        if let Some(not_after) = self.not_after {
+            if now > not_after {
+                return Err(KeyPolicyError::new_err(format!(
+                    "key{} expired at {} (now {})",
+                    self.label_suffix(),
+                    not_after,
+                    now
+                )));
+            }
+        }
+        if let (Some(allowed), Some(wanted)) = (self.usage, usage) {
+            if allowed != wanted {
+                return Err(KeyPolicyError::new_err(format!(
+                    "key{} is for '{}', not '{}'",
+                    self.label_suffix(),
+                    allowed.name(),
+                    wanted.name()
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn label_suffix(&self) -> String {
+        self.label
+            .as_ref()
+            .map_or_else(String::new, |label| format!(" '{}'", label))
+    }
+}
+
+#[pymethods]
+impl KeyMetadata {
+    // stub: usage: Literal["encrypt", "sign"] | None
+    #[new]
+    #[pyo3(signature = (usage = None, label = None, not_after = None, created_at = None))]
+    fn new(
+        usage: Option<&str>,
+        label: Option<String>,
+        not_after: Option<u64>,
+        created_at: Option<u64>,
+    ) -> PyResult<Self> {
+        if label.as_ref().is_some_and(|l| l.len() > u16::MAX as usize) {
+            return Err(PyValueError::new_err(format!(
+                "key label must be at most {} bytes",
+                u16::MAX
+            )));
+        }
+        Ok(KeyMetadata {
+            created_at: created_at.unwrap_or_else(now),
+            not_after,
+            usage: usage.map(KeyUsage::from_name).transpose()?,
+            label,
+        })
+    }
+
+    /// Unix seconds.
+    #[getter]
+    fn created_at(&self) -> u64 {
+        self.created_at
+    }
+
+    /// Unix seconds after which the key is expired, or None for no expiry.
+    #[getter]
+    fn not_after(&self) -> Option<u64> {
+        self.not_after
+    }
+
+    /// "encrypt", "sign", or None for unrestricted.
+    // stub: -> Literal["encrypt", "sign"] | None
+    #[getter]
+    fn usage(&self) -> Option<&'static str> {
+        self.usage.map(KeyUsage::name)
+    }
+
+    #[getter]
+    fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    /// Whether the key has expired at `now` (Unix seconds, default the
+    /// current time).
+    #[pyo3(signature = (now = None))]
+    fn is_expired(&self, now: Option<u64>) -> bool {
+        let now = now.unwrap_or_else(self::now);
+        self.not_after.is_some_and(|t| now > t)
+    }
+
+    /// Raise KeyPolicyError if the key has expired, or if `usage` is given
+    /// and the key is marked for the other one.
+    // stub: usage: Literal["encrypt", "sign"] | None
+    #[pyo3(signature = (usage = None, now = None))]
+    fn check(&self, usage: Option<&str>, now: Option<u64>) -> PyResult<()> {
+        let usage = usage.map(KeyUsage::from_name).transpose()?;
+        self.enforce(usage, now.unwrap_or_else(self::now))
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        self == other
+    }
+
+    fn __repr__(&self) -> String {
+        let opt = |v: Option<String>| v.unwrap_or_else(|| "None".to_owned());
+        format!(
+            "KeyMetadata(usage={}, label={}, not_after={}, created_at={})",
+            opt(self.usage.map(|u| format!("'{}'", u.name()))),
+            opt(self.label.as_ref().map(|l| format!("'{}'", l))),
+            opt(self.not_after.map(|t| t.to_string())),
+            self.created_at
+        )
+    }
+}
+
+/// Prefix `key_bytes` with `metadata`, for storing the two together.
+#[pyfunction]
+pub fn attach_metadata(
+    py: Python,
+    key_bytes: Buffer,
+    metadata: &KeyMetadata,
+) -> PyResult<Py<PyBytes>> {
+    let mut out = Vec::with_capacity(64 + key_bytes.len());
+    out.extend_from_slice(MAGIC);
+    out.push(VERSION);
+    metadata.encode_into(&mut out);
+    out.extend_from_slice(&key_bytes);
+    Ok(PyBytes::new_bound(py, &out).unbind())
+}
+
+/// Split an attach_metadata() envelope into (key, metadata). Unless
+/// `enforce` is off, raises KeyPolicyError if the key has expired or is
+/// marked for a usage other than `usage`. The key comes back as SecretBytes,
+/// as it may be a secret key.
+// stub: usage: Literal["encrypt", "sign"] | None
+#[pyfunction]
+#[pyo3(signature = (envelope, usage = None, enforce = true))]
+pub fn detach_metadata(
+    py: Python,
+    envelope: Buffer,
+    usage: Option<&str>,
+    enforce: bool,
+) -> PyResult<(Py<SecretBytes>, KeyMetadata)> {
+    let usage = usage.map(KeyUsage::from_name).transpose()?;
+    let mut r = Reader::new("key metadata envelope", &envelope);
+    if r.take(MAGIC.len()).ok() != Some(&MAGIC[..]) {
+        return Err(PyValueError::new_err("not a key metadata envelope"));
+    }
+    let version = r.u8()?;
+    if version != VERSION {
+        return Err(PyValueError::new_err(format!(
+            "unsupported key metadata version {}",
+            version
+        )));
+    }
+    let metadata = KeyMetadata::decode_from(&mut r)?;
+    if enforce {
+        metadata.enforce(usage, now())?;
+    }
+    Ok((secret_bytes(py, r.rest())?, metadata))
+}
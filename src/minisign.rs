@@ -0,0 +1,499 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// minisign-style release signing with Falcon
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// For release pipelines built around minisign / signify, with Falcon in place
+// of Ed25519:
+//
+//   public_key, secret_key = minisign_keygen(password="release passphrase")
+//   minisign_sign_file(secret_key, "app.tar.gz", password="release passphrase")
+//   trusted = minisign_verify_file(public_key, "app.tar.gz")
+//
+// Keys and signatures are minisign's text files: an "untrusted comment:"
+// line, then one base64 line (standard alphabet, padded):
+//
+//   public key   algorithm (2) | key id (8) | public key
+//   secret key   algorithm (2) | kdf (2) | key id (8) | key data
+//
+// where the algorithm is "F5" for Falcon-512 or "F1" for Falcon-1024 and the
+// key id is random, shown as hex the way minisign shows it. The key data is
+// key id | secret key | public key, either as is (kdf "\0\0", no password)
+// or sealed under the password (kdf "Ar") as export_encrypted() does it:
+// Argon2id, then AES-256-GCM with a key commitment, magic "PQMK".
+//
+// A .minisig signature file (next to the data file unless `sig_path` says
+// otherwise) is
+//
+//   untrusted comment: <text>
+//   base64(algorithm | key id | signature over SHA-512(data))
+//   trusted comment: <text>
+//   base64(signature over signature || trusted comment)
+//
+// as in minisign's hashed mode, with SHA-512 for BLAKE2b. The trusted comment
+// defaults to "timestamp:<now>" (plus the file name for files) and is what
+// verification returns; it can't be changed without the second signature
+// failing. Verification raises SignatureVerificationError if the signature's
+// algorithm or key id isn't the public key's or either signature doesn't
+// verify. Public keys are also taken as the bare base64 line. These files
+// don't interoperate with minisign itself, which only knows Ed25519.
+
+use std::path::{Path, PathBuf};
+
+use base64ct::{Base64, Encoding};
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use sha2::{Digest, Sha512};
+use zeroize::Zeroizing;
+
+use crate::buffer::Buffer;
+use crate::errors::{InvalidKeyError, SignatureVerificationError};
+use crate::keywrap::{
+    Password, SealingKey, DEFAULT_ITERATIONS, DEFAULT_MEMORY_KIB, DEFAULT_PARALLELISM,
+    MINISIGN_ENVELOPE,
+};
+use crate::metadata::now;
+use crate::secret::secret_bytes;
+use crate::sigfile::file_digest;
+use crate::sign::SignatureAlgorithm;
+use crate::util::write_atomic;
+
+const UNTRUSTED: &str = "untrusted comment: ";
+const TRUSTED: &str = "trusted comment: ";
+const SIGNATURE_COMMENT: &str = "signature from minisign secret key";
+
+const KEY_ID_LEN: usize = 8;
+const KDF_NONE: &[u8; 2] = b"\0\0";
+const KDF_ARGON2: &[u8; 2] = b"Ar";
+
+struct MinisignAlgorithm {
+    id: &'static [u8; 2],
+    alg: &'static SignatureAlgorithm,
+}
+
+const MINISIGN_ALGORITHMS: &[MinisignAlgorithm] = &[
+    MinisignAlgorithm {
+        id: b"F5",
+        alg: &crate::falcon512::ALGORITHM,
+    },
+    MinisignAlgorithm {
+        id: b"F1",
+        alg: &crate::falcon1024::ALGORITHM,
+    },
+];
+
+fn by_id(what: &str, id: &[u8]) -> PyResult<&'static MinisignAlgorithm> {
+    MINISIGN_ALGORITHMS
+        .iter()
+        .find(|a| a.id == id)
+        .ok_or_else(|| {
+            PyValueError::new_err(format!(
+                "{}: unsupported algorithm '{}'",
+                what,
+                String::from_utf8_lossy(id)
+            ))
+        })
+}
+
+fn by_name(name: &str) -> PyResult<&'static MinisignAlgorithm> {
+    let wanted = crate::kem::normalize_name(name);
+    MINISIGN_ALGORITHMS
+        .iter()
+        .find(|a| a.alg.name == wanted)
+        .ok_or_else(|| {
+            PyValueError::new_err(format!(
+                "no minisign algorithm for '{}' (expected falcon512 or falcon1024)",
+                name
+            ))
+        })
+}
+
+fn key_id_hex(key_id: &[u8]) -> String {
+    let key_id: [u8; KEY_ID_LEN] = key_id.try_into().expect("key ids are 8 bytes");
+    format!("{:016X}", u64::from_le_bytes(key_id))
+}
+
+fn check_comment(what: &str, comment: &str) -> PyResult<()> {
+    if comment.contains(['\n', '\r']) {
+        return Err(PyValueError::new_err(format!(
+            "{} can't contain line breaks",
+            what
+        )));
+    }
+    Ok(())
+}
+
+fn decode(what: &str, line: &str) -> PyResult<Vec<u8>> {
+    Base64::decode_vec(line.trim())
+        .map_err(|_| PyValueError::new_err(format!("malformed {}: not base64", what)))
+}
+
+/// The base64 line of a key file, after its optional untrusted comment.
+fn key_line<'a>(what: &str, text: &'a str) -> PyResult<&'a str> {
+    let mut lines = text.lines().map(str::trim).filter(|l| !l.is_empty());
+    let mut line = lines.next();
+    if line.is_some_and(|l| l.starts_with(UNTRUSTED)) {
+        line = lines.next();
+    }
+    match (line, lines.next()) {
+        (Some(line), None) => Ok(line),
+        _ => Err(PyValueError::new_err(format!(
+            "malformed {}: expected a comment line and a base64 line",
+            what
+        ))),
+    }
+}
+
+// ─── Keys ─────────────────────────────────────────────────────────────────────
+
+struct PublicKey {
+    algorithm: &'static MinisignAlgorithm,
+    key_id: Vec<u8>,
+    pk: Vec<u8>,
+}
+
+fn parse_public_key(text: &str) -> PyResult<PublicKey> {
+    const WHAT: &str = "minisign public key";
+    let data = decode(WHAT, key_line(WHAT, text)?)?;
+    if data.len() < 2 + KEY_ID_LEN {
+        return Err(PyValueError::new_err(format!(
+            "malformed {}: too short",
+            WHAT
+        )));
+    }
+    let algorithm = by_id(WHAT, &data[..2])?;
+    let pk = data[2 + KEY_ID_LEN..].to_vec();
+    if pk.len() != algorithm.alg.public_key_bytes {
+        return Err(PyValueError::new_err(format!(
+            "malformed {}: {} bytes of {} key",
+            WHAT,
+            pk.len(),
+            algorithm.alg.name
+        )));
+    }
+    Ok(PublicKey {
+        algorithm,
+        key_id: data[2..2 + KEY_ID_LEN].to_vec(),
+        pk,
+    })
+}
+
+struct SecretKey {
+    algorithm: &'static MinisignAlgorithm,
+    key_id: Vec<u8>,
+    /// key id | secret key | public key.
+    key_data: Zeroizing<Vec<u8>>,
+}
+
+impl SecretKey {
+    fn sk(&self) -> &[u8] {
+        &self.key_data[KEY_ID_LEN..KEY_ID_LEN + self.algorithm.alg.secret_key_bytes]
+    }
+
+    fn pk(&self) -> &[u8] {
+        &self.key_data[KEY_ID_LEN + self.algorithm.alg.secret_key_bytes..]
+    }
+}
+
+fn parse_secret_key(text: &str, password: Option<Password>) -> PyResult<SecretKey> {
+    const WHAT: &str = "minisign secret key";
+    let data = Zeroizing::new(decode(WHAT, key_line(WHAT, text)?)?);
+    if data.len() < 4 + KEY_ID_LEN {
+        return Err(PyValueError::new_err(format!(
+            "malformed {}: too short",
+            WHAT
+        )));
+    }
+    let algorithm = by_id(WHAT, &data[..2])?;
+    let key_id = data[4..4 + KEY_ID_LEN].to_vec();
+    let sealed = &data[4 + KEY_ID_LEN..];
+    let key_data = match (&data[2..4], password) {
+        (kdf, None) if kdf == KDF_NONE => Zeroizing::new(sealed.to_vec()),
+        (kdf, Some(password)) if kdf == KDF_ARGON2 => {
+            SealingKey::open(&MINISIGN_ENVELOPE, sealed, password.as_bytes())?.1
+        }
+        (kdf, Some(_)) if kdf == KDF_NONE => {
+            return Err(PyValueError::new_err(format!(
+                "{} isn't password-protected; pass no password",
+                WHAT
+            )))
+        }
+        (kdf, None) if kdf == KDF_ARGON2 => {
+            return Err(PyValueError::new_err(format!(
+                "{} is password-protected; pass its password",
+                WHAT
+            )))
+        }
+        (kdf, _) => {
+            return Err(PyValueError::new_err(format!(
+                "{}: unsupported kdf '{}'",
+                WHAT,
+                String::from_utf8_lossy(kdf)
+            )))
+        }
+    };
+    let alg = algorithm.alg;
+    if key_data.len() != KEY_ID_LEN + alg.secret_key_bytes + alg.public_key_bytes {
+        return Err(PyValueError::new_err(format!(
+            "malformed {}: wrong key length for {}",
+            WHAT, alg.name
+        )));
+    }
+    if key_data[..KEY_ID_LEN] != key_id[..] {
+        return Err(PyValueError::new_err(format!(
+            "malformed {}: the key ids don't match",
+            WHAT
+        )));
+    }
+    Ok(SecretKey {
+        algorithm,
+        key_id,
+        key_data,
+    })
+}
+
+// ─── Signatures ───────────────────────────────────────────────────────────────
+
+fn sign_digest(
+    py: Python,
+    key: &SecretKey,
+    digest: &[u8],
+    trusted_comment: &str,
+    untrusted_comment: &str,
+) -> PyResult<String> {
+    check_comment("trusted_comment", trusted_comment)?;
+    check_comment("untrusted_comment", untrusted_comment)?;
+    let alg = key.algorithm.alg;
+    let sk = secret_bytes(py, key.sk())?;
+    let sk = sk.bind(py);
+
+    let signature = (alg.sign)(py, sk.extract()?, PyBytes::new_bound(py, digest).extract()?)?;
+    let signature = signature.as_bytes(py);
+    let pk = PyBytes::new_bound(py, key.pk());
+    let valid = (alg.verify)(
+        pk.extract()?,
+        PyBytes::new_bound(py, digest).extract()?,
+        PyBytes::new_bound(py, signature).extract()?,
+    )?;
+    if !valid {
+        return Err(InvalidKeyError::new_err(
+            "minisign secret key: the public key doesn't match the secret key",
+        ));
+    }
+    let global = [signature, trusted_comment.as_bytes()].concat();
+    let global = (alg.sign)(
+        py,
+        sk.extract()?,
+        PyBytes::new_bound(py, &global).extract()?,
+    )?;
+
+    let blob = [&key.algorithm.id[..], &key.key_id, signature].concat();
+    Ok(format!(
+        "{}{}\n{}\n{}{}\n{}\n",
+        UNTRUSTED,
+        untrusted_comment,
+        Base64::encode_string(&blob),
+        TRUSTED,
+        trusted_comment,
+        Base64::encode_string(global.as_bytes(py))
+    ))
+}
+
+/// Check `signature` over `digest` against `public_key` and return its
+/// trusted comment.
+fn verify_digest(py: Python, public_key: &str, signature: &str, digest: &[u8]) -> PyResult<String> {
+    const WHAT: &str = "minisign signature";
+    let key = parse_public_key(public_key)?;
+    let lines: Vec<&str> = signature.lines().collect();
+    let (blob, trusted, global) = match lines[..] {
+        [untrusted, blob, trusted, global] | [untrusted, blob, trusted, global, ""]
+            if untrusted.starts_with(UNTRUSTED) && trusted.starts_with(TRUSTED) =>
+        {
+            (blob, &trusted[TRUSTED.len()..], global)
+        }
+        _ => {
+            return Err(PyValueError::new_err(format!(
+                "malformed {}: expected four lines, the first and third comments",
+                WHAT
+            )))
+        }
+    };
+    let blob = decode(WHAT, blob)?;
+    let global = decode(WHAT, global)?;
+    if blob.len() < 2 + KEY_ID_LEN {
+        return Err(PyValueError::new_err(format!(
+            "malformed {}: too short",
+            WHAT
+        )));
+    }
+    let algorithm = by_id(WHAT, &blob[..2])?;
+    let (key_id, sig) = blob[2..].split_at(KEY_ID_LEN);
+    if !std::ptr::eq(algorithm, key.algorithm) {
+        return Err(SignatureVerificationError::new_err(format!(
+            "signature is {}, but the public key is {}",
+            algorithm.alg.name, key.algorithm.alg.name
+        )));
+    }
+    if key_id != key.key_id {
+        return Err(SignatureVerificationError::new_err(format!(
+            "signature is by key {}, not by the public key {}",
+            key_id_hex(key_id),
+            key_id_hex(&key.key_id)
+        )));
+    }
+
+    let alg = algorithm.alg;
+    let pk = PyBytes::new_bound(py, &key.pk);
+    let check = |msg: &[u8], sig: &[u8]| -> PyResult<bool> {
+        (alg.verify)(
+            pk.extract()?,
+            PyBytes::new_bound(py, msg).extract()?,
+            PyBytes::new_bound(py, sig).extract()?,
+        )
+    };
+    if !check(digest, sig)? {
+        return Err(SignatureVerificationError::new_err(
+            "minisign signature doesn't verify",
+        ));
+    }
+    if !check(&[sig, trusted.as_bytes()].concat(), &global)? {
+        return Err(SignatureVerificationError::new_err(
+            "minisign trusted comment signature doesn't verify",
+        ));
+    }
+    Ok(trusted.to_string())
+}
+
+fn sig_path_for(data_path: &Path, sig_path: Option<PathBuf>) -> PathBuf {
+    sig_path.unwrap_or_else(|| {
+        let mut path = data_path.as_os_str().to_os_string();
+        path.push(".minisig");
+        path.into()
+    })
+}
+
+// ─── Python API ───────────────────────────────────────────────────────────────
+
+/// A new Falcon key pair as minisign-style (public key file, secret key
+/// file) text, the secret key sealed under `password` if one is given.
+// stub: algorithm: Literal["falcon512", "falcon1024"]
+#[pyfunction]
+#[pyo3(signature = (password = None, algorithm = "falcon512"))]
+pub fn minisign_keygen(
+    py: Python,
+    password: Option<Password>,
+    algorithm: &str,
+) -> PyResult<(String, String)> {
+    let algorithm = by_name(algorithm)?;
+    let (pk, sk) = (algorithm.alg.keygen)(py)?;
+    let sk: Buffer = sk.bind(py).extract()?;
+    let pk = pk.as_bytes(py);
+    let mut key_id = [0u8; KEY_ID_LEN];
+    getrandom::fill(&mut key_id).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+
+    let public_key = format!(
+        "{}minisign public key {}\n{}\n",
+        UNTRUSTED,
+        key_id_hex(&key_id),
+        Base64::encode_string(&[&algorithm.id[..], &key_id, pk].concat())
+    );
+    let key_data = Zeroizing::new([&key_id[..], &sk[..], pk].concat());
+    let (kdf, comment, sealed) = match password {
+        Some(password) => {
+            let key = SealingKey::derive(
+                &MINISIGN_ENVELOPE,
+                password.as_bytes(),
+                DEFAULT_MEMORY_KIB,
+                DEFAULT_ITERATIONS,
+                DEFAULT_PARALLELISM,
+            )?;
+            let sealed = Zeroizing::new(key.seal(&key_data)?);
+            (KDF_ARGON2, "minisign encrypted secret key", sealed)
+        }
+        None => (KDF_NONE, "minisign unencrypted secret key", key_data),
+    };
+    let blob = Zeroizing::new([&algorithm.id[..], kdf, &key_id, &sealed].concat());
+    let secret_key = format!(
+        "{}{}\n{}\n",
+        UNTRUSTED,
+        comment,
+        Base64::encode_string(&blob)
+    );
+    Ok((public_key, secret_key))
+}
+
+/// Sign `data` with a minisign_keygen() secret key. Returns the signature
+/// file's text.
+#[pyfunction]
+#[pyo3(signature = (secret_key, data, password = None, trusted_comment = None, untrusted_comment = SIGNATURE_COMMENT))]
+pub fn minisign_sign(
+    py: Python,
+    secret_key: &str,
+    data: Buffer,
+    password: Option<Password>,
+    trusted_comment: Option<String>,
+    untrusted_comment: &str,
+) -> PyResult<String> {
+    let key = parse_secret_key(secret_key, password)?;
+    let trusted_comment = trusted_comment.unwrap_or_else(|| format!("timestamp:{}", now()));
+    sign_digest(
+        py,
+        &key,
+        &Sha512::digest(&*data),
+        &trusted_comment,
+        untrusted_comment,
+    )
+}
+
+/// Check a minisign_sign() signature over `data` and return its trusted
+/// comment.
+#[pyfunction]
+pub fn minisign_verify(
+    py: Python,
+    public_key: &str,
+    data: Buffer,
+    signature: &str,
+) -> PyResult<String> {
+    verify_digest(py, public_key, signature, &Sha512::digest(&*data))
+}
+
+/// Sign the file at `data_path` and write the signature file to `sig_path`
+/// (default `data_path` + ".minisig"). Returns the signature file's text.
+#[pyfunction]
+#[pyo3(signature = (secret_key, data_path, password = None, trusted_comment = None, untrusted_comment = SIGNATURE_COMMENT, sig_path = None))]
+pub fn minisign_sign_file(
+    py: Python,
+    secret_key: &str,
+    data_path: PathBuf,
+    password: Option<Password>,
+    trusted_comment: Option<String>,
+    untrusted_comment: &str,
+    sig_path: Option<PathBuf>,
+) -> PyResult<String> {
+    let key = parse_secret_key(secret_key, password)?;
+    let trusted_comment = trusted_comment.unwrap_or_else(|| {
+        let file_name = data_path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        format!("timestamp:{}\tfile:{}\thashed", now(), file_name)
+    });
+    let digest = file_digest(&data_path)?;
+    let signature = sign_digest(py, &key, &digest, &trusted_comment, untrusted_comment)?;
+    write_atomic(&sig_path_for(&data_path, sig_path), signature.as_bytes())?;
+    Ok(signature)
+}
+
+/// Check the signature file at `sig_path` (default `data_path` +
+/// ".minisig") over the file at `data_path` and return its trusted comment.
+#[pyfunction]
+#[pyo3(signature = (public_key, data_path, sig_path = None))]
+pub fn minisign_verify_file(
+    py: Python,
+    public_key: &str,
+    data_path: PathBuf,
+    sig_path: Option<PathBuf>,
+) -> PyResult<String> {
+    let signature = std::fs::read_to_string(sig_path_for(&data_path, sig_path))?;
+    verify_digest(py, public_key, &signature, &file_digest(&data_path)?)
+}
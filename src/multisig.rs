@@ -0,0 +1,329 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// Multi-signatures with k-of-n verification
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// For releases that need several approvers. Each approver adds a signature
+// to the same container, in any order and on their own machine:
+//
+//   ms = MultiSignature(release)            # or MultiSignature.from_bytes(blob)
+//   ms.add(alice_sk, alice_pk)
+//   blob = ms.to_bytes()                    # pass on to the next approver
+//
+//   MultiSignature.from_bytes(blob).verify_strict(release, [alice_pk, bob_pk,
+//                                                           carol_pk], 2)
+//
+// Separately collected containers for the same message combine with merge().
+// Every signature is over
+//
+//   label || SHA-256(message)
+//
+// so the container carries the message digest rather than the message, and
+// later approvers sign without it. Signers are identified by key ID, the
+// first 8 bytes of fingerprint(algorithm, pk) as in KeyBundle. Each signer
+// picks their own scheme (Falcon-512 / 1024 by key length unless
+// `algorithm` names another), and the layout is
+//
+//   magic "PQMS" | version 1 | SHA-256(message) | count u16
+//     | count x (key ID | name length u8 | algorithm name
+//                | signature length u32 | signature)
+//
+// verify() counts the distinct public keys in `public_keys` with a valid
+// signature in the container; signatures from keys not in the list, and
+// ones that don't verify, are ignored. valid_signers() says which counted.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use sha2::{Digest, Sha256};
+
+use crate::attached::algorithm_named;
+use crate::buffer::Buffer;
+use crate::errors::{
+    check_length, InvalidKeyError, InvalidLengthError, SignatureVerificationError,
+};
+use crate::sign::SignatureAlgorithm;
+use crate::signcrypt::falcon_for;
+use crate::util::{pinning_fingerprint, Reader};
+
+const MAGIC: &[u8; 4] = b"PQMS";
+const VERSION: u8 = 1;
+const LABEL: &[u8] = b"entropic-chaos multisignature v1\0";
+const KEY_ID_LEN: usize = 8;
+
+type KeyId = [u8; KEY_ID_LEN];
+
+pub(crate) fn key_id(alg: &SignatureAlgorithm, pk: &[u8]) -> KeyId {
+    pinning_fingerprint(alg.name, pk)[..KEY_ID_LEN]
+        .try_into()
+        .unwrap()
+}
+
+struct Entry {
+    id: KeyId,
+    alg: &'static SignatureAlgorithm,
+    sig: Vec<u8>,
+}
+
+#[pyclass(module = "pqcrypto_bindings")]
+pub struct MultiSignature {
+    digest: [u8; 32],
+    entries: Vec<Entry>,
+}
+
+impl MultiSignature {
+    fn signed_data(&self) -> Vec<u8> {
+        [LABEL, &self.digest[..]].concat()
+    }
+
+    /// Add or replace the signature from `entry`'s key.
+    fn insert(&mut self, entry: Entry) -> PyResult<()> {
+        if let Some(existing) = self.entries.iter_mut().find(|e| e.id == entry.id) {
+            *existing = entry;
+        } else {
+            if self.entries.len() == u16::MAX as usize {
+                return Err(InvalidLengthError::new_err("multisignature is full"));
+            }
+            self.entries.push(entry);
+        }
+        Ok(())
+    }
+
+    /// An empty container for signatures over `msg`.
+    pub(crate) fn for_message(msg: &[u8]) -> Self {
+        MultiSignature {
+            digest: Sha256::digest(msg).into(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Whether the signatures are over `msg`.
+    pub(crate) fn is_over(&self, msg: &[u8]) -> bool {
+        Sha256::digest(msg)[..] == self.digest[..]
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub(crate) fn parse(blob: &[u8]) -> PyResult<Self> {
+        let mut r = Reader::new("multisignature", blob);
+        if r.take(MAGIC.len())? != MAGIC {
+            return Err(PyValueError::new_err("not a multisignature"));
+        }
+        let version = r.u8()?;
+        if version != VERSION {
+            return Err(PyValueError::new_err(format!(
+                "unsupported multisignature version {}",
+                version
+            )));
+        }
+        let mut ms = MultiSignature {
+            digest: r.take(32)?.try_into().unwrap(),
+            entries: Vec::new(),
+        };
+        for _ in 0..r.u16()? {
+            let id: KeyId = r.take(KEY_ID_LEN)?.try_into().unwrap();
+            let name_len = r.u8()? as usize;
+            let name = std::str::from_utf8(r.take(name_len)?)
+                .map_err(|_| PyValueError::new_err("multisignature algorithm name isn't UTF-8"))?;
+            let alg = algorithm_named(name)?;
+            let sig_len = r.u32()? as usize;
+            let sig = r.take(sig_len)?.to_vec();
+            ms.insert(Entry { id, alg, sig })?;
+        }
+        r.finish()?;
+        Ok(ms)
+    }
+
+    pub(crate) fn encode(&self) -> Vec<u8> {
+        let mut blob = Vec::new();
+        blob.extend_from_slice(MAGIC);
+        blob.push(VERSION);
+        blob.extend_from_slice(&self.digest);
+        blob.extend_from_slice(&(self.entries.len() as u16).to_be_bytes());
+        for e in &self.entries {
+            blob.extend_from_slice(&e.id);
+            blob.push(e.alg.name.len() as u8);
+            blob.extend_from_slice(e.alg.name.as_bytes());
+            blob.extend_from_slice(&(e.sig.len() as u32).to_be_bytes());
+            blob.extend_from_slice(&e.sig);
+        }
+        blob
+    }
+
+    /// Sign with `sk` under `alg` and add the signature, replacing any
+    /// earlier one from the same key.
+    pub(crate) fn sign(
+        &mut self,
+        py: Python,
+        alg: &'static SignatureAlgorithm,
+        sk: Buffer,
+        pk: Buffer,
+    ) -> PyResult<()> {
+        check_length(
+            &format!("{} public key", alg.name),
+            &pk,
+            alg.public_key_bytes,
+        )?;
+        let id = key_id(alg, &pk);
+        let signed = PyBytes::new_bound(py, &self.signed_data());
+        let sig = (alg.sign)(py, sk, signed.extract()?)?;
+        if !(alg.verify)(pk, signed.extract()?, sig.bind(py).extract()?)? {
+            return Err(InvalidKeyError::new_err(format!(
+                "{} public key doesn't match the secret key",
+                alg.name
+            )));
+        }
+        let sig = sig.as_bytes(py).to_vec();
+        self.insert(Entry { id, alg, sig })
+    }
+
+    /// Indices into `public_keys` of the distinct keys with a valid signature.
+    pub(crate) fn signers(
+        &self,
+        py: Python,
+        msg: &[u8],
+        public_keys: &[Buffer],
+    ) -> PyResult<Vec<usize>> {
+        let mut found = Vec::new();
+        if !self.is_over(msg) {
+            return Ok(found);
+        }
+        let signed = PyBytes::new_bound(py, &self.signed_data());
+        for (i, pk) in public_keys.iter().enumerate() {
+            if public_keys[..i].iter().any(|other| other[..] == pk[..]) {
+                continue;
+            }
+            let entry = self
+                .entries
+                .iter()
+                .find(|e| pk.len() == e.alg.public_key_bytes && e.id == key_id(e.alg, pk));
+            if let Some(entry) = entry {
+                let pk = PyBytes::new_bound(py, pk);
+                let sig = PyBytes::new_bound(py, &entry.sig);
+                if (entry.alg.verify)(pk.extract()?, signed.extract()?, sig.extract()?)? {
+                    found.push(i);
+                }
+            }
+        }
+        Ok(found)
+    }
+}
+
+fn check_threshold(threshold: usize, keys: usize) -> PyResult<()> {
+    if threshold == 0 || threshold > keys {
+        return Err(PyValueError::new_err(format!(
+            "threshold must be between 1 and the {} public keys given, got {}",
+            keys, threshold
+        )));
+    }
+    Ok(())
+}
+
+#[pymethods]
+impl MultiSignature {
+    /// An empty container for signatures over `msg`.
+    #[new]
+    fn new(msg: Buffer) -> Self {
+        Self::for_message(&msg)
+    }
+
+    #[staticmethod]
+    fn from_bytes(blob: Buffer) -> PyResult<Self> {
+        Self::parse(&blob)
+    }
+
+    fn to_bytes(&self, py: Python) -> Py<PyBytes> {
+        PyBytes::new_bound(py, &self.encode()).unbind()
+    }
+
+    /// Sign with `sk` and add the signature, replacing any earlier one from
+    /// the same key. `pk` is the matching public key, which names the signer.
+    #[pyo3(signature = (sk, pk, algorithm = None))]
+    fn add(&mut self, py: Python, sk: Buffer, pk: Buffer, algorithm: Option<&str>) -> PyResult<()> {
+        let alg = match algorithm {
+            Some(name) => algorithm_named(name)?,
+            None => falcon_for("secret key", sk.len(), |alg| alg.secret_key_bytes)?,
+        };
+        self.sign(py, alg, sk, pk)
+    }
+
+    /// Take in the signatures from another container for the same message.
+    fn merge(&mut self, other: &Self) -> PyResult<()> {
+        if other.digest != self.digest {
+            return Err(PyValueError::new_err(
+                "can't merge multisignatures over different messages",
+            ));
+        }
+        for e in &other.entries {
+            self.insert(Entry {
+                id: e.id,
+                alg: e.alg,
+                sig: e.sig.clone(),
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Key IDs of the signatures held, in the order they were added.
+    #[getter]
+    fn key_ids(&self, py: Python) -> Vec<Py<PyBytes>> {
+        self.entries
+            .iter()
+            .map(|e| PyBytes::new_bound(py, &e.id).unbind())
+            .collect()
+    }
+
+    fn __len__(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Indices into `public_keys` of the keys whose signature over `msg` is
+    /// in the container and verifies.
+    fn valid_signers(
+        &self,
+        py: Python,
+        msg: Buffer,
+        public_keys: Vec<Buffer>,
+    ) -> PyResult<Vec<usize>> {
+        self.signers(py, &msg, &public_keys)
+    }
+
+    /// Whether at least `threshold` of `public_keys` validly signed `msg`.
+    fn verify(
+        &self,
+        py: Python,
+        msg: Buffer,
+        public_keys: Vec<Buffer>,
+        threshold: usize,
+    ) -> PyResult<bool> {
+        check_threshold(threshold, public_keys.len())?;
+        Ok(self.signers(py, &msg, &public_keys)?.len() >= threshold)
+    }
+
+    /// verify() that raises SignatureVerificationError, saying how many
+    /// signatures verified, instead of returning False.
+    fn verify_strict(
+        &self,
+        py: Python,
+        msg: Buffer,
+        public_keys: Vec<Buffer>,
+        threshold: usize,
+    ) -> PyResult<()> {
+        check_threshold(threshold, public_keys.len())?;
+        let valid = self.signers(py, &msg, &public_keys)?.len();
+        if valid < threshold {
+            return Err(SignatureVerificationError::new_err(format!(
+                "multisignature has {} valid signatures from the {} keys given, needs {}",
+                valid,
+                public_keys.len(),
+                threshold
+            )));
+        }
+        Ok(())
+    }
+
+    fn __repr__(&self) -> String {
+        format!("MultiSignature(signatures={})", self.entries.len())
+    }
+}
@@ -0,0 +1,227 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// Counter-based AEAD nonces
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// For encrypting many messages under one key without managing nonces:
+//
+//   tx = NonceSequence(key)
+//   box = tx.encrypt(b"first", aad=b"header")
+//   box = tx.encrypt(b"second", aad=b"header")
+//
+//   rx = NonceSequence(key)
+//   msg = rx.decrypt(box, aad=b"header")      # in the order they were sent
+//
+// Message i is sealed under the nonce
+//
+//   nonce_i = iv XOR i (as a big-endian integer the length of the nonce)
+//
+// as TLS 1.3 and QUIC do it. The iv defaults to HKDF-SHA256(salt = "",
+// IKM = key, info = label, nonce length); pass iv= to take it from a key
+// schedule instead. next_nonce() hands out the nonces for use with the
+// *_encrypt() functions directly.
+//
+// Nonces are scoped to the key, not the object: the process keeps a
+// high-water mark per key (indexed by a hash, so no key is retained), and a
+// sequence that would issue a nonce at or below one already issued under its
+// key raises NonceReuseError, as does one that has reached `limit`. That
+// catches a second NonceSequence over the same key, or one restored with a
+// stale `start`. Across restarts, persist `counter` and pass it back as
+// `start`. decrypt() doesn't consult the mark (reading reuses nothing) and
+// only advances when the message authenticates.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use hkdf::Hkdf;
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
+
+use crate::aead::{Aead, KEY_LEN, TAG_LEN};
+use crate::buffer::Buffer;
+use crate::errors::{check_length, NonceReuseError};
+
+const IV_LABEL: &[u8] = b"entropic-chaos nonce sequence iv\0";
+const KEY_ID_LABEL: &[u8] = b"entropic-chaos nonce sequence key id\0";
+
+// ─── Nonce high-water marks ───────────────────────────────────────────────────
+//
+// Key id -> next unused counter, for every key this process has issued
+// nonces under.
+
+static HIGH_WATER: Mutex<BTreeMap<[u8; 32], u64>> = Mutex::new(BTreeMap::new());
+
+/// Claim `counter` under `key_id`, refusing it if it has been issued before.
+fn claim(key_id: &[u8; 32], counter: u64) -> PyResult<()> {
+    let mut marks = HIGH_WATER.lock().unwrap_or_else(|e| e.into_inner());
+    let next = marks.entry(*key_id).or_insert(0);
+    if counter < *next {
+        return Err(NonceReuseError::new_err(format!(
+            "nonce reuse: counter {} is behind counter {} already issued under this key",
+            counter, *next
+        )));
+    }
+    *next = counter + 1;
+    Ok(())
+}
+
+// ─── NonceSequence ────────────────────────────────────────────────────────────
+
+#[pyclass(module = "pqcrypto_bindings")]
+pub struct NonceSequence {
+    aead: Aead,
+    key: Zeroizing<[u8; KEY_LEN]>,
+    key_id: [u8; 32],
+    iv: Vec<u8>,
+    counter: u64,
+    limit: u64,
+}
+
+impl NonceSequence {
+    fn nonce(&self) -> PyResult<Vec<u8>> {
+        if self.counter >= self.limit {
+            return Err(NonceReuseError::new_err(format!(
+                "nonce sequence is exhausted after {} messages; switch to a new key",
+                self.limit
+            )));
+        }
+        let mut nonce = self.iv.clone();
+        let start = nonce.len() - 8;
+        for (n, c) in nonce[start..].iter_mut().zip(self.counter.to_be_bytes()) {
+            *n ^= c;
+        }
+        Ok(nonce)
+    }
+
+    /// The next nonce for encryption, claimed so it can't be issued again.
+    fn issue(&mut self) -> PyResult<Vec<u8>> {
+        let nonce = self.nonce()?;
+        claim(&self.key_id, self.counter)?;
+        self.counter += 1;
+        Ok(nonce)
+    }
+}
+
+#[pymethods]
+impl NonceSequence {
+    /// A sequence for `key`, whose first nonce is number `start`. At most
+    /// `limit` nonces are issued in all (counting those before `start`).
+    // stub: cipher: Literal["chacha20poly1305", "aes256gcm", "xchacha20poly1305"]
+    #[new]
+    #[pyo3(signature = (key, cipher = "chacha20poly1305", start = 0, limit = None, iv = None))]
+    fn new(
+        key: Buffer,
+        cipher: &str,
+        start: u64,
+        limit: Option<u64>,
+        iv: Option<Buffer>,
+    ) -> PyResult<Self> {
+        let aead = Aead::from_name(cipher)?;
+        check_length("NonceSequence key", &key, KEY_LEN)?;
+        let limit = limit.unwrap_or(u64::MAX);
+        if start > limit {
+            return Err(PyValueError::new_err(format!(
+                "start {} is past the limit of {} nonces",
+                start, limit
+            )));
+        }
+        let iv = match iv {
+            Some(iv) => {
+                check_length("NonceSequence iv", &iv, aead.nonce_len())?;
+                iv.to_vec()
+            }
+            None => {
+                let mut iv = vec![0u8; aead.nonce_len()];
+                Hkdf::<Sha256>::new(None, &key)
+                    .expand(IV_LABEL, &mut iv)
+                    .expect("nonce length is a valid HKDF-SHA256 length");
+                iv
+            }
+        };
+        let mut stored = Zeroizing::new([0u8; KEY_LEN]);
+        stored.copy_from_slice(&key);
+        let key_id = Sha256::new()
+            .chain_update(KEY_ID_LABEL)
+            .chain_update(&key[..])
+            .finalize()
+            .into();
+        Ok(NonceSequence {
+            aead,
+            key: stored,
+            key_id,
+            iv,
+            counter: start,
+            limit,
+        })
+    }
+
+    #[getter]
+    fn cipher(&self) -> &'static str {
+        self.aead.name()
+    }
+
+    /// The number of the next nonce; pass it as `start` to resume.
+    #[getter]
+    fn counter(&self) -> u64 {
+        self.counter
+    }
+
+    /// Nonces left before `limit`.
+    #[getter]
+    fn remaining(&self) -> u64 {
+        self.limit.saturating_sub(self.counter)
+    }
+
+    /// Issue the next nonce, for a *_encrypt() call under this key.
+    fn next_nonce<'py>(&mut self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        Ok(PyBytes::new_bound(py, &self.issue()?))
+    }
+
+    /// Encrypt `data` under the next nonce. Returns ciphertext || tag.
+    #[pyo3(signature = (data, aad = None))]
+    fn encrypt(&mut self, py: Python, data: Buffer, aad: Option<Buffer>) -> PyResult<Py<PyBytes>> {
+        let nonce = self.issue()?;
+        let mut body = Vec::with_capacity(data.len() + TAG_LEN);
+        body.extend_from_slice(&data);
+        self.aead.encrypt(
+            &self.key[..],
+            &nonce,
+            aad.as_deref().unwrap_or_default(),
+            &mut body,
+        )?;
+        Ok(PyBytes::new_bound(py, &body).unbind())
+    }
+
+    /// Decrypt the next message in order. Raises DecryptionError, without
+    /// advancing, if it doesn't authenticate under the expected nonce.
+    #[pyo3(signature = (data, aad = None))]
+    fn decrypt(&mut self, py: Python, data: Buffer, aad: Option<Buffer>) -> PyResult<Py<PyBytes>> {
+        let nonce = self.nonce()?;
+        let mut body = data.to_vec();
+        self.aead.decrypt(
+            &self.key[..],
+            &nonce,
+            aad.as_deref().unwrap_or_default(),
+            &mut body,
+        )?;
+        self.counter += 1;
+        Ok(PyBytes::new_bound(py, &body).unbind())
+    }
+
+    fn __reduce__(&self) -> PyResult<()> {
+        Err(PyTypeError::new_err(
+            "cannot pickle 'NonceSequence': a copy would reuse its nonces; persist \
+             counter and pass it back as start instead",
+        ))
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "NonceSequence(cipher='{}', counter={})",
+            self.aead.name(),
+            self.counter
+        )
+    }
+}
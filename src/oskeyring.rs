@@ -0,0 +1,89 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// Secret keys in the platform keychain
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// For desktop apps, so a secret key lives in the OS credential store rather
+// than in a file:
+//
+//   store_in_keyring("alice@laptop", sk)
+//   sk = load_from_keyring("alice@laptop")
+//   delete_from_keyring("alice@laptop")
+//
+// Backed by the macOS Keychain, the Windows Credential Manager (DPAPI
+// protected, per user) and the freedesktop Secret Service (GNOME Keyring,
+// KWallet) elsewhere, through the keyring crate. Entries sit under the service
+// name "pqcrypto_bindings" with the label as the account. The bytes are
+// stored as given, so pass a to_der() / secret_key_to_der() encoding to keep
+// the algorithm alongside the key.
+//
+// The Credential Manager caps a secret at 2560 bytes, so larger keys (ML-KEM-
+// 1024, SPHINCS+, Classic McEliece) can't be stored there; store_in_keyring()
+// raises InvalidLengthError for them on Windows. Wrap one with
+// export_encrypted() and keep the password in the keychain instead.
+
+use pyo3::exceptions::{PyKeyError, PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use zeroize::Zeroizing;
+
+use crate::buffer::Buffer;
+use crate::errors::InvalidLengthError;
+use crate::secret::{secret_bytes, SecretBytes};
+
+const SERVICE: &str = "pqcrypto_bindings";
+
+/// CRED_MAX_CREDENTIAL_BLOB_SIZE.
+#[cfg(windows)]
+const MAX_SECRET_LEN: usize = 5 * 512;
+
+fn keyring_err(label: &str, e: keyring::Error) -> PyErr {
+    match e {
+        keyring::Error::NoEntry => PyKeyError::new_err(label.to_owned()),
+        keyring::Error::TooLong(..) | keyring::Error::Invalid(..) => {
+            PyValueError::new_err(format!("keyring label '{}': {}", label, e))
+        }
+        e => PyRuntimeError::new_err(format!("keyring entry '{}': {}", label, e)),
+    }
+}
+
+fn entry(label: &str) -> PyResult<keyring::Entry> {
+    if label.is_empty() {
+        return Err(PyValueError::new_err("keyring label must not be empty"));
+    }
+    keyring::Entry::new(SERVICE, label).map_err(|e| keyring_err(label, e))
+}
+
+/// Store `sk` in the platform keychain under `label`, replacing any key
+/// already there.
+#[pyfunction]
+pub fn store_in_keyring(label: &str, sk: Buffer) -> PyResult<()> {
+    if sk.is_empty() {
+        return Err(InvalidLengthError::new_err(
+            "cannot store an empty secret key",
+        ));
+    }
+    #[cfg(windows)]
+    crate::errors::check_max_length("secret key", &sk, MAX_SECRET_LEN)?;
+    entry(label)?
+        .set_secret(&sk)
+        .map_err(|e| keyring_err(label, e))
+}
+
+/// The secret key stored under `label`. Raises KeyError if there is none.
+#[pyfunction]
+pub fn load_from_keyring(py: Python, label: &str) -> PyResult<Py<SecretBytes>> {
+    let sk = Zeroizing::new(
+        entry(label)?
+            .get_secret()
+            .map_err(|e| keyring_err(label, e))?,
+    );
+    secret_bytes(py, &sk)
+}
+
+/// Remove the secret key stored under `label`. Raises KeyError if there is
+/// none.
+#[pyfunction]
+pub fn delete_from_keyring(label: &str) -> PyResult<()> {
+    entry(label)?
+        .delete_credential()
+        .map_err(|e| keyring_err(label, e))
+}
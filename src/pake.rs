@@ -0,0 +1,707 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// Password-authenticated key exchange over ML-KEM
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// For pairing two devices from a short PIN, or logging in with a password,
+// without either being open to offline guessing:
+//
+//   client = PakeInitiator("482913")
+//   msg1 = client.message                      # -> server
+//
+//   server = PakeResponder(password="482913")
+//   msg2 = server.respond(msg1)                # -> client
+//   msg3 = client.finish(msg2)                 # -> server
+//   server.finish(msg3)
+//
+//   chan = SecureChannel.from_handshake(client)
+//
+// The exchange is OCAKE (Beguinet, Chevalier, Pointcheval, Ricosset and
+// Rossi, "GeT a CAKE", ACNS 2023) over ML-KEM / Kyber: the client sends an
+// ephemeral public key masked under the password, the server unmasks and
+// encapsulates to it, and each side confirms the result:
+//
+//   msg1 = magic "PQPK" | version 1 | type 1 | name length u8 | KEM name
+//            | nonce (32) | masked pk
+//   msg2 = magic "PQPK" | version 1 | type 2 | KEM ciphertext | server MAC (32)
+//   msg3 = magic "PQPK" | version 1 | type 3 | client MAC (32)
+//            [| signature length u16 | signature]
+//
+// The mask is added to the public key's polynomial t coefficient-wise mod q
+// and XORed into its seed rho, taken from
+//
+//   SHAKE256(label || "mask" || password key || KEM name || nonce)
+//
+// Since t and rho look uniformly random, so does every unmasking: someone
+// who records the exchange, or plays the server, learns nothing about wrong
+// guesses. Both sides then derive
+//
+//   session_id = SHA-256(label || msg1 || msg2 up to the MAC)
+//   prk        = HKDF-Extract(salt = session_id, IKM = shared secret || password key)
+//
+// with the MACs as HMAC-SHA256 over session_id under keys expanded from prk,
+// and the session keys as in ake.rs. A wrong password, on either side, or a
+// modified message raises DecryptionError in finish(). Each run lets an
+// active attacker test one guess, so the application must limit failed
+// attempts: a 6-digit PIN withstands only so many.
+//
+// The password key is HKDF-SHA256 of the password, salted with a hash of
+// `context` (device or account identifiers both sides know). That is the
+// balanced mode, where both sides know the password. For a server that
+// shouldn't, there is an augmented mode in the style of OPAQUE (Jarecki,
+// Krawczyk and Xu, Eurocrypt 2018), here by the Omega method (Gentry,
+// MacKenzie and Ramzan, Crypto 2006):
+//
+//   record = pake_register("hunter2", context=b"alice")   # stored by server
+//   params = pake_params(record)                          # sent to the client
+//
+//   client = PakeInitiator("hunter2", context=b"alice", params=params)
+//   server = PakeResponder(record=record, context=b"alice")
+//
+// The password goes through Argon2id under the record's salt and costs, and
+// the output gives both the password key and the seed of a Falcon-512 key
+// pair. The record keeps the password key and the Falcon public key; the
+// client signs session_id in msg3 to prove it holds the private key too. A
+// stolen record still needs an Argon2id dictionary attack before it lets
+// anyone log in as the client (it does let them pose as the server, as with
+// any augmented PAKE), so it is returned as SecretBytes. Objects don't
+// pickle.
+
+use hkdf::hmac::{Hmac, KeyInit, Mac};
+use hkdf::Hkdf;
+use pyo3::exceptions::{PyRuntimeError, PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
+
+use crate::ake::Session;
+use crate::buffer::Buffer;
+use crate::errors::DecryptionError;
+use crate::kem::{find_kem, KemAlgorithm};
+use crate::keywrap::{
+    derive_key, Password, DEFAULT_ITERATIONS, DEFAULT_MEMORY_KIB, DEFAULT_PARALLELISM,
+    MAX_MEMORY_KIB,
+};
+use crate::noise::{decapsulate, encapsulate, keygen};
+use crate::secret::{secret_bytes, SecretBytes};
+use crate::seeded::falcon512_keypair_from_seed;
+use crate::sign::find_signature;
+use crate::util::{ct_eq, Reader};
+use crate::xof::{ShakeVariant, Sponge};
+
+const MAGIC: &[u8; 4] = b"PQPK";
+const RECORD_MAGIC: &[u8; 4] = b"PQPR";
+const PARAMS_MAGIC: &[u8; 4] = b"PQPP";
+const VERSION: u8 = 1;
+const LABEL: &[u8] = b"entropic-chaos pake v1\0";
+
+const MSG1: u8 = 1;
+const MSG2: u8 = 2;
+const MSG3: u8 = 3;
+
+const NONCE_LEN: usize = 32;
+const MAC_LEN: usize = 32;
+const SALT_LEN: usize = 16;
+const KEY_LEN: usize = 32;
+const SIGNATURE_SCHEME: &str = "falcon512";
+
+const INITIATOR_TO_RESPONDER: &[u8] = b"initiator to responder";
+const RESPONDER_TO_INITIATOR: &[u8] = b"responder to initiator";
+
+// ─── Password keys ────────────────────────────────────────────────────────────
+
+/// Argon2id costs and salt of an augmented record: what pake_params() sends.
+struct Params {
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+    salt: [u8; SALT_LEN],
+}
+
+impl Params {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.memory_kib.to_be_bytes());
+        out.extend_from_slice(&self.iterations.to_be_bytes());
+        out.extend_from_slice(&self.parallelism.to_be_bytes());
+        out.extend_from_slice(&self.salt);
+    }
+
+    fn decode(r: &mut Reader) -> PyResult<Self> {
+        Ok(Params {
+            memory_kib: r.u32()?,
+            iterations: r.u32()?,
+            parallelism: r.u32()?,
+            salt: r.take(SALT_LEN)?.try_into().unwrap(),
+        })
+    }
+}
+
+fn read_magic(r: &mut Reader, magic: &[u8; 4], what: &str) -> PyResult<()> {
+    if r.take(magic.len())? != magic {
+        return Err(PyValueError::new_err(format!("not a PAKE {}", what)));
+    }
+    let version = r.u8()?;
+    if version != VERSION {
+        return Err(PyValueError::new_err(format!(
+            "unsupported PAKE {} version {}",
+            what, version
+        )));
+    }
+    Ok(())
+}
+
+fn expand_password(ikm: &[u8], context: &[u8], purpose: &[u8], out: &mut [u8]) {
+    let salt = Sha256::new()
+        .chain_update(LABEL)
+        .chain_update(context)
+        .finalize();
+    Hkdf::<Sha256>::new(Some(&salt), ikm)
+        .expand(&[LABEL, purpose].concat(), out)
+        .expect("valid HKDF-SHA256 length");
+}
+
+fn balanced_key(password: &[u8], context: &[u8]) -> Zeroizing<[u8; KEY_LEN]> {
+    let mut key = Zeroizing::new([0u8; KEY_LEN]);
+    expand_password(password, context, b"balanced password key", key.as_mut());
+    key
+}
+
+/// An augmented client's secrets: the password key and its Falcon-512 key
+/// pair.
+struct Augmented {
+    password_key: Zeroizing<[u8; KEY_LEN]>,
+    pk: Vec<u8>,
+    sk: Zeroizing<Vec<u8>>,
+}
+
+fn augmented_keys(password: &[u8], context: &[u8], params: &Params) -> PyResult<Augmented> {
+    if params.memory_kib > MAX_MEMORY_KIB {
+        return Err(PyValueError::new_err(format!(
+            "PAKE parameters ask for {} KiB of Argon2 memory (limit {})",
+            params.memory_kib, MAX_MEMORY_KIB
+        )));
+    }
+    let argon = argon2::Params::new(
+        params.memory_kib,
+        params.iterations,
+        params.parallelism,
+        Some(32),
+    )
+    .map_err(|e| PyValueError::new_err(format!("invalid Argon2id parameters: {}", e)))?;
+    let stretched = derive_key(password, &params.salt, argon)?;
+
+    let mut password_key = Zeroizing::new([0u8; KEY_LEN]);
+    expand_password(
+        &stretched[..],
+        context,
+        b"augmented password key",
+        password_key.as_mut(),
+    );
+    let mut seed = Zeroizing::new([0u8; 48]);
+    expand_password(&stretched[..], context, b"signing key", seed.as_mut());
+
+    let alg = find_signature(SIGNATURE_SCHEME).expect("Falcon-512 is registered");
+    let mut pk = vec![0u8; alg.public_key_bytes];
+    let mut sk = Zeroizing::new(vec![0u8; alg.secret_key_bytes]);
+    if !falcon512_keypair_from_seed(&seed, &mut pk, &mut sk) {
+        return Err(PyRuntimeError::new_err("Falcon-512 key generation failed"));
+    }
+    Ok(Augmented {
+        password_key,
+        pk,
+        sk,
+    })
+}
+
+/// Register `password` for augmented PAKE. Returns the record for the
+/// server to store; pake_params() gives the part the client needs.
+#[pyfunction]
+#[pyo3(signature = (
+    password,
+    context = None,
+    memory_kib = DEFAULT_MEMORY_KIB,
+    iterations = DEFAULT_ITERATIONS,
+    parallelism = DEFAULT_PARALLELISM,
+))]
+pub fn pake_register(
+    py: Python,
+    password: Password,
+    context: Option<Buffer>,
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+) -> PyResult<Py<SecretBytes>> {
+    let mut salt = [0u8; SALT_LEN];
+    getrandom::fill(&mut salt).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+    let params = Params {
+        memory_kib,
+        iterations,
+        parallelism,
+        salt,
+    };
+    let context = context.as_deref().unwrap_or_default();
+    let keys = augmented_keys(password.as_bytes(), context, &params)?;
+
+    let mut record = Zeroizing::new(Vec::new());
+    record.extend_from_slice(RECORD_MAGIC);
+    record.push(VERSION);
+    params.encode(&mut record);
+    record.extend_from_slice(&keys.password_key[..]);
+    record.extend_from_slice(&(keys.pk.len() as u16).to_be_bytes());
+    record.extend_from_slice(&keys.pk);
+    secret_bytes(py, &record)
+}
+
+/// A pake_register() record: its Argon2id parameters, password key and
+/// Falcon public key.
+fn read_record(record: &[u8]) -> PyResult<(Params, Zeroizing<[u8; KEY_LEN]>, Vec<u8>)> {
+    let mut r = Reader::new("PAKE record", record);
+    read_magic(&mut r, RECORD_MAGIC, "record")?;
+    let params = Params::decode(&mut r)?;
+    let mut password_key = Zeroizing::new([0u8; KEY_LEN]);
+    password_key.copy_from_slice(r.take(KEY_LEN)?);
+    let pk_len = r.u16()? as usize;
+    let pk = r.take(pk_len)?.to_vec();
+    r.finish()?;
+    Ok((params, password_key, pk))
+}
+
+/// The public part of a pake_register() record, for the client's
+/// PakeInitiator(params=...).
+#[pyfunction]
+pub fn pake_params(py: Python, record: Buffer) -> PyResult<Py<PyBytes>> {
+    let (params, _, _) = read_record(&record)?;
+    let mut out = Vec::new();
+    out.extend_from_slice(PARAMS_MAGIC);
+    out.push(VERSION);
+    params.encode(&mut out);
+    Ok(PyBytes::new_bound(py, &out).unbind())
+}
+
+// ─── Masking ──────────────────────────────────────────────────────────────────
+
+const Q: u16 = 3329;
+const SEED_LEN: usize = 32;
+
+/// ML-KEM and Kyber public keys are t (12-bit coefficients mod q) || rho.
+fn pake_kem(name: &str) -> PyResult<&'static KemAlgorithm> {
+    find_kem(name)
+        .filter(|kem| kem.name.starts_with("mlkem") || kem.name.starts_with("kyber"))
+        .ok_or_else(|| {
+            PyValueError::new_err(format!(
+                "PAKE needs an ML-KEM or Kyber parameter set, got '{}'",
+                name
+            ))
+        })
+}
+
+/// Add (`unmask` false) or subtract the password mask to a public key.
+/// Raises ValueError if a coefficient isn't reduced mod q, which no masked
+/// key from an honest client has.
+fn apply_mask(
+    password_key: &[u8],
+    kem: &KemAlgorithm,
+    nonce: &[u8],
+    pk: &[u8],
+    unmask: bool,
+) -> PyResult<Vec<u8>> {
+    let mut sponge = Sponge::new(ShakeVariant::Shake256);
+    for part in [LABEL, b"mask", password_key, kem.name.as_bytes(), nonce] {
+        sponge.absorb(part);
+    }
+    let (t, rho) = pk.split_at(pk.len() - SEED_LEN);
+
+    let mut out = Vec::with_capacity(pk.len());
+    let mut stream = [0u8; 3];
+    let mut pending: Option<u16> = None;
+    let mut next_mask = |sponge: &mut Sponge| loop {
+        if let Some(c) = pending.take() {
+            return c;
+        }
+        sponge.squeeze(&mut stream);
+        let d1 = u16::from(stream[0]) | (u16::from(stream[1] & 0x0f) << 8);
+        let d2 = u16::from(stream[1] >> 4) | (u16::from(stream[2]) << 4);
+        if d2 < Q {
+            pending = Some(d2);
+        }
+        if d1 < Q {
+            return d1;
+        }
+    };
+    for chunk in t.chunks(3) {
+        let c1 = u16::from(chunk[0]) | (u16::from(chunk[1] & 0x0f) << 8);
+        let c2 = u16::from(chunk[1] >> 4) | (u16::from(chunk[2]) << 4);
+        if c1 >= Q || c2 >= Q {
+            return Err(PyValueError::new_err("malformed PAKE message: masked key"));
+        }
+        let [m1, m2] = [next_mask(&mut sponge), next_mask(&mut sponge)];
+        let (c1, c2) = if unmask {
+            ((c1 + Q - m1) % Q, (c2 + Q - m2) % Q)
+        } else {
+            ((c1 + m1) % Q, (c2 + m2) % Q)
+        };
+        out.push(c1 as u8);
+        out.push(((c1 >> 8) as u8) | ((c2 as u8) << 4));
+        out.push((c2 >> 4) as u8);
+    }
+    let mut rho_mask = [0u8; SEED_LEN];
+    sponge.squeeze(&mut rho_mask);
+    out.extend(rho.iter().zip(rho_mask).map(|(a, b)| a ^ b));
+    Ok(out)
+}
+
+// ─── Key schedule ─────────────────────────────────────────────────────────────
+
+/// What both sides derive once the KEM is done.
+struct Keys {
+    id: [u8; 32],
+    prk: Hkdf<Sha256>,
+}
+
+impl Keys {
+    fn derive(ss: &[u8], password_key: &[u8], msg1: &[u8], msg2_body: &[u8]) -> Self {
+        let id: [u8; 32] = Sha256::new()
+            .chain_update(LABEL)
+            .chain_update(msg1)
+            .chain_update(msg2_body)
+            .finalize()
+            .into();
+        let ikm = Zeroizing::new([ss, password_key].concat());
+        Keys {
+            id,
+            prk: Hkdf::<Sha256>::new(Some(&id), &ikm),
+        }
+    }
+
+    fn expand(&self, purpose: &[u8]) -> Zeroizing<[u8; KEY_LEN]> {
+        let mut key = Zeroizing::new([0u8; KEY_LEN]);
+        self.prk
+            .expand(&[LABEL, purpose].concat(), key.as_mut())
+            .expect("32 bytes is a valid HKDF-SHA256 length");
+        key
+    }
+
+    fn mac(&self, purpose: &[u8]) -> [u8; MAC_LEN] {
+        let key = self.expand(purpose);
+        let mut mac = <Hmac<Sha256> as KeyInit>::new_from_slice(&key[..])
+            .expect("HMAC takes keys of any length");
+        mac.update(&self.id);
+        mac.finalize().into_bytes().into()
+    }
+
+    fn session(&self, initiator: bool) -> Session {
+        let (i2r, r2i) = (
+            self.expand(INITIATOR_TO_RESPONDER),
+            self.expand(RESPONDER_TO_INITIATOR),
+        );
+        let (send, recv) = if initiator { (i2r, r2i) } else { (r2i, i2r) };
+        Session {
+            id: self.id,
+            send,
+            recv,
+        }
+    }
+}
+
+fn signed_data(id: &[u8]) -> Vec<u8> {
+    [LABEL, b"client signature", id].concat()
+}
+
+fn header(kind: u8) -> Vec<u8> {
+    let mut msg = Vec::new();
+    msg.extend_from_slice(MAGIC);
+    msg.push(VERSION);
+    msg.push(kind);
+    msg
+}
+
+fn read_header(r: &mut Reader, kind: u8) -> PyResult<()> {
+    read_magic(r, MAGIC, "message")?;
+    let got = r.u8()?;
+    if got != kind {
+        return Err(PyValueError::new_err(format!(
+            "expected PAKE message {}, got type {}",
+            kind, got
+        )));
+    }
+    Ok(())
+}
+
+fn confirmation_failed() -> PyErr {
+    DecryptionError::new_err("PAKE confirmation failed: wrong password or a modified message")
+}
+
+fn established(session: &Option<Session>) -> PyResult<&Session> {
+    session
+        .as_ref()
+        .ok_or_else(|| PyValueError::new_err("the PAKE exchange isn't complete"))
+}
+
+fn session_keys(py: Python, session: &Session) -> PyResult<(Py<SecretBytes>, Py<SecretBytes>)> {
+    Ok((
+        secret_bytes(py, &session.send[..])?,
+        secret_bytes(py, &session.recv[..])?,
+    ))
+}
+
+// ─── PakeInitiator ────────────────────────────────────────────────────────────
+
+#[pyclass(module = "pqcrypto_bindings")]
+pub struct PakeInitiator {
+    kem: &'static KemAlgorithm,
+    password_key: Zeroizing<[u8; KEY_LEN]>,
+    /// Falcon-512 secret key, in augmented mode.
+    signing_key: Option<Zeroizing<Vec<u8>>>,
+    ephemeral_sk: Option<Zeroizing<Vec<u8>>>,
+    msg1: Vec<u8>,
+    session: Option<Session>,
+}
+
+impl PakeInitiator {
+    pub(crate) fn session(&self) -> PyResult<&Session> {
+        established(&self.session)
+    }
+}
+
+#[pymethods]
+impl PakeInitiator {
+    /// Start an exchange under `password`: balanced, or augmented against a
+    /// record if `params` is its pake_params().
+    #[new]
+    #[pyo3(signature = (password, context = None, params = None, kem = "mlkem768"))]
+    fn new(
+        py: Python,
+        password: Password,
+        context: Option<Buffer>,
+        params: Option<Buffer>,
+        kem: &str,
+    ) -> PyResult<Self> {
+        let kem = pake_kem(kem)?;
+        let context = context.as_deref().unwrap_or_default();
+        let (password_key, signing_key) = match params {
+            Some(params) => {
+                let mut r = Reader::new("PAKE parameters", &params);
+                read_magic(&mut r, PARAMS_MAGIC, "parameters")?;
+                let params = Params::decode(&mut r)?;
+                r.finish()?;
+                let keys = augmented_keys(password.as_bytes(), context, &params)?;
+                (keys.password_key, Some(keys.sk))
+            }
+            None => (balanced_key(password.as_bytes(), context), None),
+        };
+
+        let ephemeral = keygen(py, kem)?;
+        let mut nonce = [0u8; NONCE_LEN];
+        getrandom::fill(&mut nonce).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let masked = apply_mask(&password_key[..], kem, &nonce, &ephemeral.pk, false)?;
+
+        let mut msg1 = header(MSG1);
+        msg1.push(kem.name.len() as u8);
+        msg1.extend_from_slice(kem.name.as_bytes());
+        msg1.extend_from_slice(&nonce);
+        msg1.extend_from_slice(&masked);
+        Ok(PakeInitiator {
+            kem,
+            password_key,
+            signing_key,
+            ephemeral_sk: Some(ephemeral.sk),
+            msg1,
+            session: None,
+        })
+    }
+
+    /// The first message, to send to the responder.
+    #[getter]
+    fn message<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new_bound(py, &self.msg1)
+    }
+
+    #[getter]
+    fn kem(&self) -> &'static str {
+        self.kem.name
+    }
+
+    /// Check the responder's reply and return the last message. Raises
+    /// DecryptionError if the passwords differ.
+    fn finish(&mut self, py: Python, msg2: Buffer) -> PyResult<Py<PyBytes>> {
+        let Some(ephemeral_sk) = self.ephemeral_sk.as_ref() else {
+            return Err(PyValueError::new_err(
+                "the PAKE exchange is already complete",
+            ));
+        };
+        let mut r = Reader::new("PAKE message 2", &msg2);
+        read_header(&mut r, MSG2)?;
+        let ct = r.take(self.kem.ciphertext_bytes)?;
+        let server_mac = r.take(MAC_LEN)?;
+        r.finish()?;
+
+        let ss = decapsulate(py, self.kem, ephemeral_sk, ct)?;
+        let body = &msg2[..msg2.len() - MAC_LEN];
+        let keys = Keys::derive(&ss, &self.password_key[..], &self.msg1, body);
+        if !ct_eq(server_mac, &keys.mac(b"responder confirmation")) {
+            return Err(confirmation_failed());
+        }
+
+        let mut msg3 = header(MSG3);
+        msg3.extend_from_slice(&keys.mac(b"initiator confirmation"));
+        if let Some(signing_key) = &self.signing_key {
+            let alg = find_signature(SIGNATURE_SCHEME).expect("Falcon-512 is registered");
+            let sk = PyBytes::new_bound(py, signing_key);
+            let signed = PyBytes::new_bound(py, &signed_data(&keys.id));
+            let sig = (alg.sign)(py, sk.extract()?, signed.extract()?)?;
+            let sig = sig.as_bytes(py);
+            msg3.extend_from_slice(&(sig.len() as u16).to_be_bytes());
+            msg3.extend_from_slice(sig);
+        }
+        self.session = Some(keys.session(true));
+        self.ephemeral_sk = None;
+        Ok(PyBytes::new_bound(py, &msg3).unbind())
+    }
+
+    #[getter]
+    fn session_id<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        Ok(PyBytes::new_bound(py, &established(&self.session)?.id))
+    }
+
+    /// (send key, receive key), as for AkeInitiator.
+    fn session_keys(&self, py: Python) -> PyResult<(Py<SecretBytes>, Py<SecretBytes>)> {
+        session_keys(py, established(&self.session)?)
+    }
+
+    fn __reduce__(&self) -> PyResult<()> {
+        Err(PyTypeError::new_err(
+            "cannot pickle 'PakeInitiator': it holds secret key material",
+        ))
+    }
+}
+
+// ─── PakeResponder ────────────────────────────────────────────────────────────
+
+#[pyclass(module = "pqcrypto_bindings")]
+pub struct PakeResponder {
+    password_key: Zeroizing<[u8; KEY_LEN]>,
+    /// The client's Falcon-512 public key, in augmented mode.
+    verifier: Option<Vec<u8>>,
+    /// Keys derived by respond(), until finish() confirms them.
+    pending: Option<Keys>,
+    session: Option<Session>,
+}
+
+impl PakeResponder {
+    pub(crate) fn session(&self) -> PyResult<&Session> {
+        established(&self.session)
+    }
+}
+
+#[pymethods]
+impl PakeResponder {
+    /// Answer exchanges under `password` (balanced) or a pake_register()
+    /// `record` (augmented); pass exactly one.
+    #[new]
+    #[pyo3(signature = (password = None, record = None, context = None))]
+    fn new(
+        password: Option<Password>,
+        record: Option<Buffer>,
+        context: Option<Buffer>,
+    ) -> PyResult<Self> {
+        let (password_key, verifier) = match (password, record) {
+            (Some(password), None) => {
+                let context = context.as_deref().unwrap_or_default();
+                (balanced_key(password.as_bytes(), context), None)
+            }
+            (None, Some(record)) => {
+                let (_, password_key, pk) = read_record(&record)?;
+                (password_key, Some(pk))
+            }
+            _ => {
+                return Err(PyValueError::new_err(
+                    "PakeResponder takes either password or record",
+                ))
+            }
+        };
+        Ok(PakeResponder {
+            password_key,
+            verifier,
+            pending: None,
+            session: None,
+        })
+    }
+
+    /// Answer the initiator's first message.
+    fn respond(&mut self, py: Python, msg1: Buffer) -> PyResult<Py<PyBytes>> {
+        if self.pending.is_some() || self.session.is_some() {
+            return Err(PyValueError::new_err("PakeResponder has already responded"));
+        }
+        let mut r = Reader::new("PAKE message 1", &msg1);
+        read_header(&mut r, MSG1)?;
+        let name_len = r.u8()? as usize;
+        let name = std::str::from_utf8(r.take(name_len)?)
+            .map_err(|_| PyValueError::new_err("PAKE KEM name isn't UTF-8"))?;
+        let kem = pake_kem(name)?;
+        let nonce = r.take(NONCE_LEN)?;
+        let masked = r.take(kem.public_key_bytes)?;
+        r.finish()?;
+
+        let pk = apply_mask(&self.password_key[..], kem, nonce, masked, true)?;
+        let (ct, ss) = encapsulate(py, kem, &pk)?;
+        let mut msg2 = header(MSG2);
+        msg2.extend_from_slice(&ct);
+        let keys = Keys::derive(&ss, &self.password_key[..], &msg1, &msg2);
+        msg2.extend_from_slice(&keys.mac(b"responder confirmation"));
+        self.pending = Some(keys);
+        Ok(PyBytes::new_bound(py, &msg2).unbind())
+    }
+
+    /// Check the initiator's last message. Raises DecryptionError if the
+    /// passwords differ.
+    fn finish(&mut self, py: Python, msg3: Buffer) -> PyResult<()> {
+        let Some(keys) = self.pending.as_ref() else {
+            return Err(PyValueError::new_err(
+                "PakeResponder has no exchange in progress",
+            ));
+        };
+        let mut r = Reader::new("PAKE message 3", &msg3);
+        read_header(&mut r, MSG3)?;
+        let client_mac = r.take(MAC_LEN)?;
+        let signature = match self.verifier {
+            Some(_) => {
+                let len = r.u16()? as usize;
+                Some(r.take(len)?)
+            }
+            None => None,
+        };
+        r.finish()?;
+
+        if !ct_eq(client_mac, &keys.mac(b"initiator confirmation")) {
+            return Err(confirmation_failed());
+        }
+        if let (Some(pk), Some(sig)) = (&self.verifier, signature) {
+            let alg = find_signature(SIGNATURE_SCHEME).expect("Falcon-512 is registered");
+            let pk = PyBytes::new_bound(py, pk);
+            let signed = PyBytes::new_bound(py, &signed_data(&keys.id));
+            let sig = PyBytes::new_bound(py, sig);
+            if !(alg.verify)(pk.extract()?, signed.extract()?, sig.extract()?)? {
+                return Err(confirmation_failed());
+            }
+        }
+        let keys = self.pending.take().expect("checked above");
+        self.session = Some(keys.session(false));
+        Ok(())
+    }
+
+    #[getter]
+    fn session_id<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        Ok(PyBytes::new_bound(py, &established(&self.session)?.id))
+    }
+
+    /// (send key, receive key), as for AkeResponder.
+    fn session_keys(&self, py: Python) -> PyResult<(Py<SecretBytes>, Py<SecretBytes>)> {
+        session_keys(py, established(&self.session)?)
+    }
+
+    fn __reduce__(&self) -> PyResult<()> {
+        Err(PyTypeError::new_err(
+            "cannot pickle 'PakeResponder': it holds secret key material",
+        ))
+    }
+}
@@ -0,0 +1,158 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// Pairwise consistency test after keygen
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// With the test on, every keygen tries the fresh key pair before returning
+// it (encapsulate then decapsulate for a KEM, sign then verify for a
+// signature scheme) and raises PairwiseConsistencyError if the round trip
+// fails, so an RNG or memory fault can't produce a key that gets persisted
+// and only fails later:
+//
+//   set_pairwise_check(True)     # on for every keygen in the process
+//   set_pairwise_check(None)     # back to the default: on in FIPS mode
+//
+// FIPS mode is off unless PQCRYPTO_BINDINGS_FIPS_MODE=1 is set when the
+// module is imported, or set_fips_mode(True) is called. For now its only
+// effect is turning this test on by default, as FIPS 140-3 requires of key
+// generation. Covers every algorithm KEM() and Signature() know, through
+// either the per-algorithm keygen functions or the classes; the stateful
+// XMSS / LMS schemes are left out, as a test signature would spend a one-time
+// key.
+
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+use crate::buffer::Buffer;
+use crate::errors::PairwiseConsistencyError;
+use crate::kem::KemAlgorithm;
+use crate::secret::{secret_bytes, SecretBytes};
+use crate::sign::SignatureAlgorithm;
+use crate::util::ct_eq;
+
+const FIPS_MODE_ENV: &str = "PQCRYPTO_BINDINGS_FIPS_MODE";
+const TEST_MESSAGE: &[u8] = b"pqcrypto_bindings pairwise consistency test";
+
+static FIPS_MODE: AtomicBool = AtomicBool::new(false);
+
+// set_pairwise_check() override: follow FIPS mode, always on, or always off.
+const FOLLOW_FIPS_MODE: u8 = 0;
+const ON: u8 = 1;
+const OFF: u8 = 2;
+static PAIRWISE_CHECK: AtomicU8 = AtomicU8::new(FOLLOW_FIPS_MODE);
+
+type KeyPair = (Py<PyBytes>, Py<SecretBytes>);
+
+/// Called once from module init.
+pub(crate) fn init_from_env() {
+    if std::env::var(FIPS_MODE_ENV).is_ok_and(|v| v == "1") {
+        FIPS_MODE.store(true, Ordering::Relaxed);
+    }
+}
+
+fn enabled() -> bool {
+    match PAIRWISE_CHECK.load(Ordering::Relaxed) {
+        ON => true,
+        OFF => false,
+        _ => FIPS_MODE.load(Ordering::Relaxed),
+    }
+}
+
+fn failed(alg: &str, why: impl std::fmt::Display) -> PyErr {
+    PairwiseConsistencyError::new_err(format!("{} pairwise consistency test failed: {}", alg, why))
+}
+
+/// Return `keys` from a KEM keygen, after an encapsulate / decapsulate round
+/// trip if the test is on.
+pub(crate) fn checked_kem_keypair(
+    py: Python,
+    alg: &KemAlgorithm,
+    keys: KeyPair,
+) -> PyResult<KeyPair> {
+    if !enabled() {
+        return Ok(keys);
+    }
+    let round_trip = || -> PyResult<bool> {
+        let (ct, ss) = (alg.encapsulate)(py, keys.0.bind(py).extract()?)?;
+        let ss2 = (alg.decapsulate)(py, keys.1.bind(py).extract()?, ct.bind(py).extract()?)?;
+        let (ss, ss2): (Buffer, Buffer) = (ss.bind(py).extract()?, ss2.bind(py).extract()?);
+        Ok(ct_eq(&ss, &ss2))
+    };
+    match round_trip() {
+        Ok(true) => Ok(keys),
+        Ok(false) => Err(failed(alg.name, "decapsulated shared secret doesn't match")),
+        Err(e) => Err(failed(alg.name, e)),
+    }
+}
+
+/// Return `keys` from a signature keygen, after a sign / verify round trip
+/// if the test is on.
+pub(crate) fn checked_signature_keypair(
+    py: Python,
+    alg: &SignatureAlgorithm,
+    keys: KeyPair,
+) -> PyResult<KeyPair> {
+    if !enabled() {
+        return Ok(keys);
+    }
+    let round_trip = || -> PyResult<bool> {
+        let msg = PyBytes::new_bound(py, TEST_MESSAGE);
+        let sig = (alg.sign)(py, keys.1.bind(py).extract()?, msg.extract()?)?;
+        (alg.verify)(
+            keys.0.bind(py).extract()?,
+            msg.extract()?,
+            sig.bind(py).extract()?,
+        )
+    };
+    match round_trip() {
+        Ok(true) => Ok(keys),
+        Ok(false) => Err(failed(alg.name, "test signature doesn't verify")),
+        Err(e) => Err(failed(alg.name, e)),
+    }
+}
+
+/// checked_kem_keypair() for keygens that build key objects rather than
+/// returning bytes.
+pub(crate) fn check_kem_key_bytes(
+    py: Python,
+    alg: &KemAlgorithm,
+    pk: &[u8],
+    sk: &[u8],
+) -> PyResult<()> {
+    if !enabled() {
+        return Ok(());
+    }
+    let keys = (PyBytes::new_bound(py, pk).unbind(), secret_bytes(py, sk)?);
+    checked_kem_keypair(py, alg, keys).map(drop)
+}
+
+/// Turn FIPS mode on or off. While on, the pairwise consistency test runs
+/// after every keygen unless set_pairwise_check(False) turned it off.
+#[pyfunction]
+pub fn set_fips_mode(enabled: bool) {
+    FIPS_MODE.store(enabled, Ordering::Relaxed);
+}
+
+#[pyfunction]
+pub fn fips_mode() -> bool {
+    FIPS_MODE.load(Ordering::Relaxed)
+}
+
+/// Force the pairwise consistency test on (True) or off (False) for every
+/// keygen, or with None go back to following FIPS mode.
+#[pyfunction]
+pub fn set_pairwise_check(enabled: Option<bool>) {
+    let value = match enabled {
+        Some(true) => ON,
+        Some(false) => OFF,
+        None => FOLLOW_FIPS_MODE,
+    };
+    PAIRWISE_CHECK.store(value, Ordering::Relaxed);
+}
+
+/// Whether keygen currently runs the pairwise consistency test.
+#[pyfunction]
+pub fn pairwise_check_enabled() -> bool {
+    enabled()
+}
@@ -0,0 +1,378 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// PQXDH-style asynchronous key agreement with signed KEM prekeys
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// For messaging, where the first message has to be sent while the recipient
+// is offline. Bob publishes prekeys ahead of time; Alice derives a session
+// secret from them alone, and Bob derives the same one when he comes online:
+//
+//   spk, spk_sk = pqxdh_signed_prekey(bob_sk, 1)            # Bob, ahead of time
+//   opk, opk_sk = pqxdh_signed_prekey(bob_sk, 2)            # one-time prekey
+//   bundle = pqxdh_bundle(bob_pk, spk, opk)                 # on the server
+//
+//   msg, secret = pqxdh_initiate(alice_sk, bob_pk, bundle)  # Alice
+//
+//   alice_pk, secret, used = pqxdh_respond(bob_pk, {1: spk_sk, 2: opk_sk}, msg)
+//   del prekey_secrets[used]                                # Bob, if not None
+//
+// This is the shape of Signal's PQXDH (rev. 3), with KEMs throughout: prekeys
+// are KEM keys (ML-KEM-768 unless `kem` names another) signed by the owner's
+// Falcon identity key, and since there is no DH to authenticate Alice, she
+// signs her initial message instead. Integers are big-endian:
+//
+//   prekey  = magic "PQPK" | version 1 | prekey id u32 | name length u8
+//               | KEM name | pk | signature length u16 | signature
+//             signed by Bob as label || "prekey" || prekey up to the signature
+//   bundle  = magic "PQPB" | version 1 | identity pk length u16 | identity pk
+//               | prekey length u16 | signed prekey
+//               | has one-time u8 [| prekey length u16 | one-time prekey]
+//   message = magic "PQXM" | version 1 | identity pk length u16 | identity pk
+//               | count u8 | count x (prekey id u32 | name length u8
+//                                     | KEM name | ct) | signature length u16
+//               | signature
+//             signed by Alice as label || "message" || SHA-256(Bob's identity pk)
+//                                 || message up to the signature
+//
+//   secret = HKDF-SHA256(salt = "", IKM = ss_signed || ss_one_time,
+//                        info = label || SHA-256(Bob's identity pk)
+//                               || SHA-256(message), 32)
+//
+// Prekey ids are Bob's to assign and must be unique across both kinds. Alice
+// checks both prekey signatures and that the bundle is for the identity she
+// expects; Bob checks Alice's signature, and pqxdh_respond() returns her
+// identity key for him to decide whether he trusts it. Without a one-time
+// prekey an initial message can be replayed to Bob, as in PQXDH, so delete
+// each one-time secret key once used. Identity keys are Falcon-512 / 1024,
+// picked by length.
+
+use hkdf::Hkdf;
+use pyo3::exceptions::{PyKeyError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
+
+use crate::buffer::Buffer;
+use crate::errors::{check_length, verification_failed, InvalidLengthError};
+use crate::kem::{find_kem, KemAlgorithm};
+use crate::noise::{decapsulate, encapsulate};
+use crate::recover::falcon_public_from_secret;
+use crate::secret::{secret_bytes, SecretBytes};
+use crate::sign::SignatureAlgorithm;
+use crate::signcrypt::falcon_for;
+use crate::util::Reader;
+
+const PREKEY_MAGIC: &[u8; 4] = b"PQPK";
+const BUNDLE_MAGIC: &[u8; 4] = b"PQPB";
+const MESSAGE_MAGIC: &[u8; 4] = b"PQXM";
+const VERSION: u8 = 1;
+const LABEL: &[u8] = b"entropic-chaos pqxdh v1\0";
+
+const SECRET_LEN: usize = 32;
+
+// ─── Shared ───────────────────────────────────────────────────────────────────
+
+fn read_magic(r: &mut Reader, magic: &[u8; 4], what: &str) -> PyResult<()> {
+    if r.take(magic.len())? != magic {
+        return Err(PyValueError::new_err(format!("not a PQXDH {}", what)));
+    }
+    let version = r.u8()?;
+    if version != VERSION {
+        return Err(PyValueError::new_err(format!(
+            "unsupported PQXDH {} version {}",
+            what, version
+        )));
+    }
+    Ok(())
+}
+
+fn read_kem(r: &mut Reader) -> PyResult<&'static KemAlgorithm> {
+    let name_len = r.u8()? as usize;
+    let name = std::str::from_utf8(r.take(name_len)?)
+        .map_err(|_| PyValueError::new_err("PQXDH KEM name isn't UTF-8"))?;
+    find_kem(name)
+        .filter(|kem| kem.name == name)
+        .ok_or_else(|| PyValueError::new_err(format!("PQXDH uses unknown KEM '{}'", name)))
+}
+
+fn put_kem(out: &mut Vec<u8>, kem: &KemAlgorithm) {
+    out.push(kem.name.len() as u8);
+    out.extend_from_slice(kem.name.as_bytes());
+}
+
+fn put_field(out: &mut Vec<u8>, what: &str, field: &[u8]) -> PyResult<()> {
+    let len = u16::try_from(field.len())
+        .map_err(|_| InvalidLengthError::new_err(format!("{} longer than 65535 bytes", what)))?;
+    out.extend_from_slice(&len.to_be_bytes());
+    out.extend_from_slice(field);
+    Ok(())
+}
+
+fn read_field<'a>(r: &mut Reader<'a>) -> PyResult<&'a [u8]> {
+    let len = r.u16()? as usize;
+    r.take(len)
+}
+
+fn sign(py: Python, alg: &SignatureAlgorithm, sk: &[u8], signed: &[u8]) -> PyResult<Py<PyBytes>> {
+    let sk = PyBytes::new_bound(py, sk);
+    (alg.sign)(py, sk.extract()?, PyBytes::new_bound(py, signed).extract()?)
+}
+
+/// Check a signature by the Falcon key `pk`, raising SignatureVerificationError.
+fn verify(py: Python, pk: &[u8], signed: &[u8], sig: &[u8]) -> PyResult<()> {
+    let alg = falcon_for("identity public key", pk.len(), |alg| alg.public_key_bytes)?;
+    let pk = PyBytes::new_bound(py, pk);
+    let msg = PyBytes::new_bound(py, signed);
+    let sig_obj = PyBytes::new_bound(py, sig);
+    if !(alg.verify)(pk.extract()?, msg.extract()?, sig_obj.extract()?)? {
+        return Err(verification_failed(alg.name, signed.len(), sig.len()));
+    }
+    Ok(())
+}
+
+fn prekey_signed_data(body: &[u8]) -> Vec<u8> {
+    [LABEL, b"prekey", body].concat()
+}
+
+fn message_signed_data(responder_pk: &[u8], body: &[u8]) -> Vec<u8> {
+    [LABEL, b"message", &Sha256::digest(responder_pk)[..], body].concat()
+}
+
+fn derive_secret(
+    py: Python,
+    secrets: &[Zeroizing<Vec<u8>>],
+    responder_pk: &[u8],
+    message: &[u8],
+) -> PyResult<Py<SecretBytes>> {
+    let ikm = Zeroizing::new(
+        secrets
+            .iter()
+            .flat_map(|ss| ss.iter().copied())
+            .collect::<Vec<u8>>(),
+    );
+    let info = [
+        LABEL,
+        &Sha256::digest(responder_pk)[..],
+        &Sha256::digest(message)[..],
+    ]
+    .concat();
+    let mut secret = Zeroizing::new([0u8; SECRET_LEN]);
+    Hkdf::<Sha256>::new(None, &ikm)
+        .expand(&info, secret.as_mut())
+        .expect("32 bytes is a valid HKDF-SHA256 length");
+    secret_bytes(py, &secret[..])
+}
+
+/// A signed prekey, checked against its owner's identity key.
+struct Prekey<'a> {
+    id: u32,
+    kem: &'static KemAlgorithm,
+    pk: &'a [u8],
+}
+
+impl<'a> Prekey<'a> {
+    fn open(py: Python, identity_pk: &[u8], prekey: &'a [u8]) -> PyResult<Self> {
+        let mut r = Reader::new("PQXDH prekey", prekey);
+        read_magic(&mut r, PREKEY_MAGIC, "prekey")?;
+        let id = r.u32()?;
+        let kem = read_kem(&mut r)?;
+        let pk = r.take(kem.public_key_bytes)?;
+        let body_len = PREKEY_MAGIC.len() + 5 + 1 + kem.name.len() + pk.len();
+        let sig = read_field(&mut r)?;
+        r.finish()?;
+        verify(
+            py,
+            identity_pk,
+            &prekey_signed_data(&prekey[..body_len]),
+            sig,
+        )?;
+        Ok(Prekey { id, kem, pk })
+    }
+}
+
+// ─── Prekeys and bundles ──────────────────────────────────────────────────────
+
+/// Generate a KEM prekey numbered `prekey_id`, signed by the identity key
+/// `identity_sk`. Returns (signed prekey to publish, prekey secret key).
+#[pyfunction]
+#[pyo3(signature = (identity_sk, prekey_id, kem = "mlkem768"))]
+pub fn pqxdh_signed_prekey(
+    py: Python,
+    identity_sk: Buffer,
+    prekey_id: u32,
+    kem: &str,
+) -> PyResult<(Py<PyBytes>, Py<SecretBytes>)> {
+    let alg = falcon_for("identity secret key", identity_sk.len(), |alg| {
+        alg.secret_key_bytes
+    })?;
+    let kem =
+        find_kem(kem).ok_or_else(|| PyValueError::new_err(format!("unsupported KEM '{}'", kem)))?;
+    let (pk, sk) = (kem.keygen)(py)?;
+
+    let mut prekey = Vec::new();
+    prekey.extend_from_slice(PREKEY_MAGIC);
+    prekey.push(VERSION);
+    prekey.extend_from_slice(&prekey_id.to_be_bytes());
+    put_kem(&mut prekey, kem);
+    prekey.extend_from_slice(pk.as_bytes(py));
+    let sig = sign(py, alg, &identity_sk, &prekey_signed_data(&prekey))?;
+    put_field(&mut prekey, "signature", sig.as_bytes(py))?;
+    Ok((PyBytes::new_bound(py, &prekey).unbind(), sk))
+}
+
+/// Assemble the bundle a server hands out for `identity_pk`, after checking
+/// that both prekeys are signed by it.
+#[pyfunction]
+#[pyo3(signature = (identity_pk, signed_prekey, one_time_prekey = None))]
+pub fn pqxdh_bundle(
+    py: Python,
+    identity_pk: Buffer,
+    signed_prekey: Buffer,
+    one_time_prekey: Option<Buffer>,
+) -> PyResult<Py<PyBytes>> {
+    Prekey::open(py, &identity_pk, &signed_prekey)?;
+    if let Some(one_time) = &one_time_prekey {
+        Prekey::open(py, &identity_pk, one_time)?;
+    }
+    let mut bundle = Vec::new();
+    bundle.extend_from_slice(BUNDLE_MAGIC);
+    bundle.push(VERSION);
+    put_field(&mut bundle, "identity public key", &identity_pk)?;
+    put_field(&mut bundle, "prekey", &signed_prekey)?;
+    match &one_time_prekey {
+        Some(one_time) => {
+            bundle.push(1);
+            put_field(&mut bundle, "prekey", one_time)?;
+        }
+        None => bundle.push(0),
+    }
+    Ok(PyBytes::new_bound(py, &bundle).unbind())
+}
+
+// ─── Key agreement ────────────────────────────────────────────────────────────
+
+/// Start a session with the owner of `responder_identity_pk` from their
+/// `bundle`. Returns (initial message to send, 32-byte session secret).
+#[pyfunction]
+pub fn pqxdh_initiate(
+    py: Python,
+    identity_sk: Buffer,
+    responder_identity_pk: Buffer,
+    bundle: Buffer,
+) -> PyResult<(Py<PyBytes>, Py<SecretBytes>)> {
+    let alg = falcon_for("identity secret key", identity_sk.len(), |alg| {
+        alg.secret_key_bytes
+    })?;
+    let identity_pk =
+        falcon_public_from_secret(py, PyBytes::new_bound(py, &identity_sk).extract()?)?;
+
+    let mut r = Reader::new("PQXDH bundle", &bundle);
+    read_magic(&mut r, BUNDLE_MAGIC, "bundle")?;
+    if read_field(&mut r)? != &responder_identity_pk[..] {
+        return Err(PyValueError::new_err(
+            "PQXDH bundle is for a different identity key",
+        ));
+    }
+    let mut prekeys = vec![Prekey::open(
+        py,
+        &responder_identity_pk,
+        read_field(&mut r)?,
+    )?];
+    match r.u8()? {
+        0 => {}
+        1 => prekeys.push(Prekey::open(
+            py,
+            &responder_identity_pk,
+            read_field(&mut r)?,
+        )?),
+        _ => return Err(PyValueError::new_err("malformed PQXDH bundle")),
+    }
+    r.finish()?;
+
+    let mut message = Vec::new();
+    message.extend_from_slice(MESSAGE_MAGIC);
+    message.push(VERSION);
+    put_field(
+        &mut message,
+        "identity public key",
+        identity_pk.as_bytes(py),
+    )?;
+    message.push(prekeys.len() as u8);
+    let mut secrets = Vec::with_capacity(prekeys.len());
+    for prekey in &prekeys {
+        let (ct, ss) = encapsulate(py, prekey.kem, prekey.pk)?;
+        message.extend_from_slice(&prekey.id.to_be_bytes());
+        put_kem(&mut message, prekey.kem);
+        message.extend_from_slice(&ct);
+        secrets.push(ss);
+    }
+    let signed = message_signed_data(&responder_identity_pk, &message);
+    let sig = sign(py, alg, &identity_sk, &signed)?;
+    put_field(&mut message, "signature", sig.as_bytes(py))?;
+
+    let secret = derive_secret(py, &secrets, &responder_identity_pk, &message)?;
+    Ok((PyBytes::new_bound(py, &message).unbind(), secret))
+}
+
+/// Complete a session from an initial message, with `prekeys` mapping each
+/// prekey id to its secret key. Returns (initiator's identity public key,
+/// session secret, id of the one-time prekey used or None).
+// stub: prekeys: Mapping[int, Buffer]
+#[pyfunction]
+pub fn pqxdh_respond(
+    py: Python,
+    identity_pk: Buffer,
+    prekeys: &Bound<'_, PyAny>,
+    message: Buffer,
+) -> PyResult<(Py<PyBytes>, Py<SecretBytes>, Option<u32>)> {
+    let mut r = Reader::new("PQXDH message", &message);
+    read_magic(&mut r, MESSAGE_MAGIC, "message")?;
+    let initiator_pk = read_field(&mut r)?;
+    let count = r.u8()?;
+    if !(1..=2).contains(&count) {
+        return Err(PyValueError::new_err("malformed PQXDH message"));
+    }
+    let mut used = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        let id = r.u32()?;
+        let kem = read_kem(&mut r)?;
+        let ct = r.take(kem.ciphertext_bytes)?;
+        used.push((id, kem, ct));
+    }
+    let body_len = message.len() - r.rest().len();
+    let mut r = Reader::new("PQXDH message", &message[body_len..]);
+    let sig = read_field(&mut r)?;
+    r.finish()?;
+    verify(
+        py,
+        initiator_pk,
+        &message_signed_data(&identity_pk, &message[..body_len]),
+        sig,
+    )?;
+
+    let mut secrets = Vec::with_capacity(used.len());
+    for &(id, kem, ct) in &used {
+        let sk: Buffer = prekeys
+            .get_item(id)
+            .map_err(|e| {
+                if e.is_instance_of::<PyKeyError>(py) {
+                    PyValueError::new_err(format!("no secret key for PQXDH prekey {}", id))
+                } else {
+                    e
+                }
+            })?
+            .extract()?;
+        check_length(
+            &format!("{} prekey {} secret key", kem.name, id),
+            &sk,
+            kem.secret_key_bytes,
+        )?;
+        secrets.push(decapsulate(py, kem, &sk, ct)?);
+    }
+    let secret = derive_secret(py, &secrets, &identity_pk, &message)?;
+    Ok((
+        PyBytes::new_bound(py, initiator_pk).unbind(),
+        secret,
+        used.get(1).map(|&(id, _, _)| id),
+    ))
+}
@@ -0,0 +1,359 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// Prehash Falcon signatures
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// For artifacts too big to hand over whole, or hashed on another machine:
+//
+//   digest = hashlib.sha512(open("image.iso", "rb").read()).digest()
+//   sig = falcon_sign_prehashed(sk, digest, "sha512")
+//   falcon_verify_prehashed(pk, digest, sig, "sha512")      # -> bool
+//
+// Falcon then signs
+//
+//   label || DER OID of the hash || digest
+//
+// after HashML-DSA (FIPS 204, section 5.4): naming the hash keeps a digest
+// from being verified as a digest under another hash of the same length, and
+// the label keeps a prehash signature from verifying as a plain
+// falcon_sign() signature over some message, and the other way round. So a
+// prehashed signature only verifies with falcon_verify_prehashed().
+//
+// To sign a file or socket stream without holding it in memory, Signer and
+// Verifier hash as data arrives and produce or check the same signatures:
+//
+//   signer = Signer(sk, "sha512")
+//   for chunk in iter(lambda: f.read(1 << 20), b""):
+//       signer.update(chunk)
+//   sig = signer.finalize()
+//
+//   verifier = Verifier(pk, "sha512")
+//   verifier.update(data)
+//   verifier.verify(sig)                                   # -> bool
+//
+// hash_alg is one of sha256, sha384, sha512, sha3-256, sha3-512 or shake256
+// (64 bytes of output); hashlib's names ("sha3_256") are accepted too. The
+// digest must be that hash's full output length. Falcon-512 / 1024 are picked
+// by key length.
+
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use zeroize::Zeroizing;
+
+use crate::buffer::Buffer;
+use crate::errors::{check_length, verification_failed};
+use crate::kem::normalize_name;
+use crate::sign::SignatureAlgorithm;
+use crate::signcrypt::falcon_for;
+use crate::xof::{Sha3, ShakeVariant, Sponge};
+
+const LABEL: &[u8] = b"entropic-chaos Falcon prehash v1\0";
+
+struct PrehashAlgorithm {
+    name: &'static str,
+    digest_len: usize,
+    /// DER encoding of the OID, tag and length included.
+    oid: &'static [u8],
+}
+
+/// The NIST hash algorithm OIDs, 2.16.840.1.101.3.4.2.x.
+const fn nist_hash(name: &'static str, digest_len: usize, oid: &'static [u8]) -> PrehashAlgorithm {
+    PrehashAlgorithm {
+        name,
+        digest_len,
+        oid,
+    }
+}
+
+const PREHASHES: &[PrehashAlgorithm] = &[
+    nist_hash("sha256", 32, &[6, 9, 96, 134, 72, 1, 101, 3, 4, 2, 1]),
+    nist_hash("sha384", 48, &[6, 9, 96, 134, 72, 1, 101, 3, 4, 2, 2]),
+    nist_hash("sha512", 64, &[6, 9, 96, 134, 72, 1, 101, 3, 4, 2, 3]),
+    nist_hash("sha3-256", 32, &[6, 9, 96, 134, 72, 1, 101, 3, 4, 2, 8]),
+    nist_hash("sha3-512", 64, &[6, 9, 96, 134, 72, 1, 101, 3, 4, 2, 10]),
+    nist_hash("shake256", 64, &[6, 9, 96, 134, 72, 1, 101, 3, 4, 2, 12]),
+];
+
+fn find_prehash(name: &str) -> PyResult<&'static PrehashAlgorithm> {
+    let wanted = normalize_name(name);
+    PREHASHES
+        .iter()
+        .find(|h| normalize_name(h.name) == wanted)
+        .ok_or_else(|| {
+            PyValueError::new_err(format!(
+                "unsupported prehash algorithm '{}', expected sha256, sha384, sha512, sha3-256, \
+             sha3-512 or shake256",
+                name
+            ))
+        })
+}
+
+/// What Falcon signs for `digest`, after checking its length.
+fn prehash_message<'py>(
+    py: Python<'py>,
+    digest: &[u8],
+    hash_alg: &str,
+) -> PyResult<Bound<'py, PyBytes>> {
+    let hash = find_prehash(hash_alg)?;
+    check_length(&format!("{} digest", hash_alg), digest, hash.digest_len)?;
+    Ok(PyBytes::new_bound(py, &[LABEL, hash.oid, digest].concat()))
+}
+
+/// Sign a digest computed with `hash_alg`, using a Falcon-512 or Falcon-1024
+/// secret key.
+#[pyfunction]
+pub fn falcon_sign_prehashed(
+    py: Python,
+    sk_bytes: Buffer,
+    digest: Buffer,
+    hash_alg: &str,
+) -> PyResult<Py<PyBytes>> {
+    let alg = falcon_for("secret key", sk_bytes.len(), |alg| alg.secret_key_bytes)?;
+    let m = prehash_message(py, &digest, hash_alg)?;
+    (alg.sign)(py, sk_bytes, m.extract()?)
+}
+
+/// Check a falcon_sign_prehashed() signature over `digest`.
+#[pyfunction]
+pub fn falcon_verify_prehashed(
+    py: Python,
+    pk_bytes: Buffer,
+    digest: Buffer,
+    sig_bytes: Buffer,
+    hash_alg: &str,
+) -> PyResult<bool> {
+    let alg = falcon_for("public key", pk_bytes.len(), |alg| alg.public_key_bytes)?;
+    let m = prehash_message(py, &digest, hash_alg)?;
+    (alg.verify)(pk_bytes, m.extract()?, sig_bytes)
+}
+
+/// falcon_verify_prehashed() that raises SignatureVerificationError instead of
+/// returning False.
+#[pyfunction]
+pub fn falcon_verify_prehashed_strict(
+    py: Python,
+    pk_bytes: Buffer,
+    digest: Buffer,
+    sig_bytes: Buffer,
+    hash_alg: &str,
+) -> PyResult<()> {
+    let alg = falcon_for("public key", pk_bytes.len(), |alg| alg.public_key_bytes)?;
+    let (digest_len, sig_len) = (digest.len(), sig_bytes.len());
+    if falcon_verify_prehashed(py, pk_bytes, digest, sig_bytes, hash_alg)? {
+        Ok(())
+    } else {
+        Err(verification_failed(alg.name, digest_len, sig_len))
+    }
+}
+
+// ─── Incremental Signer / Verifier ────────────────────────────────────────────
+
+enum Hasher {
+    Sha256(Sha256),
+    Sha384(Sha384),
+    Sha512(Sha512),
+    Sha3(Sha3),
+    Shake256(Sponge),
+}
+
+impl Hasher {
+    fn new(hash: &PrehashAlgorithm) -> Self {
+        match hash.name {
+            "sha256" => Hasher::Sha256(Sha256::new()),
+            "sha384" => Hasher::Sha384(Sha384::new()),
+            "sha512" => Hasher::Sha512(Sha512::new()),
+            "sha3-256" => Hasher::Sha3(Sha3::new(false)),
+            "sha3-512" => Hasher::Sha3(Sha3::new(true)),
+            "shake256" => Hasher::Shake256(Sponge::new(ShakeVariant::Shake256)),
+            _ => unreachable!("every PREHASHES entry has a hasher"),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Hasher::Sha256(h) => h.update(data),
+            Hasher::Sha384(h) => h.update(data),
+            Hasher::Sha512(h) => h.update(data),
+            Hasher::Sha3(h) => h.absorb(data),
+            Hasher::Shake256(h) => h.absorb(data),
+        }
+    }
+
+    fn finalize(self, digest_len: usize) -> Vec<u8> {
+        match self {
+            Hasher::Sha256(h) => h.finalize().to_vec(),
+            Hasher::Sha384(h) => h.finalize().to_vec(),
+            Hasher::Sha512(h) => h.finalize().to_vec(),
+            Hasher::Sha3(h) => h.finalize(),
+            Hasher::Shake256(mut h) => {
+                let mut out = vec![0u8; digest_len];
+                h.squeeze(&mut out);
+                out
+            }
+        }
+    }
+}
+
+/// The digest being built, until finalize() / verify() takes it.
+struct Digesting {
+    hash: &'static PrehashAlgorithm,
+    hasher: Option<Hasher>,
+}
+
+impl Digesting {
+    fn new(hash_alg: &str) -> PyResult<Self> {
+        let hash = find_prehash(hash_alg)?;
+        Ok(Digesting {
+            hash,
+            hasher: Some(Hasher::new(hash)),
+        })
+    }
+
+    fn update(&mut self, data: &[u8], done: &str) -> PyResult<()> {
+        match &mut self.hasher {
+            Some(hasher) => {
+                hasher.update(data);
+                Ok(())
+            }
+            None => Err(PyValueError::new_err(format!(
+                "can't update() after {}",
+                done
+            ))),
+        }
+    }
+
+    /// What Falcon signs for the data so far.
+    fn message<'py>(&mut self, py: Python<'py>, done: &str) -> PyResult<Bound<'py, PyBytes>> {
+        let hasher = self
+            .hasher
+            .take()
+            .ok_or_else(|| PyValueError::new_err(format!("{} was already called", done)))?;
+        let digest = hasher.finalize(self.hash.digest_len);
+        Ok(PyBytes::new_bound(
+            py,
+            &[LABEL, self.hash.oid, &digest].concat(),
+        ))
+    }
+}
+
+#[pyclass(module = "pqcrypto_bindings")]
+pub struct Signer {
+    alg: &'static SignatureAlgorithm,
+    sk: Zeroizing<Vec<u8>>,
+    digesting: Digesting,
+}
+
+#[pymethods]
+impl Signer {
+    /// Sign data fed through update() with a Falcon-512 or Falcon-1024
+    /// secret key, prehashed with `hash_alg`.
+    #[new]
+    #[pyo3(signature = (sk_bytes, hash_alg = "sha512"))]
+    fn new(sk_bytes: Buffer, hash_alg: &str) -> PyResult<Self> {
+        let alg = falcon_for("secret key", sk_bytes.len(), |alg| alg.secret_key_bytes)?;
+        Ok(Signer {
+            alg,
+            sk: Zeroizing::new(sk_bytes.to_vec()),
+            digesting: Digesting::new(hash_alg)?,
+        })
+    }
+
+    #[getter]
+    fn algorithm(&self) -> &'static str {
+        self.alg.name
+    }
+
+    fn update(&mut self, data: Buffer) -> PyResult<()> {
+        self.digesting.update(&data, "finalize()")
+    }
+
+    /// The signature over everything passed to update(); the same as
+    /// falcon_sign_prehashed() over its digest. Only callable once.
+    fn finalize(&mut self, py: Python) -> PyResult<Py<PyBytes>> {
+        let m = self.digesting.message(py, "finalize()")?;
+        let sk = PyBytes::new_bound(py, &self.sk);
+        (self.alg.sign)(py, sk.extract()?, m.extract()?)
+    }
+
+    fn __reduce__(&self) -> PyResult<()> {
+        Err(PyTypeError::new_err(
+            "cannot pickle 'Signer': it holds secret key material and a hash state",
+        ))
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Signer('{}', '{}')",
+            self.alg.name, self.digesting.hash.name
+        )
+    }
+}
+
+#[pyclass(module = "pqcrypto_bindings")]
+pub struct Verifier {
+    alg: &'static SignatureAlgorithm,
+    pk: Vec<u8>,
+    digesting: Digesting,
+}
+
+impl Verifier {
+    fn check(&mut self, py: Python, sig_bytes: Buffer) -> PyResult<bool> {
+        let m = self.digesting.message(py, "verify()")?;
+        let pk = PyBytes::new_bound(py, &self.pk);
+        (self.alg.verify)(pk.extract()?, m.extract()?, sig_bytes)
+    }
+}
+
+#[pymethods]
+impl Verifier {
+    /// Check a Signer / falcon_sign_prehashed() signature over data fed
+    /// through update().
+    #[new]
+    #[pyo3(signature = (pk_bytes, hash_alg = "sha512"))]
+    fn new(pk_bytes: Buffer, hash_alg: &str) -> PyResult<Self> {
+        let alg = falcon_for("public key", pk_bytes.len(), |alg| alg.public_key_bytes)?;
+        Ok(Verifier {
+            alg,
+            pk: pk_bytes.to_vec(),
+            digesting: Digesting::new(hash_alg)?,
+        })
+    }
+
+    #[getter]
+    fn algorithm(&self) -> &'static str {
+        self.alg.name
+    }
+
+    fn update(&mut self, data: Buffer) -> PyResult<()> {
+        self.digesting.update(&data, "verify()")
+    }
+
+    /// Whether `sig_bytes` signs everything passed to update(). Only
+    /// callable once.
+    fn verify(&mut self, py: Python, sig_bytes: Buffer) -> PyResult<bool> {
+        self.check(py, sig_bytes)
+    }
+
+    /// verify() that raises SignatureVerificationError instead of returning
+    /// False.
+    fn verify_strict(&mut self, py: Python, sig_bytes: Buffer) -> PyResult<()> {
+        let sig_len = sig_bytes.len();
+        if self.check(py, sig_bytes)? {
+            Ok(())
+        } else {
+            Err(verification_failed(
+                self.alg.name,
+                self.digesting.hash.digest_len,
+                sig_len,
+            ))
+        }
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Verifier('{}', '{}')",
+            self.alg.name, self.digesting.hash.name
+        )
+    }
+}
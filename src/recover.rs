@@ -0,0 +1,217 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// Public key recovery from a secret key
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// For backups that only kept the secret key:
+//
+//   pk = kyber_public_from_secret(sk)
+//   pk = falcon_public_from_secret(sk)
+//
+// Parameter sets are told apart by length, as elsewhere. A Kyber secret key
+// is s || pk || H(pk) || z, so its public key is copied out, after checking
+// it against the stored SHA3-256 hash. A Falcon secret key is (f, g, F); the
+// public key is h = g / f mod q, computed with PQClean's own routine and
+// encoded as crypto_sign_keypair would.
+
+use std::os::raw::{c_int, c_uint};
+
+use pqcrypto_falcon::{falcon1024, falcon512};
+use pqcrypto_kyber::{kyber1024, kyber512, kyber768};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use zeroize::Zeroizing;
+
+use crate::buffer::Buffer;
+use crate::errors::{InvalidKeyError, InvalidLengthError};
+
+extern "C" {
+    fn sha3_256(output: *mut u8, input: *const u8, inlen: usize);
+}
+
+// ─── Kyber ────────────────────────────────────────────────────────────────────
+
+/// The public key embedded in a Kyber-512 / 768 / 1024 secret key.
+#[pyfunction]
+pub fn kyber_public_from_secret(py: Python, sk_bytes: Buffer) -> PyResult<Py<PyBytes>> {
+    let pk_len = [
+        (kyber512::secret_key_bytes(), kyber512::public_key_bytes()),
+        (kyber768::secret_key_bytes(), kyber768::public_key_bytes()),
+        (kyber1024::secret_key_bytes(), kyber1024::public_key_bytes()),
+    ]
+    .into_iter()
+    .find(|&(sk_len, _)| sk_len == sk_bytes.len())
+    .map(|(_, pk_len)| pk_len)
+    .ok_or_else(|| {
+        InvalidLengthError::new_err(format!(
+            "Kyber secret key: expected {}, {} or {} bytes, got {}",
+            kyber512::secret_key_bytes(),
+            kyber768::secret_key_bytes(),
+            kyber1024::secret_key_bytes(),
+            sk_bytes.len()
+        ))
+    })?;
+
+    // s || pk || H(pk) || z, with H(pk) and z 32 bytes each.
+    let start = sk_bytes.len() - 64 - pk_len;
+    let pk = &sk_bytes[start..start + pk_len];
+    let stored_hash = &sk_bytes[start + pk_len..start + pk_len + 32];
+
+    let mut hash = [0u8; 32];
+    unsafe { sha3_256(hash.as_mut_ptr(), pk.as_ptr(), pk.len()) };
+    if hash != stored_hash {
+        return Err(InvalidKeyError::new_err(
+            "Kyber secret key: embedded public key doesn't match its stored hash",
+        ));
+    }
+
+    Ok(PyBytes::new_bound(py, pk).unbind())
+}
+
+// ─── Falcon ───────────────────────────────────────────────────────────────────
+
+/// Decode f, g and F from a Falcon secret key (as crypto_sign_signature
+/// does) and write the matching public key. False if the key doesn't decode
+/// or f isn't invertible.
+macro_rules! falcon_public_from_secret {
+    (
+        $fn_name:ident,
+        $logn:expr,
+        $trim_i8_decode:ident,
+        $compute_public:ident,
+        $modq_encode:ident,
+        $max_fg_bits:ident,
+        $max_big_fg_bits:ident
+    ) => {
+        fn $fn_name(sk: &[u8], pk: &mut [u8]) -> bool {
+            const N: usize = 1 << $logn;
+
+            extern "C" {
+                fn $trim_i8_decode(
+                    x: *mut i8,
+                    logn: c_uint,
+                    bits: c_uint,
+                    input: *const u8,
+                    max_in_len: usize,
+                ) -> usize;
+                fn $compute_public(
+                    h: *mut u16,
+                    f: *const i8,
+                    g: *const i8,
+                    logn: c_uint,
+                    tmp: *mut u8,
+                ) -> c_int;
+                fn $modq_encode(
+                    out: *mut u8,
+                    max_out_len: usize,
+                    x: *const u16,
+                    logn: c_uint,
+                ) -> usize;
+                static $max_fg_bits: [u8; 11];
+                static $max_big_fg_bits: [u8; 11];
+            }
+
+            if sk[0] != 0x50 + $logn as u8 {
+                return false;
+            }
+
+            let mut f = Zeroizing::new([0i8; N]);
+            let mut g = Zeroizing::new([0i8; N]);
+            let mut big_f = Zeroizing::new([0i8; N]);
+            let (fg_bits, big_fg_bits) = unsafe {
+                (
+                    $max_fg_bits[$logn] as c_uint,
+                    $max_big_fg_bits[$logn] as c_uint,
+                )
+            };
+
+            let mut u = 1;
+            for (x, bits) in [
+                (&mut f, fg_bits),
+                (&mut g, fg_bits),
+                (&mut big_f, big_fg_bits),
+            ] {
+                let v = unsafe {
+                    $trim_i8_decode(x.as_mut_ptr(), $logn, bits, sk[u..].as_ptr(), sk.len() - u)
+                };
+                if v == 0 {
+                    return false;
+                }
+                u += v;
+            }
+            if u != sk.len() {
+                return false;
+            }
+
+            let mut h = [0u16; N];
+            // compute_public() uses N u16s of scratch space.
+            let mut tmp = Zeroizing::new([0u16; N]);
+            let ok = unsafe {
+                $compute_public(
+                    h.as_mut_ptr(),
+                    f.as_ptr(),
+                    g.as_ptr(),
+                    $logn,
+                    tmp.as_mut_ptr().cast(),
+                )
+            };
+            if ok == 0 {
+                return false;
+            }
+
+            pk[0] = $logn as u8;
+            let v = unsafe { $modq_encode(pk[1..].as_mut_ptr(), pk.len() - 1, h.as_ptr(), $logn) };
+            v == pk.len() - 1
+        }
+    };
+}
+
+falcon_public_from_secret!(
+    falcon512_public_from_secret,
+    9,
+    PQCLEAN_FALCON512_CLEAN_trim_i8_decode,
+    PQCLEAN_FALCON512_CLEAN_compute_public,
+    PQCLEAN_FALCON512_CLEAN_modq_encode,
+    PQCLEAN_FALCON512_CLEAN_max_fg_bits,
+    PQCLEAN_FALCON512_CLEAN_max_FG_bits
+);
+
+falcon_public_from_secret!(
+    falcon1024_public_from_secret,
+    10,
+    PQCLEAN_FALCON1024_CLEAN_trim_i8_decode,
+    PQCLEAN_FALCON1024_CLEAN_compute_public,
+    PQCLEAN_FALCON1024_CLEAN_modq_encode,
+    PQCLEAN_FALCON1024_CLEAN_max_fg_bits,
+    PQCLEAN_FALCON1024_CLEAN_max_FG_bits
+);
+
+/// The public key for a Falcon-512 / 1024 secret key.
+#[pyfunction]
+pub fn falcon_public_from_secret(py: Python, sk_bytes: Buffer) -> PyResult<Py<PyBytes>> {
+    type PublicFn = fn(&[u8], &mut [u8]) -> bool;
+    let (derive, pk_len): (PublicFn, usize) = if sk_bytes.len() == falcon512::secret_key_bytes() {
+        (falcon512_public_from_secret, falcon512::public_key_bytes())
+    } else if sk_bytes.len() == falcon1024::secret_key_bytes() {
+        (
+            falcon1024_public_from_secret,
+            falcon1024::public_key_bytes(),
+        )
+    } else {
+        return Err(InvalidLengthError::new_err(format!(
+            "Falcon secret key: expected {} or {} bytes, got {}",
+            falcon512::secret_key_bytes(),
+            falcon1024::secret_key_bytes(),
+            sk_bytes.len()
+        )));
+    };
+
+    let pk = PyBytes::new_bound_with(py, pk_len, |pk| {
+        if !derive(&sk_bytes, pk) {
+            return Err(InvalidKeyError::new_err(
+                "Falcon secret key doesn't decode to a valid key",
+            ));
+        }
+        Ok(())
+    })?;
+    Ok(pk.unbind())
+}
@@ -0,0 +1,529 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// Session resumption tickets for SecureChannel
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// For clients that reconnect often: instead of a full key exchange, the
+// client presents a ticket from an earlier channel and both sides derive
+// fresh keys in one round trip, with no public-key operations:
+//
+//   ticket = server_chan.issue_ticket(ticket_key)          # server, sent over
+//   saved = client_chan.accept_ticket(ticket)              #   the old channel
+//
+//   client = ResumeInitiator(saved)                        # later
+//   server = ResumeResponder(ticket_key)
+//   client.finish(server.respond(client.message))
+//   chan = SecureChannel.from_handshake(client)
+//
+// `ticket_key` is a 32-byte server secret; rotate it to revoke every ticket.
+// Both ends of a channel hold the same resumption secret, derived from its
+// initial keys, and each ticket carries a pre-shared key (PSK) derived from
+// it as in TLS 1.3 (RFC 8446, section 4.6.1):
+//
+//   resumption = HKDF-SHA256(salt = "", IKM = keys in byte order,
+//                            info = label || "resumption", 32)
+//   psk        = HKDF-SHA256(salt = "", IKM = resumption,
+//                            info = label || "psk" || ticket nonce, 32)
+//
+// The server's copy of the PSK travels inside the ticket, so the server
+// keeps no per-client state:
+//
+//   ticket = magic "PQRT" | version 1 | lifetime u32 | age_add u32
+//              | ticket nonce (16) | sealed length u16 | sealed
+//   sealed = XChaCha20-Poly1305(ticket_key, random nonce,
+//                               issued_at u64 | lifetime u32 | age_add u32 | psk)
+//
+//   hello  = magic "PQRH" | version 1 | sealed length u16 | sealed
+//              | obfuscated age u32 | client random (32) | binder (32)
+//   reply  = magic "PQRS" | version 1 | server random (32) | finished (32)
+//
+// binder is HMAC-SHA256 keyed from the PSK over the hello so far, and
+// finished likewise over SHA-256(hello || reply so far): a hello proves the
+// client holds the ticket's PSK, a reply that the server could open it. The
+// session keys are
+//
+//   session_id = SHA-256(label || hello || reply)
+//   key        = HKDF-SHA256(salt = session_id, IKM = psk,
+//                            info = label || direction, 32)
+//
+// with directions as in ake.rs. Anti-replay follows TLS 1.3 (section 8.3):
+// the client sends how long it has held the ticket, masked with age_add so
+// hellos can't be linked by it, and the server rejects the hello with
+// KeyPolicyError unless that matches its own view of the ticket's age to
+// within `max_skew` seconds, or if the ticket has outlived `lifetime`. That
+// bounds a replay to a few seconds; servers that must rule it out entirely
+// should also remember client randoms for that long. Resumption reuses the
+// PSK, so it has no forward secrecy with respect to `ticket_key` beyond what
+// rotating that key gives. A ResumptionTicket holds the PSK: store its
+// to_bytes() as a secret. None of the classes pickle. Times are Unix
+// seconds; `now` defaults to the current time.
+
+use hkdf::hmac::{Hmac, KeyInit, Mac};
+use hkdf::Hkdf;
+use pyo3::exceptions::{PyRuntimeError, PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
+
+use crate::aead::{Aead, KEY_LEN};
+use crate::ake::Session;
+use crate::buffer::Buffer;
+use crate::errors::{check_length, DecryptionError, KeyPolicyError};
+use crate::metadata::now as current_time;
+use crate::secret::{secret_bytes, secret_bytes_with, SecretBytes};
+use crate::util::{ct_eq, Reader};
+
+const TICKET_MAGIC: &[u8; 4] = b"PQRT";
+const HELLO_MAGIC: &[u8; 4] = b"PQRH";
+const REPLY_MAGIC: &[u8; 4] = b"PQRS";
+const SAVED_MAGIC: &[u8; 4] = b"PQRC";
+const VERSION: u8 = 1;
+const LABEL: &[u8] = b"entropic-chaos resumption v1\0";
+
+const NONCE_LEN: usize = 16;
+const RANDOM_LEN: usize = 32;
+const MAC_LEN: usize = 32;
+const SEALED_LEN: usize = 24 + 8 + 4 + 4 + KEY_LEN + 16;
+
+pub(crate) const DEFAULT_LIFETIME: u32 = 86400;
+const DEFAULT_MAX_SKEW: u64 = 10;
+
+const INITIATOR_TO_RESPONDER: &[u8] = b"initiator to responder";
+const RESPONDER_TO_INITIATOR: &[u8] = b"responder to initiator";
+
+// ─── Key schedule ─────────────────────────────────────────────────────────────
+
+fn expand(ikm: &[u8], salt: Option<&[u8]>, info: &[&[u8]]) -> Zeroizing<[u8; KEY_LEN]> {
+    let mut out = Zeroizing::new([0u8; KEY_LEN]);
+    let mut components = vec![LABEL];
+    components.extend_from_slice(info);
+    Hkdf::<Sha256>::new(salt, ikm)
+        .expand_multi_info(&components, out.as_mut())
+        .expect("32 bytes is a valid HKDF-SHA256 length");
+    out
+}
+
+/// The resumption secret of a channel with these initial keys; the same on
+/// both ends, whichever way round they hold them.
+pub(crate) fn resumption_secret(send: &[u8], recv: &[u8]) -> Zeroizing<[u8; KEY_LEN]> {
+    let (lo, hi) = if send <= recv {
+        (send, recv)
+    } else {
+        (recv, send)
+    };
+    let ikm = Zeroizing::new([lo, hi].concat());
+    expand(&ikm, None, &[b"resumption"])
+}
+
+fn ticket_psk(resumption: &[u8], nonce: &[u8]) -> Zeroizing<[u8; KEY_LEN]> {
+    expand(resumption, None, &[b"psk", nonce])
+}
+
+fn mac(psk: &[u8], purpose: &[u8], data: &[u8]) -> [u8; MAC_LEN] {
+    let key = expand(psk, None, &[purpose]);
+    let mut mac =
+        <Hmac<Sha256> as KeyInit>::new_from_slice(&key[..]).expect("HMAC takes keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().into()
+}
+
+fn random<const N: usize>() -> PyResult<[u8; N]> {
+    let mut out = [0u8; N];
+    getrandom::fill(&mut out).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+    Ok(out)
+}
+
+fn derive_session(psk: &[u8], hello: &[u8], reply: &[u8], initiator: bool) -> Session {
+    let id: [u8; 32] = Sha256::new()
+        .chain_update(LABEL)
+        .chain_update(hello)
+        .chain_update(reply)
+        .finalize()
+        .into();
+    let i2r = expand(psk, Some(&id), &[INITIATOR_TO_RESPONDER]);
+    let r2i = expand(psk, Some(&id), &[RESPONDER_TO_INITIATOR]);
+    let (send, recv) = if initiator { (i2r, r2i) } else { (r2i, i2r) };
+    Session { id, send, recv }
+}
+
+fn read_magic(r: &mut Reader, magic: &[u8; 4], what: &str) -> PyResult<()> {
+    if r.take(magic.len())? != magic {
+        return Err(PyValueError::new_err(format!("not a resumption {}", what)));
+    }
+    let version = r.u8()?;
+    if version != VERSION {
+        return Err(PyValueError::new_err(format!(
+            "unsupported resumption {} version {}",
+            what, version
+        )));
+    }
+    Ok(())
+}
+
+// ─── Tickets ──────────────────────────────────────────────────────────────────
+
+/// A ticket for the client of the channel with `resumption` as its
+/// resumption secret; SecureChannel.issue_ticket().
+pub(crate) fn issue_ticket(
+    resumption: &[u8],
+    ticket_key: &[u8],
+    lifetime: u32,
+    now: u64,
+) -> PyResult<Vec<u8>> {
+    check_length("ticket key", ticket_key, KEY_LEN)?;
+    let nonce: [u8; NONCE_LEN] = random()?;
+    let age_add = u32::from_be_bytes(random()?);
+    let psk = ticket_psk(resumption, &nonce);
+
+    let aead_nonce: [u8; 24] = random()?;
+    let mut sealed = Zeroizing::new(Vec::with_capacity(SEALED_LEN));
+    sealed.extend_from_slice(&now.to_be_bytes());
+    sealed.extend_from_slice(&lifetime.to_be_bytes());
+    sealed.extend_from_slice(&age_add.to_be_bytes());
+    sealed.extend_from_slice(&psk[..]);
+    Aead::XChaCha20Poly1305.encrypt(ticket_key, &aead_nonce, TICKET_MAGIC, &mut sealed)?;
+
+    let mut ticket = Vec::new();
+    ticket.extend_from_slice(TICKET_MAGIC);
+    ticket.push(VERSION);
+    ticket.extend_from_slice(&lifetime.to_be_bytes());
+    ticket.extend_from_slice(&age_add.to_be_bytes());
+    ticket.extend_from_slice(&nonce);
+    ticket.extend_from_slice(&(SEALED_LEN as u16).to_be_bytes());
+    ticket.extend_from_slice(&aead_nonce);
+    ticket.extend_from_slice(&sealed);
+    Ok(ticket)
+}
+
+/// What the client keeps of a ticket; SecureChannel.accept_ticket().
+#[pyclass(module = "pqcrypto_bindings")]
+pub struct ResumptionTicket {
+    sealed: Vec<u8>,
+    psk: Zeroizing<[u8; KEY_LEN]>,
+    lifetime: u32,
+    age_add: u32,
+    received_at: u64,
+}
+
+impl ResumptionTicket {
+    pub(crate) fn accept(resumption: &[u8], ticket: &[u8], now: u64) -> PyResult<Self> {
+        let mut r = Reader::new("resumption ticket", ticket);
+        read_magic(&mut r, TICKET_MAGIC, "ticket")?;
+        let lifetime = r.u32()?;
+        let age_add = r.u32()?;
+        let nonce = r.take(NONCE_LEN)?;
+        let sealed_len = r.u16()? as usize;
+        let sealed = r.take(sealed_len)?.to_vec();
+        r.finish()?;
+        Ok(ResumptionTicket {
+            sealed,
+            psk: ticket_psk(resumption, nonce),
+            lifetime,
+            age_add,
+            received_at: now,
+        })
+    }
+
+    fn check_fresh(&self, now: u64) -> PyResult<()> {
+        if now > self.expires_at() {
+            return Err(KeyPolicyError::new_err(format!(
+                "resumption ticket expired at {}",
+                self.expires_at()
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[pymethods]
+impl ResumptionTicket {
+    /// Ticket lifetime in seconds, as set by the server.
+    #[getter]
+    fn lifetime(&self) -> u32 {
+        self.lifetime
+    }
+
+    #[getter]
+    fn received_at(&self) -> u64 {
+        self.received_at
+    }
+
+    #[getter]
+    fn expires_at(&self) -> u64 {
+        self.received_at.saturating_add(self.lifetime as u64)
+    }
+
+    /// Serialize for storage. The result holds the ticket's PSK, so it is
+    /// returned as SecretBytes and must be stored as a secret.
+    fn to_bytes(&self, py: Python) -> PyResult<Py<SecretBytes>> {
+        let len = SAVED_MAGIC.len() + 1 + 16 + 2 + self.sealed.len() + KEY_LEN;
+        secret_bytes_with(py, len, |out| {
+            let mut at = 0;
+            for field in [
+                &SAVED_MAGIC[..],
+                &[VERSION],
+                &self.lifetime.to_be_bytes(),
+                &self.age_add.to_be_bytes(),
+                &self.received_at.to_be_bytes(),
+                &(self.sealed.len() as u16).to_be_bytes(),
+                &self.sealed,
+                &self.psk[..],
+            ] {
+                out[at..at + field.len()].copy_from_slice(field);
+                at += field.len();
+            }
+            Ok(())
+        })
+    }
+
+    #[staticmethod]
+    fn from_bytes(data: Buffer) -> PyResult<Self> {
+        let mut r = Reader::new("saved resumption ticket", &data);
+        read_magic(&mut r, SAVED_MAGIC, "saved ticket")?;
+        let lifetime = r.u32()?;
+        let age_add = r.u32()?;
+        let received_at = r.u64()?;
+        let sealed_len = r.u16()? as usize;
+        let sealed = r.take(sealed_len)?.to_vec();
+        let mut psk = Zeroizing::new([0u8; KEY_LEN]);
+        psk.copy_from_slice(r.take(KEY_LEN)?);
+        r.finish()?;
+        Ok(ResumptionTicket {
+            sealed,
+            psk,
+            lifetime,
+            age_add,
+            received_at,
+        })
+    }
+
+    fn __reduce__(&self) -> PyResult<()> {
+        Err(PyTypeError::new_err(
+            "cannot pickle 'ResumptionTicket': it holds a pre-shared key; use to_bytes()",
+        ))
+    }
+
+    fn __repr__(&self) -> String {
+        format!("ResumptionTicket(expires_at={})", self.expires_at())
+    }
+}
+
+// ─── ResumeInitiator ──────────────────────────────────────────────────────────
+
+#[pyclass(module = "pqcrypto_bindings")]
+pub struct ResumeInitiator {
+    psk: Zeroizing<[u8; KEY_LEN]>,
+    hello: Vec<u8>,
+    session: Option<Session>,
+}
+
+impl ResumeInitiator {
+    pub(crate) fn session(&self) -> PyResult<&Session> {
+        established(&self.session)
+    }
+}
+
+fn established(session: &Option<Session>) -> PyResult<&Session> {
+    session
+        .as_ref()
+        .ok_or_else(|| PyValueError::new_err("the resumption isn't complete"))
+}
+
+#[pymethods]
+impl ResumeInitiator {
+    /// Start resuming with `ticket`. Raises KeyPolicyError if it has expired.
+    #[new]
+    #[pyo3(signature = (ticket, now = None))]
+    fn new(ticket: &ResumptionTicket, now: Option<u64>) -> PyResult<Self> {
+        let now = now.unwrap_or_else(current_time);
+        ticket.check_fresh(now)?;
+        let age = now.saturating_sub(ticket.received_at) as u32;
+
+        let mut hello = Vec::new();
+        hello.extend_from_slice(HELLO_MAGIC);
+        hello.push(VERSION);
+        hello.extend_from_slice(&(ticket.sealed.len() as u16).to_be_bytes());
+        hello.extend_from_slice(&ticket.sealed);
+        hello.extend_from_slice(&age.wrapping_add(ticket.age_add).to_be_bytes());
+        hello.extend_from_slice(&random::<RANDOM_LEN>()?);
+        let binder = mac(&ticket.psk[..], b"binder", &hello);
+        hello.extend_from_slice(&binder);
+        Ok(ResumeInitiator {
+            psk: Zeroizing::new(*ticket.psk),
+            hello,
+            session: None,
+        })
+    }
+
+    /// The hello, to send to the server.
+    #[getter]
+    fn message<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new_bound(py, &self.hello)
+    }
+
+    /// Check the server's reply and derive the session keys. Raises
+    /// DecryptionError if it doesn't come from the ticket's issuer.
+    fn finish(&mut self, reply: Buffer) -> PyResult<()> {
+        if self.session.is_some() {
+            return Err(PyValueError::new_err("the resumption is already complete"));
+        }
+        let mut r = Reader::new("resumption reply", &reply);
+        read_magic(&mut r, REPLY_MAGIC, "reply")?;
+        r.take(RANDOM_LEN)?;
+        let finished = r.take(MAC_LEN)?;
+        r.finish()?;
+        let body = &reply[..reply.len() - MAC_LEN];
+        let transcript = Sha256::new()
+            .chain_update(&self.hello)
+            .chain_update(body)
+            .finalize();
+        if !ct_eq(finished, &mac(&self.psk[..], b"finished", &transcript)) {
+            return Err(DecryptionError::new_err(
+                "resumption reply doesn't authenticate under the ticket",
+            ));
+        }
+        self.session = Some(derive_session(&self.psk[..], &self.hello, &reply, true));
+        Ok(())
+    }
+
+    #[getter]
+    fn session_id<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        Ok(PyBytes::new_bound(py, &established(&self.session)?.id))
+    }
+
+    /// (send key, receive key), as for AkeInitiator.
+    fn session_keys(&self, py: Python) -> PyResult<(Py<SecretBytes>, Py<SecretBytes>)> {
+        let session = established(&self.session)?;
+        Ok((
+            secret_bytes(py, &session.send[..])?,
+            secret_bytes(py, &session.recv[..])?,
+        ))
+    }
+
+    fn __reduce__(&self) -> PyResult<()> {
+        Err(PyTypeError::new_err(
+            "cannot pickle 'ResumeInitiator': it holds a pre-shared key",
+        ))
+    }
+}
+
+// ─── ResumeResponder ──────────────────────────────────────────────────────────
+
+#[pyclass(module = "pqcrypto_bindings")]
+pub struct ResumeResponder {
+    ticket_key: Zeroizing<[u8; KEY_LEN]>,
+    max_skew: u64,
+    session: Option<Session>,
+}
+
+impl ResumeResponder {
+    pub(crate) fn session(&self) -> PyResult<&Session> {
+        established(&self.session)
+    }
+}
+
+#[pymethods]
+impl ResumeResponder {
+    /// Accept tickets issued under `ticket_key`, allowing the client's and
+    /// server's views of a ticket's age to differ by `max_skew` seconds.
+    #[new]
+    #[pyo3(signature = (ticket_key, max_skew = DEFAULT_MAX_SKEW))]
+    fn new(ticket_key: Buffer, max_skew: u64) -> PyResult<Self> {
+        check_length("ticket key", &ticket_key, KEY_LEN)?;
+        let mut key = Zeroizing::new([0u8; KEY_LEN]);
+        key.copy_from_slice(&ticket_key);
+        Ok(ResumeResponder {
+            ticket_key: key,
+            max_skew,
+            session: None,
+        })
+    }
+
+    /// Check a client's hello and return the reply. Raises DecryptionError
+    /// for a ticket or binder that doesn't authenticate, and KeyPolicyError
+    /// for an expired ticket or an age that doesn't match.
+    #[pyo3(signature = (hello, now = None))]
+    fn respond(&mut self, py: Python, hello: Buffer, now: Option<u64>) -> PyResult<Py<PyBytes>> {
+        if self.session.is_some() {
+            return Err(PyValueError::new_err("the resumption is already complete"));
+        }
+        let now = now.unwrap_or_else(current_time);
+        let mut r = Reader::new("resumption hello", &hello);
+        read_magic(&mut r, HELLO_MAGIC, "hello")?;
+        let sealed_len = r.u16()? as usize;
+        let sealed = r.take(sealed_len)?;
+        let obfuscated_age = r.u32()?;
+        r.take(RANDOM_LEN)?;
+        let binder = r.take(MAC_LEN)?;
+        r.finish()?;
+
+        if sealed.len() != SEALED_LEN {
+            return Err(DecryptionError::new_err(
+                "resumption ticket doesn't authenticate",
+            ));
+        }
+        let (aead_nonce, body) = sealed.split_at(24);
+        let mut plain = Zeroizing::new(body.to_vec());
+        Aead::XChaCha20Poly1305
+            .decrypt(&self.ticket_key[..], aead_nonce, TICKET_MAGIC, &mut plain)
+            .map_err(|_| DecryptionError::new_err("resumption ticket doesn't authenticate"))?;
+        let issued_at = u64::from_be_bytes(plain[..8].try_into().unwrap());
+        let lifetime = u32::from_be_bytes(plain[8..12].try_into().unwrap());
+        let age_add = u32::from_be_bytes(plain[12..16].try_into().unwrap());
+        let psk = &plain[16..];
+
+        let body = &hello[..hello.len() - MAC_LEN];
+        if !ct_eq(binder, &mac(psk, b"binder", body)) {
+            return Err(DecryptionError::new_err(
+                "resumption hello binder doesn't authenticate",
+            ));
+        }
+        if now > issued_at.saturating_add(lifetime as u64) {
+            return Err(KeyPolicyError::new_err(format!(
+                "resumption ticket expired at {}",
+                issued_at.saturating_add(lifetime as u64)
+            )));
+        }
+        let client_age = obfuscated_age.wrapping_sub(age_add) as u64;
+        let server_age = now.saturating_sub(issued_at);
+        if client_age.abs_diff(server_age) > self.max_skew {
+            return Err(KeyPolicyError::new_err(format!(
+                "resumption hello claims a ticket age of {}s, expected {}s; replayed or delayed",
+                client_age, server_age
+            )));
+        }
+
+        let mut reply = Vec::new();
+        reply.extend_from_slice(REPLY_MAGIC);
+        reply.push(VERSION);
+        reply.extend_from_slice(&random::<RANDOM_LEN>()?);
+        let transcript = Sha256::new()
+            .chain_update(&hello[..])
+            .chain_update(&reply)
+            .finalize();
+        let finished = mac(psk, b"finished", &transcript);
+        reply.extend_from_slice(&finished);
+        self.session = Some(derive_session(psk, &hello, &reply, false));
+        Ok(PyBytes::new_bound(py, &reply).unbind())
+    }
+
+    #[getter]
+    fn session_id<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        Ok(PyBytes::new_bound(py, &established(&self.session)?.id))
+    }
+
+    /// (send key, receive key), as for AkeResponder.
+    fn session_keys(&self, py: Python) -> PyResult<(Py<SecretBytes>, Py<SecretBytes>)> {
+        let session = established(&self.session)?;
+        Ok((
+            secret_bytes(py, &session.send[..])?,
+            secret_bytes(py, &session.recv[..])?,
+        ))
+    }
+
+    fn __reduce__(&self) -> PyResult<()> {
+        Err(PyTypeError::new_err(
+            "cannot pickle 'ResumeResponder': it holds the ticket key",
+        ))
+    }
+}
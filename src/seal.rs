@@ -0,0 +1,517 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// Single-shot public-key encryption
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// For encrypting a message to a KEM public key in one call:
+//
+//   blob = seal(pk, b"attack at dawn")
+//   msg = open(sk, blob)
+//
+// A fresh encapsulation to `pk` gives a one-time shared secret; HKDF-SHA256
+// turns it into an AEAD key and nonce, which encrypt the message. The AEAD is
+// ChaCha20-Poly1305 unless seal() is passed cipher="aes256gcm" or
+// "xchacha20poly1305". Blob layout:
+//
+//   magic "PQSL" | version 1 | AEAD id (1 = ChaCha20-Poly1305, 2 = AES-256-GCM,
+//   3 = XChaCha20-Poly1305) | name length u8 | KEM name | KEM ciphertext
+//   | AEAD ciphertext || tag (16)
+//
+//   prk        = HKDF-Extract(salt = "", IKM = shared secret)
+//   key||nonce = HKDF-Expand(prk, info = label || header || KEM ciphertext,
+//                            32 + the AEAD's nonce length)
+//
+// where the header is everything before the KEM ciphertext. The AEAD's
+// associated data is header || KEM ciphertext || aad, so any change to the
+// blob, or a different `aad`, fails open() with DecryptionError. The KEM and
+// AEAD are named in the blob, so open() needs only the secret key. Without
+// `algorithm`, seal() picks Kyber-512 / 768 / 1024 by the key's length; pass
+// it for ML-KEM (whose keys have the same lengths) or any other KEM().
+//
+// Given a list of public keys, seal() encrypts the message once, for any of
+// the recipients to open: a random 32-byte content key encrypts the body and
+// is wrapped to each recipient in turn. The blob is then version 2:
+//
+//   magic "PQSL" | version 2 | AEAD id | recipient count u16
+//   | per recipient: name length u8 | KEM name | KEM ciphertext
+//                    | wrapped content key (32 + 16-byte tag)
+//   | AEAD ciphertext || tag (16)
+//
+//   kek||nonce = HKDF-Expand(HKDF-Extract("", ss_i),
+//                            info = wrap label || fixed header || entry_i)
+//   key||nonce = HKDF-Expand(HKDF-Extract("", content key),
+//                            info = label v2 || header)
+//
+// where the fixed header is everything before the first entry and entry_i is
+// recipient i's name length, name and KEM ciphertext. Each wrap authenticates
+// fixed header || entry_i; the body authenticates the whole header || aad.
+// Entries carry no key ids, so the blob doesn't say who can open it: open()
+// tries each entry whose KEM takes a secret key of the given length. Every
+// recipient learns the content key, so a multi-recipient blob shows only that
+// one of them, or the sender, wrote it.
+//
+// AEADs like AES-GCM and ChaCha20-Poly1305 don't commit to their key: a blob
+// can be built that decrypts, to different plaintexts, under two keys. With
+// several recipients, or a key from a guessable secret, that lets a sender show
+// recipients different messages ("invisible salamanders", Dodis et al., CRYPTO
+// 2018) or turn open()'s success or failure into a partitioning oracle (Len,
+// Grubbs and Ristenpart, USENIX Security 2021). seal(committing=True) adds the
+// 0x80 flag to the AEAD id and stretches each HKDF output by 32 bytes, a
+// commitment to the key that goes in front of the ciphertext it protects (the
+// body and, in version 2, each wrapped content key):
+//
+//   key||nonce||commitment = HKDF-Expand(prk, info, 32 + nonce length + 32)
+//
+// open() recomputes it and compares it before decrypting. Finding a blob
+// whose commitment holds under two keys means finding an HKDF-SHA256
+// collision. Committed blobs open as usual; open(require_committing=True)
+// also refuses any blob sealed without it, which is what stops an attacker
+// from simply sending an uncommitted one.
+
+use hkdf::Hkdf;
+use pyo3::exceptions::{PyRuntimeError, PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use sha2::Sha256;
+use zeroize::Zeroizing;
+
+use crate::aead::{Aead, KEY_LEN, TAG_LEN};
+use crate::buffer::Buffer;
+use crate::errors::{DecryptionError, InvalidLengthError};
+use crate::kem::{find_kem, KemAlgorithm};
+use crate::util::{ct_eq, Reader};
+
+const MAGIC: &[u8; 4] = b"PQSL";
+const VERSION: u8 = 1;
+const MULTI_VERSION: u8 = 2;
+const LABEL: &[u8] = b"entropic-chaos seal v1\0";
+const MULTI_LABEL: &[u8] = b"entropic-chaos seal v2\0";
+const WRAP_LABEL: &[u8] = b"entropic-chaos seal v2 wrap\0";
+/// Flag in the AEAD id byte of blobs sealed with committing=True.
+const COMMITTING: u8 = 0x80;
+const COMMITMENT_LEN: usize = 32;
+const MAX_RECIPIENTS: usize = u16::MAX as usize;
+
+const KYBER: &[&KemAlgorithm] = &[
+    &crate::kyber512::ALGORITHM,
+    &crate::kyber768::ALGORITHM,
+    &crate::kyber1024::ALGORITHM,
+];
+
+pub(crate) fn kem_for(algorithm: Option<&str>, pk: &[u8]) -> PyResult<&'static KemAlgorithm> {
+    match algorithm {
+        Some(name) => find_kem(name)
+            .ok_or_else(|| PyValueError::new_err(format!("unsupported KEM '{}'", name))),
+        None => KYBER
+            .iter()
+            .copied()
+            .find(|alg| alg.public_key_bytes == pk.len())
+            .ok_or_else(|| {
+                InvalidLengthError::new_err(format!(
+                    "{}-byte public key isn't Kyber-512 / 768 / 1024; pass algorithm= for \
+                     other KEMs",
+                    pk.len()
+                ))
+            }),
+    }
+}
+
+/// The AEAD and whether its key is committed to: the AEAD id byte.
+#[derive(Clone, Copy)]
+pub(crate) struct Suite {
+    pub(crate) aead: Aead,
+    pub(crate) committing: bool,
+}
+
+impl Suite {
+    fn id(self) -> u8 {
+        self.aead as u8 | if self.committing { COMMITTING } else { 0 }
+    }
+
+    fn from_id(id: u8) -> PyResult<Self> {
+        let aead = Aead::from_id(id & !COMMITTING).ok_or_else(|| {
+            DecryptionError::new_err(format!("unsupported sealed blob cipher {}", id))
+        })?;
+        Ok(Suite {
+            aead,
+            committing: id & COMMITTING != 0,
+        })
+    }
+
+    fn commitment_len(self) -> usize {
+        if self.committing {
+            COMMITMENT_LEN
+        } else {
+            0
+        }
+    }
+}
+
+/// One HKDF output: AEAD key, nonce, then the key commitment if any.
+struct Okm {
+    bytes: Zeroizing<Vec<u8>>,
+    nonce_len: usize,
+}
+
+impl Okm {
+    fn key(&self) -> &[u8] {
+        &self.bytes[..KEY_LEN]
+    }
+
+    fn nonce(&self) -> &[u8] {
+        &self.bytes[KEY_LEN..KEY_LEN + self.nonce_len]
+    }
+
+    fn commitment(&self) -> &[u8] {
+        &self.bytes[KEY_LEN + self.nonce_len..]
+    }
+}
+
+/// The AEAD key and nonce for one message, or one wrapped content key.
+fn derive(label: &[u8], suite: Suite, ikm: &[u8], transcript: &[u8]) -> PyResult<Okm> {
+    let mut info = Vec::with_capacity(label.len() + transcript.len());
+    info.extend_from_slice(label);
+    info.extend_from_slice(transcript);
+    let nonce_len = suite.aead.nonce_len();
+    let mut bytes = Zeroizing::new(vec![0u8; KEY_LEN + nonce_len + suite.commitment_len()]);
+    Hkdf::<Sha256>::new(None, ikm)
+        .expand(&info, bytes.as_mut())
+        .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+    Ok(Okm { bytes, nonce_len })
+}
+
+fn associated_data(transcript: &[u8], aad: &[u8]) -> Vec<u8> {
+    let mut ad = Vec::with_capacity(transcript.len() + aad.len());
+    ad.extend_from_slice(transcript);
+    ad.extend_from_slice(aad);
+    ad
+}
+
+/// Encrypt `plaintext` under keys derived from `ikm` and `transcript`,
+/// authenticating transcript || aad. Returns commitment || ciphertext || tag.
+fn encrypt_under(
+    suite: Suite,
+    label: &[u8],
+    ikm: &[u8],
+    transcript: &[u8],
+    plaintext: &[u8],
+    aad: &[u8],
+) -> PyResult<Vec<u8>> {
+    let okm = derive(label, suite, ikm, transcript)?;
+    let ad = associated_data(transcript, aad);
+
+    let mut sealed = Vec::with_capacity(suite.commitment_len() + plaintext.len() + TAG_LEN);
+    sealed.extend_from_slice(okm.commitment());
+    let mut body = Vec::with_capacity(plaintext.len() + TAG_LEN);
+    body.extend_from_slice(plaintext);
+    suite.aead.encrypt(okm.key(), okm.nonce(), &ad, &mut body)?;
+    sealed.extend_from_slice(&body);
+    Ok(sealed)
+}
+
+/// Reverse encrypt_under(), checking the commitment first.
+fn decrypt_under(
+    suite: Suite,
+    label: &[u8],
+    ikm: &[u8],
+    transcript: &[u8],
+    aad: &[u8],
+    sealed: &[u8],
+) -> PyResult<Zeroizing<Vec<u8>>> {
+    if sealed.len() < suite.commitment_len() + TAG_LEN {
+        return Err(DecryptionError::new_err("sealed blob is truncated"));
+    }
+    let (commitment, body) = sealed.split_at(suite.commitment_len());
+    let okm = derive(label, suite, ikm, transcript)?;
+    let failed =
+        || DecryptionError::new_err("wrong secret key, or the sealed blob has been modified");
+    if !ct_eq(commitment, okm.commitment()) {
+        return Err(failed());
+    }
+    let ad = associated_data(transcript, aad);
+
+    let mut plaintext = Zeroizing::new(body.to_vec());
+    suite
+        .aead
+        .decrypt(okm.key(), okm.nonce(), &ad, &mut plaintext)
+        .map_err(|_| failed())?;
+    Ok(plaintext)
+}
+
+pub(crate) fn seal_single(
+    py: Python,
+    suite: Suite,
+    pk: Buffer,
+    plaintext: &[u8],
+    aad: &[u8],
+    algorithm: Option<&str>,
+) -> PyResult<Vec<u8>> {
+    let alg = kem_for(algorithm, &pk)?;
+    let (ct, ss) = (alg.encapsulate)(py, pk)?;
+    let ss: Buffer = ss.bind(py).extract()?;
+    let ct = ct.as_bytes(py);
+
+    let mut blob = Vec::with_capacity(MAGIC.len() + 3 + alg.name.len() + ct.len());
+    blob.extend_from_slice(MAGIC);
+    blob.push(VERSION);
+    blob.push(suite.id());
+    blob.push(alg.name.len() as u8);
+    blob.extend_from_slice(alg.name.as_bytes());
+    blob.extend_from_slice(ct);
+
+    let sealed = encrypt_under(suite, LABEL, &ss, &blob, plaintext, aad)?;
+    blob.extend_from_slice(&sealed);
+    Ok(blob)
+}
+
+fn seal_multi(
+    py: Python,
+    suite: Suite,
+    pks: Vec<Buffer>,
+    plaintext: &[u8],
+    aad: &[u8],
+    algorithm: Option<&str>,
+) -> PyResult<Vec<u8>> {
+    if pks.is_empty() || pks.len() > MAX_RECIPIENTS {
+        return Err(PyValueError::new_err(format!(
+            "seal() takes between 1 and {} recipients, got {}",
+            MAX_RECIPIENTS,
+            pks.len()
+        )));
+    }
+    let mut cek = Zeroizing::new([0u8; KEY_LEN]);
+    getrandom::fill(cek.as_mut()).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+
+    let mut blob = Vec::new();
+    blob.extend_from_slice(MAGIC);
+    blob.push(MULTI_VERSION);
+    blob.push(suite.id());
+    blob.extend_from_slice(&(pks.len() as u16).to_be_bytes());
+    let fixed_len = blob.len();
+
+    for pk in pks {
+        let alg = kem_for(algorithm, &pk)?;
+        let (ct, ss) = (alg.encapsulate)(py, pk)?;
+        let ss: Buffer = ss.bind(py).extract()?;
+
+        let entry_start = blob.len();
+        blob.push(alg.name.len() as u8);
+        blob.extend_from_slice(alg.name.as_bytes());
+        blob.extend_from_slice(ct.as_bytes(py));
+        let mut wrap_ad = blob[..fixed_len].to_vec();
+        wrap_ad.extend_from_slice(&blob[entry_start..]);
+
+        let wrapped = encrypt_under(suite, WRAP_LABEL, &ss, &wrap_ad, &cek[..], &[])?;
+        blob.extend_from_slice(&wrapped);
+    }
+
+    let sealed = encrypt_under(suite, MULTI_LABEL, &cek[..], &blob, plaintext, aad)?;
+    blob.extend_from_slice(&sealed);
+    Ok(blob)
+}
+
+/// Encrypt `plaintext` to the KEM public key `pk`, or to each of a list of
+/// public keys. `aad` is authenticated but not included in the blob; open()
+/// must be given the same bytes. `cipher` is "chacha20poly1305",
+/// "aes256gcm" or "xchacha20poly1305". `committing` adds a key commitment, for
+/// blobs with several recipients or keys that might be guessed.
+// stub: pk: Buffer | Sequence[Buffer]
+// stub: cipher: Literal["chacha20poly1305", "aes256gcm", "xchacha20poly1305"]
+#[pyfunction]
+#[pyo3(signature = (
+    pk,
+    plaintext,
+    aad = None,
+    algorithm = None,
+    cipher = "chacha20poly1305",
+    committing = false,
+))]
+pub fn seal(
+    py: Python,
+    pk: &Bound<'_, PyAny>,
+    plaintext: Buffer,
+    aad: Option<Buffer>,
+    algorithm: Option<&str>,
+    cipher: &str,
+    committing: bool,
+) -> PyResult<Py<PyBytes>> {
+    let suite = Suite {
+        aead: Aead::from_name(cipher)?,
+        committing,
+    };
+    let aad = aad.as_deref().unwrap_or_default();
+    let blob = match pk.extract::<Buffer>() {
+        Ok(pk) => seal_single(py, suite, pk, &plaintext, aad, algorithm)?,
+        Err(_) => {
+            let pks: Vec<Buffer> = pk.extract().map_err(|_| {
+                PyTypeError::new_err("pk must be a public key or a list of public keys")
+            })?;
+            seal_multi(py, suite, pks, &plaintext, aad, algorithm)?
+        }
+    };
+    Ok(PyBytes::new_bound(py, &blob).unbind())
+}
+
+fn read_kem(r: &mut Reader) -> PyResult<(&'static KemAlgorithm, u8)> {
+    let name_len = r.u8()?;
+    let name = std::str::from_utf8(r.take(name_len as usize)?)
+        .map_err(|_| DecryptionError::new_err("sealed blob has a malformed KEM name"))?;
+    let alg = find_kem(name)
+        .filter(|alg| alg.name == name)
+        .ok_or_else(|| {
+            DecryptionError::new_err(format!("sealed blob uses unknown KEM '{}'", name))
+        })?;
+    Ok((alg, name_len))
+}
+
+fn decapsulate(
+    py: Python,
+    alg: &KemAlgorithm,
+    sk: &Bound<'_, PyAny>,
+    ct: &[u8],
+) -> PyResult<Buffer> {
+    let ct = PyBytes::new_bound(py, ct);
+    (alg.decapsulate)(py, sk.extract()?, ct.extract()?)?
+        .bind(py)
+        .extract()
+}
+
+fn open_single(
+    py: Python,
+    suite: Suite,
+    r: &mut Reader,
+    sk: &Bound<'_, PyAny>,
+    blob: &[u8],
+    aad: &[u8],
+) -> PyResult<Zeroizing<Vec<u8>>> {
+    let (alg, _) = read_kem(r)?;
+    let ct = r.take(alg.ciphertext_bytes)?;
+    let sealed = r.rest();
+    let transcript = &blob[..blob.len() - sealed.len()];
+
+    let ss = decapsulate(py, alg, sk, ct)?;
+    decrypt_under(suite, LABEL, &ss, transcript, aad, sealed)
+}
+
+fn open_multi(
+    py: Python,
+    suite: Suite,
+    r: &mut Reader,
+    sk: &Bound<'_, PyAny>,
+    blob: &[u8],
+    aad: &[u8],
+) -> PyResult<Zeroizing<Vec<u8>>> {
+    let count = r.u16()?;
+    let fixed = &blob[..MAGIC.len() + 4];
+    let sk_len = sk.extract::<Buffer>()?.len();
+
+    let mut cek = None;
+    for _ in 0..count {
+        let (alg, name_len) = read_kem(r)?;
+        let ct = r.take(alg.ciphertext_bytes)?;
+        let wrapped = r.take(suite.commitment_len() + KEY_LEN + TAG_LEN)?;
+        if cek.is_some() || alg.secret_key_bytes != sk_len {
+            continue;
+        }
+
+        let mut wrap_ad = fixed.to_vec();
+        wrap_ad.push(name_len);
+        wrap_ad.extend_from_slice(alg.name.as_bytes());
+        wrap_ad.extend_from_slice(ct);
+
+        let ss = decapsulate(py, alg, sk, ct)?;
+        cek = decrypt_under(suite, WRAP_LABEL, &ss, &wrap_ad, &[], wrapped).ok();
+    }
+    let cek = cek.ok_or_else(|| {
+        DecryptionError::new_err(
+            "the secret key opens none of the sealed blob's recipient entries, or the blob \
+             has been modified",
+        )
+    })?;
+
+    let sealed = r.rest();
+    let transcript = &blob[..blob.len() - sealed.len()];
+    decrypt_under(suite, MULTI_LABEL, &cek, transcript, aad, sealed)
+}
+
+/// The plaintext of a seal() blob (single- or multi-recipient).
+pub(crate) fn open_blob(
+    py: Python,
+    sk: &Bound<'_, PyAny>,
+    blob: &[u8],
+    aad: &[u8],
+    require_committing: bool,
+) -> PyResult<Zeroizing<Vec<u8>>> {
+    let mut r = Reader::with_error("sealed blob", blob, DecryptionError::new_err::<String>);
+    if r.take(MAGIC.len())? != MAGIC {
+        return Err(DecryptionError::new_err("not a sealed blob"));
+    }
+    let version = r.u8()?;
+    if version != VERSION && version != MULTI_VERSION {
+        return Err(DecryptionError::new_err(format!(
+            "unsupported sealed blob version {}",
+            version
+        )));
+    }
+    let suite = Suite::from_id(r.u8()?)?;
+    if require_committing && !suite.committing {
+        return Err(DecryptionError::new_err(
+            "sealed blob isn't key-committing; it must be sealed with committing=True",
+        ));
+    }
+
+    if version == VERSION {
+        open_single(py, suite, &mut r, sk, blob, aad)
+    } else {
+        open_multi(py, suite, &mut r, sk, blob, aad)
+    }
+}
+
+/// Decrypt a seal() blob with the matching secret key, or for a
+/// multi-recipient blob, any one recipient's. Raises DecryptionError for the
+/// wrong key, a modified blob or a different `aad`, and with
+/// `require_committing`, for a blob sealed without committing=True.
+// stub: sk: Buffer
+#[pyfunction]
+#[pyo3(name = "open", signature = (sk, blob, aad = None, require_committing = false))]
+pub fn open_sealed(
+    py: Python,
+    sk: &Bound<'_, PyAny>,
+    blob: Buffer,
+    aad: Option<Buffer>,
+    require_committing: bool,
+) -> PyResult<Py<PyBytes>> {
+    let aad = aad.as_deref().unwrap_or_default();
+    let plaintext = open_blob(py, sk, &blob, aad, require_committing)?;
+    Ok(PyBytes::new_bound(py, &plaintext).unbind())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncated_blobs_raise_decryption_error() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let (pk, sk) = (crate::mlkem768::ALGORITHM.keygen)(py).unwrap();
+            let sk = sk.into_bound(py).into_any();
+            for committing in [false, true] {
+                let suite = Suite {
+                    aead: Aead::ChaCha20Poly1305,
+                    committing,
+                };
+                let pk = || Buffer::from_bytes(py, pk.as_bytes(py));
+                let single = seal_single(py, suite, pk(), b"msg", b"", Some("mlkem768")).unwrap();
+                let multi =
+                    seal_multi(py, suite, vec![pk()], b"msg", b"", Some("mlkem768")).unwrap();
+                for blob in [single, multi] {
+                    assert_eq!(&open_blob(py, &sk, &blob, b"", false).unwrap()[..], b"msg");
+                    for len in 0..blob.len() {
+                        let err = open_blob(py, &sk, &blob[..len], b"", false).unwrap_err();
+                        assert!(err.is_instance_of::<DecryptionError>(py), "length {}", len);
+                    }
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,235 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// SecretBytes: wipeable secret return values
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// A `bytes` object can't be wiped from Python, so secret keys and shared
+// secrets come back as SecretBytes instead:
+//
+//   pk, sk = kyber_keygen()
+//   ct, ss = kyber_encapsulate(pk)
+//   key = derive(ss.expose())   # explicit copy out as bytes
+//   ss.destroy()                # wipe now rather than at garbage collection
+//
+// The bytes live in their own page-aligned allocation, locked into RAM where
+// the OS allows it (so they are never written to swap), and are zeroed on
+// destroy() or drop. SecretBytes supports the buffer protocol read-only, so it
+// can be passed straight back into any function here without an expose().
+
+use std::alloc::{self, Layout};
+use std::os::raw::c_int;
+use std::ptr::NonNull;
+
+use pyo3::exceptions::{PyBufferError, PyTypeError, PyValueError};
+use pyo3::ffi;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use zeroize::Zeroize;
+
+use crate::buffer::Buffer;
+use crate::util::ct_eq;
+
+// ─── Locked allocation ────────────────────────────────────────────────────────
+
+// Whole pages of its own, so unlocking on drop can never unlock a neighbour.
+struct LockedBuf {
+    ptr: NonNull<u8>,
+    len: usize,
+    layout: Layout,
+    lock: Option<region::LockGuard>,
+}
+
+// SAFETY: LockedBuf owns its allocation exclusively, like a Box<[u8]>.
+unsafe impl Send for LockedBuf {}
+unsafe impl Sync for LockedBuf {}
+
+impl LockedBuf {
+    /// `len` zero bytes.
+    fn new(len: usize) -> PyResult<Self> {
+        let page = region::page::size();
+        let size = len.max(1).div_ceil(page) * page;
+        let layout = Layout::from_size_align(size, page)
+            .map_err(|_| PyValueError::new_err("secret too large"))?;
+
+        // SAFETY: size is non-zero.
+        let ptr = NonNull::new(unsafe { alloc::alloc_zeroed(layout) })
+            .unwrap_or_else(|| alloc::handle_alloc_error(layout));
+        // Best effort: locking fails under a tight RLIMIT_MEMLOCK, and the
+        // secret is still wiped on drop either way.
+        let lock = region::lock(ptr.as_ptr(), size).ok();
+
+        Ok(LockedBuf {
+            ptr,
+            len,
+            layout,
+            lock,
+        })
+    }
+
+    fn as_slice(&self) -> &[u8] {
+        // SAFETY: ptr is valid for layout.size() >= len bytes until drop.
+        unsafe { std::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        // SAFETY: as above, and &mut self makes the access exclusive.
+        unsafe { std::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl Drop for LockedBuf {
+    fn drop(&mut self) {
+        self.as_mut_slice().zeroize();
+        // Unlock before giving the pages back to the allocator.
+        self.lock = None;
+        // SAFETY: allocated in new() with this layout.
+        unsafe { alloc::dealloc(self.ptr.as_ptr(), self.layout) };
+    }
+}
+
+// ─── SecretBytes ──────────────────────────────────────────────────────────────
+
+#[pyclass(module = "pqcrypto_bindings")]
+pub struct SecretBytes {
+    buf: Option<LockedBuf>,
+    exports: usize,
+}
+
+impl SecretBytes {
+    fn bytes(&self) -> PyResult<&[u8]> {
+        self.buf
+            .as_ref()
+            .map(LockedBuf::as_slice)
+            .ok_or_else(|| PyValueError::new_err("SecretBytes has been destroyed"))
+    }
+}
+
+/// Copy secret material into a new SecretBytes object.
+pub(crate) fn secret_bytes(py: Python, data: &[u8]) -> PyResult<Py<SecretBytes>> {
+    secret_bytes_with(py, data.len(), |buf| {
+        buf.copy_from_slice(data);
+        Ok(())
+    })
+}
+
+/// Like `PyBytes::new_bound_with`: `init` writes the secret straight into the
+/// locked allocation, so it never passes through an unwiped buffer.
+pub(crate) fn secret_bytes_with(
+    py: Python,
+    len: usize,
+    init: impl FnOnce(&mut [u8]) -> PyResult<()>,
+) -> PyResult<Py<SecretBytes>> {
+    let mut buf = LockedBuf::new(len)?;
+    init(buf.as_mut_slice())?;
+    Py::new(
+        py,
+        SecretBytes {
+            buf: Some(buf),
+            exports: 0,
+        },
+    )
+}
+
+#[pymethods]
+impl SecretBytes {
+    /// Wrap secret material you already hold. The source object itself is
+    /// not wiped.
+    #[new]
+    fn new(data: Buffer) -> PyResult<Self> {
+        let mut buf = LockedBuf::new(data.len())?;
+        buf.as_mut_slice().copy_from_slice(&data);
+        Ok(SecretBytes {
+            buf: Some(buf),
+            exports: 0,
+        })
+    }
+
+    /// A plain `bytes` copy of the secret. That copy can't be wiped; keep it
+    /// as short-lived as possible.
+    fn expose(&self, py: Python) -> PyResult<Py<PyBytes>> {
+        Ok(PyBytes::new_bound(py, self.bytes()?).unbind())
+    }
+
+    /// Zero and free the secret now. Any later use raises ValueError.
+    /// Calling it twice is harmless.
+    pub(crate) fn destroy(&mut self) -> PyResult<()> {
+        if self.exports > 0 {
+            return Err(PyBufferError::new_err(
+                "cannot destroy SecretBytes while a memoryview of it is alive",
+            ));
+        }
+        self.buf = None;
+        Ok(())
+    }
+
+    /// False once destroy() has been called.
+    #[getter]
+    fn alive(&self) -> bool {
+        self.buf.is_some()
+    }
+
+    /// True if the OS agreed to keep these bytes out of swap.
+    #[getter]
+    fn locked(&self) -> bool {
+        self.buf.as_ref().is_some_and(|b| b.lock.is_some())
+    }
+
+    fn __len__(&self) -> PyResult<usize> {
+        Ok(self.bytes()?.len())
+    }
+
+    /// Constant-time comparison against another SecretBytes or any bytes-like
+    /// object.
+    fn __eq__(&self, other: &Bound<'_, PyAny>) -> PyResult<bool> {
+        let this = self.bytes()?;
+        if let Ok(other) = other.downcast::<SecretBytes>() {
+            return Ok(ct_eq(this, other.borrow().bytes()?));
+        }
+        match other.extract::<Buffer>() {
+            Ok(other) => Ok(ct_eq(this, &other)),
+            Err(_) => Ok(false),
+        }
+    }
+
+    fn __repr__(&self) -> String {
+        match &self.buf {
+            Some(buf) => format!("SecretBytes(<{} bytes redacted>)", buf.len),
+            None => "SecretBytes(<destroyed>)".to_string(),
+        }
+    }
+
+    fn __reduce__(&self) -> PyResult<()> {
+        Err(PyTypeError::new_err(
+            "cannot pickle 'SecretBytes': pickle secret.expose() explicitly if you must",
+        ))
+    }
+
+    unsafe fn __getbuffer__(
+        mut slf: PyRefMut<'_, Self>,
+        view: *mut ffi::Py_buffer,
+        flags: c_int,
+    ) -> PyResult<()> {
+        let (ptr, len) = {
+            let data = slf.bytes()?;
+            (data.as_ptr(), data.len())
+        };
+        // Read-only; PyBuffer_FillInfo rejects PyBUF_WRITABLE requests and
+        // takes its own reference to the owner.
+        if ffi::PyBuffer_FillInfo(
+            view,
+            slf.as_ptr(),
+            ptr as *mut _,
+            len as ffi::Py_ssize_t,
+            1,
+            flags,
+        ) == -1
+        {
+            return Err(PyErr::fetch(slf.py()));
+        }
+        slf.exports += 1;
+        Ok(())
+    }
+
+    unsafe fn __releasebuffer__(&mut self, _view: *mut ffi::Py_buffer) {
+        self.exports -= 1;
+    }
+}
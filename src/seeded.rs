@@ -0,0 +1,366 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// Deterministic key generation from a seed
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// The same seed always gives the same key pair, for reproducible test
+// fixtures and for recovering a key from a backed-up seed:
+//
+//   pk, sk = kyber_keygen_from_seed(seed, params="kyber768")
+//   pk, sk = falcon_keygen_from_seed(seed, params="falcon512")
+//
+// Seeds are 32 or 64 bytes. They are expanded with
+//
+//   SHAKE256("pqcrypto_bindings keygen_from_seed " || name || 0x00 || seed)
+//
+// into the randomness PQClean's keygen would otherwise draw from the OS: the
+// 64 bytes d || z for Kyber, the 48-byte SHAKE256 seed for Falcon. Keys come
+// out in the usual encodings and work with every other Kyber / Falcon
+// function. The expansion is part of the format; changing it would break
+// recovery from existing seeds.
+//
+// Kyber uses PQClean's crypto_kem_keypair_derand. Falcon has no seeded entry
+// point, so its crypto_sign_keypair is reproduced below over the library's
+// internal keygen and encoders. Both call the portable "clean" builds, whose
+// output doesn't depend on the CPU.
+
+use std::os::raw::{c_int, c_uint};
+
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use zeroize::Zeroizing;
+
+use crate::buffer::Buffer;
+use crate::errors::{check_length, InvalidLengthError};
+use crate::kem::find_kem;
+use crate::pct::{checked_kem_keypair, checked_signature_keypair};
+use crate::secret::{secret_bytes_with, SecretBytes};
+use crate::sign::find_signature;
+use crate::xof::{ShakeIncCtx, ShakeVariant, Sponge};
+
+const LABEL: &[u8] = b"pqcrypto_bindings keygen_from_seed ";
+
+// ─── PQClean FFI ──────────────────────────────────────────────────────────────
+
+extern "C" {
+    fn PQCLEAN_KYBER512_CLEAN_crypto_kem_keypair_derand(
+        pk: *mut u8,
+        sk: *mut u8,
+        coins: *const u8,
+    ) -> c_int;
+    fn PQCLEAN_KYBER768_CLEAN_crypto_kem_keypair_derand(
+        pk: *mut u8,
+        sk: *mut u8,
+        coins: *const u8,
+    ) -> c_int;
+    fn PQCLEAN_KYBER1024_CLEAN_crypto_kem_keypair_derand(
+        pk: *mut u8,
+        sk: *mut u8,
+        coins: *const u8,
+    ) -> c_int;
+    fn PQCLEAN_MLKEM512_CLEAN_crypto_kem_keypair_derand(
+        pk: *mut u8,
+        sk: *mut u8,
+        coins: *const u8,
+    ) -> c_int;
+    fn PQCLEAN_MLKEM768_CLEAN_crypto_kem_keypair_derand(
+        pk: *mut u8,
+        sk: *mut u8,
+        coins: *const u8,
+    ) -> c_int;
+    fn PQCLEAN_MLKEM1024_CLEAN_crypto_kem_keypair_derand(
+        pk: *mut u8,
+        sk: *mut u8,
+        coins: *const u8,
+    ) -> c_int;
+}
+
+fn expand_seed(name: &str, seed: &[u8]) -> PyResult<Sponge> {
+    if seed.len() != 32 && seed.len() != 64 {
+        return Err(InvalidLengthError::new_err(format!(
+            "{} seed: expected 32 or 64 bytes, got {}",
+            name,
+            seed.len()
+        )));
+    }
+    let mut xof = Sponge::new(ShakeVariant::Shake256);
+    for part in [LABEL, name.as_bytes(), &[0], seed] {
+        xof.absorb(part);
+    }
+    Ok(xof)
+}
+
+fn keygen_failed(name: &str) -> PyErr {
+    PyRuntimeError::new_err(format!("{} key generation failed", name))
+}
+
+// ─── Kyber ────────────────────────────────────────────────────────────────────
+
+type KyberKeypairDerand = unsafe extern "C" fn(*mut u8, *mut u8, *const u8) -> c_int;
+
+/// Deterministic Kyber key pair: (public_key, secret_key). `params` is
+/// "kyber512", "kyber768" or "kyber1024".
+// stub: params: Literal["kyber512", "kyber768", "kyber1024"]
+#[pyfunction]
+#[pyo3(signature = (seed, params = "kyber512"))]
+pub fn kyber_keygen_from_seed(
+    py: Python,
+    seed: Buffer,
+    params: &str,
+) -> PyResult<(Py<PyBytes>, Py<SecretBytes>)> {
+    use pqcrypto_kyber::{kyber1024, kyber512, kyber768};
+
+    let (keypair, pk_len, sk_len): (KyberKeypairDerand, usize, usize) = match params {
+        "kyber512" => (
+            PQCLEAN_KYBER512_CLEAN_crypto_kem_keypair_derand,
+            kyber512::public_key_bytes(),
+            kyber512::secret_key_bytes(),
+        ),
+        "kyber768" => (
+            PQCLEAN_KYBER768_CLEAN_crypto_kem_keypair_derand,
+            kyber768::public_key_bytes(),
+            kyber768::secret_key_bytes(),
+        ),
+        "kyber1024" => (
+            PQCLEAN_KYBER1024_CLEAN_crypto_kem_keypair_derand,
+            kyber1024::public_key_bytes(),
+            kyber1024::secret_key_bytes(),
+        ),
+        _ => {
+            return Err(PyValueError::new_err(format!(
+                "unsupported Kyber parameter set '{}' (expected kyber512, kyber768 or kyber1024)",
+                params
+            )))
+        }
+    };
+
+    let mut coins = Zeroizing::new([0u8; 64]);
+    expand_seed(params, &seed)?.squeeze(coins.as_mut());
+
+    let mut sk = None;
+    let pk = PyBytes::new_bound_with(py, pk_len, |pk_buf| {
+        sk = Some(secret_bytes_with(py, sk_len, |sk_buf| {
+            let rc = unsafe { keypair(pk_buf.as_mut_ptr(), sk_buf.as_mut_ptr(), coins.as_ptr()) };
+            if rc != 0 {
+                return Err(keygen_failed(params));
+            }
+            Ok(())
+        })?);
+        Ok(())
+    })?;
+
+    let sk = sk.expect("secret key initialised alongside public key");
+    let alg = find_kem(params).expect("Kyber parameter sets are registered");
+    checked_kem_keypair(py, alg, (pk.unbind(), sk))
+}
+
+// ─── ML-KEM ───────────────────────────────────────────────────────────────────
+
+/// The ML-KEM secret key for a FIPS 203 seed d || z, the 64 bytes the seed
+/// form of an ML-KEM PKCS#8 key carries. Unlike kyber_keygen_from_seed, the
+/// seed goes to keygen as is.
+pub(crate) fn mlkem_secret_key_from_seed(
+    name: &str,
+    seed: &[u8],
+) -> PyResult<Zeroizing<Vec<u8>>> {
+    use pqcrypto_mlkem::{mlkem1024, mlkem512, mlkem768};
+
+    let (keypair, pk_len, sk_len): (KyberKeypairDerand, usize, usize) = match name {
+        "mlkem512" => (
+            PQCLEAN_MLKEM512_CLEAN_crypto_kem_keypair_derand,
+            mlkem512::public_key_bytes(),
+            mlkem512::secret_key_bytes(),
+        ),
+        "mlkem768" => (
+            PQCLEAN_MLKEM768_CLEAN_crypto_kem_keypair_derand,
+            mlkem768::public_key_bytes(),
+            mlkem768::secret_key_bytes(),
+        ),
+        "mlkem1024" => (
+            PQCLEAN_MLKEM1024_CLEAN_crypto_kem_keypair_derand,
+            mlkem1024::public_key_bytes(),
+            mlkem1024::secret_key_bytes(),
+        ),
+        _ => unreachable!("not an ML-KEM parameter set: {name}"),
+    };
+    check_length(&format!("{} seed", name), seed, 64)?;
+
+    let mut pk = vec![0u8; pk_len];
+    let mut sk = Zeroizing::new(vec![0u8; sk_len]);
+    let rc = unsafe { keypair(pk.as_mut_ptr(), sk.as_mut_ptr(), seed.as_ptr()) };
+    if rc != 0 {
+        return Err(keygen_failed(name));
+    }
+    Ok(sk)
+}
+
+// ─── Falcon ───────────────────────────────────────────────────────────────────
+
+/// PQCLEAN_FALCON*_CLEAN_crypto_sign_keypair with the 48-byte seed taken
+/// from the caller instead of randombytes().
+macro_rules! falcon_keypair_from_seed {
+    (
+        $fn_name:ident,
+        $logn:expr,
+        $temp_len:expr,
+        $keygen:ident,
+        $trim_i8_encode:ident,
+        $modq_encode:ident,
+        $max_fg_bits:ident,
+        $max_big_fg_bits:ident
+    ) => {
+        pub(crate) fn $fn_name(seed: &[u8; 48], pk: &mut [u8], sk: &mut [u8]) -> bool {
+            const N: usize = 1 << $logn;
+
+            extern "C" {
+                fn $keygen(
+                    rng: *mut ShakeIncCtx,
+                    f: *mut i8,
+                    g: *mut i8,
+                    big_f: *mut i8,
+                    big_g: *mut i8,
+                    h: *mut u16,
+                    logn: c_uint,
+                    tmp: *mut u8,
+                );
+                fn $trim_i8_encode(
+                    out: *mut u8,
+                    max_out_len: usize,
+                    x: *const i8,
+                    logn: c_uint,
+                    bits: c_uint,
+                ) -> usize;
+                fn $modq_encode(
+                    out: *mut u8,
+                    max_out_len: usize,
+                    x: *const u16,
+                    logn: c_uint,
+                ) -> usize;
+                static $max_fg_bits: [u8; 11];
+                static $max_big_fg_bits: [u8; 11];
+            }
+
+            let mut f = Zeroizing::new([0i8; N]);
+            let mut g = Zeroizing::new([0i8; N]);
+            let mut big_f = Zeroizing::new([0i8; N]);
+            let mut h = [0u16; N];
+            // keygen() wants 64-bit alignment for its scratch space.
+            let mut tmp = Zeroizing::new(vec![0u64; $temp_len / 8]);
+
+            let mut rng = Sponge::new(ShakeVariant::Shake256);
+            rng.absorb(seed);
+            unsafe {
+                $keygen(
+                    rng.as_mut_ptr(),
+                    f.as_mut_ptr(),
+                    g.as_mut_ptr(),
+                    big_f.as_mut_ptr(),
+                    std::ptr::null_mut(),
+                    h.as_mut_ptr(),
+                    $logn,
+                    tmp.as_mut_ptr().cast(),
+                );
+            }
+            drop(rng);
+
+            let (fg_bits, big_fg_bits) = unsafe {
+                (
+                    $max_fg_bits[$logn] as c_uint,
+                    $max_big_fg_bits[$logn] as c_uint,
+                )
+            };
+
+            sk[0] = 0x50 + $logn as u8;
+            let mut u = 1;
+            for (x, bits) in [(&f, fg_bits), (&g, fg_bits), (&big_f, big_fg_bits)] {
+                let v = unsafe {
+                    $trim_i8_encode(sk[u..].as_mut_ptr(), sk.len() - u, x.as_ptr(), $logn, bits)
+                };
+                if v == 0 {
+                    return false;
+                }
+                u += v;
+            }
+            if u != sk.len() {
+                return false;
+            }
+
+            pk[0] = $logn as u8;
+            let v = unsafe { $modq_encode(pk[1..].as_mut_ptr(), pk.len() - 1, h.as_ptr(), $logn) };
+            v == pk.len() - 1
+        }
+    };
+}
+
+falcon_keypair_from_seed!(
+    falcon512_keypair_from_seed,
+    9,
+    14336,
+    PQCLEAN_FALCON512_CLEAN_keygen,
+    PQCLEAN_FALCON512_CLEAN_trim_i8_encode,
+    PQCLEAN_FALCON512_CLEAN_modq_encode,
+    PQCLEAN_FALCON512_CLEAN_max_fg_bits,
+    PQCLEAN_FALCON512_CLEAN_max_FG_bits
+);
+
+falcon_keypair_from_seed!(
+    falcon1024_keypair_from_seed,
+    10,
+    28672,
+    PQCLEAN_FALCON1024_CLEAN_keygen,
+    PQCLEAN_FALCON1024_CLEAN_trim_i8_encode,
+    PQCLEAN_FALCON1024_CLEAN_modq_encode,
+    PQCLEAN_FALCON1024_CLEAN_max_fg_bits,
+    PQCLEAN_FALCON1024_CLEAN_max_FG_bits
+);
+
+/// Deterministic Falcon key pair: (public_key, secret_key). `params` is
+/// "falcon512" or "falcon1024".
+// stub: params: Literal["falcon512", "falcon1024"]
+#[pyfunction]
+#[pyo3(signature = (seed, params = "falcon512"))]
+pub fn falcon_keygen_from_seed(
+    py: Python,
+    seed: Buffer,
+    params: &str,
+) -> PyResult<(Py<PyBytes>, Py<SecretBytes>)> {
+    use pqcrypto_falcon::{falcon1024, falcon512};
+
+    type KeypairFn = fn(&[u8; 48], &mut [u8], &mut [u8]) -> bool;
+    let (keypair, pk_len, sk_len): (KeypairFn, usize, usize) = match params {
+        "falcon512" => (
+            falcon512_keypair_from_seed,
+            falcon512::public_key_bytes(),
+            falcon512::secret_key_bytes(),
+        ),
+        "falcon1024" => (
+            falcon1024_keypair_from_seed,
+            falcon1024::public_key_bytes(),
+            falcon1024::secret_key_bytes(),
+        ),
+        _ => {
+            return Err(PyValueError::new_err(format!(
+                "unsupported Falcon parameter set '{}' (expected falcon512 or falcon1024)",
+                params
+            )))
+        }
+    };
+
+    let mut falcon_seed = Zeroizing::new([0u8; 48]);
+    expand_seed(params, &seed)?.squeeze(falcon_seed.as_mut());
+
+    let mut sk = None;
+    let pk = PyBytes::new_bound_with(py, pk_len, |pk_buf| {
+        sk = Some(secret_bytes_with(py, sk_len, |sk_buf| {
+            if !keypair(&falcon_seed, pk_buf, sk_buf) {
+                return Err(keygen_failed(params));
+            }
+            Ok(())
+        })?);
+        Ok(())
+    })?;
+
+    let sk = sk.expect("secret key initialised alongside public key");
+    let alg = find_signature(params).expect("Falcon parameter sets are registered");
+    checked_signature_keypair(py, alg, (pk.unbind(), sk))
+}
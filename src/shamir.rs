@@ -0,0 +1,300 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// Shamir secret sharing
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// Split a secret key across custodians so any `k` of the `n` shares rebuild
+// it and fewer reveal nothing about it:
+//
+//   shares = split_secret(sk, n=5, k=3)
+//   sk = combine_shares([shares[0], shares[2], shares[4]])
+//
+// Each byte of the secret is the constant term of its own random polynomial
+// of degree k - 1 over GF(2^8) (the AES field, x^8 + x^4 + x^3 + x + 1); share
+// i holds every polynomial evaluated at x = i. A share is
+//
+//   x u8 | k u8 | one byte per secret byte
+//
+// so combine_shares() can tell when it has been given too few. The field
+// arithmetic has no secret-dependent branches or table lookups. Shares aren't
+// authenticated: a corrupted share rebuilds the wrong secret, not an error.
+
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use zeroize::Zeroizing;
+
+use crate::buffer::Buffer;
+use crate::errors::InvalidLengthError;
+use crate::secret::{secret_bytes_with, SecretBytes};
+
+const SHARE_HEADER_LEN: usize = 2;
+
+// ─── GF(2^8) ──────────────────────────────────────────────────────────────────
+
+fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0u8;
+    for _ in 0..8 {
+        product ^= a & (b & 1).wrapping_neg();
+        // Multiply a by x, reducing by the field polynomial when it overflows.
+        let carry = (a >> 7).wrapping_neg();
+        a = (a << 1) ^ (carry & 0x1b);
+        b >>= 1;
+    }
+    product
+}
+
+/// a^254, which is a^-1 for non-zero a (and 0 for 0).
+fn gf_inv(a: u8) -> u8 {
+    let a2 = gf_mul(a, a);
+    let a3 = gf_mul(a2, a);
+    let a6 = gf_mul(a3, a3);
+    let a12 = gf_mul(a6, a6);
+    let a15 = gf_mul(a12, a3);
+    let a30 = gf_mul(a15, a15);
+    let a60 = gf_mul(a30, a30);
+    let a63 = gf_mul(a60, a3);
+    let a126 = gf_mul(a63, a63);
+    let a127 = gf_mul(a126, a);
+    gf_mul(a127, a127)
+}
+
+// ─── Python API ───────────────────────────────────────────────────────────────
+
+/// Split `secret` into `n` shares, any `k` of which rebuild it. Shares come
+/// back as SecretBytes, in x order (share i is for x = i + 1).
+#[pyfunction]
+pub fn split_secret(
+    py: Python,
+    secret: Buffer,
+    n: usize,
+    k: usize,
+) -> PyResult<Vec<Py<SecretBytes>>> {
+    if k < 2 || k > n || n > 255 {
+        return Err(PyValueError::new_err(format!(
+            "need 2 <= k <= n <= 255, got n={}, k={}",
+            n, k
+        )));
+    }
+    // Both fit in the share header's bytes now.
+    let (n, k) = (n as u8, k as u8);
+    if secret.is_empty() {
+        return Err(InvalidLengthError::new_err("cannot split an empty secret"));
+    }
+
+    // coefficients[j * len + b] is the x^(j+1) coefficient for byte b.
+    let len = secret.len();
+    let mut coefficients = Zeroizing::new(vec![0u8; (k as usize - 1) * len]);
+    getrandom::fill(&mut coefficients).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+
+    (1..=n)
+        .map(|x| {
+            secret_bytes_with(py, SHARE_HEADER_LEN + len, |share| {
+                share[0] = x;
+                share[1] = k;
+                for (b, y) in share[SHARE_HEADER_LEN..].iter_mut().enumerate() {
+                    // Horner's rule, from the highest-degree coefficient down.
+                    let mut acc = 0u8;
+                    for j in (0..k as usize - 1).rev() {
+                        acc = gf_mul(acc, x) ^ coefficients[j * len + b];
+                    }
+                    *y = gf_mul(acc, x) ^ secret[b];
+                }
+                Ok(())
+            })
+        })
+        .collect()
+}
+
+/// Rebuild a secret from at least `k` shares made by split_secret(). Extra
+/// shares beyond the first `k` are ignored.
+#[pyfunction]
+pub fn combine_shares(py: Python, shares: Vec<Buffer>) -> PyResult<Py<SecretBytes>> {
+    let first = shares
+        .first()
+        .ok_or_else(|| PyValueError::new_err("no shares given"))?;
+    if first.len() <= SHARE_HEADER_LEN {
+        return Err(InvalidLengthError::new_err(format!(
+            "share: expected more than {} bytes, got {}",
+            SHARE_HEADER_LEN,
+            first.len()
+        )));
+    }
+    let k = first[1] as usize;
+    if k < 2 {
+        return Err(PyValueError::new_err(format!(
+            "share has threshold {}, expected at least 2",
+            k
+        )));
+    }
+    if shares.len() < k {
+        return Err(PyValueError::new_err(format!(
+            "need {} shares to rebuild the secret, got {}",
+            k,
+            shares.len()
+        )));
+    }
+
+    let shares = &shares[..k];
+    for (i, share) in shares.iter().enumerate() {
+        if share.len() != first.len() {
+            return Err(InvalidLengthError::new_err(format!(
+                "share {}: expected {} bytes like the first share, got {}",
+                i,
+                first.len(),
+                share.len()
+            )));
+        }
+        if share[1] as usize != k {
+            return Err(PyValueError::new_err(format!(
+                "share {} has threshold {}, the first share has {}",
+                i, share[1], k
+            )));
+        }
+        if share[0] == 0 || shares[..i].iter().any(|s| s[0] == share[0]) {
+            return Err(PyValueError::new_err(format!(
+                "share {} has a zero or repeated x coordinate ({})",
+                i, share[0]
+            )));
+        }
+    }
+
+    // Lagrange basis at x = 0: l_i = prod_{j != i} x_j / (x_j - x_i), where
+    // subtraction in GF(2^8) is XOR.
+    let basis: Vec<u8> = shares
+        .iter()
+        .enumerate()
+        .map(|(i, si)| {
+            let (mut num, mut den) = (1u8, 1u8);
+            for (j, sj) in shares.iter().enumerate() {
+                if i != j {
+                    num = gf_mul(num, sj[0]);
+                    den = gf_mul(den, sj[0] ^ si[0]);
+                }
+            }
+            gf_mul(num, gf_inv(den))
+        })
+        .collect();
+
+    secret_bytes_with(py, first.len() - SHARE_HEADER_LEN, |secret| {
+        for (b, out) in secret.iter_mut().enumerate() {
+            *out = shares.iter().zip(&basis).fold(0, |acc, (share, &l)| {
+                acc ^ gf_mul(share[SHARE_HEADER_LEN + b], l)
+            });
+        }
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(py: Python, secret: &[u8], n: usize, k: usize) -> PyResult<Vec<Vec<u8>>> {
+        let shares = split_secret(py, Buffer::from_bytes(py, secret), n, k)?;
+        Ok(shares
+            .iter()
+            .map(|s| s.bind(py).extract::<Buffer>().unwrap().to_vec())
+            .collect())
+    }
+
+    fn combine(py: Python, shares: &[&[u8]]) -> PyResult<Vec<u8>> {
+        let shares = shares.iter().map(|s| Buffer::from_bytes(py, s)).collect();
+        let secret: Buffer = combine_shares(py, shares)?.bind(py).extract()?;
+        Ok(secret.to_vec())
+    }
+
+    #[test]
+    fn gf256_known_answers() {
+        // FIPS 197, section 4.2.
+        assert_eq!(gf_mul(0x57, 0x83), 0xc1);
+        assert_eq!(gf_mul(0x57, 0x13), 0xfe);
+        // The AES S-box is built on this inverse: S(0x53) = affine(0xca).
+        assert_eq!(gf_inv(0x53), 0xca);
+        assert_eq!(gf_inv(0), 0);
+        for a in 1..=255u8 {
+            assert_eq!(gf_mul(a, gf_inv(a)), 1, "a = {:#04x}", a);
+        }
+    }
+
+    #[test]
+    fn combine_known_answer() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            // f(x) = 0x42 + 0x57x, worked by hand with the products above:
+            // 0x57 * 2 = 0xae and 0x57 * 3 = 0xae ^ 0x57 = 0xf9.
+            let shares: [&[u8]; 3] = [&[1, 2, 0x15], &[2, 2, 0xec], &[3, 2, 0xbb]];
+            for (a, b) in [(0, 1), (0, 2), (1, 2), (2, 0)] {
+                assert_eq!(combine(py, &[shares[a], shares[b]]).unwrap(), [0x42]);
+            }
+        });
+    }
+
+    #[test]
+    fn any_k_shares_rebuild_the_secret() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let secret: Vec<u8> = (0..=255).collect();
+            let shares = split(py, &secret, 5, 3).unwrap();
+            assert_eq!(shares.len(), 5);
+            for (i, share) in shares.iter().enumerate() {
+                assert_eq!(share[..SHARE_HEADER_LEN], [i as u8 + 1, 3]);
+                assert_eq!(share.len(), SHARE_HEADER_LEN + secret.len());
+            }
+            for a in 0..5 {
+                for b in a + 1..5 {
+                    for c in b + 1..5 {
+                        let subset = [&shares[c][..], &shares[a], &shares[b]];
+                        assert_eq!(combine(py, &subset).unwrap(), secret);
+                    }
+                }
+            }
+            let all: Vec<&[u8]> = shares.iter().map(|s| &s[..]).collect();
+            assert_eq!(combine(py, &all).unwrap(), secret);
+
+            let shares = split(py, b"k", 255, 255).unwrap();
+            let all: Vec<&[u8]> = shares.iter().map(|s| &s[..]).collect();
+            assert_eq!(combine(py, &all).unwrap(), b"k");
+        });
+    }
+
+    #[test]
+    fn split_bounds() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            for (n, k) in [
+                (5, 1),
+                (5, 0),
+                (3, 4),
+                (256, 2),
+                (256, 256),
+                (usize::MAX, 2),
+            ] {
+                let err = split(py, b"secret", n, k).unwrap_err();
+                assert!(err.is_instance_of::<PyValueError>(py), "n={}, k={}", n, k);
+            }
+            let err = split(py, b"", 3, 2).unwrap_err();
+            assert!(err.is_instance_of::<InvalidLengthError>(py));
+        });
+    }
+
+    #[test]
+    fn combine_rejects_bad_share_sets() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let shares = split(py, b"secret", 5, 3).unwrap();
+            let value_error = |shares: &[&[u8]]| {
+                let err = combine(py, shares).unwrap_err();
+                assert!(err.is_instance_of::<PyValueError>(py));
+            };
+            // k - 1 shares, none, and a repeated x coordinate.
+            value_error(&[&shares[0], &shares[1]]);
+            value_error(&[]);
+            value_error(&[&shares[0], &shares[1], &shares[0]]);
+            let mut zero_x = shares[2].clone();
+            zero_x[0] = 0;
+            value_error(&[&shares[0], &shares[1], &zero_x]);
+
+            let err = combine(py, &[&shares[0], &shares[1], &shares[2][..5]]).unwrap_err();
+            assert!(err.is_instance_of::<InvalidLengthError>(py));
+        });
+    }
+}
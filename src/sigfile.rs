@@ -0,0 +1,246 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// Detached signature files
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// For signing release artifacts and checking them from scripts:
+//
+//   sign_file(sk, pk, "app.tar.gz")          # writes app.tar.gz.sig
+//   verify_file(pk, "app.tar.gz")            # True / False
+//
+//   sig = SignatureFile.read("app.tar.gz.sig")
+//   sig.algorithm, sig.fingerprint, sig.created
+//
+// A .sig file is binary, integers big-endian, times Unix seconds:
+//
+//   magic "PQSF" | version 1 | name length u8 | algorithm name
+//     | fingerprint(algorithm, pk) (32 bytes) | created u64
+//     | signature length u32 | signature
+//
+// The signature covers label || everything before the signature length ||
+// SHA-512(file), so the metadata can't be changed without breaking it, and
+// files of any size are hashed in chunks rather than read into memory. The
+// fingerprint is the one fingerprint() returns; verify_file() returns False
+// straight away for a public key that doesn't match it. sig_path defaults to
+// data_path with ".sig" appended. Algorithms are chosen as in
+// sign_attached().
+
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use sha2::{Digest, Sha512};
+
+use crate::attached::algorithm_named;
+use crate::buffer::Buffer;
+use crate::errors::{check_length, InvalidKeyError};
+use crate::metadata::now as current_time;
+use crate::sign::SignatureAlgorithm;
+use crate::signcrypt::falcon_for;
+use crate::util::{pinning_fingerprint, write_atomic, Reader};
+
+const MAGIC: &[u8; 4] = b"PQSF";
+const VERSION: u8 = 1;
+const LABEL: &[u8] = b"entropic-chaos signature file v1\0";
+
+const READ_CHUNK: usize = 64 * 1024;
+
+pub(crate) fn file_digest(path: &Path) -> PyResult<[u8; 64]> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha512::new();
+    let mut buf = vec![0u8; READ_CHUNK];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            return Ok(hasher.finalize().into());
+        }
+        hasher.update(&buf[..n]);
+    }
+}
+
+fn sig_path_for(data_path: &Path, sig_path: Option<PathBuf>) -> PathBuf {
+    sig_path.unwrap_or_else(|| {
+        let mut path = data_path.as_os_str().to_os_string();
+        path.push(".sig");
+        path.into()
+    })
+}
+
+#[pyclass(module = "pqcrypto_bindings")]
+pub struct SignatureFile {
+    alg: &'static SignatureAlgorithm,
+    fingerprint: [u8; 32],
+    created: u64,
+    signature: Vec<u8>,
+}
+
+impl SignatureFile {
+    fn header(&self) -> Vec<u8> {
+        let mut header = Vec::with_capacity(MAGIC.len() + 2 + self.alg.name.len() + 40);
+        header.extend_from_slice(MAGIC);
+        header.push(VERSION);
+        header.push(self.alg.name.len() as u8);
+        header.extend_from_slice(self.alg.name.as_bytes());
+        header.extend_from_slice(&self.fingerprint);
+        header.extend_from_slice(&self.created.to_be_bytes());
+        header
+    }
+
+    /// What gets signed: the label, the file up to the signature, the digest.
+    fn signed_data(&self, digest: &[u8]) -> Vec<u8> {
+        [LABEL, &self.header(), digest].concat()
+    }
+
+    fn parse(data: &[u8]) -> PyResult<Self> {
+        let mut r = Reader::new("signature file", data);
+        if r.take(MAGIC.len())? != MAGIC {
+            return Err(PyValueError::new_err("not a signature file"));
+        }
+        let version = r.u8()?;
+        if version != VERSION {
+            return Err(PyValueError::new_err(format!(
+                "unsupported signature file version {}",
+                version
+            )));
+        }
+        let name_len = r.u8()? as usize;
+        let name = std::str::from_utf8(r.take(name_len)?)
+            .map_err(|_| PyValueError::new_err("signature file algorithm name isn't UTF-8"))?;
+        let alg = algorithm_named(name)?;
+        let fingerprint = r.take(32)?.try_into().unwrap();
+        let created = r.u64()?;
+        let sig_len = r.u32()? as usize;
+        let signature = r.take(sig_len)?.to_vec();
+        r.finish()?;
+        Ok(SignatureFile {
+            alg,
+            fingerprint,
+            created,
+            signature,
+        })
+    }
+}
+
+#[pymethods]
+impl SignatureFile {
+    #[staticmethod]
+    fn from_bytes(data: Buffer) -> PyResult<Self> {
+        Self::parse(&data)
+    }
+
+    /// Parse the .sig file at `path`.
+    #[staticmethod]
+    fn read(path: PathBuf) -> PyResult<Self> {
+        Self::parse(&std::fs::read(path)?)
+    }
+
+    fn to_bytes(&self, py: Python) -> Py<PyBytes> {
+        let mut data = self.header();
+        data.extend_from_slice(&(self.signature.len() as u32).to_be_bytes());
+        data.extend_from_slice(&self.signature);
+        PyBytes::new_bound(py, &data).unbind()
+    }
+
+    /// Write the .sig file to `path`, replacing it atomically.
+    fn write(&self, py: Python, path: PathBuf) -> PyResult<()> {
+        write_atomic(&path, self.to_bytes(py).as_bytes(py))
+    }
+
+    #[getter]
+    fn algorithm(&self) -> &'static str {
+        self.alg.name
+    }
+
+    /// fingerprint(algorithm, pk) of the signing key.
+    #[getter]
+    fn fingerprint<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new_bound(py, &self.fingerprint)
+    }
+
+    /// When the file was signed, by the signer's clock.
+    #[getter]
+    fn created(&self) -> u64 {
+        self.created
+    }
+
+    #[getter]
+    fn signature<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new_bound(py, &self.signature)
+    }
+
+    /// Whether this is a valid signature by `pk` over the file at `data_path`.
+    fn verify(&self, py: Python, pk: Buffer, data_path: PathBuf) -> PyResult<bool> {
+        if pk.len() != self.alg.public_key_bytes
+            || pinning_fingerprint(self.alg.name, &pk) != self.fingerprint
+        {
+            return Ok(false);
+        }
+        let signed = PyBytes::new_bound(py, &self.signed_data(&file_digest(&data_path)?));
+        let sig = PyBytes::new_bound(py, &self.signature);
+        (self.alg.verify)(pk, signed.extract()?, sig.extract()?)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "SignatureFile(algorithm='{}', created={})",
+            self.alg.name, self.created
+        )
+    }
+}
+
+/// Sign the file at `data_path` with `sk`, whose public key is `pk`, and
+/// write the .sig file to `sig_path`. Returns the SignatureFile written.
+#[pyfunction]
+#[pyo3(signature = (sk, pk, data_path, sig_path = None, algorithm = None, created = None))]
+pub fn sign_file(
+    py: Python,
+    sk: Buffer,
+    pk: Buffer,
+    data_path: PathBuf,
+    sig_path: Option<PathBuf>,
+    algorithm: Option<&str>,
+    created: Option<u64>,
+) -> PyResult<SignatureFile> {
+    let alg = match algorithm {
+        Some(name) => algorithm_named(name)?,
+        None => falcon_for("secret key", sk.len(), |alg| alg.secret_key_bytes)?,
+    };
+    check_length(
+        &format!("{} public key", alg.name),
+        &pk,
+        alg.public_key_bytes,
+    )?;
+    let mut sig_file = SignatureFile {
+        alg,
+        fingerprint: pinning_fingerprint(alg.name, &pk),
+        created: created.unwrap_or_else(current_time),
+        signature: Vec::new(),
+    };
+    let signed = PyBytes::new_bound(py, &sig_file.signed_data(&file_digest(&data_path)?));
+    let sig = (alg.sign)(py, sk, signed.extract()?)?;
+    if !(alg.verify)(pk, signed.extract()?, sig.bind(py).extract()?)? {
+        return Err(InvalidKeyError::new_err(format!(
+            "{} public key doesn't match the secret key",
+            alg.name
+        )));
+    }
+    sig_file.signature = sig.as_bytes(py).to_vec();
+    sig_file.write(py, sig_path_for(&data_path, sig_path))?;
+    Ok(sig_file)
+}
+
+/// Whether the .sig file at `sig_path` is a valid signature by `pk` over the
+/// file at `data_path`. A malformed .sig file raises ValueError.
+#[pyfunction]
+#[pyo3(signature = (pk, data_path, sig_path = None))]
+pub fn verify_file(
+    py: Python,
+    pk: Buffer,
+    data_path: PathBuf,
+    sig_path: Option<PathBuf>,
+) -> PyResult<bool> {
+    let sig_path = sig_path_for(&data_path, sig_path);
+    SignatureFile::read(sig_path)?.verify(py, pk, data_path)
+}
@@ -0,0 +1,189 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// Generic signature dispatch
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// The signature counterpart of KEM(name):
+//
+//   sig = Signature("falcon512")
+//   pk, sk = sig.keygen()
+//   s = sig.sign(sk, msg)
+//   sig.verify(pk, msg, s)          # -> bool
+//   sig.verify_strict(pk, msg, s)   # raises SignatureVerificationError
+//
+// Names follow the same matching rules as KEM(). Options specific to one
+// scheme (ML-DSA's mode=, context strings) take their defaults here; call
+// the per-algorithm functions to set them. The stateful XMSS/LMS
+// schemes are left out on purpose: their keys are state objects, not bytes.
+
+use std::borrow::Cow;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+use crate::buffer::Buffer;
+use crate::errors::{verification_failed, InvalidLengthError};
+use crate::kem::normalize_name;
+use crate::secret::SecretBytes;
+
+type KeygenFn = fn(Python) -> PyResult<(Py<PyBytes>, Py<SecretBytes>)>;
+type SignFn = fn(Python, Buffer, Buffer) -> PyResult<Py<PyBytes>>;
+pub(crate) type VerifyFn = fn(Buffer, Buffer, Buffer) -> PyResult<bool>;
+
+pub(crate) struct SignatureAlgorithm {
+    pub name: &'static str,
+    pub public_key_bytes: usize,
+    pub secret_key_bytes: usize,
+    /// Upper bound; Falcon and the composites produce shorter signatures.
+    pub signature_bytes: usize,
+    pub keygen: KeygenFn,
+    pub sign: SignFn,
+    pub verify: VerifyFn,
+}
+
+pub(crate) const SIGNATURES: &[&SignatureAlgorithm] = &[
+    &crate::falcon512::ALGORITHM,
+    &crate::falcon1024::ALGORITHM,
+    &crate::mldsa44::ALGORITHM,
+    &crate::mldsa65::ALGORITHM,
+    &crate::mldsa87::ALGORITHM,
+    &crate::sphincssha2128ssimple::ALGORITHM,
+    &crate::sphincssha2192ssimple::ALGORITHM,
+    &crate::sphincssha2256ssimple::ALGORITHM,
+    &crate::sphincsshake128fsimple::ALGORITHM,
+    &crate::sphincsshake192fsimple::ALGORITHM,
+    &crate::sphincsshake256fsimple::ALGORITHM,
+    &crate::classical::ED25519,
+    &crate::hybrid::ED25519_FALCON512,
+    &crate::hybrid::MLDSA44_P256,
+    &crate::hybrid::MLDSA65_P256,
+];
+
+/// verify() that raises SignatureVerificationError instead of returning
+/// False. Malformed keys and signatures raise as they do from verify().
+pub(crate) fn verify_strict(
+    alg: &SignatureAlgorithm,
+    pk_bytes: Buffer,
+    msg: Buffer,
+    sig_bytes: Buffer,
+) -> PyResult<()> {
+    let (msg_len, sig_len) = (msg.len(), sig_bytes.len());
+    if (alg.verify)(pk_bytes, msg, sig_bytes)? {
+        Ok(())
+    } else {
+        Err(verification_failed(alg.name, msg_len, sig_len))
+    }
+}
+
+/// Longest context string, as in FIPS 204 and RFC 8032.
+const MAX_CONTEXT_LEN: usize = 255;
+
+/// Prefix for context-bound messages under schemes with no context input of
+/// their own.
+const CONTEXT_LABEL: &[u8] = b"entropic-chaos signature context\0";
+
+/// Check a `context=` argument: at most 255 bytes.
+pub(crate) fn check_context(context: &[u8]) -> PyResult<()> {
+    if context.len() > MAX_CONTEXT_LEN {
+        return Err(InvalidLengthError::new_err(format!(
+            "context string must be at most {} bytes, got {}",
+            MAX_CONTEXT_LEN,
+            context.len()
+        )));
+    }
+    Ok(())
+}
+
+/// What Falcon, SPHINCS+ and Ed25519, which have no context input here, sign
+/// for `msg` under `context`: `msg` itself for the empty context, so existing
+/// signatures still verify, and otherwise
+///
+///   label || context length u8 || context || msg
+pub(crate) fn context_message<'a>(context: &[u8], msg: &'a [u8]) -> PyResult<Cow<'a, [u8]>> {
+    check_context(context)?;
+    if context.is_empty() {
+        return Ok(Cow::Borrowed(msg));
+    }
+    let mut m = Vec::with_capacity(CONTEXT_LABEL.len() + 1 + context.len() + msg.len());
+    m.extend_from_slice(CONTEXT_LABEL);
+    m.push(context.len() as u8);
+    m.extend_from_slice(context);
+    m.extend_from_slice(msg);
+    Ok(Cow::Owned(m))
+}
+
+pub(crate) fn find_signature(name: &str) -> Option<&'static SignatureAlgorithm> {
+    let wanted = normalize_name(name);
+    SIGNATURES.iter().copied().find(|alg| alg.name == wanted)
+}
+
+/// Fill the `sign` submodule: the Signature class plus one instance per
+/// scheme, e.g. `sign.falcon512.sign(sk, msg)`.
+pub(crate) fn add_to_submodule(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<Signature>()?;
+    for alg in SIGNATURES {
+        m.add(alg.name, Signature { alg })?;
+    }
+    Ok(())
+}
+
+#[pyclass(module = "pqcrypto_bindings", name = "Signature", frozen)]
+pub struct Signature {
+    alg: &'static SignatureAlgorithm,
+}
+
+#[pymethods]
+impl Signature {
+    #[new]
+    fn new(name: &str) -> PyResult<Self> {
+        let alg = find_signature(name).ok_or_else(|| {
+            PyValueError::new_err(format!("unsupported signature scheme '{}'", name))
+        })?;
+        Ok(Signature { alg })
+    }
+
+    /// Canonical algorithm name, e.g. "falcon512".
+    #[getter]
+    fn name(&self) -> &'static str {
+        self.alg.name
+    }
+
+    #[getter]
+    fn public_key_bytes(&self) -> usize {
+        self.alg.public_key_bytes
+    }
+
+    #[getter]
+    fn secret_key_bytes(&self) -> usize {
+        self.alg.secret_key_bytes
+    }
+
+    /// Maximum signature length.
+    #[getter]
+    fn signature_bytes(&self) -> usize {
+        self.alg.signature_bytes
+    }
+
+    /// (public_key, secret_key)
+    fn keygen(&self, py: Python) -> PyResult<(Py<PyBytes>, Py<SecretBytes>)> {
+        (self.alg.keygen)(py)
+    }
+
+    fn sign(&self, py: Python, sk_bytes: Buffer, msg: Buffer) -> PyResult<Py<PyBytes>> {
+        (self.alg.sign)(py, sk_bytes, msg)
+    }
+
+    fn verify(&self, pk_bytes: Buffer, msg: Buffer, sig_bytes: Buffer) -> PyResult<bool> {
+        (self.alg.verify)(pk_bytes, msg, sig_bytes)
+    }
+
+    /// Like verify(), but raises SignatureVerificationError instead of
+    /// returning False.
+    fn verify_strict(&self, pk_bytes: Buffer, msg: Buffer, sig_bytes: Buffer) -> PyResult<()> {
+        verify_strict(self.alg, pk_bytes, msg, sig_bytes)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Signature('{}')", self.alg.name)
+    }
+}
@@ -0,0 +1,152 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// Signcryption: Falcon-signed, Kyber-sealed messages
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// For a message only the recipient can read and only the sender could have
+// written:
+//
+//   blob = sign_and_seal(alice_sk, bob_pk, b"meet at noon")
+//   msg = open_and_verify(bob_sk, alice_pk, blob)
+//
+// Sign, then encrypt, with the signature bound to the recipient:
+//
+//   signed = label || SHA-256(recipient pk) || aad length u32 || aad || msg
+//   sig    = Falcon-Sign(sender sk, signed)
+//   blob   = seal(recipient pk, sig length u16 || sig || msg,
+//                 aad = label || aad)
+//
+// Binding the recipient's key stops surreptitious forwarding (Davis,
+// "Defective Sign & Encrypt", 2001): Bob can't re-seal Alice's signed
+// message to Carol and pass it off as addressed to her. Encrypting the
+// signature hides who sent the message from anyone but the recipient. The
+// label keeps these blobs and signatures from being taken for plain seal()
+// blobs or signatures over `msg`.
+//
+// Falcon-512 / 1024 and Kyber-512 / 768 / 1024 are picked by key length. The
+// recipient's public key is read out of their Kyber secret key, so
+// open_and_verify() needs only that and the sender's public key. A wrong
+// recipient key or a modified blob raises DecryptionError; a blob that
+// decrypts but wasn't signed by `sender_pk` raises SignatureVerificationError.
+
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use sha2::{Digest, Sha256};
+
+use crate::aead::Aead;
+use crate::buffer::Buffer;
+use crate::errors::{verification_failed, InvalidLengthError};
+use crate::recover::kyber_public_from_secret;
+use crate::seal::{open_blob, seal_single, Suite};
+use crate::sign::SignatureAlgorithm;
+use crate::util::Reader;
+
+const LABEL: &[u8] = b"entropic-chaos signcrypt v1\0";
+
+const FALCON: &[&SignatureAlgorithm] =
+    &[&crate::falcon512::ALGORITHM, &crate::falcon1024::ALGORITHM];
+
+pub(crate) fn falcon_for(
+    what: &str,
+    key_len: usize,
+    key_bytes: fn(&SignatureAlgorithm) -> usize,
+) -> PyResult<&'static SignatureAlgorithm> {
+    FALCON
+        .iter()
+        .copied()
+        .find(|alg| key_bytes(alg) == key_len)
+        .ok_or_else(|| {
+            InvalidLengthError::new_err(format!(
+                "{}-byte {} isn't Falcon-512 / 1024",
+                key_len, what
+            ))
+        })
+}
+
+/// What the sender signs.
+fn signed_data(recipient_pk: &[u8], aad: &[u8], msg: &[u8]) -> Vec<u8> {
+    let mut signed = Vec::with_capacity(LABEL.len() + 32 + 4 + aad.len() + msg.len());
+    signed.extend_from_slice(LABEL);
+    signed.extend_from_slice(&Sha256::digest(recipient_pk));
+    signed.extend_from_slice(&(aad.len() as u32).to_be_bytes());
+    signed.extend_from_slice(aad);
+    signed.extend_from_slice(msg);
+    signed
+}
+
+fn seal_aad(aad: &[u8]) -> Vec<u8> {
+    let mut ad = Vec::with_capacity(LABEL.len() + aad.len());
+    ad.extend_from_slice(LABEL);
+    ad.extend_from_slice(aad);
+    ad
+}
+
+/// Sign `msg` with the sender's Falcon secret key, then seal it to the
+/// recipient's Kyber public key. `aad` is signed and authenticated but not
+/// included in the blob; open_and_verify() must be given the same bytes.
+#[pyfunction]
+#[pyo3(signature = (sender_sk, recipient_pk, msg, aad = None))]
+pub fn sign_and_seal(
+    py: Python,
+    sender_sk: Buffer,
+    recipient_pk: Buffer,
+    msg: Buffer,
+    aad: Option<Buffer>,
+) -> PyResult<Py<PyBytes>> {
+    let aad = aad.as_deref().unwrap_or_default();
+    let alg = falcon_for("sender secret key", sender_sk.len(), |alg| {
+        alg.secret_key_bytes
+    })?;
+    if u32::try_from(aad.len()).is_err() {
+        return Err(InvalidLengthError::new_err(
+            "aad longer than 2^32 - 1 bytes",
+        ));
+    }
+
+    let signed = PyBytes::new_bound(py, &signed_data(&recipient_pk, aad, &msg));
+    let sig = (alg.sign)(py, sender_sk, signed.extract()?)?;
+    let sig = sig.as_bytes(py);
+
+    let mut inner = Vec::with_capacity(2 + sig.len() + msg.len());
+    inner.extend_from_slice(&(sig.len() as u16).to_be_bytes());
+    inner.extend_from_slice(sig);
+    inner.extend_from_slice(&msg);
+
+    let suite = Suite {
+        aead: Aead::ChaCha20Poly1305,
+        committing: false,
+    };
+    let blob = seal_single(py, suite, recipient_pk, &inner, &seal_aad(aad), None)?;
+    Ok(PyBytes::new_bound(py, &blob).unbind())
+}
+
+/// Decrypt a sign_and_seal() blob with the recipient's Kyber secret key and
+/// verify it against the sender's Falcon public key. Returns the message.
+// stub: recipient_sk: Buffer
+#[pyfunction]
+#[pyo3(signature = (recipient_sk, sender_pk, blob, aad = None))]
+pub fn open_and_verify(
+    py: Python,
+    recipient_sk: &Bound<'_, PyAny>,
+    sender_pk: Buffer,
+    blob: Buffer,
+    aad: Option<Buffer>,
+) -> PyResult<Py<PyBytes>> {
+    let aad = aad.as_deref().unwrap_or_default();
+    let alg = falcon_for("sender public key", sender_pk.len(), |alg| {
+        alg.public_key_bytes
+    })?;
+    let recipient_pk = kyber_public_from_secret(py, recipient_sk.extract()?)?;
+
+    let inner = open_blob(py, recipient_sk, &blob, &seal_aad(aad), false)?;
+    let mut r = Reader::new("signcrypted message", &inner);
+    let sig_len = r.u16()? as usize;
+    let sig = r.take(sig_len)?;
+    let msg = r.rest();
+
+    let signed = PyBytes::new_bound(py, &signed_data(recipient_pk.as_bytes(py), aad, msg));
+    let sig_bytes = PyBytes::new_bound(py, sig);
+    if !(alg.verify)(sender_pk, signed.extract()?, sig_bytes.extract()?)? {
+        return Err(verification_failed(alg.name, msg.len(), sig.len()));
+    }
+    Ok(PyBytes::new_bound(py, msg).unbind())
+}
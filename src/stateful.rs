@@ -0,0 +1,587 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// Stateful hash-based signatures (NIST SP 800-208)
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// Every XMSS/LMS leaf is a one-time signature key: signing twice with the
+// same leaf leaks enough to forge. So unlike the stateless schemes in lib.rs the
+// secret key never crosses into Python as plain bytes to be passed back in.
+// It lives inside a state object that owns the leaf index, advances it before
+// a signature is released, and refuses to hand out a leaf twice.
+//
+// Persist `state.to_bytes()` after every sign() and before publishing the
+// signature; a restored state that is behind a leaf this process has already
+// used is rejected.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use pyo3::exceptions::{PyRuntimeError, PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use zeroize::Zeroizing;
+
+use crate::buffer::Buffer;
+use crate::errors::{
+    check_length, verification_failed, InvalidKeyError, InvalidLengthError, SignatureError,
+};
+use crate::util::{ct_eq, fingerprint};
+
+// ─── Leaf high-water marks ────────────────────────────────────────────────────
+//
+// Public key -> next unused leaf, for every key this process has signed with.
+// Catches stale copies of a state (an old backup, a second from_bytes() of the
+// same blob) before they can reuse a leaf.
+
+static HIGH_WATER: Mutex<BTreeMap<Vec<u8>, u64>> = Mutex::new(BTreeMap::new());
+
+fn leaf_high_water(pk: &[u8]) -> u64 {
+    let marks = HIGH_WATER.lock().unwrap_or_else(|e| e.into_inner());
+    marks.get(pk).copied().unwrap_or(0)
+}
+
+fn ensure_leaf_unused(pk: &[u8], index: u64) -> PyResult<()> {
+    let used = leaf_high_water(pk);
+    if index < used {
+        return Err(SignatureError::new_err(format!(
+            "state rollback: leaf {} is behind leaf {} already used by this key",
+            index, used
+        )));
+    }
+    Ok(())
+}
+
+fn record_leaf_used(pk: &[u8], index: u64) {
+    let mut marks = HIGH_WATER.lock().unwrap_or_else(|e| e.into_inner());
+    let next = marks.entry(pk.to_vec()).or_insert(0);
+    *next = (*next).max(index + 1);
+}
+
+fn state_pickle_error(class: &str) -> PyErr {
+    PyTypeError::new_err(format!(
+        "cannot pickle '{class}': a copy would sign with the same leaves; \
+         persist state.to_bytes() and restore with from_bytes() instead"
+    ))
+}
+
+fn random_seed(len: usize) -> PyResult<Zeroizing<Vec<u8>>> {
+    let mut seed = Zeroizing::new(vec![0u8; len]);
+    getrandom::fill(&mut seed).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+    Ok(seed)
+}
+
+// ───────────────────────────────────────────────────────────────────────────────
+// XMSS (RFC 8391)
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// XMSS-SHA2_10_256 only, for now. The underlying core rebuilds the whole tree
+// to compute the authentication path on every signature, so signing costs as
+// much as keygen: well under a second at h = 10, but tens of seconds per
+// signature at h = 16. Taller trees wait for a core with BDS traversal.
+
+use xmss::{XmssParameter, XmssSha2_10_256};
+
+const XMSS_OID_LEN: usize = 4;
+const XMSS_INDEX_LEN: usize = 4;
+
+fn xmss_err(e: xmss::Error) -> PyErr {
+    match e {
+        xmss::Error::InvalidKeyLength { .. }
+        | xmss::Error::InvalidSignatureLength { .. }
+        | xmss::Error::InvalidSeedLength { .. } => InvalidLengthError::new_err(e.to_string()),
+        xmss::Error::InvalidOid(_) => InvalidKeyError::new_err(e.to_string()),
+        _ => SignatureError::new_err(e.to_string()),
+    }
+}
+
+/// Dispatch on an XMSS parameter-set name, binding `$p` to the matching type.
+macro_rules! with_xmss_params {
+    ($name:expr, $p:ident => $body:expr) => {
+        match $name {
+            "XMSS-SHA2_10_256" => {
+                type $p = XmssSha2_10_256;
+                $body
+            }
+            other => Err(PyValueError::new_err(format!(
+                "unsupported XMSS parameter set: {}",
+                other
+            ))),
+        }
+    };
+}
+
+fn xmss_params_from_oid(bytes: &[u8]) -> PyResult<(&'static str, u32)> {
+    let oid = bytes
+        .get(..XMSS_OID_LEN)
+        .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or_else(|| InvalidLengthError::new_err("XMSS key too short to carry an OID"))?;
+
+    match oid {
+        0x0000_0001 => Ok((XmssSha2_10_256::NAME, 10)),
+        other => Err(InvalidKeyError::new_err(format!(
+            "unsupported XMSS OID: 0x{:08x}",
+            other
+        ))),
+    }
+}
+
+/// XMSS signing state: the secret key plus its next unused leaf.
+#[pyclass(module = "pqcrypto_bindings")]
+pub struct XmssState {
+    params: &'static str,
+    height: u32,
+    sk: Zeroizing<Vec<u8>>,
+    pk: Vec<u8>,
+}
+
+impl XmssState {
+    fn next_index(&self) -> u64 {
+        let b = &self.sk[XMSS_OID_LEN..XMSS_OID_LEN + XMSS_INDEX_LEN];
+        u64::from(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn max_signatures(&self) -> u64 {
+        1u64 << self.height
+    }
+}
+
+#[pymethods]
+impl XmssState {
+    /// Parameter set name, e.g. "XMSS-SHA2_10_256".
+    #[getter]
+    fn params(&self) -> &'static str {
+        self.params
+    }
+
+    /// Index of the next leaf sign() will use.
+    #[getter]
+    fn index(&self) -> u64 {
+        self.next_index()
+    }
+
+    /// Total number of signatures this key can ever produce.
+    #[getter(max_signatures)]
+    fn max_signatures_py(&self) -> u64 {
+        self.max_signatures()
+    }
+
+    /// Signatures left before the key is exhausted.
+    #[getter]
+    fn remaining(&self) -> u64 {
+        self.max_signatures().saturating_sub(self.next_index())
+    }
+
+    fn public_key(&self, py: Python) -> Py<PyBytes> {
+        PyBytes::new_bound(py, &self.pk).unbind()
+    }
+
+    /// Serialized secret key including the current leaf index.
+    fn to_bytes(&self, py: Python) -> Py<PyBytes> {
+        PyBytes::new_bound(py, &self.sk).unbind()
+    }
+
+    #[staticmethod]
+    fn from_bytes(sk_bytes: Buffer) -> PyResult<Self> {
+        let (params, height) = xmss_params_from_oid(&sk_bytes)?;
+
+        let pk = with_xmss_params!(params, P => {
+            let sk = xmss::SigningKey::<P>::try_from(&*sk_bytes).map_err(xmss_err)?;
+            let vk = xmss::VerifyingKey::<P>::from(&sk);
+            Ok(vk.as_ref().to_vec())
+        })?;
+
+        let state = XmssState {
+            params,
+            height,
+            sk: Zeroizing::new(sk_bytes.to_vec()),
+            pk,
+        };
+        ensure_leaf_unused(&state.pk, state.next_index())?;
+
+        Ok(state)
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        ct_eq(&self.sk, &other.sk)
+    }
+
+    /// Never picklable, not even with an opt-in: an unpickled copy signing in
+    /// another process would reuse the same leaves.
+    fn __reduce__(&self) -> PyResult<()> {
+        Err(state_pickle_error("XmssState"))
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "XmssState(params='{}', fingerprint='{}', index={}, remaining={})",
+            self.params,
+            fingerprint(&self.pk),
+            self.next_index(),
+            self.remaining()
+        )
+    }
+}
+
+// ─── XMSS: keygen(params) -> (public_key, state) ──────────────────────────────
+
+#[pyfunction]
+#[pyo3(signature = (params = "XMSS-SHA2_10_256"))]
+pub fn xmss_keygen(py: Python, params: &str) -> PyResult<(Py<PyBytes>, XmssState)> {
+    let state = with_xmss_params!(params, P => {
+        let seed = random_seed(P::SEED_LEN)?;
+        let mut kp = xmss::KeyPair::<P>::from_seed(&seed).map_err(xmss_err)?;
+
+        let pk = kp.verifying_key().as_ref().to_vec();
+        let sk = Zeroizing::new(kp.signing_key().as_ref().to_vec());
+        let (params, height) = xmss_params_from_oid(&pk)?;
+
+        Ok(XmssState { params, height, sk, pk })
+    })?;
+
+    Ok((PyBytes::new_bound(py, &state.pk).unbind(), state))
+}
+
+// ─── XMSS: sign(state, msg) -> detached signature, advancing the state ────────
+
+#[pyfunction]
+pub fn xmss_sign(py: Python, state: &Bound<'_, XmssState>, msg: Buffer) -> PyResult<Py<PyBytes>> {
+    let mut state = state.borrow_mut();
+
+    let index = state.next_index();
+    if index >= state.max_signatures() {
+        return Err(SignatureError::new_err(format!(
+            "{} key exhausted: all {} signatures used",
+            state.params,
+            state.max_signatures()
+        )));
+    }
+    ensure_leaf_unused(&state.pk, index)?;
+
+    let sig = with_xmss_params!(state.params, P => {
+        let mut sk = xmss::SigningKey::<P>::try_from(state.sk.as_slice()).map_err(xmss_err)?;
+        let sig = sk.sign_detached(&msg).map_err(xmss_err)?;
+        state.sk.copy_from_slice(sk.as_ref());
+        Ok(sig.as_ref().to_vec())
+    })?;
+    record_leaf_used(&state.pk, index);
+
+    Ok(PyBytes::new_bound(py, &sig).unbind())
+}
+
+// ─── XMSS: verify(pk, msg, sig) -> bool, verify_strict raises ─────────────────
+
+#[pyfunction]
+pub fn xmss_verify(pk_bytes: Buffer, msg: Buffer, sig_bytes: Buffer) -> PyResult<bool> {
+    let (params, _) = xmss_params_from_oid(&pk_bytes)?;
+
+    with_xmss_params!(params, P => {
+        let pk = xmss::VerifyingKey::<P>::try_from(&*pk_bytes).map_err(xmss_err)?;
+        let sig = xmss::DetachedSignature::<P>::try_from(&*sig_bytes).map_err(xmss_err)?;
+        Ok(pk.verify_detached(&sig, &msg).is_ok())
+    })
+}
+
+#[pyfunction]
+pub fn xmss_verify_strict(pk_bytes: Buffer, msg: Buffer, sig_bytes: Buffer) -> PyResult<()> {
+    let (params, _) = xmss_params_from_oid(&pk_bytes)?;
+    let (msg_len, sig_len) = (msg.len(), sig_bytes.len());
+    if xmss_verify(pk_bytes, msg, sig_bytes)? {
+        Ok(())
+    } else {
+        Err(verification_failed(params, msg_len, sig_len))
+    }
+}
+
+// ───────────────────────────────────────────────────────────────────────────────
+// LMS / HSS (RFC 8554)
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// SHA-256/M32 trees, one entry per HSS level; a single level is plain LMS.
+// Signing rebuilds each level's tree, so [10] costs about what XMSS-SHA2_10_256
+// does while [10, 10] gives a million signatures for twice that.
+
+use hbs_lms::{HssParameter, LmotsAlgorithm, LmsAlgorithm, Seed, Sha256_256};
+
+type LmsHash = Sha256_256;
+
+const LMS_INDEX_LEN: usize = 8;
+const LMS_MAX_LEVELS: usize = 8;
+const LMS_PARAM_SET_END: u8 = 0xff;
+
+fn lms_err(what: &str) -> PyErr {
+    SignatureError::new_err(format!("LMS {} failed", what))
+}
+
+fn lms_algorithm(height: u32) -> PyResult<LmsAlgorithm> {
+    match height {
+        5 => Ok(LmsAlgorithm::LmsH5),
+        10 => Ok(LmsAlgorithm::LmsH10),
+        15 => Ok(LmsAlgorithm::LmsH15),
+        20 => Ok(LmsAlgorithm::LmsH20),
+        25 => Ok(LmsAlgorithm::LmsH25),
+        other => Err(PyValueError::new_err(format!(
+            "unsupported LMS tree height: {} (expected 5, 10, 15, 20 or 25)",
+            other
+        ))),
+    }
+}
+
+fn lmots_algorithm(winternitz: u32) -> PyResult<LmotsAlgorithm> {
+    match winternitz {
+        1 => Ok(LmotsAlgorithm::LmotsW1),
+        2 => Ok(LmotsAlgorithm::LmotsW2),
+        4 => Ok(LmotsAlgorithm::LmotsW4),
+        8 => Ok(LmotsAlgorithm::LmotsW8),
+        other => Err(PyValueError::new_err(format!(
+            "unsupported LM-OTS Winternitz parameter: {} (expected 1, 2, 4 or 8)",
+            other
+        ))),
+    }
+}
+
+/// Decode the per-level (height, winternitz) pairs packed into a secret key.
+fn lms_levels_from_sk(sk: &[u8]) -> PyResult<Vec<(u32, u32)>> {
+    let packed = sk
+        .get(LMS_INDEX_LEN..LMS_INDEX_LEN + LMS_MAX_LEVELS)
+        .ok_or_else(|| InvalidLengthError::new_err("LMS secret key too short"))?;
+
+    let mut levels = Vec::new();
+    for &b in packed.iter().take_while(|&&b| b != LMS_PARAM_SET_END) {
+        let height = match b >> 4 {
+            5 => 5,
+            6 => 10,
+            7 => 15,
+            8 => 20,
+            9 => 25,
+            other => {
+                return Err(InvalidKeyError::new_err(format!(
+                    "unsupported LMS typecode in secret key: {}",
+                    other
+                )))
+            }
+        };
+        let winternitz = match b & 0x0f {
+            1 => 1,
+            2 => 2,
+            3 => 4,
+            4 => 8,
+            other => {
+                return Err(InvalidKeyError::new_err(format!(
+                    "unsupported LM-OTS typecode in secret key: {}",
+                    other
+                )))
+            }
+        };
+        levels.push((height, winternitz));
+    }
+
+    if levels.is_empty() {
+        return Err(InvalidKeyError::new_err("LMS secret key has no levels"));
+    }
+    Ok(levels)
+}
+
+fn lms_parameters(levels: &[(u32, u32)]) -> PyResult<Vec<HssParameter<LmsHash>>> {
+    if levels.is_empty() || levels.len() > LMS_MAX_LEVELS {
+        return Err(PyValueError::new_err(format!(
+            "HSS needs 1 to {} levels, got {}",
+            LMS_MAX_LEVELS,
+            levels.len()
+        )));
+    }
+
+    levels
+        .iter()
+        .map(|&(h, w)| Ok(HssParameter::new(lmots_algorithm(w)?, lms_algorithm(h)?)))
+        .collect()
+}
+
+/// LMS/HSS signing state: the secret key plus its next unused leaf.
+#[pyclass(module = "pqcrypto_bindings")]
+pub struct LmsState {
+    levels: Vec<(u32, u32)>,
+    sk: Zeroizing<Vec<u8>>,
+    pk: Vec<u8>,
+}
+
+impl LmsState {
+    fn from_keygen(levels: Vec<(u32, u32)>, seed: &[u8]) -> PyResult<Self> {
+        let parameters = lms_parameters(&levels)?;
+
+        let mut lms_seed = Seed::<LmsHash>::default();
+        check_length("LMS seed", seed, lms_seed.len())?;
+        lms_seed.as_mut_slice().copy_from_slice(seed);
+
+        let (sk, pk) = hbs_lms::keygen::<LmsHash>(&parameters, &lms_seed, None)
+            .map_err(|_| lms_err("keygen"))?;
+
+        Ok(LmsState {
+            levels,
+            sk: Zeroizing::new(sk.as_slice().to_vec()),
+            pk: pk.as_slice().to_vec(),
+        })
+    }
+
+    fn next_index(&self) -> u64 {
+        // hbs-lms wipes the key (parameters included) once the last leaf is
+        // used, which would otherwise read back as leaf 0.
+        if self.sk[LMS_INDEX_LEN] == LMS_PARAM_SET_END {
+            return self.max_signatures();
+        }
+
+        let mut b = [0u8; LMS_INDEX_LEN];
+        b.copy_from_slice(&self.sk[..LMS_INDEX_LEN]);
+        u64::from_be_bytes(b)
+    }
+
+    fn max_signatures(&self) -> u64 {
+        let total_height: u32 = self.levels.iter().map(|&(h, _)| h).sum();
+        1u64.checked_shl(total_height).unwrap_or(u64::MAX)
+    }
+
+    fn params_name(&self) -> String {
+        self.levels
+            .iter()
+            .map(|&(h, w)| format!("LMS_SHA256_M32_H{}/LMOTS_SHA256_N32_W{}", h, w))
+            .collect::<Vec<_>>()
+            .join(" + ")
+    }
+}
+
+#[pymethods]
+impl LmsState {
+    /// Per-level parameter sets, e.g. "LMS_SHA256_M32_H10/LMOTS_SHA256_N32_W4".
+    #[getter]
+    fn params(&self) -> String {
+        self.params_name()
+    }
+
+    /// Index of the next leaf sign() will use.
+    #[getter]
+    fn index(&self) -> u64 {
+        self.next_index()
+    }
+
+    /// Total number of signatures this key can ever produce.
+    #[getter(max_signatures)]
+    fn max_signatures_py(&self) -> u64 {
+        self.max_signatures()
+    }
+
+    /// Signatures left before the key is exhausted.
+    #[getter]
+    fn remaining(&self) -> u64 {
+        self.max_signatures().saturating_sub(self.next_index())
+    }
+
+    fn public_key(&self, py: Python) -> Py<PyBytes> {
+        PyBytes::new_bound(py, &self.pk).unbind()
+    }
+
+    /// Serialized secret key including the current leaf index.
+    fn to_bytes(&self, py: Python) -> Py<PyBytes> {
+        PyBytes::new_bound(py, &self.sk).unbind()
+    }
+
+    #[staticmethod]
+    fn from_bytes(sk_bytes: Buffer) -> PyResult<Self> {
+        let levels = lms_levels_from_sk(&sk_bytes)?;
+
+        // The key is just (index, parameters, seed): rerunning keygen on the
+        // seed recovers the public key, then the index is restored on top.
+        let seed_start = LMS_INDEX_LEN + LMS_MAX_LEVELS;
+        let seed_len = Seed::<LmsHash>::default().len();
+        check_length("LMS secret key", &sk_bytes, seed_start + seed_len)?;
+        let mut state = LmsState::from_keygen(levels, &sk_bytes[seed_start..])?;
+        state.sk.copy_from_slice(&sk_bytes);
+        ensure_leaf_unused(&state.pk, state.next_index())?;
+
+        Ok(state)
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        ct_eq(&self.sk, &other.sk)
+    }
+
+    /// Never picklable, not even with an opt-in: an unpickled copy signing in
+    /// another process would reuse the same leaves.
+    fn __reduce__(&self) -> PyResult<()> {
+        Err(state_pickle_error("LmsState"))
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "LmsState(params='{}', fingerprint='{}', index={}, remaining={})",
+            self.params_name(),
+            fingerprint(&self.pk),
+            self.next_index(),
+            self.remaining()
+        )
+    }
+}
+
+// ─── LMS: keygen(heights, winternitz) -> (public_key, state) ──────────────────
+
+#[pyfunction]
+#[pyo3(signature = (heights = vec![10], winternitz = 4))]
+pub fn lms_keygen(
+    py: Python,
+    heights: Vec<u32>,
+    winternitz: u32,
+) -> PyResult<(Py<PyBytes>, LmsState)> {
+    let levels = heights.into_iter().map(|h| (h, winternitz)).collect();
+    let seed = random_seed(Seed::<LmsHash>::default().len())?;
+    let state = LmsState::from_keygen(levels, &seed)?;
+
+    Ok((PyBytes::new_bound(py, &state.pk).unbind(), state))
+}
+
+// ─── LMS: sign(state, msg) -> HSS signature, advancing the state ──────────────
+
+#[pyfunction]
+pub fn lms_sign(py: Python, state: &Bound<'_, LmsState>, msg: Buffer) -> PyResult<Py<PyBytes>> {
+    let mut state = state.borrow_mut();
+
+    let index = state.next_index();
+    if index >= state.max_signatures() {
+        return Err(SignatureError::new_err(format!(
+            "{} key exhausted: all {} signatures used",
+            state.params_name(),
+            state.max_signatures()
+        )));
+    }
+    ensure_leaf_unused(&state.pk, index)?;
+
+    let mut updated = None;
+    let sig = hbs_lms::sign::<LmsHash>(
+        &msg,
+        &state.sk,
+        &mut |new_key: &[u8]| {
+            updated = Some(Zeroizing::new(new_key.to_vec()));
+            Ok(())
+        },
+        None,
+    )
+    .map_err(|_| lms_err("signing"))?;
+
+    let updated = updated.ok_or_else(|| lms_err("state update"))?;
+    state.sk.copy_from_slice(&updated);
+    record_leaf_used(&state.pk, index);
+
+    Ok(PyBytes::new_bound(py, sig.as_ref()).unbind())
+}
+
+// ─── LMS: verify(pk, msg, sig) -> bool, verify_strict raises ──────────────────
+
+#[pyfunction]
+pub fn lms_verify(pk_bytes: Buffer, msg: Buffer, sig_bytes: Buffer) -> bool {
+    hbs_lms::verify::<LmsHash>(&msg, &sig_bytes, &pk_bytes).is_ok()
+}
+
+#[pyfunction]
+pub fn lms_verify_strict(pk_bytes: Buffer, msg: Buffer, sig_bytes: Buffer) -> PyResult<()> {
+    if hbs_lms::verify::<LmsHash>(&msg, &sig_bytes, &pk_bytes).is_ok() {
+        Ok(())
+    } else {
+        Err(verification_failed("LMS", msg.len(), sig_bytes.len()))
+    }
+}
@@ -0,0 +1,396 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// Chunked streaming AEAD (STREAM)
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// For encrypting data too large to hold in memory, a chunk at a time:
+//
+//   enc = StreamEncryptor(key)
+//   out.write(enc.header)
+//   for chunk in chunks[:-1]:
+//       out.write(enc.encrypt_chunk(chunk))
+//   out.write(enc.encrypt_last(chunks[-1]))
+//
+//   dec = StreamDecryptor(key, header)
+//   ... dec.decrypt_chunk(ct) for every chunk but the last,
+//   ... dec.decrypt_last(ct) for the last one
+//
+// This is the STREAM construction of Hoang, Reyhanitabar, Rogaway and Vizár
+// ("Online Authenticated-Encryption and its Nonce-Reuse Misuse-Resistance",
+// CRYPTO 2015). Chunk i is sealed with the AEAD under the nonce
+//
+//   prefix | i as u32 big-endian | 1 if last chunk else 0
+//
+// where the prefix is random per stream and sent as the header (7 bytes, or
+// 19 for XChaCha20-Poly1305). Each chunk gains a 16-byte tag and is
+// authenticated on its own, so plaintext can be released as it arrives.
+// Because the position and last flag are in the nonce, reordered, dropped or
+// duplicated chunks fail to decrypt, and so does a stream cut short: the
+// chunk the reader is forced to treat as last wasn't sealed as last. Framing
+// (chunk lengths) is the caller's; fixed-size chunks need none.
+//
+// `key` is 32 bytes, e.g. from hkdf_sha256() over a KEM shared secret;
+// `aad` is bound to every chunk. A stream holds at most 2^32 chunks. With a
+// 7-byte random prefix, use a fresh key well before 2^24 streams, or pass
+// cipher="xchacha20poly1305", whose 19-byte prefix never repeats in
+// practice. After decryption fails the decryptor refuses further chunks.
+
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use zeroize::Zeroizing;
+
+use crate::aead::{Aead, KEY_LEN, TAG_LEN};
+use crate::buffer::Buffer;
+use crate::errors::{check_length, DecryptionError};
+
+// Counter and last-chunk flag, after the prefix.
+const NONCE_SUFFIX_LEN: usize = 5;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum State {
+    Open,
+    Finished,
+    Failed,
+}
+
+/// What StreamEncryptor and StreamDecryptor have in common: the key, the
+/// nonce prefix and the position in the stream.
+pub(crate) struct Stream {
+    aead: Aead,
+    key: Zeroizing<[u8; KEY_LEN]>,
+    prefix: Vec<u8>,
+    aad: Vec<u8>,
+    counter: u32,
+    state: State,
+}
+
+impl Stream {
+    pub(crate) fn new(aead: Aead, key: &[u8], prefix: Vec<u8>, aad: &[u8]) -> PyResult<Self> {
+        check_length("stream key", key, KEY_LEN)?;
+        let mut stream_key = Zeroizing::new([0u8; KEY_LEN]);
+        stream_key.copy_from_slice(key);
+        Ok(Stream {
+            aead,
+            key: stream_key,
+            prefix,
+            aad: aad.to_vec(),
+            counter: 0,
+            state: State::Open,
+        })
+    }
+
+    pub(crate) fn prefix_len(aead: Aead) -> usize {
+        aead.nonce_len() - NONCE_SUFFIX_LEN
+    }
+
+    /// The nonce for the next chunk, or an error if there can't be one.
+    fn next_nonce(&self, last: bool) -> PyResult<Vec<u8>> {
+        match self.state {
+            State::Open => {}
+            State::Finished => {
+                return Err(PyValueError::new_err(
+                    "the stream's last chunk has been processed",
+                ))
+            }
+            State::Failed => {
+                return Err(DecryptionError::new_err(
+                    "an earlier chunk of this stream failed to decrypt",
+                ))
+            }
+        }
+        if !last && self.counter == u32::MAX {
+            return Err(PyValueError::new_err(
+                "stream is at its 2^32-chunk limit; only the last chunk can follow",
+            ));
+        }
+        let mut nonce = Vec::with_capacity(self.aead.nonce_len());
+        nonce.extend_from_slice(&self.prefix);
+        nonce.extend_from_slice(&self.counter.to_be_bytes());
+        nonce.push(last as u8);
+        Ok(nonce)
+    }
+
+    fn advance(&mut self, last: bool) {
+        if last {
+            self.state = State::Finished;
+        } else {
+            self.counter += 1;
+        }
+    }
+
+    /// Encrypt the next chunk in place, appending its tag.
+    pub(crate) fn encrypt_in_place(&mut self, buf: &mut Vec<u8>, last: bool) -> PyResult<()> {
+        let nonce = self.next_nonce(last)?;
+        self.aead.encrypt(&self.key[..], &nonce, &self.aad, buf)?;
+        self.advance(last);
+        Ok(())
+    }
+
+    /// Verify and decrypt the next chunk in place, dropping its tag.
+    pub(crate) fn decrypt_in_place(
+        &mut self,
+        py: Python,
+        buf: &mut Vec<u8>,
+        last: bool,
+    ) -> PyResult<()> {
+        let nonce = self.next_nonce(last)?;
+        if let Err(e) = self.aead.decrypt(&self.key[..], &nonce, &self.aad, buf) {
+            if !e.is_instance_of::<DecryptionError>(py) {
+                return Err(e);
+            }
+            self.state = State::Failed;
+            return Err(DecryptionError::new_err(format!(
+                "stream chunk {} failed to authenticate: wrong key, header or aad, or the \
+                 stream was modified, reordered or truncated",
+                self.counter
+            )));
+        }
+        self.advance(last);
+        Ok(())
+    }
+
+    fn encrypt(&mut self, py: Python, data: &[u8], last: bool) -> PyResult<Py<PyBytes>> {
+        let mut body = Vec::with_capacity(data.len() + TAG_LEN);
+        body.extend_from_slice(data);
+        self.encrypt_in_place(&mut body, last)?;
+        Ok(PyBytes::new_bound(py, &body).unbind())
+    }
+
+    fn decrypt(&mut self, py: Python, data: &[u8], last: bool) -> PyResult<Py<PyBytes>> {
+        let mut body = data.to_vec();
+        self.decrypt_in_place(py, &mut body, last)?;
+        Ok(PyBytes::new_bound(py, &body).unbind())
+    }
+}
+
+// ─── StreamEncryptor ──────────────────────────────────────────────────────────
+
+#[pyclass(module = "pqcrypto_bindings")]
+pub struct StreamEncryptor {
+    stream: Stream,
+}
+
+#[pymethods]
+impl StreamEncryptor {
+    /// A new stream under `key`, with a fresh random header.
+    // stub: cipher: Literal["chacha20poly1305", "aes256gcm", "xchacha20poly1305"]
+    #[new]
+    #[pyo3(signature = (key, aad = None, cipher = "chacha20poly1305"))]
+    fn new(key: Buffer, aad: Option<Buffer>, cipher: &str) -> PyResult<Self> {
+        let aead = Aead::from_name(cipher)?;
+        let mut prefix = vec![0u8; Stream::prefix_len(aead)];
+        getrandom::fill(&mut prefix).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let aad = aad.as_deref().unwrap_or_default();
+        Ok(StreamEncryptor {
+            stream: Stream::new(aead, &key, prefix, aad)?,
+        })
+    }
+
+    /// The per-stream nonce prefix; StreamDecryptor needs it.
+    #[getter]
+    fn header<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new_bound(py, &self.stream.prefix)
+    }
+
+    #[getter]
+    fn cipher(&self) -> &'static str {
+        self.stream.aead.name()
+    }
+
+    #[getter]
+    fn finished(&self) -> bool {
+        self.stream.state == State::Finished
+    }
+
+    /// Encrypt a chunk that isn't the last. Returns ciphertext || tag.
+    fn encrypt_chunk(&mut self, py: Python, data: Buffer) -> PyResult<Py<PyBytes>> {
+        self.stream.encrypt(py, &data, false)
+    }
+
+    /// Encrypt the last chunk (which may be empty) and end the stream.
+    #[pyo3(signature = (data = None))]
+    fn encrypt_last(&mut self, py: Python, data: Option<Buffer>) -> PyResult<Py<PyBytes>> {
+        self.stream
+            .encrypt(py, data.as_deref().unwrap_or_default(), true)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "StreamEncryptor(cipher='{}', chunks={})",
+            self.cipher(),
+            self.stream.counter
+        )
+    }
+}
+
+// ─── StreamDecryptor ──────────────────────────────────────────────────────────
+
+#[pyclass(module = "pqcrypto_bindings")]
+pub struct StreamDecryptor {
+    stream: Stream,
+}
+
+#[pymethods]
+impl StreamDecryptor {
+    /// Decrypt the stream with `header` from its StreamEncryptor. `aad` and
+    /// `cipher` must match the encryptor's.
+    // stub: cipher: Literal["chacha20poly1305", "aes256gcm", "xchacha20poly1305"]
+    #[new]
+    #[pyo3(signature = (key, header, aad = None, cipher = "chacha20poly1305"))]
+    fn new(key: Buffer, header: Buffer, aad: Option<Buffer>, cipher: &str) -> PyResult<Self> {
+        let aead = Aead::from_name(cipher)?;
+        check_length("stream header", &header, Stream::prefix_len(aead))?;
+        let aad = aad.as_deref().unwrap_or_default();
+        Ok(StreamDecryptor {
+            stream: Stream::new(aead, &key, header.to_vec(), aad)?,
+        })
+    }
+
+    #[getter]
+    fn cipher(&self) -> &'static str {
+        self.stream.aead.name()
+    }
+
+    /// True once the last chunk has decrypted. A stream that ends while this
+    /// is False has been truncated.
+    #[getter]
+    fn finished(&self) -> bool {
+        self.stream.state == State::Finished
+    }
+
+    /// Decrypt and verify a chunk that isn't the last.
+    fn decrypt_chunk(&mut self, py: Python, data: Buffer) -> PyResult<Py<PyBytes>> {
+        self.stream.decrypt(py, &data, false)
+    }
+
+    /// Decrypt and verify the last chunk. Raises DecryptionError if it wasn't
+    /// sealed as the last, i.e. the stream was cut short.
+    fn decrypt_last(&mut self, py: Python, data: Buffer) -> PyResult<Py<PyBytes>> {
+        self.stream.decrypt(py, &data, true)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "StreamDecryptor(cipher='{}', chunks={})",
+            self.cipher(),
+            self.stream.counter
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: [u8; KEY_LEN] = [7; KEY_LEN];
+
+    /// Three full chunks and an empty last one.
+    fn encrypt_stream(py: Python, cipher: &str) -> (Vec<u8>, Vec<Vec<u8>>) {
+        let mut enc = StreamEncryptor::new(Buffer::from_bytes(py, &KEY), None, cipher).unwrap();
+        let mut chunks = Vec::new();
+        for i in 0..3u8 {
+            let ct = enc
+                .encrypt_chunk(py, Buffer::from_bytes(py, &[i; 100]))
+                .unwrap();
+            chunks.push(ct.as_bytes(py).to_vec());
+        }
+        chunks.push(enc.encrypt_last(py, None).unwrap().as_bytes(py).to_vec());
+        assert!(enc.finished());
+        (enc.header(py).as_bytes().to_vec(), chunks)
+    }
+
+    fn decryptor(py: Python, header: &[u8], cipher: &str) -> StreamDecryptor {
+        let key = Buffer::from_bytes(py, &KEY);
+        StreamDecryptor::new(key, Buffer::from_bytes(py, header), None, cipher).unwrap()
+    }
+
+    fn chunk(py: Python, dec: &mut StreamDecryptor, ct: &[u8], last: bool) -> PyResult<Vec<u8>> {
+        let ct = Buffer::from_bytes(py, ct);
+        let pt = if last {
+            dec.decrypt_last(py, ct)?
+        } else {
+            dec.decrypt_chunk(py, ct)?
+        };
+        Ok(pt.as_bytes(py).to_vec())
+    }
+
+    #[test]
+    fn round_trip() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            for cipher in ["chacha20poly1305", "aes256gcm", "xchacha20poly1305"] {
+                let (header, chunks) = encrypt_stream(py, cipher);
+                assert_eq!(
+                    header.len(),
+                    Stream::prefix_len(Aead::from_name(cipher).unwrap())
+                );
+                let mut dec = decryptor(py, &header, cipher);
+                for (i, ct) in chunks[..3].iter().enumerate() {
+                    assert_eq!(chunk(py, &mut dec, ct, false).unwrap(), [i as u8; 100]);
+                }
+                assert_eq!(chunk(py, &mut dec, &chunks[3], true).unwrap(), b"");
+                assert!(dec.finished());
+            }
+        });
+    }
+
+    #[test]
+    fn truncated_stream_fails() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let (header, chunks) = encrypt_stream(py, "chacha20poly1305");
+            let mut dec = decryptor(py, &header, "chacha20poly1305");
+            chunk(py, &mut dec, &chunks[0], false).unwrap();
+            // Stream cut after chunk 1: the reader takes it as the last.
+            let err = chunk(py, &mut dec, &chunks[1], true).unwrap_err();
+            assert!(err.is_instance_of::<DecryptionError>(py));
+            assert!(!dec.finished());
+        });
+    }
+
+    #[test]
+    fn reordered_chunks_fail() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let (header, chunks) = encrypt_stream(py, "chacha20poly1305");
+            let mut dec = decryptor(py, &header, "chacha20poly1305");
+            let err = chunk(py, &mut dec, &chunks[1], false).unwrap_err();
+            assert!(err.is_instance_of::<DecryptionError>(py));
+        });
+    }
+
+    #[test]
+    fn no_chunks_after_the_last() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let mut enc =
+                StreamEncryptor::new(Buffer::from_bytes(py, &KEY), None, "chacha20poly1305")
+                    .unwrap();
+            enc.encrypt_last(py, Some(Buffer::from_bytes(py, b"end")))
+                .unwrap();
+            let err = enc
+                .encrypt_chunk(py, Buffer::from_bytes(py, b"more"))
+                .unwrap_err();
+            assert!(err.is_instance_of::<PyValueError>(py));
+            assert!(enc.encrypt_last(py, None).is_err());
+        });
+    }
+
+    #[test]
+    fn failed_stream_stays_failed() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let (header, chunks) = encrypt_stream(py, "chacha20poly1305");
+            let mut dec = decryptor(py, &header, "chacha20poly1305");
+            let mut tampered = chunks[0].clone();
+            tampered[0] ^= 1;
+            assert!(chunk(py, &mut dec, &tampered, false).is_err());
+            // The genuine chunk doesn't revive it, nor does anything after.
+            for (i, ct) in chunks.iter().enumerate() {
+                let err = chunk(py, &mut dec, ct, i == 3).unwrap_err();
+                assert!(err.is_instance_of::<DecryptionError>(py));
+            }
+            assert!(!dec.finished());
+        });
+    }
+}
@@ -0,0 +1,200 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// Timestamped signatures: signing time and expiry under the signature
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// For audit logs and signed documents whose signatures should only be
+// accepted while fresh:
+//
+//   env = sign_timestamped(sk, doc, not_after=now + 86400)
+//   signed_at, not_after = verify_timestamped(pk, doc, env, max_age=3600)
+//
+// The envelope is detached (it doesn't carry the message), integers
+// big-endian, times Unix seconds:
+//
+//   magic "PQTS" | version 1 | name length u8 | algorithm name
+//     | signed_at u64 | has not_after u8 [| not_after u64]
+//     | signature length u32 | signature
+//
+// The signature covers label || everything before the signature length ||
+// message, so neither time can be changed without breaking it. signed_at is
+// the signer's own clock, not a third-party timestamp: it says when the key
+// holder claims to have signed, which is what freshness checks need.
+//
+// verify_timestamped() checks the signature first, then raises
+// SignatureExpiredError (a SignatureVerificationError) if the envelope is
+// past not_after, older than max_age, or dated more than clock_skew seconds
+// in the future. `now` defaults to the current time. Algorithms are chosen as
+// in sign_attached().
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+use crate::attached::algorithm_named;
+use crate::buffer::Buffer;
+use crate::errors::{check_length, verification_failed, InvalidLengthError, SignatureExpiredError};
+use crate::metadata::now as current_time;
+use crate::signcrypt::falcon_for;
+use crate::util::Reader;
+
+const MAGIC: &[u8; 4] = b"PQTS";
+const VERSION: u8 = 1;
+const LABEL: &[u8] = b"entropic-chaos timestamped signature v1\0";
+
+/// What gets signed: the label, the envelope up to the signature, the message.
+fn signed_data(header: &[u8], msg: &[u8]) -> Vec<u8> {
+    [LABEL, header, msg].concat()
+}
+
+/// Sign `msg` along with the signing time and, if given, an expiry.
+#[pyfunction]
+#[pyo3(signature = (sk, msg, not_after = None, signed_at = None, algorithm = None))]
+pub fn sign_timestamped(
+    py: Python,
+    sk: Buffer,
+    msg: Buffer,
+    not_after: Option<u64>,
+    signed_at: Option<u64>,
+    algorithm: Option<&str>,
+) -> PyResult<Py<PyBytes>> {
+    let alg = match algorithm {
+        Some(name) => algorithm_named(name)?,
+        None => falcon_for("secret key", sk.len(), |alg| alg.secret_key_bytes)?,
+    };
+    let signed_at = signed_at.unwrap_or_else(current_time);
+    if not_after.is_some_and(|t| t < signed_at) {
+        return Err(PyValueError::new_err(format!(
+            "not_after {} is before signed_at {}",
+            not_after.unwrap(),
+            signed_at
+        )));
+    }
+
+    let mut header = Vec::with_capacity(MAGIC.len() + 2 + alg.name.len() + 17);
+    header.extend_from_slice(MAGIC);
+    header.push(VERSION);
+    header.push(alg.name.len() as u8);
+    header.extend_from_slice(alg.name.as_bytes());
+    header.extend_from_slice(&signed_at.to_be_bytes());
+    match not_after {
+        Some(t) => {
+            header.push(1);
+            header.extend_from_slice(&t.to_be_bytes());
+        }
+        None => header.push(0),
+    }
+
+    let signed = PyBytes::new_bound(py, &signed_data(&header, &msg));
+    let sig = (alg.sign)(py, sk, signed.extract()?)?;
+    let sig = sig.as_bytes(py);
+    if u32::try_from(sig.len()).is_err() {
+        return Err(InvalidLengthError::new_err(
+            "signature longer than 2^32 - 1 bytes",
+        ));
+    }
+
+    let mut envelope = header;
+    envelope.extend_from_slice(&(sig.len() as u32).to_be_bytes());
+    envelope.extend_from_slice(sig);
+    Ok(PyBytes::new_bound(py, &envelope).unbind())
+}
+
+/// Verify a sign_timestamped() envelope over `msg` and check its times.
+/// Returns (signed_at, not_after).
+#[pyfunction]
+#[pyo3(signature = (
+    pk,
+    msg,
+    envelope,
+    max_age = None,
+    now = None,
+    clock_skew = 0,
+    algorithm = None,
+))]
+#[allow(clippy::too_many_arguments)]
+pub fn verify_timestamped(
+    py: Python,
+    pk: Buffer,
+    msg: Buffer,
+    envelope: Buffer,
+    max_age: Option<u64>,
+    now: Option<u64>,
+    clock_skew: u64,
+    algorithm: Option<&str>,
+) -> PyResult<(u64, Option<u64>)> {
+    let mut r = Reader::new("timestamped signature", &envelope);
+    if r.take(MAGIC.len())? != MAGIC {
+        return Err(PyValueError::new_err("not a timestamped signature"));
+    }
+    let version = r.u8()?;
+    if version != VERSION {
+        return Err(PyValueError::new_err(format!(
+            "unsupported timestamped signature version {}",
+            version
+        )));
+    }
+    let name_len = r.u8()? as usize;
+    let name = std::str::from_utf8(r.take(name_len)?)
+        .map_err(|_| PyValueError::new_err("timestamped signature algorithm name isn't UTF-8"))?;
+    let alg = algorithm_named(name)?;
+    if let Some(expected) = algorithm {
+        let expected = algorithm_named(expected)?;
+        if !std::ptr::eq(alg, expected) {
+            return Err(PyValueError::new_err(format!(
+                "envelope is signed with {}, expected {}",
+                alg.name, expected.name
+            )));
+        }
+    }
+    check_length(
+        &format!("{} public key", alg.name),
+        &pk,
+        alg.public_key_bytes,
+    )?;
+    let signed_at = r.u64()?;
+    let not_after = match r.u8()? {
+        0 => None,
+        1 => Some(r.u64()?),
+        flag => {
+            return Err(PyValueError::new_err(format!(
+                "invalid not_after flag {} in timestamped signature",
+                flag
+            )))
+        }
+    };
+    let header_len = MAGIC.len() + 2 + name_len + 9 + if not_after.is_some() { 8 } else { 0 };
+    let sig_len = r.u32()? as usize;
+    let sig = r.take(sig_len)?;
+    r.finish()?;
+
+    let signed = PyBytes::new_bound(py, &signed_data(&envelope[..header_len], &msg));
+    let sig_bytes = PyBytes::new_bound(py, sig);
+    if !(alg.verify)(pk, signed.extract()?, sig_bytes.extract()?)? {
+        return Err(verification_failed(alg.name, msg.len(), sig.len()));
+    }
+
+    let now = now.unwrap_or_else(current_time);
+    if signed_at > now.saturating_add(clock_skew) {
+        return Err(SignatureExpiredError::new_err(format!(
+            "signature is dated {}, in the future (now {})",
+            signed_at, now
+        )));
+    }
+    if let Some(t) = not_after {
+        if now > t {
+            return Err(SignatureExpiredError::new_err(format!(
+                "signature expired at {} (now {})",
+                t, now
+            )));
+        }
+    }
+    if let Some(max_age) = max_age {
+        if now.saturating_sub(signed_at) > max_age {
+            return Err(SignatureExpiredError::new_err(format!(
+                "signature made at {} is older than {} seconds (now {})",
+                signed_at, max_age, now
+            )));
+        }
+    }
+    Ok((signed_at, not_after))
+}
@@ -0,0 +1,110 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// TLS 1.3 hybrid key shares (draft-ietf-tls-ecdhe-mlkem)
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// Wire codec for the X25519MLKEM768 named group (0x11EC). No handshake logic,
+// just the byte layouts, so a Python TLS experiment can put shares on the wire
+// that real stacks accept:
+//
+//   client key_exchange = mlkem768_pk (1184) || x25519_pk (32)
+//   server key_exchange = mlkem768_ct (1088) || x25519_pk (32)
+//   shared secret       = mlkem768_ss (32)   || x25519_ss (32)
+//
+// Note the ML-KEM half comes first for this group, unlike the ECDH-first
+// order of SecP256r1MLKEM768.
+
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+use crate::buffer::Buffer;
+use crate::errors::check_length;
+use crate::secret::{secret_bytes_with, SecretBytes};
+
+pub const X25519MLKEM768_GROUP: u16 = 0x11ec;
+
+const MLKEM768_PK_LEN: usize = 1184;
+const MLKEM768_CT_LEN: usize = 1088;
+const MLKEM768_SS_LEN: usize = 32;
+const X25519_LEN: usize = 32;
+
+fn concat(py: Python, first: &[u8], second: &[u8]) -> PyResult<Py<PyBytes>> {
+    let out = PyBytes::new_bound_with(py, first.len() + second.len(), |buf| {
+        buf[..first.len()].copy_from_slice(first);
+        buf[first.len()..].copy_from_slice(second);
+        Ok(())
+    })?;
+    Ok(out.unbind())
+}
+
+fn split(
+    py: Python,
+    what: &str,
+    share: Buffer,
+    first_len: usize,
+) -> PyResult<(Py<PyBytes>, Py<PyBytes>)> {
+    check_length(what, &share, first_len + X25519_LEN)?;
+    let (first, second) = share.split_at(first_len);
+    Ok((
+        PyBytes::new_bound(py, first).unbind(),
+        PyBytes::new_bound(py, second).unbind(),
+    ))
+}
+
+// ─── ClientHello share ────────────────────────────────────────────────────────
+
+#[pyfunction]
+pub fn x25519mlkem768_encode_client_share(
+    py: Python,
+    mlkem_pk: Buffer,
+    x25519_pk: Buffer,
+) -> PyResult<Py<PyBytes>> {
+    check_length("ML-KEM-768 public key", &mlkem_pk, MLKEM768_PK_LEN)?;
+    check_length("X25519 public key", &x25519_pk, X25519_LEN)?;
+    concat(py, &mlkem_pk, &x25519_pk)
+}
+
+#[pyfunction]
+pub fn x25519mlkem768_decode_client_share(
+    py: Python,
+    share: Buffer,
+) -> PyResult<(Py<PyBytes>, Py<PyBytes>)> {
+    split(py, "X25519MLKEM768 client share", share, MLKEM768_PK_LEN)
+}
+
+// ─── ServerHello share ────────────────────────────────────────────────────────
+
+#[pyfunction]
+pub fn x25519mlkem768_encode_server_share(
+    py: Python,
+    mlkem_ct: Buffer,
+    x25519_pk: Buffer,
+) -> PyResult<Py<PyBytes>> {
+    check_length("ML-KEM-768 ciphertext", &mlkem_ct, MLKEM768_CT_LEN)?;
+    check_length("X25519 public key", &x25519_pk, X25519_LEN)?;
+    concat(py, &mlkem_ct, &x25519_pk)
+}
+
+#[pyfunction]
+pub fn x25519mlkem768_decode_server_share(
+    py: Python,
+    share: Buffer,
+) -> PyResult<(Py<PyBytes>, Py<PyBytes>)> {
+    split(py, "X25519MLKEM768 server share", share, MLKEM768_CT_LEN)
+}
+
+// ─── Shared secret ────────────────────────────────────────────────────────────
+
+#[pyfunction]
+pub fn x25519mlkem768_shared_secret(
+    py: Python,
+    mlkem_ss: Buffer,
+    x25519_ss: Buffer,
+) -> PyResult<Py<SecretBytes>> {
+    check_length("ML-KEM-768 shared secret", &mlkem_ss, MLKEM768_SS_LEN)?;
+    check_length("X25519 shared secret", &x25519_ss, X25519_LEN)?;
+    secret_bytes_with(py, MLKEM768_SS_LEN + X25519_LEN, |buf| {
+        buf[..MLKEM768_SS_LEN].copy_from_slice(&mlkem_ss);
+        buf[MLKEM768_SS_LEN..].copy_from_slice(&x25519_ss);
+        Ok(())
+    })
+}
@@ -0,0 +1,156 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// Signed tokens in the style of PASETO
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// For session and API tokens where JWT's "alg" header has been a liability:
+//
+//   token = token_sign(sk, b'{"sub": "alice"}', footer=b'{"kid": "k1"}')
+//   footer = token_footer(token)           # unverified, to pick the key
+//   claims = token_verify(pk, token, footer=b'{"kid": "k1"}')
+//
+// A token is
+//
+//   "pq1.public." base64url(claims || signature) [ "." base64url(footer) ]
+//
+// with base64url unpadded, as PASETO (https://paseto.io) lays out its
+// public tokens. The version fixes the algorithm: pq1 is ML-DSA-65 and
+// nothing else, so there is no header for an attacker to rewrite and keys
+// of any other scheme are refused by length. What gets signed is PASETO's
+// pre-authentication encoding of the header, claims, footer and an implicit
+// assertion:
+//
+//   PAE(pieces) = LE64(count) || for each piece: LE64(length) || piece
+//
+// which can't be split two ways, so no token verifies with its bytes moved
+// between claims and footer. The footer is authenticated but not encrypted;
+// the implicit assertion is never sent, and both sides must pass the same
+// one (a tenant ID, say). Claims and footer are opaque bytes; checking
+// expiry and audience is up to the caller. A token that doesn't verify, or
+// whose footer isn't the expected one, raises SignatureVerificationError.
+
+use base64ct::{Base64UrlUnpadded, Encoding};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+use crate::buffer::Buffer;
+use crate::errors::{check_length, verification_failed, SignatureVerificationError};
+use crate::sign::SignatureAlgorithm;
+use crate::util::ct_eq;
+
+const HEADER: &str = "pq1.public.";
+const ALGORITHM: &SignatureAlgorithm = &crate::mldsa65::ALGORITHM;
+/// ML-DSA-65 signatures are always this long.
+const SIGNATURE_LEN: usize = 3309;
+
+fn pae(pieces: &[&[u8]]) -> Vec<u8> {
+    let mut out = Vec::new();
+    out.extend_from_slice(&(pieces.len() as u64).to_le_bytes());
+    for piece in pieces {
+        out.extend_from_slice(&(piece.len() as u64).to_le_bytes());
+        out.extend_from_slice(piece);
+    }
+    out
+}
+
+fn malformed(what: &str) -> PyErr {
+    PyValueError::new_err(format!("malformed pq1.public token: {}", what))
+}
+
+/// Split a token into its payload and footer, decoded.
+fn parse(token: &str) -> PyResult<(Vec<u8>, Vec<u8>)> {
+    let body = token
+        .strip_prefix(HEADER)
+        .ok_or_else(|| PyValueError::new_err("not a pq1.public token"))?;
+    let (payload, footer) = match body.split_once('.') {
+        Some((_, "")) => return Err(malformed("empty footer is present")),
+        Some((payload, footer)) => (payload, Some(footer)),
+        None => (body, None),
+    };
+    let payload =
+        Base64UrlUnpadded::decode_vec(payload).map_err(|_| malformed("payload isn't base64url"))?;
+    let footer = match footer {
+        Some(footer) => Base64UrlUnpadded::decode_vec(footer)
+            .map_err(|_| malformed("footer isn't base64url"))?,
+        None => Vec::new(),
+    };
+    if payload.len() < SIGNATURE_LEN {
+        return Err(malformed("payload is shorter than a signature"));
+    }
+    Ok((payload, footer))
+}
+
+/// Sign `claims` with an ML-DSA-65 secret key into a pq1.public token.
+#[pyfunction]
+#[pyo3(signature = (sk, claims, footer = None, implicit = None))]
+pub fn token_sign(
+    py: Python,
+    sk: Buffer,
+    claims: Buffer,
+    footer: Option<Buffer>,
+    implicit: Option<Buffer>,
+) -> PyResult<String> {
+    check_length(
+        "pq1 token secret key (ML-DSA-65)",
+        &sk,
+        ALGORITHM.secret_key_bytes,
+    )?;
+    let footer = footer.as_deref().unwrap_or_default();
+    let implicit = implicit.as_deref().unwrap_or_default();
+    let signed = pae(&[HEADER.as_bytes(), &claims, footer, implicit]);
+    let signed = PyBytes::new_bound(py, &signed);
+    let sig = (ALGORITHM.sign)(py, sk, signed.extract()?)?;
+
+    let mut token = String::from(HEADER);
+    token.push_str(&Base64UrlUnpadded::encode_string(
+        &[&claims[..], sig.as_bytes(py)].concat(),
+    ));
+    if !footer.is_empty() {
+        token.push('.');
+        token.push_str(&Base64UrlUnpadded::encode_string(footer));
+    }
+    Ok(token)
+}
+
+/// Verify a pq1.public token against an ML-DSA-65 public key and return its
+/// claims. If `footer` is given, the token's must equal it.
+#[pyfunction]
+#[pyo3(signature = (pk, token, footer = None, implicit = None))]
+pub fn token_verify(
+    py: Python,
+    pk: Buffer,
+    token: &str,
+    footer: Option<Buffer>,
+    implicit: Option<Buffer>,
+) -> PyResult<Py<PyBytes>> {
+    check_length(
+        "pq1 token public key (ML-DSA-65)",
+        &pk,
+        ALGORITHM.public_key_bytes,
+    )?;
+    let (payload, token_footer) = parse(token)?;
+    if let Some(expected) = footer {
+        if !ct_eq(&token_footer, &expected) {
+            return Err(SignatureVerificationError::new_err(
+                "pq1.public token footer doesn't match the expected one",
+            ));
+        }
+    }
+    let (claims, sig) = payload.split_at(payload.len() - SIGNATURE_LEN);
+    let implicit = implicit.as_deref().unwrap_or_default();
+    let signed = pae(&[HEADER.as_bytes(), claims, &token_footer, implicit]);
+    let signed = PyBytes::new_bound(py, &signed);
+    let sig_bytes = PyBytes::new_bound(py, sig);
+    if !(ALGORITHM.verify)(pk, signed.extract()?, sig_bytes.extract()?)? {
+        return Err(verification_failed(ALGORITHM.name, claims.len(), sig.len()));
+    }
+    Ok(PyBytes::new_bound(py, claims).unbind())
+}
+
+/// The footer of a pq1.public token, without verifying anything: for
+/// choosing the key to verify it with.
+#[pyfunction]
+pub fn token_footer(py: Python, token: &str) -> PyResult<Py<PyBytes>> {
+    let (_, footer) = parse(token)?;
+    Ok(PyBytes::new_bound(py, &footer).unbind())
+}
@@ -0,0 +1,367 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// TPM2 sealing of secret keys
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// For servers, so a secret key at rest only opens on the same machine in the
+// same boot state:
+//
+//   blob = seal_to_tpm(sk, pcr_policy=[0, 2, 4, 7])
+//   sk = unseal_from_tpm(blob)
+//
+// A TPM sealed object holds at most 128 bytes, so the TPM seals a random
+// 256-bit key under a policy on the current values of the listed SHA-256 bank
+// PCRs, and that key encrypts the secret key with AES-256-GCM. The sealed
+// object's parent is the owner hierarchy's ECC P-256 storage key, recreated
+// from the hierarchy seed each time, so nothing needs to be persisted in the
+// TPM. Blob layout (integers big-endian):
+//
+//   magic "PQTP" | version 1 | PCR count u8 | PCR indices (u8 each)
+//   | sealed public length u16 | TPMT_PUBLIC | sealed private length u16
+//   | TPM2B_PRIVATE contents | nonce (12) | ciphertext || tag (16)
+//
+// with everything before the ciphertext authenticated as associated data.
+// unseal_from_tpm() fails once any of the PCRs has changed (a different
+// kernel, bootloader or Secure Boot state) or on any other TPM.
+//
+// Only built with the `tpm` cargo feature, which links the tpm2-tss
+// libraries; the TPM is found through the TPM2TOOLS_TCTI / TCTI environment
+// variables, else /dev/tpmrm0. Without the feature both functions raise
+// RuntimeError.
+
+use aes_gcm::aead::{AeadInOut, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use zeroize::Zeroizing;
+
+use crate::buffer::Buffer;
+use crate::errors::DecryptionError;
+use crate::secret::{secret_bytes, SecretBytes};
+use crate::util::Reader;
+
+const MAGIC: &[u8; 4] = b"PQTP";
+const VERSION: u8 = 1;
+const KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+/// PCRs 0-23, the ones every PC Client TPM has.
+const PCR_COUNT: u8 = 24;
+
+/// The TPM side: seal a data key to the PCRs, and unseal it again.
+#[cfg(feature = "tpm")]
+mod backend {
+    use std::str::FromStr;
+
+    use pyo3::exceptions::PyRuntimeError;
+    use pyo3::prelude::*;
+    use sha2::{Digest as _, Sha256};
+    use tss_esapi::abstraction::pcr;
+    use tss_esapi::attributes::{ObjectAttributesBuilder, SessionAttributesBuilder};
+    use tss_esapi::constants::SessionType;
+    use tss_esapi::handles::{KeyHandle, SessionHandle};
+    use tss_esapi::interface_types::algorithm::{HashingAlgorithm, PublicAlgorithm};
+    use tss_esapi::interface_types::ecc::EccCurve;
+    use tss_esapi::interface_types::resource_handles::Hierarchy;
+    use tss_esapi::interface_types::session_handles::PolicySession;
+    use tss_esapi::structures::{
+        Digest, EccPoint, KeyedHashScheme, PcrSelectionList, PcrSelectionListBuilder, PcrSlot,
+        Private, Public, PublicBuilder, PublicEccParametersBuilder, PublicKeyedHashParameters,
+        SensitiveData, SymmetricDefinition, SymmetricDefinitionObject,
+    };
+    use tss_esapi::traits::{Marshall, UnMarshall};
+    use tss_esapi::{Context, TctiNameConf};
+    use zeroize::Zeroizing;
+
+    fn tpm_err(what: &str, e: tss_esapi::Error) -> PyErr {
+        PyRuntimeError::new_err(format!("TPM {}: {}", what, e))
+    }
+
+    fn context() -> PyResult<Context> {
+        let tcti = TctiNameConf::from_environment_variable()
+            .or_else(|_| TctiNameConf::from_str("device:/dev/tpmrm0"))
+            .map_err(|e| tpm_err("TCTI", e))?;
+        Context::new(tcti).map_err(|e| tpm_err("connect", e))
+    }
+
+    fn selection(pcrs: &[u8]) -> PyResult<PcrSelectionList> {
+        let slots = pcrs
+            .iter()
+            .map(|&i| PcrSlot::try_from(1u32 << i))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| tpm_err("PCR selection", e))?;
+        PcrSelectionListBuilder::new()
+            .with_selection(HashingAlgorithm::Sha256, &slots)
+            .build()
+            .map_err(|e| tpm_err("PCR selection", e))
+    }
+
+    /// The owner hierarchy's storage key, the same every time for the same
+    /// TPM and template.
+    fn storage_key(ctx: &mut Context) -> PyResult<KeyHandle> {
+        let attributes = ObjectAttributesBuilder::new()
+            .with_fixed_tpm(true)
+            .with_fixed_parent(true)
+            .with_sensitive_data_origin(true)
+            .with_user_with_auth(true)
+            .with_decrypt(true)
+            .with_restricted(true)
+            .build()
+            .map_err(|e| tpm_err("storage key", e))?;
+        let params = PublicEccParametersBuilder::new_restricted_decryption_key(
+            SymmetricDefinitionObject::AES_128_CFB,
+            EccCurve::NistP256,
+        )
+        .build()
+        .map_err(|e| tpm_err("storage key", e))?;
+        let public = PublicBuilder::new()
+            .with_public_algorithm(PublicAlgorithm::Ecc)
+            .with_name_hashing_algorithm(HashingAlgorithm::Sha256)
+            .with_object_attributes(attributes)
+            .with_ecc_parameters(params)
+            .with_ecc_unique_identifier(EccPoint::default())
+            .build()
+            .map_err(|e| tpm_err("storage key", e))?;
+        ctx.execute_with_nullauth_session(|ctx| {
+            ctx.create_primary(Hierarchy::Owner, public, None, None, None, None)
+        })
+        .map(|r| r.key_handle)
+        .map_err(|e| tpm_err("storage key", e))
+    }
+
+    /// A policy session (or trial session, to compute the policy digest)
+    /// that has run TPM2_PolicyPCR over `pcrs`.
+    fn pcr_session(
+        ctx: &mut Context,
+        session_type: SessionType,
+        pcrs: &PcrSelectionList,
+        pcr_digest: Digest,
+    ) -> PyResult<PolicySession> {
+        let session = ctx
+            .start_auth_session(
+                None,
+                None,
+                None,
+                session_type,
+                SymmetricDefinition::AES_128_CFB,
+                HashingAlgorithm::Sha256,
+            )
+            .map_err(|e| tpm_err("policy session", e))?
+            .ok_or_else(|| PyRuntimeError::new_err("TPM policy session: no session handle"))?;
+        let (attributes, mask) = SessionAttributesBuilder::new()
+            .with_decrypt(true)
+            .with_encrypt(true)
+            .build();
+        ctx.tr_sess_set_attributes(session, attributes, mask)
+            .map_err(|e| tpm_err("policy session", e))?;
+        let session = PolicySession::try_from(session).map_err(|e| tpm_err("policy session", e))?;
+        ctx.policy_pcr(session, pcr_digest, pcrs.clone())
+            .map_err(|e| tpm_err("PCR policy", e))?;
+        Ok(session)
+    }
+
+    /// Seal `key` to the current values of `pcrs`. Returns the marshalled
+    /// public and private parts of the sealed object.
+    pub(super) fn seal(key: &[u8], pcrs: &[u8]) -> PyResult<(Vec<u8>, Vec<u8>)> {
+        let mut ctx = context()?;
+        let pcrs = selection(pcrs)?;
+
+        // TPM2_PolicyPCR digests the concatenated PCR values, lowest first.
+        let values = pcr::read_all(&mut ctx, pcrs.clone()).map_err(|e| tpm_err("PCR read", e))?;
+        let bank = values
+            .pcr_bank(HashingAlgorithm::Sha256)
+            .ok_or_else(|| PyRuntimeError::new_err("TPM has no SHA-256 PCR bank"))?;
+        let mut hasher = Sha256::new();
+        for (_, digest) in bank {
+            hasher.update(digest.value());
+        }
+        let pcr_digest =
+            Digest::try_from(hasher.finalize().to_vec()).map_err(|e| tpm_err("PCR digest", e))?;
+
+        let trial = pcr_session(&mut ctx, SessionType::Trial, &pcrs, pcr_digest)?;
+        let policy = ctx
+            .policy_get_digest(trial)
+            .map_err(|e| tpm_err("PCR policy", e))?;
+        ctx.flush_context(SessionHandle::from(trial).into())
+            .map_err(|e| tpm_err("policy session", e))?;
+
+        let attributes = ObjectAttributesBuilder::new()
+            .with_fixed_tpm(true)
+            .with_fixed_parent(true)
+            .with_no_da(true)
+            .build()
+            .map_err(|e| tpm_err("sealed object", e))?;
+        let public = PublicBuilder::new()
+            .with_public_algorithm(PublicAlgorithm::KeyedHash)
+            .with_name_hashing_algorithm(HashingAlgorithm::Sha256)
+            .with_object_attributes(attributes)
+            .with_auth_policy(policy)
+            .with_keyed_hash_parameters(PublicKeyedHashParameters::new(KeyedHashScheme::Null))
+            .with_keyed_hash_unique_identifier(Digest::default())
+            .build()
+            .map_err(|e| tpm_err("sealed object", e))?;
+        let data =
+            SensitiveData::try_from(key.to_vec()).map_err(|e| tpm_err("sealed object", e))?;
+
+        let parent = storage_key(&mut ctx)?;
+        let sealed = ctx.execute_with_nullauth_session(|ctx| {
+            ctx.create(parent, public, None, Some(data), None, None)
+        });
+        ctx.flush_context(parent.into())
+            .map_err(|e| tpm_err("storage key", e))?;
+        let sealed = sealed.map_err(|e| tpm_err("seal", e))?;
+
+        let public = sealed
+            .out_public
+            .marshall()
+            .map_err(|e| tpm_err("sealed object", e))?;
+        Ok((public, sealed.out_private.value().to_vec()))
+    }
+
+    /// Unseal the key sealed by seal(), if `pcrs` still hold the values they
+    /// had then.
+    pub(super) fn unseal(
+        pcrs: &[u8],
+        public: &[u8],
+        private: &[u8],
+    ) -> PyResult<Zeroizing<Vec<u8>>> {
+        let mut ctx = context()?;
+        let pcrs = selection(pcrs)?;
+        let public = Public::unmarshall(public).map_err(|e| tpm_err("sealed object", e))?;
+        let private =
+            Private::try_from(private.to_vec()).map_err(|e| tpm_err("sealed object", e))?;
+
+        let parent = storage_key(&mut ctx)?;
+        let sealed = ctx.execute_with_nullauth_session(|ctx| ctx.load(parent, private, public));
+        ctx.flush_context(parent.into())
+            .map_err(|e| tpm_err("storage key", e))?;
+        let sealed = sealed.map_err(|e| tpm_err("load sealed object", e))?;
+
+        // An empty digest makes the TPM use the PCRs' current values.
+        let key = pcr_session(&mut ctx, SessionType::Policy, &pcrs, Digest::default()).and_then(
+            |session| {
+                ctx.execute_with_session(Some(session.into()), |ctx| ctx.unseal(sealed.into()))
+                    .map_err(|e| tpm_err("unseal (PCR values changed?)", e))
+            },
+        );
+        ctx.flush_context(sealed.into())
+            .map_err(|e| tpm_err("sealed object", e))?;
+        Ok(Zeroizing::new(key?.value().to_vec()))
+    }
+}
+
+#[cfg(not(feature = "tpm"))]
+mod backend {
+    use pyo3::exceptions::PyRuntimeError;
+    use pyo3::prelude::*;
+    use zeroize::Zeroizing;
+
+    fn unavailable() -> PyErr {
+        PyRuntimeError::new_err(
+            "pqcrypto_bindings was built without TPM support (cargo feature `tpm`)",
+        )
+    }
+
+    pub(super) fn seal(_key: &[u8], _pcrs: &[u8]) -> PyResult<(Vec<u8>, Vec<u8>)> {
+        Err(unavailable())
+    }
+
+    pub(super) fn unseal(
+        _pcrs: &[u8],
+        _public: &[u8],
+        _private: &[u8],
+    ) -> PyResult<Zeroizing<Vec<u8>>> {
+        Err(unavailable())
+    }
+}
+
+fn len_u16(what: &str, bytes: &[u8]) -> PyResult<[u8; 2]> {
+    u16::try_from(bytes.len())
+        .map(u16::to_be_bytes)
+        .map_err(|_| PyRuntimeError::new_err(format!("TPM {} is too long", what)))
+}
+
+/// Seal `sk` to this machine's TPM and the current values of the PCRs in
+/// `pcr_policy` (SHA-256 bank).
+#[pyfunction]
+pub fn seal_to_tpm(py: Python, sk: Buffer, pcr_policy: Vec<u8>) -> PyResult<Py<PyBytes>> {
+    let mut pcrs = pcr_policy;
+    pcrs.sort_unstable();
+    pcrs.dedup();
+    if pcrs.is_empty() {
+        return Err(PyValueError::new_err(
+            "pcr_policy must list at least one PCR",
+        ));
+    }
+    if let Some(&pcr) = pcrs.iter().find(|&&i| i >= PCR_COUNT) {
+        return Err(PyValueError::new_err(format!(
+            "PCR {} out of range (0-{})",
+            pcr,
+            PCR_COUNT - 1
+        )));
+    }
+
+    let mut key = Zeroizing::new([0u8; KEY_LEN]);
+    let mut nonce = [0u8; NONCE_LEN];
+    getrandom::fill(&mut *key).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+    getrandom::fill(&mut nonce).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+    let (public, private) = backend::seal(&*key, &pcrs)?;
+
+    let mut blob = Vec::with_capacity(64 + public.len() + private.len() + sk.len() + TAG_LEN);
+    blob.extend_from_slice(MAGIC);
+    blob.push(VERSION);
+    blob.push(pcrs.len() as u8);
+    blob.extend_from_slice(&pcrs);
+    blob.extend_from_slice(&len_u16("sealed public", &public)?);
+    blob.extend_from_slice(&public);
+    blob.extend_from_slice(&len_u16("sealed private", &private)?);
+    blob.extend_from_slice(&private);
+    blob.extend_from_slice(&nonce);
+
+    // Encrypted in place, so the only plaintext copy is overwritten.
+    let mut body = Vec::with_capacity(sk.len() + TAG_LEN);
+    body.extend_from_slice(&sk);
+    Aes256Gcm::new(&(*key).into())
+        .encrypt_in_place(&Nonce::from(nonce), &blob, &mut body)
+        .map_err(|_| PyRuntimeError::new_err("AES-256-GCM encryption failed"))?;
+    blob.extend_from_slice(&body);
+    Ok(PyBytes::new_bound(py, &blob).unbind())
+}
+
+/// The secret key sealed by seal_to_tpm(). Fails on another machine, or
+/// once any of the PCRs it was sealed to has changed.
+#[pyfunction]
+pub fn unseal_from_tpm(py: Python, blob: Buffer) -> PyResult<Py<SecretBytes>> {
+    let mut r = Reader::new("TPM blob", &blob);
+    if r.take(MAGIC.len())? != MAGIC {
+        return Err(PyValueError::new_err("not a TPM-sealed key blob"));
+    }
+    let version = r.u8()?;
+    if version != VERSION {
+        return Err(PyValueError::new_err(format!(
+            "unsupported TPM blob version {}",
+            version
+        )));
+    }
+    let count = r.u8()? as usize;
+    let pcrs = r.take(count)?;
+    let len = r.u16()? as usize;
+    let public = r.take(len)?;
+    let len = r.u16()? as usize;
+    let private = r.take(len)?;
+    let nonce: [u8; NONCE_LEN] = r.take(NONCE_LEN)?.try_into().unwrap();
+    let body = r.rest();
+    if body.len() < TAG_LEN {
+        return Err(PyValueError::new_err("TPM blob is truncated"));
+    }
+    let header = &blob[..blob.len() - body.len()];
+
+    let key = backend::unseal(pcrs, public, private)?;
+    let cipher = Aes256Gcm::new_from_slice(&key)
+        .map_err(|_| DecryptionError::new_err("TPM unsealed a key of the wrong length"))?;
+    let mut sk = Zeroizing::new(body.to_vec());
+    cipher
+        .decrypt_in_place(&Nonce::from(nonce), header, &mut *sk)
+        .map_err(|_| DecryptionError::new_err("TPM blob has been modified"))?;
+    secret_bytes(py, &sk)
+}
@@ -0,0 +1,592 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// TUF-style role metadata over multi-signatures
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// Signed root and targets metadata for update systems, after The Update
+// Framework. The root names each role's keys and threshold:
+//
+//   root = tuf_root({"root": ([a_pk, b_pk, c_pk], 2), "targets": ([ci_pk], 1)},
+//                   expires=now + 365 * 86400)
+//   root = tuf_sign(tuf_sign(root, a_sk, a_pk), b_sk, b_pk)
+//
+//   targets = tuf_targets({"app-1.2.tar.gz": tarball}, expires=now + 7 * 86400)
+//   targets = tuf_sign(targets, ci_sk, ci_pk)
+//
+//   tuf_verify_target(root, targets, "app-1.2.tar.gz", downloaded)
+//
+// Metadata is JSON, {"signatures": container, "signed": body}, where the
+// container is a base64 MultiSignature blob over the canonical body:
+// json.dumps with sorted keys, no whitespace and ASCII only. Bodies are
+//
+//   {"_type": "root", "spec_version": "1.0", "version": n,
+//    "expires": "2027-01-01T00:00:00Z",
+//    "keys": {keyid: {"keytype": algorithm, "scheme": algorithm,
+//                     "keyval": {"public": hex(pk)}}, ...},
+//    "roles": {"root": {"keyids": [keyid, ...], "threshold": k},
+//              "targets": {...}}}
+//
+//   {"_type": "targets", "spec_version": "1.0", "version": n, "expires": ...,
+//    "targets": {path: {"length": n, "hashes": {"sha256": hex}}, ...}}
+//
+// Keys are Falcon-512 / 1024 or ML-DSA-44 / 65 / 87 by length, and a keyid
+// is the MultiSignature key ID in hex. tuf_verify(trusted_root, metadata)
+// checks metadata against a root the caller already trusts:
+//
+//   - targets need a threshold of signatures from the root's targets keys,
+//     and neither they nor the root may have expired;
+//   - a new root needs version trusted + 1 and a threshold of signatures
+//     from both the trusted root keys and its own, so clients step through
+//     rotations one version at a time. Expiry isn't checked on roots, as
+//     the intermediate ones of a rotation may well have expired; the final
+//     root's is, when targets are verified;
+//   - the trusted root itself (same version and body) verifies against its
+//     own root role.
+//
+// Failures raise SignatureVerificationError, expired metadata
+// SignatureExpiredError. There's no snapshot or timestamp role and no
+// delegation.
+
+use std::collections::BTreeMap;
+
+use base64ct::{Base64, Encoding};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict, PyList};
+use sha2::{Digest, Sha256};
+
+use crate::buffer::Buffer;
+use crate::errors::{InvalidLengthError, SignatureExpiredError, SignatureVerificationError};
+use crate::metadata::now;
+use crate::multisig::{key_id, MultiSignature};
+use crate::sign::SignatureAlgorithm;
+use crate::util::unhex;
+use crate::x509::{civil_from_days, days_from_civil};
+
+const SPEC_VERSION: &str = "1.0";
+const ROLES: [&str; 2] = ["root", "targets"];
+
+/// The schemes offered. Their key lengths are all different.
+const TUF_ALGORITHMS: &[&SignatureAlgorithm] = &[
+    &crate::falcon512::ALGORITHM,
+    &crate::falcon1024::ALGORITHM,
+    &crate::mldsa44::ALGORITHM,
+    &crate::mldsa65::ALGORITHM,
+    &crate::mldsa87::ALGORITHM,
+];
+
+fn by_name(name: &str) -> PyResult<&'static SignatureAlgorithm> {
+    let wanted = crate::kem::normalize_name(name);
+    TUF_ALGORITHMS
+        .iter()
+        .copied()
+        .find(|alg| alg.name == wanted)
+        .ok_or_else(|| {
+            PyValueError::new_err(format!(
+                "no TUF key type '{}' (expected a Falcon or ML-DSA parameter set)",
+                name
+            ))
+        })
+}
+
+fn by_key_length(
+    what: &str,
+    len: usize,
+    key_bytes: fn(&SignatureAlgorithm) -> usize,
+) -> PyResult<&'static SignatureAlgorithm> {
+    TUF_ALGORITHMS
+        .iter()
+        .copied()
+        .find(|alg| key_bytes(alg) == len)
+        .ok_or_else(|| {
+            InvalidLengthError::new_err(format!(
+                "TUF {}: {} bytes isn't a Falcon or ML-DSA key length",
+                what, len
+            ))
+        })
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn keyid(alg: &SignatureAlgorithm, pk: &[u8]) -> String {
+    hex(&key_id(alg, pk))
+}
+
+fn malformed(why: &str) -> PyErr {
+    PyValueError::new_err(format!("malformed TUF metadata: {}", why))
+}
+
+// ─── Time ─────────────────────────────────────────────────────────────────────
+
+fn format_time(t: u64) -> String {
+    let (year, month, day) = civil_from_days((t / 86400) as i64);
+    let secs = t % 86400;
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60
+    )
+}
+
+/// "YYYY-MM-DDTHH:MM:SSZ", the only form TUF uses.
+fn parse_time(text: &str) -> PyResult<u64> {
+    let bad = || malformed(&format!("'expires' isn't a UTC time: '{}'", text));
+    let b = text.as_bytes();
+    let shape = b.len() == 20
+        && b.iter().enumerate().all(|(i, &c)| match i {
+            4 | 7 => c == b'-',
+            10 => c == b'T',
+            13 | 16 => c == b':',
+            19 => c == b'Z',
+            _ => c.is_ascii_digit(),
+        });
+    if !shape {
+        return Err(bad());
+    }
+    let field = |from: usize, to: usize| -> u32 { text[from..to].parse().unwrap() };
+    let (month, day) = (field(5, 7), field(8, 10));
+    let (hour, minute, second) = (field(11, 13), field(14, 16), field(17, 19));
+    if !(1..=12).contains(&month) || day == 0 || hour > 23 || minute > 59 || second > 59 {
+        return Err(bad());
+    }
+    let days = days_from_civil(i64::from(field(0, 4)), month, day);
+    // Rejects the 31st of a 30-day month and the like.
+    if days < 0 || civil_from_days(days).2 != day {
+        return Err(bad());
+    }
+    Ok(days as u64 * 86400 + u64::from(hour * 3600 + minute * 60 + second))
+}
+
+// ─── JSON ─────────────────────────────────────────────────────────────────────
+
+fn json<'py>(py: Python<'py>) -> PyResult<Bound<'py, PyModule>> {
+    py.import_bound("json")
+}
+
+/// Sorted keys, no whitespace, ASCII only: the bytes the signatures cover.
+fn canonical(py: Python, object: &Bound<'_, PyDict>) -> PyResult<String> {
+    let kwargs = PyDict::new_bound(py);
+    kwargs.set_item("sort_keys", true)?;
+    kwargs.set_item("separators", (",", ":"))?;
+    json(py)?
+        .call_method("dumps", (object,), Some(&kwargs))?
+        .extract()
+}
+
+fn member<'py, T: FromPyObject<'py>>(
+    object: &Bound<'py, PyDict>,
+    key: &str,
+    kind: &str,
+) -> PyResult<T> {
+    object
+        .get_item(key)?
+        .ok_or_else(|| malformed(&format!("'{}' missing", key)))?
+        .extract()
+        .map_err(|_| malformed(&format!("'{}' isn't {}", key, kind)))
+}
+
+fn new_body<'py>(
+    py: Python<'py>,
+    kind: &str,
+    version: u64,
+    expires: u64,
+) -> PyResult<Bound<'py, PyDict>> {
+    if version == 0 {
+        return Err(PyValueError::new_err("TUF metadata versions start at 1"));
+    }
+    let body = PyDict::new_bound(py);
+    body.set_item("_type", kind)?;
+    body.set_item("spec_version", SPEC_VERSION)?;
+    body.set_item("version", version)?;
+    body.set_item("expires", format_time(expires))?;
+    Ok(body)
+}
+
+fn envelope(py: Python, body: &Bound<'_, PyDict>, signatures: &MultiSignature) -> PyResult<String> {
+    let object = PyDict::new_bound(py);
+    object.set_item("signatures", Base64::encode_string(&signatures.encode()))?;
+    object.set_item("signed", body)?;
+    canonical(py, &object)
+}
+
+fn unsigned(py: Python, body: &Bound<'_, PyDict>) -> PyResult<String> {
+    let signatures = MultiSignature::for_message(canonical(py, body)?.as_bytes());
+    envelope(py, body, &signatures)
+}
+
+struct Metadata<'py> {
+    body: Bound<'py, PyDict>,
+    canonical: String,
+    signatures: MultiSignature,
+    kind: String,
+    version: u64,
+    expires: u64,
+}
+
+fn parse_metadata<'py>(py: Python<'py>, text: &Bound<'py, PyAny>) -> PyResult<Metadata<'py>> {
+    let object = json(py)?
+        .call_method1("loads", (text,))
+        .map_err(|_| malformed("not JSON"))?
+        .downcast_into::<PyDict>()
+        .map_err(|_| malformed("not a JSON object"))?;
+    let body: Bound<PyDict> = member(&object, "signed", "an object")?;
+    let signatures: String = member(&object, "signatures", "a string")?;
+    let signatures =
+        Base64::decode_vec(&signatures).map_err(|_| malformed("'signatures' isn't base64"))?;
+    let spec_version: String = member(&body, "spec_version", "a string")?;
+    if spec_version != SPEC_VERSION {
+        return Err(PyValueError::new_err(format!(
+            "unsupported TUF spec_version '{}'",
+            spec_version
+        )));
+    }
+    let version: u64 = member(&body, "version", "a positive integer")?;
+    if version == 0 {
+        return Err(malformed("'version' isn't a positive integer"));
+    }
+    let expires: String = member(&body, "expires", "a string")?;
+    Ok(Metadata {
+        canonical: canonical(py, &body)?,
+        signatures: MultiSignature::parse(&signatures)?,
+        kind: member(&body, "_type", "a string")?,
+        expires: parse_time(&expires)?,
+        version,
+        body,
+    })
+}
+
+// ─── Roles ────────────────────────────────────────────────────────────────────
+
+struct Role {
+    keys: Vec<Buffer>,
+    threshold: usize,
+}
+
+fn check_threshold(role: &str, threshold: usize, keys: usize) -> PyResult<()> {
+    if threshold == 0 || threshold > keys {
+        return Err(PyValueError::new_err(format!(
+            "{} threshold must be between 1 and its {} keys, got {}",
+            role, keys, threshold
+        )));
+    }
+    Ok(())
+}
+
+/// The keys and threshold of `name` in a root, checking each keyid against
+/// its key.
+fn role(py: Python, root: &Metadata, name: &str) -> PyResult<Role> {
+    let keys: Bound<PyDict> = member(&root.body, "keys", "an object")?;
+    let roles: Bound<PyDict> = member(&root.body, "roles", "an object")?;
+    let role: Bound<PyDict> = member(&roles, name, "an object")?;
+    let keyids: Vec<String> = member(&role, "keyids", "a list of strings")?;
+    let threshold: usize = member(&role, "threshold", "a positive integer")?;
+
+    let mut role_keys = Vec::new();
+    for (i, id) in keyids.iter().enumerate() {
+        if keyids[..i].contains(id) {
+            return Err(malformed(&format!("keyid {} listed twice", id)));
+        }
+        let key: Bound<PyDict> = member(&keys, id, "an object")?;
+        let keytype: String = member(&key, "keytype", "a string")?;
+        let keyval: Bound<PyDict> = member(&key, "keyval", "an object")?;
+        let public: String = member(&keyval, "public", "a string")?;
+        let pk = unhex(&public).ok_or_else(|| malformed("public key isn't hex"))?;
+        let alg = by_name(&keytype)?;
+        if pk.len() != alg.public_key_bytes || keyid(alg, &pk) != *id {
+            return Err(malformed(&format!("keyid {} doesn't match its key", id)));
+        }
+        role_keys.push(PyBytes::new_bound(py, &pk).extract()?);
+    }
+    check_threshold(name, threshold, role_keys.len())?;
+    Ok(Role {
+        keys: role_keys,
+        threshold,
+    })
+}
+
+fn check_signed(py: Python, meta: &Metadata, role: &Role, keys: &str) -> PyResult<()> {
+    let signed = meta
+        .signatures
+        .signers(py, meta.canonical.as_bytes(), &role.keys)?
+        .len();
+    if signed < role.threshold {
+        return Err(SignatureVerificationError::new_err(format!(
+            "TUF {} metadata has valid signatures from {} of the {} keys, {} required",
+            meta.kind, signed, keys, role.threshold
+        )));
+    }
+    Ok(())
+}
+
+fn check_expiry(meta: &Metadata, what: &str, at: u64) -> PyResult<()> {
+    if at >= meta.expires {
+        return Err(SignatureExpiredError::new_err(format!(
+            "{} metadata expired at {}",
+            what,
+            format_time(meta.expires)
+        )));
+    }
+    Ok(())
+}
+
+fn verify_metadata<'py>(
+    py: Python<'py>,
+    root: &Bound<'py, PyAny>,
+    metadata: &Bound<'py, PyAny>,
+    time: Option<u64>,
+) -> PyResult<Metadata<'py>> {
+    let at = time.unwrap_or_else(now);
+    let trusted = parse_metadata(py, root)?;
+    if trusted.kind != "root" {
+        return Err(PyValueError::new_err(format!(
+            "trusted root is TUF {} metadata",
+            trusted.kind
+        )));
+    }
+    let root_role = role(py, &trusted, "root")?;
+    let targets_role = role(py, &trusted, "targets")?;
+
+    let meta = parse_metadata(py, metadata)?;
+    match meta.kind.as_str() {
+        "root" => {
+            let own = role(py, &meta, "root")?;
+            role(py, &meta, "targets")?;
+            if meta.version == trusted.version {
+                if meta.canonical != trusted.canonical {
+                    return Err(SignatureVerificationError::new_err(format!(
+                        "TUF root version {} differs from the trusted one",
+                        meta.version
+                    )));
+                }
+                check_signed(py, &meta, &root_role, "root")?;
+            } else if meta.version == trusted.version + 1 {
+                check_signed(py, &meta, &root_role, "trusted root")?;
+                check_signed(py, &meta, &own, "new root")?;
+            } else {
+                return Err(SignatureVerificationError::new_err(format!(
+                    "TUF root has version {}, expected {}",
+                    meta.version,
+                    trusted.version + 1
+                )));
+            }
+        }
+        "targets" => {
+            check_signed(py, &meta, &targets_role, "targets")?;
+            check_expiry(&trusted, "TUF root", at)?;
+            check_expiry(&meta, "TUF targets", at)?;
+        }
+        other => {
+            return Err(PyValueError::new_err(format!(
+                "unsupported TUF metadata type '{}'",
+                other
+            )))
+        }
+    }
+    Ok(meta)
+}
+
+// ─── Python API ───────────────────────────────────────────────────────────────
+
+/// Unsigned root metadata from {"root": (public_keys, threshold),
+/// "targets": (public_keys, threshold)}, expiring at `expires` (Unix time).
+#[pyfunction]
+#[pyo3(signature = (roles, expires, version = 1))]
+pub fn tuf_root(
+    py: Python,
+    roles: BTreeMap<String, (Vec<Buffer>, usize)>,
+    expires: u64,
+    version: u64,
+) -> PyResult<String> {
+    if let Some(name) = roles.keys().find(|name| !ROLES.contains(&name.as_str())) {
+        return Err(PyValueError::new_err(format!(
+            "unknown TUF role '{}'",
+            name
+        )));
+    }
+    let body = new_body(py, "root", version, expires)?;
+    let keys = PyDict::new_bound(py);
+    let role_objects = PyDict::new_bound(py);
+    for name in ROLES {
+        let (pks, threshold) = roles
+            .get(name)
+            .ok_or_else(|| PyValueError::new_err(format!("TUF role '{}' missing", name)))?;
+        let mut keyids: Vec<String> = Vec::new();
+        for pk in pks {
+            let alg = by_key_length("public key", pk.len(), |alg| alg.public_key_bytes)?;
+            let id = keyid(alg, pk);
+            if keyids.contains(&id) {
+                continue;
+            }
+            let keyval = PyDict::new_bound(py);
+            keyval.set_item("public", hex(pk))?;
+            let key = PyDict::new_bound(py);
+            key.set_item("keytype", alg.name)?;
+            key.set_item("scheme", alg.name)?;
+            key.set_item("keyval", keyval)?;
+            keys.set_item(&id, key)?;
+            keyids.push(id);
+        }
+        check_threshold(name, *threshold, keyids.len())?;
+        let role = PyDict::new_bound(py);
+        role.set_item("keyids", PyList::new_bound(py, keyids))?;
+        role.set_item("threshold", *threshold)?;
+        role_objects.set_item(name, role)?;
+    }
+    body.set_item("keys", keys)?;
+    body.set_item("roles", role_objects)?;
+    unsigned(py, &body)
+}
+
+/// Unsigned targets metadata listing the length and SHA-256 of each
+/// {path: contents}, expiring at `expires` (Unix time).
+#[pyfunction]
+#[pyo3(signature = (targets, expires, version = 1))]
+pub fn tuf_targets(
+    py: Python,
+    targets: BTreeMap<String, Buffer>,
+    expires: u64,
+    version: u64,
+) -> PyResult<String> {
+    let body = new_body(py, "targets", version, expires)?;
+    let files = PyDict::new_bound(py);
+    for (path, data) in &targets {
+        if path.is_empty() {
+            return Err(PyValueError::new_err("TUF target paths can't be empty"));
+        }
+        let hashes = PyDict::new_bound(py);
+        hashes.set_item("sha256", hex(&Sha256::digest(&data[..])))?;
+        let file = PyDict::new_bound(py);
+        file.set_item("length", data.len())?;
+        file.set_item("hashes", hashes)?;
+        files.set_item(path, file)?;
+    }
+    body.set_item("targets", files)?;
+    unsigned(py, &body)
+}
+
+/// Add a signature with `sk` to TUF metadata, replacing any earlier one by
+/// the same key. Raises ValueError if the body changed since it was first
+/// signed. The algorithm is taken from the secret key's length unless
+/// `algorithm` names one.
+// stub: metadata: str | bytes
+#[pyfunction]
+#[pyo3(signature = (metadata, sk, pk, algorithm = None))]
+pub fn tuf_sign(
+    py: Python,
+    metadata: &Bound<'_, PyAny>,
+    sk: Buffer,
+    pk: Buffer,
+    algorithm: Option<&str>,
+) -> PyResult<String> {
+    let alg = match algorithm {
+        Some(name) => by_name(name)?,
+        None => by_key_length("secret key", sk.len(), |alg| alg.secret_key_bytes)?,
+    };
+    let mut meta = parse_metadata(py, metadata)?;
+    if !meta.signatures.is_over(meta.canonical.as_bytes()) {
+        // Unsigned metadata may be edited by hand before the first signature.
+        if !meta.signatures.is_empty() {
+            return Err(PyValueError::new_err(
+                "TUF metadata was changed after it was signed",
+            ));
+        }
+        meta.signatures = MultiSignature::for_message(meta.canonical.as_bytes());
+    }
+    meta.signatures.sign(py, alg, sk, pk)?;
+    envelope(py, &meta.body, &meta.signatures)
+}
+
+/// Verify root or targets metadata against a trusted root (see above) at
+/// `time` (Unix time, default now) and return its "signed" body.
+// stub: root: str | bytes
+// stub: metadata: str | bytes
+// stub: -> dict[str, Any]
+#[pyfunction]
+#[pyo3(signature = (root, metadata, time = None))]
+pub fn tuf_verify<'py>(
+    py: Python<'py>,
+    root: &Bound<'py, PyAny>,
+    metadata: &Bound<'py, PyAny>,
+    time: Option<u64>,
+) -> PyResult<Bound<'py, PyDict>> {
+    Ok(verify_metadata(py, root, metadata, time)?.body)
+}
+
+/// Verify targets metadata against a trusted root, then check that `data`
+/// is the target listed at `path`. Raises SignatureVerificationError if it
+/// isn't listed or its length or SHA-256 differs.
+// stub: root: str | bytes
+// stub: targets: str | bytes
+#[pyfunction]
+#[pyo3(signature = (root, targets, path, data, time = None))]
+pub fn tuf_verify_target(
+    py: Python,
+    root: &Bound<'_, PyAny>,
+    targets: &Bound<'_, PyAny>,
+    path: &str,
+    data: Buffer,
+    time: Option<u64>,
+) -> PyResult<()> {
+    let meta = verify_metadata(py, root, targets, time)?;
+    if meta.kind != "targets" {
+        return Err(PyValueError::new_err(format!(
+            "expected TUF targets metadata, got {}",
+            meta.kind
+        )));
+    }
+    let files: Bound<PyDict> = member(&meta.body, "targets", "an object")?;
+    let file: Bound<PyDict> = match files.get_item(path)? {
+        Some(file) => file
+            .downcast_into()
+            .map_err(|_| malformed(&format!("target '{}' isn't an object", path)))?,
+        None => {
+            return Err(SignatureVerificationError::new_err(format!(
+                "'{}' isn't a TUF target",
+                path
+            )))
+        }
+    };
+    let length: u64 = member(&file, "length", "an integer")?;
+    let hashes: Bound<PyDict> = member(&file, "hashes", "an object")?;
+    let sha256: String = member(&hashes, "sha256", "a string")?;
+    if length != data.len() as u64 || sha256 != hex(&Sha256::digest(&data[..])) {
+        return Err(SignatureVerificationError::new_err(format!(
+            "'{}' doesn't match its TUF targets entry",
+            path
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn times_round_trip() {
+        for (t, text) in [
+            (0, "1970-01-01T00:00:00Z"),
+            (951_782_400, "2000-02-29T00:00:00Z"),
+            (1_798_761_599, "2026-12-31T23:59:59Z"),
+            (253_402_300_799, "9999-12-31T23:59:59Z"),
+        ] {
+            assert_eq!(format_time(t), text);
+            assert_eq!(parse_time(text).unwrap(), t);
+        }
+        for text in [
+            "2026-02-30T00:00:00Z",
+            "2026-04-31T00:00:00Z",
+            "2026-13-01T00:00:00Z",
+            "2026-01-01T24:00:00Z",
+            "2026-01-01 00:00:00Z",
+            "2026-01-01T00:00:00+00:00",
+        ] {
+            assert!(parse_time(text).is_err(), "{}", text);
+        }
+    }
+}
@@ -0,0 +1,211 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// Shared helpers for the key object classes and binary formats
+// ───────────────────────────────────────────────────────────────────────────────
+
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+use pyo3::exceptions::{PyRuntimeError, PyTypeError, PyValueError};
+use pyo3::{PyErr, PyResult};
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+
+/// Constant-time byte comparison for key objects' `__eq__`. Lengths are
+/// public (they only reveal the parameter set), contents are not.
+pub(crate) fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    a.ct_eq(b).into()
+}
+
+/// Bytes from lowercase or uppercase hex, or None if it isn't hex.
+pub(crate) fn unhex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) || !text.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&text[i..i + 2], 16).ok())
+        .collect()
+}
+
+const FINGERPRINT_LEN: usize = 8;
+
+fn hex_prefix(digest: &[u8]) -> String {
+    digest[..FINGERPRINT_LEN]
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Short identifier for a public key: the first 8 bytes of SHA-256 over it,
+/// as hex. Secret key objects show the fingerprint of their public key.
+pub(crate) fn fingerprint(pk: &[u8]) -> String {
+    hex_prefix(&Sha256::digest(pk))
+}
+
+/// For secret keys whose public key can't be recovered: a fingerprint over
+/// the secret key under its own label, so it can never collide with (or be
+/// mistaken for) a public key fingerprint.
+pub(crate) fn secret_fingerprint(sk: &[u8]) -> String {
+    let mut h = Sha256::new();
+    h.update(b"entropic-chaos secret key fingerprint\0");
+    h.update(sk);
+    hex_prefix(&h.finalize())
+}
+
+/// Full SHA-256 fingerprint for pinning a public key: the digest of a label,
+/// the canonical algorithm name and the key, so the same bytes under two
+/// algorithms never share a fingerprint.
+pub(crate) fn pinning_fingerprint(algorithm: &str, pk: &[u8]) -> [u8; 32] {
+    let mut h = Sha256::new();
+    h.update(b"entropic-chaos public key fingerprint\0");
+    h.update(algorithm.as_bytes());
+    h.update([0u8]);
+    h.update(pk);
+    h.finalize().into()
+}
+
+/// Uppercase hex in groups of four, for reading a fingerprint aloud or
+/// comparing it by eye: "3F2A 9C01 ...".
+pub(crate) fn format_fingerprint(digest: &[u8]) -> String {
+    digest
+        .chunks(2)
+        .map(|pair| {
+            pair.iter()
+                .map(|b| format!("{:02X}", b))
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Raised by `__reduce__` on secret key objects whose `allow_pickle` is off.
+/// Pickles end up in multiprocessing queues, caches and log files, so a
+/// secret key only travels that way when the caller asks for it.
+pub(crate) fn secret_pickle_error(class: &str) -> PyErr {
+    PyTypeError::new_err(format!(
+        "cannot pickle '{class}': it holds secret key material \
+         (set allow_pickle = True on the object to pickle it anyway)"
+    ))
+}
+
+/// Write `path` via a temporary file in the same directory, fsync'd, then
+/// renamed over it, so a crash leaves the old file or the new one, never a
+/// torn one. `fill` writes the contents; if it fails, the temporary file is
+/// removed and `path` is untouched.
+pub(crate) fn write_atomic_with(
+    path: &Path,
+    fill: impl FnOnce(&mut File) -> PyResult<()>,
+) -> PyResult<()> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| PyValueError::new_err(format!("not a file path: {}", path.display())))?;
+
+    let mut suffix = [0u8; 8];
+    getrandom::fill(&mut suffix).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+    let mut tmp_name = file_name.to_os_string();
+    tmp_name.push(format!(
+        ".{}.tmp",
+        suffix
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<String>()
+    ));
+    let tmp = dir.join(tmp_name);
+
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    let result = (|| {
+        let mut file = options.open(&tmp)?;
+        fill(&mut file)?;
+        file.sync_all()?;
+        fs::rename(&tmp, path)?;
+        // Persist the rename itself.
+        #[cfg(unix)]
+        File::open(dir)?.sync_all()?;
+        Ok::<_, PyErr>(())
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result
+}
+
+/// Write `data` to `path` with write_atomic_with().
+pub(crate) fn write_atomic(path: &Path, data: &[u8]) -> PyResult<()> {
+    write_atomic_with(path, |file| Ok(file.write_all(data)?))
+}
+
+/// Cursor over one of the length-prefixed binary formats (keystore payloads,
+/// metadata envelopes). Integers are big-endian; running short or leaving
+/// bytes over raises ValueError naming `what`, or the error given to
+/// with_error().
+pub(crate) struct Reader<'a> {
+    what: &'static str,
+    data: &'a [u8],
+    error: fn(String) -> PyErr,
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(what: &'static str, data: &'a [u8]) -> Self {
+        Reader::with_error(what, data, PyValueError::new_err::<String>)
+    }
+
+    /// For formats whose parse failures have their own exception, such as
+    /// DecryptionError for ciphertexts.
+    pub(crate) fn with_error(
+        what: &'static str,
+        data: &'a [u8],
+        error: fn(String) -> PyErr,
+    ) -> Self {
+        Reader { what, data, error }
+    }
+
+    pub(crate) fn take(&mut self, n: usize) -> PyResult<&'a [u8]> {
+        if self.data.len() < n {
+            return Err((self.error)(format!("{} is truncated", self.what)));
+        }
+        let (head, rest) = self.data.split_at(n);
+        self.data = rest;
+        Ok(head)
+    }
+
+    pub(crate) fn u8(&mut self) -> PyResult<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub(crate) fn u16(&mut self) -> PyResult<u16> {
+        Ok(u16::from_be_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    pub(crate) fn u32(&mut self) -> PyResult<u32> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    pub(crate) fn u64(&mut self) -> PyResult<u64> {
+        Ok(u64::from_be_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    /// Whatever is left, consuming it.
+    pub(crate) fn rest(&mut self) -> &'a [u8] {
+        std::mem::take(&mut self.data)
+    }
+
+    pub(crate) fn finish(self) -> PyResult<()> {
+        if !self.data.is_empty() {
+            return Err((self.error)(format!(
+                "{} has {} trailing bytes",
+                self.what,
+                self.data.len()
+            )));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,81 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// Public key validation
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// For servers taking public keys from clients, to reject a bad one before
+// encapsulating to it:
+//
+//   validate_public_key("mlkem768", client_pk)   # raises on a malformed key
+//
+// FIPS 203 (section 7.2) requires two checks on an ML-KEM encapsulation key:
+// the length, and the modulus check, that every 12-bit coefficient of the
+// encoded vector t is below q = 3329 (ByteEncode12(ByteDecode12(t)) == t).
+// Round-3 Kyber keys use the same encoding and get the same check, as does
+// the ML-KEM-768 half of an X-Wing key. Every other algorithm has no
+// standardized check beyond the length, so only that is checked.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::buffer::Buffer;
+use crate::errors::{check_length, PublicKeyValidationError};
+use crate::kem::find_kem;
+use crate::sign::find_signature;
+
+const KYBER_Q: u16 = 3329;
+
+/// The length of the encoded t at the start of a Kyber / ML-KEM style key,
+/// for the algorithms that have one.
+fn encoded_t_len(name: &str) -> Option<usize> {
+    match name {
+        "kyber512" | "mlkem512" => Some(2 * 384),
+        "kyber768" | "mlkem768" | "xwing" => Some(3 * 384),
+        "kyber1024" | "mlkem1024" => Some(4 * 384),
+        _ => None,
+    }
+}
+
+/// ML-KEM's modulus check: every 12-bit little-endian coefficient below q.
+/// The key is public, so this needn't run in constant time.
+fn modulus_check(name: &str, t: &[u8]) -> PyResult<()> {
+    for (i, b) in t.chunks_exact(3).enumerate() {
+        let c0 = u16::from(b[0]) | (u16::from(b[1] & 0x0f) << 8);
+        let c1 = u16::from(b[1] >> 4) | (u16::from(b[2]) << 4);
+        for (j, c) in [c0, c1].into_iter().enumerate() {
+            if c >= KYBER_Q {
+                return Err(PublicKeyValidationError::new_err(format!(
+                    "{} public key fails the modulus check: coefficient {} is {} (must be below {})",
+                    name,
+                    2 * i + j,
+                    c,
+                    KYBER_Q
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Run the standardized input checks on a public key: the length for every
+/// algorithm, plus the FIPS 203 modulus check for ML-KEM, Kyber and X-Wing.
+/// Raises InvalidLengthError or PublicKeyValidationError; returns None if
+/// the key passes.
+#[pyfunction]
+pub fn validate_public_key(name: &str, pk_bytes: Buffer) -> PyResult<()> {
+    let (alg_name, pk_len) = if let Some(alg) = find_kem(name) {
+        (alg.name, alg.public_key_bytes)
+    } else if let Some(alg) = find_signature(name) {
+        (alg.name, alg.public_key_bytes)
+    } else {
+        return Err(PyValueError::new_err(format!(
+            "unsupported algorithm '{}'",
+            name
+        )));
+    };
+    check_length(&format!("{} public key", alg_name), &pk_bytes, pk_len)?;
+
+    if let Some(t_len) = encoded_t_len(alg_name) {
+        modulus_check(alg_name, &pk_bytes[..t_len])?;
+    }
+    Ok(())
+}
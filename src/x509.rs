@@ -0,0 +1,1611 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// X.509 certificates with Falcon and ML-DSA
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// For internal PKIs that want to start issuing PQ certificates:
+//
+//   root = generate_self_signed_cert(ca_sk, ca_pk, "CN=Example Root,O=Example",
+//                                    validity=10 * 365 * 86400)
+//   csr = generate_csr(dev_sk, dev_pk, "CN=device-7,O=Example",
+//                      san=["device-7.example.com"])
+//   cert = generate_cert(ca_sk, root, csr)
+//   pem = certificate_to_pem(cert)
+//   path = verify_cert_chain(cert, [], [root])      # leaf first, root last
+//
+// Certificates are RFC 5280 v3, DER encoded. The SubjectPublicKeyInfo and the
+// signature use the same OID per scheme, with no parameters: the NIST arc
+// for ML-DSA-44 / 65 / 87 (2.16.840.1.101.3.4.3.17 / 18 / 19, as in RFC
+// 9881) and oqs-provider's for Falcon-512 / 1024 (1.3.9999.3.11 / 14, as in
+// encoding.rs). ML-DSA signs the TBSCertificate in pure mode with an empty
+// context. Each certificate gets
+//
+//   basicConstraints     critical; cA and the optional pathLenConstraint
+//   keyUsage             critical; keyCertSign and cRLSign too for a CA
+//   subjectKeyIdentifier SHA-256 of the public key, cut to 160 bits
+//                        (RFC 7093, method 1)
+//   authorityKeyIdentifier the issuer's key identifier (not when self-signed)
+//   subjectAltName       when there are names; critical if the subject is
+//                        empty
+//
+// and a random positive 128-bit serial unless `serial` is given. Subjects are
+// RFC 4514 strings with one attribute per RDN, most specific first, e.g.
+// "CN=device-7,OU=Sensors,O=Example,C=DE", with CN, O, OU, C, L, ST,
+// serialNumber, emailAddress and DC known; "\," escapes a comma. SAN entries
+// are IP addresses, URIs (anything with "://"), e-mail addresses (anything
+// with "@") or DNS names. Times are Unix seconds; validity runs from
+// `not_before` (default now) for `validity` seconds.
+//
+// generate_csr() writes a PKCS#10 request (RFC 2986) signed with the key
+// being enrolled, carrying the SAN entries as an extensionRequest attribute
+// (critical, again, if the subject is empty), so devices can enroll against
+// CAs that take CSRs, this module's or any other.
+//
+// generate_cert() checks the CSR's self-signature, takes its subject, key and
+// requested subjectAltName (unless `san` replaces it), and refuses a
+// ca_cert that isn't a CA, a ca_sk that doesn't match it, or a validity
+// running past the CA's. Certificates and CSRs are taken as DER or PEM.
+//
+// verify_cert_chain() builds a path from the leaf through any of the
+// intermediates to one of the roots, depth first, and checks at each link
+// the issuer's signature, that the issuer is a CA (basicConstraints cA, and
+// keyCertSign if it has keyUsage) within its pathLenConstraint, and that
+// every certificate is valid at `time`. Issuers are matched by exact DER
+// subject bytes and, when both are present, by authorityKeyIdentifier /
+// subjectKeyIdentifier. Roots are trust anchors: their own signatures
+// aren't checked, but their validity and constraints are. A critical
+// extension other than basicConstraints, keyUsage, subjectAltName or
+// extendedKeyUsage fails the path (so name and policy constraints are
+// refused, not ignored). Key purposes and names are left to the caller.
+// Failures raise CertificateVerificationError with the reason the last
+// candidate path was turned down.
+
+use pkcs8::ObjectIdentifier;
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use sha2::{Digest, Sha256};
+
+use crate::buffer::Buffer;
+use crate::encoding::{pem_decode, pem_encode, Pem};
+use crate::errors::{
+    check_length, CertificateVerificationError, InvalidLengthError, SignatureVerificationError,
+};
+use crate::metadata::now;
+use crate::sign::SignatureAlgorithm;
+
+/// One year.
+const DEFAULT_VALIDITY: u64 = 365 * 86400;
+/// 9999-12-31T23:59:59Z, the last time GeneralizedTime can express.
+const MAX_TIME: u64 = 253_402_300_799;
+
+pub(crate) const CERTIFICATE_LABEL: &str = "CERTIFICATE";
+const CSR_LABEL: &str = "CERTIFICATE REQUEST";
+
+// ─── Algorithms ───────────────────────────────────────────────────────────────
+
+pub(crate) struct CertAlgorithm {
+    pub alg: &'static SignatureAlgorithm,
+    oid: ObjectIdentifier,
+}
+
+const fn cert_algorithm(alg: &'static SignatureAlgorithm, oid: &str) -> CertAlgorithm {
+    CertAlgorithm {
+        alg,
+        oid: ObjectIdentifier::new_unwrap(oid),
+    }
+}
+
+/// Their public key lengths are all different.
+const CERT_ALGORITHMS: &[CertAlgorithm] = &[
+    cert_algorithm(&crate::falcon512::ALGORITHM, "1.3.9999.3.11"),
+    cert_algorithm(&crate::falcon1024::ALGORITHM, "1.3.9999.3.14"),
+    cert_algorithm(&crate::mldsa44::ALGORITHM, "2.16.840.1.101.3.4.3.17"),
+    cert_algorithm(&crate::mldsa65::ALGORITHM, "2.16.840.1.101.3.4.3.18"),
+    cert_algorithm(&crate::mldsa87::ALGORITHM, "2.16.840.1.101.3.4.3.19"),
+];
+
+fn algorithm_for_public_key(pk: &[u8]) -> PyResult<&'static CertAlgorithm> {
+    CERT_ALGORITHMS
+        .iter()
+        .find(|a| a.alg.public_key_bytes == pk.len())
+        .ok_or_else(|| {
+            InvalidLengthError::new_err(format!(
+                "certificate public key: {} bytes isn't a Falcon or ML-DSA key length",
+                pk.len()
+            ))
+        })
+}
+
+fn algorithm_for_oid(what: &str, oid: ObjectIdentifier) -> PyResult<&'static CertAlgorithm> {
+    CERT_ALGORITHMS
+        .iter()
+        .find(|a| a.oid == oid)
+        .ok_or_else(|| PyValueError::new_err(format!("unsupported {} algorithm OID {}", what, oid)))
+}
+
+const OID_BASIC_CONSTRAINTS: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.5.29.19");
+const OID_KEY_USAGE: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.5.29.15");
+const OID_SUBJECT_KEY_ID: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.5.29.14");
+const OID_AUTHORITY_KEY_ID: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.5.29.35");
+const OID_SUBJECT_ALT_NAME: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.5.29.17");
+const OID_EXTENSION_REQUEST: ObjectIdentifier =
+    ObjectIdentifier::new_unwrap("1.2.840.113549.1.9.14");
+
+// ─── DER writing ──────────────────────────────────────────────────────────────
+
+const BOOLEAN: u8 = 0x01;
+pub(crate) const INTEGER: u8 = 0x02;
+const BIT_STRING: u8 = 0x03;
+pub(crate) const OCTET_STRING: u8 = 0x04;
+const OID: u8 = 0x06;
+const UTF8_STRING: u8 = 0x0c;
+const PRINTABLE_STRING: u8 = 0x13;
+const IA5_STRING: u8 = 0x16;
+const UTC_TIME: u8 = 0x17;
+const GENERALIZED_TIME: u8 = 0x18;
+pub(crate) const SEQUENCE: u8 = 0x30;
+pub(crate) const SET: u8 = 0x31;
+pub(crate) const CONTEXT_0: u8 = 0xa0;
+const CONTEXT_3: u8 = 0xa3;
+
+pub(crate) fn tlv(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut out = vec![tag];
+    if content.len() < 0x80 {
+        out.push(content.len() as u8);
+    } else {
+        let len = content.len().to_be_bytes();
+        let skip = len.iter().take_while(|b| **b == 0).count();
+        out.push(0x80 | (len.len() - skip) as u8);
+        out.extend_from_slice(&len[skip..]);
+    }
+    out.extend_from_slice(content);
+    out
+}
+
+pub(crate) fn sequence(parts: &[&[u8]]) -> Vec<u8> {
+    tlv(SEQUENCE, &parts.concat())
+}
+
+pub(crate) fn oid(oid: &ObjectIdentifier) -> Vec<u8> {
+    tlv(OID, oid.as_bytes())
+}
+
+/// A non-negative INTEGER from big-endian magnitude bytes.
+pub(crate) fn unsigned_integer(magnitude: &[u8]) -> Vec<u8> {
+    let skip = magnitude.iter().take_while(|b| **b == 0).count();
+    let mut content = magnitude[skip..].to_vec();
+    if content.first().is_none_or(|b| b & 0x80 != 0) {
+        content.insert(0, 0);
+    }
+    tlv(INTEGER, &content)
+}
+
+fn bit_string(bytes: &[u8]) -> Vec<u8> {
+    tlv(BIT_STRING, &[&[0u8][..], bytes].concat())
+}
+
+pub(crate) fn algorithm_identifier(alg: &CertAlgorithm) -> Vec<u8> {
+    sequence(&[&oid(&alg.oid)])
+}
+
+fn subject_public_key_info(alg: &CertAlgorithm, pk: &[u8]) -> Vec<u8> {
+    sequence(&[&algorithm_identifier(alg), &bit_string(pk)])
+}
+
+/// (year, month, day) of a day count since 1970-01-01.
+pub(crate) fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+pub(crate) fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = year - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = (month as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// UTCTime through 2049, GeneralizedTime after, as RFC 5280 requires.
+pub(crate) fn time(t: u64) -> Vec<u8> {
+    let (year, month, day) = civil_from_days((t / 86400) as i64);
+    let secs = t % 86400;
+    let clock = format!(
+        "{:02}{:02}{:02}{:02}{:02}Z",
+        month,
+        day,
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60
+    );
+    if year < 2050 {
+        tlv(UTC_TIME, format!("{:02}{}", year % 100, clock).as_bytes())
+    } else {
+        tlv(GENERALIZED_TIME, format!("{:04}{}", year, clock).as_bytes())
+    }
+}
+
+// ─── DER reading ──────────────────────────────────────────────────────────────
+
+/// Strict DER: single-byte tags and minimal definite lengths. Errors are
+/// ValueError naming `what`.
+pub(crate) struct Der<'a> {
+    what: &'static str,
+    data: &'a [u8],
+}
+
+impl<'a> Der<'a> {
+    pub(crate) fn new(what: &'static str, data: &'a [u8]) -> Self {
+        Der { what, data }
+    }
+
+    pub(crate) fn malformed(&self, why: &str) -> PyErr {
+        PyValueError::new_err(format!("malformed {}: {}", self.what, why))
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub(crate) fn peek_tag(&self) -> Option<u8> {
+        self.data.first().copied()
+    }
+
+    /// The next element's tag, contents and whole encoding.
+    pub(crate) fn next(&mut self) -> PyResult<(u8, &'a [u8], &'a [u8])> {
+        let data = self.data;
+        let truncated = || self.malformed("truncated");
+        let tag = *data.first().ok_or_else(truncated)?;
+        if tag & 0x1f == 0x1f {
+            return Err(self.malformed("multi-byte tags aren't supported"));
+        }
+        let first = *data.get(1).ok_or_else(truncated)?;
+        let (len, header) = if first < 0x80 {
+            (first as usize, 2)
+        } else {
+            let count = (first & 0x7f) as usize;
+            if count == 0 || count > 4 {
+                return Err(self.malformed("unsupported length encoding"));
+            }
+            let bytes = data.get(2..2 + count).ok_or_else(truncated)?;
+            if bytes[0] == 0 {
+                return Err(self.malformed("non-minimal length"));
+            }
+            let len = bytes.iter().fold(0usize, |n, b| n << 8 | *b as usize);
+            if len < 0x80 {
+                return Err(self.malformed("non-minimal length"));
+            }
+            (len, 2 + count)
+        };
+        let end = header
+            .checked_add(len)
+            .filter(|end| *end <= data.len())
+            .ok_or_else(truncated)?;
+        self.data = &data[end..];
+        Ok((tag, &data[header..end], &data[..end]))
+    }
+
+    /// Contents and whole encoding of the next element, which must be `tag`.
+    pub(crate) fn expect_raw(&mut self, tag: u8) -> PyResult<(&'a [u8], &'a [u8])> {
+        let (found, content, raw) = self.next()?;
+        if found != tag {
+            return Err(self.malformed(&format!(
+                "expected tag 0x{:02x}, found 0x{:02x}",
+                tag, found
+            )));
+        }
+        Ok((content, raw))
+    }
+
+    pub(crate) fn expect(&mut self, tag: u8) -> PyResult<&'a [u8]> {
+        Ok(self.expect_raw(tag)?.0)
+    }
+
+    pub(crate) fn optional(&mut self, tag: u8) -> PyResult<Option<&'a [u8]>> {
+        if self.peek_tag() == Some(tag) {
+            Ok(Some(self.expect(tag)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub(crate) fn nested(&self, content: &'a [u8]) -> Der<'a> {
+        Der::new(self.what, content)
+    }
+
+    /// A reader over the contents of the next element, which must be `tag`.
+    pub(crate) fn enter(&mut self, tag: u8) -> PyResult<Der<'a>> {
+        let content = self.expect(tag)?;
+        Ok(self.nested(content))
+    }
+
+    pub(crate) fn oid(&mut self) -> PyResult<ObjectIdentifier> {
+        let content = self.expect(OID)?;
+        ObjectIdentifier::from_bytes(content).map_err(|_| self.malformed("bad OID"))
+    }
+
+    fn boolean(&mut self) -> PyResult<bool> {
+        match self.expect(BOOLEAN)? {
+            [0x00] => Ok(false),
+            [0xff] => Ok(true),
+            _ => Err(self.malformed("bad BOOLEAN")),
+        }
+    }
+
+    pub(crate) fn small_integer(&mut self) -> PyResult<u64> {
+        match self.expect(INTEGER)? {
+            [] => Err(self.malformed("empty INTEGER")),
+            [b, ..] if b & 0x80 != 0 => Err(self.malformed("negative INTEGER")),
+            bytes if bytes.len() > 9 || (bytes.len() == 9 && bytes[0] != 0) => {
+                Err(self.malformed("INTEGER too large"))
+            }
+            bytes => Ok(bytes.iter().fold(0u64, |n, b| n << 8 | *b as u64)),
+        }
+    }
+
+    /// The contents of a BIT STRING with no unused bits.
+    fn bit_string(&mut self) -> PyResult<&'a [u8]> {
+        match self.expect(BIT_STRING)? {
+            [0, rest @ ..] => Ok(rest),
+            _ => Err(self.malformed("BIT STRING with unused bits")),
+        }
+    }
+
+    pub(crate) fn time(&mut self) -> PyResult<u64> {
+        let (tag, content, _) = self.next()?;
+        let text = std::str::from_utf8(content).map_err(|_| self.malformed("bad time"))?;
+        let digits = |s: &str| -> Option<u32> {
+            s.bytes()
+                .all(|b| b.is_ascii_digit())
+                .then(|| s.parse().ok())?
+        };
+        let (year, rest) = match (tag, text.len()) {
+            (UTC_TIME, 13) => {
+                let yy = digits(&text[..2]).ok_or_else(|| self.malformed("bad time"))?;
+                (if yy < 50 { 2000 + yy } else { 1900 + yy }, &text[2..])
+            }
+            (GENERALIZED_TIME, 15) => (
+                digits(&text[..4]).ok_or_else(|| self.malformed("bad time"))?,
+                &text[4..],
+            ),
+            _ => return Err(self.malformed("bad time")),
+        };
+        let field = |i: usize| digits(&rest[2 * i..2 * i + 2]);
+        let parsed = (|| {
+            if !rest.ends_with('Z') {
+                return None;
+            }
+            let (month, day) = (field(0)?, field(1)?);
+            let (hour, minute, second) = (field(2)?, field(3)?, field(4)?);
+            if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+                return None;
+            }
+            if hour > 23 || minute > 59 || second > 59 {
+                return None;
+            }
+            let days = days_from_civil(year as i64, month, day);
+            let secs = days * 86400 + (hour * 3600 + minute * 60 + second) as i64;
+            u64::try_from(secs).ok()
+        })();
+        parsed.ok_or_else(|| self.malformed("bad time"))
+    }
+
+    pub(crate) fn finish(&self) -> PyResult<()> {
+        if !self.is_empty() {
+            return Err(self.malformed("trailing data"));
+        }
+        Ok(())
+    }
+}
+
+/// DER as given, or the DER inside a PEM block with `label`.
+pub(crate) fn der_or_pem(what: &str, data: &[u8], label: &str) -> PyResult<Vec<u8>> {
+    if !data.starts_with(b"-----BEGIN") {
+        return Ok(data.to_vec());
+    }
+    let (found, der) = pem_rfc7468::decode_vec(data)
+        .map_err(|e| PyValueError::new_err(format!("{}: malformed PEM: {}", what, e)))?;
+    if found != label {
+        return Err(PyValueError::new_err(format!(
+            "{}: expected a {} PEM block, got '{}'",
+            what, label, found
+        )));
+    }
+    Ok(der)
+}
+
+// ─── Names and extensions ─────────────────────────────────────────────────────
+
+struct NameAttribute {
+    short: &'static str,
+    oid: ObjectIdentifier,
+    tag: u8,
+}
+
+const fn attribute(short: &'static str, oid: &str, tag: u8) -> NameAttribute {
+    NameAttribute {
+        short,
+        oid: ObjectIdentifier::new_unwrap(oid),
+        tag,
+    }
+}
+
+const NAME_ATTRIBUTES: &[NameAttribute] = &[
+    attribute("CN", "2.5.4.3", UTF8_STRING),
+    attribute("C", "2.5.4.6", PRINTABLE_STRING),
+    attribute("L", "2.5.4.7", UTF8_STRING),
+    attribute("ST", "2.5.4.8", UTF8_STRING),
+    attribute("O", "2.5.4.10", UTF8_STRING),
+    attribute("OU", "2.5.4.11", UTF8_STRING),
+    attribute("serialNumber", "2.5.4.5", PRINTABLE_STRING),
+    attribute("emailAddress", "1.2.840.113549.1.9.1", IA5_STRING),
+    attribute("DC", "0.9.2342.19200300.100.1.25", IA5_STRING),
+];
+
+fn is_printable(value: &str) -> bool {
+    value
+        .bytes()
+        .all(|b| b.is_ascii_alphanumeric() || b" '()+,-./:=?".contains(&b))
+}
+
+/// (type, value) pairs of an RFC 4514 string, in the string's order.
+fn parse_subject(subject: &str) -> PyResult<Vec<(String, String)>> {
+    let bad = |why: &str| PyValueError::new_err(format!("subject '{}': {}", subject, why));
+    let mut pairs = Vec::new();
+    if subject.trim().is_empty() {
+        return Ok(pairs);
+    }
+    let (mut key, mut value, mut in_value) = (String::new(), String::new(), false);
+    let mut finish = |key: &mut String, value: &mut String, in_value: bool| {
+        if !in_value || key.trim().is_empty() {
+            return Err(bad("expected TYPE=value attributes"));
+        }
+        pairs.push((key.trim().to_owned(), value.trim().to_owned()));
+        key.clear();
+        value.clear();
+        Ok(())
+    };
+    let mut chars = subject.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                let escaped = chars.next().ok_or_else(|| bad("ends with a backslash"))?;
+                if in_value { &mut value } else { &mut key }.push(escaped);
+            }
+            '=' if !in_value => in_value = true,
+            ',' => {
+                finish(&mut key, &mut value, in_value)?;
+                in_value = false;
+            }
+            '+' => return Err(bad("multi-valued RDNs aren't supported")),
+            _ => if in_value { &mut value } else { &mut key }.push(c),
+        }
+    }
+    finish(&mut key, &mut value, in_value)?;
+    Ok(pairs)
+}
+
+/// The DER Name for an RFC 4514 string.
+fn encode_name(subject: &str) -> PyResult<Vec<u8>> {
+    let mut rdns = Vec::new();
+    for (key, value) in parse_subject(subject)?.iter().rev() {
+        let attr = NAME_ATTRIBUTES
+            .iter()
+            .find(|a| a.short.eq_ignore_ascii_case(key))
+            .ok_or_else(|| {
+                PyValueError::new_err(format!("unsupported subject attribute '{}'", key))
+            })?;
+        let valid = match attr.tag {
+            PRINTABLE_STRING => is_printable(value),
+            IA5_STRING => value.is_ascii(),
+            _ => true,
+        };
+        if !valid || value.is_empty() || (attr.short == "C" && value.len() != 2) {
+            return Err(PyValueError::new_err(format!(
+                "invalid {} value '{}'",
+                attr.short, value
+            )));
+        }
+        let pair = sequence(&[&oid(&attr.oid), &tlv(attr.tag, value.as_bytes())]);
+        rdns.extend_from_slice(&tlv(SET, &pair));
+    }
+    Ok(tlv(SEQUENCE, &rdns))
+}
+
+const GENERAL_NAME_EMAIL: u8 = 0x81;
+const GENERAL_NAME_DNS: u8 = 0x82;
+const GENERAL_NAME_URI: u8 = 0x86;
+const GENERAL_NAME_IP: u8 = 0x87;
+
+/// The DER GeneralNames for subjectAltName entries.
+fn encode_san(entries: &[String]) -> PyResult<Vec<u8>> {
+    let mut names = Vec::new();
+    for entry in entries {
+        if let Ok(ip) = entry.parse::<std::net::IpAddr>() {
+            let octets = match ip {
+                std::net::IpAddr::V4(ip) => ip.octets().to_vec(),
+                std::net::IpAddr::V6(ip) => ip.octets().to_vec(),
+            };
+            names.extend_from_slice(&tlv(GENERAL_NAME_IP, &octets));
+            continue;
+        }
+        if entry.is_empty() || !entry.is_ascii() || entry.contains(char::is_whitespace) {
+            return Err(PyValueError::new_err(format!(
+                "invalid subjectAltName '{}' (IDNA-encode internationalized names)",
+                entry
+            )));
+        }
+        let tag = if entry.contains("://") {
+            GENERAL_NAME_URI
+        } else if entry.contains('@') {
+            GENERAL_NAME_EMAIL
+        } else {
+            GENERAL_NAME_DNS
+        };
+        names.extend_from_slice(&tlv(tag, entry.as_bytes()));
+    }
+    Ok(tlv(SEQUENCE, &names))
+}
+
+fn extension(id: &ObjectIdentifier, critical: bool, value: &[u8]) -> Vec<u8> {
+    let mut content = oid(id);
+    if critical {
+        content.extend_from_slice(&tlv(BOOLEAN, &[0xff]));
+    }
+    content.extend_from_slice(&tlv(OCTET_STRING, value));
+    tlv(SEQUENCE, &content)
+}
+
+/// RFC 7093 method 1: the leftmost 160 bits of SHA-256 over the key.
+fn key_identifier(pk: &[u8]) -> Vec<u8> {
+    Sha256::digest(pk)[..20].to_vec()
+}
+
+// ─── Parsing ──────────────────────────────────────────────────────────────────
+
+struct Extension<'a> {
+    id: ObjectIdentifier,
+    critical: bool,
+    value: &'a [u8],
+}
+
+fn parse_extensions<'a>(der: &mut Der<'a>) -> PyResult<Vec<Extension<'a>>> {
+    let mut list = der.enter(SEQUENCE)?;
+    let mut extensions: Vec<Extension> = Vec::new();
+    while !list.is_empty() {
+        let mut ext = list.enter(SEQUENCE)?;
+        let id = ext.oid()?;
+        let critical = if ext.peek_tag() == Some(BOOLEAN) {
+            ext.boolean()?
+        } else {
+            false
+        };
+        let value = ext.expect(OCTET_STRING)?;
+        ext.finish()?;
+        if extensions.iter().any(|e| e.id == id) {
+            return Err(list.malformed(&format!("extension {} appears twice", id)));
+        }
+        extensions.push(Extension {
+            id,
+            critical,
+            value,
+        });
+    }
+    Ok(extensions)
+}
+
+fn find_extension<'a, 'b>(
+    extensions: &'b [Extension<'a>],
+    id: ObjectIdentifier,
+) -> Option<&'b Extension<'a>> {
+    extensions.iter().find(|e| e.id == id)
+}
+
+pub(crate) fn parse_algorithm(der: &mut Der, what: &str) -> PyResult<&'static CertAlgorithm> {
+    let mut alg = der.enter(SEQUENCE)?;
+    let id = alg.oid()?;
+    if !alg.is_empty() {
+        return Err(alg.malformed(&format!("{} algorithm has parameters", what)));
+    }
+    algorithm_for_oid(what, id)
+}
+
+fn parse_public_key<'a>(der: &mut Der<'a>) -> PyResult<(&'static CertAlgorithm, &'a [u8])> {
+    let mut spki = der.enter(SEQUENCE)?;
+    let algorithm = parse_algorithm(&mut spki, "public key")?;
+    let pk = spki.bit_string()?;
+    spki.finish()?;
+    check_length(
+        &format!("{} public key", algorithm.alg.name),
+        pk,
+        algorithm.alg.public_key_bytes,
+    )?;
+    Ok((algorithm, pk))
+}
+
+pub(crate) fn verify(
+    py: Python,
+    algorithm: &CertAlgorithm,
+    pk: &[u8],
+    msg: &[u8],
+    sig: &[u8],
+) -> PyResult<bool> {
+    (algorithm.alg.verify)(
+        PyBytes::new_bound(py, pk).extract()?,
+        PyBytes::new_bound(py, msg).extract()?,
+        PyBytes::new_bound(py, sig).extract()?,
+    )
+}
+
+struct Csr<'a> {
+    subject: &'a [u8],
+    algorithm: &'static CertAlgorithm,
+    public_key: &'a [u8],
+    /// The requested subjectAltName, as DER GeneralNames.
+    san: Option<&'a [u8]>,
+}
+
+/// Parse a PKCS#10 request and check its self-signature.
+fn parse_csr<'a>(py: Python, der: &'a [u8]) -> PyResult<Csr<'a>> {
+    let mut outer = Der::new("CSR", der);
+    let mut csr = outer.enter(SEQUENCE)?;
+    outer.finish()?;
+    let (info, info_raw) = csr.expect_raw(SEQUENCE)?;
+    let signature_algorithm = parse_algorithm(&mut csr, "CSR signature")?;
+    let signature = csr.bit_string()?;
+    csr.finish()?;
+
+    let mut info = csr.nested(info);
+    if info.small_integer()? != 0 {
+        return Err(info.malformed("unsupported version"));
+    }
+    let (_, subject) = info.expect_raw(SEQUENCE)?;
+    let (algorithm, public_key) = parse_public_key(&mut info)?;
+    let mut attributes = info.enter(CONTEXT_0)?;
+    info.finish()?;
+    let mut san = None;
+    while !attributes.is_empty() {
+        let mut attr = attributes.enter(SEQUENCE)?;
+        let id = attr.oid()?;
+        let mut values = attr.enter(SET)?;
+        attr.finish()?;
+        if id == OID_EXTENSION_REQUEST {
+            let extensions = parse_extensions(&mut values)?;
+            san = find_extension(&extensions, OID_SUBJECT_ALT_NAME).map(|e| e.value);
+        }
+    }
+
+    if !std::ptr::eq(signature_algorithm, algorithm) {
+        return Err(PyValueError::new_err(
+            "CSR isn't signed with the algorithm of its own key",
+        ));
+    }
+    if !verify(py, algorithm, public_key, info_raw, signature)? {
+        return Err(SignatureVerificationError::new_err(
+            "CSR self-signature doesn't verify",
+        ));
+    }
+    Ok(Csr {
+        subject,
+        algorithm,
+        public_key,
+        san,
+    })
+}
+
+pub(crate) struct Certificate<'a> {
+    pub der: &'a [u8],
+    tbs: &'a [u8],
+    signature_algorithm: &'static CertAlgorithm,
+    signature: &'a [u8],
+    /// The serialNumber INTEGER's contents.
+    pub serial: &'a [u8],
+    pub issuer: &'a [u8],
+    pub subject: &'a [u8],
+    pub algorithm: &'static CertAlgorithm,
+    pub public_key: &'a [u8],
+    not_before: u64,
+    not_after: u64,
+    extensions: Vec<Extension<'a>>,
+}
+
+const ISSUER_UNIQUE_ID: u8 = 0x81;
+const SUBJECT_UNIQUE_ID: u8 = 0x82;
+
+/// Parse a certificate's structure; the signature isn't checked.
+pub(crate) fn parse_certificate(der: &[u8]) -> PyResult<Certificate<'_>> {
+    let mut outer = Der::new("certificate", der);
+    let mut cert = outer.enter(SEQUENCE)?;
+    outer.finish()?;
+    let (tbs_content, tbs_raw) = cert.expect_raw(SEQUENCE)?;
+    let signature_algorithm = parse_algorithm(&mut cert, "certificate signature")?;
+    let signature = cert.bit_string()?;
+    cert.finish()?;
+
+    let mut tbs = cert.nested(tbs_content);
+
+    if let Some(version) = tbs.optional(CONTEXT_0)? {
+        let mut version = tbs.nested(version);
+        if version.small_integer()? > 2 {
+            return Err(version.malformed("unsupported version"));
+        }
+        version.finish()?;
+    }
+    let serial = tbs.expect(INTEGER)?;
+    if !std::ptr::eq(
+        parse_algorithm(&mut tbs, "certificate signature")?,
+        signature_algorithm,
+    ) {
+        return Err(tbs.malformed("the two signature algorithms differ"));
+    }
+    let (_, issuer) = tbs.expect_raw(SEQUENCE)?;
+    let mut validity = tbs.enter(SEQUENCE)?;
+    let not_before = validity.time()?;
+    let not_after = validity.time()?;
+    validity.finish()?;
+    let (_, subject) = tbs.expect_raw(SEQUENCE)?;
+    let (algorithm, public_key) = parse_public_key(&mut tbs)?;
+    tbs.optional(ISSUER_UNIQUE_ID)?;
+    tbs.optional(SUBJECT_UNIQUE_ID)?;
+    let extensions = match tbs.optional(CONTEXT_3)? {
+        Some(extensions) => {
+            let mut extensions = tbs.nested(extensions);
+            let list = parse_extensions(&mut extensions)?;
+            extensions.finish()?;
+            list
+        }
+        None => Vec::new(),
+    };
+    tbs.finish()?;
+    Ok(Certificate {
+        der,
+        tbs: tbs_raw,
+        signature_algorithm,
+        signature,
+        serial,
+        issuer,
+        subject,
+        algorithm,
+        public_key,
+        not_before,
+        not_after,
+        extensions,
+    })
+}
+
+impl Certificate<'_> {
+    /// (cA, pathLenConstraint) from basicConstraints.
+    fn basic_constraints(&self) -> PyResult<(bool, Option<u64>)> {
+        let Some(ext) = find_extension(&self.extensions, OID_BASIC_CONSTRAINTS) else {
+            return Ok((false, None));
+        };
+        let mut outer = Der::new("basicConstraints", ext.value);
+        let mut bc = outer.enter(SEQUENCE)?;
+        outer.finish()?;
+        let ca = if bc.peek_tag() == Some(BOOLEAN) {
+            bc.boolean()?
+        } else {
+            false
+        };
+        let path_length = if bc.is_empty() {
+            None
+        } else {
+            Some(bc.small_integer()?)
+        };
+        bc.finish()?;
+        Ok((ca, path_length))
+    }
+
+    /// The subjectKeyIdentifier, or one computed as for new certificates.
+    fn key_identifier(&self) -> PyResult<Vec<u8>> {
+        Ok(match self.subject_key_id()? {
+            Some(id) => id.to_vec(),
+            None => key_identifier(self.public_key),
+        })
+    }
+
+    pub(crate) fn subject_key_id(&self) -> PyResult<Option<&[u8]>> {
+        let Some(ext) = find_extension(&self.extensions, OID_SUBJECT_KEY_ID) else {
+            return Ok(None);
+        };
+        let mut der = Der::new("subjectKeyIdentifier", ext.value);
+        let id = der.expect(OCTET_STRING)?;
+        der.finish()?;
+        Ok(Some(id))
+    }
+
+    /// The keyIdentifier of authorityKeyIdentifier, if there is one.
+    fn authority_key_id(&self) -> PyResult<Option<&[u8]>> {
+        let Some(ext) = find_extension(&self.extensions, OID_AUTHORITY_KEY_ID) else {
+            return Ok(None);
+        };
+        let mut outer = Der::new("authorityKeyIdentifier", ext.value);
+        let mut aki = outer.enter(SEQUENCE)?;
+        outer.finish()?;
+        aki.optional(0x80)
+    }
+
+    /// The first byte of keyUsage's bits, if there is keyUsage.
+    fn key_usage(&self) -> PyResult<Option<u8>> {
+        let Some(ext) = find_extension(&self.extensions, OID_KEY_USAGE) else {
+            return Ok(None);
+        };
+        let mut der = Der::new("keyUsage", ext.value);
+        let bits = der.expect(BIT_STRING)?;
+        der.finish()?;
+        Ok(Some(bits.get(1).copied().unwrap_or(0)))
+    }
+
+    /// Whether keyUsage, if present, has keyCertSign.
+    fn may_sign_certificates(&self) -> PyResult<bool> {
+        Ok(self.key_usage()?.is_none_or(|bits| bits & 0x04 != 0))
+    }
+
+    /// Whether keyUsage, if present, has digitalSignature or
+    /// nonRepudiation.
+    pub(crate) fn may_sign_content(&self) -> PyResult<bool> {
+        Ok(self.key_usage()?.is_none_or(|bits| bits & 0xc0 != 0))
+    }
+}
+
+// ─── Certificate requests ─────────────────────────────────────────────────────
+
+/// A PKCS#10 request for `subject` and `public_key`, with the subjectAltName
+/// in an extensionRequest attribute, signed with `sk`.
+fn request_certificate(
+    py: Python,
+    sk: Buffer,
+    algorithm: &'static CertAlgorithm,
+    public_key: &[u8],
+    subject: &[u8],
+    san: Option<&[u8]>,
+) -> PyResult<Vec<u8>> {
+    let empty_subject = subject == [SEQUENCE, 0];
+    let mut attributes = Vec::new();
+    match san {
+        Some(san) if san != [SEQUENCE, 0] => {
+            let extensions = tlv(
+                SEQUENCE,
+                &extension(&OID_SUBJECT_ALT_NAME, empty_subject, san),
+            );
+            attributes = sequence(&[&oid(&OID_EXTENSION_REQUEST), &tlv(SET, &extensions)]);
+        }
+        _ if empty_subject => {
+            return Err(PyValueError::new_err(
+                "a CSR with an empty subject needs subjectAltName entries",
+            ));
+        }
+        _ => {}
+    }
+    let info = sequence(&[
+        &unsigned_integer(&[0]),
+        subject,
+        &subject_public_key_info(algorithm, public_key),
+        &tlv(CONTEXT_0, &attributes),
+    ]);
+    let signature = (algorithm.alg.sign)(py, sk, PyBytes::new_bound(py, &info).extract()?)?;
+    let signature = signature.as_bytes(py);
+    if !verify(py, algorithm, public_key, &info, signature)? {
+        return Err(PyValueError::new_err("sk doesn't match pk"));
+    }
+    Ok(sequence(&[
+        &info,
+        &algorithm_identifier(algorithm),
+        &bit_string(signature),
+    ]))
+}
+
+// ─── Issuance ─────────────────────────────────────────────────────────────────
+
+struct Issuer<'a> {
+    algorithm: &'static CertAlgorithm,
+    name: &'a [u8],
+    public_key: &'a [u8],
+    /// None when self-signed.
+    key_id: Option<Vec<u8>>,
+}
+
+struct Request<'a> {
+    subject: &'a [u8],
+    algorithm: &'static CertAlgorithm,
+    public_key: &'a [u8],
+    san: Option<Vec<u8>>,
+    serial: Option<u128>,
+    not_before: u64,
+    not_after: u64,
+    is_ca: bool,
+    path_length: Option<u32>,
+}
+
+fn validity(not_before: Option<u64>, validity: u64) -> PyResult<(u64, u64)> {
+    let not_before = not_before.unwrap_or_else(now);
+    if validity == 0 {
+        return Err(PyValueError::new_err("validity must be positive"));
+    }
+    let not_after = not_before
+        .checked_add(validity)
+        .filter(|t| *t <= MAX_TIME)
+        .ok_or_else(|| PyValueError::new_err("certificate would be valid past the year 9999"))?;
+    Ok((not_before, not_after))
+}
+
+fn serial_number(serial: Option<u128>) -> PyResult<Vec<u8>> {
+    match serial {
+        Some(0) => Err(PyValueError::new_err("serial must be positive")),
+        Some(serial) => Ok(unsigned_integer(&serial.to_be_bytes())),
+        None => {
+            let mut serial = [0u8; 16];
+            getrandom::fill(&mut serial).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+            // Positive and always 16 bytes long.
+            serial[0] = serial[0] & 0x7f | 0x40;
+            Ok(unsigned_integer(&serial))
+        }
+    }
+}
+
+fn issue(py: Python, sk: Buffer, issuer: &Issuer, request: &Request) -> PyResult<Vec<u8>> {
+    if request.path_length.is_some() && !request.is_ca {
+        return Err(PyValueError::new_err(
+            "path_length only applies to CA certificates",
+        ));
+    }
+    let mut basic_constraints = Vec::new();
+    if request.is_ca {
+        basic_constraints.extend_from_slice(&tlv(BOOLEAN, &[0xff]));
+    }
+    if let Some(path_length) = request.path_length {
+        basic_constraints.extend_from_slice(&unsigned_integer(&path_length.to_be_bytes()));
+    }
+    // digitalSignature, plus keyCertSign and cRLSign for a CA.
+    let key_usage: &[u8] = if request.is_ca {
+        &[0x01, 0x86]
+    } else {
+        &[0x07, 0x80]
+    };
+
+    let mut extensions = vec![
+        extension(
+            &OID_BASIC_CONSTRAINTS,
+            true,
+            &tlv(SEQUENCE, &basic_constraints),
+        ),
+        extension(&OID_KEY_USAGE, true, &tlv(BIT_STRING, key_usage)),
+        extension(
+            &OID_SUBJECT_KEY_ID,
+            false,
+            &tlv(OCTET_STRING, &key_identifier(request.public_key)),
+        ),
+    ];
+    if let Some(key_id) = &issuer.key_id {
+        let aki = tlv(SEQUENCE, &tlv(0x80, key_id));
+        extensions.push(extension(&OID_AUTHORITY_KEY_ID, false, &aki));
+    }
+    let empty_subject = request.subject == [SEQUENCE, 0];
+    match &request.san {
+        Some(san) if san != &[SEQUENCE, 0] => {
+            extensions.push(extension(&OID_SUBJECT_ALT_NAME, empty_subject, san));
+        }
+        _ if empty_subject => {
+            return Err(PyValueError::new_err(
+                "a certificate with an empty subject needs subjectAltName entries",
+            ));
+        }
+        _ => {}
+    }
+
+    let tbs = sequence(&[
+        &tlv(CONTEXT_0, &unsigned_integer(&[2])),
+        &serial_number(request.serial)?,
+        &algorithm_identifier(issuer.algorithm),
+        issuer.name,
+        &sequence(&[&time(request.not_before), &time(request.not_after)]),
+        request.subject,
+        &subject_public_key_info(request.algorithm, request.public_key),
+        &tlv(CONTEXT_3, &tlv(SEQUENCE, &extensions.concat())),
+    ]);
+    let signature = (issuer.algorithm.alg.sign)(py, sk, PyBytes::new_bound(py, &tbs).extract()?)?;
+    let signature = signature.as_bytes(py);
+    if !verify(py, issuer.algorithm, issuer.public_key, &tbs, signature)? {
+        return Err(PyValueError::new_err(
+            "the signing key doesn't match the issuer's public key",
+        ));
+    }
+    Ok(sequence(&[
+        &tbs,
+        &algorithm_identifier(issuer.algorithm),
+        &bit_string(signature),
+    ]))
+}
+
+// ─── Chain verification ───────────────────────────────────────────────────────
+
+/// Longest path tried, leaf and root included.
+const MAX_PATH_LEN: usize = 10;
+
+/// Extensions this module can be held to when marked critical.
+const UNDERSTOOD_EXTENSIONS: &[ObjectIdentifier] = &[
+    OID_BASIC_CONSTRAINTS,
+    OID_KEY_USAGE,
+    OID_SUBJECT_ALT_NAME,
+    ObjectIdentifier::new_unwrap("2.5.29.37"), // extendedKeyUsage
+];
+
+fn chain_error(msg: String) -> PyErr {
+    CertificateVerificationError::new_err(msg)
+}
+
+/// An RFC 4514 string for a DER Name, for messages.
+pub(crate) fn name_string(name: &[u8]) -> String {
+    let parse = || -> PyResult<Vec<String>> {
+        let mut outer = Der::new("name", name);
+        let mut rdns = outer.enter(SEQUENCE)?;
+        let mut parts = Vec::new();
+        while !rdns.is_empty() {
+            let mut rdn = rdns.enter(SET)?;
+            while !rdn.is_empty() {
+                let mut pair = rdn.enter(SEQUENCE)?;
+                let id = pair.oid()?;
+                let (_, value, _) = pair.next()?;
+                let key = NAME_ATTRIBUTES
+                    .iter()
+                    .find(|a| a.oid == id)
+                    .map_or_else(|| id.to_string(), |a| a.short.to_string());
+                let value = String::from_utf8_lossy(value).replace(',', "\\,");
+                parts.push(format!("{}={}", key, value));
+            }
+        }
+        parts.reverse();
+        Ok(parts)
+    };
+    match parse() {
+        Ok(parts) if parts.is_empty() => "(empty subject)".to_string(),
+        Ok(parts) => parts.join(","),
+        Err(_) => "(unparseable name)".to_string(),
+    }
+}
+
+impl Certificate<'_> {
+    pub(crate) fn describe(&self) -> String {
+        format!("certificate '{}'", name_string(self.subject))
+    }
+
+    fn check_time(&self, at: u64) -> PyResult<()> {
+        if at < self.not_before {
+            return Err(chain_error(format!("{} isn't valid yet", self.describe())));
+        }
+        if at > self.not_after {
+            return Err(chain_error(format!("{} has expired", self.describe())));
+        }
+        Ok(())
+    }
+
+    fn check_critical_extensions(&self) -> PyResult<()> {
+        for ext in &self.extensions {
+            if ext.critical && !UNDERSTOOD_EXTENSIONS.contains(&ext.id) {
+                return Err(chain_error(format!(
+                    "{} has unsupported critical extension {}",
+                    self.describe(),
+                    ext.id
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether `issuer`'s subject and key identifier match this certificate's
+    /// issuer, before any signature check.
+    fn names_issuer(&self, issuer: &Certificate) -> PyResult<bool> {
+        if self.issuer != issuer.subject {
+            return Ok(false);
+        }
+        Ok(match (self.authority_key_id()?, issuer.subject_key_id()?) {
+            (Some(aki), Some(ski)) => aki == ski,
+            _ => true,
+        })
+    }
+}
+
+/// Check that `issuer` may have issued `cert` and did: a CA, inside its path
+/// length with `below` intermediates under it, valid at `at`, and with a
+/// signature that verifies.
+fn check_link(
+    py: Python,
+    cert: &Certificate,
+    issuer: &Certificate,
+    below: usize,
+    at: u64,
+) -> PyResult<()> {
+    match issuer.basic_constraints()? {
+        (false, _) => {
+            return Err(chain_error(format!(
+                "{} isn't a CA certificate",
+                issuer.describe()
+            )))
+        }
+        (true, Some(limit)) if below as u64 > limit => {
+            return Err(chain_error(format!(
+                "{} allows {} intermediates below it, the path has {}",
+                issuer.describe(),
+                limit,
+                below
+            )))
+        }
+        _ => {}
+    }
+    if !issuer.may_sign_certificates()? {
+        return Err(chain_error(format!(
+            "{} has no keyCertSign key usage",
+            issuer.describe()
+        )));
+    }
+    issuer.check_time(at)?;
+    issuer.check_critical_extensions()?;
+    if !std::ptr::eq(issuer.algorithm, cert.signature_algorithm) {
+        return Err(chain_error(format!(
+            "{} is signed with {}, but its issuer's key is {}",
+            cert.describe(),
+            cert.signature_algorithm.alg.name,
+            issuer.algorithm.alg.name
+        )));
+    }
+    if !verify(
+        py,
+        issuer.algorithm,
+        issuer.public_key,
+        cert.tbs,
+        cert.signature,
+    )? {
+        return Err(chain_error(format!(
+            "{} signature doesn't verify under '{}'",
+            cert.describe(),
+            name_string(issuer.subject)
+        )));
+    }
+    Ok(())
+}
+
+struct PathSearch<'c, 'a> {
+    intermediates: &'c [Certificate<'a>],
+    roots: &'c [Certificate<'a>],
+    at: u64,
+    /// Why the last candidate issuer was turned down, for the final error.
+    last_error: Option<PyErr>,
+}
+
+impl<'c, 'a> PathSearch<'c, 'a> {
+    /// Extend `path` up to a root, depth first; true once it ends in one.
+    fn extend(&mut self, py: Python, path: &mut Vec<&'c Certificate<'a>>) -> PyResult<bool> {
+        let cert = *path.last().expect("path starts at the leaf");
+        let below = path.len() - 1;
+        for root in self.roots {
+            if cert.names_issuer(root)? {
+                match check_link(py, cert, root, below, self.at) {
+                    Ok(()) => {
+                        path.push(root);
+                        return Ok(true);
+                    }
+                    Err(e) => self.last_error = Some(e),
+                }
+            }
+        }
+        if path.len() + 1 >= MAX_PATH_LEN {
+            return Ok(false);
+        }
+        for ca in self.intermediates {
+            if path.iter().any(|c| c.der == ca.der) || !cert.names_issuer(ca)? {
+                continue;
+            }
+            match check_link(py, cert, ca, below, self.at) {
+                Ok(()) => {
+                    path.push(ca);
+                    if self.extend(py, path)? {
+                        return Ok(true);
+                    }
+                    path.pop();
+                }
+                Err(e) => self.last_error = Some(e),
+            }
+        }
+        Ok(false)
+    }
+}
+
+/// A path from `leaf` to one of `roots` valid at `at`, leaf first.
+pub(crate) fn build_path<'c, 'a>(
+    py: Python,
+    leaf: &'c Certificate<'a>,
+    intermediates: &'c [Certificate<'a>],
+    roots: &'c [Certificate<'a>],
+    at: u64,
+) -> PyResult<Vec<&'c Certificate<'a>>> {
+    leaf.check_time(at)?;
+    leaf.check_critical_extensions()?;
+    let mut path = vec![leaf];
+    if roots.iter().any(|root| root.der == leaf.der) {
+        return Ok(path);
+    }
+    let mut search = PathSearch {
+        intermediates,
+        roots,
+        at,
+        last_error: None,
+    };
+    if !search.extend(py, &mut path)? {
+        return Err(search.last_error.unwrap_or_else(|| {
+            chain_error(format!(
+                "no path from {} to a trusted root",
+                leaf.describe()
+            ))
+        }));
+    }
+    Ok(path)
+}
+
+// ─── Python API ───────────────────────────────────────────────────────────────
+
+/// A self-signed certificate for `pk`, a CA certificate unless `is_ca` is
+/// False. Returns DER.
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+#[pyo3(signature = (sk, pk, subject, validity = DEFAULT_VALIDITY, serial = None, san = None, is_ca = true, path_length = None, not_before = None))]
+pub fn generate_self_signed_cert(
+    py: Python,
+    sk: Buffer,
+    pk: Buffer,
+    subject: &str,
+    validity: u64,
+    serial: Option<u128>,
+    san: Option<Vec<String>>,
+    is_ca: bool,
+    path_length: Option<u32>,
+    not_before: Option<u64>,
+) -> PyResult<Py<PyBytes>> {
+    let algorithm = algorithm_for_public_key(&pk)?;
+    check_length(
+        &format!("{} secret key", algorithm.alg.name),
+        &sk,
+        algorithm.alg.secret_key_bytes,
+    )?;
+    let name = encode_name(subject)?;
+    let (not_before, not_after) = self::validity(not_before, validity)?;
+    let issuer = Issuer {
+        algorithm,
+        name: &name,
+        public_key: &pk,
+        key_id: None,
+    };
+    let request = Request {
+        subject: &name,
+        algorithm,
+        public_key: &pk,
+        san: san.as_deref().map(encode_san).transpose()?,
+        serial,
+        not_before,
+        not_after,
+        is_ca,
+        path_length,
+    };
+    let cert = issue(py, sk, &issuer, &request)?;
+    Ok(PyBytes::new_bound(py, &cert).unbind())
+}
+
+/// A certificate for the key and subject of `csr`, issued by the CA with
+/// `ca_cert` and its secret key `ca_sk`. Returns DER.
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+#[pyo3(signature = (ca_sk, ca_cert, csr, validity = DEFAULT_VALIDITY, serial = None, san = None, is_ca = false, path_length = None, not_before = None))]
+pub fn generate_cert(
+    py: Python,
+    ca_sk: Buffer,
+    ca_cert: Buffer,
+    csr: Buffer,
+    validity: u64,
+    serial: Option<u128>,
+    san: Option<Vec<String>>,
+    is_ca: bool,
+    path_length: Option<u32>,
+    not_before: Option<u64>,
+) -> PyResult<Py<PyBytes>> {
+    let ca_der = der_or_pem("ca_cert", &ca_cert, CERTIFICATE_LABEL)?;
+    let ca = parse_certificate(&ca_der)?;
+    match ca.basic_constraints()? {
+        (false, _) => {
+            return Err(PyValueError::new_err("ca_cert isn't a CA certificate"));
+        }
+        (true, Some(0)) if is_ca => {
+            return Err(PyValueError::new_err(
+                "ca_cert's pathLenConstraint of 0 doesn't allow issuing CA certificates",
+            ));
+        }
+        _ => {}
+    }
+    check_length(
+        &format!("{} CA secret key", ca.algorithm.alg.name),
+        &ca_sk,
+        ca.algorithm.alg.secret_key_bytes,
+    )?;
+    let csr_der = der_or_pem("csr", &csr, CSR_LABEL)?;
+    let csr = parse_csr(py, &csr_der)?;
+    let (not_before, not_after) = self::validity(not_before, validity)?;
+    if not_after > ca.not_after {
+        return Err(PyValueError::new_err(
+            "certificate would be valid past ca_cert's notAfter",
+        ));
+    }
+    let issuer = Issuer {
+        algorithm: ca.algorithm,
+        name: ca.subject,
+        public_key: ca.public_key,
+        key_id: Some(ca.key_identifier()?),
+    };
+    let san = match san {
+        Some(entries) => Some(encode_san(&entries)?),
+        None => csr.san.map(<[u8]>::to_vec),
+    };
+    let request = Request {
+        subject: csr.subject,
+        algorithm: csr.algorithm,
+        public_key: csr.public_key,
+        san,
+        serial,
+        not_before,
+        not_after,
+        is_ca,
+        path_length,
+    };
+    let cert = issue(py, ca_sk, &issuer, &request)?;
+    Ok(PyBytes::new_bound(py, &cert).unbind())
+}
+
+/// A PKCS#10 certificate signing request for `pk`, signed with its secret
+/// key `sk`, asking for `subject` and the subjectAltName entries in `san`.
+/// Returns DER.
+#[pyfunction]
+#[pyo3(signature = (sk, pk, subject, san = None))]
+pub fn generate_csr(
+    py: Python,
+    sk: Buffer,
+    pk: Buffer,
+    subject: &str,
+    san: Option<Vec<String>>,
+) -> PyResult<Py<PyBytes>> {
+    let algorithm = algorithm_for_public_key(&pk)?;
+    check_length(
+        &format!("{} secret key", algorithm.alg.name),
+        &sk,
+        algorithm.alg.secret_key_bytes,
+    )?;
+    let name = encode_name(subject)?;
+    let san = san.as_deref().map(encode_san).transpose()?;
+    let csr = request_certificate(py, sk, algorithm, &pk, &name, san.as_deref())?;
+    Ok(PyBytes::new_bound(py, &csr).unbind())
+}
+
+fn from_pem(py: Python, pem: Pem, expected: &str) -> PyResult<Py<PyBytes>> {
+    let (label, der) = pem_decode(&pem, PyValueError::new_err::<String>)?;
+    if label != expected {
+        return Err(PyValueError::new_err(format!(
+            "expected a {} PEM block, got '{}'",
+            expected, label
+        )));
+    }
+    Ok(PyBytes::new_bound(py, &der).unbind())
+}
+
+/// A DER certificate in a "CERTIFICATE" PEM block.
+#[pyfunction]
+pub fn certificate_to_pem(cert: Buffer) -> PyResult<String> {
+    parse_certificate(&cert)?;
+    Ok(pem_encode(CERTIFICATE_LABEL, &cert)?.to_string())
+}
+
+/// The DER certificate in a "CERTIFICATE" PEM block.
+#[pyfunction]
+pub fn certificate_from_pem(py: Python, pem: Pem) -> PyResult<Py<PyBytes>> {
+    from_pem(py, pem, CERTIFICATE_LABEL)
+}
+
+/// A DER CSR in a "CERTIFICATE REQUEST" PEM block, after checking its
+/// self-signature.
+#[pyfunction]
+pub fn csr_to_pem(py: Python, csr: Buffer) -> PyResult<String> {
+    parse_csr(py, &csr)?;
+    Ok(pem_encode(CSR_LABEL, &csr)?.to_string())
+}
+
+/// The DER CSR in a "CERTIFICATE REQUEST" PEM block.
+#[pyfunction]
+pub fn csr_from_pem(py: Python, pem: Pem) -> PyResult<Py<PyBytes>> {
+    from_pem(py, pem, CSR_LABEL)
+}
+
+/// Validate `leaf` up to one of `roots` through `intermediates` at `time`
+/// (default now). Returns the path as DER certificates, leaf first and root
+/// last; raises CertificateVerificationError if there is none.
+#[pyfunction]
+#[pyo3(signature = (leaf, intermediates, roots, time = None))]
+pub fn verify_cert_chain(
+    py: Python,
+    leaf: Buffer,
+    intermediates: Vec<Buffer>,
+    roots: Vec<Buffer>,
+    time: Option<u64>,
+) -> PyResult<Vec<Py<PyBytes>>> {
+    let at = time.unwrap_or_else(now);
+    let load = |what: &str, certs: &[Buffer]| -> PyResult<Vec<Vec<u8>>> {
+        certs
+            .iter()
+            .map(|cert| der_or_pem(what, cert, CERTIFICATE_LABEL))
+            .collect()
+    };
+    let leaf_der = der_or_pem("leaf", &leaf, CERTIFICATE_LABEL)?;
+    let intermediate_ders = load("intermediates", &intermediates)?;
+    let root_ders = load("roots", &roots)?;
+    if root_ders.is_empty() {
+        return Err(PyValueError::new_err(
+            "verify_cert_chain needs at least one root",
+        ));
+    }
+    let leaf = parse_certificate(&leaf_der)?;
+    let intermediates = intermediate_ders
+        .iter()
+        .map(|der| parse_certificate(der))
+        .collect::<PyResult<Vec<_>>>()?;
+    let roots = root_ders
+        .iter()
+        .map(|der| parse_certificate(der))
+        .collect::<PyResult<Vec<_>>>()?;
+
+    let path = build_path(py, &leaf, &intermediates, &roots, at)?;
+    Ok(path
+        .iter()
+        .map(|cert| PyBytes::new_bound(py, cert.der).unbind())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unhex(s: &str) -> Vec<u8> {
+        crate::util::unhex(s).unwrap()
+    }
+
+    /// (pk, sk) for Falcon-512 from a fixed seed, so certificates over it
+    /// have fixed TBSCertificate bytes.
+    fn falcon_key(py: Python) -> (Vec<u8>, Vec<u8>) {
+        let seed = Buffer::from_bytes(py, &[0x2a; 32]);
+        let (pk, sk) = crate::seeded::falcon_keygen_from_seed(py, seed, "falcon512").unwrap();
+        let sk: Buffer = sk.bind(py).extract().unwrap();
+        (pk.as_bytes(py).to_vec(), sk.to_vec())
+    }
+
+    const NOT_BEFORE: u64 = 1_700_000_000;
+    const VALIDITY: u64 = 30 * 365 * 86400;
+    const SERIAL: u128 = 0x0102_0304_0506_0708_090a_0b0c_0d0e_0f10;
+
+    // The root's TBSCertificate around its public key, as built independently
+    // with `openssl asn1parse -genconf`: v3, the serial above, Falcon-512,
+    // "C=DE, O=Example, CN=Example Root" as issuer and subject, a UTCTime
+    // notBefore and a GeneralizedTime notAfter (2053), then basicConstraints
+    // (CA, pathlen 1), keyUsage, subjectKeyIdentifier and subjectAltName.
+    const ROOT_TBS_BEFORE_KEY: &str =
+        "308204afa00302010202100102030405060708090a0b0c0d0e0f10300706\
+        052bce0f030b3036310b30090603550406130244453110300e060355040a0c074578616d706c653115301306\
+        035504030c0c4578616d706c6520526f6f743020170d3233313131343232313332305a180f32303533313130\
+        363232313332305a3036310b30090603550406130244453110300e060355040a0c074578616d706c65311530\
+        1306035504030c0c4578616d706c6520526f6f743082038f300706052bce0f030b0382038200";
+    const ROOT_TBS_AFTER_KEY: &str = "a368306630120603551d130101ff040830060101ff020101300e060355\
+        1d0f0101ff040403020186301d0603551d0e041604143437ba0a9514fc169ad4c9c886a9a7aa0caf1fbe3021\
+        0603551d11041a30188210726f6f742e6578616d706c652e636f6d8704c0000201";
+
+    fn root(py: Python, pk: &[u8], sk: &[u8]) -> Vec<u8> {
+        generate_self_signed_cert(
+            py,
+            Buffer::from_bytes(py, sk),
+            Buffer::from_bytes(py, pk),
+            "CN=Example Root,O=Example,C=DE",
+            VALIDITY,
+            Some(SERIAL),
+            Some(vec!["root.example.com".into(), "192.0.2.1".into()]),
+            true,
+            Some(1),
+            Some(NOT_BEFORE),
+        )
+        .unwrap()
+        .as_bytes(py)
+        .to_vec()
+    }
+
+    #[test]
+    fn self_signed_certificate_kat() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let (pk, sk) = falcon_key(py);
+            let der = root(py, &pk, &sk);
+            let cert = parse_certificate(&der).unwrap();
+            let want = [
+                unhex(ROOT_TBS_BEFORE_KEY),
+                pk.clone(),
+                unhex(ROOT_TBS_AFTER_KEY),
+            ]
+            .concat();
+            assert_eq!(cert.tbs, &want[..]);
+            assert!(verify(py, cert.algorithm, &pk, cert.tbs, cert.signature).unwrap());
+
+            // And back out of the parser.
+            assert_eq!(cert.serial, &SERIAL.to_be_bytes()[..]);
+            assert_eq!(cert.issuer, cert.subject);
+            assert_eq!(name_string(cert.subject), "CN=Example Root,O=Example,C=DE");
+            assert_eq!(cert.public_key, &pk[..]);
+            assert_eq!(
+                (cert.not_before, cert.not_after),
+                (NOT_BEFORE, NOT_BEFORE + VALIDITY)
+            );
+            assert_eq!(cert.basic_constraints().unwrap(), (true, Some(1)));
+            assert!(cert.may_sign_certificates().unwrap());
+            assert_eq!(cert.key_identifier().unwrap(), key_identifier(&pk));
+            assert_eq!(cert.authority_key_id().unwrap(), None);
+        });
+    }
+
+    #[test]
+    fn issued_certificate_chains_to_root() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let (ca_pk, ca_sk) = falcon_key(py);
+            let root_der = root(py, &ca_pk, &ca_sk);
+            let (pk, sk) = (crate::mldsa44::ALGORITHM.keygen)(py).unwrap();
+            let sk: Buffer = sk.bind(py).extract().unwrap();
+            let csr = generate_csr(
+                py,
+                sk,
+                Buffer::from_bytes(py, pk.as_bytes(py)),
+                "CN=device-7,O=Example",
+                Some(vec!["device-7.example.com".into()]),
+            )
+            .unwrap();
+            let leaf_der = generate_cert(
+                py,
+                Buffer::from_bytes(py, &ca_sk),
+                Buffer::from_bytes(py, &root_der),
+                Buffer::from_bytes(py, csr.as_bytes(py)),
+                365 * 86400,
+                None,
+                None,
+                false,
+                None,
+                Some(NOT_BEFORE),
+            )
+            .unwrap();
+            let leaf_der = leaf_der.as_bytes(py);
+
+            let leaf = parse_certificate(leaf_der).unwrap();
+            let root = parse_certificate(&root_der).unwrap();
+            assert_eq!(leaf.issuer, root.subject);
+            assert_eq!(name_string(leaf.subject), "CN=device-7,O=Example");
+            assert_eq!(leaf.public_key, pk.as_bytes(py));
+            assert_eq!(leaf.basic_constraints().unwrap(), (false, None));
+            assert_eq!(
+                leaf.authority_key_id().unwrap(),
+                Some(&key_identifier(&ca_pk)[..])
+            );
+            let roots = [root];
+            let path = build_path(py, &leaf, &[], &roots, NOT_BEFORE + 86400).unwrap();
+            assert_eq!(path.len(), 2);
+            // Not yet valid, and expired.
+            assert!(build_path(py, &leaf, &[], &roots, NOT_BEFORE - 1).is_err());
+            assert!(build_path(py, &leaf, &[], &roots, NOT_BEFORE + 366 * 86400).is_err());
+        });
+    }
+}
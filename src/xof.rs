@@ -0,0 +1,295 @@
+// ───────────────────────────────────────────────────────────────────────────────
+// SHAKE128 / SHAKE256 extendable-output functions (FIPS 202)
+// ───────────────────────────────────────────────────────────────────────────────
+//
+// For key derivation, domain separation and seed expansion the way the PQ
+// schemes themselves do it:
+//
+//   key = shake256(b"myproto v1 key\0" + ss, 32)
+//
+//   xof = Shake("shake256")
+//   xof.update(b"myproto v1\0")
+//   xof.update(transcript)
+//   enc_key = xof.squeeze(32)      # successive squeezes continue the stream
+//   mac_key = xof.squeeze(32)
+//
+// A Shake absorbs until its first squeeze(); update() after that raises
+// ValueError. copy() forks the state, e.g. to squeeze several streams from
+// one shared prefix. SHAKE has no built-in domain separation: prefix each
+// use with its own label, length-delimited or null-terminated so no two
+// inputs collide.
+//
+// Both run on PQClean's fips202 code, the same Keccak the KEMs and
+// signatures here use internally.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use zeroize::Zeroize;
+
+use crate::buffer::Buffer;
+use crate::kem::normalize_name;
+
+// ─── PQClean FFI ──────────────────────────────────────────────────────────────
+
+/// fips202.h's shake128incctx / shake256incctx: the Keccak state plus the
+/// absorb/squeeze position.
+#[repr(C)]
+#[derive(Clone)]
+pub(crate) struct ShakeIncCtx {
+    ctx: [u64; 26],
+}
+
+extern "C" {
+    fn shake128_inc_init(state: *mut ShakeIncCtx);
+    fn shake128_inc_absorb(state: *mut ShakeIncCtx, input: *const u8, inlen: usize);
+    fn shake128_inc_finalize(state: *mut ShakeIncCtx);
+    fn shake128_inc_squeeze(output: *mut u8, outlen: usize, state: *mut ShakeIncCtx);
+    fn shake128_inc_ctx_release(state: *mut ShakeIncCtx);
+
+    fn shake256_inc_init(state: *mut ShakeIncCtx);
+    fn shake256_inc_absorb(state: *mut ShakeIncCtx, input: *const u8, inlen: usize);
+    fn shake256_inc_finalize(state: *mut ShakeIncCtx);
+    fn shake256_inc_squeeze(output: *mut u8, outlen: usize, state: *mut ShakeIncCtx);
+    fn shake256_inc_ctx_release(state: *mut ShakeIncCtx);
+
+    fn sha3_256_inc_init(state: *mut ShakeIncCtx);
+    fn sha3_256_inc_absorb(state: *mut ShakeIncCtx, input: *const u8, inlen: usize);
+    fn sha3_256_inc_finalize(output: *mut u8, state: *mut ShakeIncCtx);
+    fn sha3_256_inc_ctx_release(state: *mut ShakeIncCtx);
+
+    fn sha3_512_inc_init(state: *mut ShakeIncCtx);
+    fn sha3_512_inc_absorb(state: *mut ShakeIncCtx, input: *const u8, inlen: usize);
+    fn sha3_512_inc_finalize(output: *mut u8, state: *mut ShakeIncCtx);
+    fn sha3_512_inc_ctx_release(state: *mut ShakeIncCtx);
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ShakeVariant {
+    Shake128,
+    Shake256,
+}
+
+impl ShakeVariant {
+    fn from_name(name: &str) -> PyResult<Self> {
+        match normalize_name(name).as_str() {
+            "shake128" => Ok(ShakeVariant::Shake128),
+            "shake256" => Ok(ShakeVariant::Shake256),
+            _ => Err(PyValueError::new_err(format!(
+                "unsupported XOF '{}', expected shake128 or shake256",
+                name
+            ))),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            ShakeVariant::Shake128 => "shake128",
+            ShakeVariant::Shake256 => "shake256",
+        }
+    }
+}
+
+/// An incremental SHAKE sponge: absorb, then squeeze. Wiped on drop, as it
+/// may hold key material.
+#[derive(Clone)]
+pub(crate) struct Sponge {
+    variant: ShakeVariant,
+    state: ShakeIncCtx,
+    squeezing: bool,
+}
+
+impl Sponge {
+    pub(crate) fn new(variant: ShakeVariant) -> Self {
+        let mut sponge = Sponge {
+            variant,
+            state: ShakeIncCtx { ctx: [0; 26] },
+            squeezing: false,
+        };
+        match variant {
+            ShakeVariant::Shake128 => unsafe { shake128_inc_init(&mut sponge.state) },
+            ShakeVariant::Shake256 => unsafe { shake256_inc_init(&mut sponge.state) },
+        }
+        sponge
+    }
+
+    /// Absorb `data`. Only valid before the first squeeze.
+    pub(crate) fn absorb(&mut self, data: &[u8]) {
+        debug_assert!(!self.squeezing, "absorb after squeeze");
+        match self.variant {
+            ShakeVariant::Shake128 => unsafe {
+                shake128_inc_absorb(&mut self.state, data.as_ptr(), data.len())
+            },
+            ShakeVariant::Shake256 => unsafe {
+                shake256_inc_absorb(&mut self.state, data.as_ptr(), data.len())
+            },
+        }
+    }
+
+    fn finalize(&mut self) {
+        if !self.squeezing {
+            match self.variant {
+                ShakeVariant::Shake128 => unsafe { shake128_inc_finalize(&mut self.state) },
+                ShakeVariant::Shake256 => unsafe { shake256_inc_finalize(&mut self.state) },
+            }
+            self.squeezing = true;
+        }
+    }
+
+    pub(crate) fn squeeze(&mut self, out: &mut [u8]) {
+        self.finalize();
+        match self.variant {
+            ShakeVariant::Shake128 => unsafe {
+                shake128_inc_squeeze(out.as_mut_ptr(), out.len(), &mut self.state)
+            },
+            ShakeVariant::Shake256 => unsafe {
+                shake256_inc_squeeze(out.as_mut_ptr(), out.len(), &mut self.state)
+            },
+        }
+    }
+
+    /// The finalized state, for PQClean functions that squeeze it themselves.
+    pub(crate) fn as_mut_ptr(&mut self) -> *mut ShakeIncCtx {
+        self.finalize();
+        &mut self.state
+    }
+}
+
+impl Drop for Sponge {
+    fn drop(&mut self) {
+        match self.variant {
+            ShakeVariant::Shake128 => unsafe { shake128_inc_ctx_release(&mut self.state) },
+            ShakeVariant::Shake256 => unsafe { shake256_inc_ctx_release(&mut self.state) },
+        }
+        self.state.ctx.zeroize();
+    }
+}
+
+/// Incremental SHA3-256 / SHA3-512 on the same Keccak code (fips202.h's
+/// sha3_*incctx have the SHAKE context layout), for prehash signing.
+#[derive(Clone)]
+pub(crate) struct Sha3 {
+    wide: bool,
+    state: ShakeIncCtx,
+}
+
+impl Sha3 {
+    /// SHA3-512 if `wide`, else SHA3-256.
+    pub(crate) fn new(wide: bool) -> Self {
+        let mut sha3 = Sha3 {
+            wide,
+            state: ShakeIncCtx { ctx: [0; 26] },
+        };
+        match wide {
+            false => unsafe { sha3_256_inc_init(&mut sha3.state) },
+            true => unsafe { sha3_512_inc_init(&mut sha3.state) },
+        }
+        sha3
+    }
+
+    pub(crate) fn absorb(&mut self, data: &[u8]) {
+        match self.wide {
+            false => unsafe { sha3_256_inc_absorb(&mut self.state, data.as_ptr(), data.len()) },
+            true => unsafe { sha3_512_inc_absorb(&mut self.state, data.as_ptr(), data.len()) },
+        }
+    }
+
+    pub(crate) fn finalize(mut self) -> Vec<u8> {
+        let mut out = vec![0u8; if self.wide { 64 } else { 32 }];
+        match self.wide {
+            false => unsafe { sha3_256_inc_finalize(out.as_mut_ptr(), &mut self.state) },
+            true => unsafe { sha3_512_inc_finalize(out.as_mut_ptr(), &mut self.state) },
+        }
+        out
+    }
+}
+
+impl Drop for Sha3 {
+    fn drop(&mut self) {
+        match self.wide {
+            false => unsafe { sha3_256_inc_ctx_release(&mut self.state) },
+            true => unsafe { sha3_512_inc_ctx_release(&mut self.state) },
+        }
+        self.state.ctx.zeroize();
+    }
+}
+
+fn squeeze_bytes(py: Python, sponge: &mut Sponge, length: usize) -> PyResult<Py<PyBytes>> {
+    let out = PyBytes::new_bound_with(py, length, |out| {
+        sponge.squeeze(out);
+        Ok(())
+    })?;
+    Ok(out.unbind())
+}
+
+// ─── One-shot ─────────────────────────────────────────────────────────────────
+
+/// SHAKE128(data), `length` bytes.
+#[pyfunction]
+pub fn shake128(py: Python, data: Buffer, length: usize) -> PyResult<Py<PyBytes>> {
+    let mut sponge = Sponge::new(ShakeVariant::Shake128);
+    sponge.absorb(&data);
+    squeeze_bytes(py, &mut sponge, length)
+}
+
+/// SHAKE256(data), `length` bytes.
+#[pyfunction]
+pub fn shake256(py: Python, data: Buffer, length: usize) -> PyResult<Py<PyBytes>> {
+    let mut sponge = Sponge::new(ShakeVariant::Shake256);
+    sponge.absorb(&data);
+    squeeze_bytes(py, &mut sponge, length)
+}
+
+// ─── Incremental ──────────────────────────────────────────────────────────────
+
+#[pyclass(module = "pqcrypto_bindings", name = "Shake")]
+pub struct Shake {
+    sponge: Sponge,
+}
+
+#[pymethods]
+impl Shake {
+    /// A fresh "shake128" or "shake256" instance, absorbing `data` if given.
+    // stub: name: Literal["shake128", "shake256"]
+    #[new]
+    #[pyo3(signature = (name = "shake256", data = None))]
+    fn new(name: &str, data: Option<Buffer>) -> PyResult<Self> {
+        let mut sponge = Sponge::new(ShakeVariant::from_name(name)?);
+        if let Some(data) = data {
+            sponge.absorb(&data);
+        }
+        Ok(Shake { sponge })
+    }
+
+    #[getter]
+    fn name(&self) -> &'static str {
+        self.sponge.variant.name()
+    }
+
+    /// Absorb more input. Raises ValueError once squeeze() has been called.
+    fn update(&mut self, data: Buffer) -> PyResult<()> {
+        if self.sponge.squeezing {
+            return Err(PyValueError::new_err(
+                "can't update() a Shake after squeeze()",
+            ));
+        }
+        self.sponge.absorb(&data);
+        Ok(())
+    }
+
+    /// The next `length` bytes of output.
+    fn squeeze(&mut self, py: Python, length: usize) -> PyResult<Py<PyBytes>> {
+        squeeze_bytes(py, &mut self.sponge, length)
+    }
+
+    /// An independent copy of the current state.
+    fn copy(&self) -> Self {
+        Shake {
+            sponge: self.sponge.clone(),
+        }
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Shake('{}')", self.sponge.variant.name())
+    }
+}